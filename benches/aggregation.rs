@@ -0,0 +1,33 @@
+//! Time-bucketed aggregation over every record of a virtual trace: start
+//! counts and occupancy, as computed for the activity strips of the trace
+//! summary. Occupancy touches every bucket a record spans, so long records
+//! cost more than short ones.
+//!
+//! ```text
+//! cargo bench --bench aggregation
+//! ```
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use rjets::{aggregate_by_bucket, BucketMetric, TraceData, TraceReader, TraceRecord, VirtualTraceReader};
+
+/// Buckets per aggregation, as many as the trace summary's activity strips.
+const BUCKETS: usize = 200;
+
+fn aggregation(c: &mut Criterion) {
+    let trace = VirtualTraceReader::new().read("").unwrap();
+    let start = trace.records_iter().map(|r| r.clk()).min().unwrap_or(0);
+    let end = trace.records_iter().map(|r| r.end_clk().unwrap_or(r.clk())).max().unwrap_or(start) + 1;
+    let records = trace.records_iter().count() as u64;
+
+    let mut group = c.benchmark_group("aggregate_by_bucket");
+    group.throughput(Throughput::Elements(records));
+    for (name, metric) in [("starts", BucketMetric::Starts), ("occupancy", BucketMetric::Occupancy)] {
+        group.bench_function(name, |b| {
+            b.iter(|| aggregate_by_bucket(trace.records_iter(), (start, end), BUCKETS, metric).max_sum())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, aggregation);
+criterion_main!(benches);
@@ -0,0 +1,92 @@
+//! Load time of Brotli-compressed traces: the former reading (4 KiB
+//! decompressor buffer) against the tuned buffers, decompressing inline and
+//! on the read-ahead thread. `parse_trace` picks between the last two by
+//! core count; the read-ahead only pays off with a core to spare.
+//!
+//! ```text
+//! cargo bench --bench load
+//! ```
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+
+use brotli::Decompressor;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use rjets::{parse_trace, parse_trace_from_reader, PrefetchReader, TraceWriter};
+use rjets::prefetch::{DECOMPRESS_BUFFER_SIZE, PREFETCH_CHUNK_SIZE};
+use serde_json::json;
+
+const THREADS: u64 = 8;
+const INSTRUCTIONS_PER_THREAD: u64 = 5_000;
+const STAGES: [&str; 5] = ["fetch", "decode", "rename", "execute", "retire"];
+
+/// Writes a pipeline-shaped trace like `jets-tracegen` output: threads of
+/// overlapping instructions, each with attributes and one event per stage.
+fn write_trace() -> PathBuf {
+    let path = std::env::temp_dir().join(format!("jets_load_bench_{}.jets.br", std::process::id()));
+    let mut writer = TraceWriter::new(path.to_str().unwrap()).unwrap();
+    writer.write_header("2.0", json!({"tool": "load bench"})).unwrap();
+    let mut next_id = 1;
+    for thread in 0..THREADS {
+        let thread_id = next_id;
+        next_id += 1;
+        writer.write_record(thread_id, None, "Thread", 0, &format!("thread_{}", thread), "", None).unwrap();
+        for instruction in 0..INSTRUCTIONS_PER_THREAD {
+            let id = next_id;
+            next_id += 1;
+            let clk = instruction as i64 * 2;
+            let data = json!({"pc": format!("0x{:08x}", 0x8000_0000 + instruction * 4), "opcode": "addi", "rd": instruction % 32});
+            writer.write_record(id, Some(thread_id), "Instruction", clk, &format!("addi x{}", instruction % 32), "", Some(data)).unwrap();
+            for (stage, name) in STAGES.iter().enumerate() {
+                writer.write_event(id, name, "", clk + stage as i64, Some(json!({"unit": stage}))).unwrap();
+            }
+            writer.write_record_end(id, clk + STAGES.len() as i64).unwrap();
+        }
+        writer.write_record_end(thread_id, INSTRUCTIONS_PER_THREAD as i64 * 2 + STAGES.len() as i64).unwrap();
+    }
+    writer.write_footer(None).unwrap();
+    drop(writer);
+    path
+}
+
+fn former_reader(path: &PathBuf) -> BufReader<Decompressor<File>> {
+    BufReader::new(Decompressor::new(File::open(path).unwrap(), 4096))
+}
+
+fn tuned_inline_reader(path: &PathBuf) -> BufReader<Decompressor<File>> {
+    BufReader::with_capacity(PREFETCH_CHUNK_SIZE, Decompressor::new(File::open(path).unwrap(), DECOMPRESS_BUFFER_SIZE))
+}
+
+fn prefetch_reader(path: &PathBuf) -> PrefetchReader {
+    PrefetchReader::new(Decompressor::new(File::open(path).unwrap(), DECOMPRESS_BUFFER_SIZE)).unwrap()
+}
+
+fn count_lines(reader: impl BufRead) -> usize {
+    reader.lines().map(Result::unwrap).count()
+}
+
+fn bench_load(c: &mut Criterion) {
+    let path = write_trace();
+    let lines = count_lines(former_reader(&path)) as u64;
+
+    let mut decompress = c.benchmark_group("decompress_lines");
+    decompress.sample_size(10).throughput(Throughput::Elements(lines));
+    decompress.bench_function("4k_inline", |b| b.iter(|| count_lines(former_reader(&path))));
+    decompress.bench_function("tuned_inline", |b| b.iter(|| count_lines(tuned_inline_reader(&path))));
+    decompress.bench_function("tuned_prefetch", |b| b.iter(|| count_lines(prefetch_reader(&path))));
+    decompress.finish();
+
+    let mut parse = c.benchmark_group("parse_jets_br");
+    parse.sample_size(10).throughput(Throughput::Elements(lines));
+    parse.bench_function("4k_inline", |b| b.iter(|| parse_trace_from_reader(former_reader(&path)).unwrap()));
+    parse.bench_function("tuned_inline", |b| b.iter(|| parse_trace_from_reader(tuned_inline_reader(&path)).unwrap()));
+    parse.bench_function("tuned_prefetch", |b| b.iter(|| parse_trace_from_reader(prefetch_reader(&path)).unwrap()));
+    parse.bench_function("parse_trace", |b| b.iter(|| parse_trace(path.to_str().unwrap()).unwrap()));
+    parse.finish();
+
+    let _ = std::fs::remove_file(&path);
+}
+
+criterion_group!(benches, bench_load);
+criterion_main!(benches);
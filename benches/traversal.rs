@@ -0,0 +1,66 @@
+//! Tree traversal on deep traces: every node visited, and a narrow viewport
+//! window where most leaves are walked past without being shown. Branch
+//! context (the tree lines drawn left of a row) grows with depth, so deep
+//! trees are where building it per node shows.
+//!
+//! ```text
+//! cargo bench --bench traversal
+//! ```
+
+use std::fmt::Write as _;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use rjets::{parse_trace_from_reader, traverse_visible, DynTraceData, TraceData, UnfilteredStrategy, ViewportFilterStrategy};
+
+/// Nesting depth of the spine.
+const DEPTH: u64 = 256;
+/// Leaves below each spine record, next to the deeper spine record.
+const LEAVES_PER_LEVEL: u64 = 16;
+
+/// A spine of nested records, each with a row of leaves: every leaf sits
+/// as deep as its level, up to [`DEPTH`].
+fn deep_trace() -> DynTraceData {
+    let mut input = String::from("{\"type\":\"header\",\"version\":\"2.0\",\"metadata\":{}}\n");
+    let mut next_id = 1;
+    let mut parent: Option<u64> = None;
+    for level in 0..DEPTH {
+        let spine = next_id;
+        next_id += 1;
+        let parent_id = parent.map_or("null".to_string(), |id| id.to_string());
+        let clk = level as i64;
+        writeln!(
+            input,
+            r#"{{"type":"record","clk":{clk},"name":"level","record_type":"Scope","id":{spine},"parent_id":{parent_id},"description":""}}"#
+        )
+        .unwrap();
+        for leaf in 0..LEAVES_PER_LEVEL {
+            let clk = clk * 100 + leaf as i64;
+            writeln!(
+                input,
+                r#"{{"type":"record","clk":{clk},"name":"op","record_type":"Op","id":{next_id},"parent_id":{spine},"description":""}}"#
+            )
+            .unwrap();
+            next_id += 1;
+        }
+        parent = Some(spine);
+    }
+    DynTraceData::Jets(parse_trace_from_reader(input.as_bytes()).unwrap())
+}
+
+fn traversal(c: &mut Criterion) {
+    let trace = deep_trace();
+    let roots = || trace.roots().iter().filter_map(|&id| trace.get_record(id)).collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("traversal");
+    group.throughput(Throughput::Elements(DEPTH * (LEAVES_PER_LEVEL + 1)));
+    group.bench_function("deep_unfiltered", |b| {
+        b.iter(|| traverse_visible(roots(), &UnfilteredStrategy).count())
+    });
+    // Leaves of the first few levels only; the rest are walked past
+    let window = ViewportFilterStrategy { start: 0, end: 4 * 100 };
+    group.bench_function("deep_narrow_window", |b| b.iter(|| traverse_visible(roots(), &window).count()));
+    group.finish();
+}
+
+criterion_group!(benches, traversal);
+criterion_main!(benches);
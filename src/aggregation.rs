@@ -0,0 +1,244 @@
+//! Time-bucketed aggregation of records.
+//!
+//! Overview widgets (activity strips, charts, heat maps) all need the same
+//! thing: a clock range cut into equal buckets with a count and a sum per
+//! bucket. [`aggregate_by_bucket`] computes that for records or events and
+//! a choice of [`BucketMetric`]; callers decide what to draw from it.
+
+use crate::traits::{AttributeAccessor, DynTraceEvent, TraceEvent, TraceRecord};
+
+/// Something with a clock span that can be aggregated.
+///
+/// Implemented for every record, and for events as instants.
+pub trait ClockSpan: AttributeAccessor {
+    /// Start and end clock; instants end where they start.
+    fn clock_span(&self) -> (i64, i64);
+}
+
+impl<'a, R: TraceRecord<'a>> ClockSpan for R {
+    fn clock_span(&self) -> (i64, i64) {
+        (self.clk(), self.end_clk().unwrap_or(self.clk()))
+    }
+}
+
+impl ClockSpan for DynTraceEvent<'_> {
+    fn clock_span(&self) -> (i64, i64) {
+        (self.clk(), self.clk())
+    }
+}
+
+/// What is accumulated per bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BucketMetric<'m> {
+    /// Items starting in the bucket; the sum equals the count
+    Starts,
+    /// Items active during the bucket; the sum is the covered clocks
+    /// (so `sum / bucket width` is the average number of active items)
+    Occupancy,
+    /// Items starting in the bucket; the sum is this numeric attribute
+    AttributeSum(&'m str),
+}
+
+/// Per-bucket counts and sums over a clock range.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TimeBuckets {
+    /// Start of bucket 0 (inclusive)
+    pub start_clk: i64,
+    /// End of the last bucket (exclusive)
+    pub end_clk: i64,
+    /// Items counted per bucket
+    pub counts: Vec<u64>,
+    /// Accumulated metric per bucket
+    pub sums: Vec<f64>,
+    /// Input position of the first item counted per bucket (0 if none was)
+    pub first_items: Vec<usize>,
+}
+
+impl TimeBuckets {
+    fn empty(range: (i64, i64), bucket_count: usize) -> Self {
+        Self {
+            start_clk: range.0,
+            end_clk: range.1,
+            counts: vec![0; bucket_count],
+            sums: vec![0.0; bucket_count],
+            first_items: vec![0; bucket_count],
+        }
+    }
+
+    /// Counts an item in a bucket and adds `value` to its sum.
+    fn add(&mut self, index: usize, position: usize, value: f64) {
+        if self.counts[index] == 0 {
+            self.first_items[index] = position;
+        }
+        self.counts[index] += 1;
+        self.sums[index] += value;
+    }
+
+    /// Range width in clocks, computed wide so it cannot overflow.
+    fn span(&self) -> i128 {
+        self.end_clk as i128 - self.start_clk as i128
+    }
+
+    /// Returns the number of buckets.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Returns true if there are no buckets.
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Width of one bucket in clocks (fractional for uneven splits).
+    pub fn bucket_width(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        self.span() as f64 / self.len() as f64
+    }
+
+    /// Clock range `[start, end)` of a bucket.
+    pub fn bucket_range(&self, index: usize) -> (i64, i64) {
+        (self.bucket_start(index), self.bucket_start(index + 1))
+    }
+
+    fn bucket_start(&self, index: usize) -> i64 {
+        if index >= self.len() {
+            return self.end_clk;
+        }
+        (self.start_clk as i128 + self.span() * index as i128 / self.len() as i128) as i64
+    }
+
+    /// Returns the bucket holding a clock, if it is within the range.
+    pub fn bucket_of(&self, clk: i64) -> Option<usize> {
+        if clk < self.start_clk || clk >= self.end_clk || self.is_empty() {
+            return None;
+        }
+        let index = (clk as i128 - self.start_clk as i128) * self.len() as i128 / self.span();
+        Some((index as usize).min(self.len() - 1))
+    }
+
+    /// Largest sum of any bucket (0 if there are none).
+    pub fn max_sum(&self) -> f64 {
+        self.sums.iter().copied().fold(0.0, f64::max)
+    }
+}
+
+/// Aggregates records or events into `bucket_count` equal buckets over `range`.
+///
+/// Records without an end clock and events are treated as instants. Items
+/// entirely outside the range are skipped; for [`BucketMetric::Occupancy`]
+/// an item overlapping the range edge only contributes the overlapping part.
+///
+/// # Arguments
+/// * `items` - Records or events to aggregate, in any order
+/// * `range` - Clock range `(start, end)`; empty or inverted ranges give all-zero buckets
+/// * `bucket_count` - Number of buckets
+/// * `metric` - What to accumulate
+pub fn aggregate_by_bucket<T: ClockSpan>(
+    items: impl IntoIterator<Item = T>,
+    range: (i64, i64),
+    bucket_count: usize,
+    metric: BucketMetric,
+) -> TimeBuckets {
+    let mut buckets = TimeBuckets::empty(range, bucket_count);
+    if range.1 <= range.0 || bucket_count == 0 {
+        return buckets;
+    }
+
+    for (position, item) in items.into_iter().enumerate() {
+        let (start, end) = item.clock_span();
+        match metric {
+            BucketMetric::Starts => {
+                if let Some(index) = buckets.bucket_of(start) {
+                    buckets.add(index, position, 1.0);
+                }
+            }
+            BucketMetric::AttributeSum(attribute) => {
+                if let Some(index) = buckets.bucket_of(start) {
+                    let value = item.attr(attribute).as_ref().and_then(numeric_value).unwrap_or(0.0);
+                    buckets.add(index, position, value);
+                }
+            }
+            BucketMetric::Occupancy => add_occupancy(&mut buckets, position, start, end),
+        }
+    }
+    buckets
+}
+
+/// Counts a span in every bucket it touches and adds the overlapping clocks.
+fn add_occupancy(buckets: &mut TimeBuckets, position: usize, start: i64, end: i64) {
+    let (clipped_start, clipped_end) = (start.max(buckets.start_clk), end.min(buckets.end_clk));
+    // Instants count in their bucket but cover no clocks
+    if start == end {
+        if let Some(index) = buckets.bucket_of(start) {
+            buckets.add(index, position, 0.0);
+        }
+        return;
+    }
+    if clipped_start >= clipped_end {
+        return;
+    }
+
+    let first = buckets.bucket_of(clipped_start).unwrap_or(0);
+    let last = buckets.bucket_of(clipped_end - 1).unwrap_or(buckets.len() - 1);
+    for index in first..=last {
+        let (bucket_start, bucket_end) = buckets.bucket_range(index);
+        let overlap = clipped_end.min(bucket_end) as i128 - clipped_start.max(bucket_start) as i128;
+        if overlap > 0 {
+            buckets.add(index, position, overlap as f64);
+        }
+    }
+}
+
+/// Reads a number from a JSON number or a numeric string.
+fn numeric_value(value: &serde_json::Value) -> Option<f64> {
+    value.as_f64().or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{DynTraceData, TraceData};
+
+    fn sample_trace() -> DynTraceData {
+        let input = concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"a","record_type":"Op","id":1,"parent_id":null,"description":"","data":{"bytes":4}}"#, "\n",
+            r#"{"type":"record","clk":5,"name":"b","record_type":"Op","id":2,"parent_id":null,"description":"","data":{"bytes":"6"}}"#, "\n",
+            r#"{"type":"record","clk":30,"name":"c","record_type":"Op","id":3,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record","clk":100,"name":"late","record_type":"Op","id":4,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record_end","clk":25,"record_id":1}"#, "\n",
+            r#"{"type":"record_end","clk":10,"record_id":2}"#, "\n",
+            r#"{"type":"record_end","clk":110,"record_id":4}"#, "\n",
+        );
+        DynTraceData::Jets(crate::parse_trace_from_reader(input.as_bytes()).unwrap())
+    }
+
+    #[test]
+    fn test_aggregate_by_bucket_metrics() {
+        let trace = sample_trace();
+
+        let starts = aggregate_by_bucket(trace.records_iter(), (0, 40), 4, BucketMetric::Starts);
+        assert_eq!(starts.counts, vec![2, 0, 0, 1]);
+        assert_eq!(starts.bucket_range(3), (30, 40));
+        assert_eq!(starts.first_items, vec![0, 0, 0, 2]);
+
+        // a covers 0..25, b 5..10; c is an instant at 30
+        let occupancy = aggregate_by_bucket(trace.records_iter(), (0, 40), 4, BucketMetric::Occupancy);
+        assert_eq!(occupancy.counts, vec![2, 1, 1, 1]);
+        assert_eq!(occupancy.sums, vec![15.0, 10.0, 5.0, 0.0]);
+
+        let bytes = aggregate_by_bucket(trace.records_iter(), (0, 40), 2, BucketMetric::AttributeSum("bytes"));
+        assert_eq!(bytes.sums, vec![10.0, 0.0]);
+        assert_eq!(bytes.max_sum(), 10.0);
+
+        let empty = aggregate_by_bucket(trace.records_iter(), (40, 40), 4, BucketMetric::Starts);
+        assert_eq!(empty.max_sum(), 0.0);
+
+        // Ranges wider than i64 can hold
+        let wide = aggregate_by_bucket(trace.records_iter(), (i64::MIN, i64::MAX), 2, BucketMetric::Occupancy);
+        assert_eq!(wide.counts, vec![0, 4]);
+        assert_eq!(wide.sums[1], 40.0);
+    }
+}
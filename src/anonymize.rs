@@ -0,0 +1,117 @@
+use rjets::anonymizer::{self, AnonymizeOptions, FieldAction};
+use rjets::logging;
+use rjets::TraceWriter;
+use anyhow::{bail, Context, Result};
+use std::env;
+
+struct Config {
+    input: String,
+    output: String,
+    options: AnonymizeOptions,
+}
+
+fn parse_args() -> Result<Config> {
+    let mut args: Vec<String> = env::args().collect();
+    let log_level = logging::take_log_level_arg(&mut args).map_err(anyhow::Error::msg)?;
+    logging::init(log_level.unwrap_or(logging::Level::WARN), None);
+    let mut options = AnonymizeOptions {
+        salt: format!("{:016x}", rand::random::<u64>()),
+        ..AnonymizeOptions::default()
+    };
+    let mut files = Vec::new();
+
+    let mut i = 1;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        match arg {
+            "-names" | "-event-names" | "-descriptions" | "-attr-action" => {
+                i += 1;
+                let value = args.get(i).with_context(|| format!("{} needs keep, strip or hash", arg))?;
+                let action = FieldAction::parse(value)
+                    .with_context(|| format!("{}: expected keep, strip or hash, got '{}'", arg, value))?;
+                match arg {
+                    "-names" => options.names = action,
+                    "-event-names" => options.event_names = action,
+                    "-descriptions" => options.descriptions = action,
+                    _ => options.attribute_action = action,
+                }
+            }
+            "-attrs" => {
+                i += 1;
+                let keys = args.get(i).context("-attrs needs a comma-separated key list")?;
+                options.attributes.extend(keys.split(',').map(str::trim).filter(|key| !key.is_empty()).map(str::to_string));
+            }
+            "-salt" => {
+                i += 1;
+                options.salt = args.get(i).context("-salt needs a value")?.clone();
+            }
+            "-h" | "-help" | "--help" => {
+                print_help();
+                std::process::exit(0);
+            }
+            arg if arg.starts_with('-') => {
+                tracing::warn!("Unknown argument: {}", arg);
+            }
+            file => files.push(file.to_string()),
+        }
+        i += 1;
+    }
+
+    let [input, output]: [String; 2] = match files.try_into() {
+        Ok(files) => files,
+        Err(_) => {
+            print_help();
+            std::process::exit(2);
+        }
+    };
+    if input == output {
+        bail!("Refusing to overwrite the input trace {}", input);
+    }
+    // Checked before the output is created, so a typo does not truncate it
+    if !std::path::Path::new(&input).is_file() {
+        bail!("No such trace file: {}", input);
+    }
+    Ok(Config { input, output, options })
+}
+
+fn print_help() {
+    println!("JETS Trace Anonymizer");
+    println!("Usage: jets-anonymize [OPTIONS] <INPUT> <OUTPUT>");
+    println!();
+    println!("Rewrites a trace for sharing: names, descriptions and chosen attribute values");
+    println!("are stripped or replaced by salted hashes, while IDs, record types, clocks and");
+    println!("the record tree are kept. Equal strings get equal hashes under one salt.");
+    println!("OUTPUT is Brotli-compressed if it ends in .br.");
+    println!();
+    println!("OPTIONS:");
+    println!("  -names <ACTION>         Record names: keep, strip or hash (default: hash)");
+    println!("  -event-names <ACTION>   Event names (default: keep)");
+    println!("  -descriptions <ACTION>  Record, event and annotation descriptions (default: strip)");
+    println!("  -attrs <KEY,...>        Attribute keys whose values are anonymized ('*' for all);");
+    println!("                          also applies to annotations and header metadata");
+    println!("  -attr-action <ACTION>   What to do with those attributes (default: hash)");
+    println!("  -salt <TEXT>            Hash salt; reuse it to get matching hashes across traces");
+    println!("                          (default: random per run)");
+    println!("  --log-level <LEVEL>     Log verbosity: error, warn, info, debug, trace (default: warn)");
+    println!("  -h, -help, --help       Show this help message");
+}
+
+fn main() -> Result<()> {
+    let config = parse_args()?;
+
+    let started = std::time::Instant::now();
+    let mut writer = TraceWriter::new(&config.output)?
+        .with_provenance(concat!("jets-anonymize ", env!("CARGO_PKG_VERSION")));
+    let summary = anonymizer::anonymize_file(&config.input, &mut writer, &config.options)?;
+    writer.finish()?;
+    tracing::info!(lines = summary.lines, elapsed_ms = started.elapsed().as_millis(), "anonymized");
+
+    for warning in &summary.warnings {
+        println!("{}: warning: {}", config.input, warning);
+    }
+    println!(
+        "{} -> {}: {} lines, {} fields hashed, {} stripped",
+        config.input, config.output, summary.lines, summary.hashed, summary.stripped
+    );
+    Ok(())
+}
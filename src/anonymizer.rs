@@ -0,0 +1,329 @@
+//! Anonymizing traces for sharing outside the organization.
+//!
+//! The rewrite streams over the JSONL lines of a trace and replaces the
+//! sensitive strings, leaving IDs, record types, clocks and the tree intact:
+//! a shared trace keeps its structure and timing but not what was running.
+//! Each field is kept, stripped or hashed ([`FieldAction`]). Hashing is
+//! salted SHA-256, so equal strings map to equal tokens under one salt (a
+//! name repeated across records stays recognizable as the same name) while
+//! common names cannot be looked up without the salt.
+//!
+//! Attribute values are selected by key, in record and event `data`, in
+//! annotations (keyed by annotation name) and in the header metadata. Traces
+//! of older versions are upgraded to the current layout on the way through;
+//! the footer is rewritten, so its counts and any provenance hash describe
+//! the new file. String record IDs are kept as they are.
+
+use std::io::BufRead;
+
+use anyhow::{bail, Context, Result};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::compat::LegacyLayout;
+use crate::parser::open_trace_file;
+use crate::schema::FormatVersion;
+use crate::streaming::check_version;
+use crate::writer::{LineCounts, TraceWriter};
+
+/// Attribute key selecting every attribute.
+pub const ALL_ATTRIBUTES: &str = "*";
+
+/// Hex digits kept from the hash in a token.
+const TOKEN_LEN: usize = 12;
+
+/// What happens to one kind of field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldAction {
+    Keep,
+    /// Names and descriptions become empty; attributes are removed
+    Strip,
+    /// Replaced by a salted hash token
+    Hash,
+}
+
+impl FieldAction {
+    /// Parses `keep`, `strip` or `hash`.
+    pub fn parse(text: &str) -> Option<Self> {
+        match text {
+            "keep" => Some(FieldAction::Keep),
+            "strip" => Some(FieldAction::Strip),
+            "hash" => Some(FieldAction::Hash),
+            _ => None,
+        }
+    }
+}
+
+/// Which fields to anonymize, and how.
+#[derive(Debug, Clone)]
+pub struct AnonymizeOptions {
+    /// Record names
+    pub names: FieldAction,
+    /// Event names (often pipeline stages, so kept by default)
+    pub event_names: FieldAction,
+    /// Record, event and annotation descriptions
+    pub descriptions: FieldAction,
+    /// Attribute keys whose values are anonymized ([`ALL_ATTRIBUTES`] for all)
+    pub attributes: Vec<String>,
+    pub attribute_action: FieldAction,
+    /// Mixed into every hash
+    pub salt: String,
+}
+
+impl Default for AnonymizeOptions {
+    fn default() -> Self {
+        AnonymizeOptions {
+            names: FieldAction::Hash,
+            event_names: FieldAction::Keep,
+            descriptions: FieldAction::Strip,
+            attributes: Vec::new(),
+            attribute_action: FieldAction::Hash,
+            salt: String::new(),
+        }
+    }
+}
+
+/// What an anonymizing pass changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AnonymizeSummary {
+    /// Lines written
+    pub lines: usize,
+    /// Fields replaced by a token
+    pub hashed: usize,
+    /// Fields emptied or removed
+    pub stripped: usize,
+    pub warnings: Vec<String>,
+}
+
+/// Anonymizes the trace at `input_path` (`.br` is decompressed) into `writer`.
+pub fn anonymize_file(input_path: &str, writer: &mut TraceWriter, options: &AnonymizeOptions) -> Result<AnonymizeSummary> {
+    let reader = open_trace_file(input_path)?;
+    anonymize_reader(reader, writer, options).with_context(|| format!("Failed to anonymize {}", input_path))
+}
+
+/// Anonymizes the JETS lines of `reader` into `writer`.
+pub fn anonymize_reader<R: BufRead>(reader: R, writer: &mut TraceWriter, options: &AnonymizeOptions) -> Result<AnonymizeSummary> {
+    let mut rewriter = Rewriter {
+        options,
+        summary: AnonymizeSummary::default(),
+    };
+    let mut layout: Option<&LegacyLayout> = None;
+
+    for (index, line) in reader.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.with_context(|| format!("Failed to read line {}", line_number))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Value::Object(mut object) =
+            serde_json::from_str(&line).with_context(|| format!("Invalid JSON at line {}", line_number))?
+        else {
+            bail!("Line {} is not a JSON object", line_number);
+        };
+        let line_type = object.get("type").and_then(Value::as_str).unwrap_or_default().to_string();
+
+        match line_type.as_str() {
+            "header" => {
+                let version = object.get("version").and_then(Value::as_str).unwrap_or_default();
+                layout = check_version(version, &mut rewriter.summary.warnings);
+                let mut metadata = match object.remove("metadata") {
+                    Some(Value::Object(metadata)) => metadata,
+                    _ => Map::new(),
+                };
+                rewriter.attributes(&mut metadata);
+                writer.write_header(&FormatVersion::CURRENT.to_string(), Value::Object(metadata))?;
+            }
+            "footer" => {
+                writer.write_footer(object.get("capture_end_clk").and_then(Value::as_i64))?;
+            }
+            _ => {
+                if let Some(layout) = layout {
+                    layout.upgrade(&mut object);
+                }
+                let mut counts = LineCounts::default();
+                match line_type.as_str() {
+                    "record" => {
+                        rewriter.field(&mut object, "name", options.names);
+                        rewriter.record_or_event(&mut object);
+                        counts.records = 1;
+                    }
+                    "event" => {
+                        rewriter.field(&mut object, "name", options.event_names);
+                        rewriter.record_or_event(&mut object);
+                        counts.events = 1;
+                    }
+                    "annotation" => {
+                        if !rewriter.annotation(&mut object) {
+                            continue;
+                        }
+                        counts.annotations = 1;
+                    }
+                    _ => {}
+                }
+                let text = serde_json::to_string(&object)?;
+                writer.write_serialized(&format!("{}\n", text), counts)?;
+            }
+        }
+        rewriter.summary.lines += 1;
+    }
+    Ok(rewriter.summary)
+}
+
+/// Applies the options to line objects.
+struct Rewriter<'a> {
+    options: &'a AnonymizeOptions,
+    summary: AnonymizeSummary,
+}
+
+impl Rewriter<'_> {
+    /// Returns the token for `text`, the same for equal texts.
+    fn token(&self, text: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.options.salt.as_bytes());
+        hasher.update([0]);
+        hasher.update(text.as_bytes());
+        let mut token: String = hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect();
+        token.truncate(TOKEN_LEN);
+        token
+    }
+
+    /// Rewrites a string field in place.
+    fn field(&mut self, object: &mut Map<String, Value>, key: &str, action: FieldAction) {
+        let Some(Value::String(text)) = object.get_mut(key) else { return };
+        if text.is_empty() {
+            return;
+        }
+        match action {
+            FieldAction::Keep => {}
+            FieldAction::Strip => {
+                text.clear();
+                self.summary.stripped += 1;
+            }
+            FieldAction::Hash => {
+                *text = self.token(text);
+                self.summary.hashed += 1;
+            }
+        }
+    }
+
+    fn record_or_event(&mut self, object: &mut Map<String, Value>) {
+        self.field(object, "description", self.options.descriptions);
+        if let Some(Value::Object(data)) = object.get_mut("data") {
+            let mut data = std::mem::take(data);
+            self.attributes(&mut data);
+            object.insert("data".to_string(), Value::Object(data));
+        }
+    }
+
+    /// Rewrites an annotation; returns false if it is to be dropped.
+    fn annotation(&mut self, object: &mut Map<String, Value>) -> bool {
+        self.field(object, "description", self.options.descriptions);
+        let name = object.get("name").and_then(Value::as_str).unwrap_or_default();
+        if !self.selects(name) {
+            return true;
+        }
+        match self.options.attribute_action {
+            FieldAction::Keep => true,
+            FieldAction::Strip => {
+                self.summary.stripped += 1;
+                false
+            }
+            FieldAction::Hash => {
+                let value = object.get("data").map(|data| self.value_token(data));
+                if let Some(token) = value {
+                    object.insert("data".to_string(), token);
+                    self.summary.hashed += 1;
+                }
+                true
+            }
+        }
+    }
+
+    /// Rewrites the selected attributes of a `data` or metadata object.
+    fn attributes(&mut self, data: &mut Map<String, Value>) {
+        if self.options.attribute_action == FieldAction::Keep || self.options.attributes.is_empty() {
+            return;
+        }
+        let keys: Vec<String> = data.keys().filter(|key| self.selects(key)).cloned().collect();
+        for key in keys {
+            match self.options.attribute_action {
+                FieldAction::Keep => {}
+                FieldAction::Strip => {
+                    data.remove(&key);
+                    self.summary.stripped += 1;
+                }
+                FieldAction::Hash => {
+                    let token = self.value_token(&data[&key]);
+                    data.insert(key, token);
+                    self.summary.hashed += 1;
+                }
+            }
+        }
+    }
+
+    fn selects(&self, key: &str) -> bool {
+        self.options.attributes.iter().any(|selected| selected == key || selected == ALL_ATTRIBUTES)
+    }
+
+    /// Hashes strings by content (so they match hashed names) and other
+    /// values by their JSON text.
+    fn value_token(&self, value: &Value) -> Value {
+        match value {
+            Value::String(text) => Value::String(self.token(text)),
+            value => Value::String(self.token(&value.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::perf_reader::SharedBuffer;
+    use crate::traits::{AttributeAccessor, TraceData, TraceEvent, TraceMetadata, TraceRecord};
+
+    #[test]
+    fn test_anonymize_keeps_structure_and_timing() {
+        let input = concat!(
+            r#"{"type":"header","version":"1.0","metadata":{"customer":"acme","clk_unit":"ns"}}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"secret_kernel","kind":"Thread","id":1,"parent":null,"description":"core 0"}"#, "\n",
+            r#"{"type":"record","clk":2,"name":"secret_kernel","kind":"Op","id":2,"parent":1,"data":{"path":"/home/a","pc":64}}"#, "\n",
+            r#"{"type":"annotation","name":"path","id":2,"data":"/home/b"}"#, "\n",
+            r#"{"type":"event","clk":3,"name":"D","id":2,"description":"decode"}"#, "\n",
+            r#"{"type":"record_end","clk":6,"id":2}"#, "\n",
+            r#"{"type":"footer","capture_end_clk":9,"total_records":2}"#, "\n",
+        );
+        let options = AnonymizeOptions {
+            attributes: vec!["path".to_string(), "customer".to_string()],
+            salt: "s".to_string(),
+            ..AnonymizeOptions::default()
+        };
+        let buffer = SharedBuffer(Rc::new(RefCell::new(Vec::new())));
+        let mut writer = TraceWriter::from_writer(Box::new(buffer.clone()));
+        let summary = anonymize_reader(input.as_bytes(), &mut writer, &options).unwrap();
+        drop(writer);
+        assert_eq!(summary.lines, 7);
+        assert_eq!((summary.hashed, summary.stripped), (5, 2));
+
+        let output = buffer.0.take();
+        let text = String::from_utf8(output.clone()).unwrap();
+        for secret in ["secret_kernel", "acme", "/home", "core 0", "decode"] {
+            assert!(!text.contains(secret), "{} leaked", secret);
+        }
+
+        let trace = crate::parser::parse_trace_from_reader(output.as_slice()).unwrap();
+        assert_eq!(trace.metadata().version(), FormatVersion::CURRENT.to_string());
+        assert_eq!(trace.metadata().capture_end_clk(), Some(9));
+        assert_eq!(trace.metadata().header_data()["clk_unit"], "ns");
+        let thread = trace.get_record(trace.root_ids()[0]).unwrap();
+        let op = thread.child_at(0).unwrap();
+        assert_eq!(thread.name(), op.name(), "equal names get equal tokens");
+        assert_eq!(thread.name().len(), TOKEN_LEN);
+        assert_eq!((op.record_type(), op.clk(), op.end_clk()), ("Op".to_string(), 2, Some(6)));
+        assert_eq!(op.attr("pc"), Some(serde_json::json!(64)));
+        let event = op.event_at(0).unwrap();
+        assert_eq!((event.name(), event.description(), event.clk()), ("D".to_string(), String::new(), 3));
+    }
+}
@@ -0,0 +1,182 @@
+//! Registry of named application actions.
+//!
+//! Every operation the user can trigger by name registers here with an
+//! optional keyboard shortcut and a handler. The command palette lists the
+//! registry, and shortcuts are dispatched from it, so menus and configurable
+//! keybindings can be built on the same list.
+
+use egui::KeyboardShortcut;
+
+use crate::app::{AppState, ApplicationCoordinator, ThemeCoordinator};
+use crate::ui::panel_manager::PanelInteraction;
+
+/// Runs an action. Actions that need the loader or the egui context return
+/// an interaction that is handled like one coming from a panel.
+pub type ActionHandler = Box<dyn Fn(&mut AppState) -> Option<PanelInteraction>>;
+
+/// A named action.
+pub struct Action {
+    /// Name shown in the command palette
+    pub name: String,
+    pub shortcut: Option<KeyboardShortcut>,
+    handler: ActionHandler,
+}
+
+/// All actions, in registration order.
+pub struct ActionRegistry {
+    actions: Vec<Action>,
+}
+
+impl Default for ActionRegistry {
+    fn default() -> Self {
+        Self::with_default_actions()
+    }
+}
+
+impl ActionRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self { actions: Vec::new() }
+    }
+
+    /// Creates a registry holding the actions of all coordinators.
+    pub fn with_default_actions() -> Self {
+        let mut registry = Self::new();
+        ApplicationCoordinator::register_actions(&mut registry);
+        ThemeCoordinator::register_actions(&mut registry);
+        registry
+    }
+
+    // ===== Queries =====
+
+    /// Returns all actions in registration order.
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    /// Returns the indices of the actions matching a fuzzy query, best first.
+    ///
+    /// An empty query matches every action in registration order.
+    pub fn matching(&self, query: &str) -> Vec<usize> {
+        let mut scored: Vec<(i32, usize)> = self
+            .actions
+            .iter()
+            .enumerate()
+            .filter_map(|(index, action)| fuzzy_score(query, &action.name).map(|score| (score, index)))
+            .collect();
+        // Stable sort keeps registration order among equal scores
+        scored.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+        scored.into_iter().map(|(_, index)| index).collect()
+    }
+
+    // ===== Mutations =====
+
+    /// Registers an action.
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        shortcut: Option<KeyboardShortcut>,
+        handler: impl Fn(&mut AppState) -> Option<PanelInteraction> + 'static,
+    ) {
+        self.actions.push(Action {
+            name: name.into(),
+            shortcut,
+            handler: Box::new(handler),
+        });
+    }
+
+    /// Runs the action at `index`.
+    pub fn invoke(&self, index: usize, state: &mut AppState) -> Option<PanelInteraction> {
+        self.actions.get(index).and_then(|action| (action.handler)(state))
+    }
+
+    /// Consumes a pressed shortcut and returns the index of its action.
+    ///
+    /// Shortcuts with more modifiers are tried first, since egui ignores extra
+    /// Shift/Alt when matching (Ctrl+P would otherwise eat Ctrl+Shift+P).
+    pub fn consume_shortcut(&self, ctx: &egui::Context) -> Option<usize> {
+        let mut candidates: Vec<(usize, KeyboardShortcut)> = self
+            .actions
+            .iter()
+            .enumerate()
+            .filter_map(|(index, action)| action.shortcut.map(|shortcut| (index, shortcut)))
+            .collect();
+        candidates.sort_by_key(|(_, shortcut)| std::cmp::Reverse(modifier_count(shortcut.modifiers)));
+
+        ctx.input_mut(|input| {
+            candidates
+                .into_iter()
+                .find(|(_, shortcut)| input.consume_shortcut(shortcut))
+                .map(|(index, _)| index)
+        })
+    }
+}
+
+/// Returns the number of modifier keys held in a shortcut.
+fn modifier_count(modifiers: egui::Modifiers) -> usize {
+    [modifiers.alt, modifiers.ctrl || modifiers.command || modifiers.mac_cmd, modifiers.shift]
+        .into_iter()
+        .filter(|&held| held)
+        .count()
+}
+
+/// Scores `candidate` against a fuzzy `query`, or None if it does not match.
+///
+/// The query characters (whitespace ignored, case-insensitive) must appear in
+/// order. Matches right after the previous one or at a word start score
+/// higher; skipped characters cost a little.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let chars: Vec<char> = candidate.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+
+    for q in query.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_lowercase) {
+        let found = (next..chars.len()).find(|&i| chars[i] == q)?;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !chars[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        score -= (found - next).min(5) as i32;
+        previous = Some(found);
+        next = found + 1;
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score_prefers_word_starts_and_runs() {
+        assert!(fuzzy_score("zf", "Zoom to Fit").is_some());
+        assert!(fuzzy_score("fz", "Zoom to Fit").is_none());
+        assert_eq!(fuzzy_score("", "Anything"), Some(0));
+        assert_eq!(fuzzy_score("ZOOM", "Zoom In"), fuzzy_score("zoom", "Zoom In"));
+        // A run starting a word beats a run across word boundaries
+        assert!(fuzzy_score("fit", "Zoom to Fit") > fuzzy_score("oto", "Zoom to Fit"));
+    }
+
+    #[test]
+    fn test_registry_matches_and_invokes_actions() {
+        let mut registry = ActionRegistry::new();
+        registry.register("Toggle Low Power Mode", None, |state| {
+            state.repaint.set_low_power(!state.repaint.low_power_enabled());
+            None
+        });
+        registry.register("Zoom to Fit", None, |_| None);
+
+        assert_eq!(registry.matching("fit"), vec![1]);
+        assert_eq!(registry.matching(""), vec![0, 1]);
+        assert_eq!(registry.matching("om"), vec![1, 0]);
+
+        let mut state = AppState::new();
+        let index = registry.matching("low power")[0];
+        assert!(registry.invoke(index, &mut state).is_none());
+        assert!(state.repaint.low_power_enabled());
+    }
+}
@@ -8,9 +8,14 @@
 //! - Mirrors established Rust UI projects (dioxus, iced)
 
 use crate::cache::TreeCache;
+use crate::domain::sorting::SortKeyRegistry;
+use crate::domain::tree_operations::{self, FilteredVisibleNode};
 use crate::state::{
     TraceState, ViewportState, SelectionState, TreeState,
-    InteractionState, ThemeState, LayoutState
+    InteractionState, ThemeState, LayoutState, NotesState, LaneState,
+    RepaintState, MiniTimelineState, TypeLegendState, ClockFilterState, ColumnFilterState, EventFilterState, FilterState, CommandPaletteState, InputSettingsState, TooltipState,
+    WindowState, ReloadState, DockState, IdleGapState, TraceHealthState, TypeHierarchyState, FocusState, LogConsoleState,
+    MeasurementState, FlameGraphState, RecentFilesState,
 };
 
 /// Main application state composed of focused state components.
@@ -43,12 +48,90 @@ pub struct AppState {
     /// UI layout state
     pub layout: LayoutState,
 
+    /// User notes and tags on records
+    pub notes: NotesState,
+
+    /// Root lanes and root selection
+    pub lanes: LaneState,
+
+    /// Repaint scheduling and low-power mode
+    pub repaint: RepaintState,
+
+    /// Details panel mini timeline zoom
+    pub mini_timeline: MiniTimelineState,
+
+    /// Details panel flame chart zoom
+    pub flame_chart: MiniTimelineState,
+
+    /// Record type colors and visibility
+    pub type_legend: TypeLegendState,
+
+    /// Clock stride record filter
+    pub clock_filter: ClockFilterState,
+
+    /// Table header column filters
+    pub column_filter: ColumnFilterState,
+
+    /// Minimum severity of shown events
+    pub event_filter: EventFilterState,
+
+    /// Event names hidden from the timeline and details panel
+    pub event_type_filter: FilterState,
+
+    /// Command palette overlay
+    pub command_palette: CommandPaletteState,
+
+    /// Click actions, wheel orientation and zoom sensitivity
+    pub input_settings: InputSettingsState,
+
+    /// Record tooltip hover tracking and pinned tooltip
+    pub tooltip: TooltipState,
+
+    /// Main window placement per monitor configuration
+    pub window: WindowState,
+
+    /// User state carried over a trace reload
+    pub reload: ReloadState,
+
+    /// Panel arrangement and layout presets
+    pub dock: DockState,
+
+    /// Idle gap window and results
+    pub idle_gaps: IdleGapState,
+
+    /// Trace health window and the last check's result
+    pub trace_health: TraceHealthState,
+
+    /// Record type hierarchy window and the folded hierarchy
+    pub type_hierarchy: TypeHierarchyState,
+
+    /// Keyboard focus order between panels and focus outline visibility
+    pub focus: FocusState,
+
+    /// Log console entries and filters
+    pub log_console: LogConsoleState,
+
+    /// A/B measurement cursors on the timeline
+    pub measurement: MeasurementState,
+
+    /// Flame graph mode of the timeline panel
+    pub flame_graph: FlameGraphState,
+
+    /// Recently opened trace files for the header's Recent menu
+    pub recent_files: RecentFilesState,
+
     // ===== Top-Level State =====
     /// Current error message to display (if any)
     pub error_message: Option<String>,
 
+    /// Notice about settings migrated or reset at startup, until dismissed
+    pub settings_notice: Option<String>,
+
     /// Tree computation cache for performance optimization
     pub tree_cache: TreeCache,
+
+    /// Custom sort keys offered next to the built-in ones
+    pub sort_keys: SortKeyRegistry,
 }
 
 impl Default for AppState {
@@ -68,23 +151,34 @@ impl AppState {
             interaction: InteractionState::new(),
             theme: ThemeState::new(),
             layout: LayoutState::new(),
+            notes: NotesState::new(),
+            lanes: LaneState::new(),
+            repaint: RepaintState::new(),
+            mini_timeline: MiniTimelineState::new(),
+            flame_chart: MiniTimelineState::new(),
+            type_legend: TypeLegendState::new(),
+            clock_filter: ClockFilterState::new(),
+            column_filter: ColumnFilterState::new(),
+            event_filter: EventFilterState::new(),
+            event_type_filter: FilterState::new(),
+            command_palette: CommandPaletteState::new(),
+            input_settings: InputSettingsState::new(),
+            tooltip: TooltipState::new(),
+            window: WindowState::new(),
+            reload: ReloadState::new(),
+            dock: DockState::new(),
+            idle_gaps: IdleGapState::new(),
+            trace_health: TraceHealthState::new(),
+            type_hierarchy: TypeHierarchyState::new(),
+            focus: FocusState::new(),
+            log_console: LogConsoleState::new(),
+            measurement: MeasurementState::new(),
+            flame_graph: FlameGraphState::new(),
+            recent_files: RecentFilesState::new(),
             error_message: None,
+            settings_notice: None,
             tree_cache: TreeCache::new(),
-        }
-    }
-
-    /// Creates a new AppState with a specific theme loaded from storage.
-    pub fn with_theme(theme_name: String) -> Self {
-        Self {
-            trace: TraceState::new(),
-            viewport: ViewportState::new(),
-            selection: SelectionState::new(),
-            tree: TreeState::new(),
-            interaction: InteractionState::new(),
-            theme: ThemeState::with_theme(theme_name),
-            layout: LayoutState::new(),
-            error_message: None,
-            tree_cache: TreeCache::new(),
+            sort_keys: SortKeyRegistry::new(),
         }
     }
 
@@ -101,8 +195,34 @@ impl AppState {
             interaction: InteractionState::new(),
             theme: ThemeState::with_theme(theme_name),
             layout,
+            notes: NotesState::new(),
+            lanes: LaneState::new(),
+            repaint: RepaintState::new(),
+            mini_timeline: MiniTimelineState::new(),
+            flame_chart: MiniTimelineState::new(),
+            type_legend: TypeLegendState::new(),
+            clock_filter: ClockFilterState::new(),
+            column_filter: ColumnFilterState::new(),
+            event_filter: EventFilterState::new(),
+            event_type_filter: FilterState::new(),
+            command_palette: CommandPaletteState::new(),
+            input_settings: InputSettingsState::new(),
+            tooltip: TooltipState::new(),
+            window: WindowState::new(),
+            reload: ReloadState::new(),
+            dock: DockState::new(),
+            idle_gaps: IdleGapState::new(),
+            trace_health: TraceHealthState::new(),
+            type_hierarchy: TypeHierarchyState::new(),
+            focus: FocusState::new(),
+            log_console: LogConsoleState::new(),
+            measurement: MeasurementState::new(),
+            flame_graph: FlameGraphState::new(),
+            recent_files: RecentFilesState::new(),
             error_message: None,
+            settings_notice: None,
             tree_cache: TreeCache::new(),
+            sort_keys: SortKeyRegistry::new(),
         }
     }
 
@@ -117,8 +237,23 @@ impl AppState {
         self.selection.clear();
         self.tree.clear();
         self.interaction.reset();
+        self.notes.clear();
+        self.lanes.clear();
+        self.mini_timeline.reset();
+        self.flame_chart.reset();
+        self.type_legend.clear();
+        self.clock_filter.clear();
+        self.column_filter.clear();
+        self.tooltip.clear();
+        self.reload.clear();
+        self.idle_gaps.clear();
+        self.trace_health.clear();
+        self.type_hierarchy.clear();
+        self.measurement.clear();
+        self.flame_graph.clear();
         self.error_message = None;
         self.tree_cache.invalidate();
+        self.tree_cache.clear_trace_summary();
     }
 
     /// Initializes viewport after trace data is loaded.
@@ -130,4 +265,39 @@ impl AppState {
         self.viewport.fit_to_trace(min_clk, max_clk);
     }
 
+    /// Collects every tree row in display order, with the active filters,
+    /// sort and pinned rows applied (empty without a trace).
+    pub fn tree_rows(&self) -> Vec<FilteredVisibleNode> {
+        let Some(trace) = self.trace.trace_data() else {
+            return Vec::new();
+        };
+        let nodes = if self.viewport.viewport_filter_enabled() {
+            tree_operations::collect_viewport_filtered_nodes_with_sort(
+                trace,
+                self.tree.expanded_nodes_set(),
+                self.lanes.hidden_roots(),
+                self.type_legend.hidden_types(),
+                self.clock_filter.stride(),
+                self.column_filter.filters(),
+                &self.tree_cache,
+                self.tree.active_sort(),
+                self.viewport.viewport_start_clk(),
+                self.viewport.viewport_end_clk(),
+                self.viewport.hide_empty_parents(),
+            )
+        } else {
+            tree_operations::collect_unfiltered_visible_nodes_with_sort(
+                trace,
+                self.tree.expanded_nodes_set(),
+                self.lanes.hidden_roots(),
+                self.type_legend.hidden_types(),
+                self.clock_filter.stride(),
+                self.column_filter.filters(),
+                &self.tree_cache,
+                self.tree.active_sort(),
+            )
+        };
+        tree_operations::retain_pinned_rows(nodes, self.tree.pinned_rows())
+    }
+
 }
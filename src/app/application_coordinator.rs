@@ -3,13 +3,33 @@
 //! Handles high-level application operations like file loading, error handling,
 //! and coordinating between different subsystems.
 
-use crate::app::AppState;
+use crate::app::{ActionRegistry, AppState};
 use crate::io::{AsyncLoader, LoadResult};
-use crate::state::SortSpec;
-use crate::domain::sorting;
-use std::path::PathBuf;
-use std::collections::HashMap;
-use rjets::{TraceMetadata, TraceData, TraceRecord};
+use crate::io::jetspack::{self, PackManifest, ViewDescriptor};
+use crate::io::settings_profile::{self, SettingsProfile};
+use crate::io::trace_slice::TraceSlice;
+use crate::state::{ClickAction, ClickGesture, DockPanel, DockSlot, ExpansionPolicy, PreservedUserState, RowAlignment, SortDir, SortKey, SortSpec, MAIN_WINDOW, SPLIT_WINDOW};
+use crate::domain::{idle_gaps, record_identity, record_navigation, sorting, tree_operations, viewport_operations};
+use crate::domain::hidden_selection::HidingFilter;
+use crate::domain::record_navigation::NavigationScope;
+use crate::domain::sorting::SortKeyRegistry;
+use crate::domain::record_identity::RecordIdentity;
+use crate::domain::statistics::TraceStatistics;
+use crate::domain::trace_health::TraceHealth;
+use crate::domain::type_hierarchy::TypeHierarchy;
+use crate::domain::type_hints::TypeHints;
+use crate::ui::filter_chips;
+use crate::ui::header::{self, ReportScope};
+use crate::ui::panel_manager::PanelInteraction;
+use crate::ui::settings_dialog;
+use crate::ui::virtual_scroll_manager::VirtualScrollManager;
+use egui::{Key, KeyboardShortcut, Modifiers};
+use std::path::{Path, PathBuf};
+use std::collections::{HashMap, HashSet};
+use rjets::{DynTraceData, TraceMetadata, TraceData, TraceEvent, TraceRecord};
+
+/// Most idle gaps listed by the idle gap finder.
+const IDLE_GAP_LIMIT: usize = 100;
 
 /// Coordinates application-level operations and workflows.
 ///
@@ -34,19 +54,190 @@ impl ApplicationCoordinator {
         state.reset_trace_state();
 
         // Start async loading
-        loader.start_file_load(path, ctx);
+        let options = state.input_settings.settings().parse_options();
+        loader.start_file_load(path, options, ctx);
+    }
+
+    /// Opens one trace file, or several as one merged trace.
+    pub fn open_files(
+        state: &mut AppState,
+        loader: &mut AsyncLoader,
+        mut paths: Vec<PathBuf>,
+        ctx: &egui::Context,
+    ) {
+        if paths.len() == 1 {
+            Self::open_file(state, loader, paths.remove(0), ctx);
+            return;
+        }
+        state.reset_trace_state();
+        tracing::info!(files = paths.len(), "merging traces");
+        let options = state.input_settings.settings().parse_options();
+        loader.start_merged_load(paths, options, ctx);
+    }
+
+    /// Reloads the current trace from disk, keeping the user state.
+    ///
+    /// Notes, tags, pinned rows, expansion, selection and the viewport are
+    /// set aside and re-resolved against the new data when it arrives (see
+    /// [`Self::restore_user_state`]). Virtual traces have no file to reload.
+    pub fn reload_trace(state: &mut AppState, loader: &mut AsyncLoader, ctx: &egui::Context) {
+        let Some(trace) = state.trace.trace_data() else {
+            return;
+        };
+        let paths: Vec<PathBuf> = match trace {
+            DynTraceData::Multi(multi) => multi.sources().map(PathBuf::from).collect(),
+            _ => state.trace.file_path().cloned().into_iter().collect(),
+        };
+        if paths.is_empty() {
+            return;
+        }
+
+        tracing::info!(files = paths.len(), "reloading trace");
+        let preserved = Self::preserve_user_state(state);
+        Self::open_files(state, loader, paths, ctx);
+        state.reload.set_pending(preserved);
+    }
+
+    /// Collects the user state that refers to records of the current trace.
+    fn preserve_user_state(state: &AppState) -> PreservedUserState {
+        let mut preserved = PreservedUserState {
+            notes: state.notes.all().clone(),
+            pinned_rows: state.tree.pinned_rows().iter().copied().collect(),
+            expanded: state.tree.expanded_nodes_set().iter().copied().collect(),
+            selected_record: state.selection.selected_record_id(),
+            selected_event: state.selection.selected_event(),
+            viewport: (state.viewport.viewport_start_clk(), state.viewport.viewport_end_clk()),
+            ..Default::default()
+        };
+
+        if let Some(trace) = state.trace.trace_data() {
+            let mut ids: Vec<u64> = preserved.notes.keys().copied().collect();
+            ids.extend(&preserved.pinned_rows);
+            ids.extend(&preserved.expanded);
+            ids.extend(preserved.selected_record);
+            ids.extend(preserved.selected_event.map(|(id, _)| id));
+            ids.sort_unstable();
+            ids.dedup();
+            preserved.identities = ids
+                .into_iter()
+                .filter_map(|id| trace.get_record(id).map(|record| RecordIdentity::of(&record)))
+                .collect();
+        }
+        preserved
+    }
+
+    /// Re-resolves user state set aside by [`Self::reload_trace`].
+    ///
+    /// Notes, tags and pinned rows whose record cannot be found again are
+    /// listed in the reload report; expansion and selection are dropped quietly.
+    fn restore_user_state(state: &mut AppState, preserved: PreservedUserState) {
+        let Some(trace) = state.trace.trace_data() else {
+            return;
+        };
+        let matched = record_identity::rematch(trace, &preserved.identities);
+        let describe = |id: u64| {
+            preserved
+                .identities
+                .iter()
+                .find(|identity| identity.id == id)
+                .map(RecordIdentity::describe)
+                .unwrap_or_else(|| format!("record {}", id))
+        };
+
+        let mut unmatched = Vec::new();
+        let mut notes = std::collections::BTreeMap::new();
+        for (id, note) in preserved.notes {
+            match matched.get(&id) {
+                Some(&new_id) => {
+                    notes.insert(new_id, note);
+                }
+                None => unmatched.push(format!("Note on {}", describe(id))),
+            }
+        }
+        state.notes.replace_all(notes);
+
+        let mut pinned = Vec::new();
+        for id in preserved.pinned_rows {
+            match matched.get(&id) {
+                Some(&new_id) => pinned.push(new_id),
+                None => unmatched.push(format!("Pinned row {}", describe(id))),
+            }
+        }
+        state.tree.pin_rows(pinned);
+
+        for id in preserved.expanded {
+            if let Some(&new_id) = matched.get(&id) {
+                state.tree.expand(new_id);
+            }
+        }
+        if let Some(&record_id) = preserved.selected_record.and_then(|id| matched.get(&id)) {
+            state.selection.select_record(record_id, None);
+        }
+        if let Some((id, event_clk)) = preserved.selected_event {
+            if let Some(&record_id) = matched.get(&id) {
+                state.selection.select_event(record_id, event_clk);
+            }
+        }
+
+        let (start, end) = preserved.viewport;
+        if end > start {
+            state.viewport.set_range(start, end, state.trace.min_clk(), state.trace.max_clk());
+        }
+        state.tree_cache.invalidate();
+        state.reload.set_unmatched(unmatched);
+    }
+
+    /// Cancels the load in progress, leaving no trace loaded.
+    ///
+    /// The previous trace was already cleared when the load started; state
+    /// set aside for a reload is dropped with it.
+    pub fn cancel_load(state: &mut AppState, loader: &mut AsyncLoader) {
+        if loader.cancel_load() {
+            state.reload.clear();
+        }
     }
 
     /// Checks for loading completion and applies results to application state.
     ///
     /// Called once per frame in the update loop.
     /// Returns true if a load operation completed (success or error).
-    pub fn check_loading_completion(state: &mut AppState, loader: &mut AsyncLoader) -> bool {
+    pub fn check_loading_completion(state: &mut AppState, loader: &mut AsyncLoader, ctx: &egui::Context) -> bool {
+        if let Some((generation, summary)) = loader.check_precompute() {
+            // A summary of a trace that has since been replaced is dropped
+            if state.trace.is_current(generation) {
+                state.tree_cache.trace_summary = Some(summary);
+            }
+        }
+        if let Some((generation, result)) = loader.check_verification() {
+            if state.trace.is_current(generation) {
+                state.trace.set_verification(result);
+            }
+        }
+        if let Some((generation, graph)) = loader.check_flame_graph() {
+            if state.trace.is_current(generation) {
+                state.flame_graph.set_graph(graph);
+            }
+        }
+        if let Some(Err(e)) = loader.check_report_export() {
+            Self::show_error(state, format!("Error exporting report: {}", e));
+        }
+        if let Some(Err(e)) = loader.check_file_export() {
+            Self::show_error(state, e);
+        }
+
         match loader.check_completion() {
-            LoadResult::Success { data, path } => {
+            LoadResult::Success { data, path, session } => {
                 // Success: Initialize trace data and viewport
                 let (min_clk, max_clk) = data.metadata().trace_extent();
 
+                let opened: Vec<PathBuf> = match &data {
+                    DynTraceData::Multi(multi) => multi.sources().map(PathBuf::from).collect(),
+                    _ => path.iter().cloned().collect(),
+                };
+                // The first file of a merge ends up the most recent
+                for path in opened.iter().rev() {
+                    state.recent_files.record_opened(path);
+                }
                 state.trace.load_trace(data, path);
                 state.error_message = None;
                 state.tree.clear();
@@ -54,12 +245,26 @@ impl ApplicationCoordinator {
                 state.tree_cache.invalidate();
 
                 state.initialize_viewport(min_clk, max_clk);
+                Self::load_type_hints(state);
+                let preserved = state.reload.take_pending();
+                if session.is_none() && preserved.is_none() {
+                    Self::apply_initial_expansion(state);
+                }
+                if let Some(manifest) = session {
+                    Self::apply_session_manifest(state, manifest);
+                }
+                if let Some(preserved) = preserved {
+                    Self::restore_user_state(state, preserved);
+                }
+                Self::start_precompute(state, loader, ctx);
+                Self::start_verification(state, loader, ctx);
                 true
             }
             LoadResult::Error(error_msg) => {
                 // Error: Display error message
                 state.error_message = Some(format!("Error loading trace: {}", error_msg));
                 state.trace.clear();
+                state.reload.clear();
                 true
             }
             LoadResult::None => {
@@ -69,27 +274,429 @@ impl ApplicationCoordinator {
         }
     }
 
-    /// Generates and loads a virtual trace in-memory.
+    /// Starts generating a virtual trace in-memory.
     ///
-    /// This is useful for testing and demonstration purposes.
-    pub fn open_virtual_trace(state: &mut AppState, loader: &mut AsyncLoader) {
-        match loader.load_virtual_trace() {
-            Ok(data) => {
-                // Get trace extent from metadata
-                let (min_clk, max_clk) = data.metadata().trace_extent();
+    /// This is useful for testing and demonstration purposes. The trace is
+    /// applied by `check_loading_completion` like a file load.
+    pub fn open_virtual_trace(state: &mut AppState, loader: &mut AsyncLoader, ctx: &egui::Context) {
+        state.reset_trace_state();
+        loader.start_virtual_load(ctx);
+    }
 
-                state.trace.load_trace(data, None);
-                state.error_message = None;
-                state.tree.clear();
-                state.selection.clear();
+    /// Reads the per-type hints of a newly opened trace, unless the user turned them off.
+    fn load_type_hints(state: &mut AppState) {
+        let honor = state.input_settings.settings().type_hints;
+        state.type_legend.set_trace_hints(TypeHints::for_trace(state.trace.trace_data(), honor));
+    }
+
+    /// Expands the tree of a newly opened trace as the settings and the
+    /// trace's type hints ask.
+    fn apply_initial_expansion(state: &mut AppState) {
+        let Some(trace) = state.trace.trace_data() else {
+            return;
+        };
+        let hints = state.type_legend.trace_hints();
+        let expanded_types: HashSet<&str> = hints.types_with_collapsed(false).collect();
+        if !expanded_types.is_empty() {
+            for record in trace.records_iter() {
+                if record.num_children() > 0 && expanded_types.contains(record.record_type().as_str()) {
+                    state.tree.expand(record.id());
+                }
+            }
+            state.tree_cache.invalidate();
+        }
+
+        let settings = state.input_settings.settings();
+        match settings.initial_expansion {
+            ExpansionPolicy::CollapseAll => {}
+            ExpansionPolicy::ExpandToDepth => {
+                let collapsed_types: HashSet<&str> = hints.types_with_collapsed(true).collect();
+                for id in tree_operations::parents_above_depth(trace, settings.initial_expand_depth) {
+                    let hinted_collapsed = trace
+                        .get_record(id)
+                        .is_some_and(|record| collapsed_types.contains(record.record_type().as_str()));
+                    if !hinted_collapsed {
+                        state.tree.expand(id);
+                    }
+                }
                 state.tree_cache.invalidate();
+            }
+            ExpansionPolicy::ExpandLongestPath => {
+                let Some((record_id, clk, first_event_clk)) = tree_operations::longest_record(trace)
+                    .and_then(|id| trace.get_record(id))
+                    .map(|record| (record.id(), record.clk(), record.event_at(0).map(|e| e.clk())))
+                else {
+                    return;
+                };
+                Self::update_record_selection(state, record_id, false, first_event_clk);
+                Self::bring_into_view(state, record_id, clk);
+            }
+        }
+    }
 
-                state.initialize_viewport(min_clk, max_clk);
+    /// Starts warming the trace summary for the newly loaded trace in the background.
+    fn start_precompute(state: &mut AppState, loader: &mut AsyncLoader, ctx: &egui::Context) {
+        state.tree_cache.clear_trace_summary();
+        if let Some(trace) = state.trace.snapshot() {
+            loader.start_precompute(trace, ctx);
+        }
+    }
+
+    /// Starts checking the content hash of the newly loaded trace file,
+    /// if its footer has a provenance block.
+    fn start_verification(state: &AppState, loader: &mut AsyncLoader, ctx: &egui::Context) {
+        let (Some(trace), Some(path)) = (state.trace.snapshot(), state.trace.file_path()) else {
+            return;
+        };
+        // Session packs prepend a manifest line, so their hash cannot be checked
+        if trace.metadata().provenance().is_some() && !jetspack::is_session_pack(path) {
+            loader.start_verification(trace.generation(), path.clone(), ctx);
+        }
+    }
+
+    /// Exports the requested analysis as a report.
+    ///
+    /// Statistics are computed on a background thread against a snapshot of
+    /// the current trace; other analyses export their last result. The
+    /// format (Markdown or HTML) is chosen from the file extension.
+    /// Failures are surfaced through the error message banner.
+    pub fn export_report(
+        state: &mut AppState,
+        loader: &mut AsyncLoader,
+        scope: ReportScope,
+        path: PathBuf,
+        ctx: &egui::Context,
+    ) {
+        let Some(trace) = state.trace.snapshot() else {
+            return;
+        };
+
+        let range = match scope {
+            ReportScope::WholeTrace => None,
+            ReportScope::ViewportRegion => {
+                Some((state.viewport.viewport_start_clk(), state.viewport.viewport_end_clk()))
+            }
+            ReportScope::TraceHealth => {
+                if let Some(health) = state.trace_health.health() {
+                    loader.start_analysis_report_export(health.clone(), path, ctx);
+                }
+                return;
+            }
+            ReportScope::TypeHierarchy => {
+                if let Some(hierarchy) = state.type_hierarchy.hierarchy() {
+                    loader.start_analysis_report_export(hierarchy.clone(), path, ctx);
+                }
+                return;
+            }
+            ReportScope::IdleGaps => {
+                if let Some(search) = state.idle_gaps.search() {
+                    loader.start_analysis_report_export(search, path, ctx);
+                }
+                return;
             }
+            ReportScope::FlameGraph => {
+                if let Some(graph) = state.flame_graph.graph() {
+                    loader.start_analysis_report_export(graph.clone(), path, ctx);
+                }
+                return;
+            }
+        };
+        let settings = state.input_settings.settings();
+        loader.start_report_export(trace, range, settings.export_aliases(), settings.column_formats, path, ctx);
+    }
+
+    /// Restores the view, notes and sorting recorded in a session pack manifest.
+    fn apply_session_manifest(state: &mut AppState, manifest: PackManifest) {
+        let view = manifest.view;
+        let (min_clk, max_clk) = (state.trace.min_clk(), state.trace.max_clk());
+        if view.viewport_end_clk > view.viewport_start_clk {
+            state.viewport.set_range(view.viewport_start_clk, view.viewport_end_clk, min_clk, max_clk);
+        }
+        state.viewport.set_viewport_filter_enabled(view.viewport_filter);
+        state.viewport.set_hide_empty_parents(view.hide_empty_parents);
+        state.lanes.set_hidden_roots(view.hidden_roots);
+        state.tree_cache.invalidate();
+
+        for id in view.expanded {
+            state.tree.expand(id);
+        }
+        if let Some(record_id) = view.selected_record {
+            state.selection.select_record(record_id, None);
+        }
+        if let Some((record_id, event_clk)) = view.selected_event {
+            state.selection.select_event(record_id, event_clk);
+        }
+        if let Some(spec) = view.active_sort {
+            Self::request_sorting(state, spec);
+        }
+
+        state.notes.replace_all(manifest.notes);
+    }
+
+    /// Saves the current finding as a `.jetspack` session pack on a background thread.
+    ///
+    /// The packed trace is the selected record's subtree (or the whole trace)
+    /// limited to the visible clock range, together with the current view,
+    /// all notes and a statistics report for that range.
+    pub fn save_session_pack(state: &AppState, loader: &mut AsyncLoader, path: &Path, ctx: &egui::Context) {
+        let Some(trace) = state.trace.snapshot() else {
+            return;
+        };
+
+        let range = (state.viewport.viewport_start_clk(), state.viewport.viewport_end_clk());
+        let view = ViewDescriptor {
+            viewport_start_clk: range.0,
+            viewport_end_clk: range.1,
+            expanded: state.tree.expanded_nodes_set().iter().copied().collect(),
+            selected_record: state.selection.selected_record_id(),
+            selected_event: state.selection.selected_event(),
+            viewport_filter: state.viewport.viewport_filter_enabled(),
+            hide_empty_parents: state.viewport.hide_empty_parents(),
+            active_sort: state.tree.active_sort(),
+            hidden_roots: state.lanes.hidden_roots().iter().copied().collect(),
+        };
+        let slice = TraceSlice {
+            subtree_root: view.selected_record,
+            range: Some(range),
+            ..Default::default()
+        };
+
+        let source = state
+            .trace
+            .file_path()
+            .and_then(|p| p.file_name())
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mut manifest = PackManifest::new(source, view);
+        manifest.notes = state.notes.all().clone();
+
+        let settings = state.input_settings.settings();
+        loader.start_session_pack_save(
+            trace,
+            slice,
+            manifest,
+            settings.export_aliases(),
+            settings.column_formats,
+            path.to_path_buf(),
+            ctx,
+        );
+    }
+
+    /// Exports the records overlapping the viewport clock range as a JETS file.
+    ///
+    /// Parents of exported records are kept, and records running past the
+    /// range end are closed at it (see [`crate::io::trace_slice::CLAMPED_END_ATTR`]).
+    pub fn export_time_slice(state: &AppState, loader: &mut AsyncLoader, path: &Path, ctx: &egui::Context) {
+        let Some(trace) = state.trace.snapshot() else {
+            return;
+        };
+
+        let slice = TraceSlice {
+            subtree_root: None,
+            range: Some((state.viewport.viewport_start_clk(), state.viewport.viewport_end_clk())),
+            clamp_ends: true,
+        };
+        loader.start_time_slice_export(trace, slice, path.to_path_buf(), ctx);
+    }
+
+    /// Converts the whole loaded trace into a JETS file on a background thread.
+    pub fn export_jets(state: &AppState, loader: &mut AsyncLoader, path: &Path, ctx: &egui::Context) {
+        if let Some(trace) = state.trace.snapshot() {
+            loader.start_jets_export(trace, path.to_path_buf(), ctx);
+        }
+    }
+
+    /// Exports the event grid of all records of `record_type` as CSV on a
+    /// background thread.
+    pub fn export_event_grid(state: &AppState, loader: &mut AsyncLoader, record_type: String, path: &Path, ctx: &egui::Context) {
+        if let Some(trace) = state.trace.snapshot() {
+            loader.start_event_grid_export(trace, record_type, path.to_path_buf(), ctx);
+        }
+    }
+
+    /// Writes the current preferences to a settings profile file.
+    pub fn export_settings_profile(state: &mut AppState, path: &Path) {
+        let profile = SettingsProfile {
+            theme: Some(state.theme.current_theme_name().to_string()),
+            column_widths: Some(*state.layout.column_widths()),
+            expand_width: Some(state.layout.expand_width()),
+            root_lanes: Some(state.lanes.root_lanes_enabled()),
+            low_power: Some(state.repaint.low_power_enabled()),
+            record_type_colors: Some(
+                state
+                    .type_legend
+                    .color_overrides()
+                    .iter()
+                    .map(|(t, c)| (t.clone(), c.to_srgba_unmultiplied()))
+                    .collect(),
+            ),
+            value_tracks: Some(
+                state
+                    .type_legend
+                    .value_tracks()
+                    .iter()
+                    .map(|(t, track)| (t.clone(), track.clone()))
+                    .collect(),
+            ),
+            event_marker_shapes: Some(
+                state
+                    .type_legend
+                    .event_shapes()
+                    .iter()
+                    .map(|(name, shape)| (name.clone(), *shape))
+                    .collect(),
+            ),
+            input_settings: Some(state.input_settings.settings().clone()),
+            ..Default::default()
+        };
+
+        if let Err(e) = settings_profile::write_settings_profile(path, &profile) {
+            Self::show_error(state, format!("Error exporting settings profile: {:#}", e));
+        }
+    }
+
+    /// Applies the preferences found in a settings profile file.
+    ///
+    /// Sections missing from the profile keep their current values. A theme
+    /// this viewer does not know is skipped and reported.
+    pub fn import_settings_profile(state: &mut AppState, path: &Path) {
+        let profile = match settings_profile::read_settings_profile(path) {
+            Ok(profile) => profile,
             Err(e) => {
-                state.error_message = Some(format!("Error generating virtual trace: {}", e));
+                Self::show_error(state, format!("Error importing settings profile: {:#}", e));
+                return;
+            }
+        };
+
+        if let Some(theme) = profile.theme {
+            if state.theme.theme_manager().get_theme(&theme).is_some() {
+                state.theme.set_theme(theme);
+            } else {
+                Self::show_error(state, format!("Settings profile uses unknown theme '{}'", theme));
+            }
+        }
+        if let Some(widths) = profile.column_widths {
+            *state.layout.column_widths_mut() = widths;
+        }
+        if let Some(width) = profile.expand_width {
+            *state.layout.expand_width_mut() = width;
+        }
+        if let Some(root_lanes) = profile.root_lanes {
+            state.lanes.set_root_lanes_enabled(root_lanes);
+        }
+        if let Some(low_power) = profile.low_power {
+            state.repaint.set_low_power(low_power);
+        }
+        if let Some(colors) = profile.record_type_colors {
+            state.type_legend.set_color_overrides(
+                colors
+                    .into_iter()
+                    .map(|(t, [r, g, b, a])| (t, egui::Color32::from_rgba_unmultiplied(r, g, b, a)))
+                    .collect(),
+            );
+        }
+        if let Some(tracks) = profile.value_tracks {
+            state.type_legend.set_value_tracks(tracks.into_iter().collect());
+        }
+        if let Some(shapes) = profile.event_marker_shapes {
+            state.type_legend.set_event_shapes(shapes.into_iter().collect());
+        }
+        if let Some(settings) = profile.input_settings {
+            state.input_settings.set_settings(settings);
+        }
+    }
+
+    /// Finds the longest idle gaps under the selected record and lists them.
+    pub fn find_idle_gaps(state: &mut AppState) {
+        let (Some(trace), Some(root_id)) = (state.trace.trace_data(), state.selection.selected_record_id()) else {
+            return;
+        };
+        let Some(root_name) = trace.get_record(root_id).map(|record| record.name()) else {
+            return;
+        };
+        let gaps = idle_gaps::find_idle_gaps(trace, root_id, IDLE_GAP_LIMIT);
+        state.idle_gaps.set_results(root_id, root_name, gaps);
+    }
+
+    /// Checks the loaded trace for anomalies and lists them.
+    pub fn check_trace_health(state: &mut AppState) {
+        let Some(trace) = state.trace.trace_data() else {
+            return;
+        };
+        let health = TraceHealth::check(trace);
+        state.trace_health.set_health(health);
+    }
+
+    /// Starts aggregating the flame graph of the subtree under `root_id` in
+    /// the background; the timeline panel shows it once it is built.
+    pub fn build_flame_graph(state: &mut AppState, loader: &mut AsyncLoader, root_id: u64, ctx: &egui::Context) {
+        let Some(trace) = state.trace.snapshot() else {
+            return;
+        };
+        state.flame_graph.set_building(root_id);
+        loader.start_flame_graph(trace, root_id, ctx);
+    }
+
+    /// Folds the loaded trace by record type and shows the hierarchy.
+    pub fn build_type_hierarchy(state: &mut AppState) {
+        let Some(trace) = state.trace.trace_data() else {
+            return;
+        };
+        let hierarchy = TypeHierarchy::build(trace);
+        state.type_hierarchy.set_hierarchy(hierarchy);
+    }
+
+    /// Selects a record and brings it into view.
+    pub fn reveal_record(state: &mut AppState, record_id: u64) {
+        let Some((clk, first_event_clk)) = state.trace.trace_data().and_then(|trace| {
+            let record = trace.get_record(record_id)?;
+            Some((record.clk(), record.event_at(0).map(|e| e.clk())))
+        }) else {
+            return;
+        };
+        let was_already_selected = state.selection.selected_record_id() == Some(record_id);
+        Self::update_record_selection(state, record_id, was_already_selected, first_event_clk);
+        Self::bring_into_view(state, record_id, clk);
+    }
+
+    /// Brings the selected record back when filters hide its row.
+    ///
+    /// Relaxes only the filters hiding it: hidden types and roots are shown,
+    /// the viewport is panned to the record, empty parents are shown, the
+    /// clock stride is turned off, failing column filters are cleared, and
+    /// the row is pinned if rows are pinned. The selection is kept.
+    pub fn reveal_selection(state: &mut AppState) {
+        let Some(record_id) = state.selection.selected_record_id() else {
+            return;
+        };
+        let Some(clk) = state.trace.trace_data().and_then(|trace| trace.get_record(record_id)).map(|r| r.clk()) else {
+            return;
+        };
+        for filter in filter_chips::hidden_selection(state) {
+            match filter {
+                HidingFilter::HiddenRoot(root_id) => {
+                    state.lanes.set_root_visible(root_id, true);
+                }
+                HidingFilter::HiddenType(record_type) => state.type_legend.toggle_type_visible(&record_type),
+                HidingFilter::Viewport => {
+                    // Centered at once, since the row list follows the range
+                    let (start, end) = (state.viewport.viewport_start_clk(), state.viewport.viewport_end_clk());
+                    let (min_clk, max_clk) = (state.trace.min_clk(), state.trace.max_clk());
+                    let (start, end) = viewport_operations::center_range(clk, start, end, min_clk, max_clk);
+                    state.viewport.set_window_range(MAIN_WINDOW, start, end, min_clk, max_clk);
+                }
+                HidingFilter::EmptyParents => state.viewport.set_hide_empty_parents(false),
+                HidingFilter::ClockStride => state.clock_filter.set_enabled(false),
+                HidingFilter::Columns(columns) => {
+                    for column in columns {
+                        state.column_filter.clear_filter(column);
+                    }
+                }
+                HidingFilter::PinnedRows => state.tree.pin_row(record_id),
             }
         }
+        Self::bring_into_view(state, record_id, clk);
+        state.repaint.request();
     }
 
     /// Handles tree node selection interaction.
@@ -132,11 +739,37 @@ impl ApplicationCoordinator {
             state.tree.collapse(record_id);
         } else {
             state.tree.expand(record_id);
+            if state.input_settings.settings().fit_on_expand {
+                Self::fit_to_expanded_record(state, record_id);
+            }
         }
         // Invalidate cache when expand/collapse changes
         state.tree_cache.invalidate();
     }
 
+    /// Fits the viewport to a just expanded record if none of its children is in view.
+    fn fit_to_expanded_record(state: &mut AppState, record_id: u64) {
+        let Some(trace) = state.trace.trace_data() else {
+            return;
+        };
+        let (view_start, view_end) = state.viewport.target_range();
+        let Some((start, end)) = tree_operations::expansion_fit_range(trace, record_id, view_start, view_end) else {
+            return;
+        };
+        let (min_clk, max_clk) = (state.trace.min_clk(), state.trace.max_clk());
+        let (start, end) = if end > start { (start, end) } else { (start - 1, start + 1) };
+        state.viewport.animate_to(start.max(min_clk), end.min(max_clk), min_clk, max_clk);
+    }
+
+    /// Handles swimlane header collapse/expand interaction.
+    ///
+    /// Only the swimlane row list depends on lane collapse state, so the
+    /// rest of the tree cache is kept.
+    pub fn handle_swimlane_toggle(state: &mut AppState, key: &str) {
+        state.lanes.toggle_lane_collapsed(key);
+        state.tree_cache.swimlane_rows = None;
+    }
+
     /// Handles timeline bar click interaction.
     ///
     /// Updates selection state and auto-selects first event for new selections.
@@ -162,6 +795,134 @@ impl ApplicationCoordinator {
         state.selection.select_event(record_id, event_clk);
     }
 
+    /// Places a measurement cursor and, once both are placed, counts the
+    /// records and events intersecting the interval between them.
+    pub fn place_measure_cursor(state: &mut AppState, clk: i64) {
+        let Some(interval) = state.measurement.place_cursor(clk) else {
+            return;
+        };
+        if let Some(trace) = state.trace.trace_data() {
+            let stats = TraceStatistics::compute_in_range(trace, Some(interval));
+            state.measurement.set_counts(stats.record_count, stats.event_count);
+        }
+    }
+
+    /// Shows an event that may be hidden in collapsed subtrees.
+    ///
+    /// Selects the event and brings its row and clock into view.
+    pub fn reveal_event(state: &mut AppState, record_id: u64, event_clk: i64) {
+        state.selection.select_event(record_id, event_clk);
+        Self::bring_into_view(state, record_id, event_clk);
+    }
+
+    /// Selects the record one step away from `record_id` (see
+    /// [`record_navigation::find_neighbor`]) and brings it into view.
+    ///
+    /// Does nothing at the first or last record of the scope.
+    pub fn navigate_from(state: &mut AppState, record_id: u64, scope: NavigationScope, forward: bool) {
+        let Some((target, clk, first_event_clk)) = state.trace.trace_data().and_then(|trace| {
+            let target = record_navigation::find_neighbor(trace, record_id, scope, forward)?;
+            let record = trace.get_record(target)?;
+            Some((target, record.clk(), record.event_at(0).map(|e| e.clk())))
+        }) else {
+            return;
+        };
+        Self::update_record_selection(state, target, false, first_event_clk);
+        Self::bring_into_view(state, target, clk);
+    }
+
+    /// Navigates from the selected record (see [`Self::navigate_from`]).
+    fn navigate_from_selection(state: &mut AppState, scope: NavigationScope, forward: bool) {
+        if let Some(record_id) = state.selection.selected_record_id() {
+            Self::navigate_from(state, record_id, scope, forward);
+        }
+    }
+
+    /// Makes a record's row and a clock of it visible.
+    ///
+    /// Expands all ancestors of the record, pans the viewport if `clk` is
+    /// outside it and scrolls the row into view.
+    fn bring_into_view(state: &mut AppState, record_id: u64, clk: i64) {
+        let Some(trace) = state.trace.trace_data() else {
+            return;
+        };
+        let mut current = trace.get_record(record_id).and_then(|r| r.parent_id());
+        while let Some(id) = current {
+            state.tree.expand(id);
+            current = trace.get_record(id).and_then(|r| r.parent_id());
+        }
+        state.tree_cache.invalidate();
+
+        let (start, end) = (state.viewport.viewport_start_clk(), state.viewport.viewport_end_clk());
+        if clk < start || clk > end {
+            let (min_clk, max_clk) = (state.trace.min_clk(), state.trace.max_clk());
+            let (start, end) = viewport_operations::center_range(clk, start, end, min_clk, max_clk);
+            state.viewport.animate_to(start, end, min_clk, max_clk);
+        }
+
+        VirtualScrollManager::scroll_to_record(state, record_id, RowAlignment::Center, true);
+    }
+
+    /// Handles a double-click or Ctrl+click on a tree row or timeline bar.
+    ///
+    /// Selects the record (if not already selected), then runs the action the
+    /// user configured for the gesture in the settings dialog. Toggling the
+    /// record in the multi-selection replaces selecting it.
+    pub fn handle_record_gesture(state: &mut AppState, record_id: u64, gesture: ClickGesture) {
+        let Some((has_children, first_event_clk, span)) = state.trace.trace_data().and_then(|trace| {
+            let record = trace.get_record(record_id)?;
+            let first_event_clk = record.event_at(0).map(|e| e.clk());
+            Some((record.num_children() > 0, first_event_clk, (record.clk(), record.end_clk())))
+        }) else {
+            return;
+        };
+
+        let action = state.input_settings.settings().action_for(gesture);
+        if action == ClickAction::ToggleSelection {
+            state.selection.toggle_record(record_id);
+            return;
+        }
+        let was_already_selected = state.selection.selected_record_id() == Some(record_id);
+        Self::update_record_selection(state, record_id, was_already_selected, first_event_clk);
+
+        match action {
+            ClickAction::SelectOnly | ClickAction::ToggleSelection => {}
+            ClickAction::ToggleExpand => {
+                if has_children {
+                    let was_expanded = state.tree.expanded_nodes_set().contains(&record_id);
+                    Self::handle_node_expand_toggle(state, record_id, was_expanded);
+                }
+            }
+            ClickAction::ZoomToRecord => {
+                let (min_clk, max_clk) = (state.trace.min_clk(), state.trace.max_clk());
+                let (start, end) = match span {
+                    (start, Some(end)) if end > start => (start, end),
+                    // Unended or zero-length records get a small window around their start
+                    (start, _) => (start - 1, start + 1),
+                };
+                state.viewport.animate_to(start.max(min_clk), end.min(max_clk), min_clk, max_clk);
+            }
+        }
+    }
+
+    /// Handles a Shift+click on a tree row.
+    ///
+    /// Selects every tree row from the range anchor to `record_id` in
+    /// display order. Without an anchor among the rows the record is
+    /// selected alone.
+    pub fn handle_range_selection(state: &mut AppState, record_id: u64) {
+        let rows = state.tree_rows();
+        let position = |id: u64| rows.iter().position(|node| node.record_id == id);
+        let (Some(anchor), Some(end)) = (state.selection.range_anchor().and_then(position), position(record_id)) else {
+            let first_event_clk = state.trace.trace_data().and_then(|trace| trace.get_record(record_id)?.event_at(0).map(|e| e.clk()));
+            state.selection.select_record(record_id, first_event_clk);
+            return;
+        };
+        let range = if anchor <= end { anchor..=end } else { end..=anchor };
+        let record_ids = rows[range].iter().map(|node| node.record_id).collect();
+        state.selection.select_range(record_ids, record_id);
+    }
+
     /// Requests sorting of tree nodes.
     ///
     /// Sets the active sort and computes sorted child indices for all parents.
@@ -176,14 +937,15 @@ impl ApplicationCoordinator {
 
         // Clear previous sorted children cache
         state.tree_cache.sorted_children.clear();
+        state.tree_cache.row_heat = None;
 
         // If we have trace data, compute sorted orderings
         if let Some(trace) = state.trace.trace_data() {
             let mut sorted_map: HashMap<(u64, SortSpec), Vec<usize>> = HashMap::new();
 
             // Compute sorted children for all parents recursively
-            for root_id in trace.root_ids().iter().copied() {
-                Self::compute_sorted_children_recursive(trace, root_id, spec, &mut sorted_map);
+            for &root_id in trace.roots() {
+                Self::compute_sorted_children_recursive(trace, root_id, spec, &state.sort_keys, &mut sorted_map);
             }
 
             // Merge results into cache
@@ -191,32 +953,348 @@ impl ApplicationCoordinator {
         }
     }
 
+    /// Sorts ascending by the value of an attribute, registering a sort key
+    /// for it on first use.
+    pub fn request_attribute_sorting(state: &mut AppState, attribute: &str) {
+        let id = state.sort_keys.register_attribute(attribute);
+        Self::request_sorting(state, SortSpec { key: SortKey::Custom(id), dir: SortDir::Asc });
+    }
+
     /// Recursively computes sorted children for a subtree.
     ///
     /// # Arguments
     /// * `trace` - Trace data
     /// * `parent_id` - Parent record ID
     /// * `spec` - Sort specification
+    /// * `sort_keys` - Custom sort keys
     /// * `out` - Output map to populate with sorted indices
     fn compute_sorted_children_recursive(
         trace: &rjets::DynTraceData,
         parent_id: u64,
         spec: SortSpec,
+        sort_keys: &SortKeyRegistry,
         out: &mut HashMap<(u64, SortSpec), Vec<usize>>,
     ) {
         if let Some(parent) = trace.get_record(parent_id) {
             // Only cache if parent has children
             if parent.num_children() > 0 {
-                let order = sorting::sort_child_indices_for_parent(trace, &parent, spec);
+                let order = sorting::sort_child_indices_for_parent(trace, &parent, spec, sort_keys);
                 out.insert((parent_id, spec), order.clone());
 
                 // Recurse into children using the sorted order
                 for &i in &order {
                     if let Some(child) = parent.child_at(i) {
-                        Self::compute_sorted_children_recursive(trace, child.id(), spec, out);
+                        Self::compute_sorted_children_recursive(trace, child.id(), spec, sort_keys, out);
                     }
                 }
             }
         }
     }
+
+    /// Registers the file, view and panel actions.
+    ///
+    /// Actions needing a file are run through the same dialogs as the header
+    /// buttons; view actions do nothing while no trace is loaded.
+    pub fn register_actions(registry: &mut ActionRegistry) {
+        let command = |key| Some(KeyboardShortcut::new(Modifiers::COMMAND, key));
+
+        registry.register(
+            "Command Palette",
+            Some(KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::P)),
+            |state| {
+                state.command_palette.toggle();
+                None
+            },
+        );
+
+        // ----- File -----
+        registry.register("Open Trace…", command(Key::O), |_| {
+            header::pick_trace_files().map(PanelInteraction::OpenFilesRequested)
+        });
+        registry.register("Reload Trace", command(Key::R), |state| {
+            state.trace.file_path()?;
+            Some(PanelInteraction::ReloadRequested)
+        });
+        registry.register("Open Virtual Trace", None, |_| Some(PanelInteraction::OpenVirtualTraceRequested));
+        registry.register("Export Trace Statistics Report…", None, |state| {
+            state.trace.trace_data()?;
+            header::pick_report_path().map(|path| PanelInteraction::ExportReportRequested {
+                scope: ReportScope::WholeTrace,
+                path,
+            })
+        });
+        registry.register("Export Viewport Statistics Report…", None, |state| {
+            state.trace.trace_data()?;
+            header::pick_report_path().map(|path| PanelInteraction::ExportReportRequested {
+                scope: ReportScope::ViewportRegion,
+                path,
+            })
+        });
+        registry.register("Export Trace Health Report…", None, |state| {
+            state.trace_health.health()?;
+            header::pick_report_path().map(|path| PanelInteraction::ExportReportRequested {
+                scope: ReportScope::TraceHealth,
+                path,
+            })
+        });
+        registry.register("Export Record Type Hierarchy Report…", None, |state| {
+            state.type_hierarchy.hierarchy()?;
+            header::pick_report_path().map(|path| PanelInteraction::ExportReportRequested {
+                scope: ReportScope::TypeHierarchy,
+                path,
+            })
+        });
+        registry.register("Export Idle Gap Report…", None, |state| {
+            state.idle_gaps.root()?;
+            header::pick_report_path().map(|path| PanelInteraction::ExportReportRequested {
+                scope: ReportScope::IdleGaps,
+                path,
+            })
+        });
+        registry.register("Export Flame Graph Report…", None, |state| {
+            state.flame_graph.graph()?;
+            header::pick_report_path().map(|path| PanelInteraction::ExportReportRequested {
+                scope: ReportScope::FlameGraph,
+                path,
+            })
+        });
+        registry.register("Save Session Pack…", command(Key::S), |state| {
+            state.trace.trace_data()?;
+            header::pick_session_pack_path().map(PanelInteraction::SaveSessionPackRequested)
+        });
+        registry.register("Export Time Slice…", None, |state| {
+            state.trace.trace_data()?;
+            header::pick_time_slice_path().map(PanelInteraction::ExportTimeSliceRequested)
+        });
+        registry.register("Export Settings Profile…", None, |_| {
+            settings_dialog::pick_profile_export_path().map(PanelInteraction::ExportSettingsProfileRequested)
+        });
+        registry.register("Import Settings Profile…", None, |_| {
+            settings_dialog::pick_profile_import_path().map(PanelInteraction::ImportSettingsProfileRequested)
+        });
+
+        // ----- View -----
+        registry.register("Zoom In", None, |state| {
+            Self::zoom_around_center(state, 1.5);
+            None
+        });
+        registry.register("Zoom Out", None, |state| {
+            Self::zoom_around_center(state, 1.0 / 1.5);
+            None
+        });
+        registry.register("Zoom to Fit", None, |state| {
+            if state.trace.trace_data().is_some() {
+                let (min_clk, max_clk) = (state.trace.min_clk(), state.trace.max_clk());
+                state.viewport.animate_to(min_clk, max_clk, min_clk, max_clk);
+            }
+            None
+        });
+        registry.register("Toggle Viewport Filter", None, |state| {
+            state.viewport.set_viewport_filter_enabled(!state.viewport.viewport_filter_enabled());
+            state.tree_cache.invalidate_filtered_cache();
+            None
+        });
+        registry.register("Toggle Split Timeline", None, |state| {
+            state.viewport.set_split(!state.viewport.is_split());
+            None
+        });
+        registry.register("Toggle Flame Graph", None, |state| {
+            state.flame_graph.set_enabled(!state.flame_graph.is_enabled());
+            None
+        });
+        registry.register("Toggle Measurement Cursors", command(Key::M), |state| {
+            state.measurement.set_enabled(!state.measurement.is_enabled());
+            None
+        });
+        registry.register("Swap Split Timeline Windows", None, |state| {
+            if state.viewport.is_split() {
+                let (min_clk, max_clk) = (state.trace.min_clk(), state.trace.max_clk());
+                let (main, split) = (state.viewport.window_range(MAIN_WINDOW), state.viewport.window_range(SPLIT_WINDOW));
+                state.viewport.set_window_range(MAIN_WINDOW, split.0, split.1, min_clk, max_clk);
+                state.viewport.set_window_range(SPLIT_WINDOW, main.0, main.1, min_clk, max_clk);
+                state.tree_cache.invalidate_filtered_cache();
+            }
+            None
+        });
+        registry.register("Toggle Clock Stride Filter", None, |state| {
+            state.clock_filter.set_enabled(!state.clock_filter.is_enabled());
+            state.tree_cache.invalidate_filtered_cache();
+            None
+        });
+        registry.register("Reveal Selected Record", None, |state| {
+            state.selection.selected_record_id()?;
+            Some(PanelInteraction::RevealSelectionRequested)
+        });
+        registry.register("Unpin Rows", None, |state| {
+            state.tree.unpin_rows();
+            state.tree_cache.invalidate_filtered_cache();
+            None
+        });
+        registry.register("Find Idle Gaps Under Selection", command(Key::G), |state| {
+            state.selection.selected_record_id()?;
+            Some(PanelInteraction::IdleGapSearchRequested)
+        });
+        // Alt+Up/Down steps between siblings; Shift narrows to the same type,
+        // Ctrl widens to same-named records anywhere in the trace
+        for scope in NavigationScope::ALL {
+            let modifiers = match scope {
+                NavigationScope::Sibling => Modifiers::ALT,
+                NavigationScope::SameType => Modifiers::ALT | Modifiers::SHIFT,
+                NavigationScope::SameName => Modifiers::ALT | Modifiers::COMMAND,
+            };
+            for (forward, key) in [(false, Key::ArrowUp), (true, Key::ArrowDown)] {
+                registry.register(scope.label(forward), Some(KeyboardShortcut::new(modifiers, key)), move |state| {
+                    Self::navigate_from_selection(state, scope, forward);
+                    None
+                });
+            }
+        }
+        registry.register("Show All Record Types", None, |state| {
+            state.type_legend.show_all_types();
+            state.tree_cache.invalidate();
+            None
+        });
+        registry.register("Reset Panel Layout", None, |state| {
+            state.dock.reset_layout();
+            None
+        });
+        registry.register("Show Log Console", None, |state| {
+            let layout = state.dock.layout_mut();
+            if layout.slot_of(DockPanel::Log).is_none() {
+                layout.move_panel(DockPanel::Log, DockSlot::Bottom);
+            }
+            layout.activate(DockPanel::Log);
+            None
+        });
+        registry.register("Toggle Root Lanes", None, |state| {
+            state.lanes.set_root_lanes_enabled(!state.lanes.root_lanes_enabled());
+            None
+        });
+
+        // ----- Windows and preferences -----
+        registry.register("Toggle Record Type Legend", command(Key::L), |state| {
+            state.type_legend.set_open(!state.type_legend.is_open());
+            None
+        });
+        registry.register("Toggle Event Filter Window", None, |state| {
+            state.event_type_filter.set_open(!state.event_type_filter.is_open());
+            None
+        });
+        registry.register("Toggle Idle Gap Window", None, |state| {
+            state.idle_gaps.set_open(!state.idle_gaps.is_open());
+            None
+        });
+        registry.register("Check Trace Health", None, |state| {
+            Self::check_trace_health(state);
+            state.trace_health.set_open(true);
+            None
+        });
+        registry.register("Show Record Type Hierarchy", None, |state| {
+            if state.type_hierarchy.hierarchy().is_none() {
+                Self::build_type_hierarchy(state);
+            }
+            state.type_hierarchy.set_open(true);
+            None
+        });
+        registry.register("Settings…", command(Key::Comma), |state| {
+            state.input_settings.set_dialog_open(!state.input_settings.is_dialog_open());
+            None
+        });
+        registry.register("Focus Next Panel", Some(KeyboardShortcut::new(Modifiers::NONE, Key::F6)), |state| {
+            Self::focus_next_panel(state, false);
+            None
+        });
+        registry.register("Focus Previous Panel", Some(KeyboardShortcut::new(Modifiers::SHIFT, Key::F6)), |state| {
+            Self::focus_next_panel(state, true);
+            None
+        });
+        registry.register("Toggle Low Power Mode", None, |state| {
+            state.repaint.set_low_power(!state.repaint.low_power_enabled());
+            None
+        });
+    }
+
+    /// Logs an error and shows it in the error banner.
+    fn show_error(state: &mut AppState, message: String) {
+        tracing::error!("{}", message);
+        state.error_message = Some(message);
+    }
+
+    /// Moves keyboard focus to the next docked panel's tab and shows that tab.
+    fn focus_next_panel(state: &mut AppState, backwards: bool) {
+        if let Some(panel) = state.focus.focus_next_panel(state.dock.layout(), backwards) {
+            state.dock.layout_mut().activate(panel);
+        }
+    }
+
+    /// Zooms the viewport around its center, if a trace is loaded.
+    pub fn zoom_around_center(state: &mut AppState, zoom_factor: f32) {
+        if state.trace.trace_data().is_none() {
+            return;
+        }
+        let (start, end) = state.viewport.target_range();
+        let center = viewport_operations::offset_clk(start, viewport_operations::clk_span(start, end) / 2.0);
+        state.viewport.animate_zoom_around(zoom_factor, center, state.trace.min_clk(), state.trace.max_clk());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two leaf roots, at the start and at the end of the `i64` clock range.
+    fn extreme_clock_state() -> AppState {
+        let input = concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":-9223372036854775808,"name":"first","record_type":"Op","id":1,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record_end","clk":-9223372036854775798,"record_id":1}"#, "\n",
+            r#"{"type":"record","clk":9223372036854775707,"name":"last","record_type":"Op","id":2,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record_end","clk":9223372036854775807,"record_id":2}"#, "\n",
+        );
+        let trace = DynTraceData::Jets(rjets::parse_trace_from_reader(input.as_bytes()).unwrap());
+        let mut state = AppState::new();
+        state.trace.load_trace(trace, None);
+        state.initialize_viewport(i64::MIN, i64::MAX);
+        state
+    }
+
+    #[test]
+    fn test_zoom_around_center_at_extreme_clocks() {
+        let mut state = extreme_clock_state();
+        state.viewport.set_range(i64::MAX - 1000, i64::MAX, i64::MIN, i64::MAX);
+        ApplicationCoordinator::zoom_around_center(&mut state, 2.0);
+        assert_eq!(state.viewport.target_range(), (i64::MAX - 750, i64::MAX - 250));
+
+        state.viewport.set_range(i64::MIN, i64::MAX, i64::MIN, i64::MAX);
+        ApplicationCoordinator::zoom_around_center(&mut state, 2.0);
+        let (start, end) = state.viewport.target_range();
+        assert!(start < 0 && end > 0, "zoomed around the middle of the trace: {start}..{end}");
+    }
+
+    #[test]
+    fn test_reveal_selection_centers_viewport_at_extreme_clocks() {
+        let mut state = extreme_clock_state();
+        state.viewport.set_viewport_filter_enabled(true);
+        state.viewport.set_range(i64::MIN, i64::MAX - 200, i64::MIN, i64::MAX);
+        state.selection.select_record(2, None);
+
+        ApplicationCoordinator::reveal_selection(&mut state);
+        let (start, end) = (state.viewport.viewport_start_clk(), state.viewport.viewport_end_clk());
+        assert!(start <= i64::MAX - 100 && end == i64::MAX, "record 2 is inside {start}..{end}");
+        assert!(filter_chips::hidden_selection(&state).is_empty());
+    }
+
+    #[test]
+    fn test_reveal_record_pans_at_extreme_clocks() {
+        let mut state = extreme_clock_state();
+        state.viewport.set_range(i64::MAX - 1000, i64::MAX, i64::MIN, i64::MAX);
+        ApplicationCoordinator::reveal_record(&mut state, 1);
+        assert_eq!(state.viewport.target_range(), (i64::MIN, i64::MIN + 1000));
+
+        state.viewport.set_range(i64::MIN, i64::MAX - 200, i64::MIN, i64::MAX);
+        ApplicationCoordinator::reveal_record(&mut state, 2);
+        let (start, end) = state.viewport.target_range();
+        assert!(start <= i64::MAX - 100 && end == i64::MAX, "record 2 is inside {start}..{end}");
+        assert_eq!(state.selection.selected_record_id(), Some(2));
+    }
 }
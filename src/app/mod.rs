@@ -1,13 +1,22 @@
 //! Application-level modules for the JETS trace viewer.
 //!
-//! This module contains the main application coordinator and centralized state management.
+//! This module contains the main application coordinator, centralized state
+//! management, the registry of named actions and session recording/replay.
 
 mod app_state;
 mod application_coordinator;
 mod theme_coordinator;
 mod settings_coordinator;
+mod repaint_coordinator;
+mod startup_timer;
+mod action_registry;
+mod session_recorder;
 
 pub use app_state::AppState;
 pub use application_coordinator::ApplicationCoordinator;
 pub use theme_coordinator::ThemeCoordinator;
-pub use settings_coordinator::SettingsCoordinator;
+pub use settings_coordinator::{SettingsCoordinator, COLUMN_WIDTHS_KEY, DEFAULT_COLUMN_WIDTHS};
+pub use repaint_coordinator::RepaintCoordinator;
+pub use startup_timer::{StartupTimer, FIRST_FRAME};
+pub use action_registry::ActionRegistry;
+pub use session_recorder::{apply_action, SessionRecorder, SessionReplay};
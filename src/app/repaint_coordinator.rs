@@ -0,0 +1,54 @@
+//! Repaint scheduling coordination.
+//!
+//! Turns the repaint requests collected in [`crate::state::RepaintState`]
+//! into egui repaint calls, applies the low-power policy to the context and
+//! drives viewport animations.
+
+use crate::app::AppState;
+
+/// Default egui animation time, restored when leaving low-power mode.
+const DEFAULT_ANIMATION_TIME: f32 = 1.0 / 12.0;
+
+/// Coordinates when the viewer asks egui for new frames.
+pub struct RepaintCoordinator;
+
+impl RepaintCoordinator {
+    /// Applies the repaint policy to the egui context if it changed.
+    ///
+    /// Called at the start of every frame.
+    pub fn apply_policy(ctx: &egui::Context, state: &mut AppState) {
+        if !state.repaint.take_policy_dirty() {
+            return;
+        }
+        let animation_time = if state.repaint.low_power_enabled() {
+            0.0
+        } else {
+            DEFAULT_ANIMATION_TIME
+        };
+        ctx.all_styles_mut(|style| style.animation_time = animation_time);
+        state.viewport.set_animations_enabled(!state.repaint.low_power_enabled());
+    }
+
+    /// Advances viewport animations to the current frame time.
+    ///
+    /// Called every frame before the panels are drawn.
+    pub fn tick_animations(ctx: &egui::Context, state: &mut AppState) {
+        let now = ctx.input(|i| i.time);
+        state.viewport.tick(now);
+    }
+
+    /// Schedules the next tick of a running animation.
+    pub fn schedule_animation(ctx: &egui::Context, state: &AppState) {
+        ctx.request_repaint_after(state.repaint.animation_interval());
+    }
+
+    /// Issues a single repaint if anything during the frame requested one.
+    ///
+    /// Called at the end of every frame.
+    pub fn finish_frame(ctx: &egui::Context, state: &mut AppState) {
+        // A running viewport animation needs every frame, not just animation ticks
+        if state.repaint.take_pending() || state.viewport.is_animating() {
+            ctx.request_repaint();
+        }
+    }
+}
@@ -0,0 +1,256 @@
+//! Session recording and replay.
+//!
+//! The recorder does not hook into every place that changes state. Once per
+//! frame it compares the parts of [`AppState`] a session script covers with
+//! what it recorded last and writes an action for each difference. View
+//! ranges are only written once a drag or animation has settled, so a pan
+//! is one action rather than one per frame.
+//!
+//! Replay runs one action per frame, waiting for a pending load to finish
+//! first, so each step acts on the trace the recording saw.
+
+use anyhow::Result;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+use crate::app::AppState;
+use crate::domain::severity::Severity;
+use crate::io::session_script::{self, ScriptAction, ScriptWriter};
+use crate::state::MAIN_WINDOW;
+use crate::ui::panel_manager::PanelInteraction;
+use rjets::DynTraceData;
+
+/// The parts of the application state a session script covers.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Observed {
+    /// Files of the loaded trace
+    paths: Vec<PathBuf>,
+    /// Main time window range (None while it is moving)
+    view_range: Option<(i64, i64)>,
+    selection: (Option<u64>, Option<i64>),
+    viewport_filter: bool,
+    /// Hidden record types, sorted
+    hidden_types: Vec<String>,
+    clock_stride: Option<(i64, i64)>,
+    min_severity: Severity,
+}
+
+impl Observed {
+    fn of(state: &AppState) -> Self {
+        let paths = match state.trace.trace_data() {
+            Some(DynTraceData::Multi(multi)) => multi.sources().map(PathBuf::from).collect(),
+            Some(_) => state.trace.file_path().cloned().into_iter().collect(),
+            None => Vec::new(),
+        };
+        let settled = state.trace.trace_data().is_some()
+            && !state.viewport.is_animating()
+            && !state.interaction.is_dragging()
+            && !state.interaction.is_selecting_region();
+        let mut hidden_types: Vec<String> = state.type_legend.hidden_types().iter().cloned().collect();
+        hidden_types.sort();
+        let selected_event = state.selection.selected_event();
+
+        Self {
+            paths,
+            view_range: settled.then(|| state.viewport.window_range(MAIN_WINDOW)),
+            selection: (state.selection.selected_record_id(), selected_event.map(|(_, clk)| clk)),
+            viewport_filter: state.viewport.viewport_filter_enabled(),
+            hidden_types,
+            clock_stride: state.clock_filter.stride().map(|stride| (stride.stride, stride.offset)),
+            min_severity: state.event_filter.min_severity(),
+        }
+    }
+
+    /// Returns the actions turning `self` into `next`.
+    fn changes_to(&self, next: &Observed) -> Vec<ScriptAction> {
+        let mut actions = Vec::new();
+        if next.paths != self.paths && !next.paths.is_empty() {
+            actions.push(ScriptAction::Open { paths: next.paths.clone() });
+        }
+        if next.viewport_filter != self.viewport_filter {
+            actions.push(ScriptAction::ViewportFilter { enabled: next.viewport_filter });
+        }
+        if next.hidden_types != self.hidden_types {
+            actions.push(ScriptAction::HiddenTypes { types: next.hidden_types.clone() });
+        }
+        if next.clock_stride != self.clock_stride {
+            actions.push(ScriptAction::ClockStride {
+                stride: next.clock_stride.map(|(stride, _)| stride),
+                offset: next.clock_stride.map_or(0, |(_, offset)| offset),
+            });
+        }
+        if next.min_severity != self.min_severity {
+            actions.push(ScriptAction::MinSeverity { severity: next.min_severity });
+        }
+        if let Some((start, end)) = next.view_range.filter(|range| self.view_range != Some(*range)) {
+            actions.push(ScriptAction::ViewRange { start, end });
+        }
+        if next.selection != self.selection {
+            actions.push(ScriptAction::Select {
+                record_id: next.selection.0,
+                event_clk: next.selection.1,
+            });
+        }
+        actions
+    }
+}
+
+/// Writes the user's actions to a session script as they happen.
+pub struct SessionRecorder {
+    writer: ScriptWriter,
+    /// State as of the last recorded action
+    last: Observed,
+}
+
+impl SessionRecorder {
+    /// Starts recording to a new script file.
+    pub fn create(path: &Path) -> Result<Self> {
+        Ok(Self {
+            writer: ScriptWriter::create(path)?,
+            last: Observed::default(),
+        })
+    }
+
+    /// Records the actions that happened since the last frame.
+    pub fn record_frame(&mut self, state: &AppState) -> Result<()> {
+        let actions = self.take_changes(state);
+        if actions.is_empty() {
+            return Ok(());
+        }
+        self.writer.append(&actions)
+    }
+
+    fn take_changes(&mut self, state: &AppState) -> Vec<ScriptAction> {
+        let mut next = Observed::of(state);
+        let actions = self.last.changes_to(&next);
+        // A moving view keeps the last range recorded
+        if next.view_range.is_none() {
+            next.view_range = self.last.view_range;
+        }
+        self.last = next;
+        actions
+    }
+}
+
+/// Runs the actions of a session script, one per frame.
+pub struct SessionReplay {
+    actions: VecDeque<ScriptAction>,
+    total: usize,
+}
+
+impl SessionReplay {
+    /// Reads the script to replay.
+    pub fn load(path: &Path) -> Result<Self> {
+        Ok(Self::new(session_script::read_script(path)?))
+    }
+
+    /// Replays the given actions in order.
+    pub fn new(actions: Vec<ScriptAction>) -> Self {
+        Self {
+            total: actions.len(),
+            actions: actions.into(),
+        }
+    }
+
+    /// Returns the next action and its 1-based step number.
+    pub fn next_action(&mut self) -> Option<(usize, ScriptAction)> {
+        let action = self.actions.pop_front()?;
+        Some((self.total - self.actions.len(), action))
+    }
+
+    /// Returns the number of actions in the script.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+}
+
+/// Applies a replayed action.
+///
+/// # Returns
+/// The interaction to dispatch for actions that need the loader (opening files).
+pub fn apply_action(state: &mut AppState, action: ScriptAction) -> Option<PanelInteraction> {
+    match action {
+        ScriptAction::Open { paths } => return Some(PanelInteraction::OpenFilesRequested(paths)),
+        ScriptAction::ViewRange { start, end } => {
+            let (min_clk, max_clk) = (state.trace.min_clk(), state.trace.max_clk());
+            state.viewport.set_window_range(MAIN_WINDOW, start, end, min_clk, max_clk);
+        }
+        ScriptAction::Select { record_id, event_clk } => match (record_id, event_clk) {
+            (Some(record_id), Some(event_clk)) => state.selection.select_event(record_id, event_clk),
+            (Some(record_id), None) => state.selection.select_record(record_id, None),
+            (None, _) => state.selection.clear(),
+        },
+        ScriptAction::ViewportFilter { enabled } => state.viewport.set_viewport_filter_enabled(enabled),
+        ScriptAction::HiddenTypes { types } => {
+            state.type_legend.show_all_types();
+            for record_type in &types {
+                state.type_legend.toggle_type_visible(record_type);
+            }
+        }
+        ScriptAction::ClockStride { stride, offset } => {
+            if let Some(stride) = stride {
+                let (stride_mut, offset_mut) = state.clock_filter.stride_and_offset_mut();
+                (*stride_mut, *offset_mut) = (stride, offset);
+            }
+            state.clock_filter.set_enabled(stride.is_some());
+        }
+        ScriptAction::MinSeverity { severity } => state.event_filter.set_min_severity(severity),
+    }
+    state.tree_cache.invalidate_filtered_cache();
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rjets::TraceData;
+
+    fn loaded_state() -> AppState {
+        let input = concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"a","record_type":"Op","id":1,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"event","clk":10,"name":"F","record_id":1,"description":""}"#, "\n",
+            r#"{"type":"record_end","clk":1000,"record_id":1}"#, "\n",
+        );
+        let trace = DynTraceData::Jets(rjets::parse_trace_from_reader(input.as_bytes()).unwrap());
+        let mut state = AppState::new();
+        state.trace.load_trace(trace, Some(PathBuf::from("a.jets")));
+        state.initialize_viewport(0, 1000);
+        state
+    }
+
+    #[test]
+    fn test_recorded_changes_replay_to_same_state() {
+        let mut recorded = loaded_state();
+        let mut last = Observed::default();
+        let mut record = |state: &AppState| {
+            let next = Observed::of(state);
+            let actions = last.changes_to(&next);
+            last = next;
+            actions
+        };
+
+        let mut script = record(&recorded);
+        let record_id = recorded.trace.trace_data().unwrap().roots()[0];
+        recorded.selection.select_event(record_id, 10);
+        recorded.viewport.set_window_range(MAIN_WINDOW, 100, 300, 0, 1000);
+        recorded.event_filter.set_min_severity(Severity::Error);
+        recorded.type_legend.toggle_type_visible("Op");
+        script.extend(record(&recorded));
+        assert!(record(&recorded).is_empty(), "nothing changed");
+
+        assert_eq!(script[0], ScriptAction::Open { paths: vec![PathBuf::from("a.jets")] });
+        assert!(script.contains(&ScriptAction::ViewRange { start: 100, end: 300 }));
+
+        // Replay everything after the open against a freshly loaded trace
+        let mut replayed = loaded_state();
+        let mut replay = SessionReplay::new(script);
+        let (step, open) = replay.next_action().unwrap();
+        assert_eq!(step, 1);
+        assert!(matches!(apply_action(&mut replayed, open), Some(PanelInteraction::OpenFilesRequested(_))));
+        while let Some((_, action)) = replay.next_action() {
+            assert!(apply_action(&mut replayed, action).is_none());
+        }
+        assert_eq!(Observed::of(&replayed), Observed::of(&recorded));
+    }
+}
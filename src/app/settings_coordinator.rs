@@ -3,49 +3,123 @@
 //! Provides a reusable API for persisting application settings to storage.
 //! This module follows the same pattern as ThemeCoordinator but is designed
 //! to be generic and extensible for any serializable settings.
+//!
+//! Stored settings carry a layout version. At startup they are read into
+//! [`PersistedSettings`], brought up to [`SETTINGS_VERSION`] by the
+//! migrations in order, and then loaded key by key; a value that still does
+//! not fit its type falls back to the default and is reported rather than
+//! silently dropped. The version is written back with the other settings,
+//! so a migration runs (and is announced) once.
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
 
-/// Coordinates generic settings persistence.
+/// Current layout version of the persisted settings.
 ///
-/// This coordinator provides type-safe loading and saving of any serializable
-/// settings to eframe's persistent storage. Settings are stored as JSON strings.
-pub struct SettingsCoordinator;
+/// Bump it and append to [`MIGRATIONS`] when a stored setting changes shape.
+pub const SETTINGS_VERSION: u32 = 1;
 
-impl SettingsCoordinator {
-    /// Loads a setting from persistent storage with a default fallback.
-    ///
-    /// # Type Parameters
-    /// * `T` - The type to deserialize, must implement Deserialize and Default
-    ///
-    /// # Arguments
-    /// * `storage` - The eframe storage interface
-    /// * `key` - The storage key for this setting
-    ///
-    /// # Returns
-    /// The deserialized value if found and valid, otherwise the default value for type T
-    ///
-    /// # Examples
-    /// ```ignore
-    /// let column_widths: [f32; 5] = SettingsCoordinator::load_setting(
-    ///     storage,
-    ///     "column_widths"
-    /// );
-    /// ```
-    pub fn load_setting<T>(storage: Option<&dyn eframe::Storage>, key: &str) -> T
+/// Storage key of the settings layout version.
+const SETTINGS_VERSION_KEY: &str = "settings_version";
+
+/// Storage key of the tree column widths.
+pub const COLUMN_WIDTHS_KEY: &str = "column_widths";
+
+/// Default tree column widths [Name, Description, Start Clock, Duration, ID].
+pub const DEFAULT_COLUMN_WIDTHS: [f32; 5] = [100.0, 300.0, 120.0, 120.0, 80.0];
+
+/// Rewrites settings of one layout version into the next.
+type Migration = fn(&mut HashMap<String, Value>);
+
+/// `MIGRATIONS[v]` turns version `v` settings into version `v + 1`.
+const MIGRATIONS: [Migration; SETTINGS_VERSION as usize] = [migrate_v0_column_widths];
+
+/// Version 0 (unversioned) stored column widths as a plain array whose
+/// length followed the columns of the build that wrote it; version 1 always
+/// has one finite, positive width per column.
+fn migrate_v0_column_widths(values: &mut HashMap<String, Value>) {
+    let Some(Value::Array(widths)) = values.get(COLUMN_WIDTHS_KEY) else {
+        return;
+    };
+    let widths: Vec<f64> = DEFAULT_COLUMN_WIDTHS
+        .iter()
+        .enumerate()
+        .map(|(index, &default)| {
+            widths
+                .get(index)
+                .and_then(Value::as_f64)
+                .filter(|width| width.is_finite() && *width > 0.0)
+                .unwrap_or(f64::from(default))
+        })
+        .collect();
+    values.insert(COLUMN_WIDTHS_KEY.to_string(), Value::from(widths));
+}
+
+/// Settings read from storage at startup, migrated to [`SETTINGS_VERSION`].
+#[derive(Debug, Default)]
+pub struct PersistedSettings {
+    values: HashMap<String, Value>,
+    /// Version the settings were stored with, if it was an older one
+    migrated_from: Option<u32>,
+    /// True if the settings come from a newer build (loaded as they are)
+    newer: bool,
+    /// Keys whose stored value could not be used, in load order
+    reset: Vec<String>,
+}
+
+impl PersistedSettings {
+    /// Loads a setting, falling back to `default` if it is missing or
+    /// does not fit `T` (the latter is reported by [`Self::notice`]).
+    pub fn load_or<T>(&mut self, key: &str, default: T) -> T
     where
-        T: for<'de> Deserialize<'de> + Default,
+        T: for<'de> Deserialize<'de>,
     {
-        if let Some(storage) = storage {
-            if let Some(json_str) = storage.get_string(key) {
-                if let Ok(value) = serde_json::from_str(&json_str) {
-                    return value;
-                }
+        let Some(value) = self.values.remove(key) else {
+            return default;
+        };
+        match serde_json::from_value(value) {
+            Ok(value) => value,
+            Err(e) => {
+                tracing::warn!(key, "stored setting reset to default: {}", e);
+                self.reset.push(key.to_string());
+                default
             }
         }
-        T::default()
     }
 
+    /// Loads a setting, falling back to the default value of `T`.
+    pub fn load<T>(&mut self, key: &str) -> T
+    where
+        T: for<'de> Deserialize<'de> + Default,
+    {
+        self.load_or(key, T::default())
+    }
+
+    /// Returns the one-time notice about migrated or reset settings, once
+    /// every setting has been loaded; None if nothing needed fixing.
+    pub fn notice(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if let Some(version) = self.migrated_from {
+            parts.push(format!("Settings were upgraded from layout version {} to {}", version, SETTINGS_VERSION));
+        }
+        if self.newer {
+            parts.push("Settings were saved by a newer version of the viewer".to_string());
+        }
+        if !self.reset.is_empty() {
+            parts.push(format!("reset to defaults: {}", self.reset.join(", ")));
+        }
+        (!parts.is_empty()).then(|| parts.join("; "))
+    }
+}
+
+/// Coordinates generic settings persistence.
+///
+/// This coordinator provides type-safe loading and saving of any serializable
+/// settings to eframe's persistent storage. Settings are stored as JSON strings.
+pub struct SettingsCoordinator;
+
+impl SettingsCoordinator {
     /// Saves a setting to persistent storage.
     ///
     /// # Type Parameters
@@ -74,57 +148,65 @@ impl SettingsCoordinator {
         }
     }
 
-    /// Loads a setting from persistent storage with a custom default.
+    /// Reads the given settings from storage and migrates them to the
+    /// current layout version.
     ///
-    /// # Type Parameters
-    /// * `T` - The type to deserialize, must implement Deserialize
+    /// Settings without a version are version 0; with no stored settings at
+    /// all (a first start) there is nothing to migrate. Values that are not
+    /// valid JSON are reset, and settings from a newer version are kept as
+    /// they are, since a migration cannot be undone.
     ///
     /// # Arguments
     /// * `storage` - The eframe storage interface
-    /// * `key` - The storage key for this setting
-    /// * `default` - The default value to use if loading fails
-    ///
-    /// # Returns
-    /// The deserialized value if found and valid, otherwise the provided default
-    pub fn load_setting_or<T>(storage: Option<&dyn eframe::Storage>, key: &str, default: T) -> T
-    where
-        T: for<'de> Deserialize<'de>,
-    {
-        if let Some(storage) = storage {
-            if let Some(json_str) = storage.get_string(key) {
-                if let Ok(value) = serde_json::from_str(&json_str) {
-                    return value;
+    /// * `keys` - Storage keys of the settings to read
+    pub fn read_persisted(storage: Option<&dyn eframe::Storage>, keys: &[&str]) -> PersistedSettings {
+        let mut settings = PersistedSettings::default();
+        let Some(storage) = storage else {
+            return settings;
+        };
+        for &key in keys {
+            let Some(json_str) = storage.get_string(key) else { continue };
+            match serde_json::from_str(&json_str) {
+                Ok(value) => {
+                    settings.values.insert(key.to_string(), value);
+                }
+                Err(e) => {
+                    tracing::warn!(key, "stored setting is not valid JSON: {}", e);
+                    settings.reset.push(key.to_string());
                 }
             }
         }
-        default
+
+        let version = storage
+            .get_string(SETTINGS_VERSION_KEY)
+            .and_then(|version| version.parse::<u32>().ok())
+            .unwrap_or(0);
+        if settings.values.is_empty() {
+            return settings;
+        }
+        if version > SETTINGS_VERSION {
+            tracing::warn!(version, current = SETTINGS_VERSION, "settings saved by a newer version");
+            settings.newer = true;
+        } else if version < SETTINGS_VERSION {
+            for migration in &MIGRATIONS[version as usize..] {
+                migration(&mut settings.values);
+            }
+            tracing::info!(from = version, to = SETTINGS_VERSION, "settings migrated");
+            settings.migrated_from = Some(version);
+        }
+        settings
     }
 
-    /// Attempts to load a setting, returning None if not found or invalid.
-    ///
-    /// # Type Parameters
-    /// * `T` - The type to deserialize, must implement Deserialize
-    ///
-    /// # Arguments
-    /// * `storage` - The eframe storage interface
-    /// * `key` - The storage key for this setting
-    ///
-    /// # Returns
-    /// Some(value) if found and valid, None otherwise
-    pub fn try_load_setting<T>(storage: Option<&dyn eframe::Storage>, key: &str) -> Option<T>
-    where
-        T: for<'de> Deserialize<'de>,
-    {
-        let storage = storage?;
-        let json_str = storage.get_string(key)?;
-        serde_json::from_str(&json_str).ok()
+    /// Marks the stored settings as having the current layout version.
+    pub fn save_settings_version(storage: &mut dyn eframe::Storage) {
+        storage.set_string(SETTINGS_VERSION_KEY, SETTINGS_VERSION.to_string());
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::HashMap;
+    use eframe::Storage;
 
     /// Simple mock storage for testing
     struct MockStorage {
@@ -159,7 +241,7 @@ mod tests {
         SettingsCoordinator::save_setting(&mut storage, "test_key", &42i32);
 
         // Load it back
-        let loaded: i32 = SettingsCoordinator::load_setting(Some(&storage), "test_key");
+        let loaded: i32 = SettingsCoordinator::read_persisted(Some(&storage), &["test_key"]).load("test_key");
         assert_eq!(loaded, 42);
     }
 
@@ -168,7 +250,7 @@ mod tests {
         let storage = MockStorage::new();
 
         // Try to load non-existent key, should return default
-        let loaded: i32 = SettingsCoordinator::load_setting(Some(&storage), "missing_key");
+        let loaded: i32 = SettingsCoordinator::read_persisted(Some(&storage), &["missing_key"]).load("missing_key");
         assert_eq!(loaded, 0); // i32::default()
     }
 
@@ -181,21 +263,38 @@ mod tests {
         SettingsCoordinator::save_setting(&mut storage, "widths", &widths);
 
         // Load it back
-        let loaded: [f32; 5] = SettingsCoordinator::load_setting(Some(&storage), "widths");
+        let loaded: [f32; 5] = SettingsCoordinator::read_persisted(Some(&storage), &["widths"]).load("widths");
         assert_eq!(loaded, widths);
     }
 
     #[test]
-    fn test_try_load_setting() {
+    fn test_unversioned_settings_are_migrated_once() {
         let mut storage = MockStorage::new();
+        storage.set_string(COLUMN_WIDTHS_KEY, "[150.0, -1.0, 90.0]".to_string());
+        storage.set_string("low_power_mode", "\"yes\"".to_string());
+        storage.set_string("expand_width", "{oops".to_string());
+        let keys = [COLUMN_WIDTHS_KEY, "low_power_mode", "expand_width", "root_lanes"];
+
+        let mut settings = SettingsCoordinator::read_persisted(Some(&storage), &keys);
+        let widths: [f32; 5] = settings.load_or(COLUMN_WIDTHS_KEY, DEFAULT_COLUMN_WIDTHS);
+        assert_eq!(widths, [150.0, 300.0, 90.0, 120.0, 80.0]);
+        assert!(!settings.load_or("low_power_mode", false));
+        assert!(!settings.load_or("root_lanes", false));
+        assert_eq!(
+            settings.notice().unwrap(),
+            "Settings were upgraded from layout version 0 to 1; reset to defaults: expand_width, low_power_mode"
+        );
 
-        // Non-existent key
-        let result: Option<i32> = SettingsCoordinator::try_load_setting(Some(&storage), "missing");
-        assert_eq!(result, None);
+        // Saved back with the version, the next start has nothing to report
+        SettingsCoordinator::save_setting(&mut storage, COLUMN_WIDTHS_KEY, &widths);
+        storage.data.remove("low_power_mode");
+        storage.data.remove("expand_width");
+        SettingsCoordinator::save_settings_version(&mut storage);
+        let mut settings = SettingsCoordinator::read_persisted(Some(&storage), &keys);
+        assert_eq!(settings.load_or(COLUMN_WIDTHS_KEY, [0.0; 5]), widths);
+        assert_eq!(settings.notice(), None);
 
-        // Save and load
-        SettingsCoordinator::save_setting(&mut storage, "test", &123i32);
-        let result: Option<i32> = SettingsCoordinator::try_load_setting(Some(&storage), "test");
-        assert_eq!(result, Some(123));
+        // A first start is not a migration
+        assert_eq!(SettingsCoordinator::read_persisted(Some(&MockStorage::new()), &keys).notice(), None);
     }
 }
@@ -0,0 +1,109 @@
+//! Startup timing log.
+//!
+//! Records how long each startup phase takes, measured from process start,
+//! and logs one summary line once startup is over: after the
+//! first frame, or after the command-line trace has loaded if one was given.
+
+use std::time::{Duration, Instant};
+
+/// Milestone marked when the first frame has been rendered.
+pub const FIRST_FRAME: &str = "first frame";
+
+/// Measures startup phases until the viewer is usable.
+#[derive(Debug, Clone)]
+pub struct StartupTimer {
+    /// Process start (entry of `main`)
+    start: Instant,
+    /// Milestones reached so far, in order
+    milestones: Vec<(&'static str, Duration)>,
+    /// Whether the summary has been logged
+    finished: bool,
+}
+
+impl Default for StartupTimer {
+    fn default() -> Self {
+        Self::start()
+    }
+}
+
+impl StartupTimer {
+    /// Starts timing now.
+    pub fn start() -> Self {
+        Self {
+            start: Instant::now(),
+            milestones: Vec::new(),
+            finished: false,
+        }
+    }
+
+    // ===== Queries =====
+
+    /// Returns true once the given milestone has been marked.
+    pub fn reached(&self, milestone: &str) -> bool {
+        self.milestones.iter().any(|(name, _)| *name == milestone)
+    }
+
+    /// Formats the milestones as `name +phase (total)` entries.
+    pub fn summary(&self) -> String {
+        let mut previous = Duration::ZERO;
+        let phases: Vec<String> = self
+            .milestones
+            .iter()
+            .map(|&(name, at)| {
+                let phase = at.saturating_sub(previous);
+                previous = at;
+                format!("{} +{} ({})", name, format_ms(phase), format_ms(at))
+            })
+            .collect();
+        format!("startup: {}", phases.join(", "))
+    }
+
+    // ===== Mutations =====
+
+    /// Marks a milestone at the current time (ignored after the summary was logged).
+    pub fn mark(&mut self, milestone: &'static str) {
+        if !self.finished {
+            self.milestones.push((milestone, self.start.elapsed()));
+        }
+    }
+
+    /// Marks the final milestone and logs the summary, once.
+    pub fn finish(&mut self, milestone: &'static str) {
+        if self.finished {
+            return;
+        }
+        self.mark(milestone);
+        self.finished = true;
+        tracing::info!("{}", self.summary());
+    }
+}
+
+/// Formats a duration in milliseconds with one decimal.
+fn format_ms(duration: Duration) -> String {
+    format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_lists_phase_and_total_per_milestone() {
+        let mut timer = StartupTimer::start();
+        timer.milestones = vec![
+            ("window", Duration::from_millis(40)),
+            (FIRST_FRAME, Duration::from_micros(65_500)),
+        ];
+        assert!(timer.reached(FIRST_FRAME));
+        assert!(!timer.reached("trace loaded"));
+        assert_eq!(
+            timer.summary(),
+            "startup: window +40.0ms (40.0ms), first frame +25.5ms (65.5ms)"
+        );
+
+        timer.finish("trace loaded");
+        timer.mark("late");
+        assert!(timer.finished);
+        assert_eq!(timer.milestones.len(), 3);
+    }
+}
@@ -2,7 +2,8 @@
 //!
 //! Handles theme selection, application, and persistent storage across sessions.
 
-use crate::app::AppState;
+use crate::app::{ActionRegistry, AppState};
+use rjets::ThemeManager;
 
 const THEME_KEY: &str = "theme_preference";
 
@@ -29,10 +30,14 @@ impl ThemeCoordinator {
         storage.flush();
     }
 
-    /// Applies the current theme to the egui context.
+    /// Applies the current theme to the egui context if it changed.
     ///
-    /// Called every frame to ensure theme is correctly applied.
-    pub fn apply_current_theme(ctx: &egui::Context, state: &AppState) {
+    /// Called every frame; the visuals are only rebuilt on the first frame
+    /// and after the theme selection changes.
+    pub fn apply_current_theme(ctx: &egui::Context, state: &mut AppState) {
+        if !state.theme.needs_apply() {
+            return;
+        }
         let theme_name = state.theme.current_theme_name();
         if let Some(theme) = state.theme.theme_manager().get_theme(theme_name) {
             let mut visuals = if theme.name == "Light" {
@@ -44,5 +49,17 @@ impl ThemeCoordinator {
             state.theme.theme_manager().apply_theme(theme, &mut visuals);
             ctx.set_visuals(visuals);
         }
+        state.theme.mark_applied();
+    }
+
+    /// Registers one "Theme: <name>" action per built-in theme.
+    pub fn register_actions(registry: &mut ActionRegistry) {
+        for theme_name in ThemeManager::new().list_themes() {
+            let theme_name = theme_name.to_string();
+            registry.register(format!("Theme: {}", theme_name), None, move |state| {
+                state.theme.set_theme(theme_name.clone());
+                None
+            });
+        }
     }
 }
@@ -1,6 +1,8 @@
 //! Caching modules for performance optimization.
 
 pub mod tree_cache;
+pub mod trace_summary;
 
 // Re-export commonly used types
 pub use tree_cache::TreeCache;
+pub use trace_summary::{TraceSummary, TypeStats, EVENT_NAME_LIMIT};
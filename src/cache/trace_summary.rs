@@ -0,0 +1,147 @@
+//! Structural summary of a loaded trace.
+//!
+//! Unlike the rest of [`crate::cache::TreeCache`], these values depend only on
+//! the trace, not on expansion or sorting, so they are computed once after a
+//! load (on a background thread, see [`crate::io::AsyncLoader::start_precompute`])
+//! and kept until the next trace is loaded.
+
+use rjets::{DynTraceData, TraceData, TraceEvent, TraceRecord};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::domain::aggregation::{aggregate_by_bucket, BucketMetric, TimeBuckets};
+use crate::domain::severity::{event_severity, Severity};
+
+/// Number of buckets in the whole-trace activity profile.
+const ACTIVITY_BUCKETS: usize = 200;
+
+/// How many records to visit between checks of the cancel flag (also checked per root).
+const CANCEL_CHECK_INTERVAL: usize = 4096;
+
+/// Most distinct event names counted; traces with unique names per event
+/// would otherwise grow the summary without bound.
+pub const EVENT_NAME_LIMIT: usize = 256;
+
+/// Totals for one record_type.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TypeStats {
+    /// Number of records of this type
+    pub count: usize,
+    /// Sum of the durations of ended records of this type
+    pub total_duration: i64,
+    /// Name of the first record seen with this type (for name-based coloring)
+    pub sample_name: String,
+}
+
+/// Whole-trace counts that are expensive to compute on the UI thread.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TraceSummary {
+    /// Maps root record_id -> number of records in its subtree (including itself)
+    pub root_subtree_sizes: HashMap<u64, usize>,
+    /// Deepest nesting level in the trace (roots are depth 0)
+    pub max_depth: usize,
+    /// Total number of records
+    pub record_count: usize,
+    /// Totals per record_type
+    pub type_stats: BTreeMap<String, TypeStats>,
+    /// Number of events per severity
+    pub severity_counts: BTreeMap<Severity, usize>,
+    /// Number of events per name (the first `EVENT_NAME_LIMIT` names seen)
+    pub event_name_counts: BTreeMap<String, usize>,
+    /// Leaf record occupancy over the whole trace extent
+    pub activity: TimeBuckets,
+    /// Record starts over the whole trace extent
+    pub starts: TimeBuckets,
+}
+
+impl TraceSummary {
+    /// Walks the whole trace once and collects the summary.
+    ///
+    /// # Returns
+    /// `None` if `cancel` was set before the walk finished.
+    pub fn compute(trace: &DynTraceData, cancel: &AtomicBool) -> Option<Self> {
+        let mut summary = Self::default();
+        let mut extent: Option<(i64, i64)> = None;
+
+        for &root_id in trace.roots() {
+            if cancel.load(Ordering::Relaxed) {
+                return None;
+            }
+            let Some(root) = trace.get_record(root_id) else {
+                continue;
+            };
+
+            let mut subtree_size = 0;
+            let mut stack = vec![(root, 0usize)];
+            while let Some((record, depth)) = stack.pop() {
+                subtree_size += 1;
+                summary.record_count += 1;
+                if summary.record_count.is_multiple_of(CANCEL_CHECK_INTERVAL) && cancel.load(Ordering::Relaxed) {
+                    return None;
+                }
+
+                let end = record.end_clk().unwrap_or(record.clk()).max(record.clk());
+                extent = Some(extent.map_or((record.clk(), end), |(lo, hi)| (lo.min(record.clk()), hi.max(end))));
+                summary.max_depth = summary.max_depth.max(depth);
+                let stats = summary.type_stats.entry(record.record_type()).or_default();
+                if stats.count == 0 {
+                    stats.sample_name = record.name();
+                }
+                stats.count += 1;
+                stats.total_duration += record.duration().unwrap_or(0);
+                for event in (0..record.num_events()).filter_map(|i| record.event_at(i)) {
+                    *summary.severity_counts.entry(event_severity(&event)).or_default() += 1;
+                    let name = event.name();
+                    if let Some(count) = summary.event_name_counts.get_mut(&name) {
+                        *count += 1;
+                    } else if summary.event_name_counts.len() < EVENT_NAME_LIMIT {
+                        summary.event_name_counts.insert(name, 1);
+                    }
+                }
+                stack.extend((0..record.num_children()).filter_map(|i| record.child_at(i)).map(|c| (c, depth + 1)));
+            }
+            summary.root_subtree_sizes.insert(root_id, subtree_size);
+        }
+
+        if cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        if let Some((start, end)) = extent {
+            let range = (start, end + 1);
+            // Leaves do the work; their parents would cover the whole trace
+            let leaves = trace.records_iter().filter(|record| record.num_children() == 0);
+            summary.activity = aggregate_by_bucket(leaves, range, ACTIVITY_BUCKETS, BucketMetric::Occupancy);
+            summary.starts = aggregate_by_bucket(trace.records_iter(), range, ACTIVITY_BUCKETS, BucketMetric::Starts);
+        }
+
+        Some(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rjets::{TraceReader, VirtualTraceReader};
+
+    #[test]
+    fn test_summary_counts_every_record() {
+        let trace = VirtualTraceReader::new().read("").unwrap();
+        let summary = TraceSummary::compute(&trace, &AtomicBool::new(false)).unwrap();
+
+        assert_eq!(summary.root_subtree_sizes.len(), trace.roots().len());
+        assert_eq!(summary.root_subtree_sizes.values().sum::<usize>(), summary.record_count);
+        assert_eq!(summary.type_stats.values().map(|t| t.count).sum::<usize>(), summary.record_count);
+        assert!(summary.type_stats.values().all(|t| !t.sample_name.is_empty()));
+        assert!(summary.max_depth > 0);
+        assert!(summary.severity_counts.values().sum::<usize>() > 0);
+        assert!(!summary.event_name_counts.is_empty() && summary.event_name_counts.len() <= EVENT_NAME_LIMIT);
+        assert!(summary.activity.max_sum() > 0.0);
+        assert_eq!(summary.starts.counts.iter().sum::<u64>() as usize, summary.record_count);
+    }
+
+    #[test]
+    fn test_summary_stops_when_cancelled() {
+        let trace = VirtualTraceReader::new().read("").unwrap();
+        assert!(TraceSummary::compute(&trace, &AtomicBool::new(true)).is_none());
+    }
+}
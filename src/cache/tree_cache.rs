@@ -2,6 +2,12 @@
 
 use std::collections::HashMap;
 use crate::state::SortSpec;
+use crate::domain::swimlanes::SwimlaneRow;
+use crate::domain::scroll_heat::RowHeatIndex;
+use crate::domain::problem_rollup::ProblemRollupCache;
+use crate::domain::duration_share::DurationShareCache;
+use crate::domain::column_stats::HeaderStats;
+use crate::cache::TraceSummary;
 
 /// Cache for expensive tree calculations.
 ///
@@ -13,30 +19,43 @@ pub struct TreeCache {
     /// Only stores entries for expanded nodes.
     pub subtree_sizes: HashMap<u64, usize>,
 
-    /// Maps record_id -> true if all direct children are collapsed (leaf optimization).
-    /// Enables O(1) skipping for wide nodes with many leaf children.
-    pub all_children_collapsed: HashMap<u64, bool>,
-
     /// Cached total visible node count.
     pub total_visible_nodes: Option<usize>,
 
-    /// Cached maximum visible depth.
-    pub max_visible_depth: Option<usize>,
-
     /// Sequence number for cache invalidation.
     /// Incremented whenever expanded_nodes changes or trace reloads.
     pub expansion_seq: u64,
 
-    /// Cached viewport range for filtered tree (start_clk, end_clk).
-    /// Used to determine if filtered cache is still valid.
-    pub filtered_viewport_range: Option<(i64, i64)>,
-
     /// Cached total filtered node count for current viewport.
     pub filtered_node_count: Option<usize>,
 
     /// Cache of per-parent sorted child index order for a given sort spec.
     /// Key: (parent_id, sort_spec) -> indices into parent.children
     pub sorted_children: HashMap<(u64, SortSpec), Vec<usize>>,
+
+    /// Cached row list for attribute swimlane mode.
+    /// Cleared when lanes are collapsed/expanded or the lane attribute changes.
+    pub swimlane_rows: Option<Vec<SwimlaneRow>>,
+
+    /// Row positions for the timeline scrollbar heat strip.
+    /// Rebuilt whenever the visible row list changes (expansion, sort, filter).
+    pub row_heat: Option<RowHeatIndex>,
+
+    /// Header tooltip stats over the tree rows, recomputed once per frame
+    /// while a stats column header is hovered.
+    pub header_stats: Option<HeaderStats>,
+
+    /// Whole-trace summary computed in the background after a load.
+    /// Survives `invalidate()`; cleared only when the trace changes.
+    pub trace_summary: Option<TraceSummary>,
+
+    /// Problem events below each record whose badge was drawn so far.
+    /// Survives `invalidate()`; cleared only when the trace changes.
+    pub problem_rollup: ProblemRollupCache,
+
+    /// Duration shares of the records shown so far.
+    /// Survives `invalidate()`; cleared only when the trace changes.
+    pub duration_shares: DurationShareCache,
 }
 
 impl TreeCache {
@@ -44,13 +63,16 @@ impl TreeCache {
     pub fn new() -> Self {
         Self {
             subtree_sizes: HashMap::new(),
-            all_children_collapsed: HashMap::new(),
             total_visible_nodes: None,
-            max_visible_depth: None,
             expansion_seq: 0,
-            filtered_viewport_range: None,
             filtered_node_count: None,
             sorted_children: HashMap::new(),
+            swimlane_rows: None,
+            row_heat: None,
+            header_stats: None,
+            trace_summary: None,
+            problem_rollup: ProblemRollupCache::new(),
+            duration_shares: DurationShareCache::new(),
         }
     }
 
@@ -62,30 +84,19 @@ impl TreeCache {
     /// - The tree structure changes
     pub fn invalidate(&mut self) {
         self.subtree_sizes.clear();
-        self.all_children_collapsed.clear();
         self.total_visible_nodes = None;
-        self.max_visible_depth = None;
         self.expansion_seq += 1;
         self.sorted_children.clear();
+        self.swimlane_rows = None;
         // Also invalidate filtered cache
         self.invalidate_filtered_cache();
     }
 
-    /// Checks if filtered cache is valid for given viewport range.
-    ///
-    /// # Arguments
-    /// * `start_clk` - Start of viewport range
-    /// * `end_clk` - End of viewport range
-    ///
-    /// # Returns
-    /// `true` if cached filtered tree matches the given range, `false` otherwise
-    pub fn is_filtered_cache_valid(&self, start_clk: i64, end_clk: i64) -> bool {
-        match self.filtered_viewport_range {
-            Some((cached_start, cached_end)) => {
-                cached_start == start_clk && cached_end == end_clk
-            }
-            None => false,
-        }
+    /// Drops the trace summary, problem roll-up and duration shares (call when a different trace is loaded).
+    pub fn clear_trace_summary(&mut self) {
+        self.trace_summary = None;
+        self.problem_rollup.clear();
+        self.duration_shares.clear();
     }
 
     /// Invalidates only the filtered tree cache (preserves unfiltered cache).
@@ -94,8 +105,8 @@ impl TreeCache {
     /// - Viewport range changes (start_clk or end_clk)
     /// - Filter is toggled on/off
     pub fn invalidate_filtered_cache(&mut self) {
-        self.filtered_viewport_range = None;
         self.filtered_node_count = None;
+        self.row_heat = None;
     }
 }
 
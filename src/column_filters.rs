@@ -0,0 +1,228 @@
+//! Per-column filters of the tree table.
+//!
+//! Each column header has a filter popover. Text columns with few distinct
+//! values filter by value list (checkboxes), numeric columns by an optional
+//! min/max range. Like the clock stride filter, column filters only decide
+//! which leaves are shown; parents stay as anchors so matching descendants
+//! remain reachable.
+
+use crate::{DynTraceData, TraceData, TraceRecord};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Most distinct values a text column may have to get a value list.
+pub const MAX_VALUE_LIST_LEN: usize = 64;
+
+/// A column of the tree table (in header order after the tree column).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TreeColumn {
+    Name,
+    Description,
+    StartClock,
+    Duration,
+    Id,
+}
+
+impl TreeColumn {
+    /// All columns in header order.
+    pub const ALL: [TreeColumn; 5] = [
+        TreeColumn::Name,
+        TreeColumn::Description,
+        TreeColumn::StartClock,
+        TreeColumn::Duration,
+        TreeColumn::Id,
+    ];
+
+    /// Returns the column at a header index (0-4).
+    pub fn from_index(index: usize) -> Option<Self> {
+        Self::ALL.get(index).copied()
+    }
+
+    /// Returns the header title.
+    pub fn title(self) -> &'static str {
+        match self {
+            TreeColumn::Name => "Name",
+            TreeColumn::Description => "Description",
+            TreeColumn::StartClock => "Start Clock",
+            TreeColumn::Duration => "Duration",
+            TreeColumn::Id => "ID",
+        }
+    }
+
+    /// Returns true for columns filtered by range rather than by value list.
+    pub fn is_numeric(self) -> bool {
+        matches!(self, TreeColumn::StartClock | TreeColumn::Duration | TreeColumn::Id)
+    }
+
+    /// Returns the text value of a record in a text column.
+    fn text<'a, R: TraceRecord<'a>>(self, record: &R) -> Option<String> {
+        match self {
+            TreeColumn::Name => Some(record.name()),
+            TreeColumn::Description => Some(record.description()),
+            _ => None,
+        }
+    }
+
+    /// Returns the numeric value of a record in a numeric column (None for a
+    /// record without a duration).
+    pub fn number<'a, R: TraceRecord<'a>>(self, record: &R) -> Option<i128> {
+        match self {
+            TreeColumn::StartClock => Some(record.clk() as i128),
+            TreeColumn::Duration => record.duration().map(i128::from),
+            TreeColumn::Id => Some(record.id() as i128),
+            _ => None,
+        }
+    }
+}
+
+/// Filter on one column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColumnFilter {
+    /// Hide records whose value is in the set (text columns)
+    Exclude(HashSet<String>),
+    /// Keep records whose value is within the bounds (numeric columns);
+    /// records without a value never match
+    Range { min: Option<i64>, max: Option<i64> },
+}
+
+impl ColumnFilter {
+    /// Returns true if the filter would not hide anything.
+    pub fn is_noop(&self) -> bool {
+        match self {
+            ColumnFilter::Exclude(values) => values.is_empty(),
+            ColumnFilter::Range { min, max } => min.is_none() && max.is_none(),
+        }
+    }
+
+    fn matches<'a, R: TraceRecord<'a>>(&self, column: TreeColumn, record: &R) -> bool {
+        match self {
+            ColumnFilter::Exclude(values) => column.text(record).is_none_or(|value| !values.contains(&value)),
+            ColumnFilter::Range { min, max } => column.number(record).is_some_and(|value| {
+                min.is_none_or(|min| value >= min as i128) && max.is_none_or(|max| value <= max as i128)
+            }),
+        }
+    }
+}
+
+/// The active filter of each column.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColumnFilters {
+    by_column: HashMap<TreeColumn, ColumnFilter>,
+}
+
+impl ColumnFilters {
+    /// Returns true if no column is filtered.
+    pub fn is_empty(&self) -> bool {
+        self.by_column.is_empty()
+    }
+
+    /// Returns the number of filtered columns.
+    pub fn len(&self) -> usize {
+        self.by_column.len()
+    }
+
+    /// Returns the filter of a column, if it has one.
+    pub fn get(&self, column: TreeColumn) -> Option<&ColumnFilter> {
+        self.by_column.get(&column)
+    }
+
+    /// Sets the filter of a column; a filter that hides nothing clears it.
+    pub fn set(&mut self, column: TreeColumn, filter: ColumnFilter) {
+        if filter.is_noop() {
+            self.by_column.remove(&column);
+        } else {
+            self.by_column.insert(column, filter);
+        }
+    }
+
+    /// Removes the filter of a column.
+    pub fn clear(&mut self, column: TreeColumn) {
+        self.by_column.remove(&column);
+    }
+
+    /// Removes all filters.
+    pub fn clear_all(&mut self) {
+        self.by_column.clear();
+    }
+
+    /// Returns true if the record passes the filter of a column (or it has none).
+    pub fn column_matches<'a, R: TraceRecord<'a>>(&self, column: TreeColumn, record: &R) -> bool {
+        self.get(column).is_none_or(|filter| filter.matches(column, record))
+    }
+
+    /// Returns true if the record passes the filters of all columns.
+    pub fn matches<'a, R: TraceRecord<'a>>(&self, record: &R) -> bool {
+        self.by_column.iter().all(|(&column, filter)| filter.matches(column, record))
+    }
+}
+
+/// Counts the distinct values of a text column over all records.
+///
+/// # Returns
+/// Values with their record counts in ascending order, or None for numeric
+/// columns and for columns with more than `limit` distinct values
+pub fn distinct_values(trace: &DynTraceData, column: TreeColumn, limit: usize) -> Option<Vec<(String, usize)>> {
+    if column.is_numeric() {
+        return None;
+    }
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for record in trace.records_iter() {
+        let value = column.text(&record)?;
+        *counts.entry(value).or_insert(0) += 1;
+        if counts.len() > limit {
+            return None;
+        }
+    }
+    Some(counts.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DynTraceRecord;
+
+    fn trace() -> DynTraceData {
+        let input = concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"Core","record_type":"Op","id":1,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record","clk":10,"name":"load","record_type":"Op","id":2,"parent_id":1,"description":"hit"}"#, "\n",
+            r#"{"type":"record","clk":20,"name":"load","record_type":"Op","id":3,"parent_id":1,"description":"miss"}"#, "\n",
+            r#"{"type":"record","clk":30,"name":"store","record_type":"Op","id":4,"parent_id":1,"description":"hit"}"#, "\n",
+            r#"{"type":"record_end","clk":12,"record_id":2}"#, "\n",
+            r#"{"type":"record_end","clk":60,"record_id":3}"#, "\n",
+            r#"{"type":"record_end","clk":100,"record_id":1}"#, "\n",
+        );
+        DynTraceData::Jets(crate::parse_trace_from_reader(input.as_bytes()).unwrap())
+    }
+
+    #[test]
+    fn test_filters_match_values_and_ranges() {
+        let trace = trace();
+        let record = |id| trace.get_record(id).unwrap();
+        let passing = |filters: &ColumnFilters| -> Vec<u64> {
+            (2..=4).filter(|&id| filters.matches::<DynTraceRecord>(&record(id))).collect()
+        };
+
+        let mut filters = ColumnFilters::default();
+        assert_eq!(passing(&filters), vec![2, 3, 4]);
+
+        filters.set(TreeColumn::Description, ColumnFilter::Exclude(HashSet::from(["miss".to_string()])));
+        assert_eq!(passing(&filters), vec![2, 4]);
+
+        // Records without a duration never match a duration range
+        filters.set(TreeColumn::Duration, ColumnFilter::Range { min: Some(1), max: None });
+        assert_eq!(passing(&filters), vec![2]);
+        filters.set(TreeColumn::Duration, ColumnFilter::Range { min: None, max: None });
+        assert_eq!(filters.len(), 1);
+
+        filters.clear_all();
+        filters.set(TreeColumn::StartClock, ColumnFilter::Range { min: Some(15), max: Some(30) });
+        assert_eq!(passing(&filters), vec![3, 4]);
+
+        assert_eq!(
+            distinct_values(&trace, TreeColumn::Name, MAX_VALUE_LIST_LEN),
+            Some(vec![("Core".to_string(), 1), ("load".to_string(), 2), ("store".to_string(), 1)])
+        );
+        assert_eq!(distinct_values(&trace, TreeColumn::Name, 2), None);
+        assert_eq!(distinct_values(&trace, TreeColumn::Id, MAX_VALUE_LIST_LEN), None);
+    }
+}
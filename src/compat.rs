@@ -0,0 +1,136 @@
+//! Adapters for older JETS layouts.
+//!
+//! Traces written by 1.x producers use different field names for the same
+//! information (`kind` instead of `record_type`, `parent` instead of
+//! `parent_id`, and `id` instead of `record_id` on lines that reference a
+//! record) and may omit `description`. Rather than teaching the parser every
+//! historical spelling, each line of an old trace is rewritten into the
+//! current layout before it is deserialized. The parser and the validator
+//! share these adapters, and [`crate::schema`] uses them to emit the schema
+//! of an old version.
+
+use serde_json::{Map, Value};
+
+use crate::schema::FormatVersion;
+
+/// A field that was renamed between a legacy layout and the current one.
+#[derive(Debug, Clone, Copy)]
+pub struct FieldRename {
+    pub line_type: &'static str,
+    /// Name used by the legacy layout
+    pub legacy: &'static str,
+    /// Name used by the current layout
+    pub current: &'static str,
+}
+
+/// How a legacy format version differs from the current layout.
+#[derive(Debug, Clone, Copy)]
+pub struct LegacyLayout {
+    /// Major version this layout applies to
+    pub major: u32,
+    pub renames: &'static [FieldRename],
+    /// Line types whose `description` was optional; filled with `""` when absent
+    pub defaulted_descriptions: &'static [&'static str],
+}
+
+/// Layout of JETS 1.x traces.
+pub const V1_LAYOUT: LegacyLayout = LegacyLayout {
+    major: 1,
+    renames: &[
+        FieldRename { line_type: "record", legacy: "kind", current: "record_type" },
+        FieldRename { line_type: "record", legacy: "parent", current: "parent_id" },
+        FieldRename { line_type: "record_end", legacy: "id", current: "record_id" },
+        FieldRename { line_type: "annotation", legacy: "id", current: "record_id" },
+        FieldRename { line_type: "event", legacy: "id", current: "record_id" },
+    ],
+    defaulted_descriptions: &["record", "annotation", "event"],
+};
+
+/// All known legacy layouts.
+const LEGACY_LAYOUTS: &[LegacyLayout] = &[V1_LAYOUT];
+
+/// Returns the adapter needed to read a trace of the given version, if any.
+///
+/// Current and newer versions need no adapter.
+pub fn layout_for(version: FormatVersion) -> Option<&'static LegacyLayout> {
+    LEGACY_LAYOUTS.iter().find(|layout| layout.major == version.major)
+}
+
+impl LegacyLayout {
+    /// Rewrites one line object into the current layout in place.
+    ///
+    /// Fields already using the current name are left alone, so traces that
+    /// declare an old version but were written with current names still read.
+    pub fn upgrade(&self, object: &mut Map<String, Value>) {
+        let Some(line_type) = object.get("type").and_then(Value::as_str).map(str::to_string) else {
+            return;
+        };
+
+        for rename in self.renames.iter().filter(|r| r.line_type == line_type) {
+            if object.contains_key(rename.current) {
+                continue;
+            }
+            if let Some(value) = object.remove(rename.legacy) {
+                object.insert(rename.current.to_string(), value);
+            }
+        }
+
+        if self.defaulted_descriptions.contains(&line_type.as_str()) && !object.contains_key("description") {
+            object.insert("description".to_string(), Value::String(String::new()));
+        }
+    }
+
+    /// Returns the legacy name of a current field, if it was renamed.
+    pub fn legacy_name(&self, line_type: &str, current: &str) -> Option<&'static str> {
+        self.renames
+            .iter()
+            .find(|r| r.line_type == line_type && r.current == current)
+            .map(|r| r.legacy)
+    }
+
+    /// Returns true if `description` was optional for this line type.
+    pub fn description_optional(&self, line_type: &str) -> bool {
+        self.defaulted_descriptions.contains(&line_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn upgraded(line: Value) -> Value {
+        let mut object = line.as_object().unwrap().clone();
+        V1_LAYOUT.upgrade(&mut object);
+        Value::Object(object)
+    }
+
+    #[test]
+    fn test_upgrade_renames_legacy_fields() {
+        let record = upgraded(json!({"type": "record", "clk": 0, "name": "A", "kind": "T", "id": 1, "parent": null}));
+        assert_eq!(record["record_type"], "T");
+        assert!(record.get("parent_id").is_some_and(Value::is_null));
+        assert_eq!(record["id"], 1);
+        assert_eq!(record["description"], "");
+
+        let end = upgraded(json!({"type": "record_end", "clk": 5, "id": 1}));
+        assert_eq!(end["record_id"], 1);
+        assert!(end.get("id").is_none());
+    }
+
+    #[test]
+    fn test_upgrade_keeps_current_names() {
+        let event = upgraded(json!({"type": "event", "clk": 5, "name": "E", "record_id": 2, "id": 9, "description": "d"}));
+        assert_eq!(event["record_id"], 2);
+        assert_eq!(event["id"], 9);
+        assert_eq!(event["description"], "d");
+    }
+
+    #[test]
+    fn test_layout_lookup() {
+        assert!(layout_for(FormatVersion::V1_0).is_some());
+        assert!(layout_for(FormatVersion { major: 1, minor: 4 }).is_some());
+        assert!(layout_for(FormatVersion::CURRENT).is_none());
+        assert!(layout_for(FormatVersion { major: 3, minor: 0 }).is_none());
+    }
+}
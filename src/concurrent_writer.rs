@@ -0,0 +1,330 @@
+//! Trace writing from several threads.
+//!
+//! [`TraceWriter`] is single-threaded. A [`ConcurrentTraceWriter`] owns one on
+//! a dedicated writer thread and hands out [`ThreadWriter`]s, one per
+//! producer thread. Each handle serializes its lines into a local batch and
+//! passes full batches to the writer thread over a bounded channel, so JSON
+//! encoding runs in parallel and a slow disk applies backpressure instead of
+//! growing memory.
+//!
+//! Lines of one handle keep their order. Batches of different handles
+//! interleave, so a record's `record_end`, events and annotations should be
+//! written through the handle that wrote the record, or after that handle was
+//! [flushed](ThreadWriter::flush). Parents may be written by any handle,
+//! since readers resolve parents once the whole trace is read.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use rjets::ConcurrentTraceWriter;
+//! # fn main() -> anyhow::Result<()> {
+//! let writer = ConcurrentTraceWriter::new("trace.jets")?;
+//! writer.write_header("2.0", serde_json::json!({}))?;
+//! std::thread::scope(|scope| {
+//!     for core in 0..4u64 {
+//!         let mut out = writer.handle();
+//!         scope.spawn(move || -> anyhow::Result<()> {
+//!             out.write_record(core, None, "Core", 0, &format!("core{}", core), "", None)?;
+//!             out.write_record_end(core, 100)
+//!         });
+//!     }
+//! });
+//! writer.finish(Some(100))?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::thread::{self, JoinHandle};
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+
+use crate::traits::ExternalId;
+use crate::writer::{self, LineCounts, TraceWriter};
+
+/// Bytes a handle buffers before passing its batch to the writer thread.
+pub const BATCH_BYTES: usize = 64 * 1024;
+
+/// Batches that may wait for the writer thread before producers block.
+const QUEUED_BATCHES: usize = 64;
+
+/// Message from the producers to the writer thread.
+enum Message {
+    Header { version: String, metadata: Value },
+    Batch { lines: String, counts: LineCounts },
+    /// Written once every handle is gone
+    Footer { capture_end_clk: Option<i64> },
+}
+
+/// A [`TraceWriter`] fed from several threads through [`ThreadWriter`] handles.
+pub struct ConcurrentTraceWriter {
+    sender: SyncSender<Message>,
+    worker: JoinHandle<Result<()>>,
+}
+
+impl ConcurrentTraceWriter {
+    /// Creates a writer for a file (Brotli-compressed if the path ends with
+    /// `.br`, like [`TraceWriter::new`]).
+    pub fn new(file_path: &str) -> Result<Self> {
+        let file_path = file_path.to_string();
+        Self::spawn(move || TraceWriter::new(&file_path))
+    }
+
+    /// Starts the writer thread with the writer built by `make`.
+    ///
+    /// `make` runs on the writer thread, since a [`TraceWriter`] cannot be
+    /// moved between threads; its error is returned here.
+    pub fn spawn<F>(make: F) -> Result<Self>
+    where
+        F: FnOnce() -> Result<TraceWriter> + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::sync_channel(QUEUED_BATCHES);
+        let (ready_sender, ready_receiver) = mpsc::channel();
+        let worker = thread::Builder::new()
+            .name("jets-writer".to_string())
+            .spawn(move || {
+                let writer = match make() {
+                    Ok(writer) => {
+                        let _ = ready_sender.send(Ok(()));
+                        writer
+                    }
+                    Err(e) => {
+                        let _ = ready_sender.send(Err(e));
+                        return Ok(());
+                    }
+                };
+                drain(writer, receiver)
+            })
+            .context("Failed to start the writer thread")?;
+
+        match ready_receiver.recv() {
+            Ok(Ok(())) => Ok(ConcurrentTraceWriter { sender, worker }),
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err(anyhow!("Writer thread stopped while starting")),
+        }
+    }
+
+    /// Writes the header line. Call before handing out handles.
+    pub fn write_header(&self, version: &str, metadata: Value) -> Result<()> {
+        send(&self.sender, Message::Header { version: version.to_string(), metadata })
+    }
+
+    /// Returns a new handle for one producer thread.
+    pub fn handle(&self) -> ThreadWriter {
+        ThreadWriter {
+            sender: self.sender.clone(),
+            lines: String::new(),
+            counts: LineCounts::default(),
+        }
+    }
+
+    /// Writes the footer once every handle has been dropped, and waits for
+    /// the writer thread to finish.
+    ///
+    /// Blocks while any handle is alive. Returns the first write error the
+    /// writer thread ran into, if any.
+    pub fn finish(self, capture_end_clk: Option<i64>) -> Result<()> {
+        // A send error means the writer thread already failed; join reports why
+        let _ = self.sender.send(Message::Footer { capture_end_clk });
+        drop(self.sender);
+        self.worker.join().map_err(|_| anyhow!("Writer thread panicked"))?
+    }
+}
+
+/// Writes messages until every sender is gone.
+fn drain(mut writer: TraceWriter, receiver: Receiver<Message>) -> Result<()> {
+    let mut footer = None;
+    for message in receiver {
+        match message {
+            Message::Header { version, metadata } => writer.write_header(&version, metadata)?,
+            Message::Batch { lines, counts } => writer.write_serialized(&lines, counts)?,
+            Message::Footer { capture_end_clk } => footer = Some(capture_end_clk),
+        }
+    }
+    if let Some(capture_end_clk) = footer {
+        writer.write_footer(capture_end_clk)?;
+    }
+    writer.finish()
+}
+
+fn send(sender: &SyncSender<Message>, message: Message) -> Result<()> {
+    sender
+        .send(message)
+        .map_err(|_| anyhow!("Writer thread stopped; see ConcurrentTraceWriter::finish for the error"))
+}
+
+/// One producer thread's end of a [`ConcurrentTraceWriter`].
+///
+/// The methods mirror [`TraceWriter`]'s. Lines are buffered until
+/// [`BATCH_BYTES`] are collected, [`flush`](Self::flush) is called or the
+/// handle is dropped.
+pub struct ThreadWriter {
+    sender: SyncSender<Message>,
+    lines: String,
+    counts: LineCounts,
+}
+
+impl ThreadWriter {
+    #[allow(clippy::too_many_arguments)]
+    pub fn write_record(
+        &mut self,
+        id: u64,
+        parent_id: Option<u64>,
+        record_type: &str,
+        clk: i64,
+        name: &str,
+        description: &str,
+        data: Option<Value>,
+    ) -> Result<()> {
+        self.write_record_external(
+            &ExternalId::Int(id),
+            parent_id.map(ExternalId::Int).as_ref(),
+            record_type,
+            clk,
+            name,
+            description,
+            data,
+        )
+    }
+
+    /// Writes a record whose ID and parent ID may be strings.
+    #[allow(clippy::too_many_arguments)]
+    pub fn write_record_external(
+        &mut self,
+        id: &ExternalId,
+        parent_id: Option<&ExternalId>,
+        record_type: &str,
+        clk: i64,
+        name: &str,
+        description: &str,
+        data: Option<Value>,
+    ) -> Result<()> {
+        self.counts.records += 1;
+        self.push(&writer::record_line(id, parent_id, record_type, clk, name, description, data))
+    }
+
+    pub fn write_record_end(&mut self, id: impl Into<ExternalId>, clk: i64) -> Result<()> {
+        self.push(&writer::record_end_line(&id.into(), clk))
+    }
+
+    pub fn write_annotation(
+        &mut self,
+        record_id: impl Into<ExternalId>,
+        name: &str,
+        description: &str,
+        data: Value,
+    ) -> Result<()> {
+        self.counts.annotations += 1;
+        self.push(&writer::annotation_line(&record_id.into(), name, description, data))
+    }
+
+    pub fn write_event(
+        &mut self,
+        record_id: impl Into<ExternalId>,
+        name: &str,
+        description: &str,
+        clk: i64,
+        data: Option<Value>,
+    ) -> Result<()> {
+        self.counts.events += 1;
+        self.push(&writer::event_line(&record_id.into(), name, description, clk, data))
+    }
+
+    /// Passes the buffered lines to the writer thread.
+    ///
+    /// Lines written by any handle afterwards land after them in the file.
+    pub fn flush(&mut self) -> Result<()> {
+        if self.lines.is_empty() {
+            return Ok(());
+        }
+        let lines = std::mem::take(&mut self.lines);
+        let counts = std::mem::take(&mut self.counts);
+        send(&self.sender, Message::Batch { lines, counts })
+    }
+
+    fn push(&mut self, line: &Value) -> Result<()> {
+        let json = serde_json::to_string(line).context("Failed to serialize to JSON")?;
+        self.lines.push_str(&json);
+        self.lines.push('\n');
+        if self.lines.len() >= BATCH_BYTES {
+            self.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ThreadWriter {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_trace_from_reader;
+    use crate::traits::{TraceData, TraceMetadata, TraceRecord};
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    /// Sink shared with the test after the writer thread is done.
+    #[derive(Clone, Default)]
+    struct SharedSink(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedSink {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_threads_write_one_trace() {
+        let sink = SharedSink::default();
+        let thread_sink = sink.clone();
+        let writer = ConcurrentTraceWriter::spawn(move || {
+            Ok(TraceWriter::from_writer(Box::new(thread_sink)).with_provenance("test"))
+        })
+        .unwrap();
+        writer.write_header("2.0", serde_json::json!({})).unwrap();
+
+        // Child records reference a root that another handle writes last
+        std::thread::scope(|scope| {
+            for core in 1..=4u64 {
+                let mut out = writer.handle();
+                scope.spawn(move || {
+                    for op in 0..1000u64 {
+                        let id = core * 10_000 + op;
+                        out.write_record(id, Some(0), "Op", op as i64, "op", "", None).unwrap();
+                        out.write_event(id, "issue", "", op as i64, None).unwrap();
+                        out.write_record_end(id, op as i64 + 1).unwrap();
+                    }
+                });
+            }
+        });
+        let mut root = writer.handle();
+        root.write_record(0, None, "Core", 0, "core", "", None).unwrap();
+        drop(root);
+        writer.finish(Some(1000)).unwrap();
+
+        let bytes = sink.0.lock().unwrap().clone();
+        let trace = parse_trace_from_reader(bytes.as_slice()).unwrap();
+        assert_eq!(trace.root_ids().len(), 1);
+        assert_eq!(trace.get_record(0).unwrap().num_children(), 4000);
+        assert_eq!(trace.get_record(20_999).unwrap().end_clk(), Some(1000));
+        let footer = String::from_utf8(bytes).unwrap().lines().last().unwrap().to_string();
+        assert!(footer.contains(r#""total_records":4001"#) && footer.contains(r#""total_events":4000"#), "{}", footer);
+        assert!(trace.metadata().warnings().is_empty());
+    }
+
+    #[test]
+    fn test_failing_writer_is_reported() {
+        let error = ConcurrentTraceWriter::spawn(|| Err(anyhow!("no disk"))).err().unwrap();
+        assert_eq!(error.to_string(), "no disk");
+    }
+}
@@ -0,0 +1,40 @@
+//! Counter records: numeric samples over time.
+//!
+//! A counter is an ordinary record with record_type [`COUNTER_RECORD_TYPE`]
+//! whose events are samples: each event carries the new counter value in its
+//! [`COUNTER_VALUE_ATTR`] attribute, and the value holds until the next
+//! sample. Hardware counters such as queue depth or power draw can so live in
+//! the same JETS file as the event data they explain. The format needs no
+//! extension for this; the parser passes the samples through like any other
+//! event, and viewers draw counter rows as step lines.
+//!
+//! Write counters with [`crate::TraceWriter::write_counter_record`] and
+//! [`crate::TraceWriter::write_counter_sample`].
+
+use crate::traits::{TraceEvent, TraceRecord};
+
+/// Record type marking a record as a counter.
+pub const COUNTER_RECORD_TYPE: &str = "Counter";
+
+/// Event attribute holding a counter sample value.
+pub const COUNTER_VALUE_ATTR: &str = "value";
+
+/// Name given to sample events by the writer helpers.
+pub const COUNTER_SAMPLE_EVENT: &str = "sample";
+
+/// Returns true if the record is a counter.
+pub fn is_counter<'a>(record: &impl TraceRecord<'a>) -> bool {
+    record.record_type() == COUNTER_RECORD_TYPE
+}
+
+/// Returns the value of a counter sample event.
+///
+/// Samples written by hand may carry the number as their whole `data` rather
+/// than under `value`; both are accepted. Non-numeric samples yield None.
+pub fn counter_value(event: &impl TraceEvent) -> Option<f64> {
+    event
+        .attr(COUNTER_VALUE_ATTR)
+        .or_else(|| event.attr("data"))
+        .and_then(|value| value.as_f64())
+        .filter(|value| value.is_finite())
+}
@@ -0,0 +1,99 @@
+//! Quick statistics of numeric tree columns.
+//!
+//! Hovering the Start Clock or Duration header shows min, mean and max over
+//! the rows currently in the tree (after expansion, filters and pinning).
+//! All stats columns are computed in one pass over the rows.
+
+use crate::domain::column_filters::TreeColumn;
+use rjets::TraceRecord;
+
+/// Columns whose header shows a stats tooltip.
+pub const STATS_COLUMNS: [TreeColumn; 2] = [TreeColumn::StartClock, TreeColumn::Duration];
+
+/// Summary of one numeric column over a set of rows.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColumnStats {
+    /// Rows with a value (rows without a duration are skipped)
+    pub count: usize,
+    pub min: i64,
+    pub max: i64,
+    pub mean: f64,
+}
+
+/// Stats of [`STATS_COLUMNS`] computed for one frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeaderStats {
+    /// Frame the stats were computed in
+    pub frame: u64,
+    /// Total rows considered
+    pub rows: usize,
+    /// Stats per entry of `STATS_COLUMNS` (None if no row has a value)
+    pub columns: [Option<ColumnStats>; STATS_COLUMNS.len()],
+}
+
+impl HeaderStats {
+    /// Computes the stats of all stats columns over `records`.
+    pub fn compute<'a, R: TraceRecord<'a>>(frame: u64, records: impl IntoIterator<Item = R>) -> Self {
+        let mut rows = 0;
+        let mut sums = [(0usize, 0f64, i64::MAX, i64::MIN); STATS_COLUMNS.len()];
+        for record in records {
+            rows += 1;
+            for (column, (count, sum, min, max)) in STATS_COLUMNS.iter().zip(&mut sums) {
+                // Stats columns hold i64 values
+                if let Some(value) = column.number(&record).map(|value| value as i64) {
+                    *count += 1;
+                    *sum += value as f64;
+                    *min = (*min).min(value);
+                    *max = (*max).max(value);
+                }
+            }
+        }
+        let columns = sums.map(|(count, sum, min, max)| {
+            (count > 0).then(|| ColumnStats {
+                count,
+                min,
+                max,
+                mean: sum / count as f64,
+            })
+        });
+        Self { frame, rows, columns }
+    }
+
+    /// Returns the stats of a column, if it is a stats column with values.
+    pub fn get(&self, column: TreeColumn) -> Option<&ColumnStats> {
+        let index = STATS_COLUMNS.iter().position(|&c| c == column)?;
+        self.columns[index].as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rjets::{DynTraceData, TraceData};
+
+    #[test]
+    fn test_header_stats_over_rows() {
+        let input = concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"a","record_type":"Op","id":1,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record","clk":10,"name":"b","record_type":"Op","id":2,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record","clk":40,"name":"c","record_type":"Op","id":3,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record_end","clk":30,"record_id":1}"#, "\n",
+            r#"{"type":"record_end","clk":20,"record_id":2}"#, "\n",
+        );
+        let trace = DynTraceData::Jets(rjets::parse_trace_from_reader(input.as_bytes()).unwrap());
+
+        let stats = HeaderStats::compute(7, [1, 2, 3].into_iter().filter_map(|id| trace.get_record(id)));
+        assert_eq!(stats.rows, 3);
+        assert_eq!(
+            stats.get(TreeColumn::StartClock),
+            Some(&ColumnStats { count: 3, min: 0, max: 40, mean: 50.0 / 3.0 })
+        );
+        let duration = stats.get(TreeColumn::Duration).unwrap();
+        assert_eq!((duration.count, duration.min, duration.max, duration.mean), (2, 10, 30, 20.0));
+        assert_eq!(stats.get(TreeColumn::Id), None);
+
+        let empty = HeaderStats::compute(7, trace.get_record(99));
+        assert_eq!(empty.get(TreeColumn::StartClock), None);
+    }
+}
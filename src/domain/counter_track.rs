@@ -0,0 +1,136 @@
+//! Step-line geometry for counter records.
+//!
+//! A counter row plots its samples as a step line: each value holds until
+//! the next sample. Samples are reduced to one column per pixel first (keeping
+//! the extremes and the last value), so a counter with millions of samples
+//! costs no more to draw than the row is wide. The value axis is scaled to the
+//! samples in view.
+
+use rjets::{counter_value, TraceEvent, TraceRecord};
+
+/// Samples of a counter that fall into one pixel column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CounterColumn {
+    /// Screen x of the column (whole pixels)
+    pub x: f32,
+    /// Smallest value in the column
+    pub min: f64,
+    /// Largest value in the column
+    pub max: f64,
+    /// Value of the last sample in the column, held until the next column
+    pub last: f64,
+}
+
+/// Returns the index of the last sample at or before `clk`.
+pub fn sample_at<'a>(record: &impl TraceRecord<'a>, clk: i64) -> Option<usize> {
+    let (mut left, mut right) = (0, record.num_events());
+    while left < right {
+        let mid = left + (right - left) / 2;
+        match record.event_at(mid) {
+            Some(event) if event.clk() <= clk => left = mid + 1,
+            Some(_) => right = mid,
+            None => break,
+        }
+    }
+    left.checked_sub(1)
+}
+
+/// Reduces samples to pixel columns.
+///
+/// # Arguments
+/// * `samples` - `(x, value)` pairs in clock order
+pub fn bucket_samples(samples: impl IntoIterator<Item = (f32, f64)>) -> Vec<CounterColumn> {
+    let mut columns: Vec<CounterColumn> = Vec::new();
+    for (x, value) in samples {
+        let x = x.round();
+        match columns.last_mut() {
+            Some(column) if column.x == x => {
+                column.min = column.min.min(value);
+                column.max = column.max.max(value);
+                column.last = value;
+            }
+            _ => columns.push(CounterColumn { x, min: value, max: value, last: value }),
+        }
+    }
+    columns
+}
+
+/// Collects the columns of a counter's samples within a clock range.
+///
+/// The sample before `start_clk` is included, since its value is still
+/// current at the left edge. Samples without a numeric value are skipped.
+pub fn visible_columns<'a>(
+    record: &impl TraceRecord<'a>,
+    start_clk: i64,
+    end_clk: i64,
+    clk_to_x: impl Fn(i64) -> f32,
+) -> Vec<CounterColumn> {
+    let first = sample_at(record, start_clk).unwrap_or(0);
+    let samples = (first..record.num_events())
+        .map_while(|i| record.event_at(i).filter(|event| event.clk() <= end_clk))
+        .filter_map(|event| Some((clk_to_x(event.clk()), counter_value(&event)?)));
+    bucket_samples(samples)
+}
+
+/// Returns the value range covered by the columns, widened when flat.
+pub fn value_range(columns: &[CounterColumn]) -> Option<(f64, f64)> {
+    let min = columns.iter().map(|c| c.min).reduce(f64::min)?;
+    let max = columns.iter().map(|c| c.max).reduce(f64::max)?;
+    if max > min {
+        Some((min, max))
+    } else {
+        // A constant counter is drawn mid-row
+        Some((min - 1.0, max + 1.0))
+    }
+}
+
+/// Builds the step-line polyline for the columns.
+///
+/// # Arguments
+/// * `columns` - Columns from [`bucket_samples`]
+/// * `end_x` - Where the last value stops (the record end or the row edge)
+/// * `range` - Value range mapped onto `bottom..top`
+/// * `top`, `bottom` - Vertical extent of the plot in screen coordinates
+pub fn step_points(columns: &[CounterColumn], end_x: f32, range: (f64, f64), top: f32, bottom: f32) -> Vec<(f32, f32)> {
+    let (min, max) = range;
+    let to_y = |value: f64| bottom - ((value - min) / (max - min)) as f32 * (bottom - top);
+
+    let mut points = Vec::with_capacity(columns.len() * 4 + 1);
+    let mut held = None;
+    for column in columns {
+        if let Some(value) = held {
+            points.push((column.x, to_y(value)));
+        }
+        points.push((column.x, to_y(column.min)));
+        points.push((column.x, to_y(column.max)));
+        points.push((column.x, to_y(column.last)));
+        held = Some(column.last);
+    }
+    if let Some(value) = held {
+        points.push((end_x.max(points[points.len() - 1].0), to_y(value)));
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_line_holds_values() {
+        let columns = bucket_samples([(0.2, 1.0), (0.4, 5.0), (0.1, 3.0), (10.0, 0.0)]);
+        assert_eq!(columns.len(), 2);
+        assert_eq!((columns[0].min, columns[0].max, columns[0].last), (1.0, 5.0, 3.0));
+
+        let range = value_range(&columns).unwrap();
+        assert_eq!(range, (0.0, 5.0));
+        let points = step_points(&columns, 20.0, range, 0.0, 10.0);
+        // The held value 3.0 runs flat to x=10 before stepping down to 0
+        assert_eq!(points[3], (10.0, 4.0));
+        assert_eq!(points.last(), Some(&(20.0, 10.0)));
+
+        let flat = bucket_samples([(0.0, 7.0)]);
+        assert_eq!(value_range(&flat), Some((6.0, 8.0)));
+        assert_eq!(value_range(&[]), None);
+    }
+}
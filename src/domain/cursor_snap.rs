@@ -0,0 +1,95 @@
+//! Snapping the timeline cursor to the nearest event of the hovered row.
+//!
+//! Stage boundaries are events, and hitting one exactly with the mouse is
+//! hard when a pixel covers many clocks. The event of the row under the
+//! pointer closest to the cursor clock is offered instead, if it is within
+//! [`SNAP_RADIUS_PX`] of the pointer.
+
+use rjets::{TraceEvent, TraceRecord};
+
+use crate::domain::viewport_operations;
+
+/// How far from the pointer an event is still snapped to, in pixels.
+pub const SNAP_RADIUS_PX: f32 = 8.0;
+
+/// The event the cursor snaps to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapTarget {
+    pub record_id: u64,
+    /// Index of the event in the record
+    pub event_index: usize,
+    pub event_clk: i64,
+}
+
+/// Returns the clocks [`SNAP_RADIUS_PX`] covers at the current zoom.
+pub fn snap_radius_clks(viewport_start: i64, viewport_end: i64, canvas_width: f32) -> i64 {
+    if canvas_width <= 0.0 {
+        return 0;
+    }
+    let clks = viewport_operations::clk_span(viewport_start, viewport_end) * SNAP_RADIUS_PX as f64 / canvas_width as f64;
+    viewport_operations::offset_clk(0, clks.ceil())
+}
+
+/// Returns the event of `record` closest to `clk`, if one is within `max_distance` clocks.
+///
+/// Events are expected in clock order (as the timeline draws them); ties go
+/// to the earlier event.
+pub fn nearest_event<'a, R: TraceRecord<'a>>(record: &R, clk: i64, max_distance: i64) -> Option<SnapTarget> {
+    let num_events = record.num_events();
+    // First event at or after the clock
+    let (mut left, mut right) = (0, num_events);
+    while left < right {
+        let mid = left + (right - left) / 2;
+        match record.event_at(mid) {
+            Some(event) if event.clk() < clk => left = mid + 1,
+            _ => right = mid,
+        }
+    }
+    let distance = |index: usize| {
+        let event_clk = record.event_at(index)?.clk();
+        Some((clk.abs_diff(event_clk), index, event_clk))
+    };
+    let before = left.checked_sub(1).and_then(distance);
+    let after = (left < num_events).then(|| distance(left)).flatten();
+    let (gap, event_index, event_clk) = match (before, after) {
+        (Some(before), Some(after)) => if after.0 < before.0 { after } else { before },
+        (before, after) => before.or(after)?,
+    };
+    (gap <= max_distance.unsigned_abs()).then_some(SnapTarget {
+        record_id: record.id(),
+        event_index,
+        event_clk,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rjets::{DynTraceData, TraceData};
+
+    #[test]
+    fn test_nearest_event_within_radius() {
+        let input = concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"op","record_type":"Op","id":1,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"event","clk":10,"name":"F","record_id":1,"description":""}"#, "\n",
+            r#"{"type":"event","clk":20,"name":"D","record_id":1,"description":""}"#, "\n",
+            r#"{"type":"event","clk":40,"name":"X","record_id":1,"description":""}"#, "\n",
+        );
+        let trace = DynTraceData::Jets(rjets::parse_trace_from_reader(input.as_bytes()).unwrap());
+        let record = trace.get_record(trace.roots()[0]).unwrap();
+
+        let snap = |clk| nearest_event(&record, clk, 5).map(|target| (target.event_index, target.event_clk));
+        assert_eq!(snap(18), Some((1, 20)));
+        assert_eq!(snap(24), Some((1, 20)));
+        assert_eq!(snap(30), None, "10 clocks from both neighbors");
+        assert_eq!(snap(15), Some((0, 10)), "ties go to the earlier event");
+        assert_eq!(snap(i64::MIN), None);
+        assert_eq!(snap(44), Some((2, 40)));
+        assert_eq!(nearest_event(&record, i64::MAX, i64::MAX).map(|target| target.event_clk), Some(40));
+
+        // 8 pixels of a 1000 pixel canvas showing 0..10000
+        assert_eq!(snap_radius_clks(0, 10_000, 1000.0), 80);
+        assert_eq!(snap_radius_clks(0, 10_000, 0.0), 0);
+    }
+}
@@ -0,0 +1,86 @@
+//! Dependency arrows between timeline bars.
+//!
+//! Records may name the records they depend on (the `depends_on` field, e.g.
+//! the loads producing an instruction's operands). The timeline draws an
+//! arrow from the end of each dependency to the start of the dependent
+//! record, but only when both rows are on screen and both clocks are in the
+//! visible range; an arrow to an off-screen bar would point nowhere useful.
+
+use std::collections::HashMap;
+
+use rjets::{DynTraceData, TraceData, TraceRecord};
+
+/// An arrow from a dependency to the record depending on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DependencyArrow {
+    /// The dependency (arrow tail)
+    pub from_id: u64,
+    /// Clock the tail sits at: the dependency's end, or its start if open
+    pub from_clk: i64,
+    /// The dependent record (arrow head)
+    pub to_id: u64,
+    /// Start clock of the dependent record
+    pub to_clk: i64,
+}
+
+/// Returns the arrows between the given rows whose both ends lie in the
+/// clock range, in row order.
+///
+/// # Arguments
+/// * `trace` - The trace data
+/// * `rows` - Records drawn on screen, with their row positions
+/// * `view_start` - Start of the visible time range
+/// * `view_end` - End of the visible time range
+pub fn visible_arrows(trace: &DynTraceData, rows: &HashMap<u64, f32>, view_start: i64, view_end: i64) -> Vec<DependencyArrow> {
+    let in_view = |clk: i64| (view_start..=view_end).contains(&clk);
+    let mut dependents: Vec<(u64, f32)> = rows.iter().map(|(&id, &y)| (id, y)).collect();
+    dependents.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+    let mut arrows = Vec::new();
+    for (to_id, _) in dependents {
+        let Some(record) = trace.get_record(to_id) else { continue };
+        let to_clk = record.clk();
+        if !in_view(to_clk) {
+            continue;
+        }
+        for from_id in record.depends_on() {
+            if !rows.contains_key(&from_id) {
+                continue;
+            }
+            let Some(dependency) = trace.get_record(from_id) else { continue };
+            let from_clk = dependency.end_clk().unwrap_or(dependency.clk());
+            if in_view(from_clk) {
+                arrows.push(DependencyArrow { from_id, from_clk, to_id, to_clk });
+            }
+        }
+    }
+    arrows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arrows_need_both_ends_visible() {
+        let input = concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"ld","record_type":"Mem","id":1,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record_end","clk":10,"record_id":1}"#, "\n",
+            r#"{"type":"record","clk":12,"name":"add","record_type":"Insn","id":2,"parent_id":null,"description":"","depends_on":[1,3]}"#, "\n",
+            r#"{"type":"record","clk":200,"name":"st","record_type":"Mem","id":3,"parent_id":null,"description":"","depends_on":[2]}"#, "\n",
+        );
+        let trace = DynTraceData::Jets(rjets::parse_trace_from_reader(input.as_bytes()).unwrap());
+        let rows: HashMap<u64, f32> = [(1, 0.0), (2, 20.0), (3, 40.0)].into();
+
+        // The store starts outside the range, so only the load arrow shows
+        assert_eq!(
+            visible_arrows(&trace, &rows, 0, 100),
+            [DependencyArrow { from_id: 1, from_clk: 10, to_id: 2, to_clk: 12 }]
+        );
+        assert_eq!(visible_arrows(&trace, &rows, 0, 300).len(), 3);
+        // A dependency whose row is scrolled away draws nothing
+        let rows: HashMap<u64, f32> = [(2, 20.0)].into();
+        assert!(visible_arrows(&trace, &rows, 0, 300).is_empty());
+    }
+}
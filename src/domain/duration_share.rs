@@ -0,0 +1,89 @@
+//! Record durations as a share of their parent and of the whole trace.
+//!
+//! A child taking 80% of its parent stands out at once as a percentage,
+//! while its raw cycle count has to be compared by eye against the parent
+//! row. Each share needs the parent looked up, so shares are computed on
+//! first request and memoized per record until the trace changes.
+
+use rjets::{DynTraceData, TraceData, TraceMetadata, TraceRecord};
+use std::collections::HashMap;
+
+/// Duration of a record as fractions of its parent's duration and of the trace extent.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DurationShare {
+    /// Fraction of the parent's duration (None for roots, open records or empty parents)
+    pub of_parent: Option<f64>,
+    /// Fraction of the whole trace extent (None for open records or an empty trace)
+    pub of_trace: Option<f64>,
+}
+
+/// Memoized shares, keyed by record ID.
+pub type DurationShareCache = HashMap<u64, DurationShare>;
+
+/// Returns the shares of a record, computing them on first request.
+pub fn duration_share(trace: &DynTraceData, record_id: u64, cache: &mut DurationShareCache) -> DurationShare {
+    if let Some(&cached) = cache.get(&record_id) {
+        return cached;
+    }
+
+    let share = trace.get_record(record_id).map_or_else(DurationShare::default, |record| {
+        let fraction = |whole: i64| record.duration().filter(|_| whole > 0).map(|part| part as f64 / whole as f64);
+        let (min_clk, max_clk) = trace.metadata().trace_extent();
+        DurationShare {
+            of_parent: record
+                .parent_id()
+                .and_then(|parent_id| trace.get_record(parent_id))
+                .and_then(|parent| parent.duration())
+                .and_then(fraction),
+            of_trace: fraction(max_clk - min_clk),
+        }
+    });
+    cache.insert(record_id, share);
+    share
+}
+
+/// Writes a fraction as a percentage, with more digits for small shares.
+pub fn format_share(fraction: f64) -> String {
+    match fraction * 100.0 {
+        0.0 => "0%".to_string(),
+        p if p < 0.1 => "<0.1%".to_string(),
+        p if p < 10.0 => format!("{:.1}%", p),
+        p => format!("{:.0}%", p),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shares_of_parent_and_trace() {
+        let input = concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"core","record_type":"Core","id":1,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record","clk":100,"name":"a","record_type":"Op","id":2,"parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"record","clk":600,"name":"b","record_type":"Op","id":3,"parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"record_end","clk":550,"record_id":2}"#, "\n",
+            r#"{"type":"record_end","clk":1000,"record_id":1}"#, "\n",
+        );
+        let trace = DynTraceData::Jets(rjets::parse_trace_from_reader(input.as_bytes()).unwrap());
+        let mut cache = DurationShareCache::new();
+
+        let root = duration_share(&trace, 1, &mut cache);
+        assert_eq!(root.of_parent, None);
+        assert_eq!(root.of_trace, Some(1.0));
+
+        let child = duration_share(&trace, 2, &mut cache);
+        assert_eq!(child.of_parent, Some(0.45));
+        assert_eq!(child.of_trace, Some(0.45));
+
+        // Open records have no share
+        assert_eq!(duration_share(&trace, 3, &mut cache), DurationShare::default());
+        assert_eq!(cache.len(), 3);
+
+        assert_eq!(format_share(0.45), "45%");
+        assert_eq!(format_share(0.0321), "3.2%");
+        assert_eq!(format_share(0.0004), "<0.1%");
+        assert_eq!(format_share(0.0), "0%");
+    }
+}
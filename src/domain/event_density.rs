@@ -0,0 +1,187 @@
+//! Pixel bucketing of events for dense rows.
+//!
+//! At low zoom a row can hold far more events than there are pixels, and
+//! individual markers pile up into a solid smear. Grouping events into
+//! fixed-width pixel columns gives a per-column count that renderers can draw
+//! as a histogram (or any other aggregate marker) while still letting the user
+//! pick the events behind a column. The columns are clock buckets from
+//! [`aggregate_by_bucket`]. Rows sparse enough for individual markers still
+//! limit which of them register interactions.
+
+use rjets::{aggregate_by_bucket, BucketMetric, ClockSpan};
+
+/// Minimum average spacing (in pixels) between event markers before a row
+/// is considered too dense to draw individual markers.
+pub const MIN_MARKER_SPACING_PX: f32 = 6.0;
+
+/// Markers whose on-screen width is below this are drawn but never interactive.
+pub const MIN_INTERACTIVE_MARKER_PX: f32 = 3.0;
+
+/// How far (in pixels) beyond a marker's edge the pointer may be for the
+/// marker to register hover/click handling.
+pub const MARKER_POINTER_BAND_PX: f32 = 4.0;
+
+/// Events that fall into one pixel column.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EventBucket {
+    /// Number of events in the column
+    pub count: u32,
+    /// Index of the first event in the column (events are clock-ordered)
+    pub first_index: usize,
+}
+
+/// Events of one row grouped into equal-width pixel columns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PixelBuckets {
+    /// Screen x of the left edge of bucket 0
+    pub origin_x: f32,
+    /// Width of each bucket in pixels
+    pub bucket_width: f32,
+    pub buckets: Vec<EventBucket>,
+}
+
+impl PixelBuckets {
+    /// Groups events into columns covering `[min_x, max_x)`.
+    ///
+    /// # Arguments
+    /// * `events` - `(event_index, event)` pairs in clock order
+    /// * `clock_range` - Clocks at `min_x` and `max_x`; events outside are skipped
+    /// * `min_x`, `max_x` - Horizontal span to bucket
+    /// * `bucket_width` - Column width in pixels (at least 1); the span is split
+    ///   into whole columns, so the actual width can be slightly smaller
+    pub fn from_events<E: ClockSpan>(
+        events: impl IntoIterator<Item = (usize, E)>,
+        clock_range: (i64, i64),
+        min_x: f32,
+        max_x: f32,
+        bucket_width: f32,
+    ) -> Self {
+        let bucket_width = bucket_width.max(1.0);
+        let num_buckets = ((max_x - min_x) / bucket_width).ceil().max(0.0) as usize;
+
+        let mut indices = Vec::new();
+        let events = events.into_iter().map(|(index, event)| {
+            indices.push(index);
+            event
+        });
+        let counted = aggregate_by_bucket(events, clock_range, num_buckets, BucketMetric::Starts);
+        let buckets = counted
+            .counts
+            .iter()
+            .zip(&counted.first_items)
+            .map(|(&count, &first)| EventBucket {
+                count: count as u32,
+                first_index: if count > 0 { indices[first] } else { 0 },
+            })
+            .collect();
+
+        Self {
+            origin_x: min_x,
+            bucket_width: if num_buckets > 0 { (max_x - min_x) / num_buckets as f32 } else { bucket_width },
+            buckets,
+        }
+    }
+
+    /// Largest count of any bucket (0 if there are no events).
+    pub fn max_count(&self) -> u32 {
+        self.buckets.iter().map(|b| b.count).max().unwrap_or(0)
+    }
+
+    /// Returns the bucket index under a screen x position.
+    pub fn bucket_at(&self, x: f32) -> Option<usize> {
+        if x < self.origin_x {
+            return None;
+        }
+        let index = ((x - self.origin_x) / self.bucket_width) as usize;
+        (index < self.buckets.len()).then_some(index)
+    }
+
+    /// Screen x range `[left, right)` of a bucket.
+    pub fn bucket_span(&self, index: usize) -> (f32, f32) {
+        let left = self.origin_x + index as f32 * self.bucket_width;
+        (left, left + self.bucket_width)
+    }
+}
+
+/// Returns true if `event_count` markers over `span_px` pixels would overlap.
+pub fn is_too_dense(event_count: usize, span_px: f32) -> bool {
+    event_count > 1 && event_count as f32 * MIN_MARKER_SPACING_PX > span_px
+}
+
+/// Returns true if an event marker should register an interaction this frame.
+///
+/// Registering a widget for every marker gets expensive on busy rows, so
+/// only markers wide enough to hit and close to the pointer qualify.
+///
+/// # Arguments
+/// * `center` - Marker center `(x, y)` on screen
+/// * `radius` - Marker radius
+/// * `visible_width` - Width of the marker left after clipping
+/// * `pointer` - Pointer position, if the pointer is over the window
+pub fn marker_is_interactive(center: (f32, f32), radius: f32, visible_width: f32, pointer: Option<(f32, f32)>) -> bool {
+    let Some((px, py)) = pointer else {
+        return false;
+    };
+    let reach = radius + MARKER_POINTER_BAND_PX;
+    visible_width >= MIN_INTERACTIVE_MARKER_PX && (px - center.0).abs() <= reach && (py - center.1).abs() <= reach
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rjets::{DynTraceData, DynTraceEvent, TraceData, TraceRecord};
+
+    #[test]
+    fn test_buckets_count_and_first_index() {
+        let input = concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":100,"name":"row","record_type":"Op","id":1,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"event","clk":100,"name":"e","record_id":1,"description":""}"#, "\n",
+            r#"{"type":"event","clk":105,"name":"e","record_id":1,"description":""}"#, "\n",
+            r#"{"type":"event","clk":119,"name":"e","record_id":1,"description":""}"#, "\n",
+            r#"{"type":"event","clk":140,"name":"e","record_id":1,"description":""}"#, "\n",
+            r#"{"type":"event","clk":300,"name":"e","record_id":1,"description":""}"#, "\n",
+        );
+        let trace = DynTraceData::Jets(rjets::parse_trace_from_reader(input.as_bytes()).unwrap());
+        let record = trace.get_record(1).unwrap();
+        let events = (0..record.num_events()).filter_map(|i| record.event_at(i).map(|e| (i, e)));
+        // Clocks 100..200 on x 10..20: 20 clocks per 2-pixel column
+        let buckets = PixelBuckets::from_events(events, (100, 200), 10.0, 20.0, 2.0);
+
+        assert_eq!(buckets.buckets.len(), 5);
+        assert_eq!(buckets.buckets[0], EventBucket { count: 3, first_index: 0 });
+        assert_eq!(buckets.buckets[2], EventBucket { count: 1, first_index: 3 });
+        assert_eq!(buckets.max_count(), 3);
+        // Clock 300 is outside the span
+        assert_eq!(buckets.buckets.iter().map(|b| b.count).sum::<u32>(), 4);
+    }
+
+    #[test]
+    fn test_bucket_lookup() {
+        let buckets = PixelBuckets::from_events(std::iter::empty::<(usize, DynTraceEvent)>(), (0, 10), 100.0, 110.0, 2.0);
+        assert_eq!(buckets.bucket_at(99.0), None);
+        assert_eq!(buckets.bucket_at(103.0), Some(1));
+        assert_eq!(buckets.bucket_at(110.0), None);
+        assert_eq!(buckets.bucket_span(1), (102.0, 104.0));
+    }
+
+    #[test]
+    fn test_density_threshold() {
+        assert!(!is_too_dense(1, 1.0));
+        assert!(!is_too_dense(10, 100.0));
+        assert!(is_too_dense(100, 100.0));
+    }
+
+    #[test]
+    fn test_marker_interaction_culling() {
+        let center = (100.0, 50.0);
+        // Pointer on or just beside the marker
+        assert!(marker_is_interactive(center, 5.0, 10.0, Some((100.0, 50.0))));
+        assert!(marker_is_interactive(center, 5.0, 10.0, Some((108.0, 47.0))));
+        // Pointer out of the band, no pointer, or marker clipped to a sliver
+        assert!(!marker_is_interactive(center, 5.0, 10.0, Some((110.0, 50.0))));
+        assert!(!marker_is_interactive(center, 5.0, 10.0, Some((100.0, 60.0))));
+        assert!(!marker_is_interactive(center, 5.0, 10.0, None));
+        assert!(!marker_is_interactive(center, 5.0, 2.0, Some((100.0, 50.0))));
+    }
+}
@@ -0,0 +1,94 @@
+//! Event marker shapes.
+//!
+//! Besides color, info-level event markers can differ in shape so that e.g.
+//! fetch, execute and memory events are told apart at a glance. A reserved
+//! `marker` event attribute ("circle", "diamond", "square", "tick") sets the
+//! shape explicitly; otherwise the shape chosen for the event name in the
+//! type legend applies. Warnings and errors keep their severity shapes.
+
+use rjets::TraceEvent;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Event attribute that sets the marker shape explicitly.
+pub const MARKER_ATTR: &str = "marker";
+
+/// Shape of an info-level event marker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MarkerShape {
+    #[default]
+    Circle,
+    Diamond,
+    Square,
+    /// Vertical line across the row
+    Tick,
+}
+
+impl MarkerShape {
+    /// All shapes, in menu order.
+    pub const ALL: [MarkerShape; 4] = [MarkerShape::Circle, MarkerShape::Diamond, MarkerShape::Square, MarkerShape::Tick];
+
+    /// Parses a shape name (case-insensitive); None for anything else.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "circle" => Some(MarkerShape::Circle),
+            "diamond" => Some(MarkerShape::Diamond),
+            "square" => Some(MarkerShape::Square),
+            "tick" => Some(MarkerShape::Tick),
+            _ => None,
+        }
+    }
+
+    /// Returns the menu label.
+    pub fn label(self) -> &'static str {
+        match self {
+            MarkerShape::Circle => "● Circle",
+            MarkerShape::Diamond => "◆ Diamond",
+            MarkerShape::Square => "■ Square",
+            MarkerShape::Tick => "| Tick",
+        }
+    }
+}
+
+/// Returns the marker shape of an event: its `marker` attribute, else the
+/// shape mapped to its name, else a circle.
+///
+/// # Arguments
+/// * `event` - The event to draw
+/// * `by_name` - Shapes chosen per event name
+pub fn event_shape(event: &impl TraceEvent, by_name: &HashMap<String, MarkerShape>) -> MarkerShape {
+    if let Some(shape) = event.attr(MARKER_ATTR).and_then(|value| value.as_str().and_then(MarkerShape::parse)) {
+        return shape;
+    }
+    if by_name.is_empty() {
+        return MarkerShape::default();
+    }
+    by_name.get(&event.name()).copied().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rjets::{DynTraceData, TraceData, TraceRecord};
+
+    #[test]
+    fn test_shape_from_attribute_then_name() {
+        let input = concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"core","record_type":"Op","id":1,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"event","clk":1,"name":"fetch","record_id":1,"description":""}"#, "\n",
+            r#"{"type":"event","clk":2,"name":"fetch","record_id":1,"description":"","data":{"marker":"Tick"}}"#, "\n",
+            r#"{"type":"event","clk":3,"name":"load","record_id":1,"description":"","data":{"marker":"star"}}"#, "\n",
+        );
+        let trace = DynTraceData::Jets(rjets::parse_trace_from_reader(input.as_bytes()).unwrap());
+        let record = trace.get_record(1).unwrap();
+        let shapes: HashMap<String, MarkerShape> = [("fetch".to_string(), MarkerShape::Diamond)].into();
+
+        let shape = |index| event_shape(&record.event_at(index).unwrap(), &shapes);
+        assert_eq!(shape(0), MarkerShape::Diamond);
+        assert_eq!(shape(1), MarkerShape::Tick);
+        assert_eq!(shape(2), MarkerShape::Circle, "unknown shape names fall back to the default");
+        assert_eq!(serde_json::to_string(&MarkerShape::Square).unwrap(), r#""square""#);
+    }
+}
@@ -0,0 +1,73 @@
+//! Event visibility filtering.
+//!
+//! The event-level counterpart of the record strategies in
+//! [`crate::domain::visibility`]: decides which events of a shown record are
+//! drawn as timeline markers and listed in the details panel. Events are
+//! hidden by name (e.g. all F1/F2 fetch events) and below a minimum severity.
+
+use crate::domain::severity::{event_severity, Severity};
+use rjets::TraceEvent;
+use std::collections::BTreeSet;
+
+/// Event filter by event name and severity.
+#[derive(Debug, Clone, Copy)]
+pub struct EventTypeFilterStrategy<'s> {
+    /// Event names not shown
+    pub hidden_names: &'s BTreeSet<String>,
+    /// Events below this severity are not shown
+    pub min_severity: Severity,
+}
+
+impl<'s> EventTypeFilterStrategy<'s> {
+    /// Creates a filter hiding the given names and events below `min_severity`.
+    pub fn new(hidden_names: &'s BTreeSet<String>, min_severity: Severity) -> Self {
+        Self { hidden_names, min_severity }
+    }
+
+    /// Should the event be shown?
+    ///
+    /// Skips the name and severity lookups the filter does not need, since
+    /// this runs for every event of every shown row.
+    pub fn include_event(&self, event: &impl TraceEvent) -> bool {
+        if self.min_severity > Severity::Info && event_severity(event) < self.min_severity {
+            return false;
+        }
+        self.hidden_names.is_empty() || !self.hidden_names.contains(&event.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rjets::{DynTraceData, TraceData, TraceRecord};
+
+    #[test]
+    fn test_events_hidden_by_name_and_severity() {
+        let input = concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"insn","record_type":"Instruction","id":1,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"event","clk":1,"name":"F1","record_id":1,"description":""}"#, "\n",
+            r#"{"type":"event","clk":2,"name":"EX","record_id":1,"description":""}"#, "\n",
+            r#"{"type":"event","clk":3,"name":"ERR_TRAP","record_id":1,"description":""}"#, "\n",
+            r#"{"type":"event","clk":4,"name":"F2","record_id":1,"description":"","data":{"severity":"warning"}}"#, "\n",
+        );
+        let trace = DynTraceData::Jets(rjets::parse_trace_from_reader(input.as_bytes()).unwrap());
+        let record = trace.get_record(1).unwrap();
+        let shown = |filter: &EventTypeFilterStrategy| -> Vec<String> {
+            (0..record.num_events())
+                .filter_map(|i| record.event_at(i))
+                .filter(|event| filter.include_event(event))
+                .map(|event| event.name())
+                .collect()
+        };
+
+        let none = BTreeSet::new();
+        assert_eq!(shown(&EventTypeFilterStrategy::new(&none, Severity::Info)).len(), 4);
+
+        let fetches: BTreeSet<String> = ["F1", "F2"].map(String::from).into();
+        let filter = EventTypeFilterStrategy::new(&fetches, Severity::Info);
+        assert_eq!(shown(&filter), ["EX", "ERR_TRAP"]);
+        assert_eq!(shown(&EventTypeFilterStrategy::new(&fetches, Severity::Warning)), ["ERR_TRAP"]);
+        assert_eq!(shown(&EventTypeFilterStrategy::new(&none, Severity::Warning)), ["ERR_TRAP", "F2"]);
+    }
+}
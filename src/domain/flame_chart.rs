@@ -0,0 +1,171 @@
+//! Flame chart layout of a thread's children.
+//!
+//! The main timeline gives every record its own row, so how many of a
+//! thread's instructions were in flight at once has to be read off many rows.
+//! A flame chart packs the thread's children into as few depth rows as
+//! possible instead: each child goes on the lowest row that is free at its
+//! start clock, so the number of rows at a clock is the number of children
+//! overlapping there (issue-width utilization).
+
+use rjets::{DynTraceRecord, TraceRecord};
+use crate::domain::viewport_operations::clk_span;
+
+/// Record type whose children get a flame chart.
+pub const FLAME_CHART_RECORD_TYPE: &str = "Thread";
+
+/// One child placed in the flame chart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlameBar {
+    pub record_id: u64,
+    pub start: i64,
+    /// End clock (equal to `start` for records without an end)
+    pub end: i64,
+    /// Row of the bar, 0 at the top
+    pub depth: usize,
+    /// Duration not covered by the child's own children
+    pub self_time: i64,
+}
+
+/// Children of one record packed into depth rows.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FlameLayout {
+    /// Bars in start clock order
+    pub bars: Vec<FlameBar>,
+    /// Number of depth rows (peak number of overlapping children)
+    pub depth_count: usize,
+    /// Clock range covering all bars
+    pub extent: (i64, i64),
+    /// Sum of the bar durations
+    pub busy_time: i64,
+}
+
+impl FlameLayout {
+    /// Returns the average number of children in flight over the extent.
+    pub fn mean_occupancy(&self) -> f64 {
+        let span = clk_span(self.extent.0, self.extent.1);
+        if span <= 0.0 {
+            0.0
+        } else {
+            self.busy_time as f64 / span
+        }
+    }
+}
+
+/// Returns true if the record gets a flame chart of its children.
+pub fn has_flame_chart(record: &DynTraceRecord) -> bool {
+    record.num_children() > 0 && record.record_type() == FLAME_CHART_RECORD_TYPE
+}
+
+/// Packs spans `(record_id, start, end, self_time)` into depth rows.
+///
+/// Spans must be sorted by start clock. Zero-length spans still occupy their
+/// row for one clock so that spans starting together are stacked.
+pub fn layout_spans(spans: &[(u64, i64, i64, i64)]) -> FlameLayout {
+    let mut row_ends: Vec<i64> = Vec::new();
+    let mut layout = FlameLayout {
+        bars: Vec::with_capacity(spans.len()),
+        extent: (i64::MAX, i64::MIN),
+        ..Default::default()
+    };
+
+    for &(record_id, start, end, self_time) in spans {
+        let occupied_until = end.max(start.saturating_add(1));
+        let depth = match row_ends.iter().position(|&row_end| row_end <= start) {
+            Some(depth) => {
+                row_ends[depth] = occupied_until;
+                depth
+            }
+            None => {
+                row_ends.push(occupied_until);
+                row_ends.len() - 1
+            }
+        };
+        layout.bars.push(FlameBar { record_id, start, end, depth, self_time });
+        layout.extent = (layout.extent.0.min(start), layout.extent.1.max(end));
+        layout.busy_time = layout.busy_time.saturating_add(end.saturating_sub(start));
+    }
+
+    layout.depth_count = row_ends.len();
+    if layout.bars.is_empty() {
+        layout.extent = (0, 0);
+    }
+    layout
+}
+
+/// Lays out the children of `record` as a flame chart.
+pub fn flame_layout(record: &DynTraceRecord) -> FlameLayout {
+    let mut spans: Vec<(u64, i64, i64, i64)> = (0..record.num_children())
+        .filter_map(|i| record.child_at(i))
+        .map(|child| {
+            let start = child.clk();
+            let end = child.end_clk().unwrap_or(start).max(start);
+            let covered = (0..child.num_children())
+                .filter_map(|i| child.child_at(i))
+                .filter_map(|grandchild| Some(grandchild.end_clk()?.saturating_sub(grandchild.clk())))
+                .fold(0i64, i64::saturating_add);
+            (child.id(), start, end, end.saturating_sub(start).saturating_sub(covered).max(0))
+        })
+        .collect();
+    spans.sort_by_key(|&(_, start, _, _)| start);
+    layout_spans(&spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rjets::{DynTraceData, TraceData};
+
+    #[test]
+    fn test_overlapping_spans_stack_and_free_rows_are_reused() {
+        let layout = layout_spans(&[
+            (1, 0, 10, 10),
+            (2, 2, 6, 4),
+            (3, 4, 8, 4),
+            (4, 6, 9, 3),
+            (5, 10, 12, 2),
+            (6, 12, 12, 0),
+            (7, 12, 12, 0),
+        ]);
+        let depths: Vec<usize> = layout.bars.iter().map(|bar| bar.depth).collect();
+        assert_eq!(depths, vec![0, 1, 2, 1, 0, 0, 1]);
+        assert_eq!(layout.depth_count, 3);
+        assert_eq!(layout.extent, (0, 12));
+        assert_eq!(layout.busy_time, 23);
+        assert!((layout.mean_occupancy() - 23.0 / 12.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_empty_layout() {
+        let layout = layout_spans(&[]);
+        assert_eq!(layout.depth_count, 0);
+        assert_eq!(layout.extent, (0, 0));
+        assert_eq!(layout.mean_occupancy(), 0.0);
+    }
+
+    #[test]
+    fn test_layout_at_extreme_clocks() {
+        let input = concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":-9223372036854775808,"name":"t","record_type":"Thread","id":1,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record","clk":-9223372036854775808,"name":"a","record_type":"Op","id":2,"parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"record","clk":-9223372036854775808,"name":"a0","record_type":"Op","id":3,"parent_id":2,"description":""}"#, "\n",
+            r#"{"type":"record_end","clk":0,"record_id":3}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"a1","record_type":"Op","id":4,"parent_id":2,"description":""}"#, "\n",
+            r#"{"type":"record_end","clk":9223372036854775807,"record_id":4}"#, "\n",
+            r#"{"type":"record_end","clk":9223372036854775807,"record_id":2}"#, "\n",
+            r#"{"type":"record","clk":9223372036854775807,"name":"b","record_type":"Op","id":5,"parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"record_end","clk":9223372036854775807,"record_id":5}"#, "\n",
+            r#"{"type":"record_end","clk":9223372036854775807,"record_id":1}"#, "\n",
+        );
+        let trace = DynTraceData::Jets(rjets::parse_trace_from_reader(input.as_bytes()).unwrap());
+        let thread = trace.get_record(1).unwrap();
+        assert!(has_flame_chart(&thread));
+
+        let layout = flame_layout(&thread);
+        assert_eq!(layout.bars.len(), 2);
+        assert_eq!(layout.bars[0].self_time, 0);
+        assert_eq!(layout.extent, (i64::MIN, i64::MAX));
+        assert_eq!(layout.busy_time, i64::MAX);
+        assert!((layout.mean_occupancy() - 0.5).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,256 @@
+//! Flame graph aggregation of a subtree.
+//!
+//! Unlike the flame chart, which places every child at its clocks, a flame
+//! graph attributes time: records with the same name under the same chain of
+//! ancestor names are merged into one frame whose total is the sum of their
+//! durations. Frames are laid out as fractions of the root's width, children
+//! sorted by total (largest first) within their parent.
+//!
+//! Children of a record may overlap in time (pipelined operations), so their
+//! totals can exceed the parent's. Such children are scaled down together to
+//! fit inside the parent; the totals themselves are kept unscaled.
+
+use std::collections::HashMap;
+
+use rjets::{DynTraceData, TraceData, TraceRecord};
+
+use crate::reporting::{Report, Reportable};
+
+/// One aggregated frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlameFrame {
+    pub name: String,
+    /// Record type of the first record merged into the frame (for coloring)
+    pub record_type: String,
+    /// Row of the frame, 0 for the root
+    pub depth: usize,
+    /// Left edge as a fraction of the root's width
+    pub offset: f64,
+    /// Width as a fraction of the root's width
+    pub width: f64,
+    /// Sum of the merged records' durations
+    pub total: i64,
+    /// Part of the total not covered by child frames
+    pub self_time: i64,
+    /// Number of records merged into the frame
+    pub count: usize,
+}
+
+/// Aggregated frames of a subtree.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FlameGraph {
+    pub root_id: u64,
+    /// Frames in depth-first order; the first is the root
+    pub frames: Vec<FlameFrame>,
+    /// Number of depth rows
+    pub depth_count: usize,
+    /// Number of records aggregated
+    pub record_count: usize,
+}
+
+impl FlameGraph {
+    /// Returns the root's total duration.
+    pub fn total(&self) -> i64 {
+        self.frames.first().map_or(0, |frame| frame.total)
+    }
+}
+
+/// Frame under construction, with its children by name.
+struct Node {
+    name: String,
+    record_type: String,
+    total: i64,
+    count: usize,
+    children: HashMap<String, usize>,
+}
+
+/// Aggregates the subtree under `root_id` into a flame graph.
+///
+/// Records without an end count with zero duration.
+pub fn build_flame_graph(trace: &DynTraceData, root_id: u64) -> Option<FlameGraph> {
+    let root = trace.get_record(root_id)?;
+    let mut nodes = vec![Node {
+        name: root.name(),
+        record_type: root.record_type(),
+        total: 0,
+        count: 0,
+        children: HashMap::new(),
+    }];
+    let mut record_count = 0;
+
+    // Iterative DFS to avoid stack overflow on deep traces
+    let mut stack = vec![(root_id, 0usize)];
+    while let Some((id, node_index)) = stack.pop() {
+        let Some(record) = trace.get_record(id) else { continue };
+        let node = &mut nodes[node_index];
+        node.total = node.total.saturating_add(record.duration().unwrap_or(0).max(0));
+        node.count += 1;
+        record_count += 1;
+
+        for i in 0..record.num_children() {
+            let Some(child) = record.child_at(i) else { continue };
+            let name = child.name();
+            let child_index = match nodes[node_index].children.get(&name) {
+                Some(&index) => index,
+                None => {
+                    let index = nodes.len();
+                    nodes[node_index].children.insert(name.clone(), index);
+                    nodes.push(Node {
+                        name,
+                        record_type: child.record_type(),
+                        total: 0,
+                        count: 0,
+                        children: HashMap::new(),
+                    });
+                    index
+                }
+            };
+            stack.push((child.id(), child_index));
+        }
+    }
+
+    let mut graph = FlameGraph {
+        root_id,
+        record_count,
+        ..Default::default()
+    };
+    let mut layout = vec![(0usize, 0usize, 0.0f64, 1.0f64)];
+    while let Some((node_index, depth, offset, width)) = layout.pop() {
+        let node = &nodes[node_index];
+        let mut children: Vec<&Node> = node.children.values().map(|&index| &nodes[index]).collect();
+        children.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.name.cmp(&b.name)));
+        let children_total = children.iter().fold(0i64, |sum, child| sum.saturating_add(child.total));
+
+        graph.depth_count = graph.depth_count.max(depth + 1);
+        graph.frames.push(FlameFrame {
+            name: node.name.clone(),
+            record_type: node.record_type.clone(),
+            depth,
+            offset,
+            width,
+            total: node.total,
+            self_time: node.total.saturating_sub(children_total).max(0),
+            count: node.count,
+        });
+
+        // Children share the parent's width, squeezed if they overlap in time
+        let scale = node.total.max(children_total);
+        let mut child_offset = offset;
+        let mut placed = Vec::with_capacity(children.len());
+        for child in children {
+            let child_width = if scale > 0 { width * child.total as f64 / scale as f64 } else { 0.0 };
+            placed.push((node.children[&child.name], depth + 1, child_offset, child_width));
+            child_offset += child_width;
+        }
+        // Reversed so the largest child is laid out (and listed) first
+        layout.extend(placed.into_iter().rev());
+    }
+
+    Some(graph)
+}
+
+impl Reportable for FlameGraph {
+    fn to_report(&self) -> Report {
+        let mut report = Report::new("Flame Graph");
+        let root_name = self.frames.first().map_or("", |frame| frame.name.as_str());
+        report.section("Summary").key_values([
+            ("Root", root_name.to_string()),
+            ("Total duration", self.total().to_string()),
+            ("Records", self.record_count.to_string()),
+            ("Frames", self.frames.len().to_string()),
+            ("Depth", self.depth_count.to_string()),
+        ]);
+
+        // Frames are depth-first, so each path extends the one at the depth above
+        let mut path: Vec<&str> = Vec::with_capacity(self.depth_count);
+        let rows = self
+            .frames
+            .iter()
+            .map(|frame| {
+                path.truncate(frame.depth);
+                path.push(&frame.name);
+                let share = if self.total() > 0 { 100.0 * frame.total as f64 / self.total() as f64 } else { 0.0 };
+                vec![
+                    path.join(" → "),
+                    frame.count.to_string(),
+                    frame.total.to_string(),
+                    frame.self_time.to_string(),
+                    format!("{:.1}%", share),
+                ]
+            })
+            .collect();
+        report.section("Frames").table(&["Frame Path", "Count", "Total", "Self", "Share of Root"], rows);
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reporting::report::ReportBlock;
+
+    #[test]
+    fn test_frames_merge_by_name_path() {
+        let input = concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"thread","record_type":"Thread","id":1,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"add","record_type":"Op","id":2,"parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"record_end","clk":30,"record_id":2}"#, "\n",
+            r#"{"type":"record","clk":30,"name":"load","record_type":"Op","id":3,"parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"record","clk":30,"name":"tlb","record_type":"Stage","id":4,"parent_id":3,"description":""}"#, "\n",
+            r#"{"type":"record_end","clk":40,"record_id":4}"#, "\n",
+            r#"{"type":"record_end","clk":70,"record_id":3}"#, "\n",
+            r#"{"type":"record","clk":70,"name":"add","record_type":"Op","id":5,"parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"record_end","clk":100,"record_id":5}"#, "\n",
+            r#"{"type":"record_end","clk":100,"record_id":1}"#, "\n",
+        );
+        let trace = DynTraceData::Jets(rjets::parse_trace_from_reader(input.as_bytes()).unwrap());
+        let graph = build_flame_graph(&trace, trace.roots()[0]).unwrap();
+
+        assert_eq!(graph.record_count, 5);
+        assert_eq!(graph.depth_count, 3);
+        assert_eq!(graph.total(), 100);
+        let summary: Vec<_> = graph
+            .frames
+            .iter()
+            .map(|frame| (frame.name.as_str(), frame.depth, frame.total, frame.self_time, frame.count))
+            .collect();
+        assert_eq!(
+            summary,
+            [("thread", 0, 100, 0, 1), ("add", 1, 60, 60, 2), ("load", 1, 40, 30, 1), ("tlb", 2, 10, 10, 1)]
+        );
+        let frame = |name: &str| graph.frames.iter().find(|frame| frame.name == name).unwrap();
+        assert_eq!((frame("add").offset, frame("add").width), (0.0, 0.6));
+        assert_eq!((frame("load").offset, frame("load").width), (0.6, 0.4));
+        assert!((frame("tlb").offset - 0.6).abs() < 1e-9 && (frame("tlb").width - 0.1).abs() < 1e-9);
+
+        assert!(build_flame_graph(&trace, 999).is_none());
+
+        let report = graph.to_report();
+        let Some(ReportBlock::Table { rows, .. }) = report.sections[1].blocks.first() else {
+            panic!("frames table missing");
+        };
+        let paths: Vec<&str> = rows.iter().map(|row| row[0].as_str()).collect();
+        assert_eq!(paths, ["thread", "thread → add", "thread → load", "thread → load → tlb"]);
+        assert_eq!(rows[3][1..], ["1", "10", "10", "10.0%"]);
+    }
+
+    #[test]
+    fn test_overlapping_children_fit_inside_parent() {
+        let input = concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"thread","record_type":"Thread","id":1,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"a","record_type":"Op","id":2,"parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"b","record_type":"Op","id":3,"parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"record_end","clk":10,"record_id":2}"#, "\n",
+            r#"{"type":"record_end","clk":10,"record_id":3}"#, "\n",
+            r#"{"type":"record_end","clk":10,"record_id":1}"#, "\n",
+        );
+        let trace = DynTraceData::Jets(rjets::parse_trace_from_reader(input.as_bytes()).unwrap());
+        let graph = build_flame_graph(&trace, trace.roots()[0]).unwrap();
+
+        let widths: Vec<_> = graph.frames.iter().map(|frame| (frame.name.as_str(), frame.total, frame.width)).collect();
+        assert_eq!(widths, [("thread", 10, 1.0), ("a", 10, 0.5), ("b", 10, 0.5)]);
+        assert_eq!(graph.frames[0].self_time, 0);
+    }
+}
@@ -0,0 +1,205 @@
+//! Finding the filters that hide a record.
+//!
+//! Filter changes keep the selection, even when the selected record no
+//! longer has a row. The filter chip row then offers to reveal it, which
+//! relaxes only the filters listed by [`hiding_filters`]: since a row must
+//! pass every filter, each of them has to give way, and none of the others
+//! needs to. The checks mirror the visibility strategies used for the row
+//! list (see [`crate::domain::visibility`]).
+
+use std::collections::HashSet;
+
+use rjets::{DynTraceData, TraceData, TraceRecord};
+
+use crate::domain::column_filters::{ColumnFilters, TreeColumn};
+use crate::domain::visibility::{
+    ClockStride, ClockStrideFilterStrategy, ColumnFilterStrategy, EmptyParentFilterStrategy, ViewportFilterStrategy,
+    VisibilityStrategy,
+};
+
+/// The row filters in effect.
+#[derive(Clone, Copy)]
+pub struct RowFilters<'s> {
+    pub hidden_roots: &'s HashSet<u64>,
+    pub hidden_types: &'s HashSet<String>,
+    /// Records the row list is limited to (empty = no limit)
+    pub pinned_rows: &'s HashSet<u64>,
+    pub clock_stride: Option<ClockStride>,
+    pub column_filters: &'s ColumnFilters,
+    /// Main window range while the viewport filter is on
+    pub viewport_range: Option<(i64, i64)>,
+    pub hide_empty_parents: bool,
+}
+
+/// A filter hiding a record, and what revealing the record does to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HidingFilter {
+    /// The record's root is hidden in the root selector (show it)
+    HiddenRoot(u64),
+    /// The record or an ancestor has a hidden type (show the type)
+    HiddenType(String),
+    /// The record starts outside the viewport (pan to it)
+    Viewport,
+    /// The record is a parent without a visible leaf (show empty parents)
+    EmptyParents,
+    /// The record starts on another clock phase (turn the stride off)
+    ClockStride,
+    /// The record fails these column filters (clear them)
+    Columns(Vec<TreeColumn>),
+    /// Rows are pinned and the record's is not (pin it too)
+    PinnedRows,
+}
+
+impl HidingFilter {
+    /// Describes how revealing the record relaxes the filter.
+    pub fn relaxation(&self) -> String {
+        match self {
+            HidingFilter::HiddenRoot(_) => "show its root".to_string(),
+            HidingFilter::HiddenType(record_type) => format!("show type '{}'", record_type),
+            HidingFilter::Viewport => "pan the viewport to it".to_string(),
+            HidingFilter::EmptyParents => "show empty parents".to_string(),
+            HidingFilter::ClockStride => "turn the clock stride off".to_string(),
+            HidingFilter::Columns(columns) => {
+                let titles: Vec<&str> = columns.iter().map(|column| column.title()).collect();
+                format!("clear the {} column filter", titles.join(", "))
+            }
+            HidingFilter::PinnedRows => "pin its row".to_string(),
+        }
+    }
+}
+
+/// Returns the filters that hide a record's row, in the order the filter
+/// chips list them; empty if it has a row when its ancestors are expanded.
+///
+/// # Arguments
+/// * `trace` - The trace data
+/// * `record_id` - The record to check
+/// * `filters` - The row filters in effect
+pub fn hiding_filters(trace: &DynTraceData, record_id: u64, filters: &RowFilters) -> Vec<HidingFilter> {
+    let Some(record) = trace.get_record(record_id) else {
+        return Vec::new();
+    };
+    // The record and its ancestors, root last
+    let mut chain = vec![record.clone()];
+    while let Some(parent) = chain.last().and_then(|r| r.parent_id()).and_then(|id| trace.get_record(id)) {
+        chain.push(parent);
+    }
+    let depth = chain.len() - 1;
+    let is_leaf = record.num_children() == 0;
+    let mut hiding = Vec::new();
+
+    if let Some((start, end)) = filters.viewport_range {
+        let outside = if is_leaf {
+            !(start..=end).contains(&record.clk())
+        } else {
+            // Subtrees of parents starting after the viewport are skipped
+            chain[1..].iter().any(|ancestor| ancestor.clk() > end)
+        };
+        if outside {
+            hiding.push(HidingFilter::Viewport);
+        } else if !is_leaf && filters.hide_empty_parents {
+            let strategy = EmptyParentFilterStrategy::new(ColumnFilterStrategy {
+                base: ClockStrideFilterStrategy {
+                    base: ViewportFilterStrategy { start, end },
+                    stride: filters.clock_stride.unwrap_or(ClockStride { stride: 1, offset: 0 }),
+                },
+                filters: filters.column_filters,
+            });
+            if !strategy.include_parent(&record, depth) {
+                hiding.push(HidingFilter::EmptyParents);
+            }
+        }
+    }
+    if is_leaf && filters.clock_stride.is_some_and(|stride| !stride.matches(record.clk())) {
+        hiding.push(HidingFilter::ClockStride);
+    }
+    if is_leaf {
+        let failing: Vec<TreeColumn> = TreeColumn::ALL
+            .into_iter()
+            .filter(|&column| !filters.column_filters.column_matches(column, &record))
+            .collect();
+        if !failing.is_empty() {
+            hiding.push(HidingFilter::Columns(failing));
+        }
+    }
+    if !filters.pinned_rows.is_empty() && !filters.pinned_rows.contains(&record_id) {
+        hiding.push(HidingFilter::PinnedRows);
+    }
+
+    // Root first, each hidden type once
+    for record_type in chain.iter().rev().map(|r| r.record_type()) {
+        let filter = HidingFilter::HiddenType(record_type.clone());
+        if filters.hidden_types.contains(&record_type) && !hiding.contains(&filter) {
+            hiding.push(filter);
+        }
+    }
+
+    let root_id = chain.last().map_or(record_id, |root| root.id());
+    if filters.hidden_roots.contains(&root_id) {
+        hiding.push(HidingFilter::HiddenRoot(root_id));
+    }
+    hiding
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::column_filters::ColumnFilter;
+
+    #[test]
+    fn test_only_filters_hiding_the_record_are_listed() {
+        let input = concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"core","record_type":"Core","id":1,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record","clk":5,"name":"t","record_type":"Thread","id":2,"parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"record","clk":7,"name":"ld","record_type":"Op","id":3,"parent_id":2,"description":""}"#, "\n",
+            r#"{"type":"record","clk":20,"name":"st","record_type":"Op","id":4,"parent_id":2,"description":""}"#, "\n",
+        );
+        let trace = DynTraceData::Jets(rjets::parse_trace_from_reader(input.as_bytes()).unwrap());
+        let empty_ids = HashSet::new();
+        let empty_types = HashSet::new();
+        let no_columns = ColumnFilters::default();
+        let none = RowFilters {
+            hidden_roots: &empty_ids,
+            hidden_types: &empty_types,
+            pinned_rows: &empty_ids,
+            clock_stride: None,
+            column_filters: &no_columns,
+            viewport_range: None,
+            hide_empty_parents: false,
+        };
+        assert!(hiding_filters(&trace, 3, &none).is_empty());
+
+        let hidden_types: HashSet<String> = ["Thread".to_string()].into();
+        let pinned: HashSet<u64> = [4].into();
+        let mut columns = ColumnFilters::default();
+        columns.set(TreeColumn::Name, ColumnFilter::Exclude(["ld".to_string()].into()));
+        columns.set(TreeColumn::Id, ColumnFilter::Range { min: Some(4), max: None });
+        let filters = RowFilters {
+            hidden_types: &hidden_types,
+            pinned_rows: &pinned,
+            clock_stride: Some(ClockStride { stride: 2, offset: 0 }),
+            column_filters: &columns,
+            viewport_range: Some((10, 30)),
+            ..none
+        };
+        assert_eq!(
+            hiding_filters(&trace, 3, &filters),
+            vec![
+                HidingFilter::Viewport,
+                HidingFilter::ClockStride,
+                HidingFilter::Columns(vec![TreeColumn::Name, TreeColumn::Id]),
+                HidingFilter::PinnedRows,
+                HidingFilter::HiddenType("Thread".to_string()),
+            ]
+        );
+        // The store passes everything but the hidden Thread type above it
+        assert_eq!(hiding_filters(&trace, 4, &filters), vec![HidingFilter::HiddenType("Thread".to_string())]);
+
+        // A parent whose only leaves are out of view is empty
+        let filters = RowFilters { viewport_range: Some((10, 15)), hide_empty_parents: true, ..none };
+        assert_eq!(hiding_filters(&trace, 2, &filters), vec![HidingFilter::EmptyParents]);
+        let filters = RowFilters { viewport_range: Some((10, 25)), hide_empty_parents: true, ..none };
+        assert!(hiding_filters(&trace, 2, &filters).is_empty());
+    }
+}
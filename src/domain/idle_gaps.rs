@@ -0,0 +1,151 @@
+//! Idle gaps under a subtree.
+//!
+//! Only leaf records do work; their parents merely group them. An idle gap is
+//! a clock interval within the subtree root's span where no leaf of the
+//! subtree is active, such as a pipeline bubble or a driver stall. Leaf spans
+//! are merged and the holes between them (and between them and the root's
+//! start and end) are ranked by length.
+
+use crate::reporting::{Report, Reportable};
+use crate::utils::format_clock;
+use rjets::{DynTraceData, TraceData, TraceRecord};
+
+/// A clock interval without active leaf records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdleGap {
+    pub start_clk: i64,
+    pub end_clk: i64,
+}
+
+impl IdleGap {
+    /// Returns the gap length in clocks.
+    pub fn duration(&self) -> i64 {
+        self.end_clk - self.start_clk
+    }
+}
+
+/// Idle gaps found under one subtree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdleGapSearch {
+    /// Name of the searched subtree root
+    pub root_name: String,
+    /// Gaps found, longest first
+    pub gaps: Vec<IdleGap>,
+}
+
+impl Reportable for IdleGapSearch {
+    fn to_report(&self) -> Report {
+        let mut report = Report::new("Idle Gaps");
+        let total: i64 = self.gaps.iter().map(IdleGap::duration).sum();
+        report.section("Summary").key_values([
+            ("Subtree", self.root_name.clone()),
+            ("Gaps listed", self.gaps.len().to_string()),
+            ("Listed idle time", format_clock(total)),
+        ]);
+
+        let section = report.section("Longest Gaps");
+        if self.gaps.is_empty() {
+            section.paragraph("No idle gaps under this subtree.");
+        } else {
+            let rows = self
+                .gaps
+                .iter()
+                .map(|gap| vec![format_clock(gap.duration()), format_clock(gap.start_clk), format_clock(gap.end_clk)])
+                .collect();
+            section.table(&["Duration", "Start", "End"], rows);
+        }
+        report
+    }
+}
+
+/// Finds the longest idle gaps under a record, longest first.
+///
+/// Leaves without an end clock are treated as instants. A root without an
+/// end clock ends at its last leaf, so no trailing gap is reported for it.
+///
+/// # Arguments
+/// * `trace` - The trace to search
+/// * `root_id` - Subtree root
+/// * `limit` - Most gaps to return
+pub fn find_idle_gaps(trace: &DynTraceData, root_id: u64, limit: usize) -> Vec<IdleGap> {
+    let Some(root) = trace.get_record(root_id) else {
+        return Vec::new();
+    };
+    // A leaf root has nothing below it that could be idle
+    if root.num_children() == 0 {
+        return Vec::new();
+    }
+    let (root_start, root_end) = (root.clk(), root.end_clk());
+
+    let mut spans = Vec::new();
+    let mut stack = vec![root];
+    while let Some(record) = stack.pop() {
+        let num_children = record.num_children();
+        if num_children == 0 {
+            spans.push((record.clk(), record.end_clk().unwrap_or(record.clk())));
+        } else {
+            stack.extend((0..num_children).filter_map(|i| record.child_at(i)));
+        }
+    }
+    spans.sort_unstable();
+
+    let mut gaps = Vec::new();
+    let mut busy_until = root_start;
+    for (start, end) in spans {
+        if start > busy_until {
+            gaps.push(IdleGap { start_clk: busy_until, end_clk: start });
+        }
+        busy_until = busy_until.max(end);
+    }
+    if let Some(root_end) = root_end {
+        if root_end > busy_until {
+            gaps.push(IdleGap { start_clk: busy_until, end_clk: root_end });
+        }
+    }
+
+    gaps.sort_by(|a, b| b.duration().cmp(&a.duration()).then(a.start_clk.cmp(&b.start_clk)));
+    gaps.truncate(limit);
+    gaps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reporting::report::ReportBlock;
+
+    #[test]
+    fn test_gaps_between_merged_leaves() {
+        let input = concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"root","record_type":"T","id":1,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record","clk":10,"name":"group","record_type":"G","id":2,"parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"record","clk":10,"name":"a","record_type":"Op","id":3,"parent_id":2,"description":""}"#, "\n",
+            r#"{"type":"record","clk":15,"name":"b","record_type":"Op","id":4,"parent_id":2,"description":""}"#, "\n",
+            r#"{"type":"record","clk":40,"name":"c","record_type":"Op","id":5,"parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"record_end","clk":20,"record_id":3}"#, "\n",
+            r#"{"type":"record_end","clk":25,"record_id":4}"#, "\n",
+            r#"{"type":"record_end","clk":45,"record_id":5}"#, "\n",
+            r#"{"type":"record_end","clk":45,"record_id":2}"#, "\n",
+            r#"{"type":"record_end","clk":100,"record_id":1}"#, "\n",
+        );
+        let trace = DynTraceData::Jets(rjets::parse_trace_from_reader(input.as_bytes()).unwrap());
+
+        // Leaves cover 10..25 and 40..45; the busy group record does not count
+        let gaps = find_idle_gaps(&trace, 1, 10);
+        let spans: Vec<_> = gaps.iter().map(|g| (g.start_clk, g.end_clk)).collect();
+        assert_eq!(spans, vec![(45, 100), (25, 40), (0, 10)]);
+        assert_eq!(find_idle_gaps(&trace, 1, 1).len(), 1);
+        assert!(find_idle_gaps(&trace, 3, 10).is_empty());
+
+        let report = IdleGapSearch { root_name: "root".to_string(), gaps }.to_report();
+        assert_eq!(report.sections[0].blocks[0], ReportBlock::KeyValues(vec![
+            ("Subtree".to_string(), "root".to_string()),
+            ("Gaps listed".to_string(), "3".to_string()),
+            ("Listed idle time".to_string(), "80".to_string()),
+        ]));
+        let ReportBlock::Table { rows, .. } = &report.sections[1].blocks[0] else {
+            panic!("gap table missing");
+        };
+        assert_eq!(rows[0], ["55", "45", "100"]);
+    }
+}
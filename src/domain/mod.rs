@@ -3,10 +3,66 @@
 //! This module contains core business logic:
 //! - Tree operations (hierarchy traversal, node queries, size calculations)
 //! - Viewport operations (coordinate transformations, clock-to-pixel conversions)
-//! - Visibility strategies (policy-driven tree traversal filtering)
+//! - Visibility strategies (policy-driven tree traversal filtering, re-exported from the library)
 //! - Sorting (child ordering independent of backend)
+//! - Statistics (record/event summaries over the trace or a clock range)
+//! - Swimlanes (attribute-keyed row grouping independent of the hierarchy)
+//! - Event density (pixel bucketing of events for crowded rows)
+//! - Scroll heat (row positions of interesting records for the scrollbar overview)
+//! - Flame chart (a thread's children packed into overlap depth rows)
+//! - Severity (event levels from the severity attribute or event name)
+//! - Problem roll-up (error/warning events summarized per collapsed subtree)
+//! - Counter track (step-line geometry for counter records)
+//! - Record identity (re-finding records after a trace reload)
+//! - Idle gaps (intervals without active leaf records under a subtree)
+//! - Aggregation (per-bucket counts and sums over a clock range, re-exported from the library)
+//! - Record navigation (previous/next sibling, same type or same name)
+//! - Name aliases (regex rewrites of displayed record names)
+//! - Type hints (per-record_type display defaults from the trace header)
+//! - Column filters (value lists and ranges from the tree header popovers, re-exported from the library)
+//! - Column stats (min/mean/max of numeric columns over the tree rows)
+//! - Event markers (marker shapes per event name or `marker` attribute)
+//! - Number formats (decimal, hex or SI display of the numeric columns)
+//! - Trace health (orphans, clock anomalies and parser warnings)
+//! - Type hierarchy (record counts and durations folded by record type chain)
+//! - Record links (attribute values referencing other records)
+//! - Record comparison (side-by-side fields of the multi-selection)
+//! - Cursor snap (nearest event of the hovered timeline row)
+//! - Flame graph (subtree durations aggregated by record name path)
+//! - Dependencies (arrows between records linked by `depends_on`)
+//! - Hidden selection (the filters hiding a record, relaxed to reveal it)
+//! - Event visibility (events hidden by name or severity in the timeline and details)
+//! - Duration share (record durations as a percentage of the parent and the trace)
 
 pub mod tree_operations;
 pub mod viewport_operations;
-pub mod visibility;
+pub use rjets::visibility;
 pub mod sorting;
+pub mod statistics;
+pub mod swimlanes;
+pub mod event_density;
+pub mod scroll_heat;
+pub mod flame_chart;
+pub mod severity;
+pub mod problem_rollup;
+pub mod counter_track;
+pub mod record_identity;
+pub mod idle_gaps;
+pub use rjets::aggregation;
+pub mod record_navigation;
+pub mod name_aliases;
+pub mod type_hints;
+pub use rjets::column_filters;
+pub mod column_stats;
+pub mod event_markers;
+pub mod number_format;
+pub mod trace_health;
+pub mod type_hierarchy;
+pub mod record_links;
+pub mod record_comparison;
+pub mod cursor_snap;
+pub mod flame_graph;
+pub mod dependencies;
+pub mod hidden_selection;
+pub mod event_visibility;
+pub mod duration_share;
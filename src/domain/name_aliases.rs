@@ -0,0 +1,158 @@
+//! Display aliases for record names.
+//!
+//! An alias rule is a regular expression and a replacement (`$1`, `${name}`
+//! refer to capture groups), e.g. `0x[0-9a-f]{8,}` → `PC` to hide long
+//! program counters. Rules rewrite names in order, each replacing every
+//! match. Only what is drawn changes: sorting, search, navigation and the
+//! trace itself keep using the original names.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// One user-defined rewrite of record names.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AliasRule {
+    /// Regular expression matched against the name
+    pub pattern: String,
+    /// Replacement text for each match
+    pub replacement: String,
+    /// Disabled rules are kept but not applied
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl Default for AliasRule {
+    fn default() -> Self {
+        Self {
+            pattern: String::new(),
+            replacement: String::new(),
+            enabled: true,
+        }
+    }
+}
+
+/// Ordered alias rules together with their compiled expressions.
+///
+/// Serializes as the list of rules; expressions are compiled on
+/// construction, and rules that fail to compile are skipped.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "Vec<AliasRule>", into = "Vec<AliasRule>")]
+pub struct NameAliases {
+    rules: Vec<AliasRule>,
+    /// Compiled pattern of each rule (an error message if it is invalid)
+    compiled: Vec<Result<Regex, String>>,
+}
+
+impl NameAliases {
+    /// Compiles a list of rules.
+    pub fn new(rules: Vec<AliasRule>) -> Self {
+        let compiled = rules
+            .iter()
+            .map(|rule| Regex::new(&rule.pattern).map_err(|e| e.to_string()))
+            .collect();
+        Self { rules, compiled }
+    }
+
+    /// Returns the rules in application order.
+    pub fn rules(&self) -> &[AliasRule] {
+        &self.rules
+    }
+
+    /// Returns why the rule at `index` cannot be applied, if it cannot.
+    pub fn rule_error(&self, index: usize) -> Option<&str> {
+        match self.compiled.get(index)? {
+            Err(message) => Some(message),
+            Ok(_) if self.rules[index].pattern.is_empty() => Some("empty pattern"),
+            Ok(_) => None,
+        }
+    }
+
+    /// Returns true if no rule would change any name.
+    pub fn is_empty(&self) -> bool {
+        self.active().next().is_none()
+    }
+
+    /// Returns the name to display for `name`.
+    pub fn apply<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        let mut result = Cow::Borrowed(name);
+        for (regex, replacement) in self.active() {
+            let rewritten = match regex.replace_all(&result, replacement.as_str()) {
+                Cow::Owned(rewritten) => Some(rewritten),
+                Cow::Borrowed(_) => None,
+            };
+            if let Some(rewritten) = rewritten {
+                result = Cow::Owned(rewritten);
+            }
+        }
+        result
+    }
+
+    /// Enabled rules with a valid, non-empty pattern.
+    fn active(&self) -> impl Iterator<Item = (&Regex, &String)> {
+        self.rules.iter().zip(&self.compiled).filter_map(|(rule, compiled)| match compiled {
+            Ok(regex) if rule.enabled && !rule.pattern.is_empty() => Some((regex, &rule.replacement)),
+            _ => None,
+        })
+    }
+}
+
+impl PartialEq for NameAliases {
+    fn eq(&self, other: &Self) -> bool {
+        self.rules == other.rules
+    }
+}
+
+impl From<Vec<AliasRule>> for NameAliases {
+    fn from(rules: Vec<AliasRule>) -> Self {
+        Self::new(rules)
+    }
+}
+
+impl From<NameAliases> for Vec<AliasRule> {
+    fn from(aliases: NameAliases) -> Self {
+        aliases.rules
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str, replacement: &str) -> AliasRule {
+        AliasRule {
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_rules_apply_in_order_and_skip_invalid() {
+        let mut rules = vec![
+            rule("0x[0-9a-f]{8,}", "PC"),
+            rule("(", "never"),
+            rule(r"^load_(\w+)", "ld.$1"),
+            rule("PC", "pc"),
+        ];
+        rules[3].enabled = false;
+        let aliases = NameAliases::new(rules);
+
+        assert_eq!(aliases.apply("load_u32 @ 0x00000000deadbeef"), "ld.u32 @ PC");
+        assert!(matches!(aliases.apply("store"), Cow::Borrowed("store")));
+        assert!(aliases.rule_error(1).is_some());
+        assert!(aliases.rule_error(0).is_none());
+        assert!(!aliases.is_empty());
+
+        // Round-trips as the plain rule list
+        let json = serde_json::to_string(&aliases).unwrap();
+        assert!(json.starts_with('['));
+        let restored: NameAliases = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, aliases);
+        assert_eq!(restored.apply("load_u8"), "ld.u8");
+    }
+}
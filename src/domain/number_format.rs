@@ -0,0 +1,143 @@
+//! Numeric display formats of the tree columns.
+//!
+//! Instruction names often carry PCs in hex, so IDs and clocks can be shown
+//! in hex too, or with SI prefixes for large cycle counts. The format chosen
+//! per column in the settings dialog applies wherever that value is shown:
+//! tree rows, the details panel, record tooltips and exported reports.
+
+use crate::domain::column_filters::TreeColumn;
+use crate::utils::format_clock;
+use rjets::ExternalId;
+use serde::{Deserialize, Serialize};
+
+/// How a numeric value is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NumberFormat {
+    #[default]
+    Decimal,
+    /// `0x`-prefixed lowercase hex
+    Hex,
+    /// Three significant digits with a k/M/G/T/P/E prefix
+    Si,
+}
+
+impl NumberFormat {
+    /// All formats, in menu order.
+    pub const ALL: [NumberFormat; 3] = [NumberFormat::Decimal, NumberFormat::Hex, NumberFormat::Si];
+
+    /// Returns the menu label.
+    pub fn label(self) -> &'static str {
+        match self {
+            NumberFormat::Decimal => "Decimal",
+            NumberFormat::Hex => "Hex",
+            NumberFormat::Si => "SI units",
+        }
+    }
+
+    /// Writes a value in this format (decimal without separators).
+    pub fn format(self, value: impl Into<i128>) -> String {
+        let value = value.into();
+        match self {
+            NumberFormat::Decimal => value.to_string(),
+            NumberFormat::Hex if value < 0 => format!("-0x{:x}", value.unsigned_abs()),
+            NumberFormat::Hex => format!("0x{:x}", value),
+            NumberFormat::Si => format_si(value),
+        }
+    }
+
+    /// Like [`NumberFormat::format`], but decimal values get thousands
+    /// separators (for tooltips and reports).
+    pub fn format_readable(self, value: i64) -> String {
+        match self {
+            NumberFormat::Decimal => format_clock(value),
+            _ => self.format(value),
+        }
+    }
+}
+
+fn format_si(value: i128) -> String {
+    const PREFIXES: [&str; 6] = ["k", "M", "G", "T", "P", "E"];
+    if value.abs() < 1000 {
+        return value.to_string();
+    }
+    let mut scaled = value as f64 / 1000.0;
+    let mut prefix = 0;
+    // Move up a prefix also when rounding would print 1000
+    while scaled.abs() >= 999.5 && prefix + 1 < PREFIXES.len() {
+        scaled /= 1000.0;
+        prefix += 1;
+    }
+    let digits = match scaled.abs() {
+        x if x < 9.995 => 2,
+        x if x < 99.95 => 1,
+        _ => 0,
+    };
+    format!("{:.*}{}", digits, scaled, PREFIXES[prefix])
+}
+
+/// The format of each numeric tree column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColumnFormats {
+    pub start_clock: NumberFormat,
+    pub duration: NumberFormat,
+    pub id: NumberFormat,
+}
+
+impl ColumnFormats {
+    /// Returns the format of a column (text columns are always decimal).
+    pub fn get(&self, column: TreeColumn) -> NumberFormat {
+        match column {
+            TreeColumn::StartClock => self.start_clock,
+            TreeColumn::Duration => self.duration,
+            TreeColumn::Id => self.id,
+            TreeColumn::Name | TreeColumn::Description => NumberFormat::Decimal,
+        }
+    }
+
+    /// Returns the format of a numeric column for editing.
+    pub fn get_mut(&mut self, column: TreeColumn) -> Option<&mut NumberFormat> {
+        match column {
+            TreeColumn::StartClock => Some(&mut self.start_clock),
+            TreeColumn::Duration => Some(&mut self.duration),
+            TreeColumn::Id => Some(&mut self.id),
+            TreeColumn::Name | TreeColumn::Description => None,
+        }
+    }
+
+    /// Writes a record ID; string IDs are shown as they are.
+    pub fn format_id(&self, id: &ExternalId) -> String {
+        match id {
+            ExternalId::Int(id) => self.id.format(*id),
+            ExternalId::Str(_) => id.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formats() {
+        assert_eq!(NumberFormat::Decimal.format(1234567), "1234567");
+        assert_eq!(NumberFormat::Decimal.format_readable(1234567), "1,234,567");
+        assert_eq!(NumberFormat::Hex.format(0x4005d0u64), "0x4005d0");
+        assert_eq!(NumberFormat::Hex.format(-255), "-0xff");
+        assert_eq!(NumberFormat::Hex.format(u64::MAX), "0xffffffffffffffff");
+
+        let si = |value: i64| NumberFormat::Si.format(value);
+        assert_eq!(si(999), "999");
+        assert_eq!(si(1234), "1.23k");
+        assert_eq!(si(45_600), "45.6k");
+        assert_eq!(si(999_999), "1.00M");
+        assert_eq!(si(-2_500_000_000), "-2.50G");
+
+        let formats = ColumnFormats { id: NumberFormat::Hex, ..Default::default() };
+        assert_eq!(formats.format_id(&ExternalId::Int(31)), "0x1f");
+        assert_eq!(formats.format_id(&ExternalId::from("op-7")), "op-7");
+        assert_eq!(formats.get(TreeColumn::Name), NumberFormat::Decimal);
+        assert_eq!(serde_json::to_string(&NumberFormat::Si).unwrap(), r#""si""#);
+    }
+}
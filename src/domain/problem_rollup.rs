@@ -0,0 +1,99 @@
+//! Roll-up of error and warning events to ancestor records.
+//!
+//! A problem event deep inside a collapsed subtree is invisible in the tree
+//! and usually too small to spot on the timeline. This module summarizes the
+//! problem events below a record (worst level, count, first occurrence) so
+//! collapsed rows can carry a badge. Summaries are computed on first request
+//! and memoized per record, so each subtree is walked at most once per trace.
+
+use rjets::{DynTraceRecord, TraceEvent, TraceRecord};
+use std::collections::HashMap;
+
+use crate::domain::severity::{event_severity, Severity};
+
+/// Problem events found below a record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubtreeProblems {
+    /// Worst severity among the events (Warning or Error)
+    pub level: Severity,
+    /// Number of problem events
+    pub count: usize,
+    /// First problem event in tree order as (record ID, event clock)
+    pub first: (u64, i64),
+}
+
+impl SubtreeProblems {
+    /// Combines two summaries; `earlier` comes first in tree order.
+    fn merge(earlier: Option<Self>, later: Option<Self>) -> Option<Self> {
+        match (earlier, later) {
+            (Some(a), Some(b)) => Some(Self {
+                level: a.level.max(b.level),
+                count: a.count + b.count,
+                first: a.first,
+            }),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+/// Memoized subtree summaries, keyed by record ID (None = no problems below).
+pub type ProblemRollupCache = HashMap<u64, Option<SubtreeProblems>>;
+
+/// Summarizes the problem events of a record itself.
+fn own_problems(record: &DynTraceRecord) -> Option<SubtreeProblems> {
+    (0..record.num_events())
+        .filter_map(|i| record.event_at(i))
+        .map(|event| (event_severity(&event), event.clk()))
+        .filter(|(level, _)| level.is_problem())
+        .fold(None, |acc, (level, clk)| {
+            SubtreeProblems::merge(acc, Some(SubtreeProblems { level, count: 1, first: (record.id(), clk) }))
+        })
+}
+
+/// Summarizes the problem events of all descendants of `record` (not its own).
+pub fn subtree_problems(record: &DynTraceRecord, cache: &mut ProblemRollupCache) -> Option<SubtreeProblems> {
+    if let Some(&cached) = cache.get(&record.id()) {
+        return cached;
+    }
+
+    let mut summary = None;
+    for child in (0..record.num_children()).filter_map(|i| record.child_at(i)) {
+        summary = SubtreeProblems::merge(summary, own_problems(&child));
+        if child.num_children() > 0 {
+            summary = SubtreeProblems::merge(summary, subtree_problems(&child, cache));
+        }
+    }
+    cache.insert(record.id(), summary);
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rjets::{parse_trace_from_reader, DynTraceData, TraceData};
+
+    #[test]
+    fn test_problems_roll_up_to_ancestors() {
+        let input = concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"core","record_type":"Core","id":1,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record","clk":10,"name":"a","record_type":"Op","id":2,"parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"event","clk":12,"name":"warn_stall","record_id":2,"description":""}"#, "\n",
+            r#"{"type":"record","clk":30,"name":"b","record_type":"Op","id":3,"parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"event","clk":31,"name":"retire","record_id":3,"description":"","data":{"severity":"error"}}"#, "\n",
+            r#"{"type":"event","clk":32,"name":"ERROR_COUNT","record_id":3,"description":"","data":{"severity":"info"}}"#, "\n",
+        );
+        let data = DynTraceData::Jets(parse_trace_from_reader(input.as_bytes()).unwrap());
+        let root = data.get_record(1).unwrap();
+
+        let mut cache = ProblemRollupCache::new();
+        let problems = subtree_problems(&root, &mut cache).unwrap();
+        assert_eq!(problems.level, Severity::Error);
+        assert_eq!(problems.count, 2);
+        assert_eq!(problems.first, (2, 12));
+        assert_eq!(cache.get(&1), Some(&Some(problems)));
+
+        let leaf = data.get_record(2).unwrap();
+        assert_eq!(subtree_problems(&leaf, &mut cache), None);
+    }
+}
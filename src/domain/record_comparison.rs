@@ -0,0 +1,112 @@
+//! Side-by-side comparison of several records.
+//!
+//! Lines up the start clock, duration and attributes of the selected records
+//! as rows with one value per record. Attribute rows cover the union of the
+//! records' keys, sorted, so a key only some records have shows as missing
+//! for the others.
+
+use std::collections::BTreeSet;
+
+use rjets::{AttributeAccessor, DynTraceData, RecordId, TraceData, TraceRecord};
+use serde_json::Value;
+
+/// What a comparison row shows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComparisonField {
+    Start,
+    Duration,
+    Attribute(String),
+}
+
+impl ComparisonField {
+    /// Row label shown in the details panel.
+    pub fn label(&self) -> &str {
+        match self {
+            ComparisonField::Start => "Start",
+            ComparisonField::Duration => "Duration",
+            ComparisonField::Attribute(key) => key,
+        }
+    }
+}
+
+/// One compared field with a value per record (`None` where it is missing).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComparisonRow {
+    pub field: ComparisonField,
+    pub values: Vec<Option<Value>>,
+}
+
+impl ComparisonRow {
+    /// Returns true if not all records have the same value.
+    pub fn differs(&self) -> bool {
+        self.values.windows(2).any(|pair| pair[0] != pair[1])
+    }
+}
+
+/// Compared records and their rows.
+#[derive(Debug, Clone, Default)]
+pub struct RecordComparison {
+    /// The records found in the trace, in the order given
+    pub records: Vec<RecordId>,
+    pub rows: Vec<ComparisonRow>,
+}
+
+/// Compares records by start clock, duration and attributes.
+pub fn compare_records(trace: &DynTraceData, record_ids: &[RecordId]) -> RecordComparison {
+    let records: Vec<_> = record_ids.iter().filter_map(|&id| trace.get_record(id)).collect();
+    let attrs: Vec<_> = records.iter().map(|record| record.attrs()).collect();
+    let keys: BTreeSet<&String> = attrs.iter().flatten().map(|(key, _)| key).collect();
+
+    let mut rows = vec![
+        ComparisonRow {
+            field: ComparisonField::Start,
+            values: records.iter().map(|record| Some(Value::from(record.clk()))).collect(),
+        },
+        ComparisonRow {
+            field: ComparisonField::Duration,
+            values: records.iter().map(|record| record.duration().map(Value::from)).collect(),
+        },
+    ];
+    rows.extend(keys.into_iter().map(|key| ComparisonRow {
+        field: ComparisonField::Attribute(key.clone()),
+        values: attrs
+            .iter()
+            .map(|record_attrs| record_attrs.iter().find(|(k, _)| k == key).map(|(_, value)| value.clone()))
+            .collect(),
+    }));
+
+    RecordComparison {
+        records: records.iter().map(|record| record.id()).collect(),
+        rows,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_comparison_rows_cover_attribute_union() {
+        let input = concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"a","record_type":"Op","id":1,"parent_id":null,"description":"","data":{"pc":64,"unit":"alu"}}"#, "\n",
+            r#"{"type":"record_end","clk":10,"record_id":1}"#, "\n",
+            r#"{"type":"record","clk":4,"name":"b","record_type":"Op","id":2,"parent_id":null,"description":"","data":{"unit":"alu","lat":3}}"#, "\n",
+        );
+        let trace = DynTraceData::Jets(rjets::parse_trace_from_reader(input.as_bytes()).unwrap());
+        let ids: Vec<_> = [1, 2, 99].iter().filter_map(|&id| trace.find_external_id(&rjets::ExternalId::Int(id))).collect();
+
+        let comparison = compare_records(&trace, &ids);
+        assert_eq!(comparison.records.len(), 2);
+        let labels: Vec<_> = comparison.rows.iter().map(|row| row.field.label()).collect();
+        assert_eq!(labels, ["Start", "Duration", "lat", "pc", "unit"]);
+
+        let row = |label: &str| comparison.rows.iter().find(|row| row.field.label() == label).unwrap();
+        assert_eq!(row("Start").values, [Some(json!(0)), Some(json!(4))]);
+        assert_eq!(row("Duration").values, [Some(json!(10)), None], "unended record has no duration");
+        assert_eq!(row("lat").values, [None, Some(json!(3))]);
+        assert!(row("pc").differs());
+        assert!(!row("unit").differs());
+    }
+}
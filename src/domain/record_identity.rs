@@ -0,0 +1,110 @@
+//! Re-finding records in a reloaded trace.
+//!
+//! User state (notes, tags, pinned rows, selection) is keyed by record ID.
+//! When a trace is reloaded from disk the IDs usually survive, but a producer
+//! that renumbers records, or a file that was rewritten in between, can leave
+//! an ID pointing at a different record or at nothing. A record is therefore
+//! remembered together with its type, name and start clock: it keeps its ID
+//! while the record there still has that type and name, and is otherwise
+//! looked up by type, name and start clock.
+
+use rjets::{DynTraceData, TraceData, TraceRecord};
+use std::collections::HashMap;
+
+/// What identifies a record across reloads.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordIdentity {
+    /// ID in the trace the identity was taken from
+    pub id: u64,
+    pub record_type: String,
+    pub name: String,
+    /// Start clock, used to tell apart same-named records
+    pub clk: i64,
+}
+
+impl RecordIdentity {
+    /// Takes the identity of a record.
+    pub fn of<'a>(record: &impl TraceRecord<'a>) -> Self {
+        Self {
+            id: record.id(),
+            record_type: record.record_type(),
+            name: record.name(),
+            clk: record.clk(),
+        }
+    }
+
+    /// Describes the record for messages, e.g. `Warp "w3" (id 12)`.
+    pub fn describe(&self) -> String {
+        format!("{} \"{}\" (id {})", self.record_type, self.name, self.id)
+    }
+}
+
+/// Finds each remembered record in `trace`.
+///
+/// # Returns
+/// A map from old to new record ID for every identity that was found;
+/// identities missing from the map could not be matched.
+pub fn rematch(trace: &DynTraceData, identities: &[RecordIdentity]) -> HashMap<u64, u64> {
+    let mut matched = HashMap::new();
+    let mut missing: HashMap<(String, String, i64), u64> = HashMap::new();
+
+    for identity in identities {
+        let same = trace
+            .get_record(identity.id)
+            .is_some_and(|record| record.record_type() == identity.record_type && record.name() == identity.name);
+        if same {
+            matched.insert(identity.id, identity.id);
+        } else {
+            missing.insert((identity.record_type.clone(), identity.name.clone(), identity.clk), identity.id);
+        }
+    }
+
+    // One pass over the trace for everything that moved
+    if !missing.is_empty() {
+        for record in trace.records_iter() {
+            if let Some(old_id) = missing.remove(&(record.record_type(), record.name(), record.clk())) {
+                matched.insert(old_id, record.id());
+                if missing.is_empty() {
+                    break;
+                }
+            }
+        }
+    }
+    matched
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trace(lines: &[&str]) -> DynTraceData {
+        let mut input = String::from(r#"{"type":"header","version":"2.0","metadata":{}}"#);
+        for line in lines {
+            input.push('\n');
+            input.push_str(line);
+        }
+        DynTraceData::Jets(rjets::parse_trace_from_reader(input.as_bytes()).unwrap())
+    }
+
+    #[test]
+    fn test_rematch_by_id_then_identity() {
+        let before = trace(&[
+            r#"{"type":"record","clk":0,"name":"a","record_type":"T","id":1,"parent_id":null,"description":""}"#,
+            r#"{"type":"record","clk":5,"name":"b","record_type":"T","id":2,"parent_id":null,"description":""}"#,
+            r#"{"type":"record","clk":9,"name":"c","record_type":"T","id":3,"parent_id":null,"description":""}"#,
+        ]);
+        // Record b was renumbered and c is gone
+        let after = trace(&[
+            r#"{"type":"record","clk":0,"name":"a","record_type":"T","id":1,"parent_id":null,"description":""}"#,
+            r#"{"type":"record","clk":9,"name":"x","record_type":"T","id":3,"parent_id":null,"description":""}"#,
+            r#"{"type":"record","clk":5,"name":"b","record_type":"T","id":7,"parent_id":null,"description":""}"#,
+        ]);
+
+        let identities: Vec<_> = (1..=3).map(|id| RecordIdentity::of(&before.get_record(id).unwrap())).collect();
+        let matched = rematch(&after, &identities);
+        assert_eq!(matched.get(&1), Some(&1));
+        assert_eq!(matched.get(&2), Some(&7));
+        assert_eq!(matched.get(&3), None);
+        assert_eq!(identities[2].describe(), "T \"c\" (id 3)");
+    }
+}
@@ -0,0 +1,78 @@
+//! Record references in attribute values.
+//!
+//! Traces have no link type, but producers often store another record's ID in
+//! an attribute (`"producer_id": 42`, `"dep": "ref:load_7"`). A value is taken
+//! as a reference when it
+//! - is a string starting with [`REF_PREFIX`] (any key), or
+//! - is an integer or string under a key that names an ID (`id`, `ref`,
+//!   `*_id`, `*Id`)
+//!
+//! and an existing record other than the owner has that ID. Everything else,
+//! e.g. a `period` that happens to equal some record's ID, stays plain text.
+
+use rjets::{DynTraceData, ExternalId, RecordId, TraceData};
+use serde_json::Value;
+
+/// Prefix marking a string attribute value as a record reference.
+pub const REF_PREFIX: &str = "ref:";
+
+/// Returns true if an attribute key names a record ID.
+fn is_id_key(key: &str) -> bool {
+    let lower = key.to_ascii_lowercase();
+    lower == "id" || lower == "ref" || lower.ends_with("_id") || lower.ends_with("-id") || key.ends_with("Id")
+}
+
+/// Returns the record ID an attribute value refers to, without checking the trace.
+pub fn referenced_id(key: &str, value: &Value) -> Option<ExternalId> {
+    match value {
+        Value::String(s) => match s.strip_prefix(REF_PREFIX) {
+            Some(target) => parse_id(target.trim()),
+            None if is_id_key(key) => parse_id(s),
+            None => None,
+        },
+        Value::Number(n) if is_id_key(key) => n.as_u64().map(ExternalId::Int),
+        _ => None,
+    }
+}
+
+/// Parses a referenced ID: digits are an integer ID, anything else a string ID.
+fn parse_id(text: &str) -> Option<ExternalId> {
+    if text.is_empty() {
+        return None;
+    }
+    Some(text.parse::<u64>().map_or_else(|_| ExternalId::from(text), ExternalId::Int))
+}
+
+/// Returns the record an attribute of record `owner` links to, if any.
+pub fn resolve_link(trace: &DynTraceData, owner: RecordId, key: &str, value: &Value) -> Option<RecordId> {
+    let id = referenced_id(key, value)?;
+    trace.find_external_id(&id).filter(|&target| target != owner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_links_resolve_by_key_or_prefix() {
+        let input = concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"load","record_type":"Op","id":"load_7","parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record","clk":1,"name":"add","record_type":"Op","id":42,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record","clk":2,"name":"use","record_type":"Op","id":43,"parent_id":null,"description":""}"#, "\n",
+        );
+        let trace = DynTraceData::Jets(rjets::parse_trace_from_reader(input.as_bytes()).unwrap());
+        let load = trace.find_external_id(&ExternalId::from("load_7")).unwrap();
+
+        assert_eq!(resolve_link(&trace, 43, "producer_id", &json!(42)), Some(42));
+        assert_eq!(resolve_link(&trace, 43, "srcId", &json!("42")), Some(42));
+        assert_eq!(resolve_link(&trace, 43, "dep", &json!("ref:load_7")), Some(load));
+        assert_eq!(resolve_link(&trace, 43, "dep", &json!("ref: 42")), Some(42));
+
+        assert_eq!(resolve_link(&trace, 43, "period", &json!(42)), None, "not an ID key");
+        assert_eq!(resolve_link(&trace, 43, "note", &json!("load_7")), None, "no prefix");
+        assert_eq!(resolve_link(&trace, 43, "parent_id", &json!(99)), None, "no such record");
+        assert_eq!(resolve_link(&trace, 42, "self_id", &json!(42)), None, "own ID");
+    }
+}
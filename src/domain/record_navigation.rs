@@ -0,0 +1,129 @@
+//! Stepping from a record to a related one.
+//!
+//! Siblings are visited in trace order (the order the backend lists a
+//! parent's children, or the roots). Records with the same name can sit
+//! anywhere in the hierarchy, so they are visited by start clock across the
+//! whole trace, ties broken by record ID.
+
+use rjets::{DynTraceData, DynTraceRecord, TraceData, TraceRecord};
+
+/// Which records a navigation step moves between.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavigationScope {
+    /// Any sibling (same parent)
+    Sibling,
+    /// Siblings with the same record_type
+    SameType,
+    /// Records with the same name anywhere in the trace
+    SameName,
+}
+
+impl NavigationScope {
+    /// All scopes, in menu order.
+    pub const ALL: [NavigationScope; 3] = [Self::Sibling, Self::SameType, Self::SameName];
+
+    /// Returns the menu and command palette label of a step.
+    pub fn label(self, forward: bool) -> &'static str {
+        match (self, forward) {
+            (Self::Sibling, false) => "Previous Sibling",
+            (Self::Sibling, true) => "Next Sibling",
+            (Self::SameType, false) => "Previous Sibling of Same Type",
+            (Self::SameType, true) => "Next Sibling of Same Type",
+            (Self::SameName, false) => "Previous Record with Same Name",
+            (Self::SameName, true) => "Next Record with Same Name",
+        }
+    }
+}
+
+/// Finds the record one step away from `record_id`.
+///
+/// # Arguments
+/// * `trace` - The trace to search
+/// * `record_id` - Record to step from
+/// * `scope` - Which records to consider
+/// * `forward` - Step to the next (true) or previous (false) record
+///
+/// # Returns
+/// The target record, or None at the first/last record of the scope.
+pub fn find_neighbor(trace: &DynTraceData, record_id: u64, scope: NavigationScope, forward: bool) -> Option<u64> {
+    let record = trace.get_record(record_id)?;
+    match scope {
+        NavigationScope::Sibling => step_among(&sibling_ids(trace, &record, None), record_id, forward),
+        NavigationScope::SameType => {
+            let record_type = record.record_type();
+            step_among(&sibling_ids(trace, &record, Some(&record_type)), record_id, forward)
+        }
+        NavigationScope::SameName => {
+            let name = record.name();
+            let current = (record.clk(), record_id);
+            let candidates = trace
+                .records_iter()
+                .filter(|other| other.name() == name)
+                .map(|other| (other.clk(), other.id()));
+            if forward {
+                candidates.filter(|&key| key > current).min().map(|(_, id)| id)
+            } else {
+                candidates.filter(|&key| key < current).max().map(|(_, id)| id)
+            }
+        }
+    }
+}
+
+/// Returns the IDs of the record's siblings (itself included) in trace order,
+/// optionally only those of one record type.
+fn sibling_ids(trace: &DynTraceData, record: &DynTraceRecord<'_>, record_type: Option<&str>) -> Vec<u64> {
+    let keep = |other: &DynTraceRecord<'_>| record_type.is_none_or(|t| other.record_type() == t);
+    match record.parent_id().and_then(|id| trace.get_record(id)) {
+        Some(parent) => (0..parent.num_children())
+            .filter_map(|i| parent.child_at(i))
+            .filter(|child| keep(child))
+            .map(|child| child.id())
+            .collect(),
+        None => trace
+            .roots()
+            .iter()
+            .filter(|&&id| trace.get_record(id).is_some_and(|root| keep(&root)))
+            .copied()
+            .collect(),
+    }
+}
+
+/// Returns the ID before or after `current` in `ids`.
+fn step_among(ids: &[u64], current: u64, forward: bool) -> Option<u64> {
+    let index = ids.iter().position(|&id| id == current)?;
+    if forward {
+        ids.get(index + 1).copied()
+    } else {
+        index.checked_sub(1).map(|previous| ids[previous])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steps_by_scope() {
+        let input = concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"core0","record_type":"Core","id":1,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"core1","record_type":"Core","id":2,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record","clk":1,"name":"add","record_type":"Instr","id":3,"parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"record","clk":2,"name":"stall","record_type":"Bubble","id":4,"parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"record","clk":3,"name":"load","record_type":"Instr","id":5,"parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"record","clk":2,"name":"add","record_type":"Instr","id":6,"parent_id":2,"description":""}"#, "\n",
+        );
+        let trace = DynTraceData::Jets(rjets::parse_trace_from_reader(input.as_bytes()).unwrap());
+
+        assert_eq!(find_neighbor(&trace, 3, NavigationScope::Sibling, true), Some(4));
+        assert_eq!(find_neighbor(&trace, 3, NavigationScope::Sibling, false), None);
+        assert_eq!(find_neighbor(&trace, 3, NavigationScope::SameType, true), Some(5));
+        assert_eq!(find_neighbor(&trace, 5, NavigationScope::SameType, false), Some(3));
+        assert_eq!(find_neighbor(&trace, 1, NavigationScope::Sibling, true), Some(2));
+
+        // Same name crosses parents, ordered by start clock
+        assert_eq!(find_neighbor(&trace, 3, NavigationScope::SameName, true), Some(6));
+        assert_eq!(find_neighbor(&trace, 6, NavigationScope::SameName, false), Some(3));
+        assert_eq!(find_neighbor(&trace, 6, NavigationScope::SameName, true), None);
+    }
+}
@@ -0,0 +1,145 @@
+//! Row heat index for the timeline scrollbar overview.
+//!
+//! The scrollbar next to the timeline paints where interesting rows sit in the
+//! full visible row list (selected record, records with notes, the longest
+//! records), so they can be found in a tree far taller than the screen. This
+//! module maps records to rows and buckets the marks into strip pixels; it
+//! knows nothing about painting.
+
+use rjets::{DynTraceData, TraceData, TraceRecord};
+use std::collections::HashMap;
+
+use crate::domain::tree_operations::FilteredVisibleNode;
+
+/// Fraction of rows (by duration, longest first) marked as long-duration.
+const LONG_DURATION_FRACTION: f64 = 0.01;
+
+/// Why a row is marked on the heat strip, in increasing priority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HeatMark {
+    /// Among the longest records of the visible rows
+    LongDuration,
+    /// Record has a note or tags
+    Noted,
+    /// Currently selected record
+    Selected,
+}
+
+/// Row positions of the visible row list, built once per row list.
+#[derive(Debug, Clone, Default)]
+pub struct RowHeatIndex {
+    /// Number of rows in the list
+    total_rows: usize,
+    /// Maps record_id -> row index
+    row_of: HashMap<u64, usize>,
+    /// Row indices of long-duration records (ascending)
+    long_rows: Vec<usize>,
+    /// Viewport range the list was filtered with (None = unfiltered)
+    filter_range: Option<(i64, i64)>,
+}
+
+impl RowHeatIndex {
+    /// Builds the index for a complete visible row list.
+    ///
+    /// # Arguments
+    /// * `trace` - The trace data
+    /// * `nodes` - All visible rows (not just the on-screen window)
+    /// * `filter_range` - Viewport range the rows were filtered with, if any
+    pub fn build(trace: &DynTraceData, nodes: &[FilteredVisibleNode], filter_range: Option<(i64, i64)>) -> Self {
+        let mut row_of = HashMap::with_capacity(nodes.len());
+        let mut durations = Vec::new();
+        for node in nodes {
+            row_of.insert(node.record_id, node.row_index);
+            if let Some(duration) = trace.get_record(node.record_id).and_then(|r| r.duration()) {
+                durations.push((node.row_index, duration));
+            }
+        }
+
+        Self {
+            total_rows: nodes.len(),
+            row_of,
+            long_rows: long_duration_rows(durations),
+            filter_range,
+        }
+    }
+
+    /// Returns the number of rows in the list.
+    pub fn total_rows(&self) -> usize {
+        self.total_rows
+    }
+
+    /// Returns the viewport range the list was filtered with (None = unfiltered).
+    pub fn filter_range(&self) -> Option<(i64, i64)> {
+        self.filter_range
+    }
+
+    /// Collects the marked rows for the current selection and notes.
+    pub fn marks(&self, selected: Option<u64>, noted: impl Iterator<Item = u64>) -> Vec<(usize, HeatMark)> {
+        let mut marks: Vec<(usize, HeatMark)> =
+            self.long_rows.iter().map(|&row| (row, HeatMark::LongDuration)).collect();
+        marks.extend(noted.filter_map(|id| self.row_of.get(&id)).map(|&row| (row, HeatMark::Noted)));
+        if let Some(&row) = selected.and_then(|id| self.row_of.get(&id)) {
+            marks.push((row, HeatMark::Selected));
+        }
+        marks
+    }
+}
+
+/// Returns the rows of the longest `LONG_DURATION_FRACTION` of records (at least one).
+fn long_duration_rows(mut durations: Vec<(usize, i64)>) -> Vec<usize> {
+    if durations.is_empty() {
+        return Vec::new();
+    }
+    let count = ((durations.len() as f64 * LONG_DURATION_FRACTION).ceil() as usize).max(1);
+    durations.select_nth_unstable_by(count - 1, |a, b| b.1.cmp(&a.1));
+    let mut rows: Vec<usize> = durations[..count].iter().map(|&(row, _)| row).collect();
+    rows.sort_unstable();
+    rows
+}
+
+/// Buckets row marks into `bucket_count` vertical bins of the strip.
+///
+/// Each bin keeps its highest-priority mark, so a selected row is never
+/// hidden by neighbouring long-duration rows.
+pub fn bucket_marks(marks: &[(usize, HeatMark)], total_rows: usize, bucket_count: usize) -> Vec<Option<HeatMark>> {
+    let mut buckets = vec![None; bucket_count];
+    if total_rows == 0 || bucket_count == 0 {
+        return buckets;
+    }
+    for &(row, mark) in marks {
+        let bucket = (row * bucket_count / total_rows).min(bucket_count - 1);
+        buckets[bucket] = buckets[bucket].max(Some(mark));
+    }
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_long_duration_rows_keeps_top_fraction() {
+        let durations: Vec<(usize, i64)> = (0..300).map(|row| (row, (row as i64 * 7) % 300)).collect();
+        let rows = long_duration_rows(durations);
+        // 1% of 300 rows, the three longest (durations 299, 298, 297)
+        assert_eq!(rows.len(), 3);
+        for row in rows {
+            assert!((row as i64 * 7) % 300 >= 297);
+        }
+        assert!(long_duration_rows(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn test_bucket_marks_keeps_highest_priority() {
+        let marks = [
+            (0, HeatMark::LongDuration),
+            (1, HeatMark::Selected),
+            (2, HeatMark::Noted),
+            (99, HeatMark::LongDuration),
+        ];
+        let buckets = bucket_marks(&marks, 100, 10);
+        assert_eq!(buckets[0], Some(HeatMark::Selected));
+        assert_eq!(buckets[5], None);
+        assert_eq!(buckets[9], Some(HeatMark::LongDuration));
+    }
+}
@@ -0,0 +1,75 @@
+//! Event severity.
+//!
+//! Events carry no level in the format itself. A reserved `severity`
+//! attribute ("error", "warning", "info", ...) sets it explicitly; events
+//! without one are classified by name prefix (ERR*/FATAL*/CRIT* are errors,
+//! WARN* are warnings) and default to info.
+
+use rjets::TraceEvent;
+use serde::{Deserialize, Serialize};
+
+/// Event attribute that sets the severity explicitly.
+pub const SEVERITY_ATTR: &str = "severity";
+
+/// Severity of an event, in increasing order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[default]
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    /// All severities, lowest first.
+    pub const ALL: [Severity; 3] = [Severity::Info, Severity::Warning, Severity::Error];
+
+    /// Parses a severity word or event name; None for anything else.
+    pub fn from_word(word: &str) -> Option<Self> {
+        let word = word.trim().to_ascii_uppercase();
+        if ["ERR", "FATAL", "CRIT"].iter().any(|prefix| word.starts_with(prefix)) {
+            Some(Severity::Error)
+        } else if word.starts_with("WARN") {
+            Some(Severity::Warning)
+        } else if ["INFO", "DEBUG", "TRACE", "NOTICE"].iter().any(|prefix| word.starts_with(prefix)) {
+            Some(Severity::Info)
+        } else {
+            None
+        }
+    }
+
+    /// Returns true for warnings and errors.
+    pub fn is_problem(self) -> bool {
+        self >= Severity::Warning
+    }
+}
+
+/// Classifies an event by its `severity` attribute, or else by its name.
+///
+/// An unrecognized `severity` value counts as info rather than falling back
+/// to the name, so "severity": "info" on an "ERROR_COUNT" event wins.
+pub fn event_severity(event: &impl TraceEvent) -> Severity {
+    match event.attr(SEVERITY_ATTR) {
+        Some(value) => value.as_str().and_then(Severity::from_word).unwrap_or_default(),
+        None => Severity::from_word(&event.name())
+            .filter(|severity| severity.is_problem())
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_severity_words() {
+        assert_eq!(Severity::from_word("error"), Some(Severity::Error));
+        assert_eq!(Severity::from_word(" Fatal "), Some(Severity::Error));
+        assert_eq!(Severity::from_word("WARNING"), Some(Severity::Warning));
+        assert_eq!(Severity::from_word("debug"), Some(Severity::Info));
+        assert_eq!(Severity::from_word("retire"), None);
+        assert!(Severity::Error > Severity::Warning && Severity::Warning > Severity::Info);
+        assert!(!Severity::Info.is_problem());
+    }
+}
@@ -2,21 +2,117 @@
 //!
 //! This module provides functions to compute sorted child indices for tree nodes,
 //! independent of backend ordering. Sorting is per-subtree and can be based on
-//! description, start clock, or duration.
+//! description, start clock, duration, or a custom comparator registered in a
+//! [`SortKeyRegistry`] (a closure, or the value of an attribute).
 
-use rjets::{DynTraceData, DynTraceRecord, TraceRecord};
-use crate::state::{SortSpec, SortKey, SortDir};
+use std::cmp::Ordering;
+
+use rjets::{AttributeAccessor, DynTraceData, DynTraceRecord, TraceRecord};
+use serde_json::Value;
+use crate::state::{ComparatorId, SortSpec, SortKey, SortDir};
+
+/// Compares two records for a custom sort key (ascending order).
+pub type BoxedComparator = Box<dyn Fn(&DynTraceRecord<'_>, &DynTraceRecord<'_>) -> Ordering + Send + Sync>;
+
+/// A registered custom sort key.
+pub struct CustomSortKey {
+    pub id: ComparatorId,
+    /// Name shown in the sort menu
+    pub label: String,
+    compare: BoxedComparator,
+}
+
+/// Custom sort keys offered next to the built-in ones.
+///
+/// IDs are never reused, so a cached order keyed by a [`SortSpec`] cannot
+/// be mistaken for that of another comparator.
+#[derive(Default)]
+pub struct SortKeyRegistry {
+    keys: Vec<CustomSortKey>,
+    next_id: u32,
+}
+
+impl SortKeyRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a comparator under a menu label.
+    pub fn register(&mut self, label: impl Into<String>, compare: BoxedComparator) -> ComparatorId {
+        let id = ComparatorId(self.next_id);
+        self.next_id += 1;
+        self.keys.push(CustomSortKey {
+            id,
+            label: label.into(),
+            compare,
+        });
+        id
+    }
+
+    /// Registers a key sorting by the value of an attribute, or returns the
+    /// one already registered for it.
+    ///
+    /// Numbers sort numerically before strings; records without the
+    /// attribute sort last.
+    pub fn register_attribute(&mut self, attribute: &str) -> ComparatorId {
+        let label = format!("Attribute: {}", attribute);
+        if let Some(key) = self.keys.iter().find(|key| key.label == label) {
+            return key.id;
+        }
+        let attribute = attribute.to_string();
+        self.register(
+            label,
+            Box::new(move |a, b| compare_attribute_values(a.attr(&attribute), b.attr(&attribute))),
+        )
+    }
+
+    /// Returns the registered keys in registration order.
+    pub fn keys(&self) -> &[CustomSortKey] {
+        &self.keys
+    }
+
+    /// Returns the menu label of a key.
+    pub fn label(&self, id: ComparatorId) -> Option<&str> {
+        self.find(id).map(|key| key.label.as_str())
+    }
+
+    fn find(&self, id: ComparatorId) -> Option<&CustomSortKey> {
+        self.keys.iter().find(|key| key.id == id)
+    }
+}
+
+/// Orders attribute values: numbers by value, then strings, then anything
+/// else by its JSON text, then missing values.
+fn compare_attribute_values(a: Option<Value>, b: Option<Value>) -> Ordering {
+    let rank = |value: &Option<Value>| match value {
+        Some(Value::Number(_)) => 0,
+        Some(Value::String(_)) => 1,
+        Some(_) => 2,
+        None => 3,
+    };
+    rank(&a).cmp(&rank(&b)).then_with(|| match (&a, &b) {
+        (Some(Value::Number(x)), Some(Value::Number(y))) => {
+            x.as_f64().unwrap_or(f64::NAN).total_cmp(&y.as_f64().unwrap_or(f64::NAN))
+        }
+        (Some(Value::String(x)), Some(Value::String(y))) => x.cmp(y),
+        (Some(x), Some(y)) => x.to_string().cmp(&y.to_string()),
+        _ => Ordering::Equal,
+    })
+}
 
 /// Computes sorted child indices for a given parent record.
 ///
 /// Returns a vector of indices into the parent's children array, ordered
 /// according to the given sort specification. The indices can then be used
 /// with `parent.child_at(index)` to iterate children in sorted order.
+/// A custom key missing from `registry` keeps the backend order.
 ///
 /// # Arguments
 /// * `trace` - The trace data (unused but kept for consistency)
 /// * `parent` - The parent record whose children should be sorted
 /// * `spec` - The sort specification (key and direction)
+/// * `registry` - Custom sort keys
 ///
 /// # Returns
 /// A vector of child indices in sorted order
@@ -24,7 +120,15 @@ pub fn sort_child_indices_for_parent(
     _trace: &DynTraceData,
     parent: &DynTraceRecord<'_>,
     spec: SortSpec,
+    registry: &SortKeyRegistry,
 ) -> Vec<usize> {
+    if let SortKey::Custom(id) = spec.key {
+        return match registry.find(id) {
+            Some(key) => sort_child_indices_with(parent, &key.compare, spec.dir),
+            None => (0..parent.num_children()).collect(),
+        };
+    }
+
     let n = parent.num_children();
     let mut items: Vec<(usize, ChildKey)> = Vec::with_capacity(n);
 
@@ -41,6 +145,18 @@ pub fn sort_child_indices_for_parent(
     items.into_iter().map(|(i, _)| i).collect()
 }
 
+/// Computes child indices ordered by a comparator (stable, so equal
+/// children keep their backend order).
+pub fn sort_child_indices_with(parent: &DynTraceRecord<'_>, compare: &BoxedComparator, dir: SortDir) -> Vec<usize> {
+    let mut children: Vec<(usize, DynTraceRecord<'_>)> =
+        (0..parent.num_children()).filter_map(|i| Some((i, parent.child_at(i)?))).collect();
+    children.sort_by(|a, b| match dir {
+        SortDir::Asc => compare(&a.1, &b.1),
+        SortDir::Desc => compare(&b.1, &a.1),
+    });
+    children.into_iter().map(|(i, _)| i).collect()
+}
+
 /// Key used for sorting child records.
 ///
 /// Only one field is populated based on the sort key.
@@ -53,7 +169,7 @@ struct ChildKey {
 }
 
 impl ChildKey {
-    /// Creates a ChildKey from a record based on the sort key.
+    /// Creates a ChildKey from a record based on a built-in sort key.
     fn from_record(rec: &DynTraceRecord<'_>, key: SortKey) -> Self {
         match key {
             SortKey::Description => ChildKey {
@@ -71,6 +187,46 @@ impl ChildKey {
                 start_clk: None,
                 duration: rec.duration(), // None sorts before Some by default
             },
+            SortKey::Custom(_) => ChildKey {
+                description: None,
+                start_clk: None,
+                duration: None,
+            },
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rjets::TraceData;
+
+    #[test]
+    fn test_custom_and_attribute_sort_keys() {
+        let input = concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"root","record_type":"Op","id":1,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record","clk":1,"name":"bb","record_type":"Op","id":2,"parent_id":1,"description":"","data":{"lat":12}}"#, "\n",
+            r#"{"type":"record","clk":2,"name":"a","record_type":"Op","id":3,"parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"record","clk":3,"name":"ccc","record_type":"Op","id":4,"parent_id":1,"description":"","data":{"lat":3.5}}"#, "\n",
+            r#"{"type":"record","clk":4,"name":"dd","record_type":"Op","id":5,"parent_id":1,"description":"","data":{"lat":"n/a"}}"#, "\n",
+        );
+        let trace = DynTraceData::Jets(rjets::parse_trace_from_reader(input.as_bytes()).unwrap());
+        let root = trace.get_record(trace.roots()[0]).unwrap();
+
+        let mut registry = SortKeyRegistry::new();
+        let by_name_length = registry.register("Name length", Box::new(|a, b| a.name().len().cmp(&b.name().len())));
+        let by_lat = registry.register_attribute("lat");
+        assert_ne!(by_name_length, by_lat);
+        assert_eq!(registry.register_attribute("lat"), by_lat, "registered once");
+        assert_eq!(registry.label(by_lat), Some("Attribute: lat"));
+
+        let order = |key, dir| sort_child_indices_for_parent(&trace, &root, SortSpec { key, dir }, &registry);
+        // Children: bb, a, ccc, dd (stable for equal lengths)
+        assert_eq!(order(SortKey::Custom(by_name_length), SortDir::Asc), [1, 0, 3, 2]);
+        assert_eq!(order(SortKey::Custom(by_name_length), SortDir::Desc), [2, 0, 3, 1]);
+        // 3.5 < 12 < "n/a" < missing
+        assert_eq!(order(SortKey::Custom(by_lat), SortDir::Asc), [2, 0, 3, 1]);
+        assert_eq!(order(SortKey::Custom(ComparatorId(99)), SortDir::Asc), [0, 1, 2, 3]);
+    }
+}
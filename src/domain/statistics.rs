@@ -0,0 +1,319 @@
+//! Trace statistics analysis.
+//!
+//! Computes summary statistics over the whole trace or over a clock range
+//! (e.g. the current viewport region): record/event totals, per-depth
+//! duration aggregates, event counts by name and the longest records.
+//! Results can be exported through the shared reporting module.
+
+use crate::domain::name_aliases::NameAliases;
+use crate::domain::number_format::ColumnFormats;
+use crate::reporting::{Report, Reportable};
+use rjets::{DynTraceData, ExternalId, TraceData, TraceEvent, TraceRecord};
+use std::collections::BTreeMap;
+
+/// Number of longest records kept in the statistics.
+const LONGEST_RECORDS_LIMIT: usize = 10;
+
+/// Duration aggregate for all records at one tree depth.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DepthStats {
+    /// Number of records at this depth
+    pub count: usize,
+    /// Number of records that have an end clock
+    pub with_duration: usize,
+    /// Sum of durations of records that have an end clock
+    pub total_duration: i64,
+    /// Shortest duration seen (if any record has an end clock)
+    pub min_duration: Option<i64>,
+    /// Longest duration seen (if any record has an end clock)
+    pub max_duration: Option<i64>,
+}
+
+impl DepthStats {
+    /// Mean duration over records that have an end clock.
+    pub fn mean_duration(&self) -> Option<f64> {
+        if self.with_duration == 0 {
+            None
+        } else {
+            Some(self.total_duration as f64 / self.with_duration as f64)
+        }
+    }
+
+    fn add(&mut self, duration: Option<i64>) {
+        self.count += 1;
+        if let Some(d) = duration {
+            self.with_duration += 1;
+            self.total_duration += d;
+            self.min_duration = Some(self.min_duration.map_or(d, |m| m.min(d)));
+            self.max_duration = Some(self.max_duration.map_or(d, |m| m.max(d)));
+        }
+    }
+}
+
+/// Short description of a record used in the longest-records list.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordSummary {
+    pub id: u64,
+    /// ID as written in the trace (may be a string)
+    pub external_id: ExternalId,
+    pub name: String,
+    pub clk: i64,
+    pub duration: i64,
+}
+
+/// Summary statistics for a trace or a clock range of it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TraceStatistics {
+    /// Clock range the statistics were restricted to (None = whole trace)
+    pub range: Option<(i64, i64)>,
+    /// Number of records included
+    pub record_count: usize,
+    /// Number of included records without children
+    pub leaf_count: usize,
+    /// Number of events included
+    pub event_count: usize,
+    /// Deepest included tree level (0 = roots)
+    pub max_depth: usize,
+    /// Duration aggregates indexed by depth
+    pub by_depth: Vec<DepthStats>,
+    /// Event counts by event name
+    pub events_by_name: BTreeMap<String, usize>,
+    /// Longest included records, longest first
+    pub longest_records: Vec<RecordSummary>,
+    /// Number formats of clocks, durations and IDs in the report
+    pub formats: ColumnFormats,
+}
+
+impl TraceStatistics {
+    /// Computes statistics over the whole trace.
+    pub fn compute(trace: &DynTraceData) -> Self {
+        Self::compute_in_range(trace, None)
+    }
+
+    /// Computes statistics, optionally restricted to a clock range.
+    ///
+    /// With a range, a record is included if its `[clk, end_clk]` interval
+    /// overlaps the range (records without an end are treated as instants),
+    /// and an event is included if its clock lies within the range. Descendants
+    /// of excluded records are still visited since children may outlive parents
+    /// in malformed traces.
+    pub fn compute_in_range(trace: &DynTraceData, range: Option<(i64, i64)>) -> Self {
+        let mut stats = TraceStatistics {
+            range,
+            ..Default::default()
+        };
+
+        let in_range = |start: i64, end: i64| match range {
+            Some((lo, hi)) => start <= hi && end >= lo,
+            None => true,
+        };
+
+        // Iterative DFS to avoid stack overflow on deep traces
+        let mut stack: Vec<(u64, usize)> = trace.roots().iter().rev().map(|&id| (id, 0)).collect();
+        while let Some((id, depth)) = stack.pop() {
+            let Some(record) = trace.get_record(id) else { continue };
+
+            let clk = record.clk();
+            if in_range(clk, record.end_clk().unwrap_or(clk)) {
+                stats.add_record(&record, depth);
+
+                for i in 0..record.num_events() {
+                    if let Some(event) = record.event_at(i) {
+                        if in_range(event.clk(), event.clk()) {
+                            stats.event_count += 1;
+                            *stats.events_by_name.entry(event.name()).or_default() += 1;
+                        }
+                    }
+                }
+            }
+
+            for i in (0..record.num_children()).rev() {
+                if let Some(child) = record.child_at(i) {
+                    stack.push((child.id(), depth + 1));
+                }
+            }
+        }
+
+        stats
+            .longest_records
+            .sort_by(|a, b| b.duration.cmp(&a.duration).then(a.id.cmp(&b.id)));
+        stats.longest_records.truncate(LONGEST_RECORDS_LIMIT);
+        stats
+    }
+
+    /// Rewrites the names of the longest records with display aliases.
+    pub fn apply_aliases(&mut self, aliases: &NameAliases) {
+        for record in &mut self.longest_records {
+            record.name = aliases.apply(&record.name).into_owned();
+        }
+    }
+
+    fn add_record(&mut self, record: &rjets::DynTraceRecord<'_>, depth: usize) {
+        self.record_count += 1;
+        if record.num_children() == 0 {
+            self.leaf_count += 1;
+        }
+        self.max_depth = self.max_depth.max(depth);
+        if self.by_depth.len() <= depth {
+            self.by_depth.resize(depth + 1, DepthStats::default());
+        }
+
+        let duration = record.duration();
+        self.by_depth[depth].add(duration);
+
+        if let Some(duration) = duration {
+            self.longest_records.push(RecordSummary {
+                id: record.id(),
+                external_id: record.external_id(),
+                name: record.name(),
+                clk: record.clk(),
+                duration,
+            });
+            // Keep the candidate list bounded while walking large traces
+            if self.longest_records.len() > LONGEST_RECORDS_LIMIT * 8 {
+                self.longest_records
+                    .sort_by(|a, b| b.duration.cmp(&a.duration).then(a.id.cmp(&b.id)));
+                self.longest_records.truncate(LONGEST_RECORDS_LIMIT);
+            }
+        }
+    }
+}
+
+impl Reportable for TraceStatistics {
+    fn to_report(&self) -> Report {
+        let title = match self.range {
+            Some(_) => "Region Statistics",
+            None => "Trace Statistics",
+        };
+        let mut report = Report::new(title);
+
+        let clock = |clk| self.formats.start_clock.format_readable(clk);
+        let duration = |duration| self.formats.duration.format_readable(duration);
+        let scope = match self.range {
+            Some((start, end)) => format!("{}..{}", clock(start), clock(end)),
+            None => "Whole trace".to_string(),
+        };
+        report.section("Summary").key_values([
+            ("Scope", scope),
+            ("Records", self.record_count.to_string()),
+            ("Leaf records", self.leaf_count.to_string()),
+            ("Events", self.event_count.to_string()),
+            ("Max depth", self.max_depth.to_string()),
+        ]);
+
+        let rows = self
+            .by_depth
+            .iter()
+            .enumerate()
+            .map(|(depth, d)| {
+                vec![
+                    depth.to_string(),
+                    d.count.to_string(),
+                    duration(d.total_duration),
+                    d.mean_duration().map(|m| format!("{:.1}", m)).unwrap_or_else(|| "-".to_string()),
+                    d.min_duration.map(duration).unwrap_or_else(|| "-".to_string()),
+                    d.max_duration.map(duration).unwrap_or_else(|| "-".to_string()),
+                ]
+            })
+            .collect();
+        let bars = self
+            .by_depth
+            .iter()
+            .enumerate()
+            .map(|(depth, d)| (format!("Depth {}", depth), d.count as f64))
+            .collect();
+        report
+            .section("Records by Depth")
+            .table(&["Depth", "Count", "Total Duration", "Mean", "Min", "Max"], rows)
+            .bar_chart("Records per depth", bars);
+
+        let events = report.section("Events by Name");
+        if self.events_by_name.is_empty() {
+            events.paragraph("No events in scope.");
+        } else {
+            let rows = self
+                .events_by_name
+                .iter()
+                .map(|(name, count)| vec![name.clone(), count.to_string()])
+                .collect();
+            let bars = self
+                .events_by_name
+                .iter()
+                .map(|(name, count)| (name.clone(), *count as f64))
+                .collect();
+            events.table(&["Event", "Count"], rows).bar_chart("Events per name", bars);
+        }
+
+        let longest = report.section("Longest Records");
+        if self.longest_records.is_empty() {
+            longest.paragraph("No records with an end clock in scope.");
+        } else {
+            let rows = self
+                .longest_records
+                .iter()
+                .map(|r| {
+                    vec![
+                        self.formats.format_id(&r.external_id),
+                        r.name.clone(),
+                        clock(r.clk),
+                        duration(r.duration),
+                    ]
+                })
+                .collect();
+            longest.table(&["ID", "Name", "Start Clock", "Duration"], rows);
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rjets::{JetsTraceReader, TraceMetadata, TraceReader};
+
+    fn sample_trace() -> DynTraceData {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/trace.jets");
+        JetsTraceReader::new().read(path).unwrap()
+    }
+
+    #[test]
+    fn test_whole_trace_counts_match_metadata() {
+        let trace = sample_trace();
+        let stats = TraceStatistics::compute(&trace);
+
+        assert_eq!(Some(stats.record_count), trace.metadata().total_records());
+        assert_eq!(Some(stats.event_count), trace.metadata().total_events());
+        assert_eq!(stats.by_depth.iter().map(|d| d.count).sum::<usize>(), stats.record_count);
+        assert_eq!(stats.events_by_name.values().sum::<usize>(), stats.event_count);
+        assert!(stats.longest_records.len() <= LONGEST_RECORDS_LIMIT);
+        assert!(stats
+            .longest_records
+            .windows(2)
+            .all(|w| w[0].duration >= w[1].duration));
+    }
+
+    #[test]
+    fn test_range_restricts_records_and_events() {
+        let trace = sample_trace();
+        let whole = TraceStatistics::compute(&trace);
+        let (min, max) = trace.metadata().trace_extent();
+        let mid = min + (max - min) / 2;
+        let region = TraceStatistics::compute_in_range(&trace, Some((min, mid)));
+
+        assert!(region.record_count <= whole.record_count);
+        assert!(region.event_count <= whole.event_count);
+
+        let empty = TraceStatistics::compute_in_range(&trace, Some((max + 1, max + 10)));
+        assert_eq!(empty.record_count, 0);
+        assert_eq!(empty.event_count, 0);
+    }
+
+    #[test]
+    fn test_report_contains_sections() {
+        let trace = sample_trace();
+        let report = TraceStatistics::compute(&trace).to_report();
+        let headings: Vec<&str> = report.sections.iter().map(|s| s.heading.as_str()).collect();
+        assert_eq!(headings, ["Summary", "Records by Depth", "Events by Name", "Longest Records"]);
+    }
+}
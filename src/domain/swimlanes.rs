@@ -0,0 +1,224 @@
+//! Attribute-keyed swimlanes.
+//!
+//! Groups records into lanes by the value of one attribute (e.g. `unit_id`)
+//! instead of following the record hierarchy. This is useful when the tree
+//! is organized by software structure but the question is about a physical
+//! unit. The result is a flat row list (lane headers followed by the lane's
+//! records in clock order) that the tree and timeline panels render in sync.
+
+use rjets::{AttributeAccessor, DynTraceData, TraceData, TraceRecord};
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Maximum number of records scanned when suggesting attribute keys.
+const KEY_SAMPLE_LIMIT: usize = 2000;
+
+/// A row in swimlane mode.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SwimlaneRow {
+    /// Lane header strip
+    Header {
+        /// Attribute value identifying the lane
+        key: String,
+        /// Number of records in the lane
+        count: usize,
+        /// Clock extent of the lane's records, if any have an end clock
+        extent: Option<(i64, i64)>,
+        /// Whether the lane's records are hidden
+        collapsed: bool,
+    },
+    /// A record inside an expanded lane
+    Record { record_id: u64 },
+}
+
+/// Lane contents accumulated while walking the trace.
+struct Lane {
+    sort_value: serde_json::Value,
+    records: Vec<(i64, u64)>,
+    extent: Option<(i64, i64)>,
+}
+
+/// Returns the lane key for an attribute value (strings without quotes).
+pub fn lane_key(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Orders lanes numerically when both values are numbers, otherwise by text.
+fn compare_lanes(a: &(String, Lane), b: &(String, Lane)) -> Ordering {
+    match (a.1.sort_value.as_f64(), b.1.sort_value.as_f64()) {
+        (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.0.cmp(&b.0),
+    }
+}
+
+/// Builds swimlane rows for all records carrying `attribute`.
+///
+/// Records without the attribute are not shown. Roots listed in
+/// `hidden_roots` are skipped together with their subtrees, as are records
+/// whose type is in `hidden_types`.
+///
+/// # Arguments
+/// * `trace` - The trace data
+/// * `attribute` - Attribute key used to group records
+/// * `collapsed` - Lane keys whose records are hidden
+/// * `hidden_roots` - Roots excluded by the root selector
+/// * `hidden_types` - Record types hidden in the type legend
+pub fn build_swimlane_rows(
+    trace: &DynTraceData,
+    attribute: &str,
+    collapsed: &HashSet<String>,
+    hidden_roots: &HashSet<u64>,
+    hidden_types: &HashSet<String>,
+) -> Vec<SwimlaneRow> {
+    let mut lanes: HashMap<String, Lane> = HashMap::new();
+
+    let mut stack: Vec<u64> = crate::domain::tree_operations::visible_roots(trace, hidden_roots).collect();
+    stack.reverse();
+    while let Some(id) = stack.pop() {
+        let Some(record) = trace.get_record(id) else {
+            continue;
+        };
+        if !hidden_types.is_empty() && hidden_types.contains(record.record_type().as_str()) {
+            continue;
+        }
+
+        if let Some(value) = record.attr(attribute) {
+            let key = lane_key(&value);
+            let lane = lanes.entry(key).or_insert_with(|| Lane {
+                sort_value: value,
+                records: Vec::new(),
+                extent: None,
+            });
+            lane.records.push((record.clk(), id));
+            if let Some(end_clk) = record.end_clk() {
+                lane.extent = Some(match lane.extent {
+                    Some((start, end)) => (start.min(record.clk()), end.max(end_clk)),
+                    None => (record.clk(), end_clk),
+                });
+            }
+        }
+
+        for i in (0..record.num_children()).rev() {
+            if let Some(child) = record.child_at(i) {
+                stack.push(child.id());
+            }
+        }
+    }
+
+    let mut lanes: Vec<(String, Lane)> = lanes.into_iter().collect();
+    lanes.sort_by(compare_lanes);
+
+    let mut rows = Vec::new();
+    for (key, mut lane) in lanes {
+        let is_collapsed = collapsed.contains(&key);
+        rows.push(SwimlaneRow::Header {
+            key,
+            count: lane.records.len(),
+            extent: lane.extent,
+            collapsed: is_collapsed,
+        });
+        if !is_collapsed {
+            lane.records.sort();
+            rows.extend(lane.records.into_iter().map(|(_, record_id)| SwimlaneRow::Record { record_id }));
+        }
+    }
+    rows
+}
+
+/// Suggests attribute keys for the lane selector.
+///
+/// Scans up to a fixed number of records and returns the attribute keys
+/// found, sorted alphabetically.
+pub fn sample_attribute_keys(trace: &DynTraceData) -> Vec<String> {
+    let mut keys = BTreeSet::new();
+    for record in trace.records_iter().take(KEY_SAMPLE_LIMIT) {
+        for (key, _) in record.attrs() {
+            keys.insert(key);
+        }
+    }
+    keys.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rjets::{JetsTraceReader, TraceReader};
+
+    fn load_test_trace() -> DynTraceData {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/trace.jets");
+        JetsTraceReader::new().read(path).expect("test trace should load")
+    }
+
+    fn headers(rows: &[SwimlaneRow]) -> Vec<(String, usize)> {
+        rows.iter()
+            .filter_map(|row| match row {
+                SwimlaneRow::Header { key, count, .. } => Some((key.clone(), *count)),
+                SwimlaneRow::Record { .. } => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_groups_records_by_attribute() {
+        let trace = load_test_trace();
+        let rows = build_swimlane_rows(&trace, "opcode", &HashSet::new(), &HashSet::new(), &HashSet::new());
+
+        let lanes = headers(&rows);
+        assert!(!lanes.is_empty());
+        let total: usize = lanes.iter().map(|(_, count)| count).sum();
+        assert_eq!(rows.len(), lanes.len() + total);
+
+        // Lanes sorted by key, each record row belongs to the preceding header
+        let keys: Vec<_> = lanes.iter().map(|(k, _)| k.clone()).collect();
+        let mut sorted = keys.clone();
+        sorted.sort();
+        assert_eq!(keys, sorted);
+
+        let mut current = None;
+        for row in &rows {
+            match row {
+                SwimlaneRow::Header { key, .. } => current = Some(key.clone()),
+                SwimlaneRow::Record { record_id } => {
+                    let record = trace.get_record(*record_id).unwrap();
+                    assert_eq!(Some(lane_key(&record.attr("opcode").unwrap())), current);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_collapsed_lane_keeps_only_header() {
+        let trace = load_test_trace();
+        let all = build_swimlane_rows(&trace, "opcode", &HashSet::new(), &HashSet::new(), &HashSet::new());
+        let (first_key, first_count) = headers(&all)[0].clone();
+
+        let collapsed: HashSet<String> = [first_key].into_iter().collect();
+        let rows = build_swimlane_rows(&trace, "opcode", &collapsed, &HashSet::new(), &HashSet::new());
+        assert_eq!(rows.len(), all.len() - first_count);
+        assert!(matches!(rows[0], SwimlaneRow::Header { collapsed: true, .. }));
+    }
+
+    #[test]
+    fn test_numeric_lanes_sort_numerically() {
+        let mut lanes = [
+            ("10".to_string(), Lane { sort_value: serde_json::json!(10), records: vec![], extent: None }),
+            ("9".to_string(), Lane { sort_value: serde_json::json!(9), records: vec![], extent: None }),
+            ("a".to_string(), Lane { sort_value: serde_json::json!("a"), records: vec![], extent: None }),
+        ];
+        lanes.sort_by(compare_lanes);
+        let keys: Vec<_> = lanes.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(keys, vec!["9", "10", "a"]);
+    }
+
+    #[test]
+    fn test_sample_attribute_keys() {
+        let trace = load_test_trace();
+        let keys = sample_attribute_keys(&trace);
+        assert!(keys.contains(&"opcode".to_string()));
+    }
+}
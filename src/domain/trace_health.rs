@@ -0,0 +1,227 @@
+//! Trace health diagnostics.
+//!
+//! The parser accepts traces that are structurally off and fixes them up
+//! silently: a record whose parent is missing is neither a root nor a child,
+//! so it never shows in the tree; a repeated `record_end` overwrites the
+//! earlier one. The health check walks every record once and lists these
+//! anomalies per kind, together with the parser's warnings, so producers can
+//! see (and jump to) what the viewer papered over.
+
+use crate::reporting::{Report, Reportable};
+use rjets::{DynTraceData, TraceData, TraceMetadata, TraceRecord};
+
+/// Most issues listed per kind; further ones are only counted.
+pub const HEALTH_ISSUE_LIMIT: usize = 500;
+
+/// Kind of trace anomaly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HealthIssueKind {
+    /// `parent_id` names a record that is not in the trace
+    Orphaned,
+    /// Starts before its parent starts
+    StartsBeforeParent,
+    /// `record_end` clock lies before the record's start
+    EndBeforeStart,
+    /// Ends after its parent's (read, not inferred) end
+    EndsAfterParent,
+}
+
+impl HealthIssueKind {
+    /// All kinds, in display order.
+    pub const ALL: [HealthIssueKind; 4] = [
+        HealthIssueKind::Orphaned,
+        HealthIssueKind::StartsBeforeParent,
+        HealthIssueKind::EndBeforeStart,
+        HealthIssueKind::EndsAfterParent,
+    ];
+
+    /// Returns the section title.
+    pub fn title(self) -> &'static str {
+        match self {
+            HealthIssueKind::Orphaned => "Orphaned records",
+            HealthIssueKind::StartsBeforeParent => "Children starting before their parent",
+            HealthIssueKind::EndBeforeStart => "Ends before start",
+            HealthIssueKind::EndsAfterParent => "Children ending after their parent",
+        }
+    }
+
+    /// Returns what the viewer does with such records.
+    pub fn consequence(self) -> &'static str {
+        match self {
+            HealthIssueKind::Orphaned => "Not shown in the tree or timeline; only reachable from here.",
+            HealthIssueKind::StartsBeforeParent => "Drawn outside the parent's bar.",
+            HealthIssueKind::EndBeforeStart => "Negative duration; the bar shrinks to a sliver at its start.",
+            HealthIssueKind::EndsAfterParent => "Drawn past the parent's end; often a repeated or late record_end.",
+        }
+    }
+}
+
+/// One anomaly of one record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthIssue {
+    pub kind: HealthIssueKind,
+    pub record_id: u64,
+    /// Record name, for the list
+    pub name: String,
+    /// Offending values, e.g. "starts at 5, parent at 10"
+    pub detail: String,
+}
+
+/// Result of a health check.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TraceHealth {
+    /// Issues per kind in record order, at most [`HEALTH_ISSUE_LIMIT`] each
+    pub issues: Vec<HealthIssue>,
+    /// Total issues per kind, indexed like [`HealthIssueKind::ALL`]
+    pub counts: [usize; HealthIssueKind::ALL.len()],
+    /// Non-fatal problems the parser reported
+    pub parser_warnings: Vec<String>,
+    /// Records checked
+    pub records: usize,
+}
+
+impl TraceHealth {
+    /// Checks every record of a trace.
+    pub fn check(trace: &DynTraceData) -> Self {
+        let mut health = TraceHealth {
+            parser_warnings: trace.metadata().warnings(),
+            ..Default::default()
+        };
+        for record in trace.records_iter() {
+            health.records += 1;
+            let (clk, end) = (record.clk(), record.end_clk());
+            if let Some(end) = end.filter(|&end| end < clk) {
+                health.push(HealthIssueKind::EndBeforeStart, &record, format!("starts at {}, ends at {}", clk, end));
+            }
+            let Some(parent_id) = record.parent_id() else { continue };
+            let Some(parent) = trace.get_record(parent_id) else {
+                health.push(HealthIssueKind::Orphaned, &record, format!("parent {} does not exist", parent_id));
+                continue;
+            };
+            if clk < parent.clk() {
+                let detail = format!("starts at {}, parent {} at {}", clk, parent.external_id(), parent.clk());
+                health.push(HealthIssueKind::StartsBeforeParent, &record, detail);
+            }
+            if let (Some(end), Some(parent_end)) = (end, parent.end_clk()) {
+                if end > parent_end && !parent.end_inferred() {
+                    let detail = format!("ends at {}, parent {} at {}", end, parent.external_id(), parent_end);
+                    health.push(HealthIssueKind::EndsAfterParent, &record, detail);
+                }
+            }
+        }
+        health
+    }
+
+    /// Returns the total number of issues of a kind.
+    pub fn count(&self, kind: HealthIssueKind) -> usize {
+        self.counts[kind as usize]
+    }
+
+    /// Returns true if no issue and no parser warning was found.
+    pub fn is_healthy(&self) -> bool {
+        self.counts.iter().all(|&count| count == 0) && self.parser_warnings.is_empty()
+    }
+
+    /// Returns the listed issues of a kind.
+    pub fn issues_of(&self, kind: HealthIssueKind) -> impl Iterator<Item = &HealthIssue> {
+        self.issues.iter().filter(move |issue| issue.kind == kind)
+    }
+
+    fn push<'a, R: TraceRecord<'a>>(&mut self, kind: HealthIssueKind, record: &R, detail: String) {
+        self.counts[kind as usize] += 1;
+        if self.counts[kind as usize] <= HEALTH_ISSUE_LIMIT {
+            self.issues.push(HealthIssue {
+                kind,
+                record_id: record.id(),
+                name: record.name(),
+                detail,
+            });
+        }
+    }
+}
+
+impl Reportable for TraceHealth {
+    fn to_report(&self) -> Report {
+        let mut report = Report::new("Trace Health");
+        let mut summary = vec![("Records checked".to_string(), self.records.to_string())];
+        summary.extend(HealthIssueKind::ALL.map(|kind| (kind.title().to_string(), self.count(kind).to_string())));
+        summary.push(("Parser warnings".to_string(), self.parser_warnings.len().to_string()));
+        let section = report.section("Summary");
+        section.key_values(summary);
+        if self.is_healthy() {
+            section.paragraph("No anomalies found.");
+        }
+
+        for kind in HealthIssueKind::ALL {
+            let count = self.count(kind);
+            if count == 0 {
+                continue;
+            }
+            let rows = self
+                .issues_of(kind)
+                .map(|issue| vec![issue.record_id.to_string(), issue.name.clone(), issue.detail.clone()])
+                .collect();
+            let section = report.section(kind.title());
+            section.paragraph(kind.consequence()).table(&["ID", "Name", "Detail"], rows);
+            if count > HEALTH_ISSUE_LIMIT {
+                section.paragraph(format!("… and {} more.", count - HEALTH_ISSUE_LIMIT));
+            }
+        }
+
+        if !self.parser_warnings.is_empty() {
+            let rows = self.parser_warnings.iter().map(|warning| vec![warning.clone()]).collect();
+            report.section("Parser Warnings").table(&["Warning"], rows);
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_finds_each_kind() {
+        let input = concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":10,"name":"core","record_type":"T","id":1,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record","clk":5,"name":"early","record_type":"Op","id":2,"parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"record","clk":20,"name":"late","record_type":"Op","id":3,"parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"record","clk":30,"name":"lost","record_type":"Op","id":4,"parent_id":99,"description":""}"#, "\n",
+            r#"{"type":"record","clk":40,"name":"fine","record_type":"Op","id":5,"parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"record_end","clk":15,"record_id":2}"#, "\n",
+            r#"{"type":"record_end","clk":60,"record_id":3}"#, "\n",
+            r#"{"type":"record_end","clk":25,"record_id":4}"#, "\n",
+            r#"{"type":"record_end","clk":45,"record_id":5}"#, "\n",
+            r#"{"type":"record_end","clk":50,"record_id":1}"#, "\n",
+            r#"{"type":"record_end","clk":50,"record_id":1}"#, "\n",
+        );
+        let trace = DynTraceData::Jets(rjets::parse_trace_from_reader(input.as_bytes()).unwrap());
+        let health = TraceHealth::check(&trace);
+
+        assert_eq!(health.records, 5, "orphans are checked too");
+        let ids = |kind| health.issues_of(kind).map(|issue| issue.record_id).collect::<Vec<_>>();
+        assert_eq!(ids(HealthIssueKind::Orphaned), vec![4]);
+        assert_eq!(ids(HealthIssueKind::StartsBeforeParent), vec![2]);
+        assert_eq!(ids(HealthIssueKind::EndBeforeStart), vec![4]);
+        assert_eq!(ids(HealthIssueKind::EndsAfterParent), vec![3]);
+        assert_eq!(health.count(HealthIssueKind::EndsAfterParent), 1);
+        assert_eq!(health.parser_warnings.len(), 1, "repeated record_end");
+        assert!(!health.is_healthy());
+
+        let report = health.to_report();
+        let headings: Vec<&str> = report.sections.iter().map(|section| section.heading.as_str()).collect();
+        assert_eq!(
+            headings,
+            [
+                "Summary",
+                "Orphaned records",
+                "Children starting before their parent",
+                "Ends before start",
+                "Children ending after their parent",
+                "Parser Warnings",
+            ]
+        );
+        assert_eq!(TraceHealth::default().to_report().sections.len(), 1, "a healthy trace has only a summary");
+    }
+}
@@ -4,25 +4,35 @@
 //! - Calculating subtree sizes
 //! - Computing node depths
 //! - Determining visible node counts
+//! - Choosing the records to expand when a trace is opened
 //!
 //! These functions are extracted from the main application to enable
 //! independent testing and clearer separation of domain logic.
 
 use crate::cache::TreeCache;
-use crate::domain::visibility::{self, VisibilityStrategy, ChildIndexProvider};
+use crate::domain::column_filters::ColumnFilters;
+use crate::domain::visibility::{self, VisibilityStrategy, ChildIndexProvider, ClockStride};
 use crate::state::SortSpec;
 use rjets::{TraceData, TraceRecord, DynTraceData, DynTraceRecord};
+use std::borrow::Cow;
 use std::collections::HashSet;
 
+/// Iterates the trace's root IDs, skipping roots hidden by the root selector.
+pub fn visible_roots<'t, T: TraceData>(trace: &'t T, hidden_roots: &'t HashSet<u64>) -> impl Iterator<Item = u64> + 't {
+    trace.roots().iter().copied().filter(move |id| !hidden_roots.contains(id))
+}
+
 /// Gets the total number of visible nodes (uses cache if available).
 ///
 /// # Arguments
 /// * `trace` - The trace data containing the tree structure
 /// * `expanded_nodes` - Set of IDs for expanded nodes
+/// * `hidden_roots` - Roots excluded by the root selector
 /// * `cache` - Tree cache for memoizing results
 pub fn get_total_visible_nodes(
     trace: &DynTraceData,
     expanded_nodes: &HashSet<u64>,
+    hidden_roots: &HashSet<u64>,
     cache: &mut TreeCache,
 ) -> usize {
     if let Some(total) = cache.total_visible_nodes {
@@ -30,7 +40,7 @@ pub fn get_total_visible_nodes(
     }
 
     let mut total = 0;
-    for root_id in trace.root_ids() {
+    for root_id in visible_roots(trace, hidden_roots) {
         total += get_subtree_size(root_id, trace, expanded_nodes, cache);
     }
 
@@ -38,75 +48,6 @@ pub fn get_total_visible_nodes(
     total
 }
 
-/// Gets the maximum visible depth (uses cache if available).
-///
-/// # Arguments
-/// * `trace` - The trace data containing the tree structure
-/// * `expanded_nodes` - Set of IDs for expanded nodes
-/// * `cache` - Tree cache for memoizing results
-pub fn get_max_visible_depth(
-    trace: &DynTraceData,
-    expanded_nodes: &HashSet<u64>,
-    cache: &mut TreeCache,
-) -> usize {
-    if let Some(depth) = cache.max_visible_depth {
-        return depth;
-    }
-
-    let depth = calculate_max_visible_depth(trace, expanded_nodes);
-    cache.max_visible_depth = Some(depth);
-    depth
-}
-
-/// Calculates the maximum visible depth in the tree, accounting for expanded nodes.
-///
-/// # Arguments
-/// * `trace` - The trace data containing the tree structure
-/// * `expanded_nodes` - Set of IDs for expanded nodes
-pub fn calculate_max_visible_depth(
-    trace: &DynTraceData,
-    expanded_nodes: &HashSet<u64>,
-) -> usize {
-    let mut max_depth = 0;
-    for root_id in trace.root_ids() {
-        let depth = calculate_node_depth(root_id, 0, trace, expanded_nodes);
-        max_depth = max_depth.max(depth);
-    }
-    max_depth
-}
-
-/// Recursively calculates the depth of a node and its visible children.
-///
-/// # Arguments
-/// * `record_id` - The ID of the record to calculate depth for
-/// * `current_depth` - The current depth in the recursion
-/// * `trace` - The trace data containing the tree structure
-/// * `expanded_nodes` - Set of IDs for expanded nodes
-pub fn calculate_node_depth(
-    record_id: u64,
-    current_depth: usize,
-    trace: &DynTraceData,
-    expanded_nodes: &HashSet<u64>,
-) -> usize {
-    let mut max_depth = current_depth;
-
-    if expanded_nodes.contains(&record_id) {
-        if let Some(record) = trace.get_record(record_id) {
-            for child in record.children() {
-                let child_depth = calculate_node_depth(
-                    child.id(),
-                    current_depth + 1,
-                    trace,
-                    expanded_nodes,
-                );
-                max_depth = max_depth.max(child_depth);
-            }
-        }
-    }
-
-    max_depth
-}
-
 /// Gets the subtree size from cache or calculates it.
 ///
 /// # Arguments
@@ -160,42 +101,6 @@ pub fn calculate_subtree_size(
     total
 }
 
-/// Checks if all children of a node are collapsed (uses cache if available).
-///
-/// # Arguments
-/// * `parent_id` - The ID of the parent record
-/// * `trace` - The trace data containing the tree structure
-/// * `expanded_nodes` - Set of IDs for expanded nodes
-/// * `cache` - Tree cache for memoizing results
-pub fn are_all_children_collapsed_cached(
-    parent_id: u64,
-    trace: &DynTraceData,
-    expanded_nodes: &HashSet<u64>,
-    cache: &mut TreeCache,
-) -> bool {
-    if let Some(&collapsed) = cache.all_children_collapsed.get(&parent_id) {
-        return collapsed;
-    }
-
-    let result = if let Some(record) = trace.get_record(parent_id) {
-        let num_children = record.num_children();
-        if num_children == 0 {
-            true // No children = treat as collapsed
-        } else {
-            (0..num_children).all(|i| {
-                record.child_at(i)
-                    .map(|child| !expanded_nodes.contains(&child.id()))
-                    .unwrap_or(true)
-            })
-        }
-    } else {
-        true
-    };
-
-    cache.all_children_collapsed.insert(parent_id, result);
-    result
-}
-
 /// A visible node with its row index and depth.
 ///
 /// Used by the visibility strategy system to return flattened tree nodes
@@ -252,31 +157,44 @@ where
     }
 }
 
-/// Generic core function for collecting visible nodes with a strategy.
-///
-/// This is a unified function that replaces the separate filtered/unfiltered traversal
-/// paths. It uses the visibility strategy pattern to determine which nodes to include.
-///
-/// # Type Parameters
-/// * `T` - The trace data type
-/// * `S` - The visibility strategy type
+/// Child index provider that uses cached sorted orderings.
 ///
-/// # Arguments
-/// * `trace` - The trace data
-/// * `expanded_nodes` - Set of expanded node IDs
-/// * `strategy` - The visibility strategy to apply
+/// This provider wraps the TreeCache and lends out the sorted child indices
+/// precomputed per (parent, sort spec), so traversing a sorted tree does not
+/// allocate an order per expanded parent on every frame.
+struct CacheChildOrder<'t> {
+    cache: &'t TreeCache,
+    sort: Option<SortSpec>,
+}
+
+impl<'a> ChildIndexProvider<'a, DynTraceRecord<'a>> for CacheChildOrder<'_> {
+    fn child_indices(
+        &self,
+        parent: &DynTraceRecord<'a>,
+        _depth: usize,
+    ) -> Option<Cow<'_, [usize]>> {
+        let sort = self.sort?;
+        let pid = parent.id();
+        self.cache.sorted_children.get(&(pid, sort)).map(|order| Cow::Borrowed(order.as_slice()))
+    }
+}
+
+/// Generic core function for collecting visible nodes with a strategy and custom child ordering.
 ///
-/// # Returns
-/// Vector of filtered visible nodes with row indices and depths
-fn collect_visible_nodes_with_strategy_generic<T, S>(
+/// Expanded records show their children, in the order the ChildIndexProvider
+/// gives (e.g., sorting); hidden roots are skipped.
+fn collect_visible_nodes_with_strategy_and_order_generic<T, S, P>(
     trace: &T,
     expanded_nodes: &HashSet<u64>,
+    hidden_roots: &HashSet<u64>,
     strategy: &S,
+    provider: P,
 ) -> Vec<FilteredVisibleNode>
 where
     T: rjets::TraceData,
     for<'a> S: VisibilityStrategy<'a, T::Record<'a>>,
     for<'a> T::Record<'a>: rjets::TraceRecord<'a>,
+    for<'a> P: ChildIndexProvider<'a, T::Record<'a>>,
 {
     // Wrap the strategy with expansion-aware logic
     let expansion_strategy: ExpansionAwareStrategy<'_, S, T::Record<'_>> = ExpansionAwareStrategy {
@@ -286,14 +204,12 @@ where
     };
 
     // Get roots as owned records
-    let roots: Vec<T::Record<'_>> = trace
-        .root_ids()
-        .iter()
-        .filter_map(|&id| trace.get_record(id))
+    let roots: Vec<T::Record<'_>> = visible_roots(trace, hidden_roots)
+        .filter_map(|id| trace.get_record(id))
         .collect();
 
-    // Traverse using the strategy and assign row indices
-    visibility::traverse_visible(roots, &expansion_strategy)
+    // Traverse using the strategy with custom ordering and assign row indices
+    visibility::traverse_visible_with_order(roots, &expansion_strategy, provider)
         .enumerate()
         .map(|(row_index, node)| FilteredVisibleNode {
             record_id: node.record.id(),
@@ -305,135 +221,100 @@ where
         .collect()
 }
 
-/// Collects visible nodes using a visibility strategy, handling expansion state.
-///
-/// Public API function that works with DynTraceData.
-///
-/// # Arguments
-/// * `trace` - The trace data
-/// * `expanded_nodes` - Set of expanded node IDs
-/// * `strategy` - The visibility strategy to apply
-///
-/// # Returns
-/// Vector of filtered visible nodes with row indices and depths
-pub fn collect_visible_nodes_with_strategy<S>(
+/// Like `collect_visible_nodes_with_strategy_and_order_generic`, but also hides
+/// records whose type is in `hidden_types` (skipping the wrapper when none are hidden).
+fn collect_with_type_filter<S>(
     trace: &DynTraceData,
     expanded_nodes: &HashSet<u64>,
-    strategy: &S,
+    hidden_roots: &HashSet<u64>,
+    hidden_types: &HashSet<String>,
+    hide_empty_parents: bool,
+    strategy: S,
+    provider: CacheChildOrder<'_>,
 ) -> Vec<FilteredVisibleNode>
 where
     for<'a> S: VisibilityStrategy<'a, DynTraceRecord<'a>>,
 {
-    collect_visible_nodes_with_strategy_generic(trace, expanded_nodes, strategy)
+    if hidden_types.is_empty() {
+        collect_with_empty_parent_filter(trace, expanded_nodes, hidden_roots, hide_empty_parents, strategy, provider)
+    } else {
+        let strategy = visibility::RecordTypeFilterStrategy { base: strategy, hidden_types };
+        collect_with_empty_parent_filter(trace, expanded_nodes, hidden_roots, hide_empty_parents, strategy, provider)
+    }
 }
 
-/// Collects unfiltered visible nodes using the unified strategy system.
-///
-/// This replaces the manual traversal in `collect_nodes_in_range` by using
-/// the UnfilteredStrategy with the visibility system.
-///
-/// # Arguments
-/// * `trace` - The trace data
-/// * `expanded_nodes` - Set of expanded node IDs
+/// Drops parents without visible leaves when `hide_empty_parents` is set.
 ///
-/// # Returns
-/// Vector of all visible nodes (expansion-filtered only)
-pub fn collect_unfiltered_visible_nodes_strategy(
+/// Applied on top of every other filter, so "visible" takes all of them into
+/// account. Rows are numbered after the drop, keeping row indices dense.
+fn collect_with_empty_parent_filter<S>(
     trace: &DynTraceData,
     expanded_nodes: &HashSet<u64>,
-) -> Vec<FilteredVisibleNode> {
-    let strategy = visibility::UnfilteredStrategy;
-    collect_visible_nodes_with_strategy(trace, expanded_nodes, &strategy)
+    hidden_roots: &HashSet<u64>,
+    hide_empty_parents: bool,
+    strategy: S,
+    provider: CacheChildOrder<'_>,
+) -> Vec<FilteredVisibleNode>
+where
+    for<'a> S: VisibilityStrategy<'a, DynTraceRecord<'a>>,
+{
+    if hide_empty_parents {
+        let strategy = visibility::EmptyParentFilterStrategy::new(strategy);
+        collect_visible_nodes_with_strategy_and_order_generic(trace, expanded_nodes, hidden_roots, &strategy, provider)
+    } else {
+        collect_visible_nodes_with_strategy_and_order_generic(trace, expanded_nodes, hidden_roots, &strategy, provider)
+    }
 }
 
-/// Collects viewport-filtered visible nodes using the unified strategy system.
-///
-/// This replaces `collect_filtered_visible_nodes` by using the ViewportFilterStrategy
-/// with the visibility system.
-///
-/// # Arguments
-/// * `trace` - The trace data
-/// * `expanded_nodes` - Set of expanded node IDs
-/// * `viewport_start_clk` - Start of viewport time range
-/// * `viewport_end_clk` - End of viewport time range
-///
-/// # Returns
-/// Vector of viewport-filtered visible nodes
-pub fn collect_viewport_filtered_nodes_strategy(
+/// Like `collect_visible_nodes_with_strategy_and_order_generic`, but also hides
+/// records whose type is in `hidden_types`, leaves outside `clock_stride` or
+/// failing `column_filters` and, with `hide_empty_parents`, parents left
+/// without visible leaves (skipping each wrapper when it would not hide anything).
+#[allow(clippy::too_many_arguments)]
+fn collect_with_record_filters<S>(
     trace: &DynTraceData,
     expanded_nodes: &HashSet<u64>,
-    viewport_start_clk: i64,
-    viewport_end_clk: i64,
-) -> Vec<FilteredVisibleNode> {
-    let strategy = visibility::ViewportFilterStrategy {
-        start: viewport_start_clk,
-        end: viewport_end_clk,
-    };
-    collect_visible_nodes_with_strategy(trace, expanded_nodes, &strategy)
-}
-
-/// Child index provider that uses cached sorted orderings.
-///
-/// This provider wraps the TreeCache and returns sorted child indices
-/// for parents that have been precomputed.
-struct CacheChildOrder<'t> {
-    cache: &'t TreeCache,
-    sort: Option<SortSpec>,
-}
-
-impl<'a> ChildIndexProvider<'a, DynTraceRecord<'a>> for CacheChildOrder<'_> {
-    fn child_indices(
-        &self,
-        parent: &DynTraceRecord<'a>,
-        _depth: usize,
-    ) -> Option<Vec<usize>> {
-        let sort = self.sort?;
-        let pid = parent.id();
-        self.cache.sorted_children.get(&(pid, sort)).cloned()
+    hidden_roots: &HashSet<u64>,
+    hidden_types: &HashSet<String>,
+    clock_stride: Option<ClockStride>,
+    column_filters: &ColumnFilters,
+    hide_empty_parents: bool,
+    strategy: S,
+    provider: CacheChildOrder<'_>,
+) -> Vec<FilteredVisibleNode>
+where
+    for<'a> S: VisibilityStrategy<'a, DynTraceRecord<'a>>,
+{
+    if column_filters.is_empty() {
+        collect_with_stride_filter(trace, expanded_nodes, hidden_roots, hidden_types, clock_stride, hide_empty_parents, strategy, provider)
+    } else {
+        let strategy = visibility::ColumnFilterStrategy { base: strategy, filters: column_filters };
+        collect_with_stride_filter(trace, expanded_nodes, hidden_roots, hidden_types, clock_stride, hide_empty_parents, strategy, provider)
     }
 }
 
-/// Generic core function for collecting visible nodes with a strategy and custom child ordering.
-///
-/// Like `collect_visible_nodes_with_strategy_generic`, but accepts a ChildIndexProvider
-/// for custom child ordering (e.g., sorting).
-fn collect_visible_nodes_with_strategy_and_order_generic<T, S, P>(
-    trace: &T,
+/// Applies the record type, clock stride and empty parent filters of `collect_with_record_filters`.
+#[allow(clippy::too_many_arguments)]
+fn collect_with_stride_filter<S>(
+    trace: &DynTraceData,
     expanded_nodes: &HashSet<u64>,
-    strategy: &S,
-    provider: P,
+    hidden_roots: &HashSet<u64>,
+    hidden_types: &HashSet<String>,
+    clock_stride: Option<ClockStride>,
+    hide_empty_parents: bool,
+    strategy: S,
+    provider: CacheChildOrder<'_>,
 ) -> Vec<FilteredVisibleNode>
 where
-    T: rjets::TraceData,
-    for<'a> S: VisibilityStrategy<'a, T::Record<'a>>,
-    for<'a> T::Record<'a>: rjets::TraceRecord<'a>,
-    for<'a> P: ChildIndexProvider<'a, T::Record<'a>>,
+    for<'a> S: VisibilityStrategy<'a, DynTraceRecord<'a>>,
 {
-    // Wrap the strategy with expansion-aware logic
-    let expansion_strategy: ExpansionAwareStrategy<'_, S, T::Record<'_>> = ExpansionAwareStrategy {
-        base_strategy: strategy,
-        expanded_nodes,
-        _phantom: std::marker::PhantomData,
-    };
-
-    // Get roots as owned records
-    let roots: Vec<T::Record<'_>> = trace
-        .root_ids()
-        .iter()
-        .filter_map(|&id| trace.get_record(id))
-        .collect();
-
-    // Traverse using the strategy with custom ordering and assign row indices
-    visibility::traverse_visible_with_order(roots, &expansion_strategy, provider)
-        .enumerate()
-        .map(|(row_index, node)| FilteredVisibleNode {
-            record_id: node.record.id(),
-            row_index,
-            depth: node.depth,
-            branch_context: node.branch_context,
-            is_last_child: node.is_last_child,
-        })
-        .collect()
+    match clock_stride {
+        Some(stride) => {
+            let strategy = visibility::ClockStrideFilterStrategy { base: strategy, stride };
+            collect_with_type_filter(trace, expanded_nodes, hidden_roots, hidden_types, hide_empty_parents, strategy, provider)
+        }
+        None => collect_with_type_filter(trace, expanded_nodes, hidden_roots, hidden_types, hide_empty_parents, strategy, provider),
+    }
 }
 
 /// Collects unfiltered visible nodes with optional sorting.
@@ -441,20 +322,29 @@ where
 /// # Arguments
 /// * `trace` - The trace data
 /// * `expanded_nodes` - Set of expanded node IDs
+/// * `hidden_roots` - Roots excluded by the root selector
+/// * `hidden_types` - Record types hidden from the legend
+/// * `clock_stride` - Only keep leaves starting in this clock residue class
+/// * `column_filters` - Only keep leaves passing the table header filters
 /// * `cache` - Tree cache containing sorted child indices
 /// * `active_sort` - Optional sort specification
 ///
 /// # Returns
 /// Vector of all visible nodes with optional sorting applied
+#[allow(clippy::too_many_arguments)]
 pub fn collect_unfiltered_visible_nodes_with_sort(
     trace: &DynTraceData,
     expanded_nodes: &HashSet<u64>,
+    hidden_roots: &HashSet<u64>,
+    hidden_types: &HashSet<String>,
+    clock_stride: Option<ClockStride>,
+    column_filters: &ColumnFilters,
     cache: &TreeCache,
     active_sort: Option<SortSpec>,
 ) -> Vec<FilteredVisibleNode> {
     let strategy = visibility::UnfilteredStrategy;
     let provider = CacheChildOrder { cache, sort: active_sort };
-    collect_visible_nodes_with_strategy_and_order_generic(trace, expanded_nodes, &strategy, provider)
+    collect_with_record_filters(trace, expanded_nodes, hidden_roots, hidden_types, clock_stride, column_filters, false, strategy, provider)
 }
 
 /// Collects viewport-filtered visible nodes with optional sorting.
@@ -462,27 +352,238 @@ pub fn collect_unfiltered_visible_nodes_with_sort(
 /// # Arguments
 /// * `trace` - The trace data
 /// * `expanded_nodes` - Set of expanded node IDs
+/// * `hidden_roots` - Roots excluded by the root selector
+/// * `hidden_types` - Record types hidden from the legend
+/// * `clock_stride` - Only keep leaves starting in this clock residue class
+/// * `column_filters` - Only keep leaves passing the table header filters
 /// * `cache` - Tree cache containing sorted child indices
 /// * `active_sort` - Optional sort specification
 /// * `viewport_start_clk` - Start of viewport time range
 /// * `viewport_end_clk` - End of viewport time range
+/// * `hide_empty_parents` - Drop parents without a leaf in the viewport
 ///
 /// # Returns
 /// Vector of viewport-filtered visible nodes with optional sorting applied
+#[allow(clippy::too_many_arguments)]
 pub fn collect_viewport_filtered_nodes_with_sort(
     trace: &DynTraceData,
     expanded_nodes: &HashSet<u64>,
+    hidden_roots: &HashSet<u64>,
+    hidden_types: &HashSet<String>,
+    clock_stride: Option<ClockStride>,
+    column_filters: &ColumnFilters,
     cache: &TreeCache,
     active_sort: Option<SortSpec>,
     viewport_start_clk: i64,
     viewport_end_clk: i64,
+    hide_empty_parents: bool,
 ) -> Vec<FilteredVisibleNode> {
     let strategy = visibility::ViewportFilterStrategy {
         start: viewport_start_clk,
         end: viewport_end_clk,
     };
     let provider = CacheChildOrder { cache, sort: active_sort };
-    collect_visible_nodes_with_strategy_and_order_generic(trace, expanded_nodes, &strategy, provider)
+    collect_with_record_filters(trace, expanded_nodes, hidden_roots, hidden_types, clock_stride, column_filters, hide_empty_parents, strategy, provider)
+}
+
+/// Keeps only pinned records and renumbers the remaining rows.
+///
+/// Depths are kept so pinned rows stay indented like in the full tree.
+/// An empty `pinned` set keeps every row.
+pub fn retain_pinned_rows(mut nodes: Vec<FilteredVisibleNode>, pinned: &HashSet<u64>) -> Vec<FilteredVisibleNode> {
+    if pinned.is_empty() {
+        return nodes;
+    }
+    nodes.retain(|node| pinned.contains(&node.record_id));
+    for (row_index, node) in nodes.iter_mut().enumerate() {
+        node.row_index = row_index;
+    }
+    nodes
+}
+
+/// Number of children requested per `children_range` call when paging through
+/// a parent whose children have expanded subtrees of their own.
+const CHILD_PAGE_SIZE: usize = 256;
+
+/// Collects only the unfiltered, naturally ordered rows in `first_row..=last_row`.
+///
+/// Produces the same nodes as `collect_unfiltered_visible_nodes_with_sort` (no
+/// hidden types, no sort) restricted to the window, but skips whole subtrees
+/// by their cached sizes and fetches children through `children_range`, so a
+/// backend is only asked for the slices that land on screen.
+///
+/// # Arguments
+/// * `trace` - The trace data
+/// * `expanded_nodes` - Set of expanded node IDs
+/// * `hidden_roots` - Roots excluded by the root selector
+/// * `cache` - Tree cache holding subtree sizes
+/// * `first_row` - First row of the window (inclusive)
+/// * `last_row` - Last row of the window (inclusive)
+pub fn collect_unfiltered_window_nodes(
+    trace: &DynTraceData,
+    expanded_nodes: &HashSet<u64>,
+    hidden_roots: &HashSet<u64>,
+    cache: &mut TreeCache,
+    first_row: usize,
+    last_row: usize,
+) -> Vec<FilteredVisibleNode> {
+    // Parents with at least one expanded child; the others have one row per child
+    let expanded_parents: HashSet<u64> = expanded_nodes
+        .iter()
+        .filter_map(|&id| trace.get_record(id)?.parent_id())
+        .collect();
+    let mut window = WindowCollector {
+        trace,
+        expanded_nodes,
+        expanded_parents: &expanded_parents,
+        first_row,
+        last_row,
+        nodes: Vec::new(),
+    };
+
+    let mut roots = visible_roots(trace, hidden_roots).peekable();
+    let mut row = 0;
+    while let Some(root_id) = roots.next() {
+        if row > last_row {
+            break;
+        }
+        let size = get_subtree_size(root_id, trace, expanded_nodes, cache);
+        if row + size > first_row {
+            let is_last = roots.peek().is_none();
+            window.visit(root_id, row, 0, Vec::new(), is_last, cache);
+        }
+        row += size;
+    }
+    window.nodes
+}
+
+/// Depth-first walk state for `collect_unfiltered_window_nodes`.
+struct WindowCollector<'t> {
+    trace: &'t DynTraceData,
+    expanded_nodes: &'t HashSet<u64>,
+    expanded_parents: &'t HashSet<u64>,
+    first_row: usize,
+    last_row: usize,
+    nodes: Vec<FilteredVisibleNode>,
+}
+
+impl WindowCollector<'_> {
+    /// Emits `record_id` (at `row`) and the part of its visible subtree inside the window.
+    fn visit(
+        &mut self,
+        record_id: u64,
+        row: usize,
+        depth: usize,
+        branch_context: Vec<bool>,
+        is_last_child: bool,
+        cache: &mut TreeCache,
+    ) {
+        let mut child_context = branch_context.clone();
+        child_context.push(!is_last_child);
+        if row >= self.first_row {
+            self.nodes.push(FilteredVisibleNode { record_id, row_index: row, depth, branch_context, is_last_child });
+        }
+        if !self.expanded_nodes.contains(&record_id) {
+            return;
+        }
+        let num_children = match self.trace.num_children_hint(record_id) {
+            Some(count) => count,
+            None => self.trace.get_record(record_id).map_or(0, |r| r.num_children()),
+        };
+        let first_child_row = row + 1;
+
+        if !self.expanded_parents.contains(&record_id) {
+            // Every child is a single row: fetch exactly the slice on screen
+            let skip = self.first_row.saturating_sub(first_child_row);
+            if skip >= num_children || first_child_row + skip > self.last_row {
+                return;
+            }
+            let len = (self.last_row + 1 - (first_child_row + skip)).min(num_children - skip);
+            for (offset, child) in self.trace.children_range(record_id, skip, len).into_iter().enumerate() {
+                let index = skip + offset;
+                self.nodes.push(FilteredVisibleNode {
+                    record_id: child.id(),
+                    row_index: first_child_row + index,
+                    depth: depth + 1,
+                    branch_context: child_context.clone(),
+                    is_last_child: index == num_children - 1,
+                });
+            }
+            return;
+        }
+
+        // Some children are expanded: page through them, skipping subtrees above the window
+        let mut child_row = first_child_row;
+        let mut start = 0;
+        while start < num_children && child_row <= self.last_row {
+            let page: Vec<u64> = self
+                .trace
+                .children_range(record_id, start, CHILD_PAGE_SIZE)
+                .into_iter()
+                .map(|child| child.id())
+                .collect();
+            if page.is_empty() {
+                break;
+            }
+            for (offset, child_id) in page.iter().enumerate() {
+                if child_row > self.last_row {
+                    break;
+                }
+                let size = get_subtree_size(*child_id, self.trace, self.expanded_nodes, cache);
+                if child_row + size > self.first_row {
+                    let is_last = start + offset == num_children - 1;
+                    self.visit(*child_id, child_row, depth + 1, child_context.clone(), is_last, cache);
+                }
+                child_row += size;
+            }
+            start += page.len();
+        }
+    }
+}
+
+/// Returns the records with children less than `depth` levels below the
+/// roots (roots are level 0).
+///
+/// Expanding them shows the first `depth` levels below the roots.
+pub fn parents_above_depth(trace: &DynTraceData, depth: usize) -> Vec<u64> {
+    let mut parents = Vec::new();
+    let mut level: Vec<DynTraceRecord<'_>> = trace.roots().iter().filter_map(|&id| trace.get_record(id)).collect();
+    for _ in 0..depth {
+        let mut next = Vec::new();
+        for record in level.iter().filter(|record| record.num_children() > 0) {
+            parents.push(record.id());
+            next.extend((0..record.num_children()).filter_map(|i| record.child_at(i)));
+        }
+        level = next;
+    }
+    parents
+}
+
+/// Returns the record with the longest duration (the earliest one on ties),
+/// or None if no record has ended.
+pub fn longest_record(trace: &DynTraceData) -> Option<u64> {
+    trace
+        .records_iter()
+        .filter_map(|record| record.duration().map(|duration| (duration, std::cmp::Reverse((record.clk(), record.id())))))
+        .max()
+        .map(|(_, std::cmp::Reverse((_, id)))| id)
+}
+
+/// Returns the range to fit the viewport to after expanding `record_id`, or
+/// None if any of its children overlaps `view_start..=view_end`.
+///
+/// The range covers the record and its children, so it is the record's own
+/// extent unless the record is open or children outlast it.
+pub fn expansion_fit_range(trace: &DynTraceData, record_id: u64, view_start: i64, view_end: i64) -> Option<(i64, i64)> {
+    let record = trace.get_record(record_id)?;
+    let span = |record: &DynTraceRecord<'_>| (record.clk(), record.end_clk().unwrap_or(record.clk()));
+    let children: Vec<(i64, i64)> = (0..record.num_children()).filter_map(|i| record.child_at(i)).map(|child| span(&child)).collect();
+    if children.is_empty() || children.iter().any(|&(start, end)| end >= view_start && start <= view_end) {
+        return None;
+    }
+    Some(children.iter().fold(span(&record), |(start, end), &(child_start, child_end)| {
+        (start.min(child_start), end.max(child_end))
+    }))
 }
 
 #[cfg(test)]
@@ -501,7 +602,7 @@ mod strategy_tests {
         for<'a> T::Record<'a>: rjets::TraceRecord<'a>,
     {
         let strategy = visibility::UnfilteredStrategy;
-        collect_visible_nodes_with_strategy_generic(trace, expanded_nodes, &strategy)
+        collect_visible_nodes_with_strategy_and_order_generic(trace, expanded_nodes, &HashSet::new(), &strategy, visibility::NaturalChildOrder)
     }
 
     /// Test-only generic helper for viewport filtering
@@ -519,7 +620,7 @@ mod strategy_tests {
             start: viewport_start_clk,
             end: viewport_end_clk,
         };
-        collect_visible_nodes_with_strategy_generic(trace, expanded_nodes, &strategy)
+        collect_visible_nodes_with_strategy_and_order_generic(trace, expanded_nodes, &HashSet::new(), &strategy, visibility::NaturalChildOrder)
     }
 
     // Mock implementations for testing using Arc for shared ownership
@@ -550,9 +651,9 @@ mod strategy_tests {
 
     // Mock event for testing
     #[derive(Clone, Copy)]
-    struct MockEvent<'a>(&'a ());
+    struct MockEvent<'a>(std::marker::PhantomData<&'a ()>);
 
-    impl<'a> rjets::AttributeAccessor for MockEvent<'a> {
+    impl rjets::AttributeAccessor for MockEvent<'_> {
         fn attr_count(&self) -> u64 { 0 }
         fn attr(&self, _key: &str) -> Option<serde_json::Value> { None }
         fn attr_at(&self, _index: u64) -> Option<(String, serde_json::Value)> { None }
@@ -577,8 +678,8 @@ mod strategy_tests {
             MockMetadata
         }
 
-        fn root_ids(&self) -> Vec<u64> {
-            self.roots.clone()
+        fn roots(&self) -> &[u64] {
+            &self.roots
         }
 
         fn get_record(&self, id: u64) -> Option<Self::Record<'_>> {
@@ -586,7 +687,7 @@ mod strategy_tests {
         }
     }
 
-    impl<'a> rjets::AttributeAccessor for &'a MockRecord {
+    impl rjets::AttributeAccessor for &MockRecord {
         fn attr_count(&self) -> u64 { 0 }
         fn attr(&self, _key: &str) -> Option<serde_json::Value> { None }
         fn attr_at(&self, _index: u64) -> Option<(String, serde_json::Value)> { None }
@@ -607,6 +708,9 @@ mod strategy_tests {
         fn name(&self) -> String {
             "test".to_string()
         }
+        fn record_type(&self) -> String {
+            "Test".to_string()
+        }
         fn id(&self) -> u64 {
             self.id
         }
@@ -767,4 +871,155 @@ mod strategy_tests {
         assert_eq!(nodes.len(), 1);
         assert_eq!(nodes[0].record_id, 1);
     }
+
+    #[test]
+    fn test_hidden_roots_excluded() {
+        // Three independent roots; hide the middle one
+        let mut records = HashMap::new();
+        for id in 1..=3 {
+            records.insert(id, Arc::new(MockRecord {
+                id,
+                clk: id as i64 * 10,
+                children: vec![],
+            }));
+        }
+        let trace = MockTrace {
+            records,
+            roots: vec![1, 2, 3],
+        };
+
+        let hidden: HashSet<u64> = [2].into_iter().collect();
+        assert_eq!(visible_roots(&trace, &hidden).collect::<Vec<_>>(), vec![1, 3]);
+
+        let nodes = collect_visible_nodes_with_strategy_and_order_generic(
+            &trace,
+            &HashSet::new(),
+            &hidden,
+            &visibility::UnfilteredStrategy,
+            visibility::NaturalChildOrder,
+        );
+        let ids: Vec<u64> = nodes.iter().map(|n| n.record_id).collect();
+        assert_eq!(ids, vec![1, 3]);
+        assert_eq!(nodes[1].row_index, 1);
+
+        let pinned: HashSet<u64> = [3].into_iter().collect();
+        let nodes = retain_pinned_rows(nodes, &pinned);
+        assert_eq!(nodes.len(), 1);
+        assert_eq!((nodes[0].record_id, nodes[0].row_index), (3, 0));
+    }
+
+    #[test]
+    fn test_window_nodes_match_full_collection() {
+        use rjets::{TraceReader, VirtualTraceReader};
+
+        let trace = VirtualTraceReader::new().read("").unwrap();
+        // Expand most parents so both the paging and the single-row slice paths run
+        let mut expanded = HashSet::new();
+        let mut stack = trace.root_ids();
+        while let Some(id) = stack.pop() {
+            let record = trace.get_record(id).unwrap();
+            if record.num_children() > 0 && id % 4 != 0 {
+                expanded.insert(id);
+                stack.extend(record.children().map(|c| c.id()));
+            }
+        }
+        let roots = trace.root_ids();
+        let hidden_roots: HashSet<u64> = roots.iter().copied().take(roots.len() - 1).take(1).collect();
+        let mut cache = TreeCache::new();
+        let full = collect_unfiltered_visible_nodes_with_sort(
+            &trace, &expanded, &hidden_roots, &HashSet::new(), None, &ColumnFilters::default(), &cache, None,
+        );
+        assert!(full.len() > 60, "only {} rows", full.len());
+
+        for (first, last) in [(0, 40), (17, 90), (full.len() / 2, full.len() / 2 + 60), (full.len() - 5, full.len() + 20)] {
+            let window = collect_unfiltered_window_nodes(&trace, &expanded, &hidden_roots, &mut cache, first, last);
+            let expected: Vec<&FilteredVisibleNode> =
+                full.iter().filter(|n| n.row_index >= first && n.row_index <= last).collect();
+            assert_eq!(window.len(), expected.len(), "window {first}..={last}");
+            for (got, want) in window.iter().zip(expected) {
+                assert_eq!(got.record_id, want.record_id);
+                assert_eq!(got.row_index, want.row_index);
+                assert_eq!(got.depth, want.depth);
+                assert_eq!(got.branch_context, want.branch_context);
+                assert_eq!(got.is_last_child, want.is_last_child);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sorted_viewport_filtered_rows_keep_leaves_in_window() {
+        use crate::state::{SortDir, SortKey};
+
+        let mut input = String::from(concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"core","record_type":"Core","id":1,"parent_id":null,"description":""}"#, "\n",
+        ));
+        for (id, clk) in [(2, 10), (3, 20), (4, 30), (5, 40), (6, 50)] {
+            input.push_str(&format!(
+                r#"{{"type":"record","clk":{clk},"name":"op","record_type":"Instr","id":{id},"parent_id":1,"description":""}}"#
+            ));
+            input.push('\n');
+        }
+        let trace = DynTraceData::Jets(rjets::parse_trace_from_reader(input.as_bytes()).unwrap());
+        let expanded: HashSet<u64> = [1].into_iter().collect();
+
+        // Newest first: natural indices in reverse
+        let sort = SortSpec { key: SortKey::StartClock, dir: SortDir::Desc };
+        let mut cache = TreeCache::new();
+        cache.sorted_children.insert((1, sort), vec![4, 3, 2, 1, 0]);
+
+        let (no_roots, no_types) = (HashSet::new(), HashSet::new());
+        let rows = |sort| {
+            collect_viewport_filtered_nodes_with_sort(&trace, &expanded, &no_roots, &no_types, None, &ColumnFilters::default(), &cache, sort, 15, 45, false)
+                .iter()
+                .map(|node| node.record_id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(rows(Some(sort)), vec![1, 5, 4, 3]);
+        assert_eq!(rows(None), vec![1, 3, 4, 5]);
+
+        // The cached order is lent out, not copied, on every traversal
+        let core = trace.get_record(1).unwrap();
+        let provider = CacheChildOrder { cache: &cache, sort: Some(sort) };
+        assert!(matches!(provider.child_indices(&core, 0), Some(Cow::Borrowed([4, 3, 2, 1, 0]))));
+        assert!(CacheChildOrder { cache: &cache, sort: None }.child_indices(&core, 0).is_none());
+    }
+
+    #[test]
+    fn test_initial_expansion_helpers() {
+        let input = concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"soc","record_type":"SoC","id":1,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"core","record_type":"Core","id":2,"parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"record","clk":1,"name":"add","record_type":"Instr","id":3,"parent_id":2,"description":""}"#, "\n",
+            r#"{"type":"record","clk":2,"name":"mul","record_type":"Instr","id":4,"parent_id":2,"description":""}"#, "\n",
+            r#"{"type":"record_end","clk":3,"record_id":3}"#, "\n",
+            r#"{"type":"record_end","clk":9,"record_id":4}"#, "\n",
+        );
+        let trace = DynTraceData::Jets(rjets::parse_trace_from_reader(input.as_bytes()).unwrap());
+
+        assert!(parents_above_depth(&trace, 0).is_empty());
+        assert_eq!(parents_above_depth(&trace, 1), vec![1]);
+        assert_eq!(parents_above_depth(&trace, 5), vec![1, 2]);
+        // Open records (soc, core) have no duration
+        assert_eq!(longest_record(&trace), Some(4));
+    }
+
+    #[test]
+    fn test_expansion_fit_range_only_when_children_out_of_view() {
+        let input = concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"core","record_type":"Core","id":1,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record","clk":500,"name":"add","record_type":"Instr","id":2,"parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"record","clk":700,"name":"mul","record_type":"Instr","id":3,"parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"record_end","clk":510,"record_id":2}"#, "\n",
+            r#"{"type":"record_end","clk":900,"record_id":1}"#, "\n",
+        );
+        let trace = DynTraceData::Jets(rjets::parse_trace_from_reader(input.as_bytes()).unwrap());
+
+        assert_eq!(expansion_fit_range(&trace, 1, 0, 100), Some((0, 900)));
+        assert_eq!(expansion_fit_range(&trace, 1, 505, 600), None, "add is in view");
+        assert_eq!(expansion_fit_range(&trace, 1, 700, 800), None, "open mul starts in view");
+        assert_eq!(expansion_fit_range(&trace, 2, 0, 100), None, "no children");
+    }
 }
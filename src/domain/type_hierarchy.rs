@@ -0,0 +1,178 @@
+//! Record type hierarchy statistics.
+//!
+//! Folds the record tree by `record_type`: every record lands in the node for
+//! its chain of ancestor types (e.g. Cluster → Core → Thread → Instruction),
+//! so a trace with millions of records collapses into a handful of nodes with
+//! counts and durations. The same type under different parent chains gets
+//! separate nodes.
+
+use crate::reporting::{Report, Reportable};
+use rjets::{DynTraceData, DynTraceRecord, TraceData, TraceRecord};
+use std::collections::HashMap;
+
+/// Records of one type under one chain of parent types.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeNode {
+    pub record_type: String,
+    /// Number of records
+    pub count: usize,
+    /// Records with a known duration
+    pub with_duration: usize,
+    /// Sum of the known durations
+    pub total_duration: i64,
+    /// Child type nodes, in order of first appearance
+    pub children: Vec<usize>,
+}
+
+impl TypeNode {
+    /// Returns the mean duration of the records with a known duration.
+    pub fn mean_duration(&self) -> Option<f64> {
+        (self.with_duration > 0).then(|| self.total_duration as f64 / self.with_duration as f64)
+    }
+}
+
+/// Result of folding a trace by record type.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TypeHierarchy {
+    /// All nodes; children refer to them by index
+    pub nodes: Vec<TypeNode>,
+    /// Nodes of root record types, in order of first appearance
+    pub roots: Vec<usize>,
+    /// Records folded
+    pub records: usize,
+}
+
+impl TypeHierarchy {
+    /// Folds every record reachable from the trace roots.
+    pub fn build(trace: &DynTraceData) -> Self {
+        let mut hierarchy = TypeHierarchy::default();
+        // (parent node, record type) -> node
+        let mut index: HashMap<(Option<usize>, String), usize> = HashMap::new();
+        let mut stack: Vec<(DynTraceRecord<'_>, Option<usize>)> =
+            trace.roots().iter().rev().filter_map(|&id| Some((trace.get_record(id)?, None))).collect();
+
+        while let Some((record, parent)) = stack.pop() {
+            let record_type = record.record_type();
+            let node = match index.get(&(parent, record_type.clone())) {
+                Some(&node) => node,
+                None => {
+                    let node = hierarchy.nodes.len();
+                    hierarchy.nodes.push(TypeNode {
+                        record_type: record_type.clone(),
+                        count: 0,
+                        with_duration: 0,
+                        total_duration: 0,
+                        children: Vec::new(),
+                    });
+                    match parent {
+                        Some(parent) => hierarchy.nodes[parent].children.push(node),
+                        None => hierarchy.roots.push(node),
+                    }
+                    index.insert((parent, record_type), node);
+                    node
+                }
+            };
+
+            let stats = &mut hierarchy.nodes[node];
+            stats.count += 1;
+            if let Some(duration) = record.duration() {
+                stats.with_duration += 1;
+                stats.total_duration += duration;
+            }
+            hierarchy.records += 1;
+
+            let num_children = record.num_children();
+            stack.extend((0..num_children).rev().filter_map(|i| Some((record.child_at(i)?, Some(node)))));
+        }
+        hierarchy
+    }
+
+    /// Returns a node by index.
+    pub fn node(&self, index: usize) -> &TypeNode {
+        &self.nodes[index]
+    }
+}
+
+impl Reportable for TypeHierarchy {
+    fn to_report(&self) -> Report {
+        let mut report = Report::new("Record Type Hierarchy");
+        report.section("Summary").key_values([
+            ("Records", self.records.to_string()),
+            ("Type nodes", self.nodes.len().to_string()),
+        ]);
+
+        // Depth-first, so each chain follows its parent chain
+        let mut rows = Vec::with_capacity(self.nodes.len());
+        let mut stack: Vec<(usize, String)> =
+            self.roots.iter().rev().map(|&root| (root, self.node(root).record_type.clone())).collect();
+        while let Some((index, path)) = stack.pop() {
+            let node = self.node(index);
+            rows.push(vec![
+                path.clone(),
+                node.count.to_string(),
+                node.total_duration.to_string(),
+                node.mean_duration().map(|m| format!("{:.1}", m)).unwrap_or_else(|| "-".to_string()),
+            ]);
+            stack.extend(
+                node.children.iter().rev().map(|&child| (child, format!("{} → {}", path, self.node(child).record_type))),
+            );
+        }
+        let section = report.section("Types");
+        if rows.is_empty() {
+            section.paragraph("No records.");
+        } else {
+            section.table(&["Type Chain", "Count", "Total Duration", "Mean"], rows);
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reporting::report::ReportBlock;
+
+    #[test]
+    fn test_build_folds_records_by_type_chain() {
+        let input = concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"core0","record_type":"Core","id":1,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"t0","record_type":"Thread","id":2,"parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"add","record_type":"Instr","id":3,"parent_id":2,"description":""}"#, "\n",
+            r#"{"type":"record","clk":5,"name":"sub","record_type":"Instr","id":4,"parent_id":2,"description":""}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"core1","record_type":"Core","id":5,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record","clk":1,"name":"irq","record_type":"Instr","id":6,"parent_id":5,"description":""}"#, "\n",
+            r#"{"type":"record_end","clk":4,"record_id":3}"#, "\n",
+            r#"{"type":"record_end","clk":13,"record_id":4}"#, "\n",
+            r#"{"type":"record_end","clk":20,"record_id":2}"#, "\n",
+            r#"{"type":"record_end","clk":20,"record_id":1}"#, "\n",
+            r#"{"type":"record_end","clk":20,"record_id":5}"#, "\n",
+        );
+        let trace = DynTraceData::Jets(rjets::parse_trace_from_reader(input.as_bytes()).unwrap());
+        let hierarchy = TypeHierarchy::build(&trace);
+
+        assert_eq!(hierarchy.records, 6);
+        assert_eq!(hierarchy.roots.len(), 1);
+        let core = hierarchy.node(hierarchy.roots[0]);
+        assert_eq!((core.record_type.as_str(), core.count), ("Core", 2));
+
+        let types = |node: &TypeNode| {
+            node.children.iter().map(|&i| hierarchy.node(i).record_type.clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(types(core), vec!["Thread", "Instr"], "Instr directly under a core is its own node");
+
+        let thread = hierarchy.node(core.children[0]);
+        let instr = hierarchy.node(thread.children[0]);
+        assert_eq!((instr.count, instr.total_duration), (2, 12));
+        assert_eq!(instr.mean_duration(), Some(6.0));
+        assert_eq!(hierarchy.node(core.children[1]).count, 1);
+
+        let report = hierarchy.to_report();
+        let Some(ReportBlock::Table { rows, .. }) = report.sections[1].blocks.first() else {
+            panic!("types table missing");
+        };
+        let chains: Vec<&str> = rows.iter().map(|row| row[0].as_str()).collect();
+        assert_eq!(chains, ["Core", "Core → Thread", "Core → Thread → Instr", "Core → Instr"]);
+        assert_eq!(rows[2][1..], ["2", "12", "6.0"]);
+    }
+}
@@ -0,0 +1,215 @@
+//! Per-record-type display hints shipped in the trace header.
+//!
+//! A producer can suggest how its record types should look by adding a
+//! `type_hints` object to the header metadata:
+//!
+//! ```json
+//! "type_hints": {
+//!   "Warp": {"collapsed": true, "color": "#d08770", "label": "{name} pc={pc}", "events": "histogram"},
+//!   "Thread": {"collapsed": false}
+//! }
+//! ```
+//!
+//! - `collapsed` — keep records of the type collapsed (`true`) or expand them
+//!   (`false`) when the trace is opened
+//! - `color` — bar color as `#rrggbb`
+//! - `label` — bar label template; `{name}`, `{record_type}`,
+//!   `{description}` and `{id}` are record fields, any other `{key}` is an
+//!   attribute
+//! - `events` — `markers` (the default), `histogram` or `hidden`
+//!
+//! Hints only fill gaps: a color chosen in the type legend wins over the
+//! hinted one, and all hints are ignored when the user turns them off in the
+//! settings. Unknown keys and malformed values are skipped.
+
+use eframe::egui::Color32;
+use rjets::{DynTraceData, TraceMetadata, TraceRecord};
+use std::collections::HashMap;
+
+/// Header metadata key holding the hints.
+pub const TYPE_HINTS_KEY: &str = "type_hints";
+
+/// How a record type's events are drawn on the timeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EventRenderMode {
+    /// Individual markers, or a histogram when they are too dense
+    #[default]
+    Markers,
+    /// Always a histogram strip
+    Histogram,
+    /// Not drawn
+    Hidden,
+}
+
+impl EventRenderMode {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "markers" => Some(Self::Markers),
+            "histogram" => Some(Self::Histogram),
+            "hidden" => Some(Self::Hidden),
+            _ => None,
+        }
+    }
+}
+
+/// Hints for one record type; `None` leaves the viewer default.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TypeHint {
+    pub collapsed: Option<bool>,
+    pub color: Option<Color32>,
+    pub label: Option<String>,
+    pub events: Option<EventRenderMode>,
+}
+
+impl TypeHint {
+    fn from_json(value: &serde_json::Value) -> Self {
+        Self {
+            collapsed: value.get("collapsed").and_then(|v| v.as_bool()),
+            color: value.get("color").and_then(|v| v.as_str()).and_then(parse_color),
+            label: value.get("label").and_then(|v| v.as_str()).map(str::to_string),
+            events: value.get("events").and_then(|v| v.as_str()).and_then(EventRenderMode::parse),
+        }
+    }
+}
+
+/// Hints of all record types named in a trace header.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TypeHints {
+    by_type: HashMap<String, TypeHint>,
+}
+
+impl TypeHints {
+    /// Reads the hints from header metadata (empty if there are none).
+    pub fn from_header(metadata: &serde_json::Value) -> Self {
+        let by_type = metadata
+            .get(TYPE_HINTS_KEY)
+            .and_then(|hints| hints.as_object())
+            .map(|hints| {
+                hints
+                    .iter()
+                    .map(|(record_type, hint)| (record_type.clone(), TypeHint::from_json(hint)))
+                    .filter(|(_, hint)| *hint != TypeHint::default())
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { by_type }
+    }
+
+    /// Returns the hints to use for a trace: its header hints if `honor` is
+    /// set, otherwise none.
+    pub fn for_trace(trace: Option<&DynTraceData>, honor: bool) -> Self {
+        match trace {
+            Some(trace) if honor => Self::from_header(trace.metadata().header_data()),
+            _ => Self::default(),
+        }
+    }
+
+    /// Returns true if no record type has hints.
+    pub fn is_empty(&self) -> bool {
+        self.by_type.is_empty()
+    }
+
+    /// Returns the number of record types with hints.
+    pub fn len(&self) -> usize {
+        self.by_type.len()
+    }
+
+    /// Returns the hints for a record type, if it has any.
+    pub fn get(&self, record_type: &str) -> Option<&TypeHint> {
+        self.by_type.get(record_type)
+    }
+
+    /// Returns the hinted color of a record type.
+    pub fn color(&self, record_type: &str) -> Option<Color32> {
+        self.get(record_type)?.color
+    }
+
+    /// Returns how events of a record type are drawn.
+    pub fn event_mode(&self, record_type: &str) -> EventRenderMode {
+        self.get(record_type).and_then(|hint| hint.events).unwrap_or_default()
+    }
+
+    /// Returns the record types hinted as collapsed (`true`) or expanded (`false`).
+    pub fn types_with_collapsed(&self, collapsed: bool) -> impl Iterator<Item = &str> {
+        self.by_type
+            .iter()
+            .filter(move |(_, hint)| hint.collapsed == Some(collapsed))
+            .map(|(record_type, _)| record_type.as_str())
+    }
+
+    /// Returns the bar label of a record from its type's template, if it has one.
+    ///
+    /// # Arguments
+    /// * `record` - The record to label
+    /// * `display_name` - The (possibly aliased) name substituted for `{name}`
+    pub fn label<'a, R: TraceRecord<'a>>(&self, record: &R, display_name: &str) -> Option<String> {
+        let template = self.get(&record.record_type())?.label.as_deref()?;
+        Some(expand_template(template, |key| match key {
+            "name" => Some(display_name.to_string()),
+            "record_type" => Some(record.record_type()),
+            "description" => Some(record.description()),
+            "id" => Some(record.id().to_string()),
+            attribute => record.attr(attribute).map(|value| match value {
+                serde_json::Value::String(s) => s,
+                other => other.to_string(),
+            }),
+        }))
+    }
+}
+
+/// Replaces each `{key}` in `template` with `lookup(key)` (empty if None).
+/// An unmatched `{` is kept as text.
+fn expand_template(template: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}') else {
+            break;
+        };
+        result.push_str(&rest[..open]);
+        if let Some(value) = lookup(&rest[open + 1..open + close]) {
+            result.push_str(&value);
+        }
+        rest = &rest[open + close + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Parses `#rrggbb`.
+fn parse_color(hex: &str) -> Option<Color32> {
+    let digits = hex.strip_prefix('#')?;
+    (digits.len() == 6 && digits.chars().all(|c| c.is_ascii_hexdigit())).then(|| rjets::hex_to_color32(digits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_hints_from_header_and_label_template() {
+        let hints = TypeHints::from_header(&json!({
+            "producer": "sim",
+            "type_hints": {
+                "Warp": {"collapsed": true, "color": "#ff8000", "label": "{name} pc={pc} {", "events": "histogram"},
+                "Thread": {"collapsed": false, "color": "orange", "events": "sparkles"},
+                "Empty": {"unknown": 1}
+            }
+        }));
+        assert_eq!(hints.len(), 2);
+        assert_eq!(hints.color("Warp"), Some(Color32::from_rgb(255, 128, 0)));
+        assert_eq!(hints.color("Thread"), None);
+        assert_eq!(hints.event_mode("Warp"), EventRenderMode::Histogram);
+        assert_eq!(hints.event_mode("Thread"), EventRenderMode::Markers);
+        assert_eq!(hints.types_with_collapsed(true).collect::<Vec<_>>(), vec!["Warp"]);
+        assert!(TypeHints::from_header(&json!({})).is_empty());
+
+        let expanded = expand_template("{name} pc={pc} ({missing}) {", |key| match key {
+            "name" => Some("w0".to_string()),
+            "pc" => Some("0x40".to_string()),
+            _ => None,
+        });
+        assert_eq!(expanded, "w0 pc=0x40 () {");
+    }
+}
@@ -2,12 +2,56 @@
 //!
 //! This module provides pure functions for:
 //! - Converting between clock values and screen coordinates
+//! - Mapping a vertical screen span to the rows it covers
+//! - Zooming within bounds set by the trace extent and a minimum viewport width
 //! - Calculating appropriate tick intervals for time axis display
 //!
 //! These functions are stateless and can be tested independently.
+//!
+//! Clocks may use the whole `i64` range (PC-based clock schemes produce values
+//! near `i64::MAX`), so differences are taken in `i128`, scaled in `f64` and
+//! converted back saturating instead of wrapping.
+
+/// Narrowest viewport zooming goes down to, in clocks (unless the trace is shorter).
+pub const MIN_VIEWPORT_CLKS: i64 = 10;
+
+/// How far beyond the canvas edges `clk_to_x` places far off-screen clocks.
+///
+/// Keeps coordinates finite and small enough for the painter while leaving
+/// them clearly outside the canvas.
+const MAX_OFFSCREEN_PX: f64 = 1.0e6;
+
+/// Returns the clock distance from `start` to `end` as `f64`, without overflow.
+pub fn clk_span(start: i64, end: i64) -> f64 {
+    (end as i128 - start as i128) as f64
+}
+
+/// Returns `clk` moved by `offset` clocks (truncated towards zero), saturating
+/// at the `i64` bounds.
+///
+/// The addition is done on integers, so a small offset from a clock near
+/// `i64::MAX` is not lost to `f64` rounding.
+pub fn offset_clk(clk: i64, offset: f64) -> i64 {
+    // Float to integer `as` truncates and saturates (NaN becomes 0)
+    saturate(clk as i128 + offset_i128(offset))
+}
+
+/// Converts a clock offset to an integer, truncated and bounded so that
+/// adding it to any `i64` fits in `i128`.
+fn offset_i128(offset: f64) -> i128 {
+    (offset as i128).clamp(-(u64::MAX as i128), u64::MAX as i128)
+}
+
+/// Clamps a widened clock to the `i64` range.
+fn saturate(clk: i128) -> i64 {
+    clk.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+}
 
 /// Converts a clock value to an X coordinate within the canvas rect.
 ///
+/// Clocks far outside the viewport are clamped to [`MAX_OFFSCREEN_PX`]
+/// beyond the canvas edges.
+///
 /// # Arguments
 /// * `clk` - The clock value to convert
 /// * `viewport_start` - The start of the visible clock range
@@ -22,12 +66,18 @@ pub fn clk_to_x(
     if viewport_end == viewport_start {
         return canvas_rect.left();
     }
-    let normalized = (clk - viewport_start) as f32 / (viewport_end - viewport_start) as f32;
-    canvas_rect.left() + normalized * canvas_rect.width()
+    let normalized = clk_span(viewport_start, clk) / clk_span(viewport_start, viewport_end);
+    let left = canvas_rect.left() as f64;
+    let width = canvas_rect.width() as f64;
+    let x = left + normalized * width;
+    x.clamp(left - MAX_OFFSCREEN_PX, left + width + MAX_OFFSCREEN_PX) as f32
 }
 
 /// Converts an X coordinate to a clock value within the viewport range.
 ///
+/// Positions beyond the canvas extrapolate the viewport scale, saturating at
+/// the `i64` bounds.
+///
 /// # Arguments
 /// * `x` - The X coordinate to convert
 /// * `viewport_start` - The start of the visible clock range
@@ -39,8 +89,142 @@ pub fn x_to_clk(
     viewport_end: i64,
     canvas_rect: egui::Rect,
 ) -> i64 {
-    let normalized = (x - canvas_rect.left()) / canvas_rect.width();
-    viewport_start + (normalized * (viewport_end - viewport_start) as f32) as i64
+    if canvas_rect.width() <= 0.0 {
+        return viewport_start;
+    }
+    let normalized = (x as f64 - canvas_rect.left() as f64) / canvas_rect.width() as f64;
+    offset_clk(viewport_start, normalized * clk_span(viewport_start, viewport_end))
+}
+
+/// Returns the inclusive range of rows covered by a vertical screen span.
+///
+/// # Arguments
+/// * `top_y` / `bottom_y` - Screen span (either order)
+/// * `content_top` - Screen Y of the top of the scroll area
+/// * `scroll_y` - Current vertical scroll offset
+/// * `row_height` - Height of one row
+pub fn y_span_to_rows(top_y: f32, bottom_y: f32, content_top: f32, scroll_y: f32, row_height: f32) -> (usize, usize) {
+    let row_at = |y: f32| ((y - content_top + scroll_y) / row_height).floor().max(0.0) as usize;
+    (row_at(top_y.min(bottom_y)), row_at(top_y.max(bottom_y)))
+}
+
+/// Returns the viewport range after panning by a wheel scroll.
+///
+/// Scrolling down (negative `scroll`) moves to later clocks by a tenth of the
+/// range per 100 units, at least 1 clock or 2% of the range. The range keeps
+/// its width and is shifted back inside the trace bounds.
+///
+/// # Arguments
+/// * `scroll` - Oriented wheel delta
+/// * `viewport_start` / `viewport_end` - Current viewport range
+/// * `trace_min` / `trace_max` - Trace bounds
+pub fn wheel_pan(scroll: f32, viewport_start: i64, viewport_end: i64, trace_min: i64, trace_max: i64) -> (i64, i64) {
+    let viewport_range = clk_span(viewport_start, viewport_end);
+    let pan_amount = (-scroll as f64 / 100.0) * viewport_range * 0.1;
+
+    // At high zoom levels (small viewport_range), ensure we always move at least 1 clock
+    let min_pan = (viewport_range * 0.02).max(1.0);
+    let pan_clk = offset_i128(if pan_amount.abs() < min_pan { min_pan.copysign(pan_amount) } else { pan_amount });
+    shift_range(pan_clk, viewport_start, viewport_end, trace_min, trace_max)
+}
+
+/// Returns the viewport range after dragging the timeline by `drag_dx` pixels.
+///
+/// Dragging right moves to earlier clocks by the clocks the pixels span. The
+/// range keeps its width and is shifted back inside the trace bounds.
+///
+/// # Arguments
+/// * `drag_dx` - Horizontal drag delta in pixels
+/// * `canvas_width` - Width of the timeline canvas in pixels
+/// * `viewport_start` / `viewport_end` - Current viewport range
+/// * `trace_min` / `trace_max` - Trace bounds
+pub fn drag_pan(drag_dx: f32, canvas_width: f32, viewport_start: i64, viewport_end: i64, trace_min: i64, trace_max: i64) -> (i64, i64) {
+    let clk_per_pixel = clk_span(viewport_start, viewport_end) / canvas_width as f64;
+    let pan_clk = offset_i128(-drag_dx as f64 * clk_per_pixel);
+    shift_range(pan_clk, viewport_start, viewport_end, trace_min, trace_max)
+}
+
+/// Shifts a viewport range by `offset` clocks, then back inside the trace bounds.
+fn shift_range(offset: i128, viewport_start: i64, viewport_end: i64, trace_min: i64, trace_max: i64) -> (i64, i64) {
+    // Widened, so the shift and clamps cannot overflow near the i64 bounds
+    let (trace_min, trace_max) = (trace_min as i128, trace_max as i128);
+    let (mut start, mut end) = (viewport_start as i128 + offset, viewport_end as i128 + offset);
+    if start < trace_min {
+        end += trace_min - start;
+        start = trace_min;
+    }
+    if end > trace_max {
+        start -= end - trace_max;
+        end = trace_max;
+    }
+    (saturate(start), saturate(end))
+}
+
+/// Returns how many times the trace extent is wider than a viewport range
+/// (1.0 = the whole trace; also 1.0 for an empty range).
+pub fn zoom_level(viewport_start: i64, viewport_end: i64, trace_min: i64, trace_max: i64) -> f64 {
+    let width = clk_span(viewport_start, viewport_end);
+    if width > 0.0 { clk_span(trace_min, trace_max) / width } else { 1.0 }
+}
+
+/// Returns the highest zoom level of a trace: a [`MIN_VIEWPORT_CLKS`] wide
+/// viewport, or the whole trace if it is shorter.
+pub fn max_zoom_level(trace_min: i64, trace_max: i64) -> f64 {
+    (clk_span(trace_min, trace_max) / MIN_VIEWPORT_CLKS as f64).max(1.0)
+}
+
+/// Returns a range resized to the zoom bounds and shifted inside the trace.
+///
+/// A range narrower than [`MIN_VIEWPORT_CLKS`] is widened around its center,
+/// one wider than the trace is narrowed to it.
+pub fn fit_range(start: i64, end: i64, trace_min: i64, trace_max: i64) -> (i64, i64) {
+    let (trace_min, trace_max) = (trace_min as i128, trace_max as i128);
+    let extent = (trace_max - trace_min).max(0);
+    let (start, end) = (start as i128, (end as i128).max(start as i128));
+    let width = (end - start).clamp((MIN_VIEWPORT_CLKS as i128).min(extent), extent);
+
+    let mut start = start + (end - start) / 2 - width / 2;
+    let mut end = start + width;
+    if start < trace_min {
+        start = trace_min;
+        end = start + width;
+    }
+    if end > trace_max {
+        end = trace_max;
+        start = end - width;
+    }
+    (saturate(start), saturate(end))
+}
+
+/// Returns a range as wide as the viewport, centered on `clk` and fitted
+/// inside the trace with [`fit_range`].
+pub fn center_range(clk: i64, viewport_start: i64, viewport_end: i64, trace_min: i64, trace_max: i64) -> (i64, i64) {
+    let half_width = (viewport_end as i128 - viewport_start as i128) / 2;
+    let offset = clk as i128 - viewport_start as i128 - half_width;
+    let (start, end) = shift_range(offset, viewport_start, viewport_end, trace_min, trace_max);
+    fit_range(start, end, trace_min, trace_max)
+}
+
+/// Returns the viewport range after zooming by a factor around a clock.
+///
+/// The focus clock keeps its screen position; the new range stays within
+/// [`fit_range`] bounds, so zooming stops at [`max_zoom_level`] and at the
+/// whole trace.
+///
+/// # Arguments
+/// * `zoom_factor` - Multiplicative zoom factor (>1 = zoom in, <1 = zoom out)
+/// * `focus_clk` - Clock value to zoom around
+/// * `viewport_start` / `viewport_end` - Current viewport range
+/// * `trace_min` / `trace_max` - Trace bounds
+pub fn zoom_range(zoom_factor: f64, focus_clk: i64, viewport_start: i64, viewport_end: i64, trace_min: i64, trace_max: i64) -> (i64, i64) {
+    let old_width = clk_span(viewport_start, viewport_end);
+    let extent = clk_span(trace_min, trace_max).max(0.0);
+    let min_width = (MIN_VIEWPORT_CLKS as f64).min(extent);
+    let width = (old_width / zoom_factor).clamp(min_width, extent).round();
+    let focus_ratio = if old_width > 0.0 { clk_span(viewport_start, focus_clk) / old_width } else { 0.5 };
+
+    let start = offset_clk(focus_clk, -(width * focus_ratio));
+    fit_range(start, offset_clk(start, width), trace_min, trace_max)
 }
 
 /// Finds the next power of 10 that is greater than or equal to the given value.
@@ -50,11 +234,114 @@ pub fn x_to_clk(
 /// * `value` - The value to find the next power of 10 for
 ///
 /// # Returns
-/// The next power of 10 >= value
-pub fn next_power_of_10(value: f32) -> i64 {
-    if value <= 0.0 {
+/// The next power of 10 >= value, at most 10^18 (the largest in `i64`)
+pub fn next_power_of_10(value: f64) -> i64 {
+    if value.is_nan() || value <= 0.0 {
         return 1;
     }
-    let log_value = value.log10().ceil();
-    10_i64.pow(log_value as u32)
+    let exponent = value.log10().ceil().clamp(0.0, 18.0) as u32;
+    10_i64.pow(exponent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_y_span_to_rows() {
+        // Rows of 20px below a scroll area at y=100, scrolled down by 3 rows
+        assert_eq!(y_span_to_rows(105.0, 145.0, 100.0, 60.0, 20.0), (3, 5));
+        assert_eq!(y_span_to_rows(145.0, 105.0, 100.0, 60.0, 20.0), (3, 5));
+        // Spans starting above the content clamp to the first row
+        assert_eq!(y_span_to_rows(50.0, 110.0, 100.0, 0.0, 20.0), (0, 0));
+    }
+
+    #[test]
+    fn test_wheel_pan_moves_and_clamps() {
+        // One 100-unit notch down moves a tenth of the range later
+        assert_eq!(wheel_pan(-100.0, 1000, 2000, 0, 10_000), (1100, 2100));
+        assert_eq!(wheel_pan(100.0, 1000, 2000, 0, 10_000), (900, 1900));
+        // Tiny scrolls still move 2% of the range
+        assert_eq!(wheel_pan(-1.0, 1000, 2000, 0, 10_000), (1020, 2020));
+        // Keeps the width at the trace edges
+        assert_eq!(wheel_pan(100.0, 50, 1050, 0, 10_000), (0, 1000));
+        assert_eq!(wheel_pan(-100.0, 9000, 10_000, 0, 10_000), (9000, 10_000));
+    }
+
+    #[test]
+    fn test_clock_conversions_at_extreme_clocks() {
+        let rect = egui::Rect::from_min_size(egui::pos2(100.0, 0.0), egui::vec2(1000.0, 20.0));
+
+        // A window near i64::MAX, narrow enough that f32 or f64 clocks could not resolve it
+        let (start, end) = (i64::MAX - 1000, i64::MAX);
+        assert_eq!(clk_to_x(start, start, end, rect), 100.0);
+        assert_eq!(clk_to_x(end, start, end, rect), 1100.0);
+        assert_eq!(clk_to_x(i64::MAX - 500, start, end, rect), 600.0);
+        assert_eq!(x_to_clk(600.0, start, end, rect), i64::MAX - 500);
+        assert_eq!(x_to_clk(1100.0, start, end, rect), i64::MAX);
+        // Beyond the canvas the clock saturates instead of wrapping
+        assert_eq!(x_to_clk(5000.0, start, end, rect), i64::MAX);
+        // Far away clocks stay finite, off the canvas on the correct side
+        let x = clk_to_x(i64::MIN, start, end, rect);
+        assert!(x.is_finite() && x < 100.0);
+
+        // The whole i64 range in one window
+        let (start, end) = (i64::MIN, i64::MAX);
+        assert_eq!(clk_to_x(0, start, end, rect), 600.0);
+        assert_eq!(x_to_clk(100.0, start, end, rect), i64::MIN);
+        assert_eq!(x_to_clk(1100.0, start, end, rect), i64::MAX);
+        assert_eq!(x_to_clk(-1.0e9, start, end, rect), i64::MIN);
+
+        // Zero-width canvases and viewports do not divide by zero
+        let empty = egui::Rect::from_min_size(egui::pos2(100.0, 0.0), egui::vec2(0.0, 20.0));
+        assert_eq!(x_to_clk(100.0, start, end, empty), start);
+        assert_eq!(clk_to_x(7, 7, 7, rect), 100.0);
+    }
+
+    #[test]
+    fn test_wheel_pan_and_ticks_at_extreme_clocks() {
+        // Panning at the top of the i64 range keeps the width and stays in bounds
+        let (start, end) = (i64::MAX - 1000, i64::MAX);
+        assert_eq!(wheel_pan(-100.0, start, end, 0, i64::MAX), (start, end));
+        assert_eq!(wheel_pan(100.0, start, end, 0, i64::MAX), (start - 100, end - 100));
+        assert_eq!(wheel_pan(100.0, i64::MIN, i64::MAX, i64::MIN, i64::MAX), (i64::MIN, i64::MAX));
+
+        // Dragging by a pixel of a window near i64::MAX, or over the whole clock domain
+        let rect_width = 1000.0;
+        assert_eq!(drag_pan(-1.0, rect_width, start - 1000, end - 1000, 0, i64::MAX), (start - 999, end - 999));
+        assert_eq!(drag_pan(-50.0, rect_width, start, end, 0, i64::MAX), (start, end));
+        assert_eq!(drag_pan(10.0, rect_width, start, end, 0, i64::MAX), (start - 10, end - 10));
+        assert_eq!(drag_pan(300.0, rect_width, i64::MIN, i64::MAX, i64::MIN, i64::MAX), (i64::MIN, i64::MAX));
+
+        // Centering on a clock keeps the width and stays in bounds
+        assert_eq!(center_range(i64::MAX - 10, 0, 1000, 0, i64::MAX), (i64::MAX - 1000, i64::MAX));
+        assert_eq!(center_range(0, i64::MIN, i64::MAX, i64::MIN, i64::MAX), (i64::MIN, i64::MAX));
+
+        assert_eq!(next_power_of_10(950.0), 1000);
+        assert_eq!(next_power_of_10(0.0), 1);
+        assert_eq!(next_power_of_10(f64::NAN), 1);
+        assert_eq!(next_power_of_10(u64::MAX as f64 / 10.0), 1_000_000_000_000_000_000);
+    }
+
+    #[test]
+    fn test_zoom_bounds_follow_trace_extent() {
+        let (min, max) = (0, 4_000_000_000);
+        assert_eq!(max_zoom_level(min, max), 400_000_000.0);
+        assert_eq!(zoom_level(1000, 1000 + MIN_VIEWPORT_CLKS, min, max), 400_000_000.0);
+
+        // Zooming in stops at the minimum width, around the focus
+        assert_eq!(zoom_range(2.0, 1000, 0, 1000, min, max), (500, 1000));
+        assert_eq!(zoom_range(1e12, 2005, 2000, 2010, min, max), (2000, 2010));
+        assert_eq!(zoom_range(1e12, 2_000_000, 0, 4_000_000, min, max), (1_999_995, 2_000_005));
+        // and zooming out at the whole trace, shifted back inside it
+        assert_eq!(zoom_range(1e-12, 3_000_000_000, 2_900_000_000, 3_100_000_000, min, max), (min, max));
+        assert_eq!(zoom_range(0.5, 3_990_000_000, 3_980_000_000, 4_000_000_000, min, max), (3_960_000_000, max));
+
+        // Traces shorter than the minimum width fit whole
+        assert_eq!(zoom_range(4.0, 2, 0, 4, 0, 4), (0, 4));
+        assert_eq!(fit_range(5, 5, 0, 0), (0, 0));
+        // Region zooms narrower than the minimum width are widened around their center
+        assert_eq!(fit_range(100, 102, min, max), (96, 106));
+        assert_eq!(fit_range(1, 2, min, max), (0, 10));
+    }
 }
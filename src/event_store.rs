@@ -0,0 +1,306 @@
+//! Per-record event storage for the JETS parser.
+//!
+//! Most records carry a handful of events, kept as plain [`JetsTraceEvent`]s.
+//! Stage-style traces put hundreds of events on one record, all with the
+//! same record ID and line type, a few distinct names and clocks close
+//! together. Records with more events than
+//! [`ParseOptions::compact_event_threshold`](crate::ParseOptions) are packed
+//! into [`CompactEvents`]:
+//!
+//! - clocks in frame-of-reference form: one base clock (the smallest) per
+//!   block of [`CLOCK_BLOCK`] events plus a 32-bit offset from it per event,
+//!   so every lookup is O(1) (no running sum as with delta encoding)
+//! - names and descriptions as 16-bit indices into a per-record string table
+//! - data values only for the events that have one
+//!
+//! Both forms are read through the same `event_at` API; a record whose
+//! events do not fit the compact limits keeps the plain form.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::lazy_payload::LazyPayload;
+use crate::parser::JetsTraceEvent;
+use crate::traits::RecordId;
+
+/// Events per record above which the parser packs them by default.
+pub const DEFAULT_COMPACT_EVENT_THRESHOLD: usize = 64;
+
+/// Number of events sharing one clock base.
+pub const CLOCK_BLOCK: usize = 64;
+
+/// Data slot of an event without data.
+const NO_DATA: u32 = u32::MAX;
+
+/// The events of one record, plain or packed.
+#[derive(Debug, Clone)]
+pub enum EventStore {
+    Plain(Vec<JetsTraceEvent>),
+    Compact(CompactEvents),
+}
+
+impl Default for EventStore {
+    fn default() -> Self {
+        EventStore::Plain(Vec::new())
+    }
+}
+
+impl EventStore {
+    /// Returns the number of events.
+    pub fn len(&self) -> usize {
+        match self {
+            EventStore::Plain(events) => events.len(),
+            EventStore::Compact(events) => events.len(),
+        }
+    }
+
+    /// Returns true if there are no events.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns true if the events are packed.
+    pub fn is_compact(&self) -> bool {
+        matches!(self, EventStore::Compact(_))
+    }
+
+    /// Appends an event (unpacking packed events first).
+    pub fn push(&mut self, event: JetsTraceEvent) {
+        if let EventStore::Compact(events) = self {
+            *self = EventStore::Plain(events.to_vec());
+        }
+        if let EventStore::Plain(events) = self {
+            events.push(event);
+        }
+    }
+
+    /// Sets the ID of the record owning the events.
+    pub(crate) fn set_record_id(&mut self, record_id: RecordId) {
+        match self {
+            EventStore::Plain(events) => events.iter_mut().for_each(|event| event.record_id = record_id),
+            EventStore::Compact(events) => events.record_id = record_id,
+        }
+    }
+
+    /// Packs the events if there are more than `threshold` and they fit the
+    /// compact limits; otherwise returns them unchanged.
+    pub fn compacted(self, threshold: usize) -> Self {
+        match self {
+            EventStore::Plain(events) if events.len() > threshold => match CompactEvents::from_events(&events) {
+                Some(compact) => EventStore::Compact(compact),
+                None => EventStore::Plain(events),
+            },
+            other => other,
+        }
+    }
+
+    /// Returns the events as plain values (cloned or unpacked).
+    pub fn to_vec(&self) -> Vec<JetsTraceEvent> {
+        match self {
+            EventStore::Plain(events) => events.clone(),
+            EventStore::Compact(events) => events.to_vec(),
+        }
+    }
+}
+
+/// Packed events of one record (see the module documentation).
+#[derive(Debug, Clone)]
+pub struct CompactEvents {
+    record_id: RecordId,
+    line_type: Arc<str>,
+    /// Smallest clock of each block of `CLOCK_BLOCK` events
+    block_base: Vec<i64>,
+    /// Clock of each event minus its block's base
+    clk_offsets: Vec<u32>,
+    /// Distinct names and descriptions
+    strings: Vec<Arc<str>>,
+    name_ids: Vec<u16>,
+    description_ids: Vec<u16>,
+    /// Index into `data` per event (`NO_DATA` for none); empty if no event has data
+    data_slots: Vec<u32>,
+    data: Vec<LazyPayload>,
+}
+
+impl CompactEvents {
+    /// Packs events of one record.
+    ///
+    /// # Returns
+    /// None if the events belong to different records, a block spans more
+    /// than `u32::MAX` clocks or there are more than 65536 distinct strings.
+    pub fn from_events(events: &[JetsTraceEvent]) -> Option<Self> {
+        let first = events.first()?;
+        if events.iter().any(|event| event.record_id != first.record_id || event.line_type != first.line_type) {
+            return None;
+        }
+
+        let mut block_base = Vec::with_capacity(events.len().div_ceil(CLOCK_BLOCK));
+        let mut clk_offsets = Vec::with_capacity(events.len());
+        for block in events.chunks(CLOCK_BLOCK) {
+            let base = block.iter().map(|event| event.clk).min()?;
+            for event in block {
+                clk_offsets.push(u32::try_from(event.clk.checked_sub(base)?).ok()?);
+            }
+            block_base.push(base);
+        }
+
+        let mut strings = Vec::new();
+        let mut string_ids: HashMap<Arc<str>, u16> = HashMap::new();
+        let mut string_id = |s: &Arc<str>| -> Option<u16> {
+            if let Some(&id) = string_ids.get(s) {
+                return Some(id);
+            }
+            let id = u16::try_from(strings.len()).ok()?;
+            strings.push(Arc::clone(s));
+            string_ids.insert(Arc::clone(s), id);
+            Some(id)
+        };
+        let mut name_ids = Vec::with_capacity(events.len());
+        let mut description_ids = Vec::with_capacity(events.len());
+        for event in events {
+            name_ids.push(string_id(&event.name)?);
+            description_ids.push(string_id(&event.description)?);
+        }
+
+        let mut data = Vec::new();
+        let mut data_slots = Vec::new();
+        if events.iter().any(|event| event.data.is_some()) {
+            data_slots.reserve(events.len());
+            for event in events {
+                match &event.data {
+                    Some(value) => {
+                        data_slots.push(u32::try_from(data.len()).ok().filter(|&slot| slot != NO_DATA)?);
+                        data.push(value.clone());
+                    }
+                    None => data_slots.push(NO_DATA),
+                }
+            }
+        }
+
+        Some(Self {
+            record_id: first.record_id,
+            line_type: Arc::clone(&first.line_type),
+            block_base,
+            clk_offsets,
+            strings,
+            name_ids,
+            description_ids,
+            data_slots,
+            data,
+        })
+    }
+
+    /// Returns the number of events.
+    pub fn len(&self) -> usize {
+        self.clk_offsets.len()
+    }
+
+    /// Returns true if there are no events.
+    pub fn is_empty(&self) -> bool {
+        self.clk_offsets.is_empty()
+    }
+
+    /// Returns the record the events belong to.
+    pub fn record_id(&self) -> RecordId {
+        self.record_id
+    }
+
+    /// Returns the clock of the event at `index` (which must be in range).
+    pub fn clk(&self, index: usize) -> i64 {
+        self.block_base[index / CLOCK_BLOCK] + self.clk_offsets[index] as i64
+    }
+
+    /// Returns the name of the event at `index` (which must be in range).
+    pub fn name(&self, index: usize) -> &Arc<str> {
+        &self.strings[self.name_ids[index] as usize]
+    }
+
+    /// Returns the description of the event at `index` (which must be in range).
+    pub fn description(&self, index: usize) -> &Arc<str> {
+        &self.strings[self.description_ids[index] as usize]
+    }
+
+    /// Returns the data of the event at `index`, if it has any.
+    pub fn data(&self, index: usize) -> Option<&LazyPayload> {
+        match self.data_slots.get(index) {
+            Some(&slot) if slot != NO_DATA => self.data.get(slot as usize),
+            _ => None,
+        }
+    }
+
+    /// Returns the event at `index` as a plain value.
+    pub fn event(&self, index: usize) -> JetsTraceEvent {
+        JetsTraceEvent {
+            clk: self.clk(index),
+            line_type: Arc::clone(&self.line_type),
+            name: Arc::clone(self.name(index)),
+            record_id: self.record_id,
+            description: Arc::clone(self.description(index)),
+            data: self.data(index).cloned(),
+        }
+    }
+
+    /// Returns all events as plain values.
+    pub fn to_vec(&self) -> Vec<JetsTraceEvent> {
+        (0..self.len()).map(|index| self.event(index)).collect()
+    }
+
+    /// Returns the approximate heap size of the packed form in bytes.
+    pub fn heap_bytes(&self) -> usize {
+        self.block_base.len() * std::mem::size_of::<i64>()
+            + self.clk_offsets.len() * std::mem::size_of::<u32>()
+            + self.strings.len() * std::mem::size_of::<Arc<str>>()
+            + (self.name_ids.len() + self.description_ids.len()) * std::mem::size_of::<u16>()
+            + self.data_slots.len() * std::mem::size_of::<u32>()
+            + self.data.iter().map(|payload| std::mem::size_of::<LazyPayload>() + payload.raw().len()).sum::<usize>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn event(clk: i64, name: &str, data: Option<serde_json::Value>) -> JetsTraceEvent {
+        JetsTraceEvent {
+            clk,
+            line_type: Arc::from("event"),
+            name: Arc::from(name),
+            record_id: 7,
+            description: Arc::from(""),
+            data: data.as_ref().map(LazyPayload::from_value),
+        }
+    }
+
+    #[test]
+    fn test_compact_round_trip_and_limits() {
+        let stages = ["fetch", "decode", "execute", "retire"];
+        let events: Vec<JetsTraceEvent> = (0..200)
+            .map(|i| event(1_000_000 + i * 3, stages[i as usize % 4], (i % 50 == 0).then(|| json!({"slot": i}))))
+            .collect();
+
+        let store = EventStore::Plain(events.clone()).compacted(DEFAULT_COMPACT_EVENT_THRESHOLD);
+        let EventStore::Compact(compact) = &store else {
+            panic!("200 events should be packed");
+        };
+        assert_eq!(compact.len(), 200);
+        assert_eq!(compact.clk(130), 1_000_390);
+        assert_eq!(compact.name(130).as_ref(), "execute");
+        assert_eq!(compact.data(100).map(|payload| payload.value()).as_deref(), Some(&json!({"slot": 100})));
+        assert_eq!(compact.data(101), None);
+        assert!(compact.heap_bytes() < events.len() * std::mem::size_of::<JetsTraceEvent>() / 4);
+        let unpacked = store.to_vec();
+        assert!(unpacked.iter().zip(&events).all(|(a, b)| a.clk == b.clk && a.name == b.name && a.data == b.data));
+
+        // Below the threshold, or spans no u32 offset can hold, stay plain
+        assert!(!EventStore::Plain(events[..10].to_vec()).compacted(DEFAULT_COMPACT_EVENT_THRESHOLD).is_compact());
+        let mut wide = events.clone();
+        wide[5].clk = i64::MAX;
+        assert!(!EventStore::Plain(wide).compacted(DEFAULT_COMPACT_EVENT_THRESHOLD).is_compact());
+
+        // Appending to packed events unpacks them
+        let mut store = store;
+        store.push(event(5, "late", None));
+        assert!(!store.is_compact());
+        assert_eq!(store.len(), 201);
+    }
+}
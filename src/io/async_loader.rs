@@ -1,17 +1,56 @@
 //! Asynchronous trace file loading.
 //!
 //! This module handles loading JETS trace files in background threads,
-//! keeping the GUI responsive during file I/O operations.
+//! keeping the GUI responsive during file I/O operations. Non-blocking
+//! backends ([`AsyncTraceReader`]) need no thread: their future is polled
+//! from `check_completion()` and wakes the UI with a repaint. Once a trace is
+//! loaded, the same loader runs a cancelable precompute pass that builds the
+//! [`TraceSummary`] off the UI thread, and report exports and flame graph
+//! builds and file exports run on their own threads. All of them work on a [`TraceSnapshot`],
+//! so a reload never waits for them.
+//! Traces with a provenance block are re-read on another thread to check
+//! their content hash.
+//!
+//! Each load has a cancel flag. Starting another load (or `cancel_load()`)
+//! sets it: JETS parses stop at their next buffer refill, other readers
+//! finish but their result is dropped, and a canceled thread never touches
+//! the loading state of the load that replaced it.
 
+use anyhow::Context as _;
 use eframe::egui;
-use rjets::{DynTraceData, JetsTraceReader, VirtualTraceReader, PipetraceReader, TraceReader};
-use std::path::PathBuf;
+use rjets::{
+    AsyncTraceReader, DynTraceData, JetsTraceReader, MultiTraceData, ParseOptions, PerfScriptReader, PerfettoTraceReader, PipetraceReader,
+    TraceData, TraceReadFuture, TraceReader, TraceWriter, Verification, VirtualTraceReader,
+};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{channel, Receiver};
 use std::thread;
+use std::time::Instant;
 use crate::io::LoadingState;
+use crate::io::event_grid::EventGrid;
+use crate::io::jetspack::{self, PackManifest, PackedReport};
+use crate::io::trace_slice::{write_trace_slice, TraceSlice};
+use crate::cache::TraceSummary;
+use crate::domain::flame_graph::{self, FlameGraph};
+use crate::domain::name_aliases::NameAliases;
+use crate::domain::number_format::ColumnFormats;
+use crate::domain::statistics::TraceStatistics;
+use crate::reporting::Reportable;
+use crate::reporting::report::ReportFormat;
+use crate::state::TraceSnapshot;
+
+/// Producer recorded in the provenance block of exported traces.
+const PRODUCER: &str = concat!("jets-gui ", env!("CARGO_PKG_VERSION"));
+
+/// Payload sent from the loading thread: trace data plus an optional session manifest.
+type LoadPayload = Result<(DynTraceData, Option<PackManifest>), String>;
 
 /// Result of a completed trace loading operation.
+#[allow(clippy::large_enum_variant)]
 pub enum LoadResult {
     /// Loading completed successfully
     Success {
@@ -19,6 +58,8 @@ pub enum LoadResult {
         data: DynTraceData,
         /// Path to the file that was loaded (None for virtual traces)
         path: Option<PathBuf>,
+        /// Session pack manifest, if a `.jetspack` file was opened
+        session: Option<PackManifest>,
     },
     /// Loading failed with an error
     Error(String),
@@ -26,6 +67,61 @@ pub enum LoadResult {
     None,
 }
 
+/// Reader used for one load.
+pub enum ReaderBackend {
+    /// Blocking reader, run on a worker thread
+    Blocking(Box<dyn TraceReader + Send>),
+    /// Non-blocking reader, polled once per frame on the UI thread
+    NonBlocking(Box<dyn AsyncTraceReader>),
+}
+
+impl ReaderBackend {
+    /// Picks the built-in reader for a file from its extension.
+    ///
+    /// `options` apply to JETS traces; other formats ignore them.
+    pub fn for_path(path: &str, options: ParseOptions) -> Self {
+        if path.ends_with(".pt") || path.ends_with(".pt.gz") {
+            Self::Blocking(Box::new(PipetraceReader::new()))
+        } else if [".perf", ".ftrace", ".txt"].iter().any(|ext| path.ends_with(ext)) {
+            Self::Blocking(Box::new(PerfScriptReader::new()))
+        } else if path.ends_with(".json") {
+            Self::Blocking(Box::new(PerfettoTraceReader::new()))
+        } else {
+            Self::Blocking(Box::new(JetsTraceReader::with_options(options)))
+        }
+    }
+}
+
+/// Reads one file of a merged load as (source, data).
+fn read_merge_part(path: &Path, options: ParseOptions, cancel: &AtomicBool) -> Result<(String, DynTraceData), String> {
+    if jetspack::is_session_pack(path) {
+        return Err(format!("{}: session packs cannot be merged", path.display()));
+    }
+    let source = path.to_string_lossy().into_owned();
+    match ReaderBackend::for_path(&source, options) {
+        ReaderBackend::Blocking(reader) => reader
+            .read_cancelable(&source, cancel)
+            .map(|data| (source, data))
+            .map_err(|e| format!("{}: {}", path.display(), e)),
+        ReaderBackend::NonBlocking(_) => Err(format!("{}: this format cannot be merged", path.display())),
+    }
+}
+
+/// Wakes the UI thread so the pending read is polled on the next frame.
+struct RepaintWaker(egui::Context);
+
+impl Wake for RepaintWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.request_repaint();
+    }
+}
+
+/// A non-blocking read in progress.
+struct PendingRead {
+    future: TraceReadFuture,
+    waker: Waker,
+}
+
 /// Manages asynchronous loading of trace files.
 ///
 /// This struct coordinates background thread file loading with the main GUI thread,
@@ -35,10 +131,40 @@ pub struct AsyncLoader {
     loading_state: Arc<Mutex<LoadingState>>,
 
     /// Channel receiver for loading results
-    loading_receiver: Option<Receiver<Result<DynTraceData, String>>>,
+    loading_receiver: Option<Receiver<LoadPayload>>,
+
+    /// Read in progress on a non-blocking backend
+    pending_read: Option<PendingRead>,
 
     /// Path of the file currently being loaded
     pending_load_path: Option<PathBuf>,
+
+    /// When the current load started (for the load time in the log)
+    load_started: Option<Instant>,
+
+    /// Set to stop the running load
+    load_cancel: Option<Arc<AtomicBool>>,
+
+    /// Channel receiver for the background precompute result, tagged with
+    /// the generation of the snapshot it was computed from
+    precompute_receiver: Option<Receiver<(u64, TraceSummary)>>,
+
+    /// Set to stop the running precompute pass
+    precompute_cancel: Option<Arc<AtomicBool>>,
+
+    /// Channel receiver for the running report export
+    report_receiver: Option<Receiver<Result<PathBuf, String>>>,
+
+    /// Channel receiver for the running file export; errors are ready to show
+    export_receiver: Option<Receiver<Result<(), String>>>,
+
+    /// Channel receiver for the provenance check, tagged with the generation
+    /// of the trace it checks
+    verification_receiver: Option<Receiver<(u64, Result<Verification, String>)>>,
+
+    /// Channel receiver for the flame graph being built, tagged with the
+    /// generation of the snapshot it is built from
+    flame_graph_receiver: Option<Receiver<(u64, FlameGraph)>>,
 }
 
 impl AsyncLoader {
@@ -47,7 +173,16 @@ impl AsyncLoader {
         Self {
             loading_state: Arc::new(Mutex::new(LoadingState::new())),
             loading_receiver: None,
+            pending_read: None,
             pending_load_path: None,
+            load_started: None,
+            load_cancel: None,
+            precompute_receiver: None,
+            precompute_cancel: None,
+            report_receiver: None,
+            export_receiver: None,
+            verification_receiver: None,
+            flame_graph_receiver: None,
         }
     }
 
@@ -62,68 +197,177 @@ impl AsyncLoader {
     ///
     /// The GUI remains responsive during loading, and a loading indicator can be displayed.
     /// Call `check_completion()` regularly (e.g., once per frame) to check for results.
+    /// A load still in flight is canceled first; `cancel_load()` cancels this one.
     ///
     /// # Arguments
     /// * `path` - Path to the trace file to load
+    /// * `options` - Parse options for JETS traces
     /// * `ctx` - egui context for requesting repaints when loading completes
-    pub fn start_file_load(&mut self, path: PathBuf, ctx: &egui::Context) {
-        // Create a channel for receiving the result
-        let (sender, receiver) = channel();
-        self.loading_receiver = Some(receiver);
-
-        // Set loading state
-        {
-            let mut state = self.loading_state.lock().unwrap();
-            state.in_progress = true;
+    pub fn start_file_load(&mut self, path: PathBuf, options: ParseOptions, ctx: &egui::Context) {
+        // Session packs carry their own manifest ahead of the trace
+        if jetspack::is_session_pack(&path) {
+            self.begin_load(path.clone());
+            self.spawn_load(ctx, move |_| {
+                jetspack::read_session_pack(&path)
+                    .map(|(manifest, data)| (DynTraceData::Jets(data), Some(manifest)))
+                    .map_err(|e| format!("{:#}", e))
+            });
+            return;
         }
 
-        self.pending_load_path = Some(path.clone());
+        let backend = ReaderBackend::for_path(&path.to_string_lossy(), options);
+        self.start_load(backend, path, ctx);
+    }
 
-        // Clone Arc and Context for background thread
-        let loading_state = Arc::clone(&self.loading_state);
-        let ctx_handle = ctx.clone();
-        let path_string = path.to_str().unwrap().to_owned();
+    /// Starts loading several trace files as one merged trace.
+    ///
+    /// The files are read in parallel on background threads and presented
+    /// under one synthetic root per file (see [`MultiTraceData`]). The first
+    /// path is reported as the path of the loaded trace.
+    ///
+    /// # Arguments
+    /// * `paths` - Trace files to merge, in root order
+    /// * `options` - Parse options for JETS traces
+    /// * `ctx` - egui context for requesting repaints when loading completes
+    pub fn start_merged_load(&mut self, paths: Vec<PathBuf>, options: ParseOptions, ctx: &egui::Context) {
+        let Some(first) = paths.first() else {
+            return;
+        };
+        self.begin_load(first.clone());
+        self.spawn_load(ctx, move |cancel| {
+            let traces = thread::scope(|scope| {
+                let readers: Vec<_> = paths
+                    .iter()
+                    .map(|path| scope.spawn(move || read_merge_part(path, options, cancel)))
+                    .collect();
+                readers
+                    .into_iter()
+                    .map(|reader| reader.join().unwrap_or_else(|_| Err("Trace reader panicked".to_string())))
+                    .collect::<Result<Vec<_>, String>>()
+            })?;
+            MultiTraceData::new(traces)
+                .map(|data| (DynTraceData::Multi(data), None))
+                .map_err(|e| e.to_string())
+        });
+    }
 
-        // Spawn background thread for file loading
-        thread::spawn(move || {
-            // Determine which reader to use based on file extension
-            let reader: Box<dyn TraceReader> = if path_string.ends_with(".pt") || path_string.ends_with(".pt.gz") {
-                Box::new(PipetraceReader::new())
-            } else {
-                Box::new(JetsTraceReader::new())
-            };
+    /// Starts loading a trace with the given reader.
+    ///
+    /// Blocking readers run on a background thread; non-blocking readers are
+    /// polled from `check_completion()`, so both report through it alike.
+    ///
+    /// # Arguments
+    /// * `backend` - Reader to load with
+    /// * `path` - Source passed to the reader (file path, or URL for network backends)
+    /// * `ctx` - egui context for requesting repaints when loading completes
+    pub fn start_load(&mut self, backend: ReaderBackend, path: PathBuf, ctx: &egui::Context) {
+        self.begin_load(path.clone());
+        let source = path.to_string_lossy().into_owned();
+
+        match backend {
+            ReaderBackend::Blocking(reader) => {
+                self.spawn_load(ctx, move |cancel| {
+                    reader.read_cancelable(&source, cancel).map(|data| (data, None)).map_err(|e| e.to_string())
+                });
+            }
+            ReaderBackend::NonBlocking(reader) => {
+                self.pending_read = Some(PendingRead {
+                    future: reader.read_async(&source),
+                    waker: Waker::from(Arc::new(RepaintWaker(ctx.clone()))),
+                });
+                // First poll happens on the next frame
+                ctx.request_repaint();
+            }
+        }
+    }
+
+    /// Cancels the load in flight, if any.
+    ///
+    /// # Returns
+    /// `true` if a load was canceled.
+    pub fn cancel_load(&mut self) -> bool {
+        let mut loading_state = self.loading_state.lock().unwrap();
+        if let Some(cancel) = self.load_cancel.take() {
+            cancel.store(true, Ordering::Relaxed);
+        }
+        let was_loading = loading_state.in_progress;
+        loading_state.in_progress = false;
+        drop(loading_state);
 
-            // Parse the trace file (blocking operation)
-            let parse_result = reader.read(&path_string);
+        self.loading_receiver = None;
+        self.pending_read = None;
+        if let Some(path) = self.pending_load_path.take().filter(|_| was_loading) {
+            tracing::info!(path = %path.display(), "trace load canceled");
+        }
+        self.load_started = None;
+        was_loading
+    }
 
-            // Convert Result<Box<dyn TraceData>, anyhow::Error> to Result<Box<dyn TraceData>, String>
-            let result = parse_result.map_err(|e| e.to_string());
+    /// Drops any load in flight and marks a new one as started.
+    fn begin_load(&mut self, path: PathBuf) {
+        // Results for the previous trace are no longer wanted
+        self.cancel_load();
+        self.cancel_precompute();
+        self.verification_receiver = None;
 
-            // Send result through channel
-            let _ = sender.send(result);
+        self.loading_state.lock().unwrap().in_progress = true;
+        tracing::info!(path = %path.display(), "loading trace");
+        self.pending_load_path = Some(path);
+        self.load_started = Some(Instant::now());
+    }
 
-            // Update loading state
-            {
-                let mut state = loading_state.lock().unwrap();
-                state.in_progress = false;
+    /// Logs the outcome of a finished load.
+    fn log_result(&mut self, result: &LoadResult) {
+        let elapsed_ms = self.load_started.take().map_or(0, |start| start.elapsed().as_millis());
+        match result {
+            LoadResult::Success { data, path, .. } => {
+                let path = path.as_ref().map(|p| p.display().to_string()).unwrap_or_default();
+                tracing::info!(path, roots = data.roots().len(), elapsed_ms, "trace loaded");
             }
+            LoadResult::Error(message) => tracing::error!(elapsed_ms, "trace load failed: {}", message),
+            LoadResult::None => {}
+        }
+    }
 
+    /// Runs a blocking load on a background thread.
+    ///
+    /// `load` is given the load's cancel flag to pass on to the reader.
+    fn spawn_load<F>(&mut self, ctx: &egui::Context, load: F)
+    where
+        F: FnOnce(&AtomicBool) -> LoadPayload + Send + 'static,
+    {
+        let (sender, receiver) = channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.loading_receiver = Some(receiver);
+        self.load_cancel = Some(Arc::clone(&cancel));
+
+        let loading_state = Arc::clone(&self.loading_state);
+        let ctx_handle = ctx.clone();
+        thread::spawn(move || {
+            let result = load(&cancel);
+            // Checked under the lock, as `cancel_load()` sets the flag under it
+            let mut loading_state = loading_state.lock().unwrap();
+            if cancel.load(Ordering::Relaxed) {
+                return;
+            }
+            let _ = sender.send(result);
+            loading_state.in_progress = false;
+            drop(loading_state);
             // Notify GUI thread to repaint
             ctx_handle.request_repaint();
         });
     }
 
-    /// Generates and loads a virtual trace in-memory.
+    /// Starts generating a virtual trace in-memory.
     ///
-    /// This is useful for testing and demonstration purposes.
-    /// The virtual trace is generated synchronously (no background thread).
-    ///
-    /// # Returns
-    /// * `Ok(data)` - Successfully generated virtual trace
-    /// * `Err(msg)` - Error generating the trace
-    pub fn load_virtual_trace(&mut self) -> Result<DynTraceData, String> {
-        let virtual_reader = VirtualTraceReader::new();
-        virtual_reader.read("").map_err(|e| e.to_string())
+    /// This is useful for testing and demonstration purposes. The virtual
+    /// reader is non-blocking and builds one root per frame; the trace is
+    /// reported by `check_completion()` without a path, as there is no file
+    /// to record or reload.
+    pub fn start_virtual_load(&mut self, ctx: &egui::Context) {
+        let backend = ReaderBackend::NonBlocking(Box::new(VirtualTraceReader::new()));
+        self.start_load(backend, PathBuf::from("virtual trace"), ctx);
+        self.pending_load_path = None;
     }
 
     /// Checks if background loading has completed and returns the result if available.
@@ -135,15 +379,37 @@ impl AsyncLoader {
     /// * `LoadResult::Error` - Loading failed with an error
     /// * `LoadResult::None` - No result available (still loading or no operation active)
     pub fn check_completion(&mut self) -> LoadResult {
+        if let Some(pending) = &mut self.pending_read {
+            let mut cx = Context::from_waker(&pending.waker);
+            let Poll::Ready(result) = pending.future.as_mut().poll(&mut cx) else {
+                return LoadResult::None;
+            };
+            self.pending_read = None;
+            self.loading_state.lock().unwrap().in_progress = false;
+            let load_result = match result {
+                Ok(data) => LoadResult::Success {
+                    data,
+                    path: self.pending_load_path.take(),
+                    session: None,
+                },
+                Err(e) => {
+                    self.pending_load_path = None;
+                    LoadResult::Error(e.to_string())
+                }
+            };
+            self.log_result(&load_result);
+            return load_result;
+        }
+
         // Try to receive result from channel
         if let Some(receiver) = &self.loading_receiver {
             if let Ok(result) = receiver.try_recv() {
                 // Process the result
                 let load_result = match result {
-                    Ok(data) => {
-                        // Success: Return data and path
+                    Ok((data, session)) => {
+                        // Success: Return data, path and session manifest
                         let path = self.pending_load_path.take();
-                        LoadResult::Success { data, path }
+                        LoadResult::Success { data, path, session }
                     }
                     Err(error_msg) => {
                         // Error: Return error message
@@ -154,6 +420,8 @@ impl AsyncLoader {
 
                 // Clear the receiver after processing
                 self.loading_receiver = None;
+                self.load_cancel = None;
+                self.log_result(&load_result);
 
                 return load_result;
             }
@@ -162,6 +430,304 @@ impl AsyncLoader {
         LoadResult::None
     }
 
+    // ===== Background Precompute =====
+
+    /// Starts computing the trace summary on a background thread.
+    ///
+    /// Any precompute still running for a previous trace is canceled first.
+    /// Call `check_precompute()` once per frame to pick up the result.
+    pub fn start_precompute(&mut self, trace: TraceSnapshot, ctx: &egui::Context) {
+        self.cancel_precompute();
+
+        let (sender, receiver) = channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.precompute_receiver = Some(receiver);
+        self.precompute_cancel = Some(Arc::clone(&cancel));
+
+        let ctx_handle = ctx.clone();
+        thread::spawn(move || {
+            let started = Instant::now();
+            if let Some(summary) = TraceSummary::compute(&trace, &cancel) {
+                tracing::debug!(
+                    records = summary.record_count,
+                    elapsed_ms = started.elapsed().as_millis(),
+                    "trace summary computed"
+                );
+                if sender.send((trace.generation(), summary)).is_ok() {
+                    ctx_handle.request_repaint();
+                }
+            }
+        });
+    }
+
+    /// Stops the running precompute pass and discards its result.
+    pub fn cancel_precompute(&mut self) {
+        if let Some(cancel) = self.precompute_cancel.take() {
+            cancel.store(true, Ordering::Relaxed);
+        }
+        self.precompute_receiver = None;
+    }
+
+    /// Returns the trace summary and its snapshot generation if the
+    /// precompute pass has finished.
+    pub fn check_precompute(&mut self) -> Option<(u64, TraceSummary)> {
+        let summary = self.precompute_receiver.as_ref()?.try_recv();
+        match summary {
+            Ok(summary) => {
+                self.precompute_receiver = None;
+                self.precompute_cancel = None;
+                Some(summary)
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => None,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                // Worker exited without a result (canceled)
+                self.precompute_receiver = None;
+                self.precompute_cancel = None;
+                None
+            }
+        }
+    }
+
+    // ===== Background Report Export =====
+
+    /// Computes statistics for `range` (None = whole trace) and writes the
+    /// report to `path` on a background thread. Record names are rewritten
+    /// with `aliases` when given; clocks, durations and IDs use `formats`.
+    ///
+    /// The export keeps running if another trace is loaded meanwhile; it
+    /// reports on the snapshot it was given. Call `check_report_export()` once
+    /// per frame to pick up the outcome.
+    pub fn start_report_export(
+        &mut self,
+        trace: TraceSnapshot,
+        range: Option<(i64, i64)>,
+        aliases: Option<NameAliases>,
+        formats: ColumnFormats,
+        path: PathBuf,
+        ctx: &egui::Context,
+    ) {
+        self.spawn_report_export(path, ctx, move || {
+            let mut statistics = match range {
+                Some(_) => TraceStatistics::compute_in_range(&trace, range),
+                None => TraceStatistics::compute(&trace),
+            };
+            if let Some(aliases) = &aliases {
+                statistics.apply_aliases(aliases);
+            }
+            statistics.formats = formats;
+            statistics
+        });
+    }
+
+    /// Writes the report of an analysis computed on the UI thread (e.g. the
+    /// trace health check) to `path` on a background thread.
+    ///
+    /// Call `check_report_export()` once per frame to pick up the outcome.
+    pub fn start_analysis_report_export(
+        &mut self,
+        analysis: impl Reportable + Send + 'static,
+        path: PathBuf,
+        ctx: &egui::Context,
+    ) {
+        self.spawn_report_export(path, ctx, move || analysis);
+    }
+
+    /// Runs `analyze` on a background thread and writes its result as a
+    /// report to `path`. Every analysis export goes through here, so they
+    /// share one outcome channel.
+    fn spawn_report_export<R, F>(&mut self, path: PathBuf, ctx: &egui::Context, analyze: F)
+    where
+        R: Reportable,
+        F: FnOnce() -> R + Send + 'static,
+    {
+        let (sender, receiver) = channel();
+        self.report_receiver = Some(receiver);
+
+        let ctx_handle = ctx.clone();
+        thread::spawn(move || {
+            let report = analyze().to_report();
+            let result = report.write_to_file(&path).map(|()| path).map_err(|e| format!("{:#}", e));
+            if sender.send(result).is_ok() {
+                ctx_handle.request_repaint();
+            }
+        });
+    }
+
+    /// Returns the outcome of the report export once it has finished.
+    pub fn check_report_export(&mut self) -> Option<Result<PathBuf, String>> {
+        let result = self.report_receiver.as_ref()?.try_recv().ok()?;
+        self.report_receiver = None;
+        Some(result)
+    }
+
+    // ===== Background File Export =====
+
+    /// Converts the whole trace into a JETS file at `path` on a background
+    /// thread (Brotli-compressed for `.br` paths).
+    ///
+    /// Call `check_file_export()` once per frame to pick up the outcome.
+    pub fn start_jets_export(&mut self, trace: TraceSnapshot, path: PathBuf, ctx: &egui::Context) {
+        self.spawn_file_export("Error exporting trace", ctx, move || {
+            let mut writer = TraceWriter::new(&path.to_string_lossy())?.with_provenance(PRODUCER);
+            rjets::writer::write_trace(&*trace, &mut writer)?;
+            writer.finish()
+        });
+    }
+
+    /// Writes the part of the trace selected by `slice` as a JETS file at
+    /// `path` on a background thread.
+    ///
+    /// Call `check_file_export()` once per frame to pick up the outcome.
+    pub fn start_time_slice_export(&mut self, trace: TraceSnapshot, slice: TraceSlice, path: PathBuf, ctx: &egui::Context) {
+        self.spawn_file_export("Error exporting time slice", ctx, move || {
+            let mut writer = TraceWriter::new(&path.to_string_lossy())?.with_provenance(PRODUCER);
+            write_trace_slice(&trace, &mut writer, &slice)?;
+            writer.finish()
+        });
+    }
+
+    /// Writes a session pack of `slice` to `path` on a background thread,
+    /// after adding a statistics report for the slice's clock range to
+    /// `manifest`. Record names in the report are rewritten with `aliases`
+    /// when given; clocks, durations and IDs use `formats`.
+    ///
+    /// Call `check_file_export()` once per frame to pick up the outcome.
+    #[allow(clippy::too_many_arguments)]
+    pub fn start_session_pack_save(
+        &mut self,
+        trace: TraceSnapshot,
+        slice: TraceSlice,
+        mut manifest: PackManifest,
+        aliases: Option<NameAliases>,
+        formats: ColumnFormats,
+        path: PathBuf,
+        ctx: &egui::Context,
+    ) {
+        self.spawn_file_export("Error saving session pack", ctx, move || {
+            let mut statistics = TraceStatistics::compute_in_range(&trace, slice.range);
+            if let Some(aliases) = &aliases {
+                statistics.apply_aliases(aliases);
+            }
+            statistics.formats = formats;
+            let report = statistics.to_report();
+            manifest.reports.push(PackedReport {
+                title: report.title.clone(),
+                markdown: report.render(ReportFormat::Markdown),
+            });
+
+            jetspack::write_session_pack(&path, &trace, &slice, &manifest)?;
+            Ok(())
+        });
+    }
+
+    /// Builds the event grid of all records of `record_type` and writes it
+    /// to `path` as CSV on a background thread.
+    ///
+    /// Call `check_file_export()` once per frame to pick up the outcome.
+    pub fn start_event_grid_export(&mut self, trace: TraceSnapshot, record_type: String, path: PathBuf, ctx: &egui::Context) {
+        self.spawn_file_export("Error exporting event grid", ctx, move || {
+            let grid = EventGrid::build(&trace, &record_type);
+            let file = std::fs::File::create(&path)
+                .with_context(|| format!("Failed to create file: {}", path.display()))?;
+            let mut out = BufWriter::new(file);
+            grid.write_csv(&mut out)?;
+            out.flush()?;
+            Ok(())
+        });
+    }
+
+    /// Runs `export` on a background thread. Every file export goes through
+    /// here, so they share one outcome channel; a failure is reported as
+    /// `"<failure>: <error>"`.
+    fn spawn_file_export<F>(&mut self, failure: &'static str, ctx: &egui::Context, export: F)
+    where
+        F: FnOnce() -> anyhow::Result<()> + Send + 'static,
+    {
+        let (sender, receiver) = channel();
+        self.export_receiver = Some(receiver);
+
+        let ctx_handle = ctx.clone();
+        thread::spawn(move || {
+            let result = export().map_err(|e| format!("{}: {:#}", failure, e));
+            if sender.send(result).is_ok() {
+                ctx_handle.request_repaint();
+            }
+        });
+    }
+
+    /// Returns the outcome of the file export once it has finished.
+    pub fn check_file_export(&mut self) -> Option<Result<(), String>> {
+        let result = self.export_receiver.as_ref()?.try_recv().ok()?;
+        self.export_receiver = None;
+        Some(result)
+    }
+
+    // ===== Background Flame Graph =====
+
+    /// Aggregates the flame graph of the subtree under `root_id` on a
+    /// background thread. The result of a build still running for another
+    /// record is dropped.
+    ///
+    /// Call `check_flame_graph()` once per frame to pick up the graph.
+    pub fn start_flame_graph(&mut self, trace: TraceSnapshot, root_id: u64, ctx: &egui::Context) {
+        let (sender, receiver) = channel();
+        self.flame_graph_receiver = Some(receiver);
+
+        let ctx_handle = ctx.clone();
+        thread::spawn(move || {
+            let started = Instant::now();
+            let Some(graph) = flame_graph::build_flame_graph(&trace, root_id) else {
+                return;
+            };
+            tracing::debug!(root_id, frames = graph.frames.len(), elapsed_ms = started.elapsed().as_millis(), "flame graph built");
+            if sender.send((trace.generation(), graph)).is_ok() {
+                ctx_handle.request_repaint();
+            }
+        });
+    }
+
+    /// Returns the flame graph and the generation of the snapshot it was
+    /// built from once the build has finished.
+    pub fn check_flame_graph(&mut self) -> Option<(u64, FlameGraph)> {
+        let result = self.flame_graph_receiver.as_ref()?.try_recv().ok()?;
+        self.flame_graph_receiver = None;
+        Some(result)
+    }
+
+    // ===== Background Provenance Check =====
+
+    /// Re-reads the trace file at `path` on a background thread and checks
+    /// its content against the footer's provenance hash.
+    ///
+    /// Call `check_verification()` once per frame to pick up the outcome.
+    pub fn start_verification(&mut self, generation: u64, path: PathBuf, ctx: &egui::Context) {
+        let (sender, receiver) = channel();
+        self.verification_receiver = Some(receiver);
+
+        let ctx_handle = ctx.clone();
+        thread::spawn(move || {
+            let started = Instant::now();
+            let result = rjets::verify_trace_file(&path.to_string_lossy()).map_err(|e| format!("{:#}", e));
+            match &result {
+                Ok(Verification::Verified(_)) => {
+                    tracing::info!(elapsed_ms = started.elapsed().as_millis(), "provenance verified")
+                }
+                Ok(verification) => tracing::warn!(path = %path.display(), "provenance check: {}", verification),
+                Err(e) => tracing::warn!(path = %path.display(), "provenance check failed: {}", e),
+            }
+            if sender.send((generation, result)).is_ok() {
+                ctx_handle.request_repaint();
+            }
+        });
+    }
+
+    /// Returns the provenance check outcome and the generation it belongs to
+    /// once the check has finished.
+    pub fn check_verification(&mut self) -> Option<(u64, Result<Verification, String>)> {
+        let result = self.verification_receiver.as_ref()?.try_recv().ok()?;
+        self.verification_receiver = None;
+        Some(result)
+    }
 }
 
 impl Default for AsyncLoader {
@@ -173,6 +739,8 @@ impl Default for AsyncLoader {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::state::TraceState;
+    use rjets::{TraceMetadata, TraceRecord};
 
     #[test]
     fn test_async_loader_creation() {
@@ -183,8 +751,23 @@ mod tests {
     #[test]
     fn test_virtual_trace_loading() {
         let mut loader = AsyncLoader::new();
-        let result = loader.load_virtual_trace();
-        assert!(result.is_ok(), "Virtual trace loading should succeed");
+        loader.start_virtual_load(&egui::Context::default());
+        assert!(loader.is_loading());
+
+        // One root per poll, then the trace, the same as a blocking read
+        let expected = VirtualTraceReader::new().read("").unwrap();
+        for _ in 0..expected.roots().len() {
+            assert!(matches!(loader.check_completion(), LoadResult::None));
+        }
+        match loader.check_completion() {
+            LoadResult::Success { data, path, session } => {
+                assert_eq!(data.roots(), expected.roots());
+                assert_eq!(data.metadata().trace_extent(), expected.metadata().trace_extent());
+                assert!(path.is_none() && session.is_none());
+            }
+            _ => panic!("virtual trace should be generated after one poll per root"),
+        }
+        assert!(!loader.is_loading());
     }
 
     #[test]
@@ -194,4 +777,273 @@ mod tests {
         matches!(result, LoadResult::None);
     }
 
+    #[test]
+    fn test_precompute_delivers_summary() {
+        let mut loader = AsyncLoader::new();
+        let mut state = TraceState::new();
+        state.load_trace(VirtualTraceReader::new().read("").unwrap(), None);
+        loader.start_precompute(state.snapshot().unwrap(), &egui::Context::default());
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(30);
+        let summary = loop {
+            if let Some((generation, summary)) = loader.check_precompute() {
+                assert!(state.is_current(generation));
+                break summary;
+            }
+            assert!(std::time::Instant::now() < deadline, "precompute did not finish");
+            thread::sleep(std::time::Duration::from_millis(5));
+        };
+        assert!(summary.record_count > 0);
+        assert!(loader.check_precompute().is_none());
+    }
+
+    #[test]
+    fn test_flame_graph_is_built_in_background() {
+        let mut loader = AsyncLoader::new();
+        let mut state = TraceState::new();
+        state.load_trace(VirtualTraceReader::new().read("").unwrap(), None);
+        let root_id = state.trace_data().unwrap().roots()[0];
+        loader.start_flame_graph(state.snapshot().unwrap(), root_id, &egui::Context::default());
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(30);
+        let graph = loop {
+            if let Some((generation, graph)) = loader.check_flame_graph() {
+                assert!(state.is_current(generation));
+                break graph;
+            }
+            assert!(std::time::Instant::now() < deadline, "flame graph was not built");
+            thread::sleep(std::time::Duration::from_millis(5));
+        };
+        assert_eq!(graph.root_id, root_id);
+        assert!(graph.record_count > 0);
+        assert!(loader.check_flame_graph().is_none());
+    }
+
+    /// Non-blocking reader that is pending on its first poll.
+    struct DeferredVirtualReader;
+
+    impl AsyncTraceReader for DeferredVirtualReader {
+        fn read_async(&self, _source: &str) -> TraceReadFuture {
+            let mut polled = false;
+            Box::pin(std::future::poll_fn(move |cx| {
+                if !polled {
+                    polled = true;
+                    cx.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
+                Poll::Ready(VirtualTraceReader::new().read(""))
+            }))
+        }
+    }
+
+    #[test]
+    fn test_non_blocking_reader_is_polled_to_completion() {
+        let mut loader = AsyncLoader::new();
+        let backend = ReaderBackend::NonBlocking(Box::new(DeferredVirtualReader));
+        loader.start_load(backend, PathBuf::from("net://trace"), &egui::Context::default());
+
+        assert!(loader.is_loading());
+        assert!(matches!(loader.check_completion(), LoadResult::None));
+        match loader.check_completion() {
+            LoadResult::Success { path, session, .. } => {
+                assert_eq!(path, Some(PathBuf::from("net://trace")));
+                assert!(session.is_none());
+            }
+            _ => panic!("non-blocking load should complete on the second poll"),
+        }
+        assert!(!loader.is_loading());
+    }
+
+    /// Non-blocking reader that never finishes and flags when its read is dropped.
+    struct StalledReader(Arc<AtomicBool>);
+
+    /// Sets the flag when the read future holding it is dropped.
+    struct DropFlag(Arc<AtomicBool>);
+
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::Relaxed);
+        }
+    }
+
+    impl AsyncTraceReader for StalledReader {
+        fn read_async(&self, _source: &str) -> TraceReadFuture {
+            let flag = DropFlag(Arc::clone(&self.0));
+            Box::pin(std::future::poll_fn(move |_| {
+                let _held = &flag;
+                Poll::Pending
+            }))
+        }
+    }
+
+    #[test]
+    fn test_non_blocking_load_can_be_canceled() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let mut loader = AsyncLoader::new();
+        let backend = ReaderBackend::NonBlocking(Box::new(StalledReader(Arc::clone(&dropped))));
+        loader.start_load(backend, PathBuf::from("net://stalled"), &egui::Context::default());
+        assert!(matches!(loader.check_completion(), LoadResult::None));
+        assert!(loader.is_loading());
+
+        assert!(loader.cancel_load());
+        assert!(dropped.load(Ordering::Relaxed), "the pending read is dropped");
+        assert!(!loader.is_loading());
+        assert!(matches!(loader.check_completion(), LoadResult::None));
+    }
+
+    #[test]
+    fn test_new_load_cancels_the_one_in_flight() {
+        let trace_path = PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/trace.jets"));
+        let ctx = egui::Context::default();
+        let mut loader = AsyncLoader::new();
+        // Still in flight until polled, unlike a read that may fail at once
+        loader.start_virtual_load(&ctx);
+        assert!(loader.cancel_load());
+        assert!(!loader.is_loading());
+        assert!(!loader.cancel_load(), "nothing left to cancel");
+
+        // The replaced load never reports, even if it finishes first
+        loader.start_file_load(trace_path.clone(), ParseOptions::default(), &ctx);
+        loader.start_file_load(trace_path.clone(), ParseOptions::default(), &ctx);
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(30);
+        loop {
+            match loader.check_completion() {
+                LoadResult::Success { path, .. } => {
+                    assert_eq!(path, Some(trace_path));
+                    break;
+                }
+                LoadResult::Error(e) => panic!("load failed: {}", e),
+                LoadResult::None => {}
+            }
+            assert!(std::time::Instant::now() < deadline, "load did not finish");
+            thread::sleep(std::time::Duration::from_millis(5));
+        }
+        assert!(!loader.is_loading());
+        thread::sleep(std::time::Duration::from_millis(50));
+        assert!(matches!(loader.check_completion(), LoadResult::None));
+    }
+
+    #[test]
+    fn test_cancel_precompute_discards_result() {
+        let mut loader = AsyncLoader::new();
+        let mut state = TraceState::new();
+        state.load_trace(VirtualTraceReader::new().read("").unwrap(), None);
+        loader.start_precompute(state.snapshot().unwrap(), &egui::Context::default());
+        loader.cancel_precompute();
+        assert!(loader.check_precompute().is_none());
+    }
+
+    #[test]
+    fn test_report_export_survives_trace_replacement() {
+        let mut loader = AsyncLoader::new();
+        let mut state = TraceState::new();
+        state.load_trace(VirtualTraceReader::new().read("").unwrap(), None);
+        let snapshot = state.snapshot().unwrap();
+
+        let path = std::env::temp_dir().join("jets_snapshot_report_test.md");
+        loader.start_report_export(snapshot.clone(), None, None, ColumnFormats::default(), path.clone(), &egui::Context::default());
+        // The UI moves on to another trace while the export runs
+        state.load_trace(VirtualTraceReader::new().read("").unwrap(), None);
+        assert!(!state.is_current(snapshot.generation()));
+        drop(snapshot);
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(30);
+        let written = loop {
+            if let Some(result) = loader.check_report_export() {
+                break result.unwrap();
+            }
+            assert!(std::time::Instant::now() < deadline, "report export did not finish");
+            thread::sleep(std::time::Duration::from_millis(5));
+        };
+        assert_eq!(written, path);
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(content.contains("Trace Statistics"));
+    }
+
+    #[test]
+    fn test_jets_export_runs_in_background() {
+        let mut loader = AsyncLoader::new();
+        let mut state = TraceState::new();
+        state.load_trace(VirtualTraceReader::new().read("").unwrap(), None);
+        let snapshot = state.snapshot().unwrap();
+
+        let path = std::env::temp_dir().join(format!("jets_background_export_{}.jets.br", std::process::id()));
+        loader.start_jets_export(snapshot.clone(), path.clone(), &egui::Context::default());
+        wait_for_file_export(&mut loader).unwrap();
+        let exported = rjets::parse_trace(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(exported.root_ids().len(), snapshot.root_ids().len());
+
+        // Failures come back ready to show
+        let missing = std::env::temp_dir().join("jets_no_such_dir").join("out.jets");
+        loader.start_jets_export(snapshot, missing, &egui::Context::default());
+        let error = wait_for_file_export(&mut loader).unwrap_err();
+        assert!(error.starts_with("Error exporting trace: "));
+    }
+
+    #[test]
+    fn test_time_slice_export_runs_in_background() {
+        let mut loader = AsyncLoader::new();
+        let mut state = TraceState::new();
+        state.load_trace(VirtualTraceReader::new().read("").unwrap(), None);
+        let snapshot = state.snapshot().unwrap();
+        let (start, end) = snapshot.metadata().trace_extent();
+        let range = (start, start + (end - start) / 4);
+
+        let path = std::env::temp_dir().join(format!("jets_background_slice_{}.jets", std::process::id()));
+        let slice = TraceSlice { subtree_root: None, range: Some(range), clamp_ends: true };
+        loader.start_time_slice_export(snapshot, slice, path.clone(), &egui::Context::default());
+        wait_for_file_export(&mut loader).unwrap();
+        let exported = rjets::parse_trace(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(exported.records_iter().all(|record| record.clk() <= range.1));
+    }
+
+    #[test]
+    fn test_session_pack_save_adds_statistics_report() {
+        let mut loader = AsyncLoader::new();
+        let mut state = TraceState::new();
+        state.load_trace(VirtualTraceReader::new().read("").unwrap(), None);
+        let snapshot = state.snapshot().unwrap();
+
+        let path = std::env::temp_dir().join(format!("jets_background_pack_{}.jetspack", std::process::id()));
+        let manifest = PackManifest::new("virtual".to_string(), Default::default());
+        let slice = TraceSlice { range: Some(snapshot.metadata().trace_extent()), ..Default::default() };
+        loader.start_session_pack_save(snapshot, slice, manifest, None, ColumnFormats::default(), path.clone(), &egui::Context::default());
+        wait_for_file_export(&mut loader).unwrap();
+        let (manifest, data) = jetspack::read_session_pack(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(manifest.reports.len(), 1);
+        assert!(manifest.reports[0].markdown.contains("Region Statistics"));
+        assert!(!data.root_ids().is_empty());
+    }
+
+    #[test]
+    fn test_event_grid_export_runs_in_background() {
+        let mut loader = AsyncLoader::new();
+        let mut state = TraceState::new();
+        state.load_trace(VirtualTraceReader::new().read("").unwrap(), None);
+        let snapshot = state.snapshot().unwrap();
+        let roots = snapshot.records_iter().filter(|record| record.record_type() == "Level0").count();
+
+        let path = std::env::temp_dir().join(format!("jets_background_grid_{}.csv", std::process::id()));
+        loader.start_event_grid_export(snapshot, "Level0".to_string(), path.clone(), &egui::Context::default());
+        wait_for_file_export(&mut loader).unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        // Header plus one row per record of the type
+        assert_eq!(csv.lines().count(), roots + 1);
+    }
+
+    fn wait_for_file_export(loader: &mut AsyncLoader) -> Result<(), String> {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(30);
+        loop {
+            if let Some(result) = loader.check_file_export() {
+                return result;
+            }
+            assert!(std::time::Instant::now() < deadline, "export did not finish");
+            thread::sleep(std::time::Duration::from_millis(5));
+        }
+    }
 }
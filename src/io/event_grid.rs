@@ -0,0 +1,146 @@
+//! Per-record event grid: one row per record of a type, one column per event name.
+//!
+//! Pipeline studies usually want an instruction × stage matrix: for every
+//! instruction, the clock at which it reached each stage. The grid holds
+//! exactly that for all records of one type, with event names as columns in
+//! the order they first occur (records taken by start clock), so stages come
+//! out in pipeline order. An event repeated on the same record keeps its
+//! first clock; records without a given event leave that cell empty.
+
+use std::collections::HashMap;
+use std::io::Write;
+
+use anyhow::Result;
+use rjets::{DynTraceData, ExternalId, TraceData, TraceEvent, TraceRecord};
+
+/// Columns written before the event columns.
+pub const FIXED_COLUMNS: [&str; 4] = ["id", "name", "clk", "end_clk"];
+
+/// One record of the grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventGridRow {
+    pub id: ExternalId,
+    pub name: String,
+    pub clk: i64,
+    pub end_clk: Option<i64>,
+    /// Clock of each event column's event, `None` if the record has none
+    pub cells: Vec<Option<i64>>,
+}
+
+/// Event clocks of all records of one type.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EventGrid {
+    /// Event names, one per column
+    pub columns: Vec<String>,
+    /// Records ordered by start clock
+    pub rows: Vec<EventGridRow>,
+}
+
+impl EventGrid {
+    /// Collects the grid for all records of `record_type`.
+    pub fn build(trace: &DynTraceData, record_type: &str) -> Self {
+        let mut records: Vec<_> = trace.records_iter().filter(|r| r.record_type() == record_type).collect();
+        records.sort_by_key(|r| (r.clk(), r.id()));
+
+        let mut columns: Vec<String> = Vec::new();
+        let mut column_of: HashMap<String, usize> = HashMap::new();
+        let mut rows = Vec::with_capacity(records.len());
+        for record in &records {
+            let mut cells: Vec<Option<i64>> = vec![None; columns.len()];
+            for event in (0..record.num_events()).filter_map(|i| record.event_at(i)) {
+                let name = event.name();
+                let column = *column_of.entry(name).or_insert_with_key(|name| {
+                    columns.push(name.clone());
+                    columns.len() - 1
+                });
+                if column >= cells.len() {
+                    cells.resize(column + 1, None);
+                }
+                cells[column].get_or_insert(event.clk());
+            }
+            rows.push(EventGridRow {
+                id: record.external_id(),
+                name: record.name(),
+                clk: record.clk(),
+                end_clk: record.end_clk(),
+                cells,
+            });
+        }
+        // Rows built before a column appeared are shorter
+        for row in &mut rows {
+            row.cells.resize(columns.len(), None);
+        }
+        EventGrid { columns, rows }
+    }
+
+    /// Returns the header row: fixed columns, then event names.
+    pub fn header(&self) -> impl Iterator<Item = &str> {
+        FIXED_COLUMNS.iter().copied().chain(self.columns.iter().map(String::as_str))
+    }
+
+    /// Writes the grid as CSV (RFC 4180 quoting, empty cells for missing values).
+    pub fn write_csv(&self, out: &mut impl Write) -> Result<()> {
+        write_csv_row(out, self.header().map(str::to_string))?;
+        for row in &self.rows {
+            let fixed = [
+                row.id.to_string(),
+                row.name.clone(),
+                row.clk.to_string(),
+                row.end_clk.map(|c| c.to_string()).unwrap_or_default(),
+            ];
+            let cells = row.cells.iter().map(|c| c.map(|c| c.to_string()).unwrap_or_default());
+            write_csv_row(out, fixed.into_iter().chain(cells))?;
+        }
+        Ok(())
+    }
+}
+
+fn write_csv_row(out: &mut impl Write, fields: impl Iterator<Item = String>) -> Result<()> {
+    let line: Vec<String> = fields.map(|f| csv_field(&f)).collect();
+    writeln!(out, "{}", line.join(","))?;
+    Ok(())
+}
+
+/// Quotes a field if it contains a separator, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_has_stage_columns_in_pipeline_order() {
+        let input = concat!(
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"thread","record_type":"Thread","id":1,"parent_id":null,"description":""}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"add, x1","record_type":"Instr","id":2,"parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"event","clk":0,"name":"F","record_id":2,"description":""}"#, "\n",
+            r#"{"type":"event","clk":1,"name":"D","record_id":2,"description":""}"#, "\n",
+            r#"{"type":"event","clk":3,"name":"D","record_id":2,"description":""}"#, "\n",
+            r#"{"type":"record_end","clk":4,"record_id":2}"#, "\n",
+            r#"{"type":"record","clk":1,"name":"ld","record_type":"Instr","id":"ld-3","parent_id":1,"description":""}"#, "\n",
+            r#"{"type":"event","clk":1,"name":"F","record_id":"ld-3","description":""}"#, "\n",
+            r#"{"type":"event","clk":5,"name":"M","record_id":"ld-3","description":""}"#, "\n",
+        );
+        let trace = DynTraceData::Jets(rjets::parse_trace_from_reader(input.as_bytes()).unwrap());
+        let grid = EventGrid::build(&trace, "Instr");
+
+        assert_eq!(grid.columns, ["F", "D", "M"]);
+        assert_eq!(grid.rows[0].cells, [Some(0), Some(1), None], "first D kept");
+        assert_eq!(grid.rows[1].cells, [Some(1), None, Some(5)]);
+
+        let mut csv = Vec::new();
+        grid.write_csv(&mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "id,name,clk,end_clk,F,D,M\n2,\"add, x1\",0,4,0,1,\nld-3,ld,1,,1,,5\n",
+        );
+        assert!(EventGrid::build(&trace, "Missing").rows.is_empty());
+    }
+}
@@ -0,0 +1,260 @@
+//! `.jetspack` session pack archives.
+//!
+//! A session pack bundles everything needed to share a finding: a slice of
+//! the trace, the view that was on screen, the user's notes/tags and rendered
+//! analysis reports. The file is a single Brotli-compressed JSON Lines stream
+//! whose first line is the [`PackManifest`]; the remaining lines are a regular
+//! JETS trace, so the trace part is read by the standard parser.
+
+use anyhow::{bail, Context, Result};
+use rjets::prefetch::brotli_reader;
+use rjets::{parse_trace_from_reader, BrotliWriter, DynTraceData, JetsTraceData, TraceWriter};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufRead, BufWriter, Write};
+use std::path::Path;
+
+use crate::io::trace_slice::{write_trace_slice, SliceSummary, TraceSlice};
+use crate::state::{RecordNote, SortSpec};
+
+/// Value of the manifest `type` field identifying a session pack.
+const MANIFEST_TYPE: &str = "jetspack";
+
+/// Current session pack format version.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// File extension used for session packs.
+pub const EXTENSION: &str = "jetspack";
+
+/// View state restored when a session pack is opened.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ViewDescriptor {
+    pub viewport_start_clk: i64,
+    pub viewport_end_clk: i64,
+    /// Record IDs that were expanded in the tree
+    pub expanded: Vec<u64>,
+    pub selected_record: Option<u64>,
+    /// Selected event as (record ID, event clock)
+    pub selected_event: Option<(u64, i64)>,
+    pub viewport_filter: bool,
+    /// Whether the viewport filter hid parents without a leaf in range
+    pub hide_empty_parents: bool,
+    pub active_sort: Option<SortSpec>,
+    /// Roots hidden by the root selector
+    pub hidden_roots: Vec<u64>,
+}
+
+/// An analysis report carried inside a session pack.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackedReport {
+    pub title: String,
+    /// Report rendered as Markdown
+    pub markdown: String,
+}
+
+/// First line of a session pack.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackManifest {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub format_version: u32,
+    /// Name of the trace the pack was cut from
+    #[serde(default)]
+    pub source: String,
+    #[serde(default)]
+    pub view: ViewDescriptor,
+    #[serde(default)]
+    pub notes: BTreeMap<u64, RecordNote>,
+    #[serde(default)]
+    pub reports: Vec<PackedReport>,
+}
+
+impl PackManifest {
+    /// Creates a manifest for the current format version.
+    pub fn new(source: String, view: ViewDescriptor) -> Self {
+        Self {
+            kind: MANIFEST_TYPE.to_string(),
+            format_version: FORMAT_VERSION,
+            source,
+            view,
+            notes: BTreeMap::new(),
+            reports: Vec::new(),
+        }
+    }
+}
+
+/// Returns true if the path looks like a session pack.
+pub fn is_session_pack(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()) == Some(EXTENSION)
+}
+
+/// Writes a session pack: the manifest line followed by the trace slice.
+pub fn write_session_pack(
+    path: &Path,
+    trace: &DynTraceData,
+    slice: &TraceSlice,
+    manifest: &PackManifest,
+) -> Result<SliceSummary> {
+    let file = File::create(path)
+        .with_context(|| format!("Failed to create file: {}", path.display()))?;
+    let mut compressor = BrotliWriter::new(BufWriter::new(file));
+
+    let manifest_line = serde_json::to_string(manifest).context("Failed to serialize manifest")?;
+    writeln!(compressor, "{}", manifest_line).context("Failed to write manifest")?;
+
+    let mut writer = TraceWriter::from_brotli(compressor);
+    let summary = write_trace_slice(trace, &mut writer, slice)?;
+    writer.finish()?;
+    Ok(summary)
+}
+
+/// Reads a session pack, returning its manifest and the embedded trace.
+pub fn read_session_pack(path: &Path) -> Result<(PackManifest, JetsTraceData)> {
+    let file = File::open(path)
+        .with_context(|| format!("Failed to open file: {}", path.display()))?;
+    let mut reader = brotli_reader(file).context("Failed to start decompression thread")?;
+
+    let mut first_line = String::new();
+    reader.read_line(&mut first_line).context("Failed to read manifest")?;
+    let manifest: PackManifest = serde_json::from_str(first_line.trim())
+        .context("Failed to parse session pack manifest")?;
+    if manifest.kind != MANIFEST_TYPE {
+        bail!("Not a session pack (type: {})", manifest.kind);
+    }
+    if manifest.format_version > FORMAT_VERSION {
+        bail!(
+            "Unsupported session pack version {} (max supported: {})",
+            manifest.format_version,
+            FORMAT_VERSION
+        );
+    }
+
+    let data = parse_trace_from_reader(reader).context("Failed to parse packed trace")?;
+    Ok((manifest, data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::trace_slice::CLAMPED_END_ATTR;
+    use rjets::{AttributeAccessor, JetsTraceReader, TraceData, TraceReader, TraceRecord};
+
+    fn load_test_trace() -> DynTraceData {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/trace.jets");
+        JetsTraceReader::new().read(path).expect("test trace should load")
+    }
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("{}_{}.jetspack", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_session_pack_round_trip() {
+        let trace = load_test_trace();
+        let path = temp_path("round_trip");
+
+        let view = ViewDescriptor {
+            viewport_start_clk: 10,
+            viewport_end_clk: 200,
+            expanded: vec![1, 2],
+            selected_record: Some(2),
+            ..Default::default()
+        };
+        let mut manifest = PackManifest::new("trace.jets".to_string(), view.clone());
+        manifest.notes.insert(
+            2,
+            RecordNote {
+                text: "stall here".to_string(),
+                tags: vec!["stall".to_string()],
+            },
+        );
+        manifest.reports.push(PackedReport {
+            title: "Stats".to_string(),
+            markdown: "# Stats".to_string(),
+        });
+
+        let summary = write_session_pack(&path, &trace, &TraceSlice::default(), &manifest)
+            .expect("pack should be written");
+        let (read_manifest, data) = read_session_pack(&path).expect("pack should be read");
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(read_manifest, manifest);
+        assert_eq!(read_manifest.view, view);
+        assert_eq!(summary.records, 103);
+        assert_eq!(summary.events, 1014);
+        assert_eq!(data.root_ids(), trace.root_ids());
+    }
+
+    #[test]
+    fn test_slice_keeps_ancestors_and_drops_out_of_range() {
+        let trace = load_test_trace();
+        let path = temp_path("slice");
+
+        // Pick a depth-2 record (thread) and restrict to its first half
+        let root = trace.get_record(trace.root_ids()[0]).unwrap();
+        let core = root.child_at(0).unwrap();
+        let thread = core.child_at(0).unwrap();
+        let start = thread.clk();
+        let end = start + thread.duration().unwrap_or(0) / 2;
+        let slice = TraceSlice {
+            subtree_root: Some(thread.id()),
+            range: Some((start, end)),
+            ..Default::default()
+        };
+
+        let manifest = PackManifest::new(String::new(), ViewDescriptor::default());
+        let summary = write_session_pack(&path, &trace, &slice, &manifest).unwrap();
+        let (_, data) = read_session_pack(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(data.root_ids(), vec![root.id()]);
+        assert!(data.get_record(core.id()).is_some());
+        assert!(data.get_record(thread.id()).is_some());
+        assert!(summary.records < 103);
+        assert!(summary.events < 1014);
+        let packed_thread = data.get_record(thread.id()).unwrap();
+        for i in 0..packed_thread.num_children() {
+            assert!(packed_thread.child_at(i).unwrap().clk() <= end);
+        }
+    }
+
+    #[test]
+    fn test_slice_clamps_ends_past_range() {
+        let trace = load_test_trace();
+        let path = temp_path("clamped");
+
+        let root = trace.get_record(trace.root_ids()[0]).unwrap();
+        let end = root.clk() + root.duration().unwrap_or(0) / 4;
+        let slice = TraceSlice {
+            range: Some((root.clk(), end)),
+            clamp_ends: true,
+            ..Default::default()
+        };
+
+        let manifest = PackManifest::new(String::new(), ViewDescriptor::default());
+        let summary = write_session_pack(&path, &trace, &slice, &manifest).unwrap();
+        let (_, data) = read_session_pack(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert!(summary.clamped > 0);
+        let packed_root = data.get_record(root.id()).unwrap();
+        assert_eq!(packed_root.end_clk(), Some(end));
+        assert_eq!(
+            packed_root.attr(CLAMPED_END_ATTR),
+            Some(serde_json::json!(root.end_clk()))
+        );
+    }
+
+    #[test]
+    fn test_rejects_non_pack_manifest() {
+        let path = temp_path("not_pack");
+        let mut w = BrotliWriter::new(File::create(&path).unwrap());
+        writeln!(w, r#"{{"type":"header","format_version":1}}"#).unwrap();
+        w.finish().unwrap();
+        let result = read_session_pack(&path);
+        let _ = std::fs::remove_file(&path);
+        assert!(result.is_err());
+    }
+}
@@ -1,7 +1,12 @@
-//! I/O modules for file loading and trace generation.
+//! I/O modules for file loading, trace slicing, event grid export, session packs, settings profiles and session scripts.
 
 pub mod file_loader;
 pub mod async_loader;
+pub mod trace_slice;
+pub mod event_grid;
+pub mod jetspack;
+pub mod settings_profile;
+pub mod session_script;
 
 // Re-export commonly used types
 pub use file_loader::LoadingState;
@@ -0,0 +1,126 @@
+//! Session scripts.
+//!
+//! A session script is a JSON-lines file of high-level user actions (opening
+//! files, view ranges, selections, filter changes), one action per line, in
+//! the order they happened. `--record <script>` writes one while the viewer is
+//! used and `--replay <script>` runs it again, so a reported UI bug can be
+//! reproduced step by step against the same trace.
+//!
+//! Records are referred to by the viewer's record IDs, which are stable for
+//! the same trace file.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::domain::severity::Severity;
+
+/// One recorded user action.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ScriptAction {
+    /// Trace files were opened (several are merged)
+    Open { paths: Vec<PathBuf> },
+    /// The main time window settled on a clock range
+    ViewRange { start: i64, end: i64 },
+    /// The selection changed (no record = cleared)
+    Select {
+        record_id: Option<u64>,
+        event_clk: Option<i64>,
+    },
+    /// The viewport filter was switched on or off
+    ViewportFilter { enabled: bool },
+    /// The set of hidden record types changed
+    HiddenTypes { types: Vec<String> },
+    /// The clock stride filter changed (no stride = off)
+    ClockStride { stride: Option<i64>, offset: i64 },
+    /// The minimum severity of shown events changed
+    MinSeverity { severity: Severity },
+}
+
+/// Appends actions to a script file, one line each.
+pub struct ScriptWriter {
+    writer: BufWriter<File>,
+}
+
+impl ScriptWriter {
+    /// Creates (or truncates) a script file.
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+        Ok(Self {
+            writer: BufWriter::new(file),
+        })
+    }
+
+    /// Writes actions and flushes, so the script survives a crash.
+    pub fn append(&mut self, actions: &[ScriptAction]) -> Result<()> {
+        for action in actions {
+            serde_json::to_writer(&mut self.writer, action)?;
+            self.writer.write_all(b"\n")?;
+        }
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Parses a script; blank lines are skipped.
+pub fn parse_script(text: &str) -> Result<Vec<ScriptAction>> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| serde_json::from_str(line).with_context(|| format!("Invalid action on line {}", index + 1)))
+        .collect()
+}
+
+/// Reads a script file.
+pub fn read_script(path: &Path) -> Result<Vec<ScriptAction>> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    parse_script(&text).with_context(|| format!("Invalid session script {}", path.display()))
+}
+
+/// Removes `<flag> <PATH>` (or `<flag>=<PATH>`) from an argument list.
+///
+/// # Returns
+/// The path given, `Ok(None)` if the flag is absent, or an error message if
+/// the path is missing.
+pub fn take_path_arg(args: &mut Vec<String>, flag: &str) -> Result<Option<PathBuf>, String> {
+    let prefix = format!("{}=", flag);
+    let Some(index) = args.iter().position(|arg| arg == flag || arg.starts_with(&prefix)) else {
+        return Ok(None);
+    };
+    let arg = args.remove(index);
+    match arg.strip_prefix(&prefix) {
+        Some(value) => Ok(Some(PathBuf::from(value))),
+        None if index < args.len() => Ok(Some(PathBuf::from(args.remove(index)))),
+        None => Err(format!("{} requires a script path", flag)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_actions_round_trip_through_lines() {
+        let actions = vec![
+            ScriptAction::Open { paths: vec![PathBuf::from("a.jets")] },
+            ScriptAction::ViewRange { start: -5, end: 100 },
+            ScriptAction::Select { record_id: Some(3), event_clk: None },
+            ScriptAction::MinSeverity { severity: Severity::Warning },
+        ];
+        let text: String = actions.iter().map(|action| serde_json::to_string(action).unwrap() + "\n\n").collect();
+        assert!(text.starts_with(r#"{"action":"open","paths":["a.jets"]}"#));
+        assert_eq!(parse_script(&text).unwrap(), actions);
+
+        let error = parse_script("{\"action\":\"open\",\"paths\":[]}\n{\"action\":\"fly\"}").unwrap_err();
+        assert_eq!(error.to_string(), "Invalid action on line 2");
+
+        let mut args: Vec<String> = ["--replay", "s.jsonl", "t.jets", "--record=out.jsonl"].map(String::from).to_vec();
+        assert_eq!(take_path_arg(&mut args, "--replay"), Ok(Some(PathBuf::from("s.jsonl"))));
+        assert_eq!(take_path_arg(&mut args, "--record"), Ok(Some(PathBuf::from("out.jsonl"))));
+        assert_eq!(args, ["t.jets"]);
+        assert!(take_path_arg(&mut vec!["--record".to_string()], "--record").is_err());
+    }
+}
@@ -0,0 +1,133 @@
+//! Settings profile files.
+//!
+//! A profile is a JSON file bundling the user preferences that make up a
+//! viewer configuration: theme, tree column layout, record type colors and
+//! value tracks, event marker shapes, and the interaction preferences of the
+//! settings dialog.
+//! Teams share one to give everybody the same setup. Window placement is machine specific and
+//! not part of a profile.
+//!
+//! Every section is optional, so a hand-written profile may carry only the
+//! colors, for example; importing it leaves the other settings alone.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::domain::event_markers::MarkerShape;
+use crate::state::{InputSettings, ValueTrack};
+
+/// Value of the `type` field identifying a settings profile.
+const PROFILE_TYPE: &str = "jets-settings-profile";
+
+/// Current settings profile format version.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// File extension used for settings profiles.
+pub const EXTENSION: &str = "json";
+
+/// User preferences bundled in a profile file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SettingsProfile {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub format_version: u32,
+    #[serde(default)]
+    pub theme: Option<String>,
+    /// Tree column widths [Name, Description, Start Clock, Duration, ID]
+    #[serde(default)]
+    pub column_widths: Option<[f32; 5]>,
+    /// Width of the tree expand column
+    #[serde(default)]
+    pub expand_width: Option<f32>,
+    #[serde(default)]
+    pub root_lanes: Option<bool>,
+    #[serde(default)]
+    pub low_power: Option<bool>,
+    /// Record type color overrides as unmultiplied RGBA
+    #[serde(default)]
+    pub record_type_colors: Option<BTreeMap<String, [u8; 4]>>,
+    /// Record type value tracks (bar height from an attribute)
+    #[serde(default)]
+    pub value_tracks: Option<BTreeMap<String, ValueTrack>>,
+    /// Event marker shapes keyed by event name
+    #[serde(default)]
+    pub event_marker_shapes: Option<BTreeMap<String, MarkerShape>>,
+    /// Click actions, wheel, tooltip, timeline and loading preferences
+    #[serde(default)]
+    pub input_settings: Option<InputSettings>,
+}
+
+impl Default for SettingsProfile {
+    fn default() -> Self {
+        Self {
+            kind: PROFILE_TYPE.to_string(),
+            format_version: FORMAT_VERSION,
+            theme: None,
+            column_widths: None,
+            expand_width: None,
+            root_lanes: None,
+            low_power: None,
+            record_type_colors: None,
+            value_tracks: None,
+            event_marker_shapes: None,
+            input_settings: None,
+        }
+    }
+}
+
+/// Writes a settings profile as pretty-printed JSON.
+pub fn write_settings_profile(path: &Path, profile: &SettingsProfile) -> Result<()> {
+    let json = serde_json::to_string_pretty(profile).context("Failed to serialize settings profile")?;
+    fs::write(path, json).with_context(|| format!("Failed to write file: {}", path.display()))
+}
+
+/// Reads a settings profile, rejecting other JSON files and newer versions.
+pub fn read_settings_profile(path: &Path) -> Result<SettingsProfile> {
+    let json = fs::read_to_string(path).with_context(|| format!("Failed to open file: {}", path.display()))?;
+    let profile: SettingsProfile = serde_json::from_str(&json).context("Failed to parse settings profile")?;
+    if profile.kind != PROFILE_TYPE {
+        bail!("Not a settings profile (type: {})", profile.kind);
+    }
+    if profile.format_version > FORMAT_VERSION {
+        bail!(
+            "Unsupported settings profile version {} (max supported: {})",
+            profile.format_version,
+            FORMAT_VERSION
+        );
+    }
+    Ok(profile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_round_trip_and_partial_profiles() {
+        let path = std::env::temp_dir().join(format!("settings_profile_{}.json", std::process::id()));
+
+        let input_settings = InputSettings { gridlines: true, ..Default::default() };
+        let profile = SettingsProfile {
+            theme: Some("Light".to_string()),
+            column_widths: Some([200.0, 250.0, 100.0, 100.0, 60.0]),
+            record_type_colors: Some([("Thread".to_string(), [10, 20, 30, 255])].into_iter().collect()),
+            event_marker_shapes: Some([("fetch".to_string(), MarkerShape::Diamond)].into_iter().collect()),
+            input_settings: Some(input_settings),
+            ..Default::default()
+        };
+        write_settings_profile(&path, &profile).unwrap();
+        assert_eq!(read_settings_profile(&path).unwrap(), profile);
+
+        fs::write(&path, r#"{"type": "jets-settings-profile", "format_version": 1, "low_power": true}"#).unwrap();
+        let partial = read_settings_profile(&path).unwrap();
+        assert_eq!(partial.low_power, Some(true));
+        assert_eq!(partial.theme, None);
+
+        fs::write(&path, r#"{"type": "jetspack", "format_version": 1}"#).unwrap();
+        assert!(read_settings_profile(&path).is_err());
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,222 @@
+//! Writing a subtree and/or clock-range slice of a loaded trace as JETS.
+//!
+//! The slice is re-emitted through `TraceWriter` so the output is a valid,
+//! self-contained JETS stream: the header is copied from the source trace,
+//! ancestors of the chosen subtree are kept so parent IDs resolve, and
+//! records/events outside the clock range are dropped. Optionally, records
+//! that run past the end of the range are cut off at it, with the original end
+//! kept in their data under [`CLAMPED_END_ATTR`].
+
+use anyhow::Result;
+use rjets::{AttributeAccessor, DynTraceData, DynTraceRecord, ExternalId, TraceData, TraceEvent, TraceMetadata, TraceRecord, TraceWriter};
+
+/// Data attribute holding the original end clock of a record whose end was
+/// clamped to the slice range (`null` if the record had no end).
+pub const CLAMPED_END_ATTR: &str = "slice_original_end_clk";
+
+/// Which part of a trace to export.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TraceSlice {
+    /// Export only this record's subtree (plus its ancestor chain); None = all roots
+    pub subtree_root: Option<u64>,
+    /// Export only records overlapping / events inside this clock range; None = unbounded
+    pub range: Option<(i64, i64)>,
+    /// End records that run past the range (or never end) at the range end
+    pub clamp_ends: bool,
+}
+
+/// Counts of what was written by [`write_trace_slice`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SliceSummary {
+    pub records: usize,
+    pub events: usize,
+    /// Records whose end was clamped to the range end
+    pub clamped: usize,
+}
+
+impl TraceSlice {
+    /// Returns true if a record with the given interval overlaps the slice range.
+    fn overlaps(&self, clk: i64, end_clk: Option<i64>) -> bool {
+        match self.range {
+            Some((start, end)) => clk <= end && end_clk.unwrap_or(clk) >= start,
+            None => true,
+        }
+    }
+
+    /// Returns true if an event clock lies inside the slice range.
+    fn contains(&self, clk: i64) -> bool {
+        match self.range {
+            Some((start, end)) => clk >= start && clk <= end,
+            None => true,
+        }
+    }
+
+    /// Returns the clamped end clock if a record with this end must be cut off.
+    fn clamped_end(&self, end_clk: Option<i64>) -> Option<i64> {
+        let (_, range_end) = self.range.filter(|_| self.clamp_ends)?;
+        end_clk.is_none_or(|end| end > range_end).then_some(range_end)
+    }
+
+    /// Returns true if the record or any of its descendants overlaps the range.
+    ///
+    /// Used to keep parents whose own interval misses the range (e.g. records
+    /// that were never ended) when some of their children fall inside it.
+    fn subtree_overlaps(&self, record: &DynTraceRecord<'_>) -> bool {
+        let mut stack = vec![record.clone()];
+        while let Some(record) = stack.pop() {
+            if self.overlaps(record.clk(), record.end_clk()) {
+                return true;
+            }
+            stack.extend((0..record.num_children()).filter_map(|i| record.child_at(i)));
+        }
+        false
+    }
+}
+
+/// Writes the selected slice of `trace` (header, records, events, footer).
+///
+/// Record data and annotations are merged in the trace API, so they are
+/// written back as the record's `data` object. IDs are written in their
+/// original (integer or string) form. Ancestors of `subtree_root`
+/// are always emitted, even if they fall outside the clock range, so the
+/// output has no dangling parent references.
+pub fn write_trace_slice(
+    trace: &DynTraceData,
+    writer: &mut TraceWriter,
+    slice: &TraceSlice,
+) -> Result<SliceSummary> {
+    let metadata = trace.metadata();
+    writer.write_header(&metadata.version(), metadata.header_data().clone())?;
+
+    let mut summary = SliceSummary::default();
+
+    // Ancestor chain of the subtree root, innermost first
+    let mut ancestors = Vec::new();
+    if let Some(root_id) = slice.subtree_root {
+        let mut current = trace.get_record(root_id).and_then(|r| r.parent_id());
+        while let Some(id) = current {
+            let Some(record) = trace.get_record(id) else { break };
+            current = record.parent_id();
+            ancestors.push(record);
+        }
+    }
+    for record in ancestors.iter().rev() {
+        write_record_line(trace, writer, record, slice, &mut summary)?;
+    }
+
+    let subtree_root = slice.subtree_root.map(|root_id| [root_id]);
+    let roots: &[u64] = match &subtree_root {
+        Some(root) => root,
+        None => trace.roots(),
+    };
+    for &root_id in roots {
+        if let Some(record) = trace.get_record(root_id) {
+            write_subtree(trace, writer, &record, slice, &mut summary)?;
+        }
+    }
+
+    // Close ancestors after their descendants
+    for record in &ancestors {
+        write_record_end(writer, record, slice)?;
+    }
+
+    let capture_end = match slice.range {
+        Some((_, end)) => Some(end),
+        None => metadata.capture_end_clk(),
+    };
+    writer.write_footer(capture_end)?;
+    Ok(summary)
+}
+
+/// Writes a record's `record` line with its attributes as data.
+///
+/// If the slice clamps the record's end, the original end is added to the data.
+fn write_record_line(
+    trace: &DynTraceData,
+    writer: &mut TraceWriter,
+    record: &DynTraceRecord<'_>,
+    slice: &TraceSlice,
+    summary: &mut SliceSummary,
+) -> Result<()> {
+    let mut attrs = record.attrs();
+    if slice.clamped_end(record.end_clk()).is_some() {
+        attrs.push((CLAMPED_END_ATTR.to_string(), record.end_clk().into()));
+        summary.clamped += 1;
+    }
+    summary.records += 1;
+
+    let data = if attrs.is_empty() {
+        None
+    } else {
+        Some(serde_json::Value::Object(attrs.into_iter().collect()))
+    };
+    let parent_id = record.parent_id().map(|pid| {
+        trace.get_record(pid).map_or(ExternalId::Int(pid), |parent| parent.external_id())
+    });
+    writer.write_record_external(
+        &record.external_id(),
+        parent_id.as_ref(),
+        &record.record_type(),
+        record.clk(),
+        &record.name(),
+        &record.description(),
+        data,
+    )
+}
+
+/// Writes a record's `record_end` line, clamped to the slice range if requested.
+fn write_record_end(writer: &mut TraceWriter, record: &DynTraceRecord<'_>, slice: &TraceSlice) -> Result<()> {
+    match slice.clamped_end(record.end_clk()).or(record.end_clk()) {
+        Some(end_clk) => writer.write_record_end(record.external_id(), end_clk),
+        None => Ok(()),
+    }
+}
+
+/// Writes a record, its in-range events and its in-range descendants (iteratively).
+fn write_subtree(
+    trace: &DynTraceData,
+    writer: &mut TraceWriter,
+    root: &DynTraceRecord<'_>,
+    slice: &TraceSlice,
+    summary: &mut SliceSummary,
+) -> Result<()> {
+    // Each stack entry is (record, children_pushed); record_end is written on the way up
+    let mut stack: Vec<(DynTraceRecord<'_>, bool)> = vec![(root.clone(), false)];
+
+    while let Some((record, visited)) = stack.pop() {
+        if visited {
+            write_record_end(writer, &record, slice)?;
+            continue;
+        }
+
+        if !slice.overlaps(record.clk(), record.end_clk()) && !slice.subtree_overlaps(&record) {
+            continue;
+        }
+
+        write_record_line(trace, writer, &record, slice, summary)?;
+
+        for i in 0..record.num_events() {
+            if let Some(event) = record.event_at(i) {
+                if !slice.contains(event.clk()) {
+                    continue;
+                }
+                let attrs = event.attrs();
+                let data = if attrs.is_empty() {
+                    None
+                } else {
+                    Some(serde_json::Value::Object(attrs.into_iter().collect()))
+                };
+                writer.write_event(record.external_id(), &event.name(), &event.description(), event.clk(), data)?;
+                summary.events += 1;
+            }
+        }
+
+        let children: Vec<_> = (0..record.num_children()).filter_map(|i| record.child_at(i)).collect();
+        stack.push((record, true));
+        for child in children.into_iter().rev() {
+            stack.push((child, false));
+        }
+    }
+
+    Ok(())
+}
@@ -19,9 +19,12 @@
 //! - `utils/` - Utility functions for formatting and geometry
 //! - `ui/` - UI panel rendering, interaction, and input handling
 //! - `rendering/` - Low-level rendering for tree nodes and timelines
+//! - `reporting/` - Markdown/HTML report export shared by analyses
 //! - `state/` - State management for viewport and selection
 
 use eframe::egui;
+use rjets::logging;
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 mod utils;
@@ -31,100 +34,248 @@ mod presentation;
 mod io;
 mod app;
 mod rendering;
+mod reporting;
 mod ui;
 mod state;
 
-use app::{AppState, ApplicationCoordinator, ThemeCoordinator, SettingsCoordinator};
+use app::{ActionRegistry, AppState, ApplicationCoordinator, ThemeCoordinator, SettingsCoordinator, RepaintCoordinator, SessionRecorder, SessionReplay, StartupTimer, COLUMN_WIDTHS_KEY, DEFAULT_COLUMN_WIDTHS, FIRST_FRAME};
 use io::AsyncLoader;
+use state::WindowState;
 use ui::panel_manager::PanelManager;
 
-const COLUMN_WIDTHS_KEY: &str = "column_widths";
 const EXPAND_WIDTH_KEY: &str = "expand_width";
+const ROOT_LANES_KEY: &str = "root_lanes";
+const LOW_POWER_KEY: &str = "low_power_mode";
+const RECORD_TYPE_COLORS_KEY: &str = "record_type_colors";
+const VALUE_TRACKS_KEY: &str = "value_tracks";
+const INPUT_SETTINGS_KEY: &str = "input_settings";
+const WINDOW_PLACEMENTS_KEY: &str = "window_placements";
+const DOCK_LAYOUT_KEY: &str = "dock_layout";
+const DOCK_PRESETS_KEY: &str = "dock_presets";
+const RECENT_FILES_KEY: &str = "recent_files";
+
+/// Keys of the JSON settings read (and migrated) at startup.
+const SETTINGS_KEYS: [&str; 11] = [
+    COLUMN_WIDTHS_KEY,
+    EXPAND_WIDTH_KEY,
+    ROOT_LANES_KEY,
+    LOW_POWER_KEY,
+    RECORD_TYPE_COLORS_KEY,
+    VALUE_TRACKS_KEY,
+    INPUT_SETTINGS_KEY,
+    WINDOW_PLACEMENTS_KEY,
+    DOCK_LAYOUT_KEY,
+    DOCK_PRESETS_KEY,
+    RECENT_FILES_KEY,
+];
 
 /// Main application entry point that initializes and launches the JETS trace viewer GUI.
 fn main() -> eframe::Result {
-    // Parse command-line arguments to check for initial file to load
-    let initial_file = std::env::args()
-        .nth(1)
-        .map(PathBuf::from);
+    let startup = StartupTimer::start();
+
+    // Parse command-line arguments: --log-level, --record/--replay scripts,
+    // then initial files to load (several are merged)
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let exit_with = |message: String| -> ! {
+        eprintln!("{}", message);
+        std::process::exit(2);
+    };
+    let log_level = match logging::take_log_level_arg(&mut args) {
+        Ok(level) => level.unwrap_or(logging::Level::INFO),
+        Err(message) => exit_with(message),
+    };
+    let record_path = io::session_script::take_path_arg(&mut args, "--record").unwrap_or_else(|message| exit_with(message));
+    let replay_path = io::session_script::take_path_arg(&mut args, "--replay").unwrap_or_else(|message| exit_with(message));
+    let log_buffer = logging::LogBuffer::new();
+    logging::init(log_level, Some(log_buffer.clone()));
+    let initial_files: Vec<PathBuf> = args.into_iter().map(PathBuf::from).collect();
+    let session = Session {
+        recorder: record_path.map(|path| SessionRecorder::create(&path).unwrap_or_else(|e| exit_with(format!("{:#}", e)))),
+        replay: replay_path.map(|path| SessionReplay::load(&path).unwrap_or_else(|e| exit_with(format!("{:#}", e)))),
+    };
 
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([1200.0, 800.0])
             .with_title("JETS Trace Viewer"),
+        // Window placement is restored per monitor configuration (see WindowState)
+        persist_window: false,
         ..Default::default()
     };
 
     eframe::run_native(
         "JETS Trace Viewer",
         options,
-        Box::new(move |cc| Ok(Box::new(JetsViewerApp::new(cc, initial_file)))),
+        Box::new(move |cc| Ok(Box::new(JetsViewerApp::new(cc, initial_files, startup, log_buffer, log_level, session)))),
     )
 }
 
+/// Session script recording and replay requested on the command line.
+#[derive(Default)]
+struct Session {
+    recorder: Option<SessionRecorder>,
+    replay: Option<SessionReplay>,
+}
+
 /// The main JETS Trace Viewer application.
 ///
 /// This struct is now much simpler, delegating most functionality to coordinators:
 /// - `ApplicationCoordinator` handles file loading, error handling, and interaction logic
 /// - `ThemeCoordinator` handles theme persistence and application
+/// - `RepaintCoordinator` decides when to ask egui for new frames
 /// - `PanelManager` handles UI panel layout and rendering
+#[derive(Default)]
 struct JetsViewerApp {
     /// Centralized application state
     state: AppState,
     /// Asynchronous file loader
     loader: AsyncLoader,
-    /// Optional file to load on first frame
-    pending_file_load: Option<PathBuf>,
-}
-
-impl Default for JetsViewerApp {
-    fn default() -> Self {
-        Self {
-            state: AppState::new(),
-            loader: AsyncLoader::new(),
-            pending_file_load: None,
-        }
-    }
+    /// Files to load once the first frame is on screen
+    pending_file_load: Vec<PathBuf>,
+    /// Startup phase timings, logged once startup is over
+    startup: StartupTimer,
+    /// Named actions for shortcuts and the command palette
+    actions: ActionRegistry,
+    /// Session script being recorded or replayed
+    session: Session,
 }
 
 impl JetsViewerApp {
     /// Creates a new viewer instance with theme and layout settings loaded from persistent storage.
-    /// Optionally accepts an initial file path to load on startup.
-    fn new(cc: &eframe::CreationContext, initial_file: Option<PathBuf>) -> Self {
+    /// Optionally accepts initial files to load on startup (several are merged).
+    /// The log console shows `log_buffer`, starting at the command-line log level.
+    fn new(
+        cc: &eframe::CreationContext,
+        initial_files: Vec<PathBuf>,
+        mut startup: StartupTimer,
+        log_buffer: logging::LogBuffer,
+        log_level: logging::Level,
+        session: Session,
+    ) -> Self {
+        startup.mark("window");
         let current_theme_name = ThemeCoordinator::load_theme_from_storage(cc.storage);
+        let mut settings = SettingsCoordinator::read_persisted(cc.storage, &SETTINGS_KEYS);
 
         // Load column widths with proper defaults (not [0.0, 0.0, 0.0, 0.0, 0.0])
-        // Default widths: [Name, Description, Start Clock, Duration, ID]
-        let default_widths = [100.0, 300.0, 120.0, 120.0, 80.0];
-        let column_widths: [f32; 5] = SettingsCoordinator::load_setting_or(
-            cc.storage,
-            COLUMN_WIDTHS_KEY,
-            default_widths
-        );
+        let column_widths: [f32; 5] = settings.load_or(COLUMN_WIDTHS_KEY, DEFAULT_COLUMN_WIDTHS);
 
         // Load expand width with proper default
         let default_expand_width = 100.0;
-        let expand_width: f32 = SettingsCoordinator::load_setting_or(
-            cc.storage,
-            EXPAND_WIDTH_KEY,
-            default_expand_width
+        let expand_width: f32 = settings.load_or(EXPAND_WIDTH_KEY, default_expand_width);
+
+        let mut state = AppState::with_theme_and_layout(current_theme_name, column_widths, expand_width);
+        state.log_console.set_buffer(log_buffer);
+        state.log_console.set_min_level(log_level);
+        state.lanes.set_root_lanes_enabled(settings.load_or(ROOT_LANES_KEY, false));
+        state.repaint.set_low_power(settings.load_or(LOW_POWER_KEY, false));
+        state.input_settings.set_settings(settings.load(INPUT_SETTINGS_KEY));
+        state.window = WindowState::with_placements(settings.load(WINDOW_PLACEMENTS_KEY));
+        // Stored as RGBA arrays since Color32 is not serializable here
+        let type_colors: HashMap<String, [u8; 4]> = settings.load_or(RECORD_TYPE_COLORS_KEY, HashMap::new());
+        state.type_legend.set_color_overrides(
+            type_colors
+                .into_iter()
+                .map(|(t, [r, g, b, a])| (t, egui::Color32::from_rgba_unmultiplied(r, g, b, a)))
+                .collect(),
         );
+        state.type_legend.set_value_tracks(settings.load_or(VALUE_TRACKS_KEY, HashMap::new()));
+        state.dock.set_layout(settings.load(DOCK_LAYOUT_KEY));
+        state.dock.set_presets(settings.load(DOCK_PRESETS_KEY));
+        state.recent_files.set_entries(settings.load(RECENT_FILES_KEY));
+        state.settings_notice = settings.notice();
+
+        startup.mark("settings");
 
         Self {
-            state: AppState::with_theme_and_layout(current_theme_name, column_widths, expand_width),
+            state,
             loader: AsyncLoader::new(),
-            pending_file_load: initial_file,
+            pending_file_load: initial_files,
+            startup,
+            actions: ActionRegistry::with_default_actions(),
+            session,
+        }
+    }
+
+    /// Runs the next replayed action once the previous step's load has finished.
+    fn step_replay(&mut self, ctx: &egui::Context) {
+        if self.loader.is_loading() || !self.pending_file_load.is_empty() {
+            return;
+        }
+        let Some(replay) = &mut self.session.replay else {
+            return;
+        };
+        let total = replay.total();
+        match replay.next_action() {
+            Some((step, action)) => {
+                tracing::info!(step, total, ?action, "replaying");
+                if let Some(interaction) = app::apply_action(&mut self.state, action) {
+                    self.handle_panel_interaction(interaction, ctx);
+                }
+                self.state.repaint.request();
+            }
+            None => {
+                tracing::info!(steps = total, "replay finished");
+                self.session.replay = None;
+            }
         }
     }
 
     /// Handles panel interactions by delegating to ApplicationCoordinator.
     fn handle_panel_interaction(&mut self, interaction: ui::panel_manager::PanelInteraction, ctx: &egui::Context) {
         match interaction {
-            ui::panel_manager::PanelInteraction::OpenFileRequested(path) => {
-                ApplicationCoordinator::open_file(&mut self.state, &mut self.loader, path, ctx);
+            ui::panel_manager::PanelInteraction::OpenFilesRequested(paths) => {
+                ApplicationCoordinator::open_files(&mut self.state, &mut self.loader, paths, ctx);
+            }
+            ui::panel_manager::PanelInteraction::ReloadRequested => {
+                ApplicationCoordinator::reload_trace(&mut self.state, &mut self.loader, ctx);
+            }
+            ui::panel_manager::PanelInteraction::LoadCancelRequested => {
+                ApplicationCoordinator::cancel_load(&mut self.state, &mut self.loader);
+            }
+            ui::panel_manager::PanelInteraction::RecordNavigationRequested { record_id, scope, forward } => {
+                ApplicationCoordinator::navigate_from(&mut self.state, record_id, scope, forward);
+            }
+            ui::panel_manager::PanelInteraction::RevealSelectionRequested => {
+                ApplicationCoordinator::reveal_selection(&mut self.state);
+            }
+            ui::panel_manager::PanelInteraction::IdleGapSearchRequested => {
+                ApplicationCoordinator::find_idle_gaps(&mut self.state);
+            }
+            ui::panel_manager::PanelInteraction::TraceHealthCheckRequested => {
+                ApplicationCoordinator::check_trace_health(&mut self.state);
+            }
+            ui::panel_manager::PanelInteraction::RecordRevealRequested(record_id) => {
+                ApplicationCoordinator::reveal_record(&mut self.state, record_id);
+            }
+            ui::panel_manager::PanelInteraction::TypeHierarchyRequested => {
+                ApplicationCoordinator::build_type_hierarchy(&mut self.state);
+            }
+            ui::panel_manager::PanelInteraction::FlameGraphRequested { root_id } => {
+                ApplicationCoordinator::build_flame_graph(&mut self.state, &mut self.loader, root_id, ctx);
             }
             ui::panel_manager::PanelInteraction::OpenVirtualTraceRequested => {
-                ApplicationCoordinator::open_virtual_trace(&mut self.state, &mut self.loader);
+                ApplicationCoordinator::open_virtual_trace(&mut self.state, &mut self.loader, ctx);
+            }
+            ui::panel_manager::PanelInteraction::ExportReportRequested { scope, path } => {
+                ApplicationCoordinator::export_report(&mut self.state, &mut self.loader, scope, path, ctx);
+            }
+            ui::panel_manager::PanelInteraction::SaveSessionPackRequested(path) => {
+                ApplicationCoordinator::save_session_pack(&self.state, &mut self.loader, &path, ctx);
+            }
+            ui::panel_manager::PanelInteraction::ExportTimeSliceRequested(path) => {
+                ApplicationCoordinator::export_time_slice(&self.state, &mut self.loader, &path, ctx);
+            }
+            ui::panel_manager::PanelInteraction::ExportJetsRequested(path) => {
+                ApplicationCoordinator::export_jets(&self.state, &mut self.loader, &path, ctx);
+            }
+            ui::panel_manager::PanelInteraction::ExportEventGridRequested { record_type, path } => {
+                ApplicationCoordinator::export_event_grid(&self.state, &mut self.loader, record_type, &path, ctx);
+            }
+            ui::panel_manager::PanelInteraction::ExportSettingsProfileRequested(path) => {
+                ApplicationCoordinator::export_settings_profile(&mut self.state, &path);
+            }
+            ui::panel_manager::PanelInteraction::ImportSettingsProfileRequested(path) => {
+                ApplicationCoordinator::import_settings_profile(&mut self.state, &path);
             }
             ui::panel_manager::PanelInteraction::TreeNodeSelected {
                 record_id,
@@ -138,6 +289,9 @@ impl JetsViewerApp {
                     first_event_clk,
                 );
             }
+            ui::panel_manager::PanelInteraction::TreeNodeRangeSelected { record_id } => {
+                ApplicationCoordinator::handle_range_selection(&mut self.state, record_id);
+            }
             ui::panel_manager::PanelInteraction::TreeNodeExpandToggled {
                 record_id,
                 was_expanded,
@@ -160,6 +314,9 @@ impl JetsViewerApp {
                     first_event_clk,
                 );
             }
+            ui::panel_manager::PanelInteraction::RecordGesture { record_id, gesture } => {
+                ApplicationCoordinator::handle_record_gesture(&mut self.state, record_id, gesture);
+            }
             ui::panel_manager::PanelInteraction::TimelineEventClicked {
                 record_id,
                 event_clk,
@@ -170,20 +327,59 @@ impl JetsViewerApp {
                     event_clk,
                 );
             }
+            ui::panel_manager::PanelInteraction::EventRevealRequested { record_id, event_clk } => {
+                ApplicationCoordinator::reveal_event(&mut self.state, record_id, event_clk);
+                self.state.repaint.request();
+            }
             ui::panel_manager::PanelInteraction::TreeSortRequested(spec) => {
                 ApplicationCoordinator::request_sorting(&mut self.state, spec);
-                ctx.request_repaint();
+                self.state.repaint.request();
+            }
+            ui::panel_manager::PanelInteraction::AttributeSortRequested(attribute) => {
+                ApplicationCoordinator::request_attribute_sorting(&mut self.state, &attribute);
+                self.state.repaint.request();
+            }
+            ui::panel_manager::PanelInteraction::SwimlaneToggled(key) => {
+                ApplicationCoordinator::handle_swimlane_toggle(&mut self.state, &key);
+            }
+            ui::panel_manager::PanelInteraction::MeasureCursorPlaced { clk } => {
+                ApplicationCoordinator::place_measure_cursor(&mut self.state, clk);
             }
         }
     }
+
+    /// Writes all persisted preferences to storage.
+    fn save_settings(&self, storage: &mut dyn eframe::Storage) {
+        ThemeCoordinator::save_theme_to_storage(storage, self.state.theme.current_theme_name());
+        SettingsCoordinator::save_setting(storage, COLUMN_WIDTHS_KEY, self.state.layout.column_widths());
+        SettingsCoordinator::save_setting(storage, EXPAND_WIDTH_KEY, &self.state.layout.expand_width());
+        SettingsCoordinator::save_setting(storage, ROOT_LANES_KEY, &self.state.lanes.root_lanes_enabled());
+        SettingsCoordinator::save_setting(storage, LOW_POWER_KEY, &self.state.repaint.low_power_enabled());
+        SettingsCoordinator::save_setting(storage, RECORD_TYPE_COLORS_KEY, &self.type_colors_setting());
+        SettingsCoordinator::save_setting(storage, VALUE_TRACKS_KEY, self.state.type_legend.value_tracks());
+        SettingsCoordinator::save_setting(storage, INPUT_SETTINGS_KEY, self.state.input_settings.settings());
+        SettingsCoordinator::save_setting(storage, WINDOW_PLACEMENTS_KEY, self.state.window.placements());
+        SettingsCoordinator::save_setting(storage, DOCK_LAYOUT_KEY, self.state.dock.layout());
+        SettingsCoordinator::save_setting(storage, DOCK_PRESETS_KEY, self.state.dock.presets());
+        SettingsCoordinator::save_setting(storage, RECENT_FILES_KEY, &self.state.recent_files.entries());
+        SettingsCoordinator::save_settings_version(storage);
+    }
+
+    /// Returns the record type color overrides in their persisted form.
+    fn type_colors_setting(&self) -> HashMap<String, [u8; 4]> {
+        self.state
+            .type_legend
+            .color_overrides()
+            .iter()
+            .map(|(t, c)| (t.clone(), c.to_srgba_unmultiplied()))
+            .collect()
+    }
 }
 
 impl eframe::App for JetsViewerApp {
     /// Called when the app is being shut down - ensures preferences are saved.
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
-        ThemeCoordinator::save_theme_to_storage(storage, self.state.theme.current_theme_name());
-        SettingsCoordinator::save_setting(storage, COLUMN_WIDTHS_KEY, self.state.layout.column_widths());
-        SettingsCoordinator::save_setting(storage, EXPAND_WIDTH_KEY, &self.state.layout.expand_width());
+        self.save_settings(storage);
     }
 
     /// Main update loop that renders all UI panels and handles application state.
@@ -191,31 +387,81 @@ impl eframe::App for JetsViewerApp {
     /// This method is now very simple - it delegates to coordinators:
     /// 1. Check for async loading completion
     /// 2. Apply theme
-    /// 3. Load initial file if specified via command line
+    /// 3. Load initial file if specified via command line (after the first frame)
     /// 4. Render all panels via PanelManager
     /// 5. Handle panel interactions
+    /// 6. Request a repaint only if the frame changed state
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
         // Check for async loading completion
-        ApplicationCoordinator::check_loading_completion(&mut self.state, &mut self.loader);
+        let load_completed = ApplicationCoordinator::check_loading_completion(&mut self.state, &mut self.loader, ctx);
+        if load_completed {
+            self.startup.finish("trace loaded");
+        }
+
+        // Apply current theme and repaint policy
+        ThemeCoordinator::apply_current_theme(ctx, &mut self.state);
+        RepaintCoordinator::apply_policy(ctx, &mut self.state);
+        RepaintCoordinator::tick_animations(ctx, &mut self.state);
+        ui::accessibility::track_focus_input(ctx, &mut self.state);
 
-        // Apply current theme
-        ThemeCoordinator::apply_current_theme(ctx, &self.state);
+        // Restore the window placement at startup, then keep tracking it
+        for command in ctx.input(|i| self.state.window.on_frame(i.viewport())) {
+            ctx.send_viewport_cmd(command);
+        }
 
         // Persist preferences during frame (for crash resilience)
         if let Some(storage) = frame.storage_mut() {
-            storage.set_string("theme_preference", self.state.theme.current_theme_name().to_string());
-            SettingsCoordinator::save_setting(storage, COLUMN_WIDTHS_KEY, self.state.layout.column_widths());
-            SettingsCoordinator::save_setting(storage, EXPAND_WIDTH_KEY, &self.state.layout.expand_width());
+            self.save_settings(storage);
+        }
+
+        // Load initial file if specified via command line. The first frame shows
+        // the empty UI right away; loading starts on the frame after it.
+        if self.startup.reached(FIRST_FRAME) && !self.pending_file_load.is_empty() {
+            let paths = std::mem::take(&mut self.pending_file_load);
+            self.startup.mark("load started");
+            ApplicationCoordinator::open_files(&mut self.state, &mut self.loader, paths, ctx);
         }
 
-        // Load initial file if specified via command line (only on first frame)
-        if let Some(path) = self.pending_file_load.take() {
-            ApplicationCoordinator::open_file(&mut self.state, &mut self.loader, path, ctx);
+        if self.startup.reached(FIRST_FRAME) {
+            self.step_replay(ctx);
         }
 
+        // Shortcuts are consumed before the panels so text fields do not see them
+        let shortcut_action = self.actions.consume_shortcut(ctx);
+
         // Render all panels and get interaction result
         if let Some(interaction) = PanelManager::render_all_panels(ctx, &mut self.state, &self.loader) {
             self.handle_panel_interaction(interaction, ctx);
         }
+
+        // Run an action chosen by shortcut or from the command palette
+        let palette_action = ui::command_palette::render_command_palette(ctx, &mut self.state.command_palette, &self.actions);
+        if let Some(index) = shortcut_action.or(palette_action) {
+            if let Some(interaction) = self.actions.invoke(index, &mut self.state) {
+                self.handle_panel_interaction(interaction, ctx);
+            }
+            self.state.repaint.request();
+        }
+
+        if !self.startup.reached(FIRST_FRAME) {
+            if !self.pending_file_load.is_empty() {
+                self.startup.mark(FIRST_FRAME);
+                ctx.request_repaint();
+            } else {
+                self.startup.finish(FIRST_FRAME);
+            }
+        }
+
+        ui::accessibility::paint_focus_outline(ctx, &self.state);
+
+        if let Some(recorder) = &mut self.session.recorder {
+            if let Err(e) = recorder.record_frame(&self.state) {
+                tracing::error!("session recording stopped: {:#}", e);
+                self.session.recorder = None;
+            }
+        }
+
+        // Only ask for another frame if something changed outside input handling
+        RepaintCoordinator::finish_frame(ctx, &mut self.state);
     }
 }
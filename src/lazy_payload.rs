@@ -0,0 +1,148 @@
+//! Record and event `data` payloads parsed on first use.
+//!
+//! Attribute-heavy traces spend much of their load time building
+//! `serde_json::Value` trees for payloads nobody looks at: most records of a
+//! large trace are never selected or hovered. The parser therefore keeps each
+//! payload as its JSON source text ([`LazyPayload`]) and only runs serde_json
+//! when attributes are first requested. Parsed values are shared through a
+//! process-wide LRU cache of [`PAYLOAD_CACHE_CAPACITY`] entries, so
+//! re-rendering the same tooltip or details panel does not parse again.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Number of parsed payloads kept in the cache.
+pub const PAYLOAD_CACHE_CAPACITY: usize = 4096;
+
+static NEXT_KEY: AtomicU64 = AtomicU64::new(0);
+
+static CACHE: once_cell::sync::Lazy<Mutex<PayloadCache>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(PayloadCache::new(PAYLOAD_CACHE_CAPACITY)));
+
+/// A JSON payload kept as source text until its value is needed.
+///
+/// Clones share the cache entry of the original.
+#[derive(Debug, Clone)]
+pub struct LazyPayload {
+    raw: Box<str>,
+    /// Cache key, unique per parsed line
+    key: u64,
+}
+
+impl LazyPayload {
+    /// Wraps JSON text that has already been checked to be well-formed.
+    pub(crate) fn from_raw(raw: &str) -> Self {
+        Self {
+            raw: raw.into(),
+            key: NEXT_KEY.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    /// Creates a payload from an already parsed value.
+    pub fn from_value(value: &serde_json::Value) -> Self {
+        Self::from_raw(&value.to_string())
+    }
+
+    /// Returns the JSON source text.
+    pub fn raw(&self) -> &str {
+        &self.raw
+    }
+
+    /// Returns the parsed value, parsing the text on a cache miss.
+    pub fn value(&self) -> Arc<serde_json::Value> {
+        if let Some(value) = CACHE.lock().unwrap().get(self.key) {
+            return value;
+        }
+        // Parse outside the lock; the text was validated when it was read
+        let value = Arc::new(serde_json::from_str(&self.raw).unwrap_or(serde_json::Value::Null));
+        CACHE.lock().unwrap().insert(self.key, Arc::clone(&value));
+        value
+    }
+}
+
+impl PartialEq for LazyPayload {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw == other.raw
+    }
+}
+
+impl Serialize for LazyPayload {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for LazyPayload {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        serde_json::Value::deserialize(deserializer).map(|value| Self::from_value(&value))
+    }
+}
+
+/// Least-recently-used map from payload key to parsed value.
+struct PayloadCache {
+    capacity: usize,
+    /// Parsed value and last-use stamp per key
+    entries: HashMap<u64, (Arc<serde_json::Value>, u64)>,
+    /// Keys by last-use stamp, oldest first
+    recency: BTreeMap<u64, u64>,
+    next_stamp: u64,
+}
+
+impl PayloadCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            next_stamp: 0,
+        }
+    }
+
+    fn get(&mut self, key: u64) -> Option<Arc<serde_json::Value>> {
+        let stamp = self.next_stamp;
+        let (value, last_used) = self.entries.get_mut(&key)?;
+        self.recency.remove(last_used);
+        self.recency.insert(stamp, key);
+        *last_used = stamp;
+        self.next_stamp += 1;
+        Some(Arc::clone(value))
+    }
+
+    fn insert(&mut self, key: u64, value: Arc<serde_json::Value>) {
+        let stamp = self.next_stamp;
+        self.next_stamp += 1;
+        if let Some((_, last_used)) = self.entries.insert(key, (value, stamp)) {
+            self.recency.remove(&last_used);
+        }
+        self.recency.insert(stamp, key);
+        while self.entries.len() > self.capacity {
+            let Some((_, oldest)) = self.recency.pop_first() else { break };
+            self.entries.remove(&oldest);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_payload_parses_on_demand_and_cache_evicts_oldest() {
+        let payload = LazyPayload::from_raw(r#"{"pc": "0x40", "size": 4}"#);
+        assert_eq!(*payload.value(), json!({"pc": "0x40", "size": 4}));
+        assert_eq!(payload, LazyPayload::from_raw(payload.raw()));
+        assert_eq!(serde_json::to_value(&payload).unwrap(), json!({"pc": "0x40", "size": 4}));
+
+        let mut cache = PayloadCache::new(2);
+        cache.insert(1, Arc::new(json!(1)));
+        cache.insert(2, Arc::new(json!(2)));
+        assert!(cache.get(1).is_some());
+        cache.insert(3, Arc::new(json!(3)));
+        assert!(cache.get(2).is_none(), "least recently used entry is evicted");
+        assert!(cache.get(1).is_some() && cache.get(3).is_some());
+        assert_eq!(cache.recency.len(), 2);
+    }
+}
@@ -1,25 +1,61 @@
 pub mod traits;
 pub mod parser;
+pub mod streaming;
+pub mod mmap_reader;
 pub mod writer;
+pub mod concurrent_writer;
+pub mod sorted_writer;
 pub mod virtual_reader;
 pub mod pipetrace_reader;
+pub mod perf_reader;
+pub mod perfetto_reader;
+pub mod multi_trace;
+pub mod counter;
 pub mod theme;
 pub mod string_intern;
+pub mod schema;
+pub mod compat;
+pub mod validator;
+pub mod anonymizer;
+pub mod logging;
+pub mod provenance;
+pub mod event_store;
+pub mod lazy_payload;
+pub mod prefetch;
+pub mod aggregation;
+pub mod column_filters;
+pub mod visibility;
+#[cfg(feature = "parquet")]
+pub mod parquet_export;
 
 // Export traits
 pub use traits::{
-    TraceReader, TraceData, TraceMetadata,
-    TraceRecord, TraceEvent, RecordId,
+    TraceReader, AsyncTraceReader, TraceReadFuture, LOAD_CANCELED, TraceData, TraceMetadata, RecordsIter, record_fold_workers,
+    TraceRecord, TraceEvent, RecordId, ExternalId, RecordAnnotation,
     DynTraceData, DynTraceMetadata, DynTraceRecord, DynTraceEvent,
     AttributeAccessor
 };
 
 // Export JETS implementation
 pub use parser::{
-    JetsTraceReader, JetsTraceData, JetsTraceMetadata,
-    JetsTraceRecord, JetsTraceEvent, parse_trace
+    JetsTraceReader, JetsTraceData, JetsTraceMetadata, JetsTraceRecord, JetsTraceRecordRef,
+    ChildRange, JetsTraceEvent, RecordArena, RecordIdMap, ParseOptions, parse_trace, parse_trace_from_reader,
+    parse_trace_with_options, parse_trace_from_reader_with_options
 };
 
+// Export line-by-line reading
+pub use streaming::{StreamingJetsParser, TraceLine};
+
+// Export memory-mapped JETS backend
+pub use mmap_reader::{MmapJetsReader, MmapJetsTraceData, MmapJetsRecordRef};
+
+// Export per-record event storage (plain or packed)
+pub use event_store::{EventStore, CompactEvents};
+pub use lazy_payload::LazyPayload;
+
+// Export read-ahead for compressed traces
+pub use prefetch::PrefetchReader;
+
 // Export virtual implementation
 pub use virtual_reader::{
     VirtualTraceReader, VirtualTraceData,
@@ -32,11 +68,39 @@ pub use pipetrace_reader::{
     PipetraceRecordRef, PipetraceEventRef
 };
 
+// Export perf script / ftrace function_graph import
+pub use perf_reader::PerfScriptReader;
+
+// Export Chrome trace event (Perfetto JSON) import
+pub use perfetto_reader::PerfettoTraceReader;
+
+// Export merged multi-file traces
+pub use multi_trace::{MultiTraceData, MultiTraceRecordRef, MultiTraceEventRef, MultiTraceMetadataRef};
+
+// Export counter record convention
+pub use counter::{COUNTER_RECORD_TYPE, COUNTER_VALUE_ATTR, is_counter, counter_value};
+
+// Export trace provenance (content hash in the footer)
+pub use provenance::{Provenance, Verification, verify_trace_file};
+
 // Export writer (unchanged)
-pub use writer::TraceWriter;
+pub use writer::{BrotliWriter, TraceWriter};
+
+// Export multi-threaded writing
+pub use concurrent_writer::{ConcurrentTraceWriter, ThreadWriter};
+
+// Export clock-ordered writing
+pub use sorted_writer::SortedTraceWriter;
+
+// Export time-bucketed aggregation of records and events
+pub use aggregation::{aggregate_by_bucket, BucketMetric, ClockSpan, TimeBuckets};
+
+// Export visibility-aware tree traversal and per-column filters
+pub use visibility::{traverse_visible, traverse_visible_with_order, VisibilityStrategy, VisibleNode, UnfilteredStrategy, ViewportFilterStrategy};
+pub use column_filters::{ColumnFilter, ColumnFilters, TreeColumn};
 
 // Export theme support
-pub use theme::{Theme, ThemeColors, ThemeManager, hex_to_color32, adjust_brightness, with_alpha};
+pub use theme::{Theme, ThemeColors, ThemeManager, hex_to_color32, adjust_brightness, with_alpha, relative_luminance, contrast_text_color};
 
 // Export string interning utility
 pub use string_intern::StringInterner;
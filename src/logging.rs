@@ -0,0 +1,224 @@
+//! Structured logging for the viewer and the command-line tools.
+//!
+//! The library and the binaries log through [`tracing`] macros
+//! (`tracing::info!(records = n, "parsed trace")`). [`init`] installs a small
+//! subscriber that prints events at or above the chosen level to stderr and,
+//! for the GUI, keeps the most recent ones in a [`LogBuffer`] the log console
+//! panel reads from.
+//!
+//! Levels are chosen with `--log-level <error|warn|info|debug|trace>` (see
+//! [`take_log_level_arg`]).
+
+use std::collections::VecDeque;
+use std::fmt::{self, Write as _};
+use std::io::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub use tracing::Level;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// Most entries a [`LogBuffer`] keeps; older ones are dropped first.
+pub const LOG_BUFFER_CAPACITY: usize = 5000;
+
+/// One logged event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    pub level: Level,
+    /// Module path the event was logged from
+    pub target: String,
+    /// Message followed by the event's fields as `key=value`
+    pub message: String,
+    /// Time since logging was initialized
+    pub elapsed: Duration,
+}
+
+impl fmt::Display for LogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>9.3}s {:>5} {}: {}",
+            self.elapsed.as_secs_f64(),
+            self.level,
+            self.target,
+            self.message
+        )
+    }
+}
+
+/// Shared, bounded list of recent log entries.
+#[derive(Debug, Clone, Default)]
+pub struct LogBuffer {
+    entries: Arc<Mutex<VecDeque<LogEntry>>>,
+}
+
+impl LogBuffer {
+    /// Creates an empty buffer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends an entry, dropping the oldest one when full.
+    pub fn push(&self, entry: LogEntry) {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() == LOG_BUFFER_CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// Returns a copy of the entries, oldest first.
+    pub fn snapshot(&self) -> Vec<LogEntry> {
+        self.entries.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Returns the number of entries held.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    /// Returns true if no entries are held.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops all entries.
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+/// Subscriber writing events to stderr and, optionally, a [`LogBuffer`].
+///
+/// Spans are accepted (so `#[instrument]` and `info_span!` work) but only
+/// events are recorded.
+pub struct LogSubscriber {
+    max_level: Level,
+    buffer: Option<LogBuffer>,
+    start: Instant,
+    next_span: AtomicU64,
+}
+
+impl LogSubscriber {
+    /// Creates a subscriber recording events at `max_level` and more severe.
+    pub fn new(max_level: Level, buffer: Option<LogBuffer>) -> Self {
+        Self {
+            max_level,
+            buffer,
+            start: Instant::now(),
+            next_span: AtomicU64::new(1),
+        }
+    }
+}
+
+impl Subscriber for LogSubscriber {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        // More verbose levels compare greater (TRACE > ERROR)
+        *metadata.level() <= self.max_level
+    }
+
+    fn max_level_hint(&self) -> Option<tracing::level_filters::LevelFilter> {
+        Some(tracing::level_filters::LevelFilter::from_level(self.max_level))
+    }
+
+    fn new_span(&self, _span: &Attributes<'_>) -> Id {
+        Id::from_u64(self.next_span.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let entry = LogEntry {
+            level: *event.metadata().level(),
+            target: event.metadata().target().to_string(),
+            message: visitor.finish(),
+            elapsed: self.start.elapsed(),
+        };
+        let _ = writeln!(std::io::stderr(), "{}", entry);
+        if let Some(buffer) = &self.buffer {
+            buffer.push(entry);
+        }
+    }
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+/// Collects an event's message and fields into one line.
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl MessageVisitor {
+    fn finish(self) -> String {
+        match (self.message.is_empty(), self.fields.is_empty()) {
+            (_, true) => self.message,
+            (true, false) => self.fields,
+            (false, false) => format!("{} {}", self.message, self.fields),
+        }
+    }
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            self.record_debug(field, &value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+            return;
+        }
+        if !self.fields.is_empty() {
+            self.fields.push(' ');
+        }
+        let _ = write!(self.fields, "{}={:?}", field.name(), value);
+    }
+}
+
+/// Installs the global subscriber.
+///
+/// # Returns
+/// `false` if a subscriber was already installed (the call has no effect).
+pub fn init(max_level: Level, buffer: Option<LogBuffer>) -> bool {
+    tracing::subscriber::set_global_default(LogSubscriber::new(max_level, buffer)).is_ok()
+}
+
+/// Parses a level name (`error`, `warn`, `info`, `debug`, `trace`, any case).
+pub fn parse_level(name: &str) -> Option<Level> {
+    name.trim().parse().ok()
+}
+
+/// Removes `--log-level <LEVEL>` (or `--log-level=<LEVEL>`) from an argument list.
+///
+/// # Returns
+/// The level given, `Ok(None)` if the flag is absent, or an error message
+/// for a missing or unknown level.
+pub fn take_log_level_arg(args: &mut Vec<String>) -> Result<Option<Level>, String> {
+    let Some(index) = args.iter().position(|arg| arg == "--log-level" || arg.starts_with("--log-level=")) else {
+        return Ok(None);
+    };
+    let flag = args.remove(index);
+    let value = match flag.strip_prefix("--log-level=") {
+        Some(value) => value.to_string(),
+        None if index < args.len() => args.remove(index),
+        None => return Err("--log-level requires a level (error, warn, info, debug, trace)".to_string()),
+    };
+    parse_level(&value)
+        .map(Some)
+        .ok_or_else(|| format!("Unknown log level '{}' (expected error, warn, info, debug or trace)", value))
+}
@@ -0,0 +1,582 @@
+//! Memory-mapped JETS backend with on-demand record decoding.
+//!
+//! [`parse_trace`](crate::parse_trace) decodes every line into the record
+//! arena, so load time and resident memory grow with everything in the
+//! trace. [`MmapJetsTraceData`] maps the file and, at load, reads only what
+//! the tree needs from each line (IDs, clocks and the parent); names,
+//! descriptions, attributes, events and annotations are decoded from the
+//! mapped lines when a record is fetched with `get_record`. The OS pages the
+//! file in and out as records are visited.
+//!
+//! Differences from the regular reader:
+//! - Only uncompressed version 2+ traces can be mapped (`.br` streams and
+//!   legacy layouts are rejected; open those with [`JetsTraceReader`](crate::JetsTraceReader))
+//! - Siblings starting at the same clock keep file order instead of being
+//!   ordered by name
+//! - Record ends are never inferred
+//! - Fetching the same record twice decodes it twice; keep the handle to
+//!   avoid that
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use rjets::{MmapJetsTraceData, TraceData, TraceRecord};
+//! # fn main() -> anyhow::Result<()> {
+//! let trace = MmapJetsTraceData::open("huge.jets")?;
+//! for &root in trace.roots() {
+//!     let record = trace.get_record(root).unwrap(); // decodes this record's lines only
+//!     println!("{} has {} children", record.name(), record.num_children());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs::File;
+use std::sync::{Arc, OnceLock};
+
+use anyhow::{anyhow, bail, Context, Result};
+use memmap2::Mmap;
+use serde::Deserialize;
+
+use crate::event_store::EventStore;
+use crate::parser::{
+    ChildRange, JetsTraceAnnotation, JetsTraceEvent, JetsTraceEventRef, JetsTraceHeader, JetsTraceFooter,
+    JetsTraceMetadata, JetsTraceMetadataRef, JetsTraceRecord, RecordIdMap, RecordKey,
+};
+use crate::streaming::{check_version, decode_line, TraceLine};
+use crate::traits::{
+    AttributeAccessor, DynTraceData, ExternalId, RecordAnnotation, RecordId, RecordsIter, TraceData, TraceReader, TraceRecord,
+};
+
+/// Reader producing a [`MmapJetsTraceData`].
+pub struct MmapJetsReader;
+
+impl MmapJetsReader {
+    pub fn new() -> Self {
+        MmapJetsReader
+    }
+}
+
+impl Default for MmapJetsReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TraceReader for MmapJetsReader {
+    fn read(&self, file_path: &str) -> Result<DynTraceData> {
+        Ok(DynTraceData::Mmap(MmapJetsTraceData::open(file_path)?))
+    }
+}
+
+/// Byte range of one line in the mapped file.
+#[derive(Debug, Clone, Copy)]
+struct LineSpan {
+    offset: usize,
+    len: u32,
+}
+
+/// What the tree needs of a record, read at load time.
+#[derive(Debug, Clone)]
+struct RecordSlot {
+    id: RecordId,
+    line: LineSpan,
+    clk: i64,
+    end_clk: Option<i64>,
+    parent: Option<RecordId>,
+    /// Slots of the children in `child_list`
+    children: ChildRange,
+    /// Event lines in `event_lines`
+    events: ChildRange,
+    /// Annotation lines in `annotation_lines`
+    annotations: ChildRange,
+}
+
+/// The fields of a line read at load time; everything else is skipped.
+#[derive(Deserialize)]
+struct IndexLine<'a> {
+    #[serde(rename = "type", borrow)]
+    line_type: Cow<'a, str>,
+    clk: Option<i64>,
+    id: Option<ExternalId>,
+    parent_id: Option<ExternalId>,
+    record_id: Option<ExternalId>,
+}
+
+/// A JETS trace read from a memory-mapped file.
+pub struct MmapJetsTraceData {
+    map: Mmap,
+    metadata: JetsTraceMetadata,
+    slots: Vec<RecordSlot>,
+    child_list: Vec<u32>,
+    event_lines: Vec<LineSpan>,
+    annotation_lines: Vec<LineSpan>,
+    slot_of: HashMap<RecordId, u32>,
+    roots: Vec<RecordId>,
+    id_map: RecordIdMap,
+}
+
+impl MmapJetsTraceData {
+    /// Maps a trace file and indexes its lines.
+    pub fn open(file_path: &str) -> Result<Self> {
+        if file_path.ends_with(".br") {
+            bail!("Compressed traces cannot be memory-mapped: {}", file_path);
+        }
+        let file = File::open(file_path).with_context(|| format!("Failed to open file: {}", file_path))?;
+        // SAFETY: the map is read-only. Like any memory-mapped reader this
+        // assumes the file is not truncated or rewritten while it is open.
+        let map = unsafe { Mmap::map(&file) }.with_context(|| format!("Failed to map file: {}", file_path))?;
+        Self::index(map).with_context(|| format!("Failed to index {}", file_path))
+    }
+
+    /// Reads the header, footer and tree structure of the mapped lines.
+    fn index(map: Mmap) -> Result<Self> {
+        let mut header: Option<JetsTraceHeader> = None;
+        let mut footer: Option<JetsTraceFooter> = None;
+        let mut warnings = Vec::new();
+        let mut id_map = RecordIdMap::default();
+        let mut slots: Vec<RecordSlot> = Vec::new();
+        // Record and parent keys per slot; handles are filled in once all IDs are known
+        let mut slot_keys: Vec<(RecordKey, Option<RecordKey>)> = Vec::new();
+        let mut slot_of_key: HashMap<RecordKey, u32> = HashMap::new();
+        // (slot, line) of events and annotations, grouped per record below
+        let mut events: Vec<(u32, LineSpan)> = Vec::new();
+        let mut annotations: Vec<(u32, LineSpan)> = Vec::new();
+
+        let mut offset = 0;
+        for (line_index, raw) in map.split(|&b| b == b'\n').enumerate() {
+            let line_number = line_index + 1;
+            let span = LineSpan { offset, len: raw.len() as u32 };
+            offset += raw.len() + 1;
+            if raw.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+
+            let line: IndexLine = serde_json::from_slice(raw)
+                .with_context(|| format!("Failed to parse JSON at line {}", line_number))?;
+            match line.line_type.as_ref() {
+                "header" | "footer" => {
+                    let text = std::str::from_utf8(raw)?;
+                    match decode_line(text).with_context(|| format!("Failed to parse JSON at line {}", line_number))? {
+                        TraceLine::Header { version, metadata } => {
+                            if line_number != 1 {
+                                bail!("Header must be first line (found at line {})", line_number);
+                            }
+                            if check_version(&version, &mut warnings).is_some() {
+                                bail!("Version {} traces cannot be memory-mapped; use the regular reader", version);
+                            }
+                            header = Some(JetsTraceHeader { version, metadata });
+                        }
+                        TraceLine::Footer { capture_end_clk, total_records, total_annotations, total_events, provenance } => {
+                            footer = Some(JetsTraceFooter { capture_end_clk, total_records, total_annotations, total_events, provenance });
+                        }
+                        _ => unreachable!("decoded by type"),
+                    }
+                }
+                "record" => {
+                    let external_id = line.id.ok_or_else(|| anyhow!("Failed to parse JSON at line {}: missing field `id`", line_number))?;
+                    let key = id_map.key(&external_id);
+                    if slot_of_key.contains_key(&key) {
+                        bail!("Duplicate record ID '{}' at line {}", external_id, line_number);
+                    }
+                    let parent = line.parent_id.map(|p| id_map.key(&p));
+                    slot_of_key.insert(key, slots.len() as u32);
+                    slot_keys.push((key, parent));
+                    slots.push(RecordSlot {
+                        id: 0,
+                        line: span,
+                        clk: line.clk.ok_or_else(|| anyhow!("Failed to parse JSON at line {}: missing field `clk`", line_number))?,
+                        end_clk: None,
+                        parent: None,
+                        children: ChildRange::default(),
+                        events: ChildRange::default(),
+                        annotations: ChildRange::default(),
+                    });
+                }
+                "record_end" => {
+                    let clk = line.clk.ok_or_else(|| anyhow!("Failed to parse JSON at line {}: missing field `clk`", line_number))?;
+                    let (record_id, slot) = target_slot(&line, &id_map, &slot_of_key, line_number)?;
+                    let slot = &mut slots[slot as usize];
+                    // The last end wins; earlier ones are only reported
+                    if let Some(previous) = slot.end_clk {
+                        warnings.push(format!(
+                            "record_end for record '{}' at line {} replaces its earlier end at clock {}",
+                            record_id, line_number, previous
+                        ));
+                    }
+                    slot.end_clk = Some(clk);
+                }
+                "event" => events.push((target_slot(&line, &id_map, &slot_of_key, line_number)?.1, span)),
+                "annotation" => annotations.push((target_slot(&line, &id_map, &slot_of_key, line_number)?.1, span)),
+                other => bail!("Failed to parse JSON at line {}: unknown line type `{}`", line_number, other),
+            }
+        }
+        let header = header.ok_or_else(|| anyhow!("Missing header line"))?;
+
+        // All integer IDs are known now, so string IDs can get handles clear of them
+        id_map.assign_handles(|handle| slot_of_key.contains_key(&RecordKey::Int(handle)));
+        let mut slot_of: HashMap<RecordId, u32> = HashMap::with_capacity(slots.len());
+        for (index, (slot, (key, parent))) in slots.iter_mut().zip(slot_keys).enumerate() {
+            slot.id = id_map.handle(key);
+            slot.parent = parent.map(|parent| id_map.handle(parent));
+            slot_of.insert(slot.id, index as u32);
+        }
+
+        let event_lines = group_by_slot(&mut slots, events, |slot| &mut slot.events);
+        let annotation_lines = group_by_slot(&mut slots, annotations, |slot| &mut slot.annotations);
+
+        // Children and roots in start order, ties in file order. Records whose
+        // parent never appears are neither (as with the regular reader).
+        let mut order: Vec<u32> = (0..slots.len() as u32).collect();
+        order.sort_by_key(|&slot| slots[slot as usize].clk);
+        let parent_slot: Vec<Option<u32>> =
+            slots.iter().map(|slot| slot.parent.and_then(|parent| slot_of.get(&parent).copied())).collect();
+        let mut children: Vec<(u32, u32)> = Vec::new();
+        let mut roots = Vec::new();
+        for &slot in &order {
+            match (slots[slot as usize].parent, parent_slot[slot as usize]) {
+                (None, _) => roots.push(slots[slot as usize].id),
+                (Some(_), Some(parent)) => children.push((parent, slot)),
+                (Some(_), None) => {}
+            }
+        }
+        let child_list = group_by_slot(&mut slots, children, |slot| &mut slot.children);
+
+        let trace_extent = slots
+            .iter()
+            .map(|slot| (slot.clk, slot.end_clk.unwrap_or(slot.clk)))
+            .reduce(|(min, max), (start, end)| (min.min(start), max.max(end)))
+            .unwrap_or((0, 1000));
+        for warning in &warnings {
+            tracing::warn!("{}", warning);
+        }
+        tracing::debug!(records = slots.len(), roots = roots.len(), "indexed memory-mapped trace");
+
+        Ok(MmapJetsTraceData {
+            map,
+            metadata: JetsTraceMetadata { header, footer, trace_extent, warnings },
+            slots,
+            child_list,
+            event_lines,
+            annotation_lines,
+            slot_of,
+            roots,
+            id_map,
+        })
+    }
+
+    /// Returns the ID map resolving string IDs.
+    pub fn id_map(&self) -> &RecordIdMap {
+        &self.id_map
+    }
+
+    fn line(&self, span: LineSpan) -> &str {
+        let bytes = &self.map[span.offset..span.offset + span.len as usize];
+        // Indexed lines were parsed as JSON, which is UTF-8
+        std::str::from_utf8(bytes).unwrap_or_default()
+    }
+
+    fn record_at(&self, slot: u32) -> MmapJetsRecordRef<'_> {
+        MmapJetsRecordRef { data: self, slot, cold: OnceLock::new() }
+    }
+
+    /// Decodes a record's own line and its event and annotation lines.
+    fn decode_record(&self, slot: &RecordSlot) -> Result<JetsTraceRecord> {
+        let TraceLine::Record { name, record_type, id, description, data, depends_on, .. } = decode_line(self.line(slot.line))? else {
+            bail!("not a record line");
+        };
+        let mut record = JetsTraceRecord {
+            name: name.into(),
+            record_type: record_type.into(),
+            id: slot.id,
+            parent_id: slot.parent,
+            description: description.into(),
+            data,
+            depends_on: depends_on
+                .iter()
+                .filter_map(|dependency| self.id_map.handle_of(dependency))
+                .filter(|handle| self.slot_of.contains_key(handle))
+                .collect(),
+            annotations: Vec::new(),
+            events: EventStore::default(),
+            external_id: match id {
+                ExternalId::Str(s) => Some(s),
+                ExternalId::Int(_) => None,
+            },
+        };
+        for &span in &self.event_lines[range(slot.events)] {
+            if let TraceLine::Event { clk, name, description, data, .. } = decode_line(self.line(span))? {
+                record.events.push(JetsTraceEvent {
+                    clk,
+                    line_type: Arc::from("event"),
+                    name: name.into(),
+                    record_id: slot.id,
+                    description: description.into(),
+                    data,
+                });
+            }
+        }
+        for &span in &self.annotation_lines[range(slot.annotations)] {
+            if let TraceLine::Annotation { name, description, data, .. } = decode_line(self.line(span))? {
+                record.annotations.push(JetsTraceAnnotation {
+                    line_type: Arc::from("annotation"),
+                    name: name.into(),
+                    record_id: slot.id,
+                    description: description.into(),
+                    data,
+                });
+            }
+        }
+        Ok(record)
+    }
+}
+
+/// Returns the slot of the record a `record_end`, event or annotation line refers to.
+fn target_slot(
+    line: &IndexLine,
+    id_map: &RecordIdMap,
+    slot_of_key: &HashMap<RecordKey, u32>,
+    line_number: usize,
+) -> Result<(ExternalId, u32)> {
+    let id = line.record_id.clone()
+        .ok_or_else(|| anyhow!("Failed to parse JSON at line {}: missing field `record_id`", line_number))?;
+    let slot = id_map.key_of(&id).and_then(|key| slot_of_key.get(&key).copied());
+    let slot = slot.ok_or_else(|| {
+        anyhow!("{} references unknown record '{}' at line {}", line.line_type, id, line_number)
+    })?;
+    Ok((id, slot))
+}
+
+/// Sorts `(slot, item)` pairs by slot, keeping the order within each slot,
+/// and records each slot's run in the returned list.
+fn group_by_slot<T: Copy>(
+    slots: &mut [RecordSlot],
+    pairs: Vec<(u32, T)>,
+    run_of: impl Fn(&mut RecordSlot) -> &mut ChildRange,
+) -> Vec<T> {
+    for &(slot, _) in &pairs {
+        run_of(&mut slots[slot as usize]).len += 1;
+    }
+    let mut next = 0u32;
+    for slot in slots.iter_mut() {
+        let run = run_of(slot);
+        run.start = next;
+        next += run.len;
+    }
+    let mut filled = vec![0u32; slots.len()];
+    let mut items: Vec<Option<T>> = vec![None; pairs.len()];
+    for (slot, item) in pairs {
+        let position = run_of(&mut slots[slot as usize]).start + filled[slot as usize];
+        items[position as usize] = Some(item);
+        filled[slot as usize] += 1;
+    }
+    items.into_iter().map(|item| item.expect("every position filled")).collect()
+}
+
+fn range(run: ChildRange) -> std::ops::Range<usize> {
+    run.start as usize..(run.start + run.len) as usize
+}
+
+/// Handle to one record of a [`MmapJetsTraceData`]; its lines are decoded on first use.
+#[derive(Clone)]
+pub struct MmapJetsRecordRef<'a> {
+    data: &'a MmapJetsTraceData,
+    slot: u32,
+    cold: OnceLock<Arc<JetsTraceRecord>>,
+}
+
+impl<'a> MmapJetsRecordRef<'a> {
+    fn slot(&self) -> &'a RecordSlot {
+        &self.data.slots[self.slot as usize]
+    }
+
+    /// Returns the decoded record, decoding it on first use.
+    pub fn cold(&self) -> &JetsTraceRecord {
+        self.cold.get_or_init(|| {
+            let slot = self.slot();
+            Arc::new(self.data.decode_record(slot).unwrap_or_else(|e| {
+                tracing::warn!(record = slot.id, "failed to decode memory-mapped record: {:#}", e);
+                JetsTraceRecord {
+                    name: Arc::from("?"),
+                    record_type: Arc::from("?"),
+                    id: slot.id,
+                    parent_id: slot.parent,
+                    description: Arc::from(""),
+                    data: None,
+                    depends_on: Vec::new(),
+                    annotations: Vec::new(),
+                    events: EventStore::default(),
+                    external_id: None,
+                }
+            }))
+        })
+    }
+}
+
+impl AttributeAccessor for MmapJetsRecordRef<'_> {
+    fn attr_count(&self) -> u64 {
+        self.cold().attr_count()
+    }
+
+    fn attr(&self, key: &str) -> Option<serde_json::Value> {
+        self.cold().attr(key)
+    }
+
+    fn attr_at(&self, index: u64) -> Option<(String, serde_json::Value)> {
+        self.cold().attr_at(index)
+    }
+
+    fn attrs(&self) -> Vec<(String, serde_json::Value)> {
+        self.cold().attrs()
+    }
+}
+
+impl<'a> TraceRecord<'a> for MmapJetsRecordRef<'a> {
+    type Event<'b> = JetsTraceEventRef<'b> where Self: 'b;
+
+    fn clk(&self) -> i64 {
+        self.slot().clk
+    }
+
+    fn end_clk(&self) -> Option<i64> {
+        self.slot().end_clk
+    }
+
+    fn duration(&self) -> Option<i64> {
+        self.end_clk().map(|end| end - self.clk())
+    }
+
+    fn name(&self) -> String {
+        self.cold().name.to_string()
+    }
+
+    fn record_type(&self) -> String {
+        self.cold().record_type.to_string()
+    }
+
+    fn id(&self) -> RecordId {
+        self.slot().id
+    }
+
+    fn parent_id(&self) -> Option<RecordId> {
+        self.slot().parent
+    }
+
+    fn external_id(&self) -> ExternalId {
+        match self.data.id_map.string_of(self.slot().id) {
+            Some(s) => ExternalId::Str(Arc::clone(s)),
+            None => ExternalId::Int(self.slot().id),
+        }
+    }
+
+    fn description(&self) -> String {
+        self.cold().description.to_string()
+    }
+
+    fn annotations(&self) -> Vec<RecordAnnotation> {
+        self.cold().record_annotations()
+    }
+
+    fn depends_on(&self) -> Vec<RecordId> {
+        self.cold().depends_on.clone()
+    }
+
+    fn num_children(&self) -> usize {
+        self.slot().children.len as usize
+    }
+
+    fn child_at(&self, index: usize) -> Option<Self> {
+        let &child = self.data.child_list[range(self.slot().children)].get(index)?;
+        Some(self.data.record_at(child))
+    }
+
+    fn num_events(&self) -> usize {
+        self.slot().events.len as usize
+    }
+
+    fn event_at(&self, index: usize) -> Option<Self::Event<'_>> {
+        match &self.cold().events {
+            EventStore::Plain(events) => events.get(index).map(JetsTraceEventRef::Plain),
+            EventStore::Compact(events) => (index < events.len()).then_some(JetsTraceEventRef::Compact { events, index }),
+        }
+    }
+
+    fn subtree_depth(&self) -> usize {
+        self.data.child_list[range(self.slot().children)]
+            .iter()
+            .map(|&child| self.data.record_at(child).subtree_depth() + 1)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl TraceData for MmapJetsTraceData {
+    type Metadata<'a> = JetsTraceMetadataRef<'a> where Self: 'a;
+    type Record<'a> = MmapJetsRecordRef<'a> where Self: 'a;
+
+    fn metadata(&self) -> Self::Metadata<'_> {
+        JetsTraceMetadataRef(&self.metadata)
+    }
+
+    fn roots(&self) -> &[RecordId] {
+        &self.roots
+    }
+
+    fn get_record(&self, id: RecordId) -> Option<Self::Record<'_>> {
+        self.slot_of.get(&id).map(|&slot| self.record_at(slot))
+    }
+
+    fn num_children_hint(&self, id: RecordId) -> Option<usize> {
+        self.slot_of.get(&id).map(|&slot| self.slots[slot as usize].children.len as usize)
+    }
+
+    fn find_external_id(&self, id: &ExternalId) -> Option<RecordId> {
+        self.id_map.handle_of(id).filter(|handle| self.slot_of.contains_key(handle))
+    }
+
+    fn records_iter(&self) -> RecordsIter<'_, Self::Record<'_>> {
+        Box::new((0..self.slots.len() as u32).map(|slot| self.record_at(slot)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_trace;
+    use crate::traits::{TraceEvent, TraceMetadata};
+
+    #[test]
+    fn test_mapped_trace_matches_parsed_trace() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/corpus/basic.jets");
+        let mapped = MmapJetsTraceData::open(path).unwrap();
+        let parsed = parse_trace(path).unwrap();
+
+        assert_eq!(mapped.roots(), parsed.roots());
+        assert_eq!(mapped.metadata().trace_extent(), parsed.metadata().trace_extent());
+        assert_eq!(mapped.metadata().capture_end_clk(), Some(24));
+        for record in parsed.records_iter() {
+            let other = mapped.get_record(record.id()).unwrap();
+            assert_eq!(
+                (other.name(), other.record_type(), other.clk(), other.end_clk(), other.external_id()),
+                (record.name(), record.record_type(), record.clk(), record.end_clk(), record.external_id()),
+            );
+            assert_eq!(other.attrs(), record.attrs());
+            let children = |r: &dyn Fn(usize) -> Option<RecordId>, n| (0..n).filter_map(r).collect::<Vec<_>>();
+            assert_eq!(
+                children(&|i| other.child_at(i).map(|c| c.id()), other.num_children()),
+                children(&|i| record.child_at(i).map(|c| c.id()), record.num_children()),
+            );
+            let events = |n, at: &dyn Fn(usize) -> Option<(i64, String)>| (0..n).filter_map(at).collect::<Vec<_>>();
+            assert_eq!(
+                events(other.num_events(), &|i| other.event_at(i).map(|e| (e.clk(), e.name()))),
+                events(record.num_events(), &|i| record.event_at(i).map(|e| (e.clk(), e.name()))),
+            );
+        }
+
+        let fetch = mapped.find_external_id(&ExternalId::from("fetch-1")).unwrap();
+        assert_eq!(mapped.get_record(fetch).unwrap().name(), "fetch");
+        assert!(MmapJetsTraceData::open(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/v1.0.jets")).is_err());
+    }
+}
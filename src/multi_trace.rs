@@ -0,0 +1,524 @@
+//! Several traces presented as one, without merging them on disk.
+//!
+//! [`MultiTraceData`] puts each trace under a synthetic root record named
+//! after its file (record_type [`FILE_RECORD_TYPE`]). Record IDs are
+//! namespaced per file: the file number goes into the top byte of the ID, so
+//! IDs of different files never collide. IDs that do not fit below the
+//! namespace are given sequential IDs from an overflow table instead. String
+//! IDs usually land there: the parser hashes them with FNV-1a over the whole
+//! `u64` range and probes past integer IDs and other strings on a collision,
+//! so they are unique within their file but most have a nonzero top byte.
+
+use std::collections::HashMap;
+use std::path::Path;
+use anyhow::{bail, Result};
+use crate::traits::{
+    AttributeAccessor, DynTraceData, DynTraceEvent, DynTraceRecord, ExternalId, RecordAnnotation, RecordId, RecordsIter,
+    TraceData, TraceEvent, TraceMetadata, TraceRecord,
+};
+
+/// Record type of the synthetic per-file root records.
+pub const FILE_RECORD_TYPE: &str = "File";
+
+/// Most traces one [`MultiTraceData`] can hold.
+pub const MAX_PARTS: usize = 254;
+
+/// Bit position of the file number in a merged ID.
+const PART_SHIFT: u32 = 56;
+
+/// Low bits of a merged ID holding the file-local ID. The all-ones value is
+/// the ID of the file's synthetic root.
+const LOCAL_MASK: u64 = (1 << PART_SHIFT) - 1;
+
+/// Top byte of IDs from the overflow table (file numbers use 1..=MAX_PARTS).
+const OVERFLOW_TAG: u64 = 0xFF;
+
+/// One of the merged traces.
+struct TracePart {
+    /// File name shown as the synthetic root's name
+    name: String,
+    /// Path or other source the trace was read from
+    source: String,
+    data: DynTraceData,
+    /// Maps file-local IDs too large for the namespace to overflow IDs
+    overflow_ids: HashMap<RecordId, RecordId>,
+}
+
+/// Where a merged ID points.
+enum Location {
+    /// The synthetic root of a part
+    File(usize),
+    /// A record of a part, by its file-local ID
+    Record(usize, RecordId),
+}
+
+/// Several traces under synthetic per-file roots, sharing one clock axis.
+pub struct MultiTraceData {
+    parts: Vec<TracePart>,
+    roots: Vec<RecordId>,
+    /// Maps overflow IDs back to (part, file-local ID)
+    overflow: HashMap<RecordId, (usize, RecordId)>,
+    header: serde_json::Value,
+    extent: (i64, i64),
+}
+
+impl MultiTraceData {
+    /// Merges traces given as (source path, data) pairs, in order.
+    ///
+    /// Walks every trace once to find IDs that need the overflow table.
+    pub fn new(traces: Vec<(String, DynTraceData)>) -> Result<Self> {
+        if traces.is_empty() {
+            bail!("No traces to merge");
+        }
+        if traces.len() > MAX_PARTS {
+            bail!("Cannot merge {} traces (at most {})", traces.len(), MAX_PARTS);
+        }
+
+        let mut parts: Vec<TracePart> = Vec::with_capacity(traces.len());
+        let mut overflow = HashMap::new();
+        for (index, (source, data)) in traces.into_iter().enumerate() {
+            let mut overflow_ids = HashMap::new();
+            for record in data.records_iter() {
+                let id = record.id();
+                if id >= LOCAL_MASK {
+                    let merged = (OVERFLOW_TAG << PART_SHIFT) | overflow.len() as u64;
+                    overflow.insert(merged, (index, id));
+                    overflow_ids.insert(id, merged);
+                }
+            }
+
+            let file_name = Path::new(&source)
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| source.clone());
+            // Same-named files from different directories get a suffix
+            let mut name = file_name.clone();
+            let mut copy = 2;
+            while parts.iter().any(|p| p.name == name) {
+                name = format!("{} ({})", file_name, copy);
+                copy += 1;
+            }
+            parts.push(TracePart { name, source, data, overflow_ids });
+        }
+
+        let extents: Vec<(i64, i64)> = parts.iter().map(|p| p.data.metadata().trace_extent()).collect();
+        let extent = (
+            extents.iter().map(|e| e.0).min().unwrap_or(0),
+            extents.iter().map(|e| e.1).max().unwrap_or(0),
+        );
+        let header = serde_json::json!({
+            "merged_files": parts
+                .iter()
+                .map(|p| {
+                    let metadata = p.data.metadata();
+                    serde_json::json!({
+                        "name": p.name,
+                        "source": p.source,
+                        "version": metadata.version(),
+                        "metadata": metadata.header_data(),
+                    })
+                })
+                .collect::<Vec<_>>(),
+        });
+        let roots = (0..parts.len()).map(file_root_id).collect();
+
+        Ok(Self { parts, roots, overflow, header, extent })
+    }
+
+    /// Returns the merged traces as (file name, data) pairs.
+    pub fn parts(&self) -> impl Iterator<Item = (&str, &DynTraceData)> {
+        self.parts.iter().map(|p| (p.name.as_str(), &p.data))
+    }
+
+    /// Returns the source paths of the merged traces, in root order.
+    pub fn sources(&self) -> impl Iterator<Item = &str> {
+        self.parts.iter().map(|p| p.source.as_str())
+    }
+
+    /// Returns the merged ID of a file-local record ID.
+    fn merged_id(&self, part: usize, local: RecordId) -> RecordId {
+        if local < LOCAL_MASK {
+            part_tag(part) | local
+        } else {
+            // Every large ID was registered by `new`
+            self.parts[part].overflow_ids.get(&local).copied().unwrap_or(local)
+        }
+    }
+
+    /// Resolves a merged ID.
+    fn locate(&self, id: RecordId) -> Option<Location> {
+        let tag = id >> PART_SHIFT;
+        if tag == OVERFLOW_TAG {
+            return self.overflow.get(&id).map(|&(part, local)| Location::Record(part, local));
+        }
+        let part = (tag as usize).checked_sub(1).filter(|&p| p < self.parts.len())?;
+        match id & LOCAL_MASK {
+            LOCAL_MASK => Some(Location::File(part)),
+            local => Some(Location::Record(part, local)),
+        }
+    }
+
+    /// Wraps a record of a part.
+    fn wrap<'a>(&'a self, part: usize, record: DynTraceRecord<'a>) -> MultiTraceRecordRef<'a> {
+        MultiTraceRecordRef::Record { data: self, part, record: Box::new(record) }
+    }
+}
+
+/// Returns the top bits shared by the merged IDs of a part.
+fn part_tag(part: usize) -> u64 {
+    (part as u64 + 1) << PART_SHIFT
+}
+
+/// Returns the ID of a part's synthetic root.
+fn file_root_id(part: usize) -> RecordId {
+    part_tag(part) | LOCAL_MASK
+}
+
+impl TraceData for MultiTraceData {
+    type Metadata<'a> = MultiTraceMetadataRef<'a> where Self: 'a;
+    type Record<'a> = MultiTraceRecordRef<'a> where Self: 'a;
+
+    fn metadata(&self) -> Self::Metadata<'_> {
+        MultiTraceMetadataRef(self)
+    }
+
+    fn roots(&self) -> &[RecordId] {
+        &self.roots
+    }
+
+    fn get_record(&self, id: RecordId) -> Option<Self::Record<'_>> {
+        match self.locate(id)? {
+            Location::File(part) => Some(MultiTraceRecordRef::File { data: self, part }),
+            Location::Record(part, local) => self.parts[part].data.get_record(local).map(|r| self.wrap(part, r)),
+        }
+    }
+
+    fn children_range(&self, id: RecordId, start: usize, len: usize) -> Vec<Self::Record<'_>> {
+        match self.locate(id) {
+            Some(Location::File(part)) => {
+                let data = &self.parts[part].data;
+                let roots = data.roots();
+                let end = start.saturating_add(len).min(roots.len());
+                roots
+                    .get(start..end)
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|&root| data.get_record(root))
+                    .map(|r| self.wrap(part, r))
+                    .collect()
+            }
+            Some(Location::Record(part, local)) => self.parts[part]
+                .data
+                .children_range(local, start, len)
+                .into_iter()
+                .map(|r| self.wrap(part, r))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn num_children_hint(&self, id: RecordId) -> Option<usize> {
+        match self.locate(id)? {
+            Location::File(part) => Some(self.parts[part].data.roots().len()),
+            Location::Record(part, local) => self.parts[part].data.num_children_hint(local),
+        }
+    }
+
+    fn records_iter(&self) -> RecordsIter<'_, Self::Record<'_>> {
+        Box::new((0..self.parts.len()).flat_map(move |part| {
+            std::iter::once(MultiTraceRecordRef::File { data: self, part })
+                .chain(self.parts[part].data.records_iter().map(move |r| self.wrap(part, r)))
+        }))
+    }
+}
+
+/// Metadata of a merged trace: per-file headers under `merged_files`,
+/// summed footer counts and the union of the extents.
+pub struct MultiTraceMetadataRef<'a>(&'a MultiTraceData);
+
+impl MultiTraceMetadataRef<'_> {
+    /// Sums a footer count over all parts; unknown if any part lacks it.
+    fn sum(&self, count: impl Fn(&crate::traits::DynTraceMetadata<'_>) -> Option<usize>) -> Option<usize> {
+        self.0.parts.iter().map(|p| count(&p.data.metadata())).sum()
+    }
+}
+
+impl TraceMetadata for MultiTraceMetadataRef<'_> {
+    fn version(&self) -> String {
+        "merged".to_string()
+    }
+
+    fn header_data(&self) -> &serde_json::Value {
+        &self.0.header
+    }
+
+    fn capture_end_clk(&self) -> Option<i64> {
+        self.0.parts.iter().filter_map(|p| p.data.metadata().capture_end_clk()).max()
+    }
+
+    fn total_records(&self) -> Option<usize> {
+        // The synthetic file roots count as records too
+        self.sum(|m| m.total_records()).map(|n| n + self.0.parts.len())
+    }
+
+    fn total_annotations(&self) -> Option<usize> {
+        self.sum(|m| m.total_annotations())
+    }
+
+    fn total_events(&self) -> Option<usize> {
+        self.sum(|m| m.total_events())
+    }
+
+    fn trace_extent(&self) -> (i64, i64) {
+        self.0.extent
+    }
+
+    fn warnings(&self) -> Vec<String> {
+        self.0
+            .parts
+            .iter()
+            .flat_map(|p| p.data.metadata().warnings().into_iter().map(move |w| format!("{}: {}", p.name, w)))
+            .collect()
+    }
+}
+
+/// A record of a merged trace: a synthetic file root or a wrapped record.
+#[derive(Clone)]
+pub enum MultiTraceRecordRef<'a> {
+    File {
+        data: &'a MultiTraceData,
+        part: usize,
+    },
+    Record {
+        data: &'a MultiTraceData,
+        part: usize,
+        record: Box<DynTraceRecord<'a>>,
+    },
+}
+
+impl<'a> MultiTraceRecordRef<'a> {
+    /// Returns the part this record belongs to.
+    fn part(&self) -> &'a TracePart {
+        match self {
+            MultiTraceRecordRef::File { data, part } | MultiTraceRecordRef::Record { data, part, .. } => {
+                &data.parts[*part]
+            }
+        }
+    }
+}
+
+impl AttributeAccessor for MultiTraceRecordRef<'_> {
+    fn attr_count(&self) -> u64 {
+        match self {
+            MultiTraceRecordRef::File { .. } => 0,
+            MultiTraceRecordRef::Record { record, .. } => record.attr_count(),
+        }
+    }
+
+    fn attr(&self, key: &str) -> Option<serde_json::Value> {
+        match self {
+            MultiTraceRecordRef::File { .. } => None,
+            MultiTraceRecordRef::Record { record, .. } => record.attr(key),
+        }
+    }
+
+    fn attr_at(&self, index: u64) -> Option<(String, serde_json::Value)> {
+        match self {
+            MultiTraceRecordRef::File { .. } => None,
+            MultiTraceRecordRef::Record { record, .. } => record.attr_at(index),
+        }
+    }
+
+    fn attrs(&self) -> Vec<(String, serde_json::Value)> {
+        match self {
+            MultiTraceRecordRef::File { .. } => Vec::new(),
+            MultiTraceRecordRef::Record { record, .. } => record.attrs(),
+        }
+    }
+}
+
+impl<'a> TraceRecord<'a> for MultiTraceRecordRef<'a> {
+    type Event<'b> = MultiTraceEventRef<'b> where Self: 'b;
+
+    fn clk(&self) -> i64 {
+        match self {
+            MultiTraceRecordRef::File { .. } => self.part().data.metadata().trace_extent().0,
+            MultiTraceRecordRef::Record { record, .. } => record.clk(),
+        }
+    }
+
+    fn end_clk(&self) -> Option<i64> {
+        match self {
+            MultiTraceRecordRef::File { .. } => Some(self.part().data.metadata().trace_extent().1),
+            MultiTraceRecordRef::Record { record, .. } => record.end_clk(),
+        }
+    }
+
+    fn end_inferred(&self) -> bool {
+        match self {
+            MultiTraceRecordRef::File { .. } => false,
+            MultiTraceRecordRef::Record { record, .. } => record.end_inferred(),
+        }
+    }
+
+    fn duration(&self) -> Option<i64> {
+        match self {
+            MultiTraceRecordRef::File { .. } => self.end_clk().map(|end| end - self.clk()),
+            MultiTraceRecordRef::Record { record, .. } => record.duration(),
+        }
+    }
+
+    fn name(&self) -> String {
+        match self {
+            MultiTraceRecordRef::File { .. } => self.part().name.clone(),
+            MultiTraceRecordRef::Record { record, .. } => record.name(),
+        }
+    }
+
+    fn record_type(&self) -> String {
+        match self {
+            MultiTraceRecordRef::File { .. } => FILE_RECORD_TYPE.to_string(),
+            MultiTraceRecordRef::Record { record, .. } => record.record_type(),
+        }
+    }
+
+    fn id(&self) -> RecordId {
+        match self {
+            MultiTraceRecordRef::File { part, .. } => file_root_id(*part),
+            MultiTraceRecordRef::Record { data, part, record } => data.merged_id(*part, record.id()),
+        }
+    }
+
+    fn parent_id(&self) -> Option<RecordId> {
+        match self {
+            MultiTraceRecordRef::File { .. } => None,
+            MultiTraceRecordRef::Record { data, part, record } => Some(match record.parent_id() {
+                Some(parent) => data.merged_id(*part, parent),
+                None => file_root_id(*part),
+            }),
+        }
+    }
+
+    /// File roots use the file name; records are prefixed with it, so IDs
+    /// stay unique when a merged trace is written out.
+    fn external_id(&self) -> ExternalId {
+        let name = &self.part().name;
+        match self {
+            MultiTraceRecordRef::File { .. } => ExternalId::Str(name.as_str().into()),
+            MultiTraceRecordRef::Record { record, .. } => {
+                ExternalId::Str(format!("{}:{}", name, record.external_id()).into())
+            }
+        }
+    }
+
+    fn description(&self) -> String {
+        match self {
+            MultiTraceRecordRef::File { .. } => self.part().source.clone(),
+            MultiTraceRecordRef::Record { record, .. } => record.description(),
+        }
+    }
+
+    fn annotations(&self) -> Vec<RecordAnnotation> {
+        match self {
+            MultiTraceRecordRef::File { .. } => Vec::new(),
+            MultiTraceRecordRef::Record { record, .. } => record.annotations(),
+        }
+    }
+
+    fn depends_on(&self) -> Vec<RecordId> {
+        match self {
+            MultiTraceRecordRef::File { .. } => Vec::new(),
+            MultiTraceRecordRef::Record { data, part, record } => {
+                record.depends_on().into_iter().map(|dependency| data.merged_id(*part, dependency)).collect()
+            }
+        }
+    }
+
+    fn num_children(&self) -> usize {
+        match self {
+            MultiTraceRecordRef::File { .. } => self.part().data.roots().len(),
+            MultiTraceRecordRef::Record { record, .. } => record.num_children(),
+        }
+    }
+
+    fn child_at(&self, index: usize) -> Option<Self> {
+        match self {
+            MultiTraceRecordRef::File { data, part } => {
+                let part_data = &data.parts[*part].data;
+                let root = *part_data.roots().get(index)?;
+                part_data.get_record(root).map(|r| data.wrap(*part, r))
+            }
+            MultiTraceRecordRef::Record { data, part, record } => record.child_at(index).map(|r| data.wrap(*part, r)),
+        }
+    }
+
+    fn num_events(&self) -> usize {
+        match self {
+            MultiTraceRecordRef::File { .. } => 0,
+            MultiTraceRecordRef::Record { record, .. } => record.num_events(),
+        }
+    }
+
+    fn event_at(&self, index: usize) -> Option<Self::Event<'_>> {
+        match self {
+            MultiTraceRecordRef::File { .. } => None,
+            MultiTraceRecordRef::Record { record, .. } => record.event_at(index).map(|event| MultiTraceEventRef {
+                record_id: self.id(),
+                event: Box::new(event),
+            }),
+        }
+    }
+
+    fn subtree_depth(&self) -> usize {
+        match self {
+            MultiTraceRecordRef::File { .. } => (0..self.num_children())
+                .filter_map(|i| self.child_at(i))
+                .map(|child| child.subtree_depth() + 1)
+                .max()
+                .unwrap_or(0),
+            MultiTraceRecordRef::Record { record, .. } => record.subtree_depth(),
+        }
+    }
+}
+
+/// An event of a merged trace, reporting the merged ID of its record.
+pub struct MultiTraceEventRef<'a> {
+    record_id: RecordId,
+    event: Box<DynTraceEvent<'a>>,
+}
+
+impl AttributeAccessor for MultiTraceEventRef<'_> {
+    fn attr_count(&self) -> u64 {
+        self.event.attr_count()
+    }
+
+    fn attr(&self, key: &str) -> Option<serde_json::Value> {
+        self.event.attr(key)
+    }
+
+    fn attr_at(&self, index: u64) -> Option<(String, serde_json::Value)> {
+        self.event.attr_at(index)
+    }
+
+    fn attrs(&self) -> Vec<(String, serde_json::Value)> {
+        self.event.attrs()
+    }
+}
+
+impl TraceEvent for MultiTraceEventRef<'_> {
+    fn clk(&self) -> i64 {
+        self.event.clk()
+    }
+
+    fn name(&self) -> String {
+        self.event.name()
+    }
+
+    fn record_id(&self) -> RecordId {
+        self.record_id
+    }
+
+    fn description(&self) -> String {
+        self.event.description()
+    }
+}
@@ -1,13 +1,17 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Read};
 use std::sync::Arc;
-use once_cell::sync::OnceCell;
+use std::sync::atomic::{AtomicBool, Ordering};
 use anyhow::{Result, Context, anyhow};
-use brotli::Decompressor;
-use crate::traits::{TraceReader, TraceData, TraceMetadata, TraceRecord, TraceEvent, RecordId, DynTraceData, AttributeAccessor};
+use crate::traits::{LOAD_CANCELED, TraceReader, TraceData, TraceMetadata, TraceRecord, TraceEvent, RecordId, ExternalId, RecordAnnotation, DynTraceData, AttributeAccessor, RecordsIter, record_fold_workers};
 use crate::string_intern::StringInterner;
+use crate::provenance::Provenance;
+use crate::event_store::{CompactEvents, EventStore, DEFAULT_COMPACT_EVENT_THRESHOLD};
+use crate::lazy_payload::LazyPayload;
+use crate::streaming::{StreamingJetsParser, TraceLine};
+use crate::prefetch::{brotli_reader, FILE_BUFFER_SIZE};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JetsTraceHeader {
@@ -21,6 +25,9 @@ pub struct JetsTraceFooter {
     pub total_records: Option<usize>,
     pub total_annotations: Option<usize>,
     pub total_events: Option<usize>,
+    /// Content hash and producer, if the writer recorded them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -64,12 +71,15 @@ pub struct JetsTraceEvent {
     #[serde(serialize_with = "serialize_arc_str", deserialize_with = "deserialize_arc_str")]
     pub description: Arc<str>,
     #[serde(default)]
-    pub data: Option<serde_json::Value>,
+    pub data: Option<LazyPayload>,
 }
 
+/// Cold per-record data: strings, attributes, events and annotations.
+///
+/// Clocks and tree links live in the hot columns of `RecordArena`; this part
+/// is only read when a record is displayed or inspected.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JetsTraceRecord {
-    pub clk: i64,
     #[serde(serialize_with = "serialize_arc_str", deserialize_with = "deserialize_arc_str")]
     pub name: Arc<str>,
     #[serde(serialize_with = "serialize_arc_str", deserialize_with = "deserialize_arc_str")]
@@ -78,25 +88,84 @@ pub struct JetsTraceRecord {
     pub parent_id: Option<RecordId>,
     #[serde(serialize_with = "serialize_arc_str", deserialize_with = "deserialize_arc_str")]
     pub description: Arc<str>,
+    /// Attribute payload, parsed when first read
     #[serde(default)]
-    pub data: Option<serde_json::Value>,
+    pub data: Option<LazyPayload>,
+    /// Records this one depends on (producers of its inputs)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<RecordId>,
 
     // These are added during parsing
     #[serde(skip)]
-    pub end_clk: Option<i64>,
-    #[serde(skip)]
-    pub duration: Option<i64>,
-    #[serde(skip)]
-    pub child_indices: Vec<usize>,  // Indices into the arena, not owned children
-    #[serde(skip)]
     pub annotations: Vec<JetsTraceAnnotation>,
     #[serde(skip)]
-    pub events: Vec<JetsTraceEvent>,
-
-    // Shared reference to the arena for resolving child indices
-    // Uses OnceCell for lazy initialization to enable self-referential structure
+    pub events: EventStore,
+    /// Original string ID (None when the trace used an integer ID)
     #[serde(skip)]
-    arena: OnceCell<Arc<Vec<JetsTraceRecord>>>,
+    pub external_id: Option<Arc<str>>,
+}
+
+/// Record arena in struct-of-arrays layout, indexed by arena position.
+///
+/// Traversal, filtering and extent computation only read clocks and tree
+/// links, so those are stored as dense columns; everything else sits in the
+/// `cold` side table. All columns have one entry per record.
+#[derive(Debug, Clone, Default)]
+pub struct RecordArena {
+    pub clk: Vec<i64>,
+    pub end_clk: Vec<Option<i64>>,
+    /// True where `end_clk` was inferred rather than read from a `record_end` line
+    pub end_inferred: Vec<bool>,
+    /// Arena index of the parent (None for roots and orphans)
+    pub parent: Vec<Option<usize>>,
+    /// Where each record's children sit in `child_list`
+    pub child_ranges: Vec<ChildRange>,
+    /// Arena indices of all children, grouped by parent and sorted by clock and name
+    pub child_list: Vec<usize>,
+    pub cold: Vec<JetsTraceRecord>,
+}
+
+/// Contiguous run of a record's children in `RecordArena::child_list`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChildRange {
+    pub start: u32,
+    pub len: u32,
+}
+
+impl RecordArena {
+    /// Returns the number of records in the arena.
+    pub fn len(&self) -> usize {
+        self.clk.len()
+    }
+
+    /// Returns true if the arena holds no records.
+    pub fn is_empty(&self) -> bool {
+        self.clk.is_empty()
+    }
+
+    /// Returns the arena indices of the children of the record at `index`.
+    pub fn children_of(&self, index: usize) -> &[usize] {
+        let range = self.child_ranges[index];
+        let start = range.start as usize;
+        &self.child_list[start..start + range.len as usize]
+    }
+
+    /// Returns a record handle for the arena index, if in range.
+    pub fn record(&self, index: usize) -> Option<JetsTraceRecordRef<'_>> {
+        (index < self.len()).then_some(JetsTraceRecordRef { arena: self, index })
+    }
+}
+
+/// A record while parsing, before its clocks move into the arena columns.
+///
+/// The handles in `cold` (its own, parent, dependencies and those of its
+/// events and annotations) are filled in from the keys once all IDs are known.
+struct PendingRecord {
+    clk: i64,
+    end_clk: Option<i64>,
+    parent: Option<RecordKey>,
+    depends_on: Vec<RecordKey>,
+    cold: JetsTraceRecord,
 }
 
 #[derive(Debug, Clone)]
@@ -104,71 +173,164 @@ pub struct JetsTraceMetadata {
     pub header: JetsTraceHeader,
     pub footer: Option<JetsTraceFooter>,
     pub trace_extent: (i64, i64), // (min_clk, max_clk)
+    pub warnings: Vec<String>,    // Non-fatal problems found while parsing
 }
 
 #[derive(Debug, Clone)]
 pub struct JetsTraceData {
     pub metadata: JetsTraceMetadata,
-    pub root_indices: Vec<usize>,                  // Indices of root records in all_records
+    pub root_indices: Vec<usize>,                  // Indices of root records in the arena
+    pub root_record_ids: Vec<RecordId>,            // IDs of root records, in root_indices order
     pub records_by_id: HashMap<RecordId, usize>,   // Maps record ID to vector index in arena
-    pub all_records: Arc<Vec<JetsTraceRecord>>,    // Arena: flat list of all records
+    pub arena: Arc<RecordArena>,                   // Arena: hot columns plus cold side table
+    pub id_map: RecordIdMap,                       // String ID <-> handle mapping
 }
 
-pub struct JetsTraceReader;
+/// A record ID while a trace is read, before handles are assigned.
+///
+/// Integer and string IDs live in separate key spaces, so `5` and `"5"` (or
+/// an integer that happens to equal a string's hash) never collide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum RecordKey {
+    /// Integer ID, used as its own handle
+    Int(u64),
+    /// String ID, by index into the map's string table
+    Str(u32),
+}
+
+/// Maps external record IDs (integers or strings) to internal `RecordId` handles.
+///
+/// Integer IDs are used as-is, over the full u64 range. A string ID gets a
+/// handle derived from a hash of the string, so the same ID maps to the same
+/// handle across loads and anything keyed by handle (notes, session packs)
+/// stays valid. String handles are assigned once every integer ID is known
+/// (see [`RecordIdMap::assign_handles`]); a handle taken by an integer ID or
+/// another string is resolved by probing to the next free one.
+#[derive(Debug, Clone, Default)]
+pub struct RecordIdMap {
+    /// String IDs in first-seen order, indexed by `RecordKey::Str`
+    strings: Vec<Arc<str>>,
+    keys: HashMap<Arc<str>, u32>,
+    /// Handle of each string, by string index (empty until assigned)
+    string_handles: Vec<RecordId>,
+    handles: HashMap<Arc<str>, RecordId>,
+    strings_by_handle: HashMap<RecordId, Arc<str>>,
+}
+
+impl RecordIdMap {
+    /// Returns the key of an external ID, registering new strings.
+    pub(crate) fn key(&mut self, id: &ExternalId) -> RecordKey {
+        match id {
+            ExternalId::Int(n) => RecordKey::Int(*n),
+            ExternalId::Str(s) => {
+                if let Some(&index) = self.keys.get(s) {
+                    return RecordKey::Str(index);
+                }
+                let index = self.strings.len() as u32;
+                self.strings.push(Arc::clone(s));
+                self.keys.insert(Arc::clone(s), index);
+                RecordKey::Str(index)
+            }
+        }
+    }
+
+    /// Returns the key of an external ID, if it occurred so far.
+    pub(crate) fn key_of(&self, id: &ExternalId) -> Option<RecordKey> {
+        match id {
+            ExternalId::Int(n) => Some(RecordKey::Int(*n)),
+            ExternalId::Str(s) => self.keys.get(s).map(|&index| RecordKey::Str(index)),
+        }
+    }
+
+    /// Gives every string a handle not used by `is_int_id` or another string.
+    ///
+    /// Call once after reading, when the integer IDs of all records are known.
+    pub(crate) fn assign_handles(&mut self, is_int_id: impl Fn(RecordId) -> bool) {
+        self.string_handles = Vec::with_capacity(self.strings.len());
+        for s in &self.strings {
+            let mut handle = fnv1a(s.as_bytes());
+            while is_int_id(handle) || self.strings_by_handle.contains_key(&handle) {
+                handle = handle.wrapping_add(1);
+            }
+            self.string_handles.push(handle);
+            self.handles.insert(Arc::clone(s), handle);
+            self.strings_by_handle.insert(handle, Arc::clone(s));
+        }
+    }
+
+    /// Returns the handle of a key (after [`RecordIdMap::assign_handles`]).
+    pub(crate) fn handle(&self, key: RecordKey) -> RecordId {
+        match key {
+            RecordKey::Int(n) => n,
+            RecordKey::Str(index) => self.string_handles[index as usize],
+        }
+    }
+
+    /// Returns the handle for an external ID, if it occurs in the trace.
+    ///
+    /// An integer ID maps to itself unless a string holds that handle;
+    /// whether a record has it is up to the caller.
+    pub fn handle_of(&self, id: &ExternalId) -> Option<RecordId> {
+        match id {
+            ExternalId::Int(n) => (!self.strings_by_handle.contains_key(n)).then_some(*n),
+            ExternalId::Str(s) => self.handles.get(s).copied(),
+        }
+    }
+
+    /// Returns the original string for a handle, if it came from a string ID.
+    pub fn string_of(&self, handle: RecordId) -> Option<&Arc<str>> {
+        self.strings_by_handle.get(&handle)
+    }
+}
+
+/// 64-bit FNV-1a hash (stable across runs, unlike `DefaultHasher`).
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for &b in bytes {
+        hash ^= u64::from(b);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+/// Optional passes applied while parsing a trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Give records without a `record_end` line an end clock from the next sibling or the parent
+    pub infer_end: bool,
+    /// Pack the events of records with more events than this (see [`crate::event_store`]);
+    /// `usize::MAX` keeps every record's events plain
+    pub compact_event_threshold: usize,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            infer_end: false,
+            compact_event_threshold: DEFAULT_COMPACT_EVENT_THRESHOLD,
+        }
+    }
+}
+
+pub struct JetsTraceReader {
+    options: ParseOptions,
+}
 
 impl JetsTraceReader {
     pub fn new() -> Self {
-        JetsTraceReader
-    }
-}
-
-#[derive(Debug, Deserialize)]
-#[serde(tag = "type")]
-enum TraceLine {
-    #[serde(rename = "header")]
-    Header {
-        version: String,
-        metadata: serde_json::Value,
-    },
-    #[serde(rename = "record")]
-    Record {
-        clk: i64,
-        name: String,
-        record_type: String,
-        id: RecordId,
-        parent_id: Option<RecordId>,
-        description: String,
-        #[serde(default)]
-        data: Option<serde_json::Value>,
-    },
-    #[serde(rename = "record_end")]
-    RecordEnd {
-        clk: i64,
-        record_id: RecordId,
-    },
-    #[serde(rename = "annotation")]
-    Annotation {
-        name: String,
-        record_id: RecordId,
-        description: String,
-        data: serde_json::Value,
-    },
-    #[serde(rename = "event")]
-    Event {
-        clk: i64,
-        name: String,
-        record_id: RecordId,
-        description: String,
-        #[serde(default)]
-        data: Option<serde_json::Value>,
-    },
-    #[serde(rename = "footer")]
-    Footer {
-        capture_end_clk: Option<i64>,
-        total_records: Option<usize>,
-        total_annotations: Option<usize>,
-        total_events: Option<usize>,
-    },
+        Self::with_options(ParseOptions::default())
+    }
+
+    /// Creates a reader that applies the given parse options.
+    pub fn with_options(options: ParseOptions) -> Self {
+        JetsTraceReader { options }
+    }
+}
+
+impl Default for JetsTraceReader {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// Parses a JETS trace file from disk.
@@ -197,18 +359,41 @@ enum TraceLine {
 /// # }
 /// ```
 pub fn parse_trace(file_path: &str) -> Result<JetsTraceData> {
+    parse_trace_with_options(file_path, ParseOptions::default())
+}
+
+/// Parses a JETS trace file from disk, applying the given parse options.
+pub fn parse_trace_with_options(file_path: &str, options: ParseOptions) -> Result<JetsTraceData> {
+    tracing::debug!(path = file_path, compressed = file_path.ends_with(".br"), "opening trace");
+    parse_trace_from_reader_with_options(open_trace_file(file_path)?, options)
+}
+
+/// Opens a trace file for line reading, decompressing `.br` files.
+pub(crate) fn open_trace_file(file_path: &str) -> Result<Box<dyn BufRead>> {
     let file = File::open(file_path)
         .with_context(|| format!("Failed to open file: {}", file_path))?;
 
     let reader: Box<dyn BufRead> = if file_path.ends_with(".br") {
-        // Brotli decompression enabled
-        let decompressor = Decompressor::new(file, 4096);
-        Box::new(BufReader::new(decompressor))
+        // Brotli decompression, ahead of the parser when a core is free
+        brotli_reader(file).context("Failed to start decompression thread")?
     } else {
         // No decompression
-        Box::new(BufReader::new(file))
+        Box::new(BufReader::with_capacity(FILE_BUFFER_SIZE, file))
     };
+    Ok(reader)
+}
+
+/// Parses JETS lines from any buffered reader.
+///
+/// This is the stream-agnostic core of [`parse_trace`]; it lets callers parse
+/// traces embedded in other containers or already held in memory. The reader
+/// must yield plain (already decompressed) JSON Lines starting with the header.
+pub fn parse_trace_from_reader<R: BufRead>(reader: R) -> Result<JetsTraceData> {
+    parse_trace_from_reader_with_options(reader, ParseOptions::default())
+}
 
+/// Parses JETS lines from any buffered reader, applying the given parse options.
+pub fn parse_trace_from_reader_with_options<R: BufRead>(reader: R, options: ParseOptions) -> Result<JetsTraceData> {
     // Create string interner to deduplicate repeated strings
     let mut interner = StringInterner::with_capacity(8192);
 
@@ -218,173 +403,281 @@ pub fn parse_trace(file_path: &str) -> Result<JetsTraceData> {
 
     let mut header: Option<JetsTraceHeader> = None;
     let mut footer: Option<JetsTraceFooter> = None;
-    let mut records_by_id: HashMap<RecordId, JetsTraceRecord> = HashMap::new();
-
-    for (line_num, line_result) in reader.lines().enumerate() {
-        let line = line_result
-            .with_context(|| format!("Failed to read line {}", line_num + 1))?;
-
-        if line.trim().is_empty() {
-            continue;
-        }
+    let mut records_by_id: HashMap<RecordKey, PendingRecord> = HashMap::new();
+    let mut id_map = RecordIdMap::default();
+    let mut warnings = Vec::new();
 
-        let trace_line: TraceLine = serde_json::from_str(&line)
-            .with_context(|| format!("Failed to parse JSON at line {}", line_num + 1))?;
+    let mut lines = StreamingJetsParser::new(reader);
+    while let Some(trace_line) = lines.next() {
+        let trace_line = trace_line?;
+        let line_number = lines.line_number();
 
         match trace_line {
             TraceLine::Header { version, metadata } => {
-                if line_num != 0 {
-                    return Err(anyhow!("Header must be first line (found at line {})", line_num + 1));
-                }
                 header = Some(JetsTraceHeader { version, metadata });
             }
 
-            TraceLine::Record { clk, name, record_type, id, parent_id, description, data } => {
-                if records_by_id.contains_key(&id) {
-                    return Err(anyhow!("Duplicate record ID '{}' at line {}", id, line_num + 1));
+            TraceLine::Record { clk, name, record_type, id: external_id, parent_id, description, data, depends_on } => {
+                let key = id_map.key(&external_id);
+                if records_by_id.contains_key(&key) {
+                    return Err(anyhow!("Duplicate record ID '{}' at line {}", external_id, line_number));
                 }
+                let parent = parent_id.map(|p| id_map.key(&p));
+                // Dependencies may be defined later; unknown ones are dropped below
+                let depends_on = depends_on.iter().map(|d| id_map.key(d)).collect();
+                let external_id = match external_id {
+                    ExternalId::Str(s) => Some(s),
+                    ExternalId::Int(_) => None,
+                };
 
-                let record = JetsTraceRecord {
+                let record = PendingRecord {
                     clk,
-                    name: interner.intern(&name),
-                    record_type: interner.intern(&record_type),
-                    id: id.clone(),
-                    parent_id,
-                    description: interner.intern(&description),
-                    data,
                     end_clk: None,
-                    duration: None,
-                    child_indices: Vec::new(),
-                    annotations: Vec::new(),
-                    events: Vec::new(),
-                    arena: OnceCell::new(),
+                    parent,
+                    depends_on,
+                    cold: JetsTraceRecord {
+                        name: interner.intern(&name),
+                        record_type: interner.intern(&record_type),
+                        id: 0,
+                        parent_id: None,
+                        description: interner.intern(&description),
+                        data,
+                        depends_on: Vec::new(),
+                        annotations: Vec::new(),
+                        events: EventStore::default(),
+                        external_id,
+                    },
                 };
 
-                records_by_id.insert(id, record);
+                records_by_id.insert(key, record);
             }
 
             TraceLine::RecordEnd { clk, record_id } => {
-                let record = records_by_id.get_mut(&record_id)
-                    .ok_or_else(|| anyhow!("record_end references unknown record '{}' at line {}", record_id, line_num + 1))?;
-
+                let record = id_map.key_of(&record_id)
+                    .and_then(|key| records_by_id.get_mut(&key))
+                    .ok_or_else(|| anyhow!("record_end references unknown record '{}' at line {}", record_id, line_number))?;
+
+                // The last end wins; earlier ones are only reported
+                if let Some(previous) = record.end_clk {
+                    warnings.push(format!(
+                        "record_end for record '{}' at line {} replaces its earlier end at clock {}",
+                        record_id,
+                        line_number,
+                        previous
+                    ));
+                }
                 record.end_clk = Some(clk);
-                record.duration = Some(clk - record.clk);
             }
 
             TraceLine::Annotation { name, record_id, description, data } => {
-                let record = records_by_id.get_mut(&record_id)
-                    .ok_or_else(|| anyhow!("annotation references unknown record '{}' at line {}", record_id, line_num + 1))?;
+                let record = id_map.key_of(&record_id)
+                    .and_then(|key| records_by_id.get_mut(&key))
+                    .ok_or_else(|| anyhow!("annotation references unknown record '{}' at line {}", record_id, line_number))?;
 
-                record.annotations.push(JetsTraceAnnotation {
+                record.cold.annotations.push(JetsTraceAnnotation {
                     line_type: Arc::clone(&annotation_type),
                     name: interner.intern(&name),
-                    record_id,
+                    record_id: 0,
                     description: interner.intern(&description),
                     data,
                 });
             }
 
             TraceLine::Event { clk, name, record_id, description, data } => {
-                let record = records_by_id.get_mut(&record_id)
-                    .ok_or_else(|| anyhow!("event references unknown record '{}' at line {}", record_id, line_num + 1))?;
+                let record = id_map.key_of(&record_id)
+                    .and_then(|key| records_by_id.get_mut(&key))
+                    .ok_or_else(|| anyhow!("event references unknown record '{}' at line {}", record_id, line_number))?;
 
-                record.events.push(JetsTraceEvent {
+                record.cold.events.push(JetsTraceEvent {
                     clk,
                     line_type: Arc::clone(&event_type),
                     name: interner.intern(&name),
-                    record_id,
+                    record_id: 0,
                     description: interner.intern(&description),
                     data,
                 });
             }
 
-            TraceLine::Footer { capture_end_clk, total_records, total_annotations, total_events } => {
+            TraceLine::Footer { capture_end_clk, total_records, total_annotations, total_events, provenance } => {
+                // The hash is checked separately (see `provenance::verify_trace_file`)
                 footer = Some(JetsTraceFooter {
                     capture_end_clk,
                     total_records,
                     total_annotations,
                     total_events,
+                    provenance,
                 });
             }
         }
     }
 
     let header = header.ok_or_else(|| anyhow!("Missing header line"))?;
+    // Version warnings come from the header, before any line's
+    let mut warnings: Vec<String> = lines.into_warnings().into_iter().chain(warnings).collect();
+
+    // All integer IDs are known now, so string IDs can get handles clear of them
+    id_map.assign_handles(|handle| records_by_id.contains_key(&RecordKey::Int(handle)));
+
+    // Fill in the handles, dropping dependencies on records that never
+    // appeared (warnings sorted, as the records are not in file order here)
+    let display_id = |key: RecordKey| match key {
+        RecordKey::Int(n) => n.to_string(),
+        RecordKey::Str(_) => id_map.string_of(id_map.handle(key)).map_or_else(String::new, |s| s.to_string()),
+    };
+    let mut dangling = Vec::new();
+    let known: std::collections::HashSet<RecordKey> = records_by_id.keys().copied().collect();
+    for (&key, record) in records_by_id.iter_mut() {
+        let id = id_map.handle(key);
+        let cold = &mut record.cold;
+        cold.id = id;
+        cold.parent_id = record.parent.map(|parent| id_map.handle(parent));
+        cold.depends_on = record
+            .depends_on
+            .iter()
+            .filter(|&&dependency| {
+                let found = known.contains(&dependency);
+                if !found {
+                    dangling.push(format!(
+                        "record '{}' depends on unknown record '{}'",
+                        display_id(key),
+                        display_id(dependency)
+                    ));
+                }
+                found
+            })
+            .map(|&dependency| id_map.handle(dependency))
+            .collect();
+        cold.events.set_record_id(id);
+        for annotation in &mut cold.annotations {
+            annotation.record_id = id;
+        }
+    }
+    dangling.sort();
+    warnings.extend(dangling);
 
     // Build flat arena with all records
-    let mut all_records: Vec<JetsTraceRecord> = records_by_id.into_values().collect();
+    let mut all_records: Vec<PendingRecord> = records_by_id.into_values().collect();
 
     // Sort records to ensure consistent ordering (parents before children when possible)
     all_records.sort_by(|a, b| {
-        a.clk.cmp(&b.clk).then_with(|| a.name.cmp(&b.name))
+        a.clk.cmp(&b.clk).then_with(|| a.cold.name.cmp(&b.cold.name))
     });
 
     // Build index mapping: record ID -> vector index in arena
     let mut id_to_index: HashMap<RecordId, usize> = HashMap::new();
     for (index, record) in all_records.iter().enumerate() {
-        id_to_index.insert(record.id, index);
+        id_to_index.insert(record.cold.id, index);
+    }
+
+    // Split into hot columns and the cold side table
+    let count = all_records.len();
+    let mut arena = RecordArena {
+        clk: Vec::with_capacity(count),
+        end_clk: Vec::with_capacity(count),
+        end_inferred: vec![false; count],
+        parent: Vec::with_capacity(count),
+        child_ranges: vec![ChildRange::default(); count],
+        child_list: Vec::new(),
+        cold: Vec::with_capacity(count),
+    };
+    for mut record in all_records {
+        arena.clk.push(record.clk);
+        arena.end_clk.push(record.end_clk);
+        arena.parent.push(record.cold.parent_id.and_then(|id| id_to_index.get(&id).copied()));
+        record.cold.events = std::mem::take(&mut record.cold.events).compacted(options.compact_event_threshold);
+        arena.cold.push(record.cold);
     }
 
-    // Build parent-child relationships using indices
-    let mut children_by_parent: HashMap<usize, Vec<usize>> = HashMap::new();
+    // Lay out each parent's children contiguously: count them, assign ranges
+    // from the running total, then fill in arena order (already sorted by
+    // clock and name)
     let mut root_indices = Vec::new();
-
-    for (index, record) in all_records.iter().enumerate() {
-        if let Some(parent_id) = record.parent_id {
-            if let Some(&parent_index) = id_to_index.get(&parent_id) {
-                children_by_parent.entry(parent_index)
-                    .or_insert_with(Vec::new)
-                    .push(index);
-            }
-        } else {
+    for index in 0..count {
+        if let Some(parent_index) = arena.parent[index] {
+            arena.child_ranges[parent_index].len += 1;
+        } else if arena.cold[index].parent_id.is_none() {
             root_indices.push(index);
         }
     }
-
-    // Sort children indices by clock time and name
-    for children in children_by_parent.values_mut() {
-        children.sort_by(|&a, &b| {
-            let rec_a = &all_records[a];
-            let rec_b = &all_records[b];
-            rec_a.clk.cmp(&rec_b.clk).then_with(|| rec_a.name.cmp(&rec_b.name))
-        });
+    let mut next_start = 0u32;
+    for range in &mut arena.child_ranges {
+        range.start = next_start;
+        next_start += range.len;
+    }
+    arena.child_list = vec![0; next_start as usize];
+    let mut filled = vec![0u32; count];
+    for index in 0..count {
+        if let Some(parent_index) = arena.parent[index] {
+            let slot = arena.child_ranges[parent_index].start + filled[parent_index];
+            arena.child_list[slot as usize] = index;
+            filled[parent_index] += 1;
+        }
     }
 
-    // Assign child_indices to each record
-    for (parent_index, child_indices) in children_by_parent {
-        all_records[parent_index].child_indices = child_indices;
+    if options.infer_end {
+        let capture_end = footer.as_ref().and_then(|f| f.capture_end_clk);
+        infer_record_ends(&mut arena, &root_indices, capture_end);
     }
 
-    // Wrap in Arc - arena references will be set lazily on first access
-    let arena = Arc::new(all_records);
-
     // Calculate trace extent (min_clk, max_clk)
     let trace_extent = calculate_trace_extent(&arena);
+    for warning in &warnings {
+        tracing::warn!("{}", warning);
+    }
+    tracing::debug!(records = count, roots = root_indices.len(), min_clk = trace_extent.0, max_clk = trace_extent.1, "parsed trace");
 
     Ok(JetsTraceData {
-        metadata: JetsTraceMetadata { header, footer, trace_extent },
+        metadata: JetsTraceMetadata { header, footer, trace_extent, warnings },
+        root_record_ids: root_indices.iter().map(|&index| arena.cold[index].id).collect(),
         root_indices,
         records_by_id: id_to_index,
-        all_records: arena,
+        arena: Arc::new(arena),
+        id_map,
     })
 }
 
+/// Sets the end of every record that has none and flags it as inferred.
+///
+/// A record ends where its next sibling with a later start begins; the last
+/// such record ends with its parent, and the last root with the capture end
+/// from the footer. Parents are handled before their children so inferred
+/// parent ends carry down. Records whose candidate end lies before their
+/// start are left open.
+fn infer_record_ends(arena: &mut RecordArena, root_indices: &[usize], capture_end: Option<i64>) {
+    let mut pending: Vec<(Vec<usize>, Option<i64>)> = vec![(root_indices.to_vec(), capture_end)];
+    while let Some((siblings, parent_end)) = pending.pop() {
+        for (position, &index) in siblings.iter().enumerate() {
+            if arena.end_clk[index].is_none() {
+                let clk = arena.clk[index];
+                let next_start = siblings[position + 1..]
+                    .iter()
+                    .map(|&sibling| arena.clk[sibling])
+                    .find(|&start| start > clk);
+                if let Some(end) = next_start.or(parent_end).filter(|&end| end >= clk) {
+                    arena.end_clk[index] = Some(end);
+                    arena.end_inferred[index] = true;
+                }
+            }
+            let children = arena.children_of(index);
+            if !children.is_empty() {
+                pending.push((children.to_vec(), arena.end_clk[index]));
+            }
+        }
+    }
+}
+
 /// Computes the minimum and maximum clock values across all records in the trace.
-fn calculate_trace_extent(all_records: &[JetsTraceRecord]) -> (i64, i64) {
-    if all_records.is_empty() {
+fn calculate_trace_extent(arena: &RecordArena) -> (i64, i64) {
+    if arena.is_empty() {
         return (0, 1000);
     }
 
     let mut min_clk = i64::MAX;
     let mut max_clk = i64::MIN;
 
-    for record in all_records {
-        min_clk = min_clk.min(record.clk);
-        if let Some(end_clk) = record.end_clk {
-            max_clk = max_clk.max(end_clk);
-        } else {
-            max_clk = max_clk.max(record.clk);
-        }
+    for (&clk, &end_clk) in arena.clk.iter().zip(&arena.end_clk) {
+        min_clk = min_clk.min(clk);
+        max_clk = max_clk.max(end_clk.unwrap_or(clk));
     }
 
     if min_clk == i64::MAX {
@@ -397,7 +690,7 @@ fn calculate_trace_extent(all_records: &[JetsTraceRecord]) -> (i64, i64) {
 // Wrapper types for GAT references
 
 #[derive(Clone, Copy)]
-pub struct JetsTraceMetadataRef<'a>(&'a JetsTraceMetadata);
+pub struct JetsTraceMetadataRef<'a>(pub(crate) &'a JetsTraceMetadata);
 
 impl<'a> TraceMetadata for JetsTraceMetadataRef<'a> {
     fn version(&self) -> String {
@@ -427,33 +720,55 @@ impl<'a> TraceMetadata for JetsTraceMetadataRef<'a> {
     fn trace_extent(&self) -> (i64, i64) {
         self.0.trace_extent()
     }
+
+    fn warnings(&self) -> Vec<String> {
+        self.0.warnings()
+    }
+
+    fn provenance(&self) -> Option<Provenance> {
+        self.0.provenance()
+    }
 }
 
+/// Handle to one record of a `RecordArena`.
 #[derive(Clone, Copy)]
-pub struct JetsTraceRecordRef<'a>(&'a JetsTraceRecord);
+pub struct JetsTraceRecordRef<'a> {
+    arena: &'a RecordArena,
+    index: usize,
+}
 
 impl<'a> JetsTraceRecordRef<'a> {
     /// Helper method to iterate over children (for compatibility)
     pub fn children(self) -> impl Iterator<Item = JetsTraceRecordRef<'a>> + 'a {
         (0..self.num_children()).filter_map(move |i| self.child_at(i))
     }
+
+    /// Returns the record's position in the arena.
+    pub fn arena_index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the cold side-table entry of the record.
+    pub fn cold(&self) -> &'a JetsTraceRecord {
+        &self.arena.cold[self.index]
+    }
 }
 
 impl<'a> AttributeAccessor for JetsTraceRecordRef<'a> {
     fn attr_count(&self) -> u64 {
-        self.0.attr_count()
+        self.cold().attr_count()
     }
 
     fn attr(&self, key: &str) -> Option<serde_json::Value> {
-        self.0.attr(key)
+        self.cold().attr(key)
     }
 
     fn attr_at(&self, index: u64) -> Option<(String, serde_json::Value)> {
-        self.0.attr_at(index)
+        self.cold().attr_at(index)
     }
 
     fn attrs(&self) -> Vec<(String, serde_json::Value)> {
-        self.0.attrs()
+        self.cold().attrs()
     }
 }
 
@@ -461,96 +776,150 @@ impl<'a> TraceRecord<'a> for JetsTraceRecordRef<'a> {
     type Event<'b> = JetsTraceEventRef<'b> where Self: 'b;
 
     fn clk(&self) -> i64 {
-        self.0.clk()
+        self.arena.clk[self.index]
     }
 
     fn end_clk(&self) -> Option<i64> {
-        self.0.end_clk()
+        self.arena.end_clk[self.index]
+    }
+
+    fn end_inferred(&self) -> bool {
+        self.arena.end_inferred[self.index]
     }
 
     fn duration(&self) -> Option<i64> {
-        self.0.duration()
+        self.end_clk().map(|end| end - self.clk())
     }
 
     fn name(&self) -> String {
-        self.0.name()
+        self.cold().name.to_string()
+    }
+
+    fn record_type(&self) -> String {
+        self.cold().record_type.to_string()
     }
 
     fn id(&self) -> RecordId {
-        self.0.id()
+        self.cold().id
     }
 
     fn parent_id(&self) -> Option<RecordId> {
-        self.0.parent_id()
+        self.cold().parent_id
+    }
+
+    fn external_id(&self) -> ExternalId {
+        let cold = self.cold();
+        match &cold.external_id {
+            Some(s) => ExternalId::Str(Arc::clone(s)),
+            None => ExternalId::Int(cold.id),
+        }
     }
 
     fn description(&self) -> String {
-        self.0.description()
+        self.cold().description.to_string()
+    }
+
+    fn annotations(&self) -> Vec<RecordAnnotation> {
+        self.cold().record_annotations()
+    }
+
+    fn depends_on(&self) -> Vec<RecordId> {
+        self.cold().depends_on.clone()
     }
 
     fn num_children(&self) -> usize {
-        self.0.num_children()
+        self.arena.child_ranges[self.index].len as usize
     }
 
     fn child_at(&self, index: usize) -> Option<Self> {
-        // Access the child directly from the arena to preserve the 'a lifetime
-        let arena = self.0.arena.get()?;
-        let &child_idx = self.0.child_indices.get(index)?;
-        let child = arena.get(child_idx)?;
-        // Lazily initialize arena for children too
-        let _ = child.arena.get_or_init(|| Arc::clone(arena));
-        Some(JetsTraceRecordRef(child))
+        let &child = self.arena.children_of(self.index).get(index)?;
+        Some(JetsTraceRecordRef { arena: self.arena, index: child })
     }
 
     fn num_events(&self) -> usize {
-        self.0.num_events()
+        self.cold().events.len()
     }
 
     fn event_at(&self, index: usize) -> Option<Self::Event<'_>> {
-        self.0.event_at(index)
+        match &self.cold().events {
+            EventStore::Plain(events) => events.get(index).map(JetsTraceEventRef::Plain),
+            EventStore::Compact(events) => {
+                (index < events.len()).then_some(JetsTraceEventRef::Compact { events, index })
+            }
+        }
     }
 
     fn subtree_depth(&self) -> usize {
-        self.0.subtree_depth()
+        self.arena.children_of(self.index)
+            .iter()
+            .map(|&child| JetsTraceRecordRef { arena: self.arena, index: child }.subtree_depth() + 1)
+            .max()
+            .unwrap_or(0)
     }
 }
 
+/// Handle to one event of a record, plain or packed.
 #[derive(Clone, Copy)]
-pub struct JetsTraceEventRef<'a>(&'a JetsTraceEvent);
+pub enum JetsTraceEventRef<'a> {
+    Plain(&'a JetsTraceEvent),
+    Compact { events: &'a CompactEvents, index: usize },
+}
+
+impl<'a> JetsTraceEventRef<'a> {
+    fn data(&self) -> Option<Arc<serde_json::Value>> {
+        match *self {
+            JetsTraceEventRef::Plain(event) => event.data.as_ref(),
+            JetsTraceEventRef::Compact { events, index } => events.data(index),
+        }
+        .map(LazyPayload::value)
+    }
+}
 
 impl<'a> AttributeAccessor for JetsTraceEventRef<'a> {
     fn attr_count(&self) -> u64 {
-        self.0.attr_count()
+        data_attr_count(self.data().as_deref())
     }
 
     fn attr(&self, key: &str) -> Option<serde_json::Value> {
-        self.0.attr(key)
+        data_attr(self.data().as_deref(), key)
     }
 
     fn attr_at(&self, index: u64) -> Option<(String, serde_json::Value)> {
-        self.0.attr_at(index)
+        data_attr_at(self.data().as_deref(), index)
     }
 
     fn attrs(&self) -> Vec<(String, serde_json::Value)> {
-        self.0.attrs()
+        data_attrs(self.data().as_deref())
     }
 }
 
 impl<'a> TraceEvent for JetsTraceEventRef<'a> {
     fn clk(&self) -> i64 {
-        self.0.clk()
+        match *self {
+            JetsTraceEventRef::Plain(event) => event.clk,
+            JetsTraceEventRef::Compact { events, index } => events.clk(index),
+        }
     }
 
     fn name(&self) -> String {
-        self.0.name()
+        match *self {
+            JetsTraceEventRef::Plain(event) => event.name.to_string(),
+            JetsTraceEventRef::Compact { events, index } => events.name(index).to_string(),
+        }
     }
 
     fn record_id(&self) -> RecordId {
-        self.0.record_id()
+        match *self {
+            JetsTraceEventRef::Plain(event) => event.record_id,
+            JetsTraceEventRef::Compact { events, .. } => events.record_id(),
+        }
     }
 
     fn description(&self) -> String {
-        self.0.description()
+        match *self {
+            JetsTraceEventRef::Plain(event) => event.description.to_string(),
+            JetsTraceEventRef::Compact { events, index } => events.description(index).to_string(),
+        }
     }
 }
 
@@ -558,11 +927,51 @@ impl<'a> TraceEvent for JetsTraceEventRef<'a> {
 
 impl TraceReader for JetsTraceReader {
     fn read(&self, file_path: &str) -> anyhow::Result<DynTraceData> {
-        let data = parse_trace(file_path)?;
+        let data = parse_trace_with_options(file_path, self.options)?;
+        Ok(DynTraceData::Jets(data))
+    }
+
+    fn read_cancelable(&self, file_path: &str, cancel: &AtomicBool) -> anyhow::Result<DynTraceData> {
+        let reader = CancelableReader { inner: open_trace_file(file_path)?, cancel };
+        let data = parse_trace_from_reader_with_options(reader, self.options)?;
         Ok(DynTraceData::Jets(data))
     }
 }
 
+/// Buffered reader that fails its next refill once `cancel` is set, which
+/// stops the parser at the end of the current buffer.
+struct CancelableReader<'c, R> {
+    inner: R,
+    cancel: &'c AtomicBool,
+}
+
+impl<R: BufRead> CancelableReader<'_, R> {
+    fn check(&self) -> std::io::Result<()> {
+        if self.cancel.load(Ordering::Relaxed) {
+            return Err(std::io::Error::other(LOAD_CANCELED));
+        }
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Read for CancelableReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.check()?;
+        self.inner.read(buf)
+    }
+}
+
+impl<R: BufRead> BufRead for CancelableReader<'_, R> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        self.check()?;
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.inner.consume(amount)
+    }
+}
+
 impl TraceMetadata for JetsTraceMetadata {
     fn version(&self) -> String {
         self.header.version.clone()
@@ -591,6 +1000,14 @@ impl TraceMetadata for JetsTraceMetadata {
     fn trace_extent(&self) -> (i64, i64) {
         self.trace_extent
     }
+
+    fn warnings(&self) -> Vec<String> {
+        self.warnings.clone()
+    }
+
+    fn provenance(&self) -> Option<Provenance> {
+        self.footer.as_ref().and_then(|f| f.provenance.clone())
+    }
 }
 
 impl TraceData for JetsTraceData {
@@ -601,239 +1018,166 @@ impl TraceData for JetsTraceData {
         JetsTraceMetadataRef(&self.metadata)
     }
 
-    fn root_ids(&self) -> Vec<u64> {
-        self.root_indices.iter()
-            .filter_map(|&idx| self.all_records.get(idx))
-            .map(|r| r.id)
-            .collect()
+    fn roots(&self) -> &[u64] {
+        &self.root_record_ids
     }
 
     fn get_record(&self, id: u64) -> Option<Self::Record<'_>> {
         self.records_by_id.get(&id)
-            .and_then(|&index| self.all_records.get(index))
-            .map(|record| {
-                // Lazily initialize arena reference on first access
-                let _ = record.arena.get_or_init(|| Arc::clone(&self.all_records));
-                JetsTraceRecordRef(record)
-            })
-    }
-}
-
-impl<'a> TraceRecord<'a> for &'a JetsTraceRecord {
-    type Event<'b> = JetsTraceEventRef<'b> where Self: 'b;
-
-    fn clk(&self) -> i64 {
-        self.clk
-    }
-
-    fn end_clk(&self) -> Option<i64> {
-        self.end_clk
-    }
-
-    fn duration(&self) -> Option<i64> {
-        self.duration
-    }
-
-    fn name(&self) -> String {
-        self.name.to_string()
-    }
-
-    fn id(&self) -> RecordId {
-        self.id
+            .and_then(|&index| self.arena.record(index))
     }
 
-    fn parent_id(&self) -> Option<RecordId> {
-        self.parent_id
-    }
-
-    fn description(&self) -> String {
-        self.description.to_string()
-    }
-
-    fn num_children(&self) -> usize {
-        self.child_indices.len()
-    }
-
-    fn child_at(&self, index: usize) -> Option<Self> {
-        let arena = self.arena.get()?;
-        let &child_idx = self.child_indices.get(index)?;
-        let child = arena.get(child_idx)?;
-        // Lazily initialize arena for children too, enabling transitive resolution
-        let _ = child.arena.get_or_init(|| Arc::clone(arena));
-        Some(child)
+    fn children_range(&self, id: RecordId, start: usize, len: usize) -> Vec<Self::Record<'_>> {
+        let Some(&index) = self.records_by_id.get(&id) else {
+            return Vec::new();
+        };
+        let children = self.arena.children_of(index);
+        let start = start.min(children.len());
+        let end = start.saturating_add(len).min(children.len());
+        children[start..end]
+            .iter()
+            .map(|&child| JetsTraceRecordRef { arena: &self.arena, index: child })
+            .collect()
     }
 
-    fn num_events(&self) -> usize {
-        self.events.len()
+    fn find_external_id(&self, id: &ExternalId) -> Option<RecordId> {
+        let handle = self.id_map.handle_of(id)?;
+        self.records_by_id.contains_key(&handle).then_some(handle)
+    }
+
+    fn records_iter(&self) -> RecordsIter<'_, Self::Record<'_>> {
+        let arena: &RecordArena = &self.arena;
+        Box::new((0..arena.len()).map(move |index| JetsTraceRecordRef { arena, index }))
+    }
+
+    fn par_fold_records<'s, A, I, F>(&'s self, init: I, fold: F) -> Vec<A>
+    where
+        Self: Sync,
+        A: Send,
+        I: Fn() -> A + Sync,
+        F: Fn(A, Self::Record<'s>) -> A + Sync,
+    {
+        // Arena positions split evenly, regardless of how the tree is shaped
+        let arena: &'s RecordArena = &self.arena;
+        let chunk_len = arena.len().div_ceil(record_fold_workers()).max(1);
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..arena.len())
+                .step_by(chunk_len)
+                .map(|start| {
+                    let (init, fold) = (&init, &fold);
+                    scope.spawn(move || {
+                        (start..(start + chunk_len).min(arena.len()))
+                            .fold(init(), |acc, index| fold(acc, JetsTraceRecordRef { arena, index }))
+                    })
+                })
+                .collect();
+            workers.into_iter().map(|w| w.join().expect("record fold worker panicked")).collect()
+        })
     }
+}
 
-    fn event_at(&self, index: usize) -> Option<Self::Event<'_>> {
-        self.events.get(index).map(JetsTraceEventRef)
+impl JetsTraceRecord {
+    /// Returns the parsed `data` payload, if the record has one.
+    pub fn data_value(&self) -> Option<Arc<serde_json::Value>> {
+        self.data.as_ref().map(LazyPayload::value)
     }
 
-    fn subtree_depth(&self) -> usize {
-        // Leaf node (no children)
-        if self.child_indices.is_empty() {
-            return 0;
-        }
-
-        // Get arena reference
-        let arena = match self.arena.get() {
-            Some(a) => a,
-            None => return 1, // Conservative: assume depth 1 if arena not initialized
-        };
-
-        // Calculate max depth of children + 1
-        let max_child_depth = self.child_indices.iter()
-            .filter_map(|&idx| arena.get(idx))
-            .map(|child| {
-                // Ensure child has arena reference
-                let _ = child.arena.get_or_init(|| Arc::clone(arena));
-                child.subtree_depth()
+    /// Returns the annotations in the trait's owned form.
+    pub fn record_annotations(&self) -> Vec<RecordAnnotation> {
+        self.annotations
+            .iter()
+            .map(|annotation| RecordAnnotation {
+                name: annotation.name.to_string(),
+                description: annotation.description.to_string(),
+                data: annotation.data.clone(),
             })
-            .max()
-            .unwrap_or(0);
-
-        max_child_depth + 1
+            .collect()
     }
 }
 
+// Record attributes: the data attributes followed by the annotations, which
+// take precedence on lookup by key.
+
 impl AttributeAccessor for &JetsTraceRecord {
     fn attr_count(&self) -> u64 {
-        let mut count = 0u64;
-        
-        // Count original data fields
-        if let Some(serde_json::Value::Object(map)) = &self.data {
-            count += map.len() as u64;
-        } else if self.data.is_some() {
-            count += 1;
-        }
-        
-        // Count annotations (merged into attributes)
-        count += self.annotations.len() as u64;
-        
-        count
+        data_attr_count(self.data_value().as_deref()) + self.annotations.len() as u64
     }
 
     fn attr(&self, key: &str) -> Option<serde_json::Value> {
-        // First check annotations (they take precedence in merged view)
-        for annotation in &self.annotations {
-            if annotation.name.as_ref() == key {
-                return Some(annotation.data.clone());
-            }
-        }
-        
-        // Then check original data
-        if let Some(data) = &self.data {
-            if let serde_json::Value::Object(map) = data {
-                return map.get(key).cloned();
-            } else if key == "data" {
-                return Some(data.clone());
-            }
+        if let Some(annotation) = self.annotations.iter().find(|annotation| annotation.name.as_ref() == key) {
+            return Some(annotation.data.clone());
         }
-        
-        None
+        data_attr(self.data_value().as_deref(), key)
     }
 
     fn attr_at(&self, index: u64) -> Option<(String, serde_json::Value)> {
-        let mut current_index = 0u64;
-        
-        // First iterate over original data fields
-        if let Some(data) = &self.data {
-            if let serde_json::Value::Object(map) = data {
-                for (key, value) in map {
-                    if current_index == index {
-                        return Some((key.clone(), value.clone()));
-                    }
-                    current_index += 1;
-                }
-            } else {
-                if current_index == index {
-                    return Some(("data".to_string(), data.clone()));
-                }
-                current_index += 1;
-            }
+        let data = self.data_value();
+        let data_count = data_attr_count(data.as_deref());
+        if index < data_count {
+            return data_attr_at(data.as_deref(), index);
         }
-        
-        // Then iterate over annotations
-        let annotation_index = (index - current_index) as usize;
-        self.annotations.get(annotation_index).map(|ann| {
-            (ann.name.to_string(), ann.data.clone())
-        })
+        self.annotations
+            .get((index - data_count) as usize)
+            .map(|annotation| (annotation.name.to_string(), annotation.data.clone()))
     }
 
     fn attrs(&self) -> Vec<(String, serde_json::Value)> {
-        let mut result = Vec::new();
-        
-        // Add original data fields
-        if let Some(data) = &self.data {
-            if let serde_json::Value::Object(map) = data {
-                for (key, value) in map {
-                    result.push((key.clone(), value.clone()));
-                }
-            } else {
-                result.push(("data".to_string(), data.clone()));
-            }
-        }
-        
-        // Add annotations (merged into attributes)
-        for annotation in &self.annotations {
-            result.push((annotation.name.to_string(), annotation.data.clone()));
-        }
-        
+        let mut result = data_attrs(self.data_value().as_deref());
+        result.extend(self.annotations.iter().map(|annotation| (annotation.name.to_string(), annotation.data.clone())));
         result
     }
 }
 
 impl AttributeAccessor for JetsTraceEvent {
     fn attr_count(&self) -> u64 {
-        if let Some(serde_json::Value::Object(map)) = &self.data {
-            map.len() as u64
-        } else if self.data.is_some() {
-            1
-        } else {
-            0
-        }
+        data_attr_count(self.data.as_ref().map(LazyPayload::value).as_deref())
     }
 
     fn attr(&self, key: &str) -> Option<serde_json::Value> {
-        if let Some(data) = &self.data {
-            if let serde_json::Value::Object(map) = data {
-                return map.get(key).cloned();
-            } else if key == "data" {
-                return Some(data.clone());
-            }
-        }
-        None
+        data_attr(self.data.as_ref().map(LazyPayload::value).as_deref(), key)
     }
 
     fn attr_at(&self, index: u64) -> Option<(String, serde_json::Value)> {
-        if let Some(data) = &self.data {
-            if let serde_json::Value::Object(map) = data {
-                return map.iter()
-                    .nth(index as usize)
-                    .map(|(k, v)| (k.clone(), v.clone()));
-            } else if index == 0 {
-                return Some(("data".to_string(), data.clone()));
-            }
-        }
-        None
+        data_attr_at(self.data.as_ref().map(LazyPayload::value).as_deref(), index)
     }
 
     fn attrs(&self) -> Vec<(String, serde_json::Value)> {
-        let mut result = Vec::new();
-        
-        if let Some(data) = &self.data {
-            if let serde_json::Value::Object(map) = data {
-                for (key, value) in map {
-                    result.push((key.clone(), value.clone()));
-                }
-            } else {
-                result.push(("data".to_string(), data.clone()));
-            }
-        }
-        
-        result
+        data_attrs(self.data.as_ref().map(LazyPayload::value).as_deref())
+    }
+}
+
+// Data attributes: the keys of an object `data`, or a single "data"
+// attribute for any other value.
+
+fn data_attr_count(data: Option<&serde_json::Value>) -> u64 {
+    match data {
+        Some(serde_json::Value::Object(map)) => map.len() as u64,
+        Some(_) => 1,
+        None => 0,
+    }
+}
+
+fn data_attr(data: Option<&serde_json::Value>, key: &str) -> Option<serde_json::Value> {
+    match data? {
+        serde_json::Value::Object(map) => map.get(key).cloned(),
+        other if key == "data" => Some(other.clone()),
+        _ => None,
+    }
+}
+
+fn data_attr_at(data: Option<&serde_json::Value>, index: u64) -> Option<(String, serde_json::Value)> {
+    match data? {
+        serde_json::Value::Object(map) => map.iter().nth(index as usize).map(|(k, v)| (k.clone(), v.clone())),
+        other if index == 0 => Some(("data".to_string(), other.clone())),
+        _ => None,
+    }
+}
+
+fn data_attrs(data: Option<&serde_json::Value>) -> Vec<(String, serde_json::Value)> {
+    match data {
+        Some(serde_json::Value::Object(map)) => map.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+        Some(other) => vec![("data".to_string(), other.clone())],
+        None => Vec::new(),
     }
 }
 
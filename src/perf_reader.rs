@@ -0,0 +1,453 @@
+//! Linux `perf script` and ftrace `function_graph` import.
+//!
+//! Software-side profiles are converted into a JETS trace so they can be
+//! opened (or merged) next to hardware traces. Clocks are nanoseconds, kept
+//! absolute so that traces captured on the same clock line up.
+//!
+//! `perf script` output becomes Process → Thread → Sample records. A sample
+//! is an instant named after the innermost frame of its callchain (or its
+//! event when there is none); event, period, CPU and the callchain are kept
+//! as attributes.
+//!
+//! ftrace `function_graph` output becomes one CPU record per CPU with the
+//! function calls nested below it as the graph nests them. With the
+//! `funcgraph-abstime` option calls start at their timestamp; without it the
+//! calls of each CPU are laid end to end from 0, so gaps between calls are
+//! lost.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::rc::Rc;
+
+use anyhow::{bail, Context, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::{json, Map, Value};
+
+use crate::parser::parse_trace_from_reader;
+use crate::schema::FormatVersion;
+use crate::traits::{DynTraceData, TraceReader};
+use crate::writer::TraceWriter;
+
+/// `comm [pid/]tid [cpu] seconds: [period] event: rest`
+static PERF_SAMPLE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^\s*(?P<comm>\S.*?)\s+(?P<pid>-?\d+)(?:/(?P<tid>-?\d+))?\s+(?:\[(?P<cpu>\d+)\]\s+)?(?P<time>\d+\.\d+):\s+(?:(?P<period>\d+)\s+)?(?P<event>\S+?):?(?:\s+(?P<rest>.*))?$",
+    )
+    .unwrap()
+});
+
+/// `address symbol+offset (dso)`
+static PERF_FRAME: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\s+(?P<addr>[0-9a-fA-F]+)\s+(?P<symbol>.+?)(?:\s+\((?P<dso>[^()]*)\))?\s*$").unwrap());
+
+/// `[seconds |] cpu) [task-pid |] [marker] [duration us] | body`
+static GRAPH_LINE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^\s*(?:(?P<time>\d+\.\d+)\s*\|\s*)?(?P<cpu>\d+)\)\s*(?:(?P<task>\S+-\d+)\s*\|)?\s*(?:[+!#*@$&]\s*)?(?:(?P<dur>\d+\.\d+)\s*us\s*)?\|\s?(?P<body>.*)$",
+    )
+    .unwrap()
+});
+
+/// Reader for `perf script` and ftrace `function_graph` text files.
+pub struct PerfScriptReader;
+
+impl PerfScriptReader {
+    pub fn new() -> Self {
+        PerfScriptReader
+    }
+
+    /// Converts `perf script` or `function_graph` text into a trace.
+    pub fn read_str(&self, text: &str) -> Result<DynTraceData> {
+        let jets = convert_to_jets(text)?;
+        Ok(DynTraceData::Jets(parse_trace_from_reader(jets.as_slice())?))
+    }
+}
+
+impl Default for PerfScriptReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TraceReader for PerfScriptReader {
+    fn read(&self, file_path: &str) -> Result<DynTraceData> {
+        let text = std::fs::read_to_string(file_path).with_context(|| format!("Failed to open file: {}", file_path))?;
+        self.read_str(&text).with_context(|| format!("Failed to import {}", file_path))
+    }
+}
+
+/// Returns true if the text looks like ftrace `function_graph` output.
+pub fn is_function_graph(text: &str) -> bool {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .take(64)
+        .any(|line| line.starts_with("# tracer: function_graph") || (!line.starts_with('#') && GRAPH_LINE.is_match(line)))
+}
+
+/// Converts `perf script` or `function_graph` text into JETS lines.
+pub fn convert_to_jets(text: &str) -> Result<Vec<u8>> {
+    let (source, nodes) = if is_function_graph(text) {
+        ("ftrace function_graph", function_graph_nodes(text))
+    } else {
+        ("perf script", perf_script_nodes(text))
+    };
+    if nodes.is_empty() {
+        bail!("No {} samples found", source);
+    }
+    write_nodes(source, &nodes)
+}
+
+/// A record to write, with its end if it has one.
+struct Node {
+    id: u64,
+    parent_id: Option<u64>,
+    record_type: &'static str,
+    name: String,
+    description: String,
+    clk: i64,
+    end_clk: Option<i64>,
+    data: Option<Value>,
+}
+
+/// One parsed `perf script` sample.
+struct PerfSample {
+    comm: String,
+    pid: i64,
+    tid: i64,
+    time: i64,
+    event: String,
+    attrs: Map<String, Value>,
+    top_symbol: Option<String>,
+}
+
+fn perf_script_nodes(text: &str) -> Vec<Node> {
+    let mut samples: Vec<PerfSample> = Vec::new();
+    let mut callchain: Vec<Value> = Vec::new();
+    let flush = |samples: &mut Vec<PerfSample>, callchain: &mut Vec<Value>| {
+        if let Some(sample) = samples.last_mut() {
+            if !callchain.is_empty() {
+                sample.attrs.insert("callchain".to_string(), Value::Array(std::mem::take(callchain)));
+            }
+        }
+    };
+
+    for line in text.lines() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(caps) = PERF_SAMPLE.captures(line) {
+            flush(&mut samples, &mut callchain);
+            let Some(time) = parse_fixed(&caps["time"], 9) else { continue };
+            let pid: i64 = caps["pid"].parse().unwrap_or(-1);
+            let tid = caps.name("tid").and_then(|tid| tid.as_str().parse().ok()).unwrap_or(pid);
+            let event = caps["event"].to_string();
+            let mut attrs = Map::new();
+            attrs.insert("event".to_string(), json!(event));
+            if let Some(cpu) = caps.name("cpu").and_then(|cpu| cpu.as_str().parse::<u32>().ok()) {
+                attrs.insert("cpu".to_string(), json!(cpu));
+            }
+            if let Some(period) = caps.name("period").and_then(|period| period.as_str().parse::<u64>().ok()) {
+                attrs.insert("period".to_string(), json!(period));
+            }
+            if let Some(rest) = caps.name("rest").map(|rest| rest.as_str().trim()).filter(|rest| !rest.is_empty()) {
+                attrs.insert("details".to_string(), json!(rest));
+            }
+            samples.push(PerfSample {
+                comm: caps["comm"].trim().to_string(),
+                pid,
+                tid,
+                time,
+                event,
+                attrs,
+                top_symbol: None,
+            });
+        } else if let Some(caps) = PERF_FRAME.captures(line) {
+            let Some(sample) = samples.last_mut() else { continue };
+            let symbol = caps["symbol"].to_string();
+            if sample.top_symbol.is_none() {
+                sample.top_symbol = Some(strip_offset(&symbol).to_string());
+            }
+            callchain.push(match caps.name("dso") {
+                Some(dso) => json!(format!("{} ({})", symbol, dso.as_str())),
+                None => json!(symbol),
+            });
+        }
+    }
+    flush(&mut samples, &mut callchain);
+
+    // Processes and threads span their samples
+    let mut next_id = 1;
+    let mut processes: BTreeMap<i64, (u64, String, i64, i64)> = BTreeMap::new();
+    let mut threads: BTreeMap<(i64, i64), (u64, String, i64, i64)> = BTreeMap::new();
+    let widen = |span: &mut (u64, String, i64, i64), time: i64| {
+        span.2 = span.2.min(time);
+        span.3 = span.3.max(time);
+    };
+    for sample in &samples {
+        let span = (0, sample.comm.clone(), sample.time, sample.time);
+        widen(processes.entry(sample.pid).or_insert_with(|| span.clone()), sample.time);
+        widen(threads.entry((sample.pid, sample.tid)).or_insert(span), sample.time);
+    }
+    let mut nodes = Vec::with_capacity(processes.len() + threads.len() + samples.len());
+    for (pid, (id, comm, start, end)) in processes.iter_mut() {
+        *id = next_id;
+        next_id += 1;
+        nodes.push(span_node(*id, None, "Process", format!("{} [{}]", comm, pid), *start, *end, json!({"pid": pid})));
+    }
+    for ((pid, tid), (id, comm, start, end)) in threads.iter_mut() {
+        *id = next_id;
+        next_id += 1;
+        let parent = processes[pid].0;
+        let data = json!({"pid": pid, "tid": tid});
+        nodes.push(span_node(*id, Some(parent), "Thread", format!("{} [{}]", comm, tid), *start, *end, data));
+    }
+    for sample in samples {
+        nodes.push(Node {
+            id: next_id,
+            parent_id: Some(threads[&(sample.pid, sample.tid)].0),
+            record_type: "Sample",
+            name: sample.top_symbol.unwrap_or_else(|| sample.event.clone()),
+            description: sample.event,
+            clk: sample.time,
+            end_clk: None,
+            data: Some(Value::Object(sample.attrs)),
+        });
+        next_id += 1;
+    }
+    nodes
+}
+
+fn span_node(id: u64, parent_id: Option<u64>, record_type: &'static str, name: String, clk: i64, end: i64, data: Value) -> Node {
+    Node {
+        id,
+        parent_id,
+        record_type,
+        name,
+        description: String::new(),
+        clk,
+        end_clk: Some(end),
+        data: Some(data),
+    }
+}
+
+fn function_graph_nodes(text: &str) -> Vec<Node> {
+    let mut nodes: Vec<Node> = Vec::new();
+    let mut next_id = 1;
+    // Per CPU: node index of the CPU record, open calls (node indices) and time cursor
+    let mut cpus: HashMap<u32, (usize, Vec<usize>, i64)> = HashMap::new();
+
+    for line in text.lines() {
+        if line.starts_with('#') {
+            continue;
+        }
+        let Some(caps) = GRAPH_LINE.captures(line) else { continue };
+        let Ok(cpu) = caps["cpu"].parse::<u32>() else { continue };
+        let time = caps.name("time").and_then(|time| parse_fixed(time.as_str(), 9));
+        let duration = caps.name("dur").and_then(|dur| parse_fixed(dur.as_str(), 3));
+        let body = caps["body"].trim();
+
+        let (cpu_index, open, cursor) = cpus.entry(cpu).or_insert_with(|| {
+            nodes.push(span_node(next_id, None, "CPU", format!("CPU {}", cpu), time.unwrap_or(0), time.unwrap_or(0), json!({"cpu": cpu})));
+            next_id += 1;
+            (nodes.len() - 1, Vec::new(), time.unwrap_or(0))
+        });
+        let now = time.unwrap_or(*cursor);
+
+        if body.starts_with('}') {
+            if let Some(index) = open.pop() {
+                let end = duration.map_or(now, |duration| nodes[index].clk.saturating_add(duration));
+                nodes[index].end_clk = Some(end);
+                *cursor = end.max(*cursor);
+            }
+        } else if let Some(function) = body.strip_suffix("() {").or_else(|| body.strip_suffix("();")) {
+            let is_leaf = body.ends_with(';');
+            let parent = open.last().copied().unwrap_or(*cpu_index);
+            let mut data = json!({"cpu": cpu});
+            if let Some(task) = caps.name("task") {
+                data["task"] = json!(task.as_str());
+            }
+            let end = is_leaf.then(|| now.saturating_add(duration.unwrap_or(0)));
+            nodes.push(Node {
+                id: next_id,
+                parent_id: Some(nodes[parent].id),
+                record_type: "Function",
+                name: function.trim().to_string(),
+                description: String::new(),
+                clk: now,
+                end_clk: end,
+                data: Some(data),
+            });
+            next_id += 1;
+            if is_leaf {
+                *cursor = end.unwrap_or(now).max(*cursor);
+            } else {
+                open.push(nodes.len() - 1);
+                *cursor = now.max(*cursor);
+            }
+        }
+    }
+
+    // Calls still open end where their CPU's output ends
+    for (cpu_index, open, cursor) in cpus.into_values() {
+        for index in open {
+            nodes[index].end_clk = Some(cursor);
+        }
+        let start = nodes[cpu_index].clk;
+        nodes[cpu_index].end_clk = Some(cursor.max(start));
+    }
+    if nodes.iter().all(|node| node.record_type == "CPU") {
+        nodes.clear();
+    }
+    nodes
+}
+
+/// Writes the nodes as a JETS trace into memory.
+fn write_nodes(source: &str, nodes: &[Node]) -> Result<Vec<u8>> {
+    let buffer = SharedBuffer::default();
+    let mut writer = TraceWriter::from_writer(Box::new(buffer.clone()));
+    writer.write_header(&FormatVersion::CURRENT.to_string(), json!({"source": source, "clk_unit": "ns"}))?;
+    for node in nodes {
+        writer.write_record(node.id, node.parent_id, node.record_type, node.clk, &node.name, &node.description, node.data.clone())?;
+    }
+    for node in nodes {
+        if let Some(end) = node.end_clk {
+            writer.write_record_end(node.id, end)?;
+        }
+    }
+    let capture_end = nodes.iter().map(|node| node.end_clk.unwrap_or(node.clk)).max();
+    writer.write_footer(capture_end)?;
+    drop(writer);
+    Ok(buffer.0.take())
+}
+
+/// In-memory sink shared with a [`TraceWriter`].
+#[derive(Clone, Default)]
+pub(crate) struct SharedBuffer(pub(crate) Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Parses a decimal such as `"1234.567890"` into an integer scaled by
+/// `10^digits` (extra fraction digits are truncated).
+fn parse_fixed(text: &str, digits: usize) -> Option<i64> {
+    let (whole, fraction) = text.split_once('.').unwrap_or((text, ""));
+    let mut fraction: String = fraction.chars().take(digits).collect();
+    while fraction.len() < digits {
+        fraction.push('0');
+    }
+    let scale = 10i64.checked_pow(digits as u32)?;
+    whole.parse::<i64>().ok()?.checked_mul(scale)?.checked_add(fraction.parse::<i64>().unwrap_or(0))
+}
+
+/// Drops a `+0x1c` offset from a symbol.
+fn strip_offset(symbol: &str) -> &str {
+    match symbol.rfind("+0x") {
+        Some(index) if index > 0 => &symbol[..index],
+        _ => symbol,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{AttributeAccessor, TraceData, TraceRecord};
+
+    #[test]
+    fn test_perf_script_samples_under_threads() {
+        let text = "\
+# ========
+# captured on: Tue Jan  7 10:00:00 2025
+# ========
+         swapper     0 [000]  1234.500000:     250000 cpu-clock:
+\tffffffff810a3b2c native_safe_halt+0xc ([kernel.kallsyms])
+\tffffffff81024d9f default_idle+0x1f ([kernel.kallsyms])
+
+ gpu worker  812/815  [003]  1234.500250:     250000 cpu-clock:u:
+\t    55d0c1e0 launch_kernel+0x40 (/usr/bin/app)
+
+ gpu worker  812/815  [003]  1234.501000: sched:sched_switch: prev_comm=app prev_pid=815
+";
+        let trace = PerfScriptReader::new().read_str(text).unwrap();
+        let roots = trace.root_ids();
+        assert_eq!(roots.len(), 2);
+
+        let app = trace.get_record(roots[1]).unwrap();
+        assert_eq!((app.name(), app.record_type()), ("gpu worker [812]".to_string(), "Process".to_string()));
+        let thread = app.child_at(0).unwrap();
+        assert_eq!(thread.name(), "gpu worker [815]");
+        assert_eq!((thread.clk(), thread.end_clk()), (1_234_500_250_000, Some(1_234_501_000_000)));
+
+        let sample = thread.child_at(0).unwrap();
+        assert_eq!(sample.name(), "launch_kernel");
+        assert_eq!(sample.attr("period"), Some(json!(250000)));
+        assert_eq!(sample.attr("cpu"), Some(json!(3)));
+        let switch = thread.child_at(1).unwrap();
+        assert_eq!(switch.name(), "sched:sched_switch");
+        assert_eq!(switch.attr("details"), Some(json!("prev_comm=app prev_pid=815")));
+
+        let idle = trace.get_record(roots[0]).unwrap().child_at(0).unwrap().child_at(0).unwrap();
+        assert_eq!(idle.name(), "native_safe_halt");
+        assert_eq!(idle.attr("callchain").unwrap().as_array().unwrap().len(), 2);
+
+        assert!(PerfScriptReader::new().read_str("no samples here\n").is_err());
+    }
+
+    #[test]
+    fn test_function_graph_nests_calls() {
+        let text = "\
+# tracer: function_graph
+#
+# CPU  DURATION                  FUNCTION CALLS
+# |     |   |                     |   |   |   |
+ 1)               |  schedule() {
+ 1)               |    rcu_note_context_switch() {
+ 1)   0.250 us    |      rcu_qs();
+ 1)   1.000 us    |    }
+ 1)   0.500 us    |    update_rq_clock();
+ 1) + 12.345 us   |  }
+ 0)   2.000 us    |  tick();
+";
+        assert!(is_function_graph(text));
+        let trace = PerfScriptReader::new().read_str(text).unwrap();
+        let roots: Vec<_> = trace.root_ids().into_iter().filter_map(|id| trace.get_record(id)).collect();
+        assert_eq!(roots.len(), 2);
+        let cpu1 = roots.iter().find(|cpu| cpu.name() == "CPU 1").unwrap();
+
+        let schedule = cpu1.child_at(0).unwrap();
+        assert_eq!((schedule.name(), schedule.clk(), schedule.end_clk()), ("schedule".to_string(), 0, Some(12_345)));
+        let rcu = schedule.child_at(0).unwrap();
+        assert_eq!(rcu.end_clk(), Some(1_000));
+        assert_eq!(rcu.child_at(0).unwrap().duration(), Some(250));
+        let update = schedule.child_at(1).unwrap();
+        assert_eq!((update.clk(), update.end_clk()), (1_000, Some(1_500)));
+
+        let with_time = " 5.000100 |   2)               |  foo() {\n 5.000200 |   2)   0.050 us    |    bar();\n 5.000300 |   2)   0.900 us    |  }\n";
+        let trace = PerfScriptReader::new().read_str(with_time).unwrap();
+        let foo = trace.get_record(trace.root_ids()[0]).unwrap().child_at(0).unwrap();
+        assert_eq!((foo.clk(), foo.end_clk()), (5_000_100_000, Some(5_000_100_900)));
+        assert_eq!(foo.child_at(0).unwrap().clk(), 5_000_200_000);
+    }
+
+    #[test]
+    fn test_function_graph_extreme_durations_saturate() {
+        let text = "\
+ 9223372036.000000 |   0)               |  foo() {
+ 9223372036.000000 |   0)   999999999.000 us    |    bar();
+ 9223372036.000000 |   0)   999999999.000 us    |  }
+";
+        let trace = PerfScriptReader::new().read_str(text).unwrap();
+        let foo = trace.get_record(trace.root_ids()[0]).unwrap().child_at(0).unwrap();
+        assert_eq!((foo.clk(), foo.end_clk()), (9_223_372_036_000_000_000, Some(i64::MAX)));
+        assert_eq!(foo.child_at(0).unwrap().end_clk(), Some(i64::MAX));
+    }
+}
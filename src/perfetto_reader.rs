@@ -0,0 +1,440 @@
+//! Chrome trace event (JSON) import, as written by Chrome tracing and
+//! exported by Perfetto.
+//!
+//! The capture is converted into a JETS trace: one Process record per `pid`
+//! with a Thread record per `tid` below it, and the thread's slices below
+//! that. Slices come from `B`/`E` pairs and `X` complete events and are
+//! nested by containment, so both styles may be mixed on one thread. Instant
+//! events (`i`/`I`) become events of the innermost slice open at their time;
+//! process- and global-scoped instants go to the process record. Names from
+//! `process_name`/`thread_name` metadata events are used when present.
+//!
+//! Timestamps are microseconds in the format and nanoseconds in the trace.
+//! Slice categories become record and event descriptions, `args` become
+//! attributes. Other phases (async, flow, counter, ...) are skipped, and so
+//! is Perfetto's native protobuf format.
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Map, Value};
+
+use crate::parser::parse_trace_from_reader;
+use crate::perf_reader::SharedBuffer;
+use crate::schema::FormatVersion;
+use crate::traits::{DynTraceData, TraceReader};
+use crate::writer::TraceWriter;
+
+/// Reader for Chrome trace event JSON files.
+pub struct PerfettoTraceReader;
+
+impl PerfettoTraceReader {
+    pub fn new() -> Self {
+        PerfettoTraceReader
+    }
+
+    /// Converts Chrome trace event JSON into a trace.
+    pub fn read_str(&self, text: &str) -> Result<DynTraceData> {
+        let jets = convert_to_jets(text)?;
+        Ok(DynTraceData::Jets(parse_trace_from_reader(jets.as_slice())?))
+    }
+}
+
+impl Default for PerfettoTraceReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TraceReader for PerfettoTraceReader {
+    fn read(&self, file_path: &str) -> Result<DynTraceData> {
+        let text = std::fs::read_to_string(file_path).with_context(|| format!("Failed to open file: {}", file_path))?;
+        self.read_str(&text).with_context(|| format!("Failed to import {}", file_path))
+    }
+}
+
+/// Converts Chrome trace event JSON into JETS lines.
+pub fn convert_to_jets(text: &str) -> Result<Vec<u8>> {
+    let events = trace_events(text)?;
+    let processes = collect_tracks(&events);
+    if processes.values().all(|process| process.is_empty()) {
+        bail!("No slices or instant events found");
+    }
+    write_tracks(&processes)
+}
+
+/// Returns the event list of either form of the format: an object with
+/// `traceEvents`, or a bare array, which may lack its closing bracket.
+fn trace_events(text: &str) -> Result<Vec<Value>> {
+    let value: Value = match serde_json::from_str(text) {
+        Ok(value) => value,
+        Err(e) => {
+            let trimmed = text.trim_end().trim_end_matches(',');
+            if !trimmed.trim_start().starts_with('[') {
+                return Err(e).context("Invalid trace JSON");
+            }
+            serde_json::from_str(&format!("{}]", trimmed)).context("Invalid trace JSON")?
+        }
+    };
+    match value {
+        Value::Array(events) => Ok(events),
+        Value::Object(mut object) => match object.remove("traceEvents") {
+            Some(Value::Array(events)) => Ok(events),
+            _ => bail!("No traceEvents array in trace JSON"),
+        },
+        _ => bail!("Trace JSON is neither an object nor an array"),
+    }
+}
+
+/// A `pid` or `tid`; usually a number, but some tools write strings.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum TrackId {
+    Number(i64),
+    Name(String),
+}
+
+impl TrackId {
+    fn from_value(value: Option<&Value>) -> Self {
+        match value {
+            Some(Value::String(name)) => TrackId::Name(name.clone()),
+            Some(value) => TrackId::Number(value.as_i64().unwrap_or(0)),
+            None => TrackId::Number(0),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        match self {
+            TrackId::Number(n) => json!(n),
+            TrackId::Name(name) => json!(name),
+        }
+    }
+}
+
+impl fmt::Display for TrackId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrackId::Number(n) => write!(f, "{}", n),
+            TrackId::Name(name) => f.write_str(name),
+        }
+    }
+}
+
+/// A slice or instant, before nesting.
+struct Span {
+    name: String,
+    category: String,
+    args: Map<String, Value>,
+    start: i64,
+    /// `None` for instants and for `B` events never ended
+    end: Option<i64>,
+}
+
+#[derive(Default)]
+struct ThreadTrack {
+    name: Option<String>,
+    slices: Vec<Span>,
+    /// `B` events waiting for their `E`
+    open: Vec<Span>,
+    instants: Vec<Span>,
+}
+
+#[derive(Default)]
+struct ProcessTrack {
+    name: Option<String>,
+    threads: BTreeMap<TrackId, ThreadTrack>,
+    /// Process- and global-scoped instants
+    instants: Vec<Span>,
+}
+
+impl ProcessTrack {
+    fn is_empty(&self) -> bool {
+        self.instants.is_empty() && self.threads.values().all(|thread| thread.slices.is_empty() && thread.instants.is_empty())
+    }
+}
+
+fn collect_tracks(events: &[Value]) -> BTreeMap<TrackId, ProcessTrack> {
+    let mut processes: BTreeMap<TrackId, ProcessTrack> = BTreeMap::new();
+    for event in events {
+        let Some(phase) = event.get("ph").and_then(Value::as_str) else { continue };
+        let process = processes.entry(TrackId::from_value(event.get("pid"))).or_default();
+        let tid = TrackId::from_value(event.get("tid"));
+        let name = event.get("name").and_then(Value::as_str).unwrap_or_default().to_string();
+        let args = match event.get("args") {
+            Some(Value::Object(args)) => args.clone(),
+            _ => Map::new(),
+        };
+
+        if phase == "M" {
+            let label = args.get("name").and_then(Value::as_str).map(str::to_string);
+            match name.as_str() {
+                "process_name" => process.name = label,
+                "thread_name" => process.threads.entry(tid).or_default().name = label,
+                _ => {}
+            }
+            continue;
+        }
+        let Some(start) = event.get("ts").and_then(micros_to_ns) else { continue };
+        let span = Span {
+            name,
+            category: event.get("cat").and_then(Value::as_str).unwrap_or_default().to_string(),
+            args,
+            start,
+            end: None,
+        };
+        match phase {
+            "B" => process.threads.entry(tid).or_default().open.push(span),
+            "E" => {
+                let thread = process.threads.entry(tid).or_default();
+                // An E closes the latest B of its thread; stray ones are dropped
+                if let Some(mut slice) = thread.open.pop() {
+                    slice.end = Some(start.max(slice.start));
+                    slice.args.extend(span.args);
+                    thread.slices.push(slice);
+                }
+            }
+            "X" => {
+                let duration = event.get("dur").and_then(micros_to_ns).unwrap_or(0).max(0);
+                // A duration running past the clock range ends at its limit
+                let end = Some(start.saturating_add(duration));
+                process.threads.entry(tid).or_default().slices.push(Span { end, ..span });
+            }
+            "i" | "I" => match event.get("s").and_then(Value::as_str) {
+                Some("p") | Some("g") => process.instants.push(span),
+                _ => process.threads.entry(tid).or_default().instants.push(span),
+            },
+            _ => {}
+        }
+    }
+    for thread in processes.values_mut().flat_map(|process| process.threads.values_mut()) {
+        let open = std::mem::take(&mut thread.open);
+        thread.slices.extend(open);
+    }
+    processes
+}
+
+/// Converts a microsecond value (integer or fractional) to nanoseconds.
+fn micros_to_ns(value: &Value) -> Option<i64> {
+    match value.as_i64() {
+        Some(micros) => micros.checked_mul(1000),
+        None => value.as_f64().filter(|micros| micros.is_finite()).map(|micros| (micros * 1000.0).round() as i64),
+    }
+}
+
+/// A record to write, with the events attached to it.
+struct Node<'a> {
+    id: u64,
+    parent_id: Option<u64>,
+    record_type: &'static str,
+    name: String,
+    span_of: Option<&'a Span>,
+    data: Value,
+    clk: i64,
+    end_clk: Option<i64>,
+    events: Vec<&'a Span>,
+}
+
+/// Latest time a span covers; instants count as their start.
+fn span_last(span: &Span) -> i64 {
+    span.end.unwrap_or(span.start)
+}
+
+fn write_tracks(processes: &BTreeMap<TrackId, ProcessTrack>) -> Result<Vec<u8>> {
+    let mut nodes: Vec<Node> = Vec::new();
+    let mut next_id = 1;
+    for (pid, process) in processes.iter().filter(|(_, process)| !process.is_empty()) {
+        let process_index = nodes.len();
+        let process_id = next_id;
+        next_id += 1;
+        let name = match &process.name {
+            Some(name) => format!("{} [{}]", name, pid),
+            None => format!("Process {}", pid),
+        };
+        nodes.push(Node {
+            id: process_id,
+            parent_id: None,
+            record_type: "Process",
+            name,
+            span_of: None,
+            data: json!({"pid": pid.to_value()}),
+            clk: i64::MAX,
+            end_clk: Some(i64::MIN),
+            events: process.instants.iter().collect(),
+        });
+
+        for (tid, thread) in &process.threads {
+            if thread.slices.is_empty() && thread.instants.is_empty() {
+                continue;
+            }
+            let thread_index = nodes.len();
+            let thread_id = next_id;
+            next_id += 1;
+            let name = match &thread.name {
+                Some(name) => format!("{} [{}]", name, tid),
+                None => format!("Thread {}", tid),
+            };
+            let spans = thread.slices.iter().chain(&thread.instants);
+            let clk = spans.clone().map(|span| span.start).min().unwrap_or(0);
+            let end = spans.map(span_last).max().unwrap_or(clk);
+            nodes.push(Node {
+                id: thread_id,
+                parent_id: Some(process_id),
+                record_type: "Thread",
+                name,
+                span_of: None,
+                data: json!({"pid": pid.to_value(), "tid": tid.to_value()}),
+                clk,
+                end_clk: Some(end),
+                events: Vec::new(),
+            });
+            nest_thread(thread, thread_index, &mut nodes, &mut next_id);
+
+            let process_node = &mut nodes[process_index];
+            process_node.clk = process_node.clk.min(clk);
+            process_node.end_clk = process_node.end_clk.max(Some(end));
+        }
+
+        let process_node = &mut nodes[process_index];
+        for instant in &process.instants {
+            process_node.clk = process_node.clk.min(instant.start);
+            process_node.end_clk = process_node.end_clk.max(Some(instant.start));
+        }
+    }
+    write_nodes(&nodes)
+}
+
+/// Adds the slices of a thread below its node, each under the innermost
+/// slice containing it, and attaches the instants the same way.
+fn nest_thread<'a>(thread: &'a ThreadTrack, thread_index: usize, nodes: &mut Vec<Node<'a>>, next_id: &mut u64) {
+    let mut slices: Vec<&Span> = thread.slices.iter().collect();
+    // Outer slices first when two start together; unended ones are outermost
+    slices.sort_by_key(|slice| (slice.start, Reverse(slice.end.unwrap_or(i64::MAX))));
+    let mut instants: Vec<&Span> = thread.instants.iter().collect();
+    instants.sort_by_key(|instant| instant.start);
+
+    // Node indices of the open slices, innermost last
+    let mut stack: Vec<usize> = Vec::new();
+    let end_of = |nodes: &[Node], index: usize| nodes[index].span_of.and_then(|span| span.end).unwrap_or(i64::MAX);
+    let mut instants = instants.into_iter().peekable();
+    for slice in slices {
+        // Instants at a slice's start belong to it, so they wait for it
+        while let Some(instant) = instants.next_if(|instant| instant.start < slice.start) {
+            while stack.last().is_some_and(|&top| end_of(nodes, top) < instant.start) {
+                stack.pop();
+            }
+            nodes[stack.last().copied().unwrap_or(thread_index)].events.push(instant);
+        }
+        while stack.last().is_some_and(|&top| end_of(nodes, top) <= slice.start) {
+            stack.pop();
+        }
+        let parent = stack.last().copied().unwrap_or(thread_index);
+        let parent_id = nodes[parent].id;
+        stack.push(nodes.len());
+        nodes.push(Node {
+            id: *next_id,
+            parent_id: Some(parent_id),
+            record_type: "Slice",
+            name: slice.name.clone(),
+            span_of: Some(slice),
+            data: Value::Object(slice.args.clone()),
+            clk: slice.start,
+            end_clk: slice.end,
+            events: Vec::new(),
+        });
+        *next_id += 1;
+    }
+    for instant in instants {
+        while stack.last().is_some_and(|&top| end_of(nodes, top) < instant.start) {
+            stack.pop();
+        }
+        nodes[stack.last().copied().unwrap_or(thread_index)].events.push(instant);
+    }
+}
+
+/// Writes the nodes as a JETS trace into memory.
+fn write_nodes(nodes: &[Node]) -> Result<Vec<u8>> {
+    let buffer = SharedBuffer::default();
+    let mut writer = TraceWriter::from_writer(Box::new(buffer.clone()));
+    writer.write_header(&FormatVersion::CURRENT.to_string(), json!({"source": "chrome trace", "clk_unit": "ns"}))?;
+    for node in nodes {
+        let description = node.span_of.map_or("", |span| span.category.as_str());
+        let data = Some(node.data.clone()).filter(|data| data.as_object().is_none_or(|data| !data.is_empty()));
+        writer.write_record(node.id, node.parent_id, node.record_type, node.clk, &node.name, description, data)?;
+    }
+    for node in nodes {
+        for event in &node.events {
+            let data = Some(Value::Object(event.args.clone())).filter(|_| !event.args.is_empty());
+            writer.write_event(node.id, &event.name, &event.category, event.start, data)?;
+        }
+    }
+    for node in nodes {
+        if let Some(end) = node.end_clk {
+            writer.write_record_end(node.id, end)?;
+        }
+    }
+    let capture_end = nodes.iter().map(|node| node.end_clk.unwrap_or(node.clk)).max();
+    writer.write_footer(capture_end)?;
+    drop(writer);
+    Ok(buffer.0.take())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{AttributeAccessor, TraceData, TraceEvent, TraceRecord};
+
+    #[test]
+    fn test_chrome_events_nest_under_threads() {
+        // Bare array without its closing bracket, as written by a crashed tracer
+        let text = r#"[
+{"ph":"M","name":"process_name","pid":7,"tid":0,"args":{"name":"renderer"}},
+{"ph":"M","name":"thread_name","pid":7,"tid":70,"args":{"name":"main"}},
+{"ph":"B","name":"frame","cat":"gfx","pid":7,"tid":70,"ts":100},
+{"ph":"X","name":"layout","cat":"gfx","pid":7,"tid":70,"ts":110.5,"dur":20,"args":{"nodes":12}},
+{"ph":"i","name":"vsync","pid":7,"tid":70,"ts":120,"s":"t"},
+{"ph":"E","pid":7,"tid":70,"ts":200,"args":{"dropped":false}},
+{"ph":"i","name":"gc","pid":7,"tid":70,"ts":250,"s":"p"},
+{"ph":"B","name":"idle","pid":"gpu","tid":1,"ts":300},
+{"ph":"C","name":"memory","pid":7,"tid":70,"ts":150,"args":{"heap":1}},
+"#;
+        let trace = PerfettoTraceReader::new().read_str(text).unwrap();
+        let roots = trace.root_ids();
+        assert_eq!(roots.len(), 2);
+
+        let renderer = trace.get_record(roots[0]).unwrap();
+        assert_eq!((renderer.name(), renderer.record_type()), ("renderer [7]".to_string(), "Process".to_string()));
+        assert_eq!((renderer.clk(), renderer.end_clk()), (100_000, Some(250_000)));
+        assert_eq!(renderer.event_at(0).unwrap().name(), "gc");
+
+        let main = renderer.child_at(0).unwrap();
+        assert_eq!(main.name(), "main [70]");
+        let frame = main.child_at(0).unwrap();
+        assert_eq!((frame.name(), frame.description()), ("frame".to_string(), "gfx".to_string()));
+        assert_eq!((frame.clk(), frame.end_clk()), (100_000, Some(200_000)));
+        assert_eq!(frame.attr("dropped"), Some(json!(false)));
+
+        let layout = frame.child_at(0).unwrap();
+        assert_eq!((layout.clk(), layout.end_clk()), (110_500, Some(130_500)));
+        assert_eq!(layout.attr("nodes"), Some(json!(12)));
+        let vsync = layout.event_at(0).unwrap();
+        assert_eq!((vsync.name(), vsync.clk()), ("vsync".to_string(), 120_000));
+
+        let gpu = trace.get_record(roots[1]).unwrap();
+        assert_eq!(gpu.name(), "Process gpu");
+        let idle = gpu.child_at(0).unwrap().child_at(0).unwrap();
+        assert_eq!((idle.name(), idle.end_clk()), ("idle".to_string(), None));
+
+        assert!(PerfettoTraceReader::new().read_str(r#"{"traceEvents":[]}"#).is_err());
+        assert!(PerfettoTraceReader::new().read_str("not json").is_err());
+    }
+    #[test]
+    fn test_complete_event_end_saturates() {
+        let text = r#"[{"ph":"X","name":"long","pid":1,"tid":1,"ts":9223372036854775,"dur":1000000}]"#;
+        let trace = PerfettoTraceReader::new().read_str(text).unwrap();
+        let process = trace.get_record(trace.root_ids()[0]).unwrap();
+        let slice = process.child_at(0).unwrap().child_at(0).unwrap();
+        assert_eq!(slice.name(), "long");
+        assert_eq!((slice.clk(), slice.end_clk()), (9_223_372_036_854_775_000, Some(i64::MAX)));
+    }
+}
@@ -1,17 +1,22 @@
 use crate::traits::{TraceReader, TraceData, TraceMetadata, TraceRecord, TraceEvent, DynTraceData, AttributeAccessor};
 
 /// stub implementation for now.
-
 pub struct PipetraceReader;
 
 impl PipetraceReader {
     pub fn new() -> Self { PipetraceReader }
 }
 
+impl Default for PipetraceReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TraceReader for PipetraceReader {
     fn read(&self, _file_path: &str) -> anyhow::Result<DynTraceData> {
         // Return an empty PipetraceData stub
-        Ok(DynTraceData::Pipetrace(PipetraceData::default()))
+        Ok(DynTraceData::Pipetrace(PipetraceData))
     }
 }
 
@@ -56,6 +61,7 @@ impl<'a> TraceRecord<'a> for PipetraceRecordRef<'a> {
     fn end_clk(&self) -> Option<i64> { self.0.end_clk() }
     fn duration(&self) -> Option<i64> { self.0.duration() }
     fn name(&self) -> String { self.0.name() }
+    fn record_type(&self) -> String { self.0.record_type() }
     fn id(&self) -> u64 { self.0.id() }
     fn parent_id(&self) -> Option<u64> { self.0.parent_id() }
     fn description(&self) -> String { self.0.description() }
@@ -109,7 +115,7 @@ impl TraceData for PipetraceData {
         PipetraceMetadataRef(&EMPTY_PIPETRACE_METADATA)
     }
 
-    fn root_ids(&self) -> Vec<u64> { Vec::new() }
+    fn roots(&self) -> &[u64] { &[] }
 
     fn get_record(&self, _id: u64) -> Option<Self::Record<'_>> { None }
 }
@@ -160,6 +166,7 @@ impl<'a> TraceRecord<'a> for &'a PipetraceRecord {
     fn end_clk(&self) -> Option<i64> { None }
     fn duration(&self) -> Option<i64> { None }
     fn name(&self) -> String { "".to_string() }
+    fn record_type(&self) -> String { "".to_string() }
     fn id(&self) -> u64 { 0 }
     fn parent_id(&self) -> Option<u64> { None }
     fn description(&self) -> String { "".to_string() }
@@ -0,0 +1,196 @@
+//! Read-ahead for compressed trace files.
+//!
+//! Brotli decompression costs about as much CPU as parsing the JSON it
+//! yields, and a plain `BufReader` over a `Decompressor` does the two in
+//! turn on the loading thread. A [`PrefetchReader`] moves the inner reader
+//! to a worker thread that fills chunks ahead of the consumer: while the
+//! parser works through one chunk, the next ones are being decompressed.
+//!
+//! With a single core the worker only adds thread switches, so
+//! [`brotli_reader`] then decompresses inline with the same buffer sizes.
+//!
+//! At most [`PREFETCH_DEPTH`] filled chunks wait in the queue, so memory
+//! stays bounded by a few chunks whatever the trace size. Consumed chunks
+//! go back to the worker to be refilled.
+//!
+//! `cargo bench --bench load` compares the tuned reading against the former
+//! 4 KiB decompressor buffer.
+
+use std::io::{self, BufRead, BufReader, Read};
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender};
+use std::thread;
+
+use brotli::Decompressor;
+
+/// Input buffer of the Brotli decompressor (compressed bytes per file read).
+pub const DECOMPRESS_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Buffer for reading uncompressed traces.
+pub const FILE_BUFFER_SIZE: usize = 256 * 1024;
+
+/// Decompressed bytes handed over per chunk.
+pub const PREFETCH_CHUNK_SIZE: usize = 256 * 1024;
+
+/// Filled chunks the worker may queue ahead of the consumer.
+pub const PREFETCH_DEPTH: usize = 2;
+
+/// Opens a Brotli stream for line reading, decompressing ahead on a worker
+/// thread when there is more than one core.
+pub fn brotli_reader<R: Read + Send + 'static>(inner: R) -> io::Result<Box<dyn BufRead>> {
+    let decompressor = Decompressor::new(inner, DECOMPRESS_BUFFER_SIZE);
+    if thread::available_parallelism().map_or(1, |n| n.get()) > 1 {
+        Ok(Box::new(PrefetchReader::new(decompressor)?))
+    } else {
+        Ok(Box::new(BufReader::with_capacity(PREFETCH_CHUNK_SIZE, decompressor)))
+    }
+}
+
+/// Buffered reader filled by a worker thread reading ahead.
+///
+/// Errors of the inner reader are returned once, after the data read before
+/// them; the stream then ends.
+pub struct PrefetchReader {
+    chunks: Receiver<io::Result<Vec<u8>>>,
+    /// Consumed chunks returned to the worker for reuse
+    recycle: Sender<Vec<u8>>,
+    current: Vec<u8>,
+    position: usize,
+    finished: bool,
+}
+
+impl PrefetchReader {
+    /// Starts reading `inner` ahead in [`PREFETCH_CHUNK_SIZE`] chunks.
+    pub fn new<R: Read + Send + 'static>(inner: R) -> io::Result<Self> {
+        Self::with_chunk_size(inner, PREFETCH_CHUNK_SIZE)
+    }
+
+    /// Starts reading `inner` ahead in chunks of `chunk_size` bytes.
+    pub fn with_chunk_size<R: Read + Send + 'static>(inner: R, chunk_size: usize) -> io::Result<Self> {
+        let (chunk_tx, chunks) = mpsc::sync_channel(PREFETCH_DEPTH);
+        let (recycle, recycled) = mpsc::channel();
+        thread::Builder::new()
+            .name("jets-prefetch".to_string())
+            .spawn(move || read_ahead(inner, chunk_size.max(1), chunk_tx, recycled))?;
+        Ok(PrefetchReader {
+            chunks,
+            recycle,
+            current: Vec::new(),
+            position: 0,
+            finished: false,
+        })
+    }
+}
+
+/// Worker loop: fills chunks until the end of `inner`, an error, or the
+/// reader being dropped.
+fn read_ahead<R: Read>(
+    mut inner: R,
+    chunk_size: usize,
+    chunks: SyncSender<io::Result<Vec<u8>>>,
+    recycled: Receiver<Vec<u8>>,
+) {
+    loop {
+        let mut chunk = recycled.try_recv().unwrap_or_default();
+        chunk.resize(chunk_size, 0);
+        let mut filled = 0;
+        while filled < chunk_size {
+            match inner.read(&mut chunk[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    chunk.truncate(filled);
+                    if filled > 0 && chunks.send(Ok(chunk)).is_err() {
+                        return;
+                    }
+                    let _ = chunks.send(Err(e));
+                    return;
+                }
+            }
+        }
+        if filled == 0 {
+            return;
+        }
+        chunk.truncate(filled);
+        let last = filled < chunk_size;
+        if chunks.send(Ok(chunk)).is_err() || last {
+            return;
+        }
+    }
+}
+
+impl BufRead for PrefetchReader {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.position >= self.current.len() && !self.finished {
+            match self.chunks.recv() {
+                Ok(Ok(chunk)) => {
+                    let consumed = std::mem::replace(&mut self.current, chunk);
+                    // The worker may already be gone
+                    let _ = self.recycle.send(consumed);
+                    self.position = 0;
+                }
+                Ok(Err(e)) => {
+                    self.finished = true;
+                    return Err(e);
+                }
+                // Worker finished: end of stream
+                Err(_) => self.finished = true,
+            }
+        }
+        Ok(&self.current[self.position.min(self.current.len())..])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.position += amount;
+    }
+}
+
+impl Read for PrefetchReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Yields its data a few bytes per read, then fails.
+    struct Trickle {
+        data: Vec<u8>,
+        position: usize,
+    }
+
+    impl Read for Trickle {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.position == self.data.len() {
+                return Err(io::Error::other("disk gone"));
+            }
+            let n = buf.len().min(3).min(self.data.len() - self.position);
+            buf[..n].copy_from_slice(&self.data[self.position..self.position + n]);
+            self.position += n;
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_prefetch_keeps_bytes_and_reports_error_after_data() {
+        let text: String = (0..200).map(|i| format!("line {}\n", i)).collect();
+        let complete = PrefetchReader::with_chunk_size(io::Cursor::new(text.clone().into_bytes()), 7).unwrap();
+        let lines: Vec<String> = complete.lines().collect::<io::Result<_>>().unwrap();
+        assert_eq!(lines.len(), 200);
+        assert_eq!(lines.join("\n") + "\n", text);
+
+        let failing = Trickle { data: text.clone().into_bytes(), position: 0 };
+        let mut reader = PrefetchReader::with_chunk_size(failing, 16).unwrap();
+        let mut read = Vec::new();
+        let error = reader.read_to_end(&mut read).unwrap_err();
+        assert_eq!(error.to_string(), "disk gone");
+        assert_eq!(read, text.as_bytes());
+        assert_eq!(reader.read(&mut [0; 8]).unwrap(), 0, "stream ends after the error");
+    }
+}
@@ -2,13 +2,20 @@
 //!
 //! This module provides functions for:
 //! - Assigning colors to records based on their name patterns
+//! - Applying per-record-type color overrides chosen in the type legend
+//!   and colors hinted by the trace header
+//! - Coloring event markers by severity
 //! - Getting the current theme's color palette
 //!
 //! Color assignment is deterministic based on record names.
 
 use egui::Color32;
+use std::collections::HashMap;
 use rjets::{ThemeManager, ThemeColors};
 
+use crate::domain::severity::Severity;
+use crate::domain::type_hints::TypeHints;
+
 /// Returns a reference to the current theme's color palette.
 ///
 /// # Arguments
@@ -49,3 +56,35 @@ pub fn get_record_color(name: &str, colors: &ThemeColors) -> Color32 {
         _ => colors.text_dim,
     }
 }
+
+/// Returns the color for a record: a user override for its type, else the
+/// color hinted by the trace, else one based on the name.
+///
+/// # Arguments
+/// * `name` - The name of the record
+/// * `record_type` - The record's type
+/// * `overrides` - Colors chosen in the type legend, keyed by record type
+/// * `hints` - Per-type hints from the trace header
+/// * `colors` - The current theme's color palette
+pub fn record_color(
+    name: &str,
+    record_type: &str,
+    overrides: &HashMap<String, Color32>,
+    hints: &TypeHints,
+    colors: &ThemeColors,
+) -> Color32 {
+    overrides
+        .get(record_type)
+        .copied()
+        .or_else(|| hints.color(record_type))
+        .unwrap_or_else(|| get_record_color(name, colors))
+}
+
+/// Returns the marker color for an event severity.
+pub fn severity_color(severity: Severity, colors: &ThemeColors) -> Color32 {
+    match severity {
+        Severity::Info => colors.yellow,
+        Severity::Warning => colors.orange,
+        Severity::Error => colors.red,
+    }
+}
@@ -0,0 +1,157 @@
+//! Limited markdown in record and event descriptions.
+//!
+//! Producer tools may format diagnostics with a small inline subset of
+//! markdown: `**bold**`, `` `code` `` and `[text](url)`. Anything else,
+//! including unclosed markers, is shown as written. A backslash escapes the
+//! next marker character.
+//!
+//! Places that can only paint plain strings (tree columns, bar labels) use
+//! [`plain_text`]; the details panel and tooltips use [`show_markup`].
+
+use egui::{Color32, RichText};
+
+/// A run of description text with one style.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MarkupSpan {
+    Text(String),
+    Bold(String),
+    Code(String),
+    Link { text: String, url: String },
+}
+
+impl MarkupSpan {
+    /// Returns the visible text of the span.
+    pub fn text(&self) -> &str {
+        match self {
+            Self::Text(text) | Self::Bold(text) | Self::Code(text) | Self::Link { text, .. } => text,
+        }
+    }
+}
+
+/// Splits a description into styled spans.
+pub fn parse_markup(text: &str) -> Vec<MarkupSpan> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        if let Some((span, len)) = markup_at(rest) {
+            if !plain.is_empty() {
+                spans.push(MarkupSpan::Text(std::mem::take(&mut plain)));
+            }
+            spans.push(span);
+            rest = &rest[len..];
+            continue;
+        }
+
+        rest = &rest[c.len_utf8()..];
+        if c == '\\' {
+            if let Some(escaped) = rest.chars().next().filter(|n| matches!(n, '*' | '`' | '[' | '\\')) {
+                plain.push(escaped);
+                rest = &rest[escaped.len_utf8()..];
+                continue;
+            }
+        }
+        plain.push(c);
+    }
+
+    if !plain.is_empty() {
+        spans.push(MarkupSpan::Text(plain));
+    }
+    spans
+}
+
+/// Parses a styled span at the start of `s`, returning it with its byte length.
+fn markup_at(s: &str) -> Option<(MarkupSpan, usize)> {
+    if let Some(body) = s.strip_prefix("**") {
+        let end = body.find("**").filter(|&end| end > 0)?;
+        return Some((MarkupSpan::Bold(body[..end].to_string()), end + 4));
+    }
+    if let Some(body) = s.strip_prefix('`') {
+        let end = body.find('`').filter(|&end| end > 0)?;
+        return Some((MarkupSpan::Code(body[..end].to_string()), end + 2));
+    }
+    if let Some(body) = s.strip_prefix('[') {
+        let label_end = body.find("](")?;
+        let label = &body[..label_end];
+        let after = &body[label_end + 2..];
+        let url_end = after.find(')')?;
+        let url = &after[..url_end];
+        if label.is_empty() || label.contains('[') || url.is_empty() || url.contains(char::is_whitespace) {
+            return None;
+        }
+        let span = MarkupSpan::Link {
+            text: label.to_string(),
+            url: url.to_string(),
+        };
+        return Some((span, 1 + label_end + 2 + url_end + 1));
+    }
+    None
+}
+
+/// Returns true if the description contains any styled span.
+pub fn has_markup(text: &str) -> bool {
+    parse_markup(text).iter().any(|span| !matches!(span, MarkupSpan::Text(_)))
+}
+
+/// Returns the description with markup removed (link targets are dropped).
+pub fn plain_text(text: &str) -> String {
+    if !text.contains(['*', '`', '[', '\\']) {
+        return text.to_string();
+    }
+    parse_markup(text).iter().map(MarkupSpan::text).collect()
+}
+
+/// Shows a description with its markup applied, wrapping at the available width.
+///
+/// Only `http(s)` links are clickable; other targets are shown on hover.
+pub fn show_markup(ui: &mut egui::Ui, text: &str, color: Color32) {
+    ui.horizontal_wrapped(|ui| {
+        ui.spacing_mut().item_spacing.x = 0.0;
+        for span in parse_markup(text) {
+            match span {
+                MarkupSpan::Text(text) => ui.label(RichText::new(text).color(color)),
+                MarkupSpan::Bold(text) => ui.label(RichText::new(text).color(color).strong()),
+                MarkupSpan::Code(text) => ui.label(RichText::new(text).code()),
+                MarkupSpan::Link { text, url } if url.starts_with("http://") || url.starts_with("https://") => {
+                    ui.hyperlink_to(text, &url).on_hover_text(url)
+                }
+                MarkupSpan::Link { text, url } => {
+                    ui.label(RichText::new(text).color(color).underline()).on_hover_text(url)
+                }
+            };
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_bold_code_and_links() {
+        let spans = parse_markup("stall in **LSU**: `ld.global` see [docs](https://example.com/x)");
+        assert_eq!(
+            spans,
+            vec![
+                MarkupSpan::Text("stall in ".into()),
+                MarkupSpan::Bold("LSU".into()),
+                MarkupSpan::Text(": ".into()),
+                MarkupSpan::Code("ld.global".into()),
+                MarkupSpan::Text(" see ".into()),
+                MarkupSpan::Link { text: "docs".into(), url: "https://example.com/x".into() },
+            ]
+        );
+        assert_eq!(plain_text("stall in **LSU**: `ld.global` see [docs](https://example.com/x)"), "stall in LSU: ld.global see docs");
+    }
+
+    #[test]
+    fn test_unclosed_and_escaped_markers_stay_plain() {
+        for text in ["a ** b", "x * y", "`open", "[label](no close", "[](empty)", "a [b] (c)"] {
+            assert!(!has_markup(text), "{text}");
+            assert_eq!(plain_text(text), text);
+        }
+        assert_eq!(plain_text(r"\*\*not bold\*\*"), "**not bold**");
+        assert_eq!(plain_text("naïve **ünïcode**"), "naïve ünïcode");
+    }
+}
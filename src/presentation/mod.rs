@@ -3,5 +3,7 @@
 //! This module contains presentation logic separated from business logic:
 //! - Color mapping for timeline bars and UI elements
 //! - Theme-related visual styling
+//! - Limited markdown in descriptions
 
 pub mod color_mapping;
+pub mod markup;
@@ -0,0 +1,153 @@
+//! Trace provenance: a content hash and producer info in the footer.
+//!
+//! A producer that wants its traces to be checkable later enables provenance
+//! on the writer ([`crate::TraceWriter::with_provenance`]). The footer then
+//! carries a `provenance` object:
+//!
+//! ```json
+//! {"type":"footer", ..., "provenance":{"algorithm":"sha256","hash":"3f2a...","producer":"jets-tracegen 0.1.1"}}
+//! ```
+//!
+//! The hash covers every line before the footer, each followed by `\n`, as
+//! plain text (so compressing a trace does not change it). The parser only
+//! reads the block; checking it means reading the file again, which viewers
+//! do in the background with [`verify_trace_file`].
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt::{self, Write as _};
+use std::io::BufRead;
+
+/// Hash algorithm written by [`ContentHasher`].
+pub const PROVENANCE_ALGORITHM: &str = "sha256";
+
+/// The `provenance` object of a footer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// Hash algorithm ([`PROVENANCE_ALGORITHM`])
+    pub algorithm: String,
+    /// Lowercase hex digest of the lines before the footer
+    pub hash: String,
+    /// Tool (and version) that wrote the trace
+    pub producer: String,
+}
+
+/// Incremental hash over trace lines.
+#[derive(Clone, Default)]
+pub struct ContentHasher {
+    hasher: Sha256,
+}
+
+impl ContentHasher {
+    /// Creates a hasher that has seen no lines.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds one line (without its line terminator).
+    pub fn update_line(&mut self, line: &str) {
+        self.hasher.update(line.as_bytes());
+        self.hasher.update(b"\n");
+    }
+
+    /// Returns the lowercase hex digest of the lines seen so far.
+    pub fn finish(self) -> String {
+        let digest = self.hasher.finalize();
+        let mut hex = String::with_capacity(digest.len() * 2);
+        for byte in digest {
+            let _ = write!(hex, "{:02x}", byte);
+        }
+        hex
+    }
+}
+
+/// Outcome of checking a trace against its provenance block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verification {
+    /// The content hash matches
+    Verified(Provenance),
+    /// The lines before the footer changed after the hash was written
+    Mismatch { provenance: Provenance, actual: String },
+    /// The footer names a hash algorithm this reader does not know
+    Unsupported(Provenance),
+    /// The trace has no provenance block
+    Unsigned,
+}
+
+impl Verification {
+    /// Compares the hash of the content with the one in the footer.
+    pub fn check(provenance: Provenance, actual: String) -> Self {
+        if provenance.algorithm != PROVENANCE_ALGORITHM {
+            Verification::Unsupported(provenance)
+        } else if provenance.hash.eq_ignore_ascii_case(&actual) {
+            Verification::Verified(provenance)
+        } else {
+            Verification::Mismatch { provenance, actual }
+        }
+    }
+}
+
+impl fmt::Display for Verification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verification::Verified(p) => write!(f, "verified ({}, written by {})", p.algorithm, p.producer),
+            Verification::Mismatch { provenance, .. } => {
+                write!(f, "hash mismatch (modified after {} wrote it)", provenance.producer)
+            }
+            Verification::Unsupported(p) => write!(f, "unsupported hash algorithm '{}'", p.algorithm),
+            Verification::Unsigned => write!(f, "no provenance"),
+        }
+    }
+}
+
+/// Checks the provenance of a JETS trace file (`.br` files are decompressed).
+pub fn verify_trace_file(file_path: &str) -> Result<Verification> {
+    verify_reader(crate::parser::open_trace_file(file_path)?)
+}
+
+/// Checks the provenance of JETS lines from any buffered reader.
+///
+/// Lines are hashed as they stream by; only the last line is parsed, to
+/// read the footer.
+pub fn verify_reader<R: BufRead>(reader: R) -> Result<Verification> {
+    let mut hasher = ContentHasher::new();
+    let mut last: Option<String> = None;
+
+    for (line_num, line_result) in reader.lines().enumerate() {
+        let line = line_result.with_context(|| format!("Failed to read line {}", line_num + 1))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(previous) = last.replace(line) {
+            hasher.update_line(&previous);
+        }
+    }
+
+    let provenance = last
+        .as_deref()
+        .and_then(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|footer| footer.get("type").and_then(|t| t.as_str()) == Some("footer"))
+        .and_then(|mut footer| footer.get_mut("provenance").map(serde_json::Value::take))
+        .map(serde_json::from_value::<Provenance>)
+        .transpose()
+        .context("Invalid provenance block in footer")?;
+
+    Ok(match provenance {
+        Some(provenance) => Verification::check(provenance, hasher.finish()),
+        None => Verification::Unsigned,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_hasher_known_digest() {
+        let mut hasher = ContentHasher::new();
+        hasher.update_line("abc");
+        // sha256("abc\n")
+        assert_eq!(hasher.finish(), "edeaaff3f1774ad2888673770c6d64097e391bc362d7d6fb34982ddf0efd18cb");
+    }
+}
@@ -0,0 +1,172 @@
+//! Flame chart rendering logic
+//!
+//! Draws a [`FlameLayout`] as stacked bars, one depth row per overlapping
+//! child, with a clock scale at the bottom. Used by the details panel for
+//! thread records; zooming and panning are reported back as interactions and
+//! kept in a `MiniTimelineState` like the event timeline above it.
+
+use eframe::egui;
+use rjets::ThemeColors;
+
+use crate::domain::flame_chart::{FlameBar, FlameLayout};
+use crate::domain::viewport_operations;
+use crate::state::InputSettings;
+use crate::utils::format_clock;
+
+/// Height of one depth row
+const DEPTH_ROW_HEIGHT: f32 = 14.0;
+/// Height of the clock labels below the bars
+const SCALE_HEIGHT: f32 = 14.0;
+/// Depth rows shown at most; deeper bars are drawn clipped
+const MAX_VISIBLE_DEPTHS: usize = 24;
+/// Bars narrower than this are drawn at this width
+const MIN_BAR_WIDTH: f32 = 1.0;
+/// Bars at least this wide get a name label
+const LABEL_MIN_WIDTH: f32 = 40.0;
+
+/// Result of user interaction with the flame chart.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FlameChartInteraction {
+    /// User clicked a bar
+    BarClicked { record_id: u64 },
+    /// User zoomed with Ctrl + mouse wheel (>1 = zoom in)
+    Zoom { zoom_factor: f32, focus_clk: i64 },
+    /// User dragged the chart horizontally
+    Pan { delta_clk: i64 },
+    /// User double-clicked to show the whole extent again
+    Reset,
+}
+
+/// Returns the clock extent of a layout, widened so it can be drawn.
+pub fn flame_extent(layout: &FlameLayout) -> (i64, i64) {
+    let (start, end) = layout.extent;
+    if end <= start {
+        (start - 1, end + 1)
+    } else {
+        (start, end)
+    }
+}
+
+/// Renders a flame chart
+///
+/// # Arguments
+/// * `ui` - The egui UI context for drawing
+/// * `layout` - Bars packed into depth rows
+/// * `range` - Visible clock range `(start, end)`
+/// * `selected_record_id` - Currently selected record (highlighted if it is a bar)
+/// * `bar_label` - Returns the name and fill color for a bar's record
+/// * `theme_colors` - Color palette for the current theme
+/// * `input_settings` - Wheel orientation and zoom sensitivity
+///
+/// # Returns
+/// * `Option<FlameChartInteraction>` - User interaction result
+pub fn render_flame_chart(
+    ui: &mut egui::Ui,
+    layout: &FlameLayout,
+    range: (i64, i64),
+    selected_record_id: Option<u64>,
+    bar_label: impl Fn(u64) -> (String, egui::Color32),
+    theme_colors: &ThemeColors,
+    input_settings: &InputSettings,
+) -> Option<FlameChartInteraction> {
+    let (start_clk, end_clk) = range;
+    let depths = layout.depth_count.clamp(1, MAX_VISIBLE_DEPTHS);
+    let (rect, response) = ui.allocate_exact_size(
+        egui::vec2(ui.available_width(), depths as f32 * DEPTH_ROW_HEIGHT + SCALE_HEIGHT),
+        egui::Sense::click_and_drag(),
+    );
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, theme_colors.extreme_background);
+
+    let track = egui::Rect::from_min_max(rect.min, egui::pos2(rect.right(), rect.bottom() - SCALE_HEIGHT));
+    let to_x = |clk: i64| viewport_operations::clk_to_x(clk, start_clk, end_clk, track);
+    let label_font = egui::FontId::proportional(10.0);
+    let pointer = response.hover_pos();
+    let mut hovered: Option<&FlameBar> = None;
+
+    for bar in &layout.bars {
+        if bar.end < start_clk || bar.start > end_clk || bar.depth >= depths {
+            continue;
+        }
+        let left = to_x(bar.start).max(track.left());
+        let right = to_x(bar.end).min(track.right()).max(left + MIN_BAR_WIDTH);
+        let top = track.top() + bar.depth as f32 * DEPTH_ROW_HEIGHT;
+        let bar_rect = egui::Rect::from_min_max(egui::pos2(left, top + 1.0), egui::pos2(right, top + DEPTH_ROW_HEIGHT - 1.0));
+
+        let (name, color) = bar_label(bar.record_id);
+        let is_selected = selected_record_id == Some(bar.record_id);
+        painter.rect_filled(bar_rect, 1.0, if is_selected { theme_colors.blue } else { color });
+        if pointer.is_some_and(|p| bar_rect.contains(p)) {
+            hovered = Some(bar);
+            painter.rect_stroke(bar_rect, 1.0, egui::Stroke::new(1.0, theme_colors.text_strong), egui::StrokeKind::Inside);
+        }
+
+        if bar_rect.width() >= LABEL_MIN_WIDTH {
+            let text_color = rjets::contrast_text_color(color, theme_colors);
+            painter.with_clip_rect(bar_rect.shrink2(egui::vec2(2.0, 0.0))).text(
+                egui::pos2(bar_rect.left() + 3.0, bar_rect.center().y),
+                egui::Align2::LEFT_CENTER,
+                name,
+                label_font.clone(),
+                text_color,
+            );
+        }
+    }
+
+    // Range labels in the bottom corners
+    painter.text(
+        egui::pos2(rect.left() + 2.0, rect.bottom() - 2.0),
+        egui::Align2::LEFT_BOTTOM,
+        format_clock(start_clk),
+        label_font.clone(),
+        theme_colors.text_dim,
+    );
+    painter.text(
+        egui::pos2(rect.right() - 2.0, rect.bottom() - 2.0),
+        egui::Align2::RIGHT_BOTTOM,
+        format_clock(end_clk),
+        label_font,
+        theme_colors.text_dim,
+    );
+
+    if response.double_clicked() {
+        return Some(FlameChartInteraction::Reset);
+    }
+    if let Some(bar) = hovered {
+        if response.clicked() {
+            return Some(FlameChartInteraction::BarClicked { record_id: bar.record_id });
+        }
+        let (name, _) = bar_label(bar.record_id);
+        response.clone().on_hover_ui(|ui| {
+            ui.label(egui::RichText::new(name).strong());
+            ui.label(format!("Start: {}", format_clock(bar.start)));
+            ui.label(format!("Duration: {}", format_clock(bar.end - bar.start)));
+            ui.label(format!("Self time: {}", format_clock(bar.self_time)));
+            ui.label(format!("Depth: {}", bar.depth));
+        });
+    }
+
+    let clk_per_px = viewport_operations::clk_span(start_clk, end_clk) / track.width().max(1.0) as f64;
+    if response.dragged() {
+        // Float to integer `as` saturates, so a drag over a huge range cannot wrap
+        let delta_clk = (-response.drag_delta().x as f64 * clk_per_px) as i64;
+        if delta_clk != 0 {
+            return Some(FlameChartInteraction::Pan { delta_clk });
+        }
+    }
+    if response.hovered() {
+        let (ctrl, scroll_y) = ui.input(|i| {
+            let scroll_y = if i.raw_scroll_delta.y != 0.0 { i.raw_scroll_delta.y } else { i.smooth_scroll_delta.y };
+            (i.modifiers.ctrl, scroll_y)
+        });
+        if ctrl && scroll_y != 0.0 {
+            let focus_x = pointer.map_or(track.center().x, |p| p.x);
+            return Some(FlameChartInteraction::Zoom {
+                zoom_factor: input_settings.wheel_zoom_factor(scroll_y),
+                focus_clk: viewport_operations::x_to_clk(focus_x, start_clk, end_clk, track),
+            });
+        }
+    }
+
+    None
+}
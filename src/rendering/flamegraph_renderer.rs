@@ -0,0 +1,115 @@
+//! Flame graph rendering logic
+//!
+//! Draws a [`FlameGraph`] as an icicle: the root on the top row and each
+//! frame's children below it, widths proportional to their aggregated time.
+//! Clicking a frame zooms into it (its ancestors stay drawn full width above);
+//! double-clicking shows the whole graph again.
+
+use eframe::egui;
+use rjets::ThemeColors;
+
+use crate::domain::flame_graph::{FlameFrame, FlameGraph};
+use crate::utils::format_clock;
+
+/// Height of one depth row
+pub const FRAME_ROW_HEIGHT: f32 = 18.0;
+/// Frames narrower than this are not drawn
+const MIN_FRAME_WIDTH: f32 = 0.5;
+/// Frames at least this wide get a name label
+const LABEL_MIN_WIDTH: f32 = 30.0;
+
+/// Result of user interaction with the flame graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlameGraphInteraction {
+    /// User clicked the frame at this index of [`FlameGraph::frames`]
+    FrameClicked { index: usize },
+    /// User double-clicked to show the whole graph again
+    Reset,
+}
+
+/// Renders a flame graph
+///
+/// # Arguments
+/// * `ui` - The egui UI context for drawing
+/// * `graph` - Aggregated frames of the subtree
+/// * `focus` - Index of the frame zoomed into, if any
+/// * `frame_color` - Returns the fill color of a frame
+/// * `theme_colors` - Color palette for the current theme
+///
+/// # Returns
+/// * `Option<FlameGraphInteraction>` - User interaction result
+pub fn render_flame_graph(
+    ui: &mut egui::Ui,
+    graph: &FlameGraph,
+    focus: Option<usize>,
+    frame_color: impl Fn(&FlameFrame) -> egui::Color32,
+    theme_colors: &ThemeColors,
+) -> Option<FlameGraphInteraction> {
+    let (rect, response) = ui.allocate_exact_size(
+        egui::vec2(ui.available_width(), graph.depth_count.max(1) as f32 * FRAME_ROW_HEIGHT),
+        egui::Sense::click(),
+    );
+    let painter = ui.painter_at(rect);
+
+    // Fraction range of the root's width shown across the rect
+    let focus_frame = focus.and_then(|index| graph.frames.get(index)).filter(|frame| frame.width > 0.0);
+    let (range_start, range_width) = focus_frame.map_or((0.0, 1.0), |frame| (frame.offset, frame.width));
+    let focus_depth = focus_frame.map_or(0, |frame| frame.depth);
+    let to_x = |fraction: f64| rect.left() + ((fraction - range_start) / range_width) as f32 * rect.width();
+
+    let label_font = egui::FontId::proportional(11.0);
+    let pointer = response.hover_pos();
+    let mut hovered: Option<(usize, &FlameFrame)> = None;
+
+    for (index, frame) in graph.frames.iter().enumerate() {
+        // Frames above the focus are its ancestors and span the whole range
+        let (left, right) = if frame.depth < focus_depth {
+            (to_x(range_start), to_x(range_start + range_width))
+        } else {
+            (to_x(frame.offset), to_x(frame.offset + frame.width))
+        };
+        let (left, right) = (left.max(rect.left()), right.min(rect.right()));
+        if right - left < MIN_FRAME_WIDTH {
+            continue;
+        }
+        let top = rect.top() + frame.depth as f32 * FRAME_ROW_HEIGHT;
+        let frame_rect = egui::Rect::from_min_max(egui::pos2(left, top + 1.0), egui::pos2(right - 1.0, top + FRAME_ROW_HEIGHT - 1.0));
+
+        let color = frame_color(frame);
+        let color = if frame.depth < focus_depth { color.gamma_multiply(0.5) } else { color };
+        painter.rect_filled(frame_rect, 1.0, color);
+        if pointer.is_some_and(|p| frame_rect.contains(p)) {
+            hovered = Some((index, frame));
+            painter.rect_stroke(frame_rect, 1.0, egui::Stroke::new(1.0, theme_colors.text_strong), egui::StrokeKind::Inside);
+        }
+
+        if frame_rect.width() >= LABEL_MIN_WIDTH {
+            let text_color = rjets::contrast_text_color(color, theme_colors);
+            painter.with_clip_rect(frame_rect.shrink2(egui::vec2(2.0, 0.0))).text(
+                egui::pos2(frame_rect.left() + 3.0, frame_rect.center().y),
+                egui::Align2::LEFT_CENTER,
+                &frame.name,
+                label_font.clone(),
+                text_color,
+            );
+        }
+    }
+
+    if response.double_clicked() {
+        return Some(FlameGraphInteraction::Reset);
+    }
+    let (index, frame) = hovered?;
+    if response.clicked() {
+        return Some(FlameGraphInteraction::FrameClicked { index });
+    }
+    let total = graph.total();
+    response.on_hover_ui(|ui| {
+        ui.label(egui::RichText::new(&frame.name).strong());
+        let share = if total > 0 { 100.0 * frame.total as f64 / total as f64 } else { 0.0 };
+        ui.label(format!("Total: {} ({:.1}% of root)", format_clock(frame.total), share));
+        ui.label(format!("Self time: {}", format_clock(frame.self_time)));
+        ui.label(format!("Records: {}", frame.count));
+        ui.label(format!("Depth: {}", frame.depth));
+    });
+    None
+}
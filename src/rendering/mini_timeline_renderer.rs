@@ -0,0 +1,230 @@
+//! Mini event timeline rendering logic
+//!
+//! Draws one record's events on a small strip with their names and the clock
+//! distance between neighbours. Used by the details panel; zooming and panning
+//! are reported back as interactions and kept in `MiniTimelineState`.
+
+use eframe::egui;
+use rjets::{DynTraceRecord, ThemeColors, TraceEvent, TraceRecord};
+
+use crate::domain::event_visibility::EventTypeFilterStrategy;
+use crate::domain::viewport_operations;
+use crate::state::InputSettings;
+use crate::utils::format_clock;
+
+/// Total height of the mini timeline strip
+const STRIP_HEIGHT: f32 = 72.0;
+/// Vertical position of the event baseline inside the strip
+const BASELINE_Y: f32 = 38.0;
+/// Pixel radius within which a click or hover picks an event
+const PICK_RADIUS: f32 = 6.0;
+/// Gap kept between neighbouring labels on the same label row
+const LABEL_GAP: f32 = 6.0;
+
+/// Result of user interaction with the mini timeline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MiniTimelineInteraction {
+    /// User clicked an event marker
+    EventClicked { event_clk: i64 },
+    /// User zoomed with Ctrl + mouse wheel (>1 = zoom in)
+    Zoom { zoom_factor: f32, focus_clk: i64 },
+    /// User dragged the strip horizontally
+    Pan { delta_clk: i64 },
+    /// User double-clicked to show all events again
+    Reset,
+}
+
+/// Returns the clock extent covering a record and all of its events.
+///
+/// A zero-length extent is widened so it can still be drawn.
+pub fn event_extent(record: &DynTraceRecord) -> (i64, i64) {
+    let mut start = record.clk();
+    let mut end = record.end_clk().unwrap_or(start);
+    if let Some(first) = record.event_at(0) {
+        start = start.min(first.clk());
+    }
+    if let Some(last) = record.num_events().checked_sub(1).and_then(|i| record.event_at(i)) {
+        end = end.max(last.clk());
+    }
+    if end <= start {
+        (start - 1, end + 1)
+    } else {
+        (start, end)
+    }
+}
+
+/// Renders the mini timeline of a record's events
+///
+/// Event names are drawn on two alternating rows above the baseline and are
+/// skipped where they would overlap; the clock distance to the previous event
+/// is drawn below the baseline where it fits.
+///
+/// # Arguments
+/// * `ui` - The egui UI context for drawing
+/// * `record` - Record whose events are drawn
+/// * `range` - Visible clock range `(start, end)`
+/// * `selected_event_clk` - Clock of the selected event of this record (if any)
+/// * `event_filter` - Events hidden by name are not drawn
+/// * `theme_colors` - Color palette for the current theme
+/// * `input_settings` - Wheel orientation and zoom sensitivity
+///
+/// # Returns
+/// * `Option<MiniTimelineInteraction>` - User interaction result
+pub fn render_mini_timeline(
+    ui: &mut egui::Ui,
+    record: &DynTraceRecord,
+    range: (i64, i64),
+    selected_event_clk: Option<i64>,
+    event_filter: &EventTypeFilterStrategy,
+    theme_colors: &ThemeColors,
+    input_settings: &InputSettings,
+) -> Option<MiniTimelineInteraction> {
+    let (start_clk, end_clk) = range;
+    let (rect, response) = ui.allocate_exact_size(
+        egui::vec2(ui.available_width(), STRIP_HEIGHT),
+        egui::Sense::click_and_drag(),
+    );
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, theme_colors.extreme_background);
+
+    // Inset so markers at the range edges are not cut in half
+    let track = rect.shrink2(egui::vec2(PICK_RADIUS, 0.0));
+    let baseline = rect.top() + BASELINE_Y;
+    let to_x = |clk: i64| viewport_operations::clk_to_x(clk, start_clk, end_clk, track);
+
+    // Record span underneath the events
+    let span_end = record.end_clk().unwrap_or(end_clk);
+    let span_left = to_x(record.clk()).max(track.left());
+    let span_right = to_x(span_end).min(track.right());
+    if span_right > span_left {
+        painter.rect_filled(
+            egui::Rect::from_min_max(egui::pos2(span_left, baseline - 3.0), egui::pos2(span_right, baseline + 3.0)),
+            1.0,
+            rjets::with_alpha(theme_colors.gray, 120),
+        );
+    }
+    painter.line_segment(
+        [egui::pos2(track.left(), baseline), egui::pos2(track.right(), baseline)],
+        egui::Stroke::new(1.0, theme_colors.border),
+    );
+
+    let label_font = egui::FontId::proportional(10.0);
+    let pointer = response.hover_pos();
+    let mut hovered: Option<(i64, String, Option<i64>)> = None;
+    let mut label_row_right = [f32::NEG_INFINITY; 2];
+    let mut previous: Option<(i64, f32)> = None;
+    let mut next_row = 0;
+
+    for i in 0..record.num_events() {
+        let Some(event) = record.event_at(i).filter(|e| event_filter.include_event(e)) else { continue };
+        let clk = event.clk();
+        if clk < start_clk {
+            previous = Some((clk, to_x(clk)));
+            continue;
+        }
+        if clk > end_clk {
+            break;
+        }
+
+        let x = to_x(clk);
+        let is_selected = selected_event_clk == Some(clk);
+        let is_hovered = pointer.is_some_and(|p| (p.x - x).abs() <= PICK_RADIUS);
+
+        // Distance to the previous event, centred between the two markers
+        if let Some((prev_clk, prev_x)) = previous {
+            let delta = format!("+{}", format_clock(clk - prev_clk));
+            let galley = painter.layout_no_wrap(delta, label_font.clone(), theme_colors.text_dim);
+            let mid = (prev_x.max(track.left()) + x) / 2.0;
+            if galley.size().x + LABEL_GAP < x - prev_x.max(track.left()) {
+                painter.galley(egui::pos2(mid - galley.size().x / 2.0, baseline + 10.0), galley, theme_colors.text_dim);
+            }
+        }
+
+        // Event name on a label row with room for it (rows alternate);
+        // the selected or hovered event is always labeled
+        let galley = painter.layout_no_wrap(event.name(), label_font.clone(), theme_colors.text);
+        let label_left = x - galley.size().x / 2.0;
+        let label_row = [next_row, 1 - next_row]
+            .into_iter()
+            .find(|&r| label_left > label_row_right[r] + LABEL_GAP)
+            .or((is_selected || is_hovered).then_some(next_row));
+        if let Some(r) = label_row {
+            let y = rect.top() + 4.0 + r as f32 * 13.0;
+            label_row_right[r] = label_left + galley.size().x;
+            painter.line_segment(
+                [egui::pos2(x, y + 12.0), egui::pos2(x, baseline)],
+                egui::Stroke::new(1.0, rjets::with_alpha(theme_colors.border, 160)),
+            );
+            painter.galley(egui::pos2(label_left, y), galley, theme_colors.text);
+            next_row = 1 - r;
+        }
+
+        let radius = if is_selected { 5.0 } else { 3.5 };
+        let color = if is_selected { theme_colors.orange } else { theme_colors.yellow };
+        painter.circle_filled(egui::pos2(x, baseline), radius, color);
+        if is_selected || is_hovered {
+            painter.circle_stroke(egui::pos2(x, baseline), radius + 1.5, egui::Stroke::new(1.0, theme_colors.text_strong));
+        }
+
+        if is_hovered && hovered.is_none() {
+            hovered = Some((clk, event.name(), previous.map(|(prev_clk, _)| clk - prev_clk)));
+        }
+        previous = Some((clk, x));
+    }
+
+    // Range labels in the bottom corners
+    painter.text(
+        egui::pos2(rect.left() + 2.0, rect.bottom() - 2.0),
+        egui::Align2::LEFT_BOTTOM,
+        format_clock(start_clk),
+        label_font.clone(),
+        theme_colors.text_dim,
+    );
+    painter.text(
+        egui::pos2(rect.right() - 2.0, rect.bottom() - 2.0),
+        egui::Align2::RIGHT_BOTTOM,
+        format_clock(end_clk),
+        label_font,
+        theme_colors.text_dim,
+    );
+
+    if response.double_clicked() {
+        return Some(MiniTimelineInteraction::Reset);
+    }
+    if let Some((clk, name, delta)) = &hovered {
+        if response.clicked() {
+            return Some(MiniTimelineInteraction::EventClicked { event_clk: *clk });
+        }
+        response.clone().on_hover_ui(|ui| {
+            ui.label(name);
+            ui.label(format!("Clock: {}", format_clock(*clk)));
+            if let Some(delta) = delta {
+                ui.label(format!("Since previous: {}", format_clock(*delta)));
+            }
+        });
+    }
+
+    let clk_per_px = viewport_operations::clk_span(start_clk, end_clk) / track.width().max(1.0) as f64;
+    if response.dragged() {
+        // Float to integer `as` saturates, so a drag over a huge range cannot wrap
+        let delta_clk = (-response.drag_delta().x as f64 * clk_per_px) as i64;
+        if delta_clk != 0 {
+            return Some(MiniTimelineInteraction::Pan { delta_clk });
+        }
+    }
+    if response.hovered() {
+        let (ctrl, scroll_y) = ui.input(|i| {
+            let scroll_y = if i.raw_scroll_delta.y != 0.0 { i.raw_scroll_delta.y } else { i.smooth_scroll_delta.y };
+            (i.modifiers.ctrl, scroll_y)
+        });
+        if ctrl && scroll_y != 0.0 {
+            let focus_x = pointer.map_or(track.center().x, |p| p.x);
+            return Some(MiniTimelineInteraction::Zoom {
+                zoom_factor: input_settings.wheel_zoom_factor(scroll_y),
+                focus_clk: viewport_operations::x_to_clk(focus_x, start_clk, end_clk, track),
+            });
+        }
+    }
+
+    None
+}
@@ -5,10 +5,20 @@
 //! - Timeline row rendering (temporal view)
 //! - Time axis rendering (clock labels and tick marks)
 //! - Timeline overlays (cursor line, region selection)
+//! - Mini event timeline (details panel)
+//! - Flame chart of a thread's children (details panel)
+//! - Flame graph of the selected subtree (timeline panel mode)
+//! - Heat strip scrollbar (row overview next to the timeline)
+//! - Record tooltips (delay, verbosity, pinning)
 //! - Text utilities (text measurement and truncation)
 
 pub mod tree_renderer;
 pub mod timeline_renderer;
 pub mod time_axis_renderer;
 pub mod timeline_overlays;
+pub mod mini_timeline_renderer;
+pub mod flame_chart_renderer;
+pub mod flamegraph_renderer;
+pub mod scroll_heat_renderer;
+pub mod tooltip_renderer;
 pub mod text_utils;
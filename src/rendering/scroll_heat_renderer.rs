@@ -0,0 +1,82 @@
+//! Heat strip scrollbar rendering logic
+//!
+//! Draws the timeline's vertical scrollbar as an overview of the whole row
+//! list: colored ticks where marked rows sit and a thumb for the rows on
+//! screen. Clicking or dragging the strip reports the scroll offset to jump to.
+
+use eframe::egui;
+use rjets::ThemeColors;
+
+use crate::domain::scroll_heat::HeatMark;
+
+/// Width of the heat strip scrollbar
+pub const STRIP_WIDTH: f32 = 12.0;
+/// Minimum thumb height so it stays grabbable in very tall trees
+const MIN_THUMB_HEIGHT: f32 = 12.0;
+/// Height of one mark tick
+const TICK_HEIGHT: f32 = 2.0;
+
+/// Renders the heat strip scrollbar
+///
+/// # Arguments
+/// * `ui` - The egui UI context for drawing
+/// * `rect` - Area of the strip
+/// * `buckets` - One entry per strip pixel row: the mark to draw there, if any
+/// * `content_height` - Height of the whole row list in pixels
+/// * `scroll_y` - Current vertical scroll offset
+/// * `theme_colors` - Color palette for the current theme
+///
+/// # Returns
+/// * `Option<f32>` - Scroll offset to jump to, if the strip was clicked or dragged
+pub fn render_heat_scrollbar(
+    ui: &mut egui::Ui,
+    rect: egui::Rect,
+    buckets: &[Option<HeatMark>],
+    content_height: f32,
+    scroll_y: f32,
+    theme_colors: &ThemeColors,
+) -> Option<f32> {
+    let response = ui.interact(rect, ui.id().with("heat_scrollbar"), egui::Sense::click_and_drag());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, theme_colors.extreme_background);
+
+    for (i, mark) in buckets.iter().enumerate() {
+        let Some(mark) = mark else { continue };
+        let color = match mark {
+            HeatMark::Selected => theme_colors.blue,
+            HeatMark::Noted => theme_colors.yellow,
+            HeatMark::LongDuration => rjets::with_alpha(theme_colors.orange, 170),
+        };
+        let y = rect.top() + i as f32;
+        painter.rect_filled(
+            egui::Rect::from_min_max(egui::pos2(rect.left() + 1.0, y), egui::pos2(rect.right() - 1.0, y + TICK_HEIGHT)),
+            0.0,
+            color,
+        );
+    }
+
+    // Thumb covering the rows on screen
+    let viewport_height = rect.height();
+    let content_height = content_height.max(viewport_height);
+    let thumb_height = (viewport_height / content_height * rect.height()).max(MIN_THUMB_HEIGHT);
+    let max_scroll = content_height - viewport_height;
+    let scroll_fraction = if max_scroll > 0.0 { (scroll_y / max_scroll).clamp(0.0, 1.0) } else { 0.0 };
+    let thumb_top = rect.top() + scroll_fraction * (rect.height() - thumb_height);
+    let thumb = egui::Rect::from_min_size(egui::pos2(rect.left(), thumb_top), egui::vec2(rect.width(), thumb_height));
+    let thumb_alpha = if response.hovered() || response.dragged() { 70 } else { 40 };
+    painter.rect_filled(thumb, 2.0, rjets::with_alpha(theme_colors.text, thumb_alpha));
+    painter.rect_stroke(thumb, 2.0, egui::Stroke::new(1.0, theme_colors.border), egui::StrokeKind::Inside);
+
+    let jump = if response.is_pointer_button_down_on() {
+        // Centre the thumb on the pointer
+        response.interact_pointer_pos().map(|pos| {
+            let fraction = ((pos.y - rect.top() - thumb_height / 2.0) / (rect.height() - thumb_height).max(1.0)).clamp(0.0, 1.0);
+            fraction * max_scroll
+        })
+    } else {
+        None
+    };
+
+    response.on_hover_text("Overview: selected record (blue), notes (yellow), longest records (orange)");
+    jump
+}
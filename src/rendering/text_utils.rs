@@ -1,9 +1,19 @@
 //! Text rendering utilities
 //!
 //! Shared utilities for text measurement and truncation.
+//!
+//! Truncation measures with the font the text is drawn in, so it follows the
+//! tree text size chosen in the settings.
 
 use eframe::egui;
 
+/// Scales every text style of a UI style, e.g. to zoom the text of one panel.
+pub fn scale_text_styles(style: &mut egui::Style, scale: f32) {
+    for font_id in style.text_styles.values_mut() {
+        font_id.size *= scale;
+    }
+}
+
 /// Truncates text to fit within a given width, adding ".." if truncated
 ///
 /// # Arguments
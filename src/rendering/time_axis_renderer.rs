@@ -4,9 +4,29 @@
 //! clock value labels, and grid lines.
 
 use eframe::egui;
+use rjets::ThemeColors;
 use crate::domain::viewport_operations;
 use crate::utils::format_clock;
 
+/// Which side of the timeline body an axis sits on.
+///
+/// Ticks are drawn from the outer edge of the axis strip, labels towards the body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AxisEdge {
+    Top,
+    Bottom,
+}
+
+/// Returns the first major tick clock and the major tick interval for a range.
+fn major_ticks(viewport_start_clk: i64, viewport_end_clk: i64) -> Option<(i64, i64)> {
+    let visible_range = viewport_operations::clk_span(viewport_start_clk, viewport_end_clk);
+    if visible_range <= 0.0 {
+        return None;
+    }
+    let tick_interval = viewport_operations::next_power_of_10(visible_range / 10.0);
+    Some(((viewport_start_clk / tick_interval) * tick_interval, tick_interval))
+}
+
 /// Renders the time axis with major and minor tick marks and clock value labels
 ///
 /// # Arguments
@@ -14,11 +34,15 @@ use crate::utils::format_clock;
 /// * `canvas_rect` - The rectangular area to draw the time axis in
 /// * `viewport_start_clk` - Start of the visible time range
 /// * `viewport_end_clk` - End of the visible time range
+/// * `edge` - Side of the timeline body the axis is on
+/// * `theme_colors` - Color palette for the current theme (axis tick and label colors)
 pub fn render_time_axis(
     ui: &mut egui::Ui,
     canvas_rect: egui::Rect,
     viewport_start_clk: i64,
     viewport_end_clk: i64,
+    edge: AxisEdge,
+    theme_colors: &ThemeColors,
 ) {
     // Use the exact rect provided (24px from header allocation)
     let axis_rect = canvas_rect;
@@ -26,16 +50,22 @@ pub fn render_time_axis(
     ui.painter().rect_filled(
         axis_rect,
         0.0,
-        ui.visuals().extreme_bg_color,
+        theme_colors.extreme_background,
     );
 
-    let visible_range = (viewport_end_clk - viewport_start_clk) as f32;
-    if visible_range <= 0.0 {
+    let Some((first_tick, tick_interval)) = major_ticks(viewport_start_clk, viewport_end_clk) else {
         return;
-    }
+    };
 
-    let tick_interval = viewport_operations::next_power_of_10(visible_range / 10.0);
-    let first_tick = (viewport_start_clk / tick_interval) * tick_interval;
+    // Tick from the outer edge inwards: (edge y, direction towards the body)
+    let (outer_y, inward) = match edge {
+        AxisEdge::Top => (axis_rect.top(), 1.0),
+        AxisEdge::Bottom => (axis_rect.bottom(), -1.0),
+    };
+    let (label_y, label_align) = match edge {
+        AxisEdge::Top => (axis_rect.top() + 12.0, egui::Align2::CENTER_TOP),
+        AxisEdge::Bottom => (axis_rect.bottom() - 12.0, egui::Align2::CENTER_BOTTOM),
+    };
 
     let mut tick_clk = first_tick;
     while tick_clk <= viewport_end_clk {
@@ -44,37 +74,71 @@ pub fn render_time_axis(
         // Draw major tick line (scaled to fit 24px height)
         ui.painter().line_segment(
             [
-                egui::pos2(x, axis_rect.top()),
-                egui::pos2(x, axis_rect.top() + 8.0),
+                egui::pos2(x, outer_y),
+                egui::pos2(x, outer_y + 8.0 * inward),
             ],
-            egui::Stroke::new(2.0, ui.visuals().text_color()),
+            egui::Stroke::new(2.0, theme_colors.axis_tick),
         );
 
         // Draw label (centered vertically in available space)
         ui.painter().text(
-            egui::pos2(x, axis_rect.top() + 12.0),
-            egui::Align2::CENTER_TOP,
+            egui::pos2(x, label_y),
+            label_align,
             format_clock(tick_clk),
             egui::FontId::proportional(10.0),
-            ui.visuals().text_color(),
+            theme_colors.axis_label,
         );
 
         // Draw minor ticks (scaled to fit)
         for i in 1..5 {
-            let minor_clk = tick_clk + (tick_interval * i) / 5;
+            let minor_clk = tick_clk.saturating_add((tick_interval * i) / 5);
             if minor_clk > viewport_end_clk {
                 break;
             }
             let minor_x = viewport_operations::clk_to_x(minor_clk, viewport_start_clk, viewport_end_clk, canvas_rect);
             ui.painter().line_segment(
                 [
-                    egui::pos2(minor_x, axis_rect.top()),
-                    egui::pos2(minor_x, axis_rect.top() + 4.0),
+                    egui::pos2(minor_x, outer_y),
+                    egui::pos2(minor_x, outer_y + 4.0 * inward),
                 ],
-                egui::Stroke::new(1.0, ui.visuals().text_color().gamma_multiply(0.5)),
+                egui::Stroke::new(1.0, theme_colors.axis_tick.gamma_multiply(0.5)),
             );
         }
 
-        tick_clk += tick_interval;
+        // The last tick before i64::MAX ends the axis
+        let Some(next) = tick_clk.checked_add(tick_interval) else { break };
+        tick_clk = next;
+    }
+}
+
+/// Renders faint vertical gridlines at the major ticks across the timeline body.
+///
+/// # Arguments
+/// * `painter` - Painter for the timeline body (drawn beneath the rows)
+/// * `body_rect` - The timeline body area
+/// * `viewport_start_clk` - Start of the visible time range
+/// * `viewport_end_clk` - End of the visible time range
+/// * `theme_colors` - Color palette for the current theme
+pub fn render_gridlines(
+    painter: &egui::Painter,
+    body_rect: egui::Rect,
+    viewport_start_clk: i64,
+    viewport_end_clk: i64,
+    theme_colors: &ThemeColors,
+) {
+    let Some((first_tick, tick_interval)) = major_ticks(viewport_start_clk, viewport_end_clk) else {
+        return;
+    };
+    let stroke = egui::Stroke::new(1.0, theme_colors.axis_tick.gamma_multiply(0.12));
+
+    let mut tick_clk = first_tick;
+    while tick_clk <= viewport_end_clk {
+        let x = viewport_operations::clk_to_x(tick_clk, viewport_start_clk, viewport_end_clk, body_rect);
+        if x >= body_rect.left() {
+            painter.vline(x, body_rect.y_range(), stroke);
+        }
+        // The last tick before i64::MAX ends the axis
+        let Some(next) = tick_clk.checked_add(tick_interval) else { break };
+        tick_clk = next;
     }
 }
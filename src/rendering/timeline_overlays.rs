@@ -1,12 +1,15 @@
-//! Timeline overlay rendering for cursor line and region selection.
+//! Timeline overlay rendering for cursor line, region selection and capture bounds.
 //!
-//! This module handles the rendering of interactive overlays on the timeline:
+//! This module handles the rendering of overlays on the timeline:
 //! - Vertical cursor line with timestamp label
+//! - Ring around the event the cursor snaps to
 //! - Region selection rectangle for zoom-to-region
+//! - A/B measurement cursors with the interval between them
+//! - Trace extent and capture end markers
 
 use eframe::egui;
-use egui::Color32;
 use rjets::ThemeColors;
+use crate::domain::viewport_operations;
 use crate::utils::format_clock;
 
 /// Renders the vertical cursor line and timestamp label at the hover position.
@@ -37,14 +40,14 @@ pub fn render_cursor_overlay(
             egui::pos2(line_x, content_top),
             egui::pos2(line_x, content_bottom),
         ],
-        egui::Stroke::new(1.5, theme_colors.yellow),
+        egui::Stroke::new(1.5, theme_colors.cursor),
     );
 
     // Draw timestamp label at the bottom of the line
     let label_text = format_clock(hover_clk);
     let font_id = egui::FontId::proportional(12.0);
-    let label_color = theme_colors.yellow;
-    let bg_color = Color32::from_rgba_premultiplied(0, 0, 0, 200);
+    let label_color = theme_colors.cursor;
+    let bg_color = theme_colors.extreme_background.gamma_multiply(0.8);
 
     // Measure text size to create background box
     let galley = painter.layout_no_wrap(
@@ -75,6 +78,81 @@ pub fn render_cursor_overlay(
     );
 }
 
+/// Renders a ring around the event the cursor snaps to (Alt+click selects it).
+///
+/// # Arguments
+/// * `ctx` - The egui context for accessing the debug painter
+/// * `event_pos` - Screen position of the event on its row
+/// * `theme_colors` - The color palette for the current theme
+pub fn render_snap_marker(ctx: &egui::Context, event_pos: egui::Pos2, theme_colors: &ThemeColors) {
+    ctx.debug_painter().circle_stroke(event_pos, 6.0, egui::Stroke::new(1.5, theme_colors.cursor));
+}
+
+/// Renders the A/B measurement cursors, shading the interval between them
+/// and labeling it with its length and the records and events it intersects.
+///
+/// # Arguments
+/// * `ctx` - The egui context for accessing the debug painter
+/// * `scroll_rect` - The scrollable area rectangle for clipping
+/// * `viewport_start_clk` - Start of the visible time range
+/// * `viewport_end_clk` - End of the visible time range
+/// * `cursors` - Clocks of cursors A and B, if placed
+/// * `counts` - Records and events intersecting the interval, once counted
+/// * `theme_colors` - The color palette for the current theme
+pub fn render_measurement_overlay(
+    ctx: &egui::Context,
+    scroll_rect: egui::Rect,
+    viewport_start_clk: i64,
+    viewport_end_clk: i64,
+    cursors: (Option<i64>, Option<i64>),
+    counts: Option<(usize, usize)>,
+    theme_colors: &ThemeColors,
+) {
+    let painter = ctx.debug_painter().with_clip_rect(scroll_rect);
+    let to_x = |clk| viewport_operations::clk_to_x(clk, viewport_start_clk, viewport_end_clk, scroll_rect);
+    let color = theme_colors.selection_border;
+    let font_id = egui::FontId::proportional(11.0);
+
+    if let (Some(a), Some(b)) = cursors {
+        let (left, right) = (to_x(a.min(b)), to_x(a.max(b)));
+        let band = egui::Rect::from_x_y_ranges(left..=right, scroll_rect.y_range());
+        painter.rect_filled(band, 0.0, theme_colors.selection_fill.gamma_multiply(0.5));
+
+        let delta = a.abs_diff(b);
+        let mut label = format!(
+            "Δ {} clk",
+            i64::try_from(delta).map_or_else(|_| delta.to_string(), format_clock)
+        );
+        if let Some((records, events)) = counts {
+            label.push_str(&format!(" · {} records · {} events", records, events));
+        }
+        // Centered over the visible part of the interval
+        let center_x = (left.max(scroll_rect.left()) + right.min(scroll_rect.right())) / 2.0;
+        let galley = painter.layout_no_wrap(label, font_id.clone(), color);
+        let padding = egui::vec2(4.0, 2.0);
+        let label_rect = egui::Rect::from_center_size(
+            egui::pos2(center_x, scroll_rect.top() + galley.size().y / 2.0 + padding.y + 18.0),
+            galley.size() + padding * 2.0,
+        );
+        painter.rect_filled(label_rect, 2.0, theme_colors.extreme_background.gamma_multiply(0.8));
+        painter.rect_stroke(label_rect, 2.0, egui::Stroke::new(1.0, color), egui::StrokeKind::Outside);
+        painter.galley(label_rect.min + padding, galley, color);
+    }
+
+    for (clk, name) in [(cursors.0, "A"), (cursors.1, "B")] {
+        let Some(clk) = clk else { continue };
+        let x = to_x(clk);
+        painter.vline(x, scroll_rect.y_range(), egui::Stroke::new(1.5, color));
+        painter.text(
+            egui::pos2(x + 3.0, scroll_rect.top() + 2.0),
+            egui::Align2::LEFT_TOP,
+            name,
+            font_id.clone(),
+            color,
+        );
+    }
+}
+
 /// Renders the region selection overlay for zoom-to-region functionality.
 ///
 /// # Arguments
@@ -82,16 +160,25 @@ pub fn render_cursor_overlay(
 /// * `scroll_rect` - The scrollable area rectangle for clipping
 /// * `start_pos` - The start position of the selection
 /// * `current_pos` - The current cursor position
+/// * `span_rows` - Limit the rectangle to the dragged vertical extent
 /// * `theme_colors` - The color palette for the current theme
 pub fn render_region_selection_overlay(
     ctx: &egui::Context,
     scroll_rect: egui::Rect,
     start_pos: egui::Pos2,
     current_pos: egui::Pos2,
+    span_rows: bool,
     theme_colors: &ThemeColors,
 ) {
-    let content_top = scroll_rect.top();
-    let content_bottom = scroll_rect.bottom();
+    // Full height unless the vertical extent selects rows too
+    let (content_top, content_bottom) = if span_rows {
+        (
+            start_pos.y.min(current_pos.y).max(scroll_rect.top()),
+            start_pos.y.max(current_pos.y).min(scroll_rect.bottom()),
+        )
+    } else {
+        (scroll_rect.top(), scroll_rect.bottom())
+    };
 
     // Calculate the selection rectangle
     let left_x = start_pos.x.min(current_pos.x);
@@ -109,14 +196,73 @@ pub fn render_region_selection_overlay(
     painter.rect_filled(
         selection_rect,
         0.0,
-        rjets::with_alpha(theme_colors.blue, 80),
+        theme_colors.selection_fill,
     );
 
     // Draw border
     painter.rect_stroke(
         selection_rect,
         0.0,
-        egui::Stroke::new(2.0, theme_colors.blue),
+        egui::Stroke::new(2.0, theme_colors.selection_border),
         egui::StrokeKind::Outside,
     );
 }
+
+/// Renders the trace extent edges and the footer capture end as labeled
+/// vertical markers beneath the rows.
+///
+/// Time after the capture end (or after the trace extent, if there is no
+/// capture end) is shaded: no data was captured there, as opposed to an
+/// empty stretch inside the capture where nothing happened.
+///
+/// # Arguments
+/// * `painter` - Painter for the timeline body (drawn beneath the rows)
+/// * `body_rect` - The timeline body area
+/// * `viewport_start_clk` - Start of the visible time range
+/// * `viewport_end_clk` - End of the visible time range
+/// * `extent` - First and last clock of the trace data
+/// * `capture_end_clk` - Capture end from the trace footer, if any
+/// * `theme_colors` - The color palette for the current theme
+pub fn render_capture_bounds(
+    painter: &egui::Painter,
+    body_rect: egui::Rect,
+    viewport_start_clk: i64,
+    viewport_end_clk: i64,
+    extent: (i64, i64),
+    capture_end_clk: Option<i64>,
+    theme_colors: &ThemeColors,
+) {
+    let to_x = |clk| viewport_operations::clk_to_x(clk, viewport_start_clk, viewport_end_clk, body_rect);
+    let color = theme_colors.axis_tick;
+
+    let captured_until = capture_end_clk.unwrap_or(extent.1).max(extent.1);
+    if captured_until < viewport_end_clk {
+        let left = to_x(captured_until).max(body_rect.left());
+        let shaded = egui::Rect::from_min_max(egui::pos2(left, body_rect.top()), body_rect.max);
+        painter.rect_filled(shaded, 0.0, color.gamma_multiply(0.06));
+    }
+
+    let mut markers = vec![(extent.0, "trace start"), (extent.1, "trace end")];
+    if let Some(capture_end) = capture_end_clk {
+        markers.push((capture_end, "capture end"));
+    }
+    let font_id = egui::FontId::proportional(10.0);
+    for (index, (clk, label)) in markers.into_iter().enumerate() {
+        if clk < viewport_start_clk || clk > viewport_end_clk {
+            continue;
+        }
+        let x = to_x(clk);
+        painter.vline(x, body_rect.y_range(), egui::Stroke::new(1.0, color.gamma_multiply(0.35)));
+        // Labels for nearby markers are stacked rather than drawn over each other
+        let y = body_rect.top() + 2.0 + index as f32 * 12.0;
+        let align = if clk == extent.0 { egui::Align2::LEFT_TOP } else { egui::Align2::RIGHT_TOP };
+        let offset = if clk == extent.0 { 3.0 } else { -3.0 };
+        painter.text(
+            egui::pos2(x + offset, y),
+            align,
+            format!("{} {}", label, format_clock(clk)),
+            font_id.clone(),
+            color.gamma_multiply(0.7),
+        );
+    }
+}
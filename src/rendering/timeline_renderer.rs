@@ -5,10 +5,23 @@
 
 use eframe::egui;
 use egui::Color32;
-use rjets::{ThemeColors, DynTraceData, TraceData, TraceRecord, TraceEvent};
+use rjets::{AttributeAccessor, ThemeColors, DynTraceData, DynTraceRecord, TraceData, TraceRecord, TraceEvent};
+use std::collections::HashMap;
 
 use crate::ui::virtual_scrolling::ROW_HEIGHT;
+use crate::domain::name_aliases::NameAliases;
+use crate::domain::counter_track;
+use crate::domain::dependencies::DependencyArrow;
+use crate::domain::event_density::{self, PixelBuckets};
+use crate::domain::event_markers::{self, MarkerShape};
 use crate::domain::viewport_operations;
+use crate::domain::event_visibility::EventTypeFilterStrategy;
+use crate::domain::severity::{event_severity, Severity};
+use crate::domain::type_hints::{EventRenderMode, TypeHints};
+use crate::presentation::color_mapping;
+use crate::rendering::{text_utils, tooltip_renderer};
+use crate::state::{ClickGesture, InputSettings, TooltipState, ValueTrack};
+use crate::ui::accessibility;
 use crate::utils::format_clock;
 
 /// Renders a single timeline row with bars and event markers
@@ -21,12 +34,19 @@ use crate::utils::format_clock;
 /// * `viewport_end_clk` - End of the visible time range
 /// * `selected_record_id` - Currently selected record ID (if any)
 /// * `selected_event` - Currently selected event (record_id, clk) tuple (if any)
+/// * `event_filter` - Events hidden by name or below the minimum severity are not drawn
+/// * `value_tracks` - Per record_type value tracks; bar height follows the attribute
+/// * `type_hints` - Per record_type label templates and event modes from the trace header
+/// * `event_shapes` - Marker shapes chosen per event name
 /// * `is_dragging` - Whether the timeline is currently being dragged
 /// * `theme_colors` - Color palette for the current theme
-/// * `get_record_color_fn` - Function to compute color for a record by name
+/// * `get_record_color_fn` - Function to compute color for a record by name and record type
+/// * `tooltip` - Record tooltip controller
+/// * `input_settings` - Tooltip delay and verbosity, name aliases
 ///
 /// # Returns
 /// * `Option<TimelineRowInteraction>` - User interaction result (bar click, event click)
+#[allow(clippy::too_many_arguments)]
 pub fn render_timeline_row<F>(
     ui: &mut egui::Ui,
     trace: &DynTraceData,
@@ -35,17 +55,20 @@ pub fn render_timeline_row<F>(
     viewport_end_clk: i64,
     selected_record_id: Option<u64>,
     selected_event: Option<(u64, i64)>,
+    event_filter: &EventTypeFilterStrategy,
+    value_tracks: &HashMap<String, ValueTrack>,
+    type_hints: &TypeHints,
+    event_shapes: &HashMap<String, MarkerShape>,
     is_dragging: bool,
     theme_colors: &ThemeColors,
     get_record_color_fn: F,
+    tooltip: &mut TooltipState,
+    input_settings: &InputSettings,
 ) -> Option<TimelineRowInteraction>
 where
-    F: Fn(&str) -> Color32,
+    F: Fn(&str, &str) -> Color32,
 {
-    let record = match trace.get_record(record_id) {
-        Some(r) => r,
-        None => return None,
-    };
+    let record = trace.get_record(record_id)?;
 
     let start_y = ui.cursor().min.y;
 
@@ -82,13 +105,32 @@ where
         );
 
         let is_selected = selected_record_id == Some(record_id);
+        let record_type = record.record_type();
         let bar_color = if is_selected {
             theme_colors.blue
         } else {
-            get_record_color_fn(&record.name())
+            get_record_color_fn(&record.name(), &record_type)
+        };
+
+        // A value track draws a faint full-height lane and fills only the
+        // fraction given by the attribute, bottom-aligned
+        let value_fraction = value_tracks
+            .get(&record_type)
+            .and_then(|track| track.fraction(&record.attr(&track.attribute)?));
+        let fill_rect = match value_fraction {
+            Some(fraction) => {
+                ui.painter().rect_filled(bar_rect, 2.0, bar_color.gamma_multiply(0.15));
+                let height = (ROW_HEIGHT * fraction).max(2.0);
+                egui::Rect::from_min_max(egui::pos2(bar_rect.min.x, bar_rect.max.y - height), bar_rect.max)
+            }
+            None => bar_rect,
         };
 
-        ui.painter().rect_filled(bar_rect, 2.0, bar_color);
+        if record.end_inferred() {
+            paint_inferred_bar(ui.painter(), fill_rect, bar_color);
+        } else {
+            ui.painter().rect_filled(fill_rect, 2.0, bar_color);
+        }
 
         if is_selected {
             ui.painter().rect_stroke(bar_rect, 2.0, egui::Stroke::new(2.0, rjets::adjust_brightness(theme_colors.blue, 1.2)), egui::StrokeKind::Outside);
@@ -98,12 +140,27 @@ where
         // Use hover sense to allow drag gestures to pass through to canvas layer
         let bar_id = ui.id().with(format!("bar_select_{}", record_id));
         let bar_response = ui.interact(bar_rect, bar_id, egui::Sense::hover());
+        accessibility::annotate_timeline_bar(
+            &bar_response,
+            || accessibility::record_label(&record.name(), &record_type, record.duration()),
+            record.clk(),
+            is_selected,
+        );
 
         // Manually detect clicks: pointer is over bar AND was clicked (not dragging)
         let pointer_over_bar = bar_response.hovered();
-        let pointer_clicked = ui.input(|i| i.pointer.primary_clicked());
-
-        if pointer_over_bar && pointer_clicked && !is_dragging {
+        let (pointer_clicked, double_clicked, ctrl_held) = ui.input(|i| {
+            (
+                i.pointer.primary_clicked(),
+                i.pointer.button_double_clicked(egui::PointerButton::Primary),
+                i.modifiers.ctrl,
+            )
+        });
+
+        if pointer_over_bar && (double_clicked || (pointer_clicked && ctrl_held)) && !is_dragging {
+            let gesture = if double_clicked { ClickGesture::DoubleClick } else { ClickGesture::CtrlClick };
+            interaction = Some(TimelineRowInteraction::Gesture { record_id, gesture });
+        } else if pointer_over_bar && pointer_clicked && !is_dragging {
             let was_already_selected = selected_record_id == Some(record_id);
             let first_event_clk = if record.num_events() > 0 {
                 record.event_at(0).map(|e| e.clk())
@@ -118,16 +175,28 @@ where
             });
         }
 
-        // Handle hover tooltip (only when not dragging)
-        if bar_response.hovered() && !is_dragging {
-            bar_response.on_hover_ui(|ui| {
-                ui.label(format!("{}", record.name()));
-                ui.label(format!("Start: {}", format_clock(start_clk)));
-                if let Some(end) = record.end_clk() {
-                    ui.label(format!("End: {}", format_clock(end)));
-                    ui.label(format!("Duration: {}", format_clock(end - start_clk)));
-                }
-            });
+        // Counter records plot their samples instead of event markers
+        if rjets::is_counter(&record) {
+            let row_rect = egui::Rect::from_min_max(
+                egui::pos2(canvas_rect.min.x, start_y),
+                egui::pos2(canvas_rect.max.x, start_y + ROW_HEIGHT)
+            );
+            render_counter_track(ui, &record, bar_rect, row_rect, viewport_start_clk, viewport_end_clk, bar_color);
+
+            if bar_response.hovered() && !is_dragging {
+                let sample = bar_response.hover_pos().and_then(|pointer| {
+                    let clk = viewport_operations::x_to_clk(pointer.x, viewport_start_clk, viewport_end_clk, row_rect);
+                    let event = record.event_at(counter_track::sample_at(&record, clk)?)?;
+                    Some((event.clk(), rjets::counter_value(&event)?))
+                });
+                tooltip_renderer::show_record_tooltip(&bar_response, tooltip, input_settings, &record, |ui| {
+                    if let Some((clk, value)) = sample {
+                        ui.separator();
+                        ui.label(format!("Value: {} (since {})", value, format_clock(clk)));
+                    }
+                });
+            }
+            return interaction;
         }
 
         // Draw event markers with binary search optimization
@@ -150,6 +219,79 @@ where
         }
         let first_visible_idx = left;
 
+        // Too many events for individual markers: draw a per-pixel histogram instead
+        let row_rect = egui::Rect::from_min_max(
+            egui::pos2(canvas_rect.min.x, start_y),
+            egui::pos2(canvas_rect.max.x, start_y + ROW_HEIGHT)
+        );
+        let visible_bar = bar_rect.intersect(row_rect);
+        let name = input_settings.display_name(&record.name()).into_owned();
+        let label = type_hints.label(&record, &name).unwrap_or(name);
+        render_bar_label(ui, &record, &label, visible_bar, bar_color, theme_colors);
+
+        let event_mode = type_hints.event_mode(&record_type);
+        let visible_events = count_events_until(&record, first_visible_idx, viewport_end_clk);
+        let density = (visible_bar.width() > 0.0
+            && event_mode != EventRenderMode::Hidden
+            && (event_mode == EventRenderMode::Histogram
+                || event_density::is_too_dense(visible_events, visible_bar.width())))
+        .then(|| {
+            let events = (first_visible_idx..first_visible_idx + visible_events)
+                .filter_map(|i| record.event_at(i).filter(|e| event_filter.include_event(e)).map(|e| (i, e)));
+            let clock_range = (
+                viewport_operations::x_to_clk(visible_bar.min.x, viewport_start_clk, viewport_end_clk, row_rect),
+                viewport_operations::x_to_clk(visible_bar.max.x, viewport_start_clk, viewport_end_clk, row_rect),
+            );
+            PixelBuckets::from_events(events, clock_range, visible_bar.min.x, visible_bar.max.x, HISTOGRAM_BUCKET_PX)
+        });
+
+        let hovered_bucket = density.as_ref().and_then(|buckets| {
+            let pointer = bar_response.hover_pos()?;
+            let index = buckets.bucket_at(pointer.x)?;
+            (buckets.buckets[index].count > 0).then_some(index)
+        });
+
+        // Handle hover tooltip (only when not dragging)
+        if bar_response.hovered() && !is_dragging {
+            let bucket_info = hovered_bucket.zip(density.as_ref()).map(|(index, buckets)| {
+                let (left_x, right_x) = buckets.bucket_span(index);
+                let from = viewport_operations::x_to_clk(left_x, viewport_start_clk, viewport_end_clk, row_rect);
+                let to = viewport_operations::x_to_clk(right_x, viewport_start_clk, viewport_end_clk, row_rect);
+                (buckets.buckets[index].count, from, to)
+            });
+            tooltip_renderer::show_record_tooltip(&bar_response, tooltip, input_settings, &record, |ui| {
+                if let Some((count, from, to)) = bucket_info {
+                    ui.separator();
+                    ui.label(format!("{} events in {}..{}", count, format_clock(from), format_clock(to)));
+                }
+            });
+        }
+
+        if event_mode == EventRenderMode::Hidden {
+            return interaction;
+        }
+
+        if let Some(buckets) = &density {
+            render_event_histogram(ui, &record, buckets, visible_bar, hovered_bucket, selected_event, theme_colors);
+
+            // Clicking a column selects its first event
+            if let Some(index) = hovered_bucket {
+                if pointer_clicked && !is_dragging {
+                    if let Some(event) = record.event_at(buckets.buckets[index].first_index) {
+                        interaction = Some(TimelineRowInteraction::EventClicked {
+                            record_id,
+                            event_clk: event.clk(),
+                        });
+                    }
+                }
+            }
+            return interaction;
+        }
+
+        // Only markers near the pointer register widgets (see marker_is_interactive)
+        let pointer = ui.input(|i| i.pointer.hover_pos()).map(|p| (p.x, p.y));
+        let clip_rect = ui.clip_rect();
+
         // Render only visible events
         for i in first_visible_idx..num_events {
             let event = match record.event_at(i) {
@@ -162,6 +304,10 @@ where
             if event_clk > viewport_end_clk {
                 break;
             }
+            if !event_filter.include_event(&event) {
+                continue;
+            }
+            let severity = event_severity(&event);
 
             let x = viewport_operations::clk_to_x(event_clk, viewport_start_clk, viewport_end_clk, egui::Rect::from_min_max(
                 egui::pos2(canvas_rect.min.x, start_y),
@@ -179,27 +325,28 @@ where
                 egui::vec2(marker_radius * 2.0, marker_radius * 2.0)
             );
 
-            let marker_id = ui.id().with(format!("event_marker_{}_{}", record_id, event_clk));
-            let marker_response = ui.interact(marker_rect, marker_id, egui::Sense::hover());
-
-            // Manually detect clicks: pointer is over marker AND was clicked (not dragging)
-            let pointer_over_marker = marker_response.hovered();
-            let pointer_clicked = ui.input(|i| i.pointer.primary_clicked());
-
-            if pointer_over_marker && pointer_clicked && !is_dragging {
-                interaction = Some(TimelineRowInteraction::EventClicked {
-                    record_id,
-                    event_clk,
-                });
+            let visible_width = marker_rect.intersect(clip_rect).width().max(0.0);
+            if event_density::marker_is_interactive((x, marker_pos.y), marker_radius, visible_width, pointer) {
+                let marker_id = ui.id().with(format!("event_marker_{}_{}", record_id, event_clk));
+                let marker_response = ui.interact(marker_rect, marker_id, egui::Sense::hover());
+
+                // Manually detect clicks: pointer is over marker AND was clicked (not dragging)
+                if marker_response.hovered() && pointer_clicked && !is_dragging {
+                    interaction = Some(TimelineRowInteraction::EventClicked {
+                        record_id,
+                        event_clk,
+                    });
+                }
             }
 
-            // Draw the event circle
+            // Draw the event marker, shaped and colored by severity (info events by their shape)
             let event_color = if is_event_selected {
                 theme_colors.red // Red fill when selected
             } else {
-                theme_colors.yellow
+                color_mapping::severity_color(severity, theme_colors)
             };
-            ui.painter().circle_filled(marker_pos, marker_radius, event_color);
+            let shape = event_markers::event_shape(&event, event_shapes);
+            paint_event_marker(ui.painter(), marker_pos, marker_radius, severity, shape, event_color);
 
             // Draw selection ring for selected events
             if is_event_selected {
@@ -215,6 +362,425 @@ where
     interaction
 }
 
+/// Draws a counter record as a step line inside its bar.
+///
+/// The bar itself is drawn faintly as the plot background; the value axis
+/// spans the samples in view.
+fn render_counter_track(
+    ui: &egui::Ui,
+    record: &DynTraceRecord,
+    bar_rect: egui::Rect,
+    row_rect: egui::Rect,
+    viewport_start_clk: i64,
+    viewport_end_clk: i64,
+    color: Color32,
+) {
+    let visible_bar = bar_rect.intersect(row_rect);
+    if visible_bar.width() <= 0.0 {
+        return;
+    }
+    let painter = ui.painter().with_clip_rect(visible_bar);
+    painter.rect_filled(bar_rect, 2.0, color.gamma_multiply(0.15));
+
+    let columns = counter_track::visible_columns(record, viewport_start_clk, viewport_end_clk, |clk| {
+        viewport_operations::clk_to_x(clk, viewport_start_clk, viewport_end_clk, row_rect)
+    });
+    let Some(range) = counter_track::value_range(&columns) else {
+        return;
+    };
+    let plot = bar_rect.shrink2(egui::vec2(0.0, 2.0));
+    let points = counter_track::step_points(&columns, bar_rect.max.x, range, plot.min.y, plot.max.y)
+        .into_iter()
+        .map(|(x, y)| egui::pos2(x, y))
+        .collect();
+    painter.add(egui::Shape::line(points, egui::Stroke::new(1.5, color)));
+}
+
+/// Draws an event marker: `shape` for info, a triangle for warnings and a
+/// diamond for errors, so levels differ in shape as well as color.
+fn paint_event_marker(
+    painter: &egui::Painter,
+    center: egui::Pos2,
+    radius: f32,
+    severity: Severity,
+    shape: MarkerShape,
+    color: Color32,
+) {
+    let points = match (severity, shape) {
+        (Severity::Info, MarkerShape::Circle) => {
+            painter.circle_filled(center, radius, color);
+            return;
+        }
+        (Severity::Info, MarkerShape::Square) => {
+            painter.rect_filled(egui::Rect::from_center_size(center, egui::vec2(radius * 1.7, radius * 1.7)), 0.0, color);
+            return;
+        }
+        (Severity::Info, MarkerShape::Tick) => {
+            painter.line_segment(
+                [center + egui::vec2(0.0, -radius * 1.6), center + egui::vec2(0.0, radius * 1.6)],
+                egui::Stroke::new(2.0, color),
+            );
+            return;
+        }
+        (Severity::Info, MarkerShape::Diamond) => vec![
+            center + egui::vec2(0.0, -radius * 1.15),
+            center + egui::vec2(radius * 0.9, 0.0),
+            center + egui::vec2(0.0, radius * 1.15),
+            center + egui::vec2(-radius * 0.9, 0.0),
+        ],
+        (Severity::Warning, _) => vec![
+            center + egui::vec2(0.0, -radius * 1.15),
+            center + egui::vec2(radius * 1.1, radius * 0.8),
+            center + egui::vec2(-radius * 1.1, radius * 0.8),
+        ],
+        (Severity::Error, _) => vec![
+            center + egui::vec2(0.0, -radius * 1.25),
+            center + egui::vec2(radius * 1.25, 0.0),
+            center + egui::vec2(0.0, radius * 1.25),
+            center + egui::vec2(-radius * 1.25, 0.0),
+        ],
+    };
+    painter.add(egui::Shape::convex_polygon(points, color, egui::Stroke::NONE));
+}
+
+/// Width of one histogram column in pixels.
+const HISTOGRAM_BUCKET_PX: f32 = 2.0;
+
+/// Minimum visible bar width (in pixels) before a label is drawn inside it.
+const BAR_LABEL_MIN_WIDTH: f32 = 24.0;
+
+/// Draws the record name inside the visible part of its bar.
+///
+/// The duration is appended when the bar is wide enough for both; otherwise
+/// the name alone is shortened with an ellipsis. Text color is chosen for
+/// contrast against the bar fill.
+fn render_bar_label(
+    ui: &egui::Ui,
+    record: &DynTraceRecord,
+    name: &str,
+    visible_bar: egui::Rect,
+    bar_color: Color32,
+    theme_colors: &ThemeColors,
+) {
+    if visible_bar.width() < BAR_LABEL_MIN_WIDTH {
+        return;
+    }
+
+    let painter = ui.painter().with_clip_rect(visible_bar);
+    let font_id = egui::FontId::proportional(11.0);
+
+    let mut label = match record.end_clk() {
+        Some(end) => {
+            let approx = if record.end_inferred() { "~" } else { "" };
+            let with_duration = format!("{} ({}{})", name, approx, format_clock(end - record.clk()));
+            text_utils::truncate_text_to_fit(&with_duration, visible_bar.width(), &font_id, &painter)
+        }
+        None => String::new(),
+    };
+    if label.is_empty() || label.ends_with("..") {
+        label = text_utils::truncate_text_to_fit(name, visible_bar.width(), &font_id, &painter);
+    }
+    if label.is_empty() {
+        return;
+    }
+
+    painter.text(
+        egui::pos2(visible_bar.min.x + 4.0, visible_bar.center().y),
+        egui::Align2::LEFT_CENTER,
+        label,
+        font_id,
+        rjets::contrast_text_color(bar_color, theme_colors),
+    );
+}
+
+/// Paints a bar whose end was inferred: a faded fill with a dashed outline.
+fn paint_inferred_bar(painter: &egui::Painter, bar_rect: egui::Rect, bar_color: Color32) {
+    painter.rect_filled(bar_rect, 2.0, bar_color.gamma_multiply(0.45));
+    let outline = [
+        bar_rect.left_top(),
+        bar_rect.right_top(),
+        bar_rect.right_bottom(),
+        bar_rect.left_bottom(),
+        bar_rect.left_top(),
+    ];
+    painter.extend(egui::Shape::dashed_line(&outline, egui::Stroke::new(1.0, bar_color), 4.0, 3.0));
+}
+
+/// Counts events from `first_idx` onward with clock at or before `end_clk`.
+fn count_events_until(record: &DynTraceRecord, first_idx: usize, end_clk: i64) -> usize {
+    // Binary search for the first event past end_clk
+    let mut left = first_idx;
+    let mut right = record.num_events();
+    while left < right {
+        let mid = left + (right - left) / 2;
+        match record.event_at(mid) {
+            Some(event) if event.clk() <= end_clk => left = mid + 1,
+            Some(_) => right = mid,
+            None => break,
+        }
+    }
+    left - first_idx
+}
+
+/// Draws a row's events as a histogram strip inside its bar.
+///
+/// Column height is proportional to the number of events in that pixel
+/// column, so bursts stand out from quiet periods. The column holding the
+/// selected event is drawn in the selection color.
+fn render_event_histogram(
+    ui: &egui::Ui,
+    record: &DynTraceRecord,
+    buckets: &PixelBuckets,
+    bar_rect: egui::Rect,
+    hovered_bucket: Option<usize>,
+    selected_event: Option<(u64, i64)>,
+    theme_colors: &ThemeColors,
+) {
+    let max_count = buckets.max_count();
+    if max_count == 0 {
+        return;
+    }
+
+    let strip = bar_rect.shrink2(egui::vec2(0.0, 3.0));
+    let painter = ui.painter();
+    let selected_clk = selected_event
+        .filter(|(id, _)| *id == record.id())
+        .map(|(_, clk)| clk);
+
+    for (index, bucket) in buckets.buckets.iter().enumerate() {
+        if bucket.count == 0 {
+            continue;
+        }
+        let (left, right) = buckets.bucket_span(index);
+        let height = (strip.height() * bucket.count as f32 / max_count as f32).max(2.0);
+        let column = egui::Rect::from_min_max(
+            egui::pos2(left, strip.max.y - height),
+            egui::pos2(right.min(strip.max.x), strip.max.y),
+        );
+
+        let holds_selection = selected_clk.is_some_and(|clk| {
+            (bucket.first_index..bucket.first_index + bucket.count as usize)
+                .any(|i| record.event_at(i).is_some_and(|e| e.clk() == clk))
+        });
+        let color = if holds_selection {
+            theme_colors.red
+        } else if hovered_bucket == Some(index) {
+            rjets::adjust_brightness(theme_colors.yellow, 1.3)
+        } else {
+            theme_colors.yellow
+        };
+        painter.rect_filled(column, 0.0, color);
+    }
+}
+
+/// Renders a root record as a lane header strip spanning the timeline width.
+///
+/// The strip shows the collapse state, root name and child count pinned to
+/// the left edge, plus a thin band marking the root's clock extent. Clicking
+/// the strip toggles the lane open or closed.
+///
+/// # Arguments
+/// * `ui` - The egui UI context for drawing
+/// * `trace` - The trace data containing the record
+/// * `record_id` - ID of the root record
+/// * `viewport_start_clk` - Start of the visible time range
+/// * `viewport_end_clk` - End of the visible time range
+/// * `is_expanded` - Whether the lane is currently expanded
+/// * `is_dragging` - Whether the timeline is currently being dragged
+/// * `theme_colors` - Color palette for the current theme
+/// * `lane_color` - Accent color for the extent band
+/// * `name_aliases` - Rewrites applied to the root name
+#[allow(clippy::too_many_arguments)]
+pub fn render_lane_header(
+    ui: &mut egui::Ui,
+    trace: &DynTraceData,
+    record_id: u64,
+    viewport_start_clk: i64,
+    viewport_end_clk: i64,
+    is_expanded: bool,
+    is_dragging: bool,
+    theme_colors: &ThemeColors,
+    lane_color: Color32,
+    name_aliases: &NameAliases,
+) -> Option<TimelineRowInteraction> {
+    let record = trace.get_record(record_id)?;
+
+    let icon = if is_expanded { "⏷" } else { "⏵" };
+    let label = format!("{} {}  ({} children)", icon, name_aliases.apply(&record.name()), record.num_children());
+    let extent = (record.clk(), record.end_clk().unwrap_or(viewport_end_clk));
+
+    let clicked = render_strip(
+        ui,
+        egui::Id::new(("lane_header", record_id)),
+        &label,
+        Some(extent),
+        viewport_start_clk,
+        viewport_end_clk,
+        is_dragging,
+        theme_colors,
+        lane_color,
+    );
+    clicked.then_some(TimelineRowInteraction::LaneToggled {
+        record_id,
+        was_expanded: is_expanded,
+    })
+}
+
+/// Renders an attribute swimlane header strip spanning the timeline width.
+///
+/// Same look as a root lane header; the band covers the clock extent of all
+/// records in the lane. Clicking the strip collapses or expands the lane.
+///
+/// # Arguments
+/// * `ui` - The egui UI context for drawing
+/// * `key` - Attribute value identifying the lane
+/// * `count` - Number of records in the lane
+/// * `extent` - Clock extent of the lane's records
+/// * `viewport_start_clk` - Start of the visible time range
+/// * `viewport_end_clk` - End of the visible time range
+/// * `collapsed` - Whether the lane is currently collapsed
+/// * `is_dragging` - Whether the timeline is currently being dragged
+/// * `theme_colors` - Color palette for the current theme
+/// * `lane_color` - Accent color for the extent band
+#[allow(clippy::too_many_arguments)]
+pub fn render_swimlane_header(
+    ui: &mut egui::Ui,
+    key: &str,
+    count: usize,
+    extent: Option<(i64, i64)>,
+    viewport_start_clk: i64,
+    viewport_end_clk: i64,
+    collapsed: bool,
+    is_dragging: bool,
+    theme_colors: &ThemeColors,
+    lane_color: Color32,
+) -> Option<TimelineRowInteraction> {
+    let icon = if collapsed { "⏵" } else { "⏷" };
+    let label = format!("{} {}  ({} records)", icon, key, count);
+
+    let clicked = render_strip(
+        ui,
+        egui::Id::new(("swimlane_header", key)),
+        &label,
+        extent,
+        viewport_start_clk,
+        viewport_end_clk,
+        is_dragging,
+        theme_colors,
+        lane_color,
+    );
+    clicked.then(|| TimelineRowInteraction::SwimlaneToggled { key: key.to_string() })
+}
+
+/// Paints a lane header strip and returns true if it was clicked.
+#[allow(clippy::too_many_arguments)]
+fn render_strip(
+    ui: &mut egui::Ui,
+    id: egui::Id,
+    label: &str,
+    extent: Option<(i64, i64)>,
+    viewport_start_clk: i64,
+    viewport_end_clk: i64,
+    is_dragging: bool,
+    theme_colors: &ThemeColors,
+    lane_color: Color32,
+) -> bool {
+    let (row_rect, _) = ui.allocate_exact_size(
+        egui::vec2(ui.available_width(), ROW_HEIGHT),
+        egui::Sense::hover()
+    );
+    let painter = ui.painter();
+
+    painter.rect_filled(row_rect, 0.0, theme_colors.panel_background);
+    painter.line_segment(
+        [row_rect.left_top(), row_rect.right_top()],
+        egui::Stroke::new(1.0, theme_colors.border),
+    );
+
+    // Clock extent of the whole lane as a thin band along the bottom edge
+    if let Some((start_clk, end_clk)) = extent {
+        let x_start = viewport_operations::clk_to_x(start_clk, viewport_start_clk, viewport_end_clk, row_rect)
+            .max(row_rect.min.x);
+        let x_end = viewport_operations::clk_to_x(end_clk, viewport_start_clk, viewport_end_clk, row_rect)
+            .min(row_rect.max.x);
+        if x_end > x_start {
+            let band = egui::Rect::from_min_max(
+                egui::pos2(x_start, row_rect.max.y - 4.0),
+                egui::pos2(x_end, row_rect.max.y - 1.0),
+            );
+            painter.rect_filled(band, 1.0, lane_color);
+        }
+    }
+
+    painter.text(
+        row_rect.left_center() + egui::vec2(6.0, -1.0),
+        egui::Align2::LEFT_CENTER,
+        label,
+        egui::FontId::proportional(13.0),
+        theme_colors.text_strong,
+    );
+
+    let response = ui.interact(row_rect, ui.id().with(id), egui::Sense::hover());
+    response.hovered() && ui.input(|i| i.pointer.primary_clicked()) && !is_dragging
+}
+
+/// Length of the sides of a dependency arrow head, in pixels
+const ARROW_HEAD_SIZE: f32 = 5.0;
+
+/// Draws dependency arrows between bars, from the end of the dependency to
+/// the start of the dependent record.
+///
+/// Arrows touching the selected record are highlighted; the others are drawn
+/// faint so that dense dependency graphs do not hide the bars.
+///
+/// # Arguments
+/// * `painter` - Painter clipped to the timeline rows
+/// * `arrows` - Arrows whose both ends are visible
+/// * `row_centers` - Vertical center of each drawn record's row
+/// * `viewport_start_clk` - Start of the visible time range
+/// * `viewport_end_clk` - End of the visible time range
+/// * `canvas_rect` - Horizontal extent of the timeline
+/// * `selected_record_id` - Currently selected record ID (if any)
+/// * `theme_colors` - Color palette for the current theme
+#[allow(clippy::too_many_arguments)]
+pub fn render_dependency_arrows(
+    painter: &egui::Painter,
+    arrows: &[DependencyArrow],
+    row_centers: &HashMap<u64, f32>,
+    viewport_start_clk: i64,
+    viewport_end_clk: i64,
+    canvas_rect: egui::Rect,
+    selected_record_id: Option<u64>,
+    theme_colors: &ThemeColors,
+) {
+    let to_x = |clk| viewport_operations::clk_to_x(clk, viewport_start_clk, viewport_end_clk, canvas_rect);
+    for arrow in arrows {
+        let (Some(&from_y), Some(&to_y)) = (row_centers.get(&arrow.from_id), row_centers.get(&arrow.to_id)) else {
+            continue;
+        };
+        let highlighted = selected_record_id.is_some_and(|id| id == arrow.from_id || id == arrow.to_id);
+        let color = if highlighted { theme_colors.orange } else { theme_colors.text_dim.gamma_multiply(0.7) };
+        let stroke = egui::Stroke::new(if highlighted { 1.5 } else { 1.0 }, color);
+
+        // Leaves and enters the bars horizontally, bending in between
+        let from = egui::pos2(to_x(arrow.from_clk), from_y);
+        let to = egui::pos2(to_x(arrow.to_clk), to_y);
+        let bend = ((to.x - from.x).abs() / 2.0).max(12.0);
+        painter.add(egui::epaint::CubicBezierShape::from_points_stroke(
+            [from, egui::pos2(from.x + bend, from.y), egui::pos2(to.x - bend, to.y), to],
+            false,
+            Color32::TRANSPARENT,
+            stroke,
+        ));
+        let head = [
+            to,
+            egui::pos2(to.x - ARROW_HEAD_SIZE, to.y - ARROW_HEAD_SIZE * 0.6),
+            egui::pos2(to.x - ARROW_HEAD_SIZE, to.y + ARROW_HEAD_SIZE * 0.6),
+        ];
+        painter.add(egui::Shape::convex_polygon(head.to_vec(), color, egui::Stroke::NONE));
+    }
+}
+
 /// Result of user interaction with a timeline row
 pub enum TimelineRowInteraction {
     /// Timeline bar was clicked to select the record
@@ -223,9 +789,23 @@ pub enum TimelineRowInteraction {
         was_already_selected: bool,
         first_event_clk: Option<i64>,
     },
+    /// Timeline bar was double-clicked or Ctrl+clicked (action is configurable)
+    Gesture {
+        record_id: u64,
+        gesture: ClickGesture,
+    },
     /// Event marker was clicked to select the event
     EventClicked {
         record_id: u64,
         event_clk: i64,
     },
+    /// Lane header strip was clicked to collapse or expand the lane
+    LaneToggled {
+        record_id: u64,
+        was_expanded: bool,
+    },
+    /// Swimlane header strip was clicked to collapse or expand the lane
+    SwimlaneToggled {
+        key: String,
+    },
 }
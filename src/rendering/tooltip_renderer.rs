@@ -0,0 +1,191 @@
+//! Record tooltips with delay, cached content and pinning.
+//!
+//! Timeline bars and tree rows show record tooltips through
+//! [`show_record_tooltip`], which applies the configured delay and verbosity
+//! and reuses the content built for the hovered record. Pressing Space while
+//! a tooltip is shown pins it; [`render_pinned_tooltip`] keeps it on screen
+//! with scrollable, selectable text until it is closed.
+
+use eframe::egui;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::domain::number_format::ColumnFormats;
+use crate::presentation::markup;
+use crate::state::{InputSettings, TooltipContent, TooltipState, TooltipVerbosity};
+use rjets::{AttributeAccessor, DynTraceRecord, ThemeColors, TraceRecord};
+
+/// Offset of a pinned tooltip from the pointer position it was pinned at.
+const PIN_OFFSET: egui::Vec2 = egui::vec2(12.0, 12.0);
+
+/// Maximum height of a pinned tooltip before its fields scroll.
+const PINNED_MAX_HEIGHT: f32 = 320.0;
+
+/// Builds the tooltip content for a record, writing clocks, duration and ID
+/// in the column formats.
+pub fn record_tooltip_content(
+    record: &DynTraceRecord<'_>,
+    verbosity: TooltipVerbosity,
+    formats: &ColumnFormats,
+) -> TooltipContent {
+    let mut fields = vec![("Start".to_string(), formats.start_clock.format_readable(record.clk()))];
+    if let Some(end) = record.end_clk() {
+        let label = if record.end_inferred() { "End (inferred)" } else { "End" };
+        fields.push((label.to_string(), formats.start_clock.format_readable(end)));
+        fields.push(("Duration".to_string(), formats.duration.format_readable(end - record.clk())));
+    }
+
+    if verbosity == TooltipVerbosity::Full {
+        fields.push(("Type".to_string(), record.record_type()));
+        fields.push(("ID".to_string(), formats.format_id(&record.external_id())));
+        fields.push(("Children".to_string(), record.num_children().to_string()));
+        fields.push(("Events".to_string(), record.num_events().to_string()));
+        let mut attrs = record.attrs();
+        attrs.sort_by(|a, b| a.0.cmp(&b.0));
+        fields.extend(attrs.into_iter().map(|(key, value)| {
+            let value = match value {
+                serde_json::Value::String(s) => s,
+                other => other.to_string(),
+            };
+            (key, value)
+        }));
+    }
+
+    TooltipContent {
+        title: record.name(),
+        description: record.description(),
+        fields,
+    }
+}
+
+/// Returns the content as plain text (for copying a pinned tooltip).
+fn content_text(content: &TooltipContent) -> String {
+    let mut text = content.title.clone();
+    if !content.description.is_empty() {
+        text.push('\n');
+        text.push_str(&markup::plain_text(&content.description));
+    }
+    for (label, value) in &content.fields {
+        text.push_str(&format!("\n{}: {}", label, value));
+    }
+    text
+}
+
+/// Shows the content's title, description and fields.
+fn show_content(ui: &mut egui::Ui, content: &TooltipContent) {
+    ui.label(egui::RichText::new(&content.title).strong());
+    if !content.description.is_empty() {
+        markup::show_markup(ui, &content.description, ui.visuals().weak_text_color());
+    }
+    egui::Grid::new("record_tooltip_fields")
+        .num_columns(2)
+        .spacing([8.0, 2.0])
+        .show(ui, |ui| {
+            for (label, value) in &content.fields {
+                ui.weak(label);
+                ui.label(value);
+                ui.end_row();
+            }
+        });
+}
+
+/// Shows the tooltip for a record hovered through `response`.
+///
+/// Nothing is shown until the record has been hovered for the configured
+/// delay. `extra` adds pointer-dependent lines below the cached content.
+pub fn show_record_tooltip(
+    response: &egui::Response,
+    tooltip: &mut TooltipState,
+    settings: &InputSettings,
+    record: &DynTraceRecord<'_>,
+    extra: impl FnOnce(&mut egui::Ui),
+) {
+    let ctx = &response.ctx;
+    let now = ctx.input(|i| i.time);
+    let hovered_for = tooltip.hover(record.id(), now, ctx.cumulative_frame_nr());
+    let delay = settings.tooltip_delay_secs();
+    if hovered_for < delay {
+        ctx.request_repaint_after(Duration::from_secs_f64(delay - hovered_for));
+        return;
+    }
+
+    let content = tooltip.content_or_build(|| {
+        let mut content = record_tooltip_content(record, settings.tooltip_verbosity, &settings.column_formats);
+        // An aliased title keeps the original name as the first field
+        let alias = settings.display_name(&content.title).into_owned();
+        if alias != content.title {
+            let original = std::mem::replace(&mut content.title, alias);
+            content.fields.insert(0, ("Name".to_string(), original));
+        }
+        content
+    });
+    if !ctx.wants_keyboard_input() && ctx.input(|i| i.key_pressed(egui::Key::Space)) {
+        if let Some(pos) = ctx.pointer_hover_pos() {
+            tooltip.pin(record.id(), Arc::clone(&content), pos + PIN_OFFSET);
+        }
+    }
+
+    egui::Tooltip::for_widget(response).at_pointer().show(|ui| {
+        show_content(ui, &content);
+        extra(ui);
+        ui.weak("Space to pin");
+    });
+}
+
+/// Renders the pinned tooltip, if any, until it is closed (✕ or Escape).
+pub fn render_pinned_tooltip(ctx: &egui::Context, tooltip: &mut TooltipState, theme_colors: &ThemeColors) {
+    let Some(pinned) = tooltip.pinned() else {
+        return;
+    };
+    let content = Arc::clone(&pinned.content);
+    let mut close = ctx.input(|i| i.key_pressed(egui::Key::Escape));
+
+    egui::Area::new(egui::Id::new(("pinned_record_tooltip", pinned.record_id)))
+        .order(egui::Order::Tooltip)
+        .fixed_pos(pinned.pos)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style())
+                .stroke(egui::Stroke::new(1.0, theme_colors.blue))
+                .show(ui, |ui| {
+                    ui.set_max_width(ctx.style().spacing.tooltip_width * 1.5);
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new("📌 Pinned").color(theme_colors.blue));
+                        if ui.small_button("📋").on_hover_text("Copy as text").clicked() {
+                            ctx.copy_text(content_text(&content));
+                        }
+                        if ui.small_button("✕").on_hover_text("Close (Esc)").clicked() {
+                            close = true;
+                        }
+                    });
+                    ui.separator();
+                    egui::ScrollArea::vertical()
+                        .max_height(PINNED_MAX_HEIGHT)
+                        .show(ui, |ui| show_content(ui, &content));
+                });
+        });
+
+    if close {
+        tooltip.unpin();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rjets::{JetsTraceReader, TraceData, TraceReader};
+
+    #[test]
+    fn test_full_verbosity_adds_attributes() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/trace.jets");
+        let trace = JetsTraceReader::new().read(path).unwrap();
+        let record = trace.records_iter().find(|r| r.attr_count() > 0).unwrap();
+
+        let formats = ColumnFormats::default();
+        let minimal = record_tooltip_content(&record, TooltipVerbosity::Minimal, &formats);
+        let full = record_tooltip_content(&record, TooltipVerbosity::Full, &formats);
+        assert_eq!(minimal.title, record.name());
+        assert!(full.fields.starts_with(&minimal.fields));
+        assert_eq!(full.fields.len(), minimal.fields.len() + 4 + record.attrs().len());
+        assert!(content_text(&full).lines().count() > full.fields.len());
+    }
+}
@@ -9,7 +9,15 @@ use std::collections::HashSet;
 
 use crate::ui::virtual_scrolling::ROW_HEIGHT;
 use crate::cache::TreeCache;
+use crate::domain::duration_share;
+use crate::domain::problem_rollup::{self, SubtreeProblems};
+use crate::domain::record_navigation::NavigationScope;
+use crate::domain::severity::Severity;
+use crate::presentation::{color_mapping, markup};
 use crate::rendering::text_utils::truncate_text_to_fit;
+use crate::rendering::tooltip_renderer;
+use crate::state::{ClickGesture, InputSettings, TooltipState};
+use crate::ui::accessibility;
 
 /// Renders a single tree node row with expand/collapse controls and column data
 ///
@@ -22,13 +30,17 @@ use crate::rendering::text_utils::truncate_text_to_fit;
 /// * `column_widths` - Array of widths for each column
 /// * `expanded_nodes` - Set of currently expanded node IDs
 /// * `selected_record_id` - Currently selected record ID (if any)
+/// * `selected_records` - All selected records (highlighted like the selected one)
 /// * `theme_colors` - Color palette for the current theme
-/// * `tree_cache` - Cache for tree computations
+/// * `tree_cache` - Cache for tree computations (problem roll-up of collapsed parents, duration shares)
 /// * `branch_context` - For each depth level, whether there are more siblings below
 /// * `is_last_child` - Whether this node is the last child of its parent
+/// * `tooltip` - Record tooltip controller
+/// * `input_settings` - Tooltip delay and verbosity, name aliases, column number formats, duration shares
 ///
 /// # Returns
 /// * `Option<TreeNodeInteraction>` - User interaction result (expand/collapse, selection)
+#[allow(clippy::too_many_arguments)]
 pub fn render_tree_node(
     ui: &mut egui::Ui,
     trace: &DynTraceData,
@@ -38,20 +50,22 @@ pub fn render_tree_node(
     column_widths: &[f32; 5],
     expanded_nodes: &HashSet<u64>,
     selected_record_id: Option<u64>,
+    selected_records: &[u64],
     theme_colors: &ThemeColors,
-    _tree_cache: &mut TreeCache,
+    tree_cache: &mut TreeCache,
     branch_context: &[bool],
     is_last_child: bool,
+    tooltip: &mut TooltipState,
+    input_settings: &InputSettings,
 ) -> Option<TreeNodeInteraction> {
     // Extract all needed data from the record first to avoid borrow checker issues
-    let record = match trace.get_record(record_id) {
-        Some(r) => r,
-        None => return None,
-    };
+    let record = trace.get_record(record_id)?;
 
     let has_children = record.num_children() > 0;
-    let name = record.name().to_string();
-    let description = record.description().to_string();
+    let name = input_settings.display_name(&record.name()).into_owned();
+    let raw_description = record.description().to_string();
+    let description = markup::plain_text(&raw_description);
+    let external_id = record.external_id();
     let clk = record.clk();
     let end_clk = record.end_clk();
 
@@ -62,7 +76,7 @@ pub fn render_tree_node(
     };
 
     let indent = depth as f32 * 20.0;
-    let is_selected = selected_record_id == Some(record_id);
+    let is_selected = selected_record_id == Some(record_id) || selected_records.contains(&record_id);
 
     let mut x_offset = 0.0;
     let start_pos = ui.cursor().min;
@@ -73,9 +87,30 @@ pub fn render_tree_node(
         egui::Sense::click()
     );
 
+    let expanded = has_children.then(|| expanded_nodes.contains(&record_id));
+    accessibility::annotate_tree_row(
+        &row_response,
+        || accessibility::record_label(&name, &record.record_type(), record.duration()),
+        depth,
+        expanded,
+        is_selected,
+    );
+
     let mut interaction = None;
 
-    if row_response.clicked() {
+    if row_response.double_clicked() {
+        interaction = Some(TreeNodeInteraction::Gesture {
+            record_id,
+            gesture: ClickGesture::DoubleClick,
+        });
+    } else if row_response.clicked() && ui.input(|i| i.modifiers.ctrl) {
+        interaction = Some(TreeNodeInteraction::Gesture {
+            record_id,
+            gesture: ClickGesture::CtrlClick,
+        });
+    } else if row_response.clicked() && ui.input(|i| i.modifiers.shift) {
+        interaction = Some(TreeNodeInteraction::RangeSelected { record_id });
+    } else if row_response.clicked() {
         // Check if this is a new selection
         let was_already_selected = selected_record_id == Some(record_id);
         interaction = Some(TreeNodeInteraction::Selected {
@@ -85,6 +120,20 @@ pub fn render_tree_node(
         });
     }
 
+    row_response.context_menu(|ui| {
+        for (index, scope) in NavigationScope::ALL.into_iter().enumerate() {
+            if index > 0 {
+                ui.separator();
+            }
+            for forward in [false, true] {
+                if ui.button(scope.label(forward)).clicked() {
+                    interaction = Some(TreeNodeInteraction::Navigate { record_id, scope, forward });
+                    ui.close();
+                }
+            }
+        }
+    });
+
     // Draw background for selected row
     if is_selected {
         ui.painter().rect_filled(
@@ -136,6 +185,13 @@ pub fn render_tree_node(
         );
     }
 
+    // Problems hidden in a collapsed subtree get a badge at the end of the name column
+    let hidden_problems = if has_children && !expanded_nodes.contains(&record_id) {
+        problem_rollup::subtree_problems(&record, &mut tree_cache.problem_rollup)
+    } else {
+        None
+    };
+
     // Tree expansion control (fixed 20px width for button area, positioned after indent)
     let button_area_width = 20.0;
     let expand_rect = egui::Rect::from_min_size(
@@ -153,6 +209,9 @@ pub fn render_tree_node(
             egui::vec2(16.0, 16.0),
         );
         let button_response = ui.interact(button_rect, button_id, egui::Sense::click());
+        button_response.widget_info(|| {
+            egui::WidgetInfo::labeled(egui::WidgetType::Button, true, if is_expanded { "Collapse" } else { "Expand" })
+        });
 
         if button_response.clicked() {
             interaction = Some(TreeNodeInteraction::ExpandToggled {
@@ -172,15 +231,33 @@ pub fn render_tree_node(
 
     x_offset += expand_width;
 
-    let font_id = egui::FontId::proportional(13.0);
-    let painter = ui.painter();
+    let font_id = egui::FontId::proportional(input_settings.tree_font_size());
 
     // Column 0: Name
     let name_rect = egui::Rect::from_min_size(
         egui::pos2(start_pos.x + x_offset, start_pos.y),
         egui::vec2(column_widths[0], ROW_HEIGHT),
     );
-    let truncated_name = truncate_text_to_fit(&name, column_widths[0], &font_id, painter);
+    let badge_width = hidden_problems.map_or(0.0, |problems| {
+        let badge_rect = problem_badge_rect(name_rect, problems.count);
+        let badge_response = ui.interact(badge_rect, ui.id().with(("problems", record_id)), egui::Sense::click());
+        paint_problem_badge(ui.painter(), badge_rect, problems, theme_colors);
+        let (first_record, first_clk) = problems.first;
+        if badge_response.clicked() {
+            interaction = Some(TreeNodeInteraction::RevealProblem {
+                record_id: first_record,
+                event_clk: first_clk,
+            });
+        }
+        let noun = match problems.level {
+            Severity::Error => "problem events (worst: error)",
+            _ => "warning events",
+        };
+        badge_response.on_hover_text(format!("{} {} below; click to show the first", problems.count, noun));
+        badge_rect.width() + 4.0
+    });
+    let painter = ui.painter();
+    let truncated_name = truncate_text_to_fit(&name, column_widths[0] - badge_width, &font_id, painter);
     painter.text(
         name_rect.left_center() + egui::vec2(4.0, 0.0),
         egui::Align2::LEFT_CENTER,
@@ -204,13 +281,18 @@ pub fn render_tree_node(
         ui.visuals().text_color(),
     );
     x_offset += column_widths[1];
+    // Full record tooltip when the column cannot show the description as written
+    if row_response.hovered() && (description != raw_description || truncated_description != description) {
+        tooltip_renderer::show_record_tooltip(&row_response, tooltip, input_settings, &record, |_| {});
+    }
 
     // Column 2: Start Clock
     let start_rect = egui::Rect::from_min_size(
         egui::pos2(start_pos.x + x_offset, start_pos.y),
         egui::vec2(column_widths[2], ROW_HEIGHT),
     );
-    let clk_str = clk.to_string();
+    let formats = &input_settings.column_formats;
+    let clk_str = formats.start_clock.format(clk);
     let truncated_clk = truncate_text_to_fit(&clk_str, column_widths[2], &font_id, painter);
     painter.text(
         start_rect.left_center() + egui::vec2(4.0, 0.0),
@@ -221,11 +303,20 @@ pub fn render_tree_node(
     );
     x_offset += column_widths[2];
 
-    // Column 3: Duration
-    let duration_str = match end_clk {
-        Some(e) => (e - clk).to_string(),
+    // Column 3: Duration (inferred ends are marked with ~ and dimmed, optionally with its share)
+    let end_inferred = record.end_inferred();
+    let mut duration_str = match end_clk {
+        Some(e) if end_inferred => format!("~{}", formats.duration.format(e - clk)),
+        Some(e) => formats.duration.format(e - clk),
         None => "N/A".to_string(),
     };
+    // Share of the parent, or of the whole trace for roots
+    if input_settings.duration_share {
+        let share = duration_share::duration_share(trace, record_id, &mut tree_cache.duration_shares);
+        if let Some(fraction) = share.of_parent.or(share.of_trace.filter(|_| record.parent_id().is_none())) {
+            duration_str.push_str(&format!(" ({})", duration_share::format_share(fraction)));
+        }
+    }
 
     let duration_rect = egui::Rect::from_min_size(
         egui::pos2(start_pos.x + x_offset, start_pos.y),
@@ -237,7 +328,7 @@ pub fn render_tree_node(
         egui::Align2::LEFT_CENTER,
         &truncated_duration,
         font_id.clone(),
-        ui.visuals().text_color(),
+        if end_inferred { ui.visuals().weak_text_color() } else { ui.visuals().text_color() },
     );
     x_offset += column_widths[3];
 
@@ -246,7 +337,7 @@ pub fn render_tree_node(
         egui::pos2(start_pos.x + x_offset, start_pos.y),
         egui::vec2(column_widths[4], ROW_HEIGHT),
     );
-    let id_str = record_id.to_string();
+    let id_str = formats.format_id(&external_id);
     let truncated_id = truncate_text_to_fit(&id_str, column_widths[4], &font_id, painter);
     painter.text(
         id_rect.left_center() + egui::vec2(4.0, 0.0),
@@ -259,6 +350,91 @@ pub fn render_tree_node(
     interaction
 }
 
+/// Returns the badge rect at the right end of the name column.
+fn problem_badge_rect(name_rect: egui::Rect, count: usize) -> egui::Rect {
+    let digits = problem_count_label(count).len() as f32;
+    let size = egui::vec2(14.0 + digits * 6.0, ROW_HEIGHT - 6.0);
+    egui::Rect::from_min_size(
+        egui::pos2(name_rect.right() - size.x - 4.0, name_rect.center().y - size.y / 2.0),
+        size,
+    )
+}
+
+/// Formats a problem count for a badge, capped so badges stay narrow.
+fn problem_count_label(count: usize) -> String {
+    if count > 999 { "999+".to_string() } else { count.to_string() }
+}
+
+/// Paints a problem badge: "!" and the count on the level color.
+fn paint_problem_badge(painter: &egui::Painter, rect: egui::Rect, problems: SubtreeProblems, theme_colors: &ThemeColors) {
+    let fill = color_mapping::severity_color(problems.level, theme_colors);
+    painter.rect_filled(rect, rect.height() / 2.0, fill);
+    painter.text(
+        rect.center(),
+        egui::Align2::CENTER_CENTER,
+        format!("!{}", problem_count_label(problems.count)),
+        egui::FontId::proportional(10.0),
+        rjets::contrast_text_color(fill, theme_colors),
+    );
+}
+
+/// Renders a swimlane header row in the tree panel.
+///
+/// Mirrors the timeline swimlane strip so both panels stay row-aligned.
+/// Returns true if the row was clicked (toggles the lane).
+///
+/// # Arguments
+/// * `ui` - The egui UI context for drawing
+/// * `attribute` - Attribute key the lanes are grouped by
+/// * `key` - Attribute value identifying the lane
+/// * `count` - Number of records in the lane
+/// * `collapsed` - Whether the lane is currently collapsed
+/// * `font_size` - Tree text size
+/// * `theme_colors` - Color palette for the current theme
+pub fn render_swimlane_header_row(
+    ui: &mut egui::Ui,
+    attribute: &str,
+    key: &str,
+    count: usize,
+    collapsed: bool,
+    font_size: f32,
+    theme_colors: &ThemeColors,
+) -> bool {
+    let (row_rect, row_response) = ui.allocate_exact_size(
+        egui::vec2(ui.available_width(), ROW_HEIGHT),
+        egui::Sense::click()
+    );
+    let painter = ui.painter();
+
+    painter.rect_filled(row_rect, 0.0, theme_colors.panel_background);
+    painter.line_segment(
+        [row_rect.left_top(), row_rect.right_top()],
+        egui::Stroke::new(1.0, theme_colors.border),
+    );
+
+    let symbol = if collapsed { "▶" } else { "▼" };
+    painter.text(
+        row_rect.left_center() + egui::vec2(10.0, 0.0),
+        egui::Align2::CENTER_CENTER,
+        symbol,
+        egui::FontId::proportional(12.0),
+        theme_colors.text_strong,
+    );
+
+    let font_id = egui::FontId::proportional(font_size);
+    let label = format!("{} = {}  ({} records)", attribute, key, count);
+    let truncated = truncate_text_to_fit(&label, row_rect.width() - 24.0, &font_id, painter);
+    painter.text(
+        row_rect.left_center() + egui::vec2(24.0, 0.0),
+        egui::Align2::LEFT_CENTER,
+        &truncated,
+        font_id,
+        theme_colors.text_strong,
+    );
+
+    row_response.clicked()
+}
+
 /// Result of user interaction with a tree node
 pub enum TreeNodeInteraction {
     /// Node was clicked to select it
@@ -267,9 +443,27 @@ pub enum TreeNodeInteraction {
         was_already_selected: bool,
         first_event_clk: Option<i64>,
     },
+    /// Node was Shift+clicked to select the rows from the range anchor to it
+    RangeSelected { record_id: u64 },
     /// Expand/collapse button was clicked
     ExpandToggled {
         record_id: u64,
         was_expanded: bool,
     },
+    /// Node was double-clicked or Ctrl+clicked (action is configurable)
+    Gesture {
+        record_id: u64,
+        gesture: ClickGesture,
+    },
+    /// Problem badge was clicked: show the first problem event below the node
+    RevealProblem {
+        record_id: u64,
+        event_clk: i64,
+    },
+    /// A navigation item of the row's context menu was chosen
+    Navigate {
+        record_id: u64,
+        scope: NavigationScope,
+        forward: bool,
+    },
 }
@@ -0,0 +1,68 @@
+//! HTML rendering backend for reports.
+
+use super::report::{Report, ReportBlock};
+use super::svg::{self, escape_xml};
+
+/// Inline stylesheet so the document has no external dependencies.
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; color: #222; }\n\
+table { border-collapse: collapse; margin: 0.5em 0; }\n\
+th, td { border: 1px solid #ccc; padding: 4px 8px; text-align: left; }\n\
+th { background: #f0f0f0; }\n\
+dt { font-weight: bold; float: left; clear: left; width: 14em; }\n\
+dd { margin-left: 15em; }\n";
+
+/// Renders a report as a standalone HTML document with inline CSS and SVG.
+pub fn render(report: &Report) -> String {
+    let title = escape_xml(&report.title);
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{}</style>\n</head>\n<body>\n<h1>{}</h1>\n",
+        title, STYLE, title
+    );
+
+    for section in &report.sections {
+        out.push_str(&format!("<h2>{}</h2>\n", escape_xml(&section.heading)));
+
+        for block in &section.blocks {
+            match block {
+                ReportBlock::Paragraph(text) => {
+                    out.push_str(&format!("<p>{}</p>\n", escape_xml(text)));
+                }
+                ReportBlock::KeyValues(pairs) => {
+                    out.push_str("<dl>\n");
+                    for (key, value) in pairs {
+                        out.push_str(&format!(
+                            "<dt>{}</dt><dd>{}</dd>\n",
+                            escape_xml(key),
+                            escape_xml(value)
+                        ));
+                    }
+                    out.push_str("</dl>\n");
+                }
+                ReportBlock::Table { headers, rows } => {
+                    out.push_str("<table>\n<tr>");
+                    for header in headers {
+                        out.push_str(&format!("<th>{}</th>", escape_xml(header)));
+                    }
+                    out.push_str("</tr>\n");
+                    for row in rows {
+                        out.push_str("<tr>");
+                        for i in 0..headers.len() {
+                            let cell = row.get(i).map(|c| escape_xml(c)).unwrap_or_default();
+                            out.push_str(&format!("<td>{}</td>", cell));
+                        }
+                        out.push_str("</tr>\n");
+                    }
+                    out.push_str("</table>\n");
+                }
+                ReportBlock::BarChart { title, bars } => {
+                    out.push_str("<figure>\n");
+                    out.push_str(&svg::render_bar_chart(title, bars));
+                    out.push_str("\n</figure>\n");
+                }
+            }
+        }
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
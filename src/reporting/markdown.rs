@@ -0,0 +1,53 @@
+//! Markdown rendering backend for reports.
+
+use super::report::{Report, ReportBlock};
+use super::svg;
+
+/// Escapes characters that would break a Markdown table cell.
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Renders a report as a Markdown document.
+///
+/// Charts are embedded as inline `<svg>` blocks so the document stays
+/// self-contained.
+pub fn render(report: &Report) -> String {
+    let mut out = format!("# {}\n", report.title);
+
+    for section in &report.sections {
+        out.push_str(&format!("\n## {}\n", section.heading));
+
+        for block in &section.blocks {
+            out.push('\n');
+            match block {
+                ReportBlock::Paragraph(text) => {
+                    out.push_str(text);
+                    out.push('\n');
+                }
+                ReportBlock::KeyValues(pairs) => {
+                    for (key, value) in pairs {
+                        out.push_str(&format!("- **{}:** {}\n", key, value));
+                    }
+                }
+                ReportBlock::Table { headers, rows } => {
+                    let cells: Vec<String> = headers.iter().map(|h| escape_cell(h)).collect();
+                    out.push_str(&format!("| {} |\n", cells.join(" | ")));
+                    out.push_str(&format!("|{}\n", " --- |".repeat(headers.len())));
+                    for row in rows {
+                        let cells: Vec<String> = (0..headers.len())
+                            .map(|i| row.get(i).map(|c| escape_cell(c)).unwrap_or_default())
+                            .collect();
+                        out.push_str(&format!("| {} |\n", cells.join(" | ")));
+                    }
+                }
+                ReportBlock::BarChart { title, bars } => {
+                    out.push_str(&svg::render_bar_chart(title, bars));
+                    out.push('\n');
+                }
+            }
+        }
+    }
+
+    out
+}
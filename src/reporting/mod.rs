@@ -0,0 +1,15 @@
+//! Report generation shared by all trace analyses.
+//!
+//! Analyses describe their results as a format-independent [`Report`]
+//! (sections of paragraphs, key/value lists, tables and bar charts) and this
+//! module renders it into a self-contained document:
+//! - Markdown (`.md`) with charts embedded as inline SVG blocks
+//! - HTML (`.html`) with inline CSS and SVG, no external resources
+
+pub mod report;
+pub mod markdown;
+pub mod html;
+pub mod svg;
+
+// Re-export commonly used types
+pub use report::{Report, Reportable};
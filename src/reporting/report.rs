@@ -0,0 +1,191 @@
+//! Format-independent report model.
+//!
+//! A [`Report`] is a titled list of sections, each holding an ordered list of
+//! content blocks. Analyses build reports through the small builder API and
+//! leave rendering to the Markdown and HTML backends.
+
+use std::path::Path;
+use anyhow::Context;
+
+/// Output format for rendered reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// Markdown document with inline SVG charts
+    Markdown,
+    /// Standalone HTML document with inline CSS and SVG charts
+    Html,
+}
+
+impl ReportFormat {
+    /// Picks the output format from a file extension.
+    ///
+    /// `.html`/`.htm` produce HTML; everything else falls back to Markdown.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()) {
+            Some(ext) if ext == "html" || ext == "htm" => ReportFormat::Html,
+            _ => ReportFormat::Markdown,
+        }
+    }
+}
+
+/// A single piece of report content.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReportBlock {
+    /// Free-form text paragraph
+    Paragraph(String),
+    /// Two-column list of labelled values
+    KeyValues(Vec<(String, String)>),
+    /// Table with a header row and data rows
+    Table {
+        headers: Vec<String>,
+        rows: Vec<Vec<String>>,
+    },
+    /// Horizontal bar chart of labelled values
+    BarChart {
+        title: String,
+        bars: Vec<(String, f64)>,
+    },
+}
+
+/// A titled group of report blocks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportSection {
+    pub heading: String,
+    pub blocks: Vec<ReportBlock>,
+}
+
+impl ReportSection {
+    /// Appends a text paragraph.
+    pub fn paragraph(&mut self, text: impl Into<String>) -> &mut Self {
+        self.blocks.push(ReportBlock::Paragraph(text.into()));
+        self
+    }
+
+    /// Appends a key/value list.
+    pub fn key_values<K, V>(&mut self, pairs: impl IntoIterator<Item = (K, V)>) -> &mut Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let pairs = pairs.into_iter().map(|(k, v)| (k.into(), v.into())).collect();
+        self.blocks.push(ReportBlock::KeyValues(pairs));
+        self
+    }
+
+    /// Appends a table. Rows shorter than the header are padded when rendered.
+    pub fn table(&mut self, headers: &[&str], rows: Vec<Vec<String>>) -> &mut Self {
+        self.blocks.push(ReportBlock::Table {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows,
+        });
+        self
+    }
+
+    /// Appends a horizontal bar chart.
+    pub fn bar_chart(&mut self, title: impl Into<String>, bars: Vec<(String, f64)>) -> &mut Self {
+        self.blocks.push(ReportBlock::BarChart {
+            title: title.into(),
+            bars,
+        });
+        self
+    }
+}
+
+/// A complete analysis report ready for rendering.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub title: String,
+    pub sections: Vec<ReportSection>,
+}
+
+impl Report {
+    /// Creates an empty report with the given title.
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            sections: Vec::new(),
+        }
+    }
+
+    /// Starts a new section and returns it for populating.
+    pub fn section(&mut self, heading: impl Into<String>) -> &mut ReportSection {
+        self.sections.push(ReportSection {
+            heading: heading.into(),
+            blocks: Vec::new(),
+        });
+        self.sections.last_mut().expect("section was just pushed")
+    }
+
+    /// Renders the report into a document string.
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Markdown => super::markdown::render(self),
+            ReportFormat::Html => super::html::render(self),
+        }
+    }
+
+    /// Renders the report and writes it to `path`, choosing the format by extension.
+    pub fn write_to_file(&self, path: &Path) -> anyhow::Result<()> {
+        let document = self.render(ReportFormat::from_path(path));
+        std::fs::write(path, document)
+            .with_context(|| format!("Failed to write report: {}", path.display()))
+    }
+}
+
+/// Implemented by analysis results that can be exported as a report.
+pub trait Reportable {
+    /// Builds a report describing this analysis result.
+    fn to_report(&self) -> Report;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(ReportFormat::from_path(&PathBuf::from("a.html")), ReportFormat::Html);
+        assert_eq!(ReportFormat::from_path(&PathBuf::from("a.HTM")), ReportFormat::Html);
+        assert_eq!(ReportFormat::from_path(&PathBuf::from("a.md")), ReportFormat::Markdown);
+        assert_eq!(ReportFormat::from_path(&PathBuf::from("report")), ReportFormat::Markdown);
+    }
+
+    #[test]
+    fn test_builder_collects_blocks_in_order() {
+        let mut report = Report::new("Stats");
+        report
+            .section("Summary")
+            .paragraph("hello")
+            .key_values([("Records", "3")])
+            .bar_chart("Counts", vec![("a".to_string(), 1.0)]);
+
+        assert_eq!(report.sections.len(), 1);
+        let blocks = &report.sections[0].blocks;
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0], ReportBlock::Paragraph("hello".to_string()));
+        assert!(matches!(blocks[2], ReportBlock::BarChart { .. }));
+    }
+
+    #[test]
+    fn test_markdown_and_html_are_self_contained() {
+        let mut report = Report::new("Stats <1>");
+        report
+            .section("Durations")
+            .table(&["Name", "Count"], vec![vec!["a|b".to_string(), "2".to_string()]])
+            .bar_chart("Counts", vec![("a".to_string(), 2.0), ("b".to_string(), 1.0)]);
+
+        let md = report.render(ReportFormat::Markdown);
+        assert!(md.starts_with("# Stats <1>"));
+        assert!(md.contains("| Name | Count |"));
+        assert!(md.contains("a\\|b"));
+        assert!(md.contains("<svg"));
+
+        let html = report.render(ReportFormat::Html);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Stats &lt;1&gt;</title>"));
+        assert!(html.contains("<svg"));
+        assert!(!html.contains("<link"));
+        assert!(!html.contains("<script"));
+    }
+}
@@ -0,0 +1,118 @@
+//! Minimal SVG chart rendering for embedded report charts.
+
+/// Height of a single bar row in pixels.
+const BAR_ROW_HEIGHT: f64 = 20.0;
+/// Width reserved for bar labels in pixels.
+const LABEL_WIDTH: f64 = 180.0;
+/// Maximum bar length in pixels.
+const BAR_AREA_WIDTH: f64 = 360.0;
+/// Width reserved for value labels after the bars.
+const VALUE_WIDTH: f64 = 100.0;
+/// Height of the chart title line.
+const TITLE_HEIGHT: f64 = 24.0;
+/// Labels longer than this are truncated with an ellipsis.
+const MAX_LABEL_CHARS: usize = 28;
+
+/// Escapes text for use inside SVG/HTML/XML content and attributes.
+pub fn escape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+/// Formats a chart value compactly (integers without decimals).
+fn format_value(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        format!("{:.2}", value)
+    }
+}
+
+/// Renders a horizontal bar chart as a standalone `<svg>` element.
+///
+/// Bars are scaled relative to the largest value; negative values are drawn
+/// as empty bars. The output uses only inline attributes so it renders the
+/// same in HTML and in Markdown viewers that allow inline SVG.
+pub fn render_bar_chart(title: &str, bars: &[(String, f64)]) -> String {
+    let max_value = bars.iter().map(|(_, v)| *v).fold(0.0_f64, f64::max);
+    let width = LABEL_WIDTH + BAR_AREA_WIDTH + VALUE_WIDTH;
+    let height = TITLE_HEIGHT + BAR_ROW_HEIGHT * bars.len().max(1) as f64;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" \
+         font-family=\"sans-serif\" font-size=\"12\">\n",
+        w = width,
+        h = height
+    );
+    svg.push_str(&format!(
+        "<text x=\"0\" y=\"16\" font-weight=\"bold\">{}</text>\n",
+        escape_xml(title)
+    ));
+
+    for (i, (label, value)) in bars.iter().enumerate() {
+        let y = TITLE_HEIGHT + i as f64 * BAR_ROW_HEIGHT;
+        let bar_width = if max_value > 0.0 {
+            (value.max(0.0) / max_value) * BAR_AREA_WIDTH
+        } else {
+            0.0
+        };
+        let label = if label.chars().count() > MAX_LABEL_CHARS {
+            let truncated: String = label.chars().take(MAX_LABEL_CHARS - 1).collect();
+            format!("{}…", truncated)
+        } else {
+            label.clone()
+        };
+
+        svg.push_str(&format!(
+            "<text x=\"{x}\" y=\"{ty:.1}\" text-anchor=\"end\">{label}</text>\n",
+            x = LABEL_WIDTH - 6.0,
+            ty = y + 14.0,
+            label = escape_xml(&label)
+        ));
+        svg.push_str(&format!(
+            "<rect x=\"{x}\" y=\"{y:.1}\" width=\"{bw:.1}\" height=\"{bh:.1}\" fill=\"#4a90d9\"/>\n",
+            x = LABEL_WIDTH,
+            y = y + 3.0,
+            bw = bar_width,
+            bh = BAR_ROW_HEIGHT - 6.0
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{x:.1}\" y=\"{ty:.1}\">{value}</text>\n",
+            x = LABEL_WIDTH + bar_width + 4.0,
+            ty = y + 14.0,
+            value = format_value(*value)
+        ));
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(escape_xml("a<b & \"c\""), "a&lt;b &amp; &quot;c&quot;");
+    }
+
+    #[test]
+    fn test_bar_chart_scales_to_max() {
+        let svg = render_bar_chart("T", &[("a".to_string(), 10.0), ("b".to_string(), 5.0)]);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>"));
+        assert!(svg.contains("width=\"360.0\""));
+        assert!(svg.contains("width=\"180.0\""));
+    }
+}
@@ -0,0 +1,460 @@
+//! Machine-readable description of the JETS line format.
+//!
+//! JETS.md is the prose specification; this module is the same information
+//! as data so that tools can use it. It describes every line type with its
+//! fields, their JSON types, whether they are required and how they differ
+//! between format versions. The description drives the [`crate::validator`]
+//! and can be emitted as a JSON Schema document for producers that want to
+//! check their emitters with off-the-shelf tooling.
+//!
+//! # Examples
+//!
+//! ```
+//! use rjets::schema::{self, FormatVersion};
+//!
+//! let schema = schema::json_schema(FormatVersion::CURRENT);
+//! assert_eq!(schema["title"], "JETS 2.0 line");
+//! ```
+
+use serde_json::{json, Map, Value};
+use std::fmt;
+
+/// A `major.minor` JETS format version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FormatVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl FormatVersion {
+    /// Version 1.0 (legacy field names, integer record IDs only).
+    pub const V1_0: FormatVersion = FormatVersion { major: 1, minor: 0 };
+    /// Version 2.0 (integer or string record IDs).
+    pub const V2_0: FormatVersion = FormatVersion { major: 2, minor: 0 };
+    /// Newest version this crate reads and writes.
+    pub const CURRENT: FormatVersion = Self::V2_0;
+
+    /// Parses a header version string such as `"2.0"` or `"1"`.
+    ///
+    /// A missing minor part is treated as `0`; anything after the minor
+    /// number (e.g. a patch level) is ignored.
+    pub fn parse(version: &str) -> Option<FormatVersion> {
+        let mut parts = version.trim().split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = match parts.next() {
+            Some(minor) => minor.parse().ok()?,
+            None => 0,
+        };
+        Some(FormatVersion { major, minor })
+    }
+}
+
+impl fmt::Display for FormatVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// JSON type of a line field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    /// Signed 64-bit integer (clock values)
+    Integer,
+    /// Unsigned integer (counters)
+    UnsignedInteger,
+    /// JSON string
+    String,
+    /// JSON object
+    Object,
+    /// Any JSON value
+    Any,
+    /// Reference to a record: unsigned integer, or string since 2.0
+    RecordId,
+    /// Like [`FieldType::RecordId`] but `null` is allowed (root records)
+    NullableRecordId,
+    /// Array of [`FieldType::RecordId`] values
+    RecordIdList,
+}
+
+impl FieldType {
+    /// Checks a value against this type for the given format version.
+    pub fn accepts(&self, value: &Value, version: FormatVersion) -> bool {
+        match self {
+            FieldType::Integer => value.is_i64(),
+            FieldType::UnsignedInteger => value.is_u64(),
+            FieldType::String => value.is_string(),
+            FieldType::Object => value.is_object(),
+            FieldType::Any => true,
+            FieldType::RecordId => {
+                value.is_u64() || (value.is_string() && version >= FormatVersion::V2_0)
+            }
+            FieldType::NullableRecordId => {
+                value.is_null() || FieldType::RecordId.accepts(value, version)
+            }
+            FieldType::RecordIdList => value
+                .as_array()
+                .is_some_and(|ids| ids.iter().all(|id| FieldType::RecordId.accepts(id, version))),
+        }
+    }
+
+    /// Human-readable type name used in validation messages.
+    pub fn describe(&self, version: FormatVersion) -> &'static str {
+        match self {
+            FieldType::Integer => "integer",
+            FieldType::UnsignedInteger => "unsigned integer",
+            FieldType::String => "string",
+            FieldType::Object => "object",
+            FieldType::Any => "any value",
+            FieldType::RecordId if version >= FormatVersion::V2_0 => "unsigned integer or string",
+            FieldType::RecordId => "unsigned integer",
+            FieldType::NullableRecordId if version >= FormatVersion::V2_0 => {
+                "unsigned integer, string or null"
+            }
+            FieldType::NullableRecordId => "unsigned integer or null",
+            FieldType::RecordIdList if version >= FormatVersion::V2_0 => {
+                "array of unsigned integers or strings"
+            }
+            FieldType::RecordIdList => "array of unsigned integers",
+        }
+    }
+
+    /// JSON Schema fragment for this type.
+    fn json_schema(&self, version: FormatVersion) -> Value {
+        let record_id = if version >= FormatVersion::V2_0 {
+            json!({ "oneOf": [
+                { "type": "integer", "minimum": 0, "maximum": u64::MAX },
+                { "type": "string" }
+            ] })
+        } else {
+            json!({ "type": "integer", "minimum": 0, "maximum": u64::MAX })
+        };
+
+        match self {
+            FieldType::Integer => json!({ "type": "integer" }),
+            FieldType::UnsignedInteger => json!({ "type": "integer", "minimum": 0 }),
+            FieldType::String => json!({ "type": "string" }),
+            FieldType::Object => json!({ "type": "object" }),
+            FieldType::Any => json!({}),
+            FieldType::RecordId => record_id,
+            FieldType::NullableRecordId => json!({ "oneOf": [record_id, { "type": "null" }] }),
+            FieldType::RecordIdList => json!({ "type": "array", "items": record_id }),
+        }
+    }
+}
+
+/// One field of a line type.
+#[derive(Debug, Clone, Copy)]
+pub struct FieldSpec {
+    pub name: &'static str,
+    pub field_type: FieldType,
+    /// Whether the field must be present
+    pub required: bool,
+    pub description: &'static str,
+}
+
+/// A way in which a line does not match its [`LineSpec`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaViolation {
+    /// A required field is absent
+    MissingField(&'static str),
+    /// A field has the wrong JSON type
+    WrongType {
+        field: &'static str,
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// A field not defined for the line type (readers ignore it)
+    UnknownField(String),
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaViolation::MissingField(name) => write!(f, "missing required field '{}'", name),
+            SchemaViolation::WrongType { field, expected, found } => {
+                write!(f, "field '{}' must be {}, found {}", field, expected, found)
+            }
+            SchemaViolation::UnknownField(name) => write!(f, "unknown field '{}'", name),
+        }
+    }
+}
+
+/// One line type (`header`, `record`, ...).
+#[derive(Debug, Clone, Copy)]
+pub struct LineSpec {
+    /// Value of the `type` field
+    pub line_type: &'static str,
+    pub description: &'static str,
+    /// Fields other than `type`
+    pub fields: &'static [FieldSpec],
+    /// Whether fields not listed above are allowed
+    pub allows_extra_fields: bool,
+}
+
+impl LineSpec {
+    /// Returns the spec of a field by name.
+    pub fn field(&self, name: &str) -> Option<&FieldSpec> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    /// Checks an object against this line type.
+    ///
+    /// # Returns
+    /// One violation per problem; empty if the object conforms.
+    pub fn check(&self, object: &Map<String, Value>, version: FormatVersion) -> Vec<SchemaViolation> {
+        let mut violations = Vec::new();
+
+        for field in self.fields {
+            // Optional fields may be written as explicit nulls
+            match object.get(field.name).filter(|v| field.required || !v.is_null()) {
+                Some(value) if !field.field_type.accepts(value, version) => {
+                    violations.push(SchemaViolation::WrongType {
+                        field: field.name,
+                        expected: field.field_type.describe(version),
+                        found: json_type_name(value),
+                    });
+                }
+                Some(_) => {}
+                None if field.required => violations.push(SchemaViolation::MissingField(field.name)),
+                None => {}
+            }
+        }
+
+        if !self.allows_extra_fields {
+            for key in object.keys() {
+                if key != "type" && self.field(key).is_none() {
+                    violations.push(SchemaViolation::UnknownField(key.clone()));
+                }
+            }
+        }
+
+        violations
+    }
+
+    /// JSON Schema for this line type.
+    fn json_schema(&self, version: FormatVersion) -> Value {
+        let mut properties = Map::new();
+        properties.insert("type".to_string(), json!({ "const": self.line_type }));
+        let mut required = vec![json!("type")];
+
+        for field in self.fields {
+            let mut schema = field.field_type.json_schema(version);
+            if !field.required && field.field_type != FieldType::Any {
+                schema = json!({ "anyOf": [schema, { "type": "null" }] });
+            }
+            if let Value::Object(map) = &mut schema {
+                map.insert("description".to_string(), json!(field.description));
+            }
+            properties.insert(field.name.to_string(), schema);
+            if field.required {
+                required.push(json!(field.name));
+            }
+        }
+
+        // Old versions spell some fields differently
+        if let Some(layout) = crate::compat::layout_for(version) {
+            properties = properties
+                .into_iter()
+                .map(|(name, schema)| {
+                    let name = layout.legacy_name(self.line_type, &name).map(str::to_string).unwrap_or(name);
+                    (name, schema)
+                })
+                .collect();
+            required = required
+                .into_iter()
+                .filter(|name| !(name == "description" && layout.description_optional(self.line_type)))
+                .map(|name| match name.as_str().and_then(|n| layout.legacy_name(self.line_type, n)) {
+                    Some(legacy) => json!(legacy),
+                    None => name,
+                })
+                .collect();
+        }
+
+        json!({
+            "title": self.line_type,
+            "description": self.description,
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "additionalProperties": self.allows_extra_fields,
+        })
+    }
+}
+
+const fn field(
+    name: &'static str,
+    field_type: FieldType,
+    required: bool,
+    description: &'static str,
+) -> FieldSpec {
+    FieldSpec { name, field_type, required, description }
+}
+
+/// All JETS line types in specification order.
+pub const LINE_SPECS: &[LineSpec] = &[
+    LineSpec {
+        line_type: "header",
+        description: "File metadata; must be the first line",
+        fields: &[
+            field("version", FieldType::String, true, "Format version, e.g. \"2.0\""),
+            field("metadata", FieldType::Object, true, "Trace metadata (hardware model, clock frequency, ...)"),
+        ],
+        allows_extra_fields: false,
+    },
+    LineSpec {
+        line_type: "record",
+        description: "Start of a hierarchical trace record",
+        fields: &[
+            field("clk", FieldType::Integer, true, "Clock cycle when the record begins"),
+            field("name", FieldType::String, true, "Short human-readable name"),
+            field("record_type", FieldType::String, true, "Semantic type, e.g. \"Instruction\""),
+            field("id", FieldType::RecordId, true, "Globally unique record ID"),
+            field("parent_id", FieldType::NullableRecordId, true, "Parent record ID; null for roots"),
+            field("description", FieldType::String, true, "Human-readable description"),
+            field("data", FieldType::Object, false, "Arbitrary additional fields"),
+            field("depends_on", FieldType::RecordIdList, false, "IDs of the records this one depends on"),
+        ],
+        allows_extra_fields: false,
+    },
+    LineSpec {
+        line_type: "record_end",
+        description: "Completion of a record",
+        fields: &[
+            field("clk", FieldType::Integer, true, "Clock cycle when the record completes"),
+            field("record_id", FieldType::RecordId, true, "ID of the ending record"),
+        ],
+        allows_extra_fields: false,
+    },
+    LineSpec {
+        line_type: "annotation",
+        description: "Non-timed metadata attached to a record",
+        fields: &[
+            field("name", FieldType::String, true, "Short annotation name"),
+            field("record_id", FieldType::RecordId, true, "ID of the annotated record"),
+            field("description", FieldType::String, true, "Human-readable description"),
+            field("data", FieldType::Any, true, "Arbitrary JSON value"),
+        ],
+        allows_extra_fields: false,
+    },
+    LineSpec {
+        line_type: "event",
+        description: "Timed operation or state change of a record",
+        fields: &[
+            field("clk", FieldType::Integer, true, "Clock cycle when the event occurs"),
+            field("name", FieldType::String, true, "Short event name"),
+            field("record_id", FieldType::RecordId, true, "ID of the owning record"),
+            field("description", FieldType::String, true, "Human-readable description"),
+            field("data", FieldType::Any, false, "Optional additional data"),
+        ],
+        allows_extra_fields: false,
+    },
+    LineSpec {
+        line_type: "footer",
+        description: "Optional trace summary; must be the last line",
+        fields: &[
+            field("capture_end_clk", FieldType::Integer, false, "Final clock timestamp"),
+            field("total_records", FieldType::UnsignedInteger, false, "Number of records written"),
+            field("total_annotations", FieldType::UnsignedInteger, false, "Number of annotations written"),
+            field("total_events", FieldType::UnsignedInteger, false, "Number of events written"),
+            field("provenance", FieldType::Object, false, "Content hash of the preceding lines and producer"),
+        ],
+        allows_extra_fields: true,
+    },
+];
+
+/// Returns the spec for a `type` value.
+pub fn line_spec(line_type: &str) -> Option<&'static LineSpec> {
+    LINE_SPECS.iter().find(|spec| spec.line_type == line_type)
+}
+
+/// Emits a JSON Schema (draft 2020-12) describing a single JETS line.
+///
+/// A trace conforms line-wise if every line validates against this schema.
+/// Ordering rules (header first, no forward references, ...) cannot be
+/// expressed per line and are only checked by [`crate::validator`].
+pub fn json_schema(version: FormatVersion) -> Value {
+    let variants: Vec<Value> = LINE_SPECS.iter().map(|spec| spec.json_schema(version)).collect();
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": format!("https://github.com/ripopov/jets/schema/jets-{}.json", version),
+        "title": format!("JETS {} line", version),
+        "description": "One line of a JETS (JSON Event Trace Streaming) file",
+        "oneOf": variants,
+    })
+}
+
+/// Name of a JSON value's type for messages.
+pub(crate) fn json_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(FormatVersion::parse("2.0"), Some(FormatVersion::V2_0));
+        assert_eq!(FormatVersion::parse("1"), Some(FormatVersion::V1_0));
+        assert_eq!(FormatVersion::parse("2.1.3"), Some(FormatVersion { major: 2, minor: 1 }));
+        assert_eq!(FormatVersion::parse("v2"), None);
+    }
+
+    #[test]
+    fn test_record_check_reports_each_problem() {
+        let spec = line_spec("record").unwrap();
+        let line = json!({"type": "record", "clk": "10", "name": "A", "record_type": "T",
+                          "id": 1, "description": "", "extra": true});
+        let violations = spec.check(line.as_object().unwrap(), FormatVersion::CURRENT);
+        assert_eq!(
+            violations,
+            vec![
+                SchemaViolation::WrongType { field: "clk", expected: "integer", found: "string" },
+                SchemaViolation::MissingField("parent_id"),
+                SchemaViolation::UnknownField("extra".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_string_ids_depend_on_version() {
+        assert!(FieldType::RecordId.accepts(&json!("a"), FormatVersion::V2_0));
+        assert!(!FieldType::RecordId.accepts(&json!("a"), FormatVersion::V1_0));
+        assert!(!FieldType::RecordId.accepts(&json!(-1), FormatVersion::V2_0));
+        assert!(FieldType::RecordId.accepts(&json!(u64::MAX), FormatVersion::V1_0));
+        assert!(FieldType::NullableRecordId.accepts(&Value::Null, FormatVersion::V1_0));
+        assert!(FieldType::RecordIdList.accepts(&json!([1, "a"]), FormatVersion::V2_0));
+        assert!(!FieldType::RecordIdList.accepts(&json!([1, "a"]), FormatVersion::V1_0));
+        assert!(!FieldType::RecordIdList.accepts(&json!(1), FormatVersion::V2_0));
+    }
+
+    #[test]
+    fn test_json_schema_covers_all_line_types() {
+        let schema = json_schema(FormatVersion::CURRENT);
+        let variants = schema["oneOf"].as_array().unwrap();
+        assert_eq!(variants.len(), LINE_SPECS.len());
+        let record = &variants[1];
+        assert_eq!(record["properties"]["type"]["const"], "record");
+        assert!(record["required"].as_array().unwrap().contains(&json!("parent_id")));
+        assert_eq!(record["additionalProperties"], false);
+    }
+
+    #[test]
+    fn test_json_schema_uses_legacy_names() {
+        let schema = json_schema(FormatVersion::V1_0);
+        let record = &schema["oneOf"][1];
+        assert!(record["properties"].get("kind").is_some());
+        assert!(record["properties"].get("record_type").is_none());
+        let required = record["required"].as_array().unwrap();
+        assert!(required.contains(&json!("parent")));
+        assert!(!required.contains(&json!("description")));
+    }
+}
@@ -0,0 +1,342 @@
+//! Clock-ordered emission on top of [`TraceWriter`].
+//!
+//! Simulators often know an event's clock only after they have moved past
+//! it, e.g. pipeline stages of an instruction are produced together while
+//! the next instruction already started. A [`SortedTraceWriter`] accepts
+//! lines up to `window` clocks out of order, holds them back and writes them
+//! in clock order. Lines with equal clocks keep the order they were written
+//! in, so a record still precedes its events at the same clock.
+//!
+//! A line arriving behind a clock that has already been written cannot be
+//! placed anymore and is rejected, which makes the writer a monotonicity
+//! check as well (a window of 0 only checks).
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use rjets::{SortedTraceWriter, TraceWriter};
+//! # fn main() -> anyhow::Result<()> {
+//! let mut writer = TraceWriter::new("trace.jets")?;
+//! writer.write_header("2.0", serde_json::json!({}))?;
+//! let mut sorted = SortedTraceWriter::new(&mut writer, 16);
+//! sorted.write_record(1, None, "Insn", 10, "add", "", None)?;
+//! sorted.write_event(1, "EX", "", 14, None)?;
+//! sorted.write_record(2, None, "Insn", 11, "sub", "", None)?; // written before the EX event
+//! sorted.finish()?;
+//! writer.write_footer(None)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+use anyhow::{bail, Result};
+use serde_json::Value;
+
+use crate::traits::ExternalId;
+use crate::writer::TraceWriter;
+
+/// A line waiting for its turn.
+enum Line {
+    Record {
+        id: ExternalId,
+        parent_id: Option<ExternalId>,
+        record_type: String,
+        name: String,
+        description: String,
+        data: Option<Value>,
+    },
+    RecordEnd {
+        id: ExternalId,
+    },
+    Annotation {
+        record_id: ExternalId,
+        name: String,
+        description: String,
+        data: Value,
+    },
+    Event {
+        record_id: ExternalId,
+        name: String,
+        description: String,
+        data: Option<Value>,
+    },
+}
+
+/// A held-back line, ordered by clock and then by arrival.
+struct Pending {
+    clk: i64,
+    seq: u64,
+    line: Line,
+}
+
+impl PartialEq for Pending {
+    fn eq(&self, other: &Self) -> bool {
+        (self.clk, self.seq) == (other.clk, other.seq)
+    }
+}
+
+impl Eq for Pending {}
+
+impl PartialOrd for Pending {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Pending {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.clk, self.seq).cmp(&(other.clk, other.seq))
+    }
+}
+
+/// Writes lines in clock order, reordering within a window.
+///
+/// The methods mirror [`TraceWriter`]'s. Header and footer are written on the
+/// wrapped writer directly, before and after (see [`finish`](Self::finish)).
+/// Dropping the writer also writes the held-back lines, but drops any error;
+/// call `finish` to see it.
+pub struct SortedTraceWriter<'w> {
+    writer: &'w mut TraceWriter,
+    /// How far behind the latest clock a line may arrive
+    window: i64,
+    pending: BinaryHeap<Reverse<Pending>>,
+    next_seq: u64,
+    /// Highest clock accepted so far
+    latest_clk: Option<i64>,
+    /// Clock of the last line written out
+    written_clk: Option<i64>,
+}
+
+impl<'w> SortedTraceWriter<'w> {
+    /// Wraps a writer; lines may arrive up to `window` clocks out of order.
+    pub fn new(writer: &'w mut TraceWriter, window: i64) -> Self {
+        SortedTraceWriter {
+            writer,
+            window: window.max(0),
+            pending: BinaryHeap::new(),
+            next_seq: 0,
+            latest_clk: None,
+            written_clk: None,
+        }
+    }
+
+    /// Returns the highest clock accepted so far, if any.
+    pub fn latest_clk(&self) -> Option<i64> {
+        self.latest_clk
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn write_record(
+        &mut self,
+        id: u64,
+        parent_id: Option<u64>,
+        record_type: &str,
+        clk: i64,
+        name: &str,
+        description: &str,
+        data: Option<Value>,
+    ) -> Result<()> {
+        self.write_record_external(
+            &ExternalId::Int(id),
+            parent_id.map(ExternalId::Int).as_ref(),
+            record_type,
+            clk,
+            name,
+            description,
+            data,
+        )
+    }
+
+    /// Writes a record whose ID and parent ID may be strings.
+    #[allow(clippy::too_many_arguments)]
+    pub fn write_record_external(
+        &mut self,
+        id: &ExternalId,
+        parent_id: Option<&ExternalId>,
+        record_type: &str,
+        clk: i64,
+        name: &str,
+        description: &str,
+        data: Option<Value>,
+    ) -> Result<()> {
+        let line = Line::Record {
+            id: id.clone(),
+            parent_id: parent_id.cloned(),
+            record_type: record_type.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            data,
+        };
+        self.push(clk, line)
+    }
+
+    pub fn write_record_end(&mut self, id: impl Into<ExternalId>, clk: i64) -> Result<()> {
+        self.push(clk, Line::RecordEnd { id: id.into() })
+    }
+
+    /// Writes an annotation. Annotations have no clock; it is written after
+    /// every line accepted so far.
+    pub fn write_annotation(
+        &mut self,
+        record_id: impl Into<ExternalId>,
+        name: &str,
+        description: &str,
+        data: Value,
+    ) -> Result<()> {
+        let line = Line::Annotation {
+            record_id: record_id.into(),
+            name: name.to_string(),
+            description: description.to_string(),
+            data,
+        };
+        let clk = self.latest_clk.unwrap_or(i64::MIN);
+        self.push(clk, line)
+    }
+
+    pub fn write_event(
+        &mut self,
+        record_id: impl Into<ExternalId>,
+        name: &str,
+        description: &str,
+        clk: i64,
+        data: Option<Value>,
+    ) -> Result<()> {
+        let line = Line::Event {
+            record_id: record_id.into(),
+            name: name.to_string(),
+            description: description.to_string(),
+            data,
+        };
+        self.push(clk, line)
+    }
+
+    /// Writes all held-back lines.
+    pub fn finish(mut self) -> Result<()> {
+        self.write_until(i64::MAX)
+    }
+
+    fn push(&mut self, clk: i64, line: Line) -> Result<()> {
+        if let Some(written) = self.written_clk.filter(|&written| clk < written) {
+            bail!(
+                "Line at clock {} arrived after clock {} was written (reorder window {})",
+                clk,
+                written,
+                self.window
+            );
+        }
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.pending.push(Reverse(Pending { clk, seq, line }));
+        let latest = self.latest_clk.map_or(clk, |latest| latest.max(clk));
+        self.latest_clk = Some(latest);
+        self.write_until(latest.saturating_sub(self.window))
+    }
+
+    /// Writes the held-back lines with clocks up to `clk`.
+    fn write_until(&mut self, clk: i64) -> Result<()> {
+        while self.pending.peek().is_some_and(|Reverse(next)| next.clk <= clk) {
+            let Some(Reverse(Pending { clk, line, .. })) = self.pending.pop() else { break };
+            self.written_clk = Some(clk);
+            match line {
+                Line::Record { id, parent_id, record_type, name, description, data } => {
+                    self.writer.write_record_external(&id, parent_id.as_ref(), &record_type, clk, &name, &description, data)?
+                }
+                Line::RecordEnd { id } => self.writer.write_record_end(id, clk)?,
+                Line::Annotation { record_id, name, description, data } => {
+                    self.writer.write_annotation(record_id, &name, &description, data)?
+                }
+                Line::Event { record_id, name, description, data } => {
+                    self.writer.write_event(record_id, &name, &description, clk, data)?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for SortedTraceWriter<'_> {
+    fn drop(&mut self) {
+        let _ = self.write_until(i64::MAX);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::io::Write;
+    use std::rc::Rc;
+
+    #[derive(Clone, Default)]
+    struct Sink(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Sink {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_lines_come_out_in_clock_order() {
+        let sink = Sink::default();
+        let mut writer = TraceWriter::from_writer(Box::new(sink.clone()));
+        let mut sorted = SortedTraceWriter::new(&mut writer, 10);
+        sorted.write_record(1, None, "Insn", 0, "a", "", None).unwrap();
+        sorted.write_event(1, "EX", "", 8, None).unwrap();
+        sorted.write_record_end(1, 9).unwrap();
+        sorted.write_record(2, None, "Insn", 2, "b", "", None).unwrap();
+        sorted.write_event(2, "F", "", 2, None).unwrap();
+        sorted.write_annotation(2, "note", "", serde_json::json!({})).unwrap();
+        sorted.write_record(3, None, "Insn", 30, "c", "", None).unwrap();
+
+        // Clock 30 moved the window past everything but itself
+        let err = sorted.write_event(1, "late", "", 7, None).unwrap_err();
+        assert!(err.to_string().contains("after clock 9"), "{}", err);
+        sorted.finish().unwrap();
+        drop(writer);
+
+        let text = String::from_utf8(sink.0.take()).unwrap();
+        let lines: Vec<serde_json::Value> = text.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        let order: Vec<(Option<i64>, &str)> =
+            lines.iter().map(|line| (line["clk"].as_i64(), line["type"].as_str().unwrap())).collect();
+        assert_eq!(
+            order,
+            vec![
+                (Some(0), "record"),
+                (Some(2), "record"),
+                (Some(2), "event"),
+                (Some(8), "event"),
+                (Some(9), "record_end"),
+                (None, "annotation"),
+                (Some(30), "record"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_drop_writes_held_back_lines() {
+        let sink = Sink::default();
+        let mut writer = TraceWriter::from_writer(Box::new(sink.clone()));
+        {
+            let mut sorted = SortedTraceWriter::new(&mut writer, 100);
+            sorted.write_record(1, None, "Insn", 5, "a", "", None).unwrap();
+            sorted.write_record(2, None, "Insn", 3, "b", "", None).unwrap();
+            // Dropped without finish() while both lines are in the window
+        }
+        drop(writer);
+
+        let text = String::from_utf8(sink.0.take()).unwrap();
+        let clocks: Vec<i64> = text
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["clk"].as_i64().unwrap())
+            .collect();
+        assert_eq!(clocks, vec![3, 5]);
+    }
+}
@@ -0,0 +1,119 @@
+//! Keyboard focus state management.
+//!
+//! F6 and Shift+F6 move keyboard focus between the docked panels in a fixed
+//! logical order (tree, timeline, details, statistics) regardless of where
+//! they are docked; Tab then walks the widgets inside the panel. A focus
+//! outline is drawn only while the user navigates with the keyboard, so
+//! clicking around does not leave rings on buttons.
+
+use crate::state::{DockLayout, DockPanel};
+
+/// State related to keyboard focus.
+///
+/// Responsibilities:
+/// - Tracking which panel F6 focused last
+/// - Holding a pending request to focus a panel
+/// - Tracking whether focus outlines should be visible
+#[derive(Debug, Clone, Default)]
+pub struct FocusState {
+    /// Panel that last received focus through F6 or Tab
+    focused_panel: Option<DockPanel>,
+    /// Panel whose tab should take focus on the next frame
+    focus_request: Option<DockPanel>,
+    /// Keyboard navigation is in progress (show focus outlines)
+    focus_visible: bool,
+}
+
+impl FocusState {
+    /// Creates a state with no focused panel.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // ===== Queries =====
+
+    /// Returns true if focus outlines should be drawn.
+    pub fn focus_visible(&self) -> bool {
+        self.focus_visible
+    }
+
+    // ===== Mutations =====
+
+    /// Updates focus visibility from this frame's input.
+    ///
+    /// # Arguments
+    /// * `navigated` - A focus navigation key (Tab, F6) was pressed
+    /// * `pointer_pressed` - A mouse button was pressed
+    pub fn on_input(&mut self, navigated: bool, pointer_pressed: bool) {
+        if navigated {
+            self.focus_visible = true;
+        } else if pointer_pressed {
+            self.focus_visible = false;
+        }
+    }
+
+    /// Requests focus for the next (or previous) docked panel in logical order.
+    ///
+    /// Returns the panel chosen, if any panel is docked.
+    pub fn focus_next_panel(&mut self, layout: &DockLayout, backwards: bool) -> Option<DockPanel> {
+        let docked: Vec<DockPanel> = DockPanel::ALL.into_iter().filter(|&p| layout.slot_of(p).is_some()).collect();
+        if docked.is_empty() {
+            return None;
+        }
+        let current = self.focused_panel.and_then(|panel| docked.iter().position(|&p| p == panel));
+        let next = match (current, backwards) {
+            (None, false) => 0,
+            (None, true) => docked.len() - 1,
+            (Some(index), false) => (index + 1) % docked.len(),
+            (Some(index), true) => (index + docked.len() - 1) % docked.len(),
+        };
+        self.focus_request = Some(docked[next]);
+        self.focused_panel = Some(docked[next]);
+        self.focus_visible = true;
+        Some(docked[next])
+    }
+
+    /// Takes the pending focus request if it is for this panel.
+    pub fn take_focus_request(&mut self, panel: DockPanel) -> bool {
+        if self.focus_request == Some(panel) {
+            self.focus_request = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Records that a widget of this panel has keyboard focus.
+    pub fn set_focused_panel(&mut self, panel: DockPanel) {
+        self.focused_panel = Some(panel);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::DockSlot;
+
+    #[test]
+    fn test_focus_cycles_in_logical_order() {
+        let mut layout = DockLayout::default();
+        // Docking the timeline before the tree does not change the order
+        layout.move_panel(DockPanel::Timeline, DockSlot::Left);
+        layout.close_panel(DockPanel::Statistics);
+
+        let mut focus = FocusState::new();
+        assert_eq!(focus.focus_next_panel(&layout, false), Some(DockPanel::Tree));
+        assert_eq!(focus.focus_next_panel(&layout, false), Some(DockPanel::Timeline));
+        assert_eq!(focus.focus_next_panel(&layout, false), Some(DockPanel::Details));
+        assert_eq!(focus.focus_next_panel(&layout, false), Some(DockPanel::Tree));
+        assert_eq!(focus.focus_next_panel(&layout, true), Some(DockPanel::Details));
+
+        assert!(!focus.take_focus_request(DockPanel::Tree));
+        assert!(focus.take_focus_request(DockPanel::Details));
+        assert!(!focus.take_focus_request(DockPanel::Details));
+
+        assert!(focus.focus_visible());
+        focus.on_input(false, true);
+        assert!(!focus.focus_visible());
+    }
+}
@@ -0,0 +1,75 @@
+//! Clock stride filter state management.
+//!
+//! Clock-domain problems often show up on one phase of a divided clock only,
+//! e.g. every other cycle. The filter keeps the leaf records whose start clock
+//! satisfies `(clk - offset) % stride == 0`. The stride and offset are kept
+//! when a new trace is loaded; the filter itself is switched off.
+
+use crate::domain::visibility::ClockStride;
+
+/// Smallest stride that filters anything.
+pub const MIN_CLOCK_STRIDE: i64 = 2;
+
+/// State related to the clock stride filter.
+///
+/// Responsibilities:
+/// - Tracking whether the filter is enabled
+/// - Tracking the stride and offset edited in the header popover
+#[derive(Debug, Clone)]
+pub struct ClockFilterState {
+    /// Whether the filter applies to tree and timeline
+    enabled: bool,
+    /// Clock period to keep one phase of
+    stride: i64,
+    /// Clock phase to keep
+    offset: i64,
+}
+
+impl Default for ClockFilterState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClockFilterState {
+    /// Creates a disabled filter keeping even clocks.
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            stride: MIN_CLOCK_STRIDE,
+            offset: 0,
+        }
+    }
+
+    /// Switches the filter off for a new trace, keeping stride and offset.
+    pub fn clear(&mut self) {
+        self.enabled = false;
+    }
+
+    // ===== Queries =====
+
+    /// Returns true if the filter is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns the clock stride to filter by, or None while the filter is off.
+    pub fn stride(&self) -> Option<ClockStride> {
+        self.enabled.then_some(ClockStride {
+            stride: self.stride,
+            offset: self.offset,
+        })
+    }
+
+    // ===== Mutations =====
+
+    /// Enables or disables the filter.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Returns mutable access to the stride and offset for editing.
+    pub fn stride_and_offset_mut(&mut self) -> (&mut i64, &mut i64) {
+        (&mut self.stride, &mut self.offset)
+    }
+}
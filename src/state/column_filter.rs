@@ -0,0 +1,79 @@
+//! Tree column filter state management.
+//!
+//! Holds the filters edited in the table header popovers and the value lists
+//! shown for text columns. Value lists are counted once per trace, on the
+//! first popover opened for a column. Both are dropped when a new trace is
+//! loaded.
+
+use std::collections::HashMap;
+
+use rjets::DynTraceData;
+
+use crate::domain::column_filters::{self, ColumnFilter, ColumnFilters, TreeColumn, MAX_VALUE_LIST_LEN};
+
+/// State related to the tree column filters.
+///
+/// Responsibilities:
+/// - Tracking the active filter of each column
+/// - Caching the distinct values of text columns for the popovers
+#[derive(Debug, Clone, Default)]
+pub struct ColumnFilterState {
+    /// Active filters applied to tree and timeline
+    filters: ColumnFilters,
+    /// Distinct values with record counts per text column (None if there are
+    /// too many for a value list)
+    value_lists: HashMap<TreeColumn, Option<Vec<(String, usize)>>>,
+}
+
+impl ColumnFilterState {
+    /// Creates a state without filters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drops filters and value lists for a new trace.
+    pub fn clear(&mut self) {
+        self.filters.clear_all();
+        self.value_lists.clear();
+    }
+
+    // ===== Queries =====
+
+    /// Returns the active filters.
+    pub fn filters(&self) -> &ColumnFilters {
+        &self.filters
+    }
+
+    /// Returns true if the column has an active filter.
+    pub fn is_filtered(&self, column: TreeColumn) -> bool {
+        self.filters.get(column).is_some()
+    }
+
+    // ===== Mutations =====
+
+    /// Sets the filter of a column (a filter that hides nothing clears it).
+    pub fn set_filter(&mut self, column: TreeColumn, filter: ColumnFilter) {
+        self.filters.set(column, filter);
+    }
+
+    /// Removes the filter of a column.
+    pub fn clear_filter(&mut self, column: TreeColumn) {
+        self.filters.clear(column);
+    }
+
+    /// Removes all filters, keeping the value lists.
+    pub fn clear_filters(&mut self) {
+        self.filters.clear_all();
+    }
+
+    /// Returns the value list of a text column, counting it on first use.
+    ///
+    /// # Returns
+    /// None for numeric columns and columns with too many distinct values
+    pub fn value_list(&mut self, trace: &DynTraceData, column: TreeColumn) -> Option<&[(String, usize)]> {
+        self.value_lists
+            .entry(column)
+            .or_insert_with(|| column_filters::distinct_values(trace, column, MAX_VALUE_LIST_LEN))
+            .as_deref()
+    }
+}
@@ -0,0 +1,84 @@
+//! Command palette state management.
+//!
+//! The palette is an overlay listing every registered action, filtered by
+//! a fuzzy query as the user types. Only the overlay state lives here; the
+//! actions themselves are in the action registry.
+
+/// State related to the command palette overlay.
+///
+/// Responsibilities:
+/// - Tracking whether the palette is open
+/// - Holding the query text
+/// - Tracking the highlighted entry of the filtered list
+#[derive(Debug, Clone, Default)]
+pub struct CommandPaletteState {
+    /// Whether the overlay is shown
+    open: bool,
+    /// Text typed into the search field
+    query: String,
+    /// Index of the highlighted entry in the filtered list
+    highlighted: usize,
+}
+
+impl CommandPaletteState {
+    /// Creates a closed palette with an empty query.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // ===== Queries =====
+
+    /// Returns true if the palette is shown.
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Returns the current query.
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Returns the highlighted entry, clamped to a list of `len` entries.
+    pub fn highlighted(&self, len: usize) -> usize {
+        self.highlighted.min(len.saturating_sub(1))
+    }
+
+    // ===== Mutations =====
+
+    /// Opens the palette with an empty query.
+    pub fn open(&mut self) {
+        self.open = true;
+        self.query.clear();
+        self.highlighted = 0;
+    }
+
+    /// Closes the palette.
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    /// Opens a closed palette and closes an open one.
+    pub fn toggle(&mut self) {
+        if self.open {
+            self.close();
+        } else {
+            self.open();
+        }
+    }
+
+    /// Returns mutable access to the query text (for the search field).
+    pub fn query_mut(&mut self) -> &mut String {
+        &mut self.query
+    }
+
+    /// Highlights the first entry again, e.g. after the query changed.
+    pub fn reset_highlight(&mut self) {
+        self.highlighted = 0;
+    }
+
+    /// Moves the highlight by `delta` entries within a list of `len` entries.
+    pub fn move_highlight(&mut self, delta: isize, len: usize) {
+        let current = self.highlighted(len) as isize;
+        self.highlighted = (current + delta).clamp(0, len.saturating_sub(1) as isize) as usize;
+    }
+}
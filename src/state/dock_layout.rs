@@ -0,0 +1,267 @@
+//! Dock layout state management.
+//!
+//! The main window is divided into four dock slots (left, center, right and
+//! bottom), each holding a list of panels shown as tabs. Users move panels
+//! between slots, save arrangements as named presets and reset to the
+//! default arrangement. Layout and presets are user preferences.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A panel that can be docked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DockPanel {
+    Tree,
+    Timeline,
+    Details,
+    Statistics,
+    Log,
+}
+
+impl DockPanel {
+    /// All dockable panels.
+    pub const ALL: [DockPanel; 5] =
+        [DockPanel::Tree, DockPanel::Timeline, DockPanel::Details, DockPanel::Statistics, DockPanel::Log];
+
+    /// Returns the tab title of the panel.
+    pub fn title(self) -> &'static str {
+        match self {
+            DockPanel::Tree => "Trace Records",
+            DockPanel::Timeline => "Timeline View",
+            DockPanel::Details => "Details",
+            DockPanel::Statistics => "Statistics",
+            DockPanel::Log => "Log",
+        }
+    }
+}
+
+/// A region of the main window panels are docked into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum DockSlot {
+    Left,
+    Center,
+    Right,
+    Bottom,
+}
+
+impl DockSlot {
+    /// All slots, in the order they are laid out (the center goes last).
+    pub const ALL: [DockSlot; 4] = [DockSlot::Bottom, DockSlot::Left, DockSlot::Right, DockSlot::Center];
+
+    /// Returns the menu label of the slot.
+    pub fn label(self) -> &'static str {
+        match self {
+            DockSlot::Left => "Left",
+            DockSlot::Center => "Center",
+            DockSlot::Right => "Right",
+            DockSlot::Bottom => "Bottom",
+        }
+    }
+}
+
+/// Panels per slot, as tabs, plus the active tab of each slot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DockLayout {
+    #[serde(default)]
+    pub left: Vec<DockPanel>,
+    #[serde(default)]
+    pub center: Vec<DockPanel>,
+    #[serde(default)]
+    pub right: Vec<DockPanel>,
+    #[serde(default)]
+    pub bottom: Vec<DockPanel>,
+    /// Active tab per slot; slots not listed show their first tab
+    #[serde(default)]
+    pub active: BTreeMap<DockSlot, DockPanel>,
+}
+
+impl Default for DockLayout {
+    /// Tree left, timeline in the center, details and statistics below; the
+    /// log console starts hidden.
+    fn default() -> Self {
+        Self {
+            left: vec![DockPanel::Tree],
+            center: vec![DockPanel::Timeline],
+            right: Vec::new(),
+            bottom: vec![DockPanel::Details, DockPanel::Statistics],
+            active: BTreeMap::new(),
+        }
+    }
+}
+
+impl DockLayout {
+    /// Returns the panels docked in a slot.
+    pub fn panels(&self, slot: DockSlot) -> &[DockPanel] {
+        match slot {
+            DockSlot::Left => &self.left,
+            DockSlot::Center => &self.center,
+            DockSlot::Right => &self.right,
+            DockSlot::Bottom => &self.bottom,
+        }
+    }
+
+    fn panels_mut(&mut self, slot: DockSlot) -> &mut Vec<DockPanel> {
+        match slot {
+            DockSlot::Left => &mut self.left,
+            DockSlot::Center => &mut self.center,
+            DockSlot::Right => &mut self.right,
+            DockSlot::Bottom => &mut self.bottom,
+        }
+    }
+
+    /// Returns the slot a panel is docked in (None if closed).
+    pub fn slot_of(&self, panel: DockPanel) -> Option<DockSlot> {
+        DockSlot::ALL.into_iter().find(|&slot| self.panels(slot).contains(&panel))
+    }
+
+    /// Returns the panel shown in a slot, if the slot holds any.
+    pub fn active_panel(&self, slot: DockSlot) -> Option<DockPanel> {
+        let panels = self.panels(slot);
+        self.active
+            .get(&slot)
+            .copied()
+            .filter(|panel| panels.contains(panel))
+            .or_else(|| panels.first().copied())
+    }
+
+    /// Shows a panel's tab in its slot.
+    pub fn activate(&mut self, panel: DockPanel) {
+        if let Some(slot) = self.slot_of(panel) {
+            self.active.insert(slot, panel);
+        }
+    }
+
+    /// Moves a panel to the end of a slot's tabs and shows it there.
+    pub fn move_panel(&mut self, panel: DockPanel, slot: DockSlot) {
+        self.close_panel(panel);
+        self.panels_mut(slot).push(panel);
+        self.activate(panel);
+    }
+
+    /// Removes a panel from the layout.
+    pub fn close_panel(&mut self, panel: DockPanel) {
+        for slot in DockSlot::ALL {
+            self.panels_mut(slot).retain(|&p| p != panel);
+        }
+    }
+
+    /// Drops duplicate panels (e.g. from a hand-edited preset), keeping the first.
+    fn normalize(&mut self) {
+        let mut seen = Vec::new();
+        for slot in DockSlot::ALL {
+            self.panels_mut(slot).retain(|&panel| {
+                let first = !seen.contains(&panel);
+                seen.push(panel);
+                first
+            });
+        }
+    }
+}
+
+/// State related to the dock layout.
+///
+/// Responsibilities:
+/// - Holding the current panel arrangement
+/// - Storing named layout presets
+/// - Managing the preset name input buffer
+#[derive(Debug, Clone, Default)]
+pub struct DockState {
+    /// Current arrangement
+    layout: DockLayout,
+    /// Saved arrangements by name
+    presets: BTreeMap<String, DockLayout>,
+    /// Text buffer for the "save preset" input
+    preset_name_input: String,
+}
+
+impl DockState {
+    /// Creates a state with the default layout and no presets.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // ===== Queries =====
+
+    /// Returns the current layout.
+    pub fn layout(&self) -> &DockLayout {
+        &self.layout
+    }
+
+    /// Returns the saved presets.
+    pub fn presets(&self) -> &BTreeMap<String, DockLayout> {
+        &self.presets
+    }
+
+    // ===== Mutations =====
+
+    /// Returns the current layout for editing.
+    pub fn layout_mut(&mut self) -> &mut DockLayout {
+        &mut self.layout
+    }
+
+    /// Replaces the current layout (e.g. when restoring settings).
+    pub fn set_layout(&mut self, mut layout: DockLayout) {
+        layout.normalize();
+        self.layout = layout;
+    }
+
+    /// Restores the default layout.
+    pub fn reset_layout(&mut self) {
+        self.layout = DockLayout::default();
+    }
+
+    /// Replaces all presets (e.g. when restoring settings).
+    pub fn set_presets(&mut self, presets: BTreeMap<String, DockLayout>) {
+        self.presets = presets;
+    }
+
+    /// Saves the current layout under a name (ignored if blank).
+    pub fn save_preset(&mut self, name: &str) {
+        let name = name.trim();
+        if !name.is_empty() {
+            self.presets.insert(name.to_string(), self.layout.clone());
+        }
+    }
+
+    /// Switches to a saved preset.
+    pub fn apply_preset(&mut self, name: &str) {
+        if let Some(layout) = self.presets.get(name).cloned() {
+            self.set_layout(layout);
+        }
+    }
+
+    /// Deletes a saved preset.
+    pub fn delete_preset(&mut self, name: &str) {
+        self.presets.remove(name);
+    }
+
+    /// Returns a mutable reference to the preset name input buffer.
+    pub fn preset_name_input_mut(&mut self) -> &mut String {
+        &mut self.preset_name_input
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_panel_between_slots() {
+        let mut dock = DockState::new();
+        assert_eq!(dock.layout().active_panel(DockSlot::Bottom), Some(DockPanel::Details));
+
+        dock.layout_mut().move_panel(DockPanel::Statistics, DockSlot::Right);
+        assert_eq!(dock.layout().slot_of(DockPanel::Statistics), Some(DockSlot::Right));
+        assert_eq!(dock.layout().panels(DockSlot::Bottom), &[DockPanel::Details]);
+
+        dock.save_preset("wide");
+        dock.layout_mut().move_panel(DockPanel::Tree, DockSlot::Center);
+        assert_eq!(dock.layout().active_panel(DockSlot::Center), Some(DockPanel::Tree));
+        assert!(dock.layout().panels(DockSlot::Left).is_empty());
+
+        dock.apply_preset("wide");
+        assert_eq!(dock.layout().slot_of(DockPanel::Tree), Some(DockSlot::Left));
+        dock.reset_layout();
+        assert_eq!(dock.layout(), &DockLayout::default());
+    }
+}
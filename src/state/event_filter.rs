@@ -0,0 +1,38 @@
+//! Event filter state management.
+//!
+//! Error events are easily lost among thousands of info events. The severity
+//! filter hides event markers below a minimum severity on the timeline;
+//! records are not affected.
+
+use crate::domain::severity::Severity;
+
+/// State related to filtering timeline events.
+///
+/// Responsibilities:
+/// - Tracking the minimum severity of shown events
+#[derive(Debug, Clone, Default)]
+pub struct EventFilterState {
+    /// Events below this severity are hidden
+    min_severity: Severity,
+}
+
+impl EventFilterState {
+    /// Creates a filter showing all events.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // ===== Queries =====
+
+    /// Returns the minimum severity of shown events.
+    pub fn min_severity(&self) -> Severity {
+        self.min_severity
+    }
+
+    // ===== Mutations =====
+
+    /// Sets the minimum severity of shown events.
+    pub fn set_min_severity(&mut self, severity: Severity) {
+        self.min_severity = severity;
+    }
+}
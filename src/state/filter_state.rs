@@ -0,0 +1,104 @@
+//! Event type filter state management.
+//!
+//! Pipeline traces carry many events per record (F1, F2, DC, EX, WB, ...),
+//! and usually only a few stages matter at a time. Event names hidden here
+//! are left out of the timeline markers and the details panel event list;
+//! records are not affected. "Show only" is stored as hiding every other
+//! known name.
+
+use crate::domain::event_visibility::EventTypeFilterStrategy;
+use crate::domain::severity::Severity;
+use std::collections::BTreeSet;
+
+/// State related to filtering events by name.
+///
+/// Responsibilities:
+/// - Tracking hidden event names
+/// - Event filter window visibility
+#[derive(Debug, Clone, Default)]
+pub struct FilterState {
+    /// Event names not shown
+    hidden_event_names: BTreeSet<String>,
+    /// Whether the event filter window is open
+    open: bool,
+}
+
+impl FilterState {
+    /// Creates a filter showing all events.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // ===== Queries =====
+
+    /// Returns the hidden event names.
+    pub fn hidden_event_names(&self) -> &BTreeSet<String> {
+        &self.hidden_event_names
+    }
+
+    /// Returns true if events with this name are shown.
+    pub fn is_event_name_visible(&self, name: &str) -> bool {
+        !self.hidden_event_names.contains(name)
+    }
+
+    /// Returns the event strategy combining hidden names with a severity floor.
+    pub fn event_strategy(&self, min_severity: Severity) -> EventTypeFilterStrategy<'_> {
+        EventTypeFilterStrategy::new(&self.hidden_event_names, min_severity)
+    }
+
+    /// Returns whether the event filter window is open.
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    // ===== Mutations =====
+
+    /// Shows or hides events with this name.
+    pub fn set_event_name_visible(&mut self, name: &str, visible: bool) {
+        if visible {
+            self.hidden_event_names.remove(name);
+        } else {
+            self.hidden_event_names.insert(name.to_string());
+        }
+    }
+
+    /// Shows only the `shown` names among `known` (e.g. only EX and WB).
+    pub fn show_only_event_names<'n>(&mut self, known: impl IntoIterator<Item = &'n str>, shown: &[&str]) {
+        self.hidden_event_names = known
+            .into_iter()
+            .filter(|name| !shown.contains(name))
+            .map(str::to_string)
+            .collect();
+    }
+
+    /// Shows every event again.
+    pub fn show_all_events(&mut self) {
+        self.hidden_event_names.clear();
+    }
+
+    /// Opens or closes the event filter window.
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_show_only_hides_the_other_known_names() {
+        let mut state = FilterState::new();
+        state.set_event_name_visible("F1", false);
+        state.set_event_name_visible("F2", false);
+        assert!(!state.is_event_name_visible("F1") && state.is_event_name_visible("EX"));
+
+        state.show_only_event_names(["F1", "F2", "EX", "WB"], &["EX", "WB"]);
+        assert_eq!(state.hidden_event_names().iter().collect::<Vec<_>>(), ["F1", "F2"]);
+        state.show_only_event_names(["F1", "F2", "EX", "WB"], &["EX"]);
+        assert!(!state.is_event_name_visible("WB"));
+
+        state.show_all_events();
+        assert!(state.hidden_event_names().is_empty());
+    }
+}
@@ -0,0 +1,92 @@
+//! Flame graph mode state management.
+//!
+//! With the mode on, the timeline panel shows a flame graph of the selected
+//! subtree (see [`crate::domain::flame_graph`]) instead of the timeline. The
+//! graph is built on a background thread and cached for the record it was
+//! built for, since aggregating a large subtree takes too long for a frame.
+
+use crate::domain::flame_graph::FlameGraph;
+
+/// State related to the flame graph mode.
+///
+/// Responsibilities:
+/// - Tracking whether the timeline panel shows the flame graph
+/// - Caching the graph of the last selected subtree
+/// - Tracking the record whose graph is being built
+/// - Tracking the frame zoomed into
+#[derive(Debug, Clone, Default)]
+pub struct FlameGraphState {
+    /// Whether the timeline panel shows the flame graph
+    enabled: bool,
+    /// Graph of the last selected subtree
+    graph: Option<FlameGraph>,
+    /// Record whose graph is being built in the background
+    building: Option<u64>,
+    /// Index of the frame zoomed into
+    focus: Option<usize>,
+}
+
+impl FlameGraphState {
+    /// Creates the state with the mode off.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drops the cached graph (e.g. when a new trace is loaded); the mode stays as is.
+    pub fn clear(&mut self) {
+        self.graph = None;
+        self.building = None;
+        self.focus = None;
+    }
+
+    // ===== Queries =====
+
+    /// Returns true if the timeline panel shows the flame graph.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns the cached graph if it was built for `root_id`.
+    pub fn graph_for(&self, root_id: u64) -> Option<&FlameGraph> {
+        self.graph.as_ref().filter(|graph| graph.root_id == root_id)
+    }
+
+    /// Returns the last built graph, whichever record it was built for.
+    pub fn graph(&self) -> Option<&FlameGraph> {
+        self.graph.as_ref()
+    }
+
+    /// Returns true if the graph of `root_id` is being built.
+    pub fn is_building(&self, root_id: u64) -> bool {
+        self.building == Some(root_id)
+    }
+
+    /// Returns the index of the frame zoomed into.
+    pub fn focus(&self) -> Option<usize> {
+        self.focus
+    }
+
+    // ===== Mutations =====
+
+    /// Shows or hides the flame graph.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Marks the graph of `root_id` as being built.
+    pub fn set_building(&mut self, root_id: u64) {
+        self.building = Some(root_id);
+    }
+
+    /// Caches a newly built graph and shows it whole.
+    pub fn set_graph(&mut self, graph: FlameGraph) {
+        self.building = self.building.filter(|&root_id| root_id != graph.root_id);
+        self.graph = Some(graph);
+        self.focus = None;
+    }
+
+    /// Zooms into a frame; the root (or `None`) shows the whole graph.
+    pub fn set_focus(&mut self, focus: Option<usize>) {
+        self.focus = focus.filter(|&index| index > 0);
+    }
+}
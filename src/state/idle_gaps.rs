@@ -0,0 +1,90 @@
+//! Idle gap finder state management.
+//!
+//! The idle gap window lists the longest intervals without active leaf
+//! records under a subtree (see [`crate::domain::idle_gaps`]). The list
+//! belongs to the loaded trace and is cleared with it.
+
+use crate::domain::idle_gaps::{IdleGap, IdleGapSearch};
+
+/// State related to the idle gap window.
+///
+/// Responsibilities:
+/// - Tracking whether the window is open
+/// - Holding the searched subtree and the gaps found under it
+/// - Tracking the gap last jumped to
+#[derive(Debug, Clone, Default)]
+pub struct IdleGapState {
+    /// Whether the window is shown
+    open: bool,
+    /// Searched subtree root (ID and name)
+    root: Option<(u64, String)>,
+    /// Gaps found, longest first
+    gaps: Vec<IdleGap>,
+    /// Index of the gap the viewport was last moved to
+    current: Option<usize>,
+}
+
+impl IdleGapState {
+    /// Creates a closed window with no results.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drops the results (e.g. when a new trace is loaded); the window stays open.
+    pub fn clear(&mut self) {
+        self.root = None;
+        self.gaps.clear();
+        self.current = None;
+    }
+
+    // ===== Queries =====
+
+    /// Returns true if the window is shown.
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Returns the searched subtree root as (ID, name), if any.
+    pub fn root(&self) -> Option<(u64, &str)> {
+        self.root.as_ref().map(|(id, name)| (*id, name.as_str()))
+    }
+
+    /// Returns the gaps found, longest first.
+    pub fn gaps(&self) -> &[IdleGap] {
+        &self.gaps
+    }
+
+    /// Returns the searched subtree and its gaps, if a search has run.
+    pub fn search(&self) -> Option<IdleGapSearch> {
+        let (_, root_name) = self.root.as_ref()?;
+        Some(IdleGapSearch {
+            root_name: root_name.clone(),
+            gaps: self.gaps.clone(),
+        })
+    }
+
+    /// Returns the index of the gap last jumped to.
+    pub fn current(&self) -> Option<usize> {
+        self.current
+    }
+
+    // ===== Mutations =====
+
+    /// Shows or hides the window.
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    /// Stores the gaps found under a subtree and opens the window.
+    pub fn set_results(&mut self, root_id: u64, root_name: String, gaps: Vec<IdleGap>) {
+        self.root = Some((root_id, root_name));
+        self.gaps = gaps;
+        self.current = None;
+        self.open = true;
+    }
+
+    /// Marks a gap as the one the viewport shows.
+    pub fn set_current(&mut self, index: usize) {
+        self.current = Some(index);
+    }
+}
@@ -0,0 +1,407 @@
+//! Input settings state management.
+//!
+//! Users disagree on what double-click and modifier-click should do, on
+//! which way the wheel should move the timeline, whether it pans time or scrolls rows and on how eager tooltips are. Whether open
+//! records get inferred ends on load, how much of the tree starts expanded, how the time axis is drawn, how record names are aliased and how numeric columns are written are kept here too. These preferences are kept
+//! in one serializable struct so they persist as a single setting.
+
+use crate::domain::name_aliases::NameAliases;
+use crate::domain::number_format::ColumnFormats;
+use rjets::ParseOptions;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// Lowest and highest wheel zoom sensitivity offered in the settings dialog.
+pub const ZOOM_SENSITIVITY_RANGE: std::ops::RangeInclusive<f32> = 0.25..=4.0;
+
+/// Shortest and longest record tooltip delay offered in the settings dialog.
+pub const TOOLTIP_DELAY_RANGE_MS: std::ops::RangeInclusive<u32> = 0..=2000;
+
+/// Shallowest and deepest initial expansion depth offered in the settings dialog.
+pub const INITIAL_EXPAND_DEPTH_RANGE: std::ops::RangeInclusive<usize> = 1..=16;
+
+/// Smallest and largest tree and details text size offered in the settings
+/// dialog; the largest still fits the shared tree/timeline row height.
+pub const TREE_FONT_SIZE_RANGE: std::ops::RangeInclusive<f32> = 9.0..=18.0;
+
+/// Tree text size the tree rows were designed for.
+pub const DEFAULT_TREE_FONT_SIZE: f32 = 13.0;
+
+/// Wheel zoom factor per scroll unit at sensitivity 1.0.
+const WHEEL_ZOOM_STEP: f32 = 0.002;
+
+/// Mouse gestures on a record (tree row or timeline bar) with a configurable action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClickGesture {
+    DoubleClick,
+    CtrlClick,
+}
+
+/// What a configurable gesture does to the record under the pointer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClickAction {
+    /// Only select the record
+    SelectOnly,
+    /// Expand or collapse the record's children
+    ToggleExpand,
+    /// Fit the timeline viewport to the record
+    ZoomToRecord,
+    /// Add the record to the multi-selection or remove it
+    ToggleSelection,
+}
+
+impl ClickAction {
+    pub const ALL: [ClickAction; 4] = [Self::SelectOnly, Self::ToggleExpand, Self::ZoomToRecord, Self::ToggleSelection];
+
+    /// Label shown in the settings dialog.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::SelectOnly => "Select only",
+            Self::ToggleExpand => "Expand / collapse",
+            Self::ZoomToRecord => "Zoom to record",
+            Self::ToggleSelection => "Add to / remove from selection",
+        }
+    }
+}
+
+/// Direction the timeline moves for a wheel scroll.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WheelOrientation {
+    /// Wheel down pans to later clocks, wheel up zooms in
+    Standard,
+    /// Wheel down pans to earlier clocks, wheel up zooms out
+    Inverted,
+}
+
+/// What a wheel scroll over the timeline does.
+///
+/// Shift+wheel does the other one. Over the tree the plain wheel always
+/// scrolls rows and Shift+wheel pans time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WheelAction {
+    /// Move the viewport to earlier or later clocks
+    PanTime,
+    /// Scroll the rows shared with the tree
+    ScrollRows,
+}
+
+impl WheelAction {
+    pub const ALL: [WheelAction; 2] = [Self::PanTime, Self::ScrollRows];
+
+    /// Label shown in the settings dialog.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::PanTime => "Pan time",
+            Self::ScrollRows => "Scroll rows",
+        }
+    }
+
+    /// Returns the other action (what Shift+wheel does).
+    pub fn swapped(self) -> Self {
+        match self {
+            Self::PanTime => Self::ScrollRows,
+            Self::ScrollRows => Self::PanTime,
+        }
+    }
+}
+
+/// What the vertical extent of a Ctrl+drag region selection does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RegionRowMode {
+    /// Only the horizontal extent is used (zoom to the clock range)
+    TimeOnly,
+    /// Also scroll the first selected row to the top
+    ScrollToRows,
+    /// Also limit the tree and timeline to the selected rows until unpinned
+    PinRows,
+}
+
+impl RegionRowMode {
+    pub const ALL: [RegionRowMode; 3] = [Self::TimeOnly, Self::ScrollToRows, Self::PinRows];
+
+    /// Label shown in the settings dialog.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::TimeOnly => "Zoom time only",
+            Self::ScrollToRows => "Zoom and scroll to rows",
+            Self::PinRows => "Zoom and pin rows",
+        }
+    }
+}
+
+/// Which tree rows are expanded when a trace has been opened.
+///
+/// Not applied when a reload or session pack restores expansion state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExpansionPolicy {
+    /// Only the roots are shown
+    CollapseAll,
+    /// Levels down to [`InputSettings::initial_expand_depth`] are shown
+    ExpandToDepth,
+    /// The ancestors of the longest record are expanded and it is selected
+    ExpandLongestPath,
+}
+
+impl ExpansionPolicy {
+    pub const ALL: [ExpansionPolicy; 3] = [Self::CollapseAll, Self::ExpandToDepth, Self::ExpandLongestPath];
+
+    /// Label shown in the settings dialog.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::CollapseAll => "Collapse all",
+            Self::ExpandToDepth => "Expand to depth",
+            Self::ExpandLongestPath => "Expand path to longest record",
+        }
+    }
+}
+
+/// How much a record tooltip shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TooltipVerbosity {
+    /// Name, description and timing
+    Minimal,
+    /// Also type, IDs, counts and every attribute
+    Full,
+}
+
+/// Persisted input preferences.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputSettings {
+    pub double_click: ClickAction,
+    pub ctrl_click: ClickAction,
+    pub wheel_orientation: WheelOrientation,
+    /// What the plain wheel does over the timeline
+    pub plain_wheel: WheelAction,
+    /// Multiplier for Ctrl+wheel zoom speed (1.0 = default)
+    pub zoom_sensitivity: f32,
+    /// Use of the vertical extent of a Ctrl+drag region selection
+    pub region_rows: RegionRowMode,
+    /// Hover time before a record tooltip appears
+    pub tooltip_delay_ms: u32,
+    pub tooltip_verbosity: TooltipVerbosity,
+    /// Give records without a `record_end` line an inferred end when loading
+    pub infer_record_ends: bool,
+    /// Tree rows expanded once a trace is opened
+    pub initial_expansion: ExpansionPolicy,
+    /// Levels below the roots shown by [`ExpansionPolicy::ExpandToDepth`]
+    pub initial_expand_depth: usize,
+    /// Fit the viewport to a record expanded in the tree when none of its children are in view
+    pub fit_on_expand: bool,
+    /// Repeat the time axis below the timeline body
+    pub bottom_axis: bool,
+    /// Draw faint vertical lines at the major ticks across the timeline body
+    pub gridlines: bool,
+    /// Mark the trace extent edges and the footer capture end on the timeline
+    pub capture_bounds: bool,
+    /// Rewrites applied to record names where they are drawn
+    pub name_aliases: NameAliases,
+    /// Also apply the name aliases in exported reports
+    pub aliases_in_exports: bool,
+    /// Honor per-type hints (collapse, color, label, events) from the trace header
+    pub type_hints: bool,
+    /// Decimal, hex or SI display of the Start Clock, Duration and ID columns
+    pub column_formats: ColumnFormats,
+    /// Show durations as a percentage of the parent (Duration column) and of the trace (details panel)
+    pub duration_share: bool,
+    /// Text size of the tree rows; the details panel scales along
+    pub tree_font_size: f32,
+}
+
+impl Default for InputSettings {
+    fn default() -> Self {
+        Self {
+            double_click: ClickAction::ToggleExpand,
+            ctrl_click: ClickAction::ToggleSelection,
+            wheel_orientation: WheelOrientation::Standard,
+            plain_wheel: WheelAction::PanTime,
+            zoom_sensitivity: 1.0,
+            region_rows: RegionRowMode::TimeOnly,
+            tooltip_delay_ms: 300,
+            tooltip_verbosity: TooltipVerbosity::Minimal,
+            infer_record_ends: false,
+            initial_expansion: ExpansionPolicy::CollapseAll,
+            initial_expand_depth: 2,
+            fit_on_expand: false,
+            bottom_axis: false,
+            gridlines: false,
+            capture_bounds: true,
+            name_aliases: NameAliases::default(),
+            aliases_in_exports: false,
+            type_hints: true,
+            column_formats: ColumnFormats::default(),
+            duration_share: false,
+            tree_font_size: DEFAULT_TREE_FONT_SIZE,
+        }
+    }
+}
+
+impl InputSettings {
+    /// Returns the action configured for a gesture.
+    pub fn action_for(&self, gesture: ClickGesture) -> ClickAction {
+        match gesture {
+            ClickGesture::DoubleClick => self.double_click,
+            ClickGesture::CtrlClick => self.ctrl_click,
+        }
+    }
+
+    /// Returns what a wheel scroll over the timeline does, Shift swapping the setting.
+    pub fn wheel_action(&self, shift: bool) -> WheelAction {
+        if shift { self.plain_wheel.swapped() } else { self.plain_wheel }
+    }
+
+    /// Applies the wheel orientation to a raw scroll delta.
+    pub fn oriented_scroll(&self, scroll_y: f32) -> f32 {
+        match self.wheel_orientation {
+            WheelOrientation::Standard => scroll_y,
+            WheelOrientation::Inverted => -scroll_y,
+        }
+    }
+
+    /// Returns the options for parsing traces opened from now on.
+    pub fn parse_options(&self) -> ParseOptions {
+        ParseOptions { infer_end: self.infer_record_ends, ..ParseOptions::default() }
+    }
+
+    /// Returns the name to draw for a record named `name`.
+    pub fn display_name<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        self.name_aliases.apply(name)
+    }
+
+    /// Returns the aliases to apply to exported reports, if enabled.
+    pub fn export_aliases(&self) -> Option<NameAliases> {
+        (self.aliases_in_exports && !self.name_aliases.is_empty()).then(|| self.name_aliases.clone())
+    }
+
+    /// Returns the record tooltip delay in seconds.
+    pub fn tooltip_delay_secs(&self) -> f64 {
+        self.tooltip_delay_ms.min(*TOOLTIP_DELAY_RANGE_MS.end()) as f64 / 1000.0
+    }
+
+    /// Returns the tree text size, kept within the offered range.
+    pub fn tree_font_size(&self) -> f32 {
+        self.tree_font_size.clamp(*TREE_FONT_SIZE_RANGE.start(), *TREE_FONT_SIZE_RANGE.end())
+    }
+
+    /// Returns the details panel text scale matching the tree text size.
+    pub fn details_text_scale(&self) -> f32 {
+        self.tree_font_size() / DEFAULT_TREE_FONT_SIZE
+    }
+
+    /// Returns the zoom factor (>1 = zoom in) for a raw Ctrl+wheel scroll delta.
+    pub fn wheel_zoom_factor(&self, scroll_y: f32) -> f32 {
+        let sensitivity = self.zoom_sensitivity.clamp(*ZOOM_SENSITIVITY_RANGE.start(), *ZOOM_SENSITIVITY_RANGE.end());
+        1.0 + self.oriented_scroll(scroll_y) * WHEEL_ZOOM_STEP * sensitivity
+    }
+}
+
+/// State related to input preferences and the settings dialog.
+///
+/// Responsibilities:
+/// - Holding the persisted input preferences
+/// - Tracking whether the settings dialog is open and its alias preview text
+#[derive(Debug, Clone, Default)]
+pub struct InputSettingsState {
+    /// Current preferences
+    settings: InputSettings,
+    /// Whether the settings dialog is shown
+    dialog_open: bool,
+    /// Sample text for the name alias preview in the settings dialog
+    alias_preview: String,
+}
+
+impl InputSettingsState {
+    /// Creates a state with default preferences and the dialog closed.
+    pub fn new() -> Self {
+        Self {
+            settings: InputSettings::default(),
+            dialog_open: false,
+            alias_preview: String::new(),
+        }
+    }
+
+    // ===== Queries =====
+
+    /// Returns the current preferences.
+    pub fn settings(&self) -> &InputSettings {
+        &self.settings
+    }
+
+    /// Returns true if the settings dialog is shown.
+    pub fn is_dialog_open(&self) -> bool {
+        self.dialog_open
+    }
+
+    // ===== Mutations =====
+
+    /// Returns the preferences for editing.
+    pub fn settings_mut(&mut self) -> &mut InputSettings {
+        &mut self.settings
+    }
+
+    /// Replaces the preferences (e.g. when restoring settings).
+    pub fn set_settings(&mut self, settings: InputSettings) {
+        self.settings = settings;
+    }
+
+    /// Returns a mutable reference to the name alias preview text.
+    pub fn alias_preview_mut(&mut self) -> &mut String {
+        &mut self.alias_preview
+    }
+
+    /// Shows or hides the settings dialog.
+    pub fn set_dialog_open(&mut self, open: bool) {
+        self.dialog_open = open;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wheel_zoom_factor_follows_orientation_and_sensitivity() {
+        let mut settings = InputSettings::default();
+        assert!((settings.wheel_zoom_factor(50.0) - 1.1).abs() < 1e-6);
+
+        settings.zoom_sensitivity = 2.0;
+        assert!((settings.wheel_zoom_factor(50.0) - 1.2).abs() < 1e-6);
+
+        settings.wheel_orientation = WheelOrientation::Inverted;
+        assert!((settings.wheel_zoom_factor(50.0) - 0.8).abs() < 1e-6);
+        assert_eq!(settings.oriented_scroll(10.0), -10.0);
+    }
+
+    #[test]
+    fn test_partial_settings_deserialize_with_defaults() {
+        let settings: InputSettings = serde_json::from_str(r#"{"double_click":"ZoomToRecord"}"#).unwrap();
+        assert_eq!(settings.action_for(ClickGesture::DoubleClick), ClickAction::ZoomToRecord);
+        assert_eq!(settings.action_for(ClickGesture::CtrlClick), ClickAction::ToggleSelection);
+        assert_eq!(settings.zoom_sensitivity, 1.0);
+        assert_eq!(settings.region_rows, RegionRowMode::TimeOnly);
+        assert_eq!(settings.plain_wheel, WheelAction::PanTime);
+        assert_eq!(settings.tree_font_size(), DEFAULT_TREE_FONT_SIZE);
+    }
+
+    #[test]
+    fn test_tree_font_size_is_clamped_and_scales_details() {
+        let mut settings = InputSettings::default();
+        assert_eq!(settings.details_text_scale(), 1.0);
+        settings.tree_font_size = 26.0;
+        assert_eq!(settings.tree_font_size(), 18.0);
+        assert!((settings.details_text_scale() - 18.0 / 13.0).abs() < 1e-6);
+        settings.tree_font_size = 1.0;
+        assert_eq!(settings.tree_font_size(), 9.0);
+    }
+
+    #[test]
+    fn test_shift_swaps_wheel_action() {
+        let mut settings = InputSettings::default();
+        assert_eq!(settings.wheel_action(false), WheelAction::PanTime);
+        assert_eq!(settings.wheel_action(true), WheelAction::ScrollRows);
+
+        settings.plain_wheel = WheelAction::ScrollRows;
+        assert_eq!(settings.wheel_action(false), WheelAction::ScrollRows);
+        assert_eq!(settings.wheel_action(true), WheelAction::PanTime);
+    }
+}
@@ -19,6 +19,8 @@ pub struct InteractionState {
     is_selecting_region: bool,
     /// Start position of region selection in screen coordinates
     region_start_pos: Option<egui::Pos2>,
+    /// Time window the drag or region selection started in (see [`crate::state::ViewportState`])
+    gesture_window: usize,
 }
 
 impl InteractionState {
@@ -29,6 +31,7 @@ impl InteractionState {
             drag_start_clk: 0,
             is_selecting_region: false,
             region_start_pos: None,
+            gesture_window: 0,
         }
     }
 
@@ -38,6 +41,7 @@ impl InteractionState {
         self.drag_start_clk = 0;
         self.is_selecting_region = false;
         self.region_start_pos = None;
+        self.gesture_window = 0;
     }
 
     // ===== Drag/Pan State Queries =====
@@ -59,6 +63,18 @@ impl InteractionState {
         self.region_start_pos
     }
 
+    // ===== Time Window Ownership =====
+
+    /// Returns true if a drag or region selection started in another time window.
+    pub fn is_busy_outside(&self, window: usize) -> bool {
+        (self.is_dragging || self.is_selecting_region) && self.gesture_window != window
+    }
+
+    /// Records the time window a drag or region selection started in.
+    pub fn set_gesture_window(&mut self, window: usize) {
+        self.gesture_window = window;
+    }
+
     // ===== Low-Level Accessors (for input handlers) =====
     // These methods provide direct mutable access to internal state
     // for performance-critical input handling code that needs fine-grained control.
@@ -0,0 +1,184 @@
+//! Timeline lane state management.
+//!
+//! Traces with many roots (e.g. one per cluster) are easier to read when
+//! each root is shown as its own collapsible lane and uninteresting roots
+//! can be hidden. Alternatively, rows can be grouped into swimlanes keyed
+//! by an attribute value. This module holds the lane display options, the
+//! root selection and the collapsed swimlanes.
+
+use std::collections::HashSet;
+
+/// State related to root lanes.
+///
+/// Responsibilities:
+/// - Tracking whether roots are drawn as lane header strips
+/// - Tracking which roots are hidden by the root selector
+/// - Tracking the swimlane attribute and collapsed swimlanes
+/// - Managing the root selector and attribute text buffers
+#[derive(Debug, Clone, Default)]
+pub struct LaneState {
+    /// Draw each root as a collapsible lane header strip in the timeline
+    root_lanes: bool,
+    /// Roots excluded by the root selector
+    hidden_roots: HashSet<u64>,
+    /// Text buffer for filtering the root selector list
+    root_filter_text: String,
+    /// Attribute key for swimlane mode (None = tree order)
+    lane_attribute: Option<String>,
+    /// Swimlane keys whose records are hidden
+    collapsed_lanes: HashSet<String>,
+    /// Text buffer for the swimlane attribute input
+    lane_attribute_text: String,
+}
+
+impl LaneState {
+    /// Creates a lane state with lanes disabled and all roots shown.
+    pub fn new() -> Self {
+        Self {
+            root_lanes: false,
+            hidden_roots: HashSet::new(),
+            root_filter_text: String::new(),
+            lane_attribute: None,
+            collapsed_lanes: HashSet::new(),
+            lane_attribute_text: String::new(),
+        }
+    }
+
+    /// Clears per-trace state (root selection, collapsed swimlanes).
+    ///
+    /// The lane options are kept so they apply to the next trace too.
+    pub fn clear(&mut self) {
+        self.hidden_roots.clear();
+        self.root_filter_text.clear();
+        self.collapsed_lanes.clear();
+    }
+
+    // ===== Queries =====
+
+    /// Returns true if roots are drawn as lane header strips.
+    pub fn root_lanes_enabled(&self) -> bool {
+        self.root_lanes
+    }
+
+    /// Returns the set of roots hidden by the root selector.
+    pub fn hidden_roots(&self) -> &HashSet<u64> {
+        &self.hidden_roots
+    }
+
+    /// Returns true if the given root is shown.
+    pub fn is_root_visible(&self, root_id: u64) -> bool {
+        !self.hidden_roots.contains(&root_id)
+    }
+
+    /// Returns the swimlane attribute, if swimlane mode is active.
+    pub fn lane_attribute(&self) -> Option<&str> {
+        self.lane_attribute.as_deref()
+    }
+
+    /// Returns the set of collapsed swimlane keys.
+    pub fn collapsed_lanes(&self) -> &HashSet<String> {
+        &self.collapsed_lanes
+    }
+
+    // ===== Mutations =====
+
+    /// Enables or disables root lanes.
+    pub fn set_root_lanes_enabled(&mut self, enabled: bool) {
+        self.root_lanes = enabled;
+    }
+
+    /// Shows or hides a root.
+    ///
+    /// # Returns
+    /// `true` if the visibility changed.
+    pub fn set_root_visible(&mut self, root_id: u64, visible: bool) -> bool {
+        if visible {
+            self.hidden_roots.remove(&root_id)
+        } else {
+            self.hidden_roots.insert(root_id)
+        }
+    }
+
+    /// Shows all roots.
+    pub fn show_all_roots(&mut self) {
+        self.hidden_roots.clear();
+    }
+
+    /// Replaces the hidden root set (e.g. when restoring a session pack).
+    pub fn set_hidden_roots(&mut self, hidden: impl IntoIterator<Item = u64>) {
+        self.hidden_roots = hidden.into_iter().collect();
+    }
+
+    /// Sets the swimlane attribute (None returns to tree order).
+    ///
+    /// Collapsed lanes are reset because their keys belong to the old attribute.
+    pub fn set_lane_attribute(&mut self, attribute: Option<String>) {
+        let attribute = attribute.filter(|a| !a.trim().is_empty());
+        if attribute != self.lane_attribute {
+            self.lane_attribute = attribute;
+            self.collapsed_lanes.clear();
+        }
+    }
+
+    /// Collapses an expanded swimlane or expands a collapsed one.
+    pub fn toggle_lane_collapsed(&mut self, key: &str) {
+        if !self.collapsed_lanes.remove(key) {
+            self.collapsed_lanes.insert(key.to_string());
+        }
+    }
+
+    /// Returns a mutable reference to the swimlane attribute text buffer.
+    pub fn lane_attribute_text_mut(&mut self) -> &mut String {
+        &mut self.lane_attribute_text
+    }
+
+    /// Returns a mutable reference to the root selector filter text.
+    pub fn root_filter_text_mut(&mut self) -> &mut String {
+        &mut self.root_filter_text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_root_visibility_toggle() {
+        let mut lanes = LaneState::new();
+        assert!(lanes.is_root_visible(1));
+
+        assert!(lanes.set_root_visible(1, false));
+        assert!(!lanes.is_root_visible(1));
+        assert!(!lanes.set_root_visible(1, false));
+
+        assert!(lanes.set_root_visible(1, true));
+        assert!(lanes.is_root_visible(1));
+    }
+
+    #[test]
+    fn test_clear_keeps_lane_option() {
+        let mut lanes = LaneState::new();
+        lanes.set_root_lanes_enabled(true);
+        lanes.set_root_visible(3, false);
+
+        lanes.clear();
+        assert!(lanes.root_lanes_enabled());
+        assert!(lanes.hidden_roots().is_empty());
+    }
+
+    #[test]
+    fn test_lane_attribute_resets_collapsed_lanes() {
+        let mut lanes = LaneState::new();
+        lanes.set_lane_attribute(Some("unit_id".to_string()));
+        lanes.toggle_lane_collapsed("3");
+        assert!(lanes.collapsed_lanes().contains("3"));
+
+        // Same attribute keeps collapse state
+        lanes.set_lane_attribute(Some("unit_id".to_string()));
+        assert!(lanes.collapsed_lanes().contains("3"));
+
+        lanes.set_lane_attribute(Some("  ".to_string()));
+        assert_eq!(lanes.lane_attribute(), None);
+        assert!(lanes.collapsed_lanes().is_empty());
+    }
+}
@@ -0,0 +1,122 @@
+//! Log console state management.
+//!
+//! The log console panel shows the entries collected by the logging
+//! subscriber (see [`rjets::logging`]). The buffer is shared with the
+//! subscriber, so entries logged on loader threads appear here as well.
+
+use rjets::logging::{Level, LogBuffer, LogEntry};
+
+/// State related to the log console panel.
+///
+/// Responsibilities:
+/// - Holding the buffer the logging subscriber writes to
+/// - Managing the minimum level and text filter of the shown entries
+#[derive(Debug, Clone)]
+pub struct LogConsoleState {
+    /// Entries collected by the subscriber
+    buffer: LogBuffer,
+    /// Least severe level shown
+    min_level: Level,
+    /// Case-insensitive text an entry's target or message must contain
+    filter: String,
+}
+
+impl Default for LogConsoleState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LogConsoleState {
+    /// Creates a console with an empty buffer showing info and above.
+    pub fn new() -> Self {
+        Self {
+            buffer: LogBuffer::new(),
+            min_level: Level::INFO,
+            filter: String::new(),
+        }
+    }
+
+    // ===== Queries =====
+
+    /// Returns the least severe level shown.
+    pub fn min_level(&self) -> Level {
+        self.min_level
+    }
+
+    /// Returns the number of entries held, shown or not.
+    pub fn total_entries(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns the entries passing the level and text filters, oldest first.
+    pub fn visible_entries(&self) -> Vec<LogEntry> {
+        let filter = self.filter.to_lowercase();
+        self.buffer
+            .snapshot()
+            .into_iter()
+            .filter(|entry| entry.level <= self.min_level)
+            .filter(|entry| {
+                filter.is_empty()
+                    || entry.message.to_lowercase().contains(&filter)
+                    || entry.target.to_lowercase().contains(&filter)
+            })
+            .collect()
+    }
+
+    // ===== Mutations =====
+
+    /// Replaces the buffer (the one the installed subscriber writes to).
+    pub fn set_buffer(&mut self, buffer: LogBuffer) {
+        self.buffer = buffer;
+    }
+
+    /// Sets the least severe level shown.
+    pub fn set_min_level(&mut self, level: Level) {
+        self.min_level = level;
+    }
+
+    /// Returns a mutable reference to the text filter.
+    pub fn filter_mut(&mut self) -> &mut String {
+        &mut self.filter
+    }
+
+    /// Drops all collected entries.
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn entry(level: Level, message: &str) -> LogEntry {
+        LogEntry {
+            level,
+            target: "rjets::parser".to_string(),
+            message: message.to_string(),
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_visible_entries_filtering() {
+        let buffer = LogBuffer::new();
+        let mut console = LogConsoleState::new();
+        console.set_buffer(buffer.clone());
+
+        buffer.push(entry(Level::ERROR, "trace load failed"));
+        buffer.push(entry(Level::INFO, "trace loaded"));
+        buffer.push(entry(Level::DEBUG, "parsed trace records=10"));
+        assert_eq!(console.visible_entries().len(), 2);
+
+        console.set_min_level(Level::TRACE);
+        *console.filter_mut() = "PARSED".to_string();
+        assert_eq!(console.visible_entries(), vec![entry(Level::DEBUG, "parsed trace records=10")]);
+
+        console.clear();
+        assert_eq!(console.total_entries(), 0);
+    }
+}
@@ -0,0 +1,115 @@
+//! Measurement cursor state management.
+//!
+//! In measurement mode, clicks on the timeline drop cursor A, then cursor B;
+//! the next click starts over with A. The records and events intersecting
+//! the interval between the cursors are counted when a cursor is placed, so
+//! the overlay does not walk the trace every frame. Cursors are clocks and
+//! belong to the loaded trace.
+
+/// State related to the A/B measurement cursors.
+///
+/// Responsibilities:
+/// - Tracking whether clicks on the timeline place cursors
+/// - Holding the cursor clocks in placement order
+/// - Caching the record and event counts of the measured interval
+#[derive(Debug, Clone, Default)]
+pub struct MeasurementState {
+    /// Whether clicks on the timeline place cursors
+    enabled: bool,
+    cursor_a: Option<i64>,
+    cursor_b: Option<i64>,
+    /// Records and events intersecting the interval (set once both cursors are placed)
+    counts: Option<(usize, usize)>,
+}
+
+impl MeasurementState {
+    /// Creates the state with measurement mode off.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Removes both cursors (e.g. when a new trace is loaded); the mode stays as is.
+    pub fn clear(&mut self) {
+        self.cursor_a = None;
+        self.cursor_b = None;
+        self.counts = None;
+    }
+
+    // ===== Queries =====
+
+    /// Returns true if clicks on the timeline place cursors.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns the cursor clocks as (A, B).
+    pub fn cursors(&self) -> (Option<i64>, Option<i64>) {
+        (self.cursor_a, self.cursor_b)
+    }
+
+    /// Returns the measured interval as (start, end), once both cursors are placed.
+    pub fn interval(&self) -> Option<(i64, i64)> {
+        let (a, b) = (self.cursor_a?, self.cursor_b?);
+        Some((a.min(b), a.max(b)))
+    }
+
+    /// Returns the counts of records and events intersecting the interval.
+    pub fn counts(&self) -> Option<(usize, usize)> {
+        self.counts
+    }
+
+    // ===== Mutations =====
+
+    /// Turns measurement mode on or off; turning it off removes the cursors.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.clear();
+        }
+    }
+
+    /// Places cursor A, or cursor B if only A is placed.
+    ///
+    /// Returns the interval to count once both cursors are placed.
+    pub fn place_cursor(&mut self, clk: i64) -> Option<(i64, i64)> {
+        if self.cursor_a.is_some() && self.cursor_b.is_none() {
+            self.cursor_b = Some(clk);
+        } else {
+            self.cursor_a = Some(clk);
+            self.cursor_b = None;
+        }
+        self.counts = None;
+        self.interval()
+    }
+
+    /// Stores the counts of records and events intersecting the interval.
+    pub fn set_counts(&mut self, records: usize, events: usize) {
+        self.counts = Some((records, events));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursors_alternate_and_restart() {
+        let mut measurement = MeasurementState::new();
+        measurement.set_enabled(true);
+
+        assert_eq!(measurement.place_cursor(50), None);
+        assert_eq!(measurement.cursors(), (Some(50), None));
+        assert_eq!(measurement.place_cursor(20), Some((20, 50)), "interval is ordered");
+        measurement.set_counts(3, 7);
+
+        // A third click starts a new measurement
+        assert_eq!(measurement.place_cursor(80), None);
+        assert_eq!(measurement.cursors(), (Some(80), None));
+        assert_eq!(measurement.counts(), None);
+
+        assert_eq!(measurement.place_cursor(i64::MIN), Some((i64::MIN, 80)));
+
+        measurement.set_enabled(false);
+        assert_eq!(measurement.cursors(), (None, None));
+    }
+}
@@ -0,0 +1,135 @@
+//! Mini event timeline state management.
+//!
+//! The details panel draws the selected record's events on their own small
+//! timeline. It is zoomed and panned independently of the main viewport so
+//! the spacing between a record's events can be inspected without losing
+//! the place in the global view. The zoomed range belongs to one record and
+//! is dropped when another record is selected. The details panel flame chart
+//! keeps its zoom in a second instance.
+
+use crate::domain::viewport_operations;
+
+/// State related to the details panel mini timeline.
+///
+/// Responsibilities:
+/// - Tracking the zoomed clock range and which record it belongs to
+/// - Zooming and panning within the record's event extent
+#[derive(Debug, Clone, Default)]
+pub struct MiniTimelineState {
+    /// Record the zoomed range belongs to
+    record_id: Option<u64>,
+    /// Zoomed clock range (None = whole extent)
+    range: Option<(i64, i64)>,
+}
+
+impl MiniTimelineState {
+    /// Creates a mini timeline state showing the whole extent.
+    pub fn new() -> Self {
+        Self {
+            record_id: None,
+            range: None,
+        }
+    }
+
+    // ===== Queries =====
+
+    /// Returns the visible clock range for a record.
+    ///
+    /// # Arguments
+    /// * `record_id` - Record shown in the details panel
+    /// * `extent` - Full clock extent of the record's events
+    pub fn range(&self, record_id: u64, extent: (i64, i64)) -> (i64, i64) {
+        match self.range {
+            Some(range) if self.record_id == Some(record_id) => range,
+            _ => extent,
+        }
+    }
+
+    /// Returns true if the record's timeline is zoomed in.
+    pub fn is_zoomed(&self, record_id: u64) -> bool {
+        self.record_id == Some(record_id) && self.range.is_some()
+    }
+
+    // ===== Mutations =====
+
+    /// Zooms around a clock value (>1 = zoom in), staying inside `extent`.
+    pub fn zoom_around(&mut self, record_id: u64, extent: (i64, i64), zoom_factor: f32, focus_clk: i64) {
+        let (start, end) = self.range(record_id, extent);
+        let old_duration = viewport_operations::clk_span(start, end).max(1.0);
+        let new_duration = (old_duration / zoom_factor as f64).max(1.0);
+        let focus_ratio = (viewport_operations::clk_span(start, focus_clk) / old_duration).clamp(0.0, 1.0);
+
+        let new_start = focus_clk as f64 - new_duration * focus_ratio;
+        self.set_range(record_id, extent, new_start as i64, (new_start + new_duration) as i64);
+    }
+
+    /// Shifts the visible range by `delta_clk`, staying inside `extent`.
+    pub fn pan(&mut self, record_id: u64, extent: (i64, i64), delta_clk: i64) {
+        let (start, end) = self.range(record_id, extent);
+        self.set_range(record_id, extent, start.saturating_add(delta_clk), end.saturating_add(delta_clk));
+    }
+
+    /// Returns to showing the whole extent.
+    pub fn reset(&mut self) {
+        self.record_id = None;
+        self.range = None;
+    }
+
+    /// Stores a range clamped to `extent`; a range covering the extent resets the zoom.
+    fn set_range(&mut self, record_id: u64, extent: (i64, i64), start: i64, end: i64) {
+        // Saturating, so a range spanning most of the clock domain cannot overflow
+        let duration = end.saturating_sub(start).min(extent.1.saturating_sub(extent.0));
+        let start = start.clamp(extent.0, extent.1 - duration);
+        let range = (start, start + duration);
+
+        self.record_id = Some(record_id);
+        self.range = (range != extent).then_some(range);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zoom_is_per_record() {
+        let mut mini = MiniTimelineState::new();
+        mini.zoom_around(1, (0, 1000), 2.0, 500);
+
+        assert_eq!(mini.range(1, (0, 1000)), (250, 750));
+        assert!(mini.is_zoomed(1));
+        // Another record starts from its full extent
+        assert_eq!(mini.range(2, (0, 80)), (0, 80));
+    }
+
+    #[test]
+    fn test_pan_and_zoom_out_stay_in_extent() {
+        let mut mini = MiniTimelineState::new();
+        mini.zoom_around(1, (0, 1000), 4.0, 0);
+        assert_eq!(mini.range(1, (0, 1000)), (0, 250));
+
+        mini.pan(1, (0, 1000), -100);
+        assert_eq!(mini.range(1, (0, 1000)), (0, 250));
+        mini.pan(1, (0, 1000), 2000);
+        assert_eq!(mini.range(1, (0, 1000)), (750, 1000));
+
+        mini.zoom_around(1, (0, 1000), 0.1, 900);
+        assert_eq!(mini.range(1, (0, 1000)), (0, 1000));
+        assert!(!mini.is_zoomed(1));
+    }
+
+    #[test]
+    fn test_pan_and_zoom_at_extreme_clocks() {
+        let mut mini = MiniTimelineState::new();
+        let extent = (i64::MIN, i64::MAX);
+        mini.zoom_around(1, extent, 4.0, 0);
+        let (start, end) = mini.range(1, extent);
+        assert!(start < 0 && end > 0 && start > i64::MIN);
+
+        mini.pan(1, extent, i64::MAX);
+        assert_eq!(mini.range(1, extent).1, i64::MAX);
+        mini.pan(1, extent, i64::MIN);
+        mini.pan(1, extent, i64::MIN);
+        assert_eq!(mini.range(1, extent).0, i64::MIN);
+    }
+}
@@ -1,13 +1,36 @@
 //! State management modules for the JETS trace viewer.
 //!
 //! This module contains state-only logic (no UI concerns):
-//! - Trace state (trace data, file path, trace extent)
+//! - Trace state (trace data, file path, trace extent, background snapshots)
 //! - Viewport state (clock ranges, zoom levels)
 //! - Selection state (selected records, events)
 //! - Tree state (expansion, visibility)
 //! - Interaction state (drag, hover, selection regions)
 //! - Theme state (theme manager, current theme)
 //! - Layout state (split ratios, column widths)
+//! - Notes state (user notes and tags on records)
+//! - Lane state (root lanes, root selector, attribute swimlanes)
+//! - Repaint state (pending repaints, low-power mode)
+//! - Mini timeline state (details panel event timeline zoom)
+//! - Type legend state (record type colors and visibility)
+//! - Clock filter state (clock stride record filter)
+//! - Column filter state (table header value lists and ranges)
+//! - Event filter state (minimum event severity)
+//! - Filter state (hidden event names, event filter window)
+//! - Command palette state (overlay, query, highlighted entry)
+//! - Input settings state (click actions, wheel orientation, zoom sensitivity, tooltips)
+//! - Tooltip state (hover delay tracking, cached content, pinned tooltip)
+//! - Window state (per-monitor window placement)
+//! - Reload state (user state carried over a trace reload)
+//! - Dock layout state (panel arrangement, layout presets)
+//! - Idle gap state (idle gap window and its results)
+//! - Trace health state (health window and the last check's result)
+//! - Type hierarchy state (type hierarchy window and the folded hierarchy)
+//! - Focus state (keyboard focus order between panels, focus outlines)
+//! - Log console state (collected log entries, level and text filter)
+//! - Measurement state (A/B timeline cursors and the counts between them)
+//! - Flame graph state (timeline panel mode, cached graph, zoomed frame)
+//! - Recent files state (recently opened traces, pinned favorites)
 
 mod trace_state;
 mod viewport;
@@ -16,11 +39,61 @@ mod tree_state;
 mod interaction;
 mod theme_state;
 mod layout_state;
+mod notes;
+mod lanes;
+mod repaint;
+mod mini_timeline;
+mod type_legend;
+mod clock_filter;
+mod column_filter;
+mod event_filter;
+mod filter_state;
+mod command_palette;
+mod input_settings;
+mod tooltip;
+mod window_state;
+mod reload;
+mod dock_layout;
+mod idle_gaps;
+mod trace_health;
+mod type_hierarchy;
+mod accessibility;
+mod log_console;
+mod measurement;
+mod flame_graph;
+mod recent_files;
 
-pub use trace_state::TraceState;
-pub use viewport::ViewportState;
+pub use trace_state::{TraceSnapshot, TraceState};
+pub use viewport::{RowAlignment, ScrollRequest, ViewportState, MAIN_WINDOW, SPLIT_WINDOW};
 pub use selection::SelectionState;
-pub use tree_state::{TreeState, SortSpec, SortKey, SortDir};
+pub use tree_state::{ComparatorId, TreeState, SortSpec, SortKey, SortDir};
 pub use interaction::InteractionState;
 pub use theme_state::ThemeState;
 pub use layout_state::LayoutState;
+pub use notes::{NotesState, RecordNote};
+pub use lanes::LaneState;
+pub use repaint::RepaintState;
+pub use mini_timeline::MiniTimelineState;
+pub use type_legend::{TypeLegendState, ValueTrack};
+pub use clock_filter::{ClockFilterState, MIN_CLOCK_STRIDE};
+pub use column_filter::ColumnFilterState;
+pub use event_filter::EventFilterState;
+pub use filter_state::FilterState;
+pub use command_palette::CommandPaletteState;
+pub use input_settings::{
+    ClickAction, ClickGesture, ExpansionPolicy, InputSettings, InputSettingsState, RegionRowMode, TooltipVerbosity,
+    WheelAction, WheelOrientation, DEFAULT_TREE_FONT_SIZE, INITIAL_EXPAND_DEPTH_RANGE, TOOLTIP_DELAY_RANGE_MS, TREE_FONT_SIZE_RANGE,
+    ZOOM_SENSITIVITY_RANGE,
+};
+pub use tooltip::{TooltipContent, TooltipState};
+pub use window_state::WindowState;
+pub use reload::{PreservedUserState, ReloadState};
+pub use dock_layout::{DockLayout, DockPanel, DockSlot, DockState};
+pub use idle_gaps::IdleGapState;
+pub use trace_health::TraceHealthState;
+pub use type_hierarchy::TypeHierarchyState;
+pub use accessibility::FocusState;
+pub use log_console::LogConsoleState;
+pub use measurement::MeasurementState;
+pub use flame_graph::FlameGraphState;
+pub use recent_files::RecentFilesState;
@@ -0,0 +1,119 @@
+//! User notes and tags attached to trace records.
+//!
+//! Notes are free-form text plus a list of short tags keyed by record ID.
+//! They belong to the currently loaded trace and travel with session packs.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A user note attached to a single record.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecordNote {
+    /// Free-form note text
+    #[serde(default)]
+    pub text: String,
+    /// Short labels for grouping findings (e.g. "stall", "bug-123")
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl RecordNote {
+    /// Returns true if the note carries neither text nor tags.
+    pub fn is_empty(&self) -> bool {
+        self.text.trim().is_empty() && self.tags.is_empty()
+    }
+}
+
+/// State holding notes for the loaded trace.
+///
+/// Responsibilities:
+/// - Storing per-record notes and tags
+/// - Dropping notes that become empty
+/// - Bulk replacement when a session pack is opened
+/// - Managing the tag input text buffer
+#[derive(Debug, Clone, Default)]
+pub struct NotesState {
+    /// Notes keyed by record ID (ordered for stable export)
+    notes: BTreeMap<u64, RecordNote>,
+    /// Text buffer for the "add tag" input
+    tag_input: String,
+}
+
+impl NotesState {
+    /// Creates an empty notes state.
+    pub fn new() -> Self {
+        Self {
+            notes: BTreeMap::new(),
+            tag_input: String::new(),
+        }
+    }
+
+    /// Removes all notes.
+    pub fn clear(&mut self) {
+        self.notes.clear();
+        self.tag_input.clear();
+    }
+
+    // ===== Queries =====
+
+    /// Returns the note for a record, if any.
+    pub fn note(&self, record_id: u64) -> Option<&RecordNote> {
+        self.notes.get(&record_id)
+    }
+
+    /// Returns all notes keyed by record ID.
+    pub fn all(&self) -> &BTreeMap<u64, RecordNote> {
+        &self.notes
+    }
+
+    // ===== Mutations =====
+
+    /// Sets the note text for a record, removing the note if it becomes empty.
+    pub fn set_text(&mut self, record_id: u64, text: String) {
+        self.notes.entry(record_id).or_default().text = text;
+        self.prune(record_id);
+    }
+
+    /// Adds a tag to a record (ignored if blank or already present).
+    ///
+    /// # Returns
+    /// `true` if the tag was added.
+    pub fn add_tag(&mut self, record_id: u64, tag: &str) -> bool {
+        let tag = tag.trim();
+        if tag.is_empty() {
+            return false;
+        }
+        let note = self.notes.entry(record_id).or_default();
+        if note.tags.iter().any(|t| t == tag) {
+            return false;
+        }
+        note.tags.push(tag.to_string());
+        true
+    }
+
+    /// Removes a tag from a record, dropping the note if it becomes empty.
+    pub fn remove_tag(&mut self, record_id: u64, tag: &str) {
+        if let Some(note) = self.notes.get_mut(&record_id) {
+            note.tags.retain(|t| t != tag);
+        }
+        self.prune(record_id);
+    }
+
+    /// Replaces all notes (e.g. when opening a session pack).
+    pub fn replace_all(&mut self, notes: BTreeMap<u64, RecordNote>) {
+        self.notes = notes;
+        self.notes.retain(|_, note| !note.is_empty());
+    }
+
+    /// Returns a mutable reference to the tag input buffer.
+    pub fn tag_input_mut(&mut self) -> &mut String {
+        &mut self.tag_input
+    }
+
+    /// Removes the note for a record if it holds nothing.
+    fn prune(&mut self, record_id: u64) {
+        if self.notes.get(&record_id).is_some_and(RecordNote::is_empty) {
+            self.notes.remove(&record_id);
+        }
+    }
+}
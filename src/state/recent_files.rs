@@ -0,0 +1,152 @@
+//! Recently opened trace files.
+//!
+//! The header's Recent menu lists pinned files first, in the order they were
+//! pinned, then the other files most recent first. Only the unpinned part is
+//! capped, so a favorite never drops off the list. The list is persisted
+//! with the other settings.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Most unpinned files remembered.
+pub const MAX_RECENT_FILES: usize = 10;
+
+/// A file in the recent files list.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentFile {
+    pub path: PathBuf,
+    /// Kept at the top of the list and never dropped
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// State related to the recent files list.
+///
+/// Responsibilities:
+/// - Recording opened files, most recent first
+/// - Pinning favorites above the others
+#[derive(Debug, Clone, Default)]
+pub struct RecentFilesState {
+    /// Pinned files, then unpinned files most recent first
+    entries: Vec<RecentFile>,
+}
+
+impl RecentFilesState {
+    /// Creates an empty list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // ===== Queries =====
+
+    /// Returns the files in menu order.
+    pub fn entries(&self) -> &[RecentFile] {
+        &self.entries
+    }
+
+    // ===== Mutations =====
+
+    /// Replaces the list (e.g. with the persisted one), restoring its order.
+    pub fn set_entries(&mut self, entries: Vec<RecentFile>) {
+        self.entries.clear();
+        for entry in entries {
+            if !self.contains(&entry.path) {
+                self.entries.push(entry);
+            }
+        }
+        self.entries.sort_by_key(|entry| !entry.pinned);
+        self.trim();
+    }
+
+    /// Records a file as just opened; a pinned file keeps its place.
+    pub fn record_opened(&mut self, path: &Path) {
+        if self.entries.iter().any(|entry| entry.pinned && entry.path == path) {
+            return;
+        }
+        self.remove(path);
+        let first_unpinned = self.pinned_count();
+        self.entries.insert(first_unpinned, RecentFile { path: path.to_path_buf(), pinned: false });
+        self.trim();
+    }
+
+    /// Pins or unpins a file. A newly pinned file goes below the other
+    /// pinned ones; an unpinned one becomes the most recent file.
+    pub fn toggle_pinned(&mut self, path: &Path) {
+        let Some(index) = self.entries.iter().position(|entry| entry.path == path) else {
+            return;
+        };
+        let mut entry = self.entries.remove(index);
+        entry.pinned = !entry.pinned;
+        let pinned_count = self.pinned_count();
+        self.entries.insert(pinned_count, entry);
+        self.trim();
+    }
+
+    /// Removes a file from the list.
+    pub fn remove(&mut self, path: &Path) {
+        self.entries.retain(|entry| entry.path != path);
+    }
+
+    /// Forgets every file that is not pinned.
+    pub fn clear_unpinned(&mut self) {
+        self.entries.retain(|entry| entry.pinned);
+    }
+
+    fn contains(&self, path: &Path) -> bool {
+        self.entries.iter().any(|entry| entry.path == path)
+    }
+
+    fn pinned_count(&self) -> usize {
+        self.entries.iter().take_while(|entry| entry.pinned).count()
+    }
+
+    /// Drops the oldest unpinned files beyond the cap.
+    fn trim(&mut self) {
+        self.entries.truncate(self.pinned_count() + MAX_RECENT_FILES);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(state: &RecentFilesState) -> Vec<&str> {
+        state.entries().iter().map(|entry| entry.path.to_str().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_pinned_files_stay_on_top_and_are_never_dropped() {
+        let mut state = RecentFilesState::new();
+        for name in ["a", "b", "c"] {
+            state.record_opened(Path::new(name));
+        }
+        assert_eq!(paths(&state), ["c", "b", "a"]);
+
+        state.toggle_pinned(Path::new("a"));
+        state.record_opened(Path::new("b"));
+        state.record_opened(Path::new("a"));
+        assert_eq!(paths(&state), ["a", "b", "c"]);
+        assert!(state.entries()[0].pinned);
+
+        for index in 0..MAX_RECENT_FILES + 5 {
+            state.record_opened(Path::new(&format!("f{}", index)));
+        }
+        assert_eq!(state.entries().len(), MAX_RECENT_FILES + 1);
+        assert_eq!(paths(&state)[..2], ["a", "f14"]);
+
+        // Unpinning makes it the most recent file
+        state.toggle_pinned(Path::new("a"));
+        assert_eq!(paths(&state)[..2], ["a", "f14"]);
+        assert!(!state.entries()[0].pinned);
+        state.clear_unpinned();
+        assert!(state.entries().is_empty());
+
+        // A persisted list is put back in menu order
+        state.set_entries(vec![
+            RecentFile { path: "x".into(), pinned: false },
+            RecentFile { path: "y".into(), pinned: true },
+            RecentFile { path: "x".into(), pinned: true },
+        ]);
+        assert_eq!(paths(&state), ["y", "x"]);
+    }
+}
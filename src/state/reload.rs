@@ -0,0 +1,84 @@
+//! Reload state management.
+//!
+//! Reloading a trace replaces its data, and with it every record ID the user
+//! state refers to. The user state is set aside when the reload starts and
+//! re-resolved against the new data once it arrives (see
+//! [`crate::domain::record_identity`]); what could not be re-matched is listed
+//! until the user dismisses it.
+
+use std::collections::BTreeMap;
+
+use crate::domain::record_identity::RecordIdentity;
+use crate::state::RecordNote;
+
+/// User state set aside while a trace reloads.
+#[derive(Debug, Clone, Default)]
+pub struct PreservedUserState {
+    /// Identities of every record referenced below
+    pub identities: Vec<RecordIdentity>,
+    /// Notes and tags keyed by old record ID
+    pub notes: BTreeMap<u64, RecordNote>,
+    /// Pinned rows by old record ID
+    pub pinned_rows: Vec<u64>,
+    /// Expanded nodes by old record ID
+    pub expanded: Vec<u64>,
+    pub selected_record: Option<u64>,
+    pub selected_event: Option<(u64, i64)>,
+    /// Viewport clock range
+    pub viewport: (i64, i64),
+}
+
+/// State related to reloading the current trace.
+///
+/// Responsibilities:
+/// - Holding the user state of a reload in progress
+/// - Listing the items a finished reload could not re-match
+#[derive(Debug, Clone, Default)]
+pub struct ReloadState {
+    /// User state waiting for the reloaded data
+    pending: Option<PreservedUserState>,
+    /// Descriptions of items lost by the last reload
+    unmatched: Vec<String>,
+}
+
+impl ReloadState {
+    /// Creates a state with no reload in progress.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forgets a reload in progress and its report (e.g. when another trace is opened).
+    pub fn clear(&mut self) {
+        self.pending = None;
+        self.unmatched.clear();
+    }
+
+    // ===== Queries =====
+
+    /// Returns the items the last reload could not re-match.
+    pub fn unmatched(&self) -> &[String] {
+        &self.unmatched
+    }
+
+    // ===== Mutations =====
+
+    /// Sets aside user state until the reloaded data arrives.
+    pub fn set_pending(&mut self, preserved: PreservedUserState) {
+        self.pending = Some(preserved);
+    }
+
+    /// Takes the user state of the reload in progress, if any.
+    pub fn take_pending(&mut self) -> Option<PreservedUserState> {
+        self.pending.take()
+    }
+
+    /// Records the items a finished reload could not re-match.
+    pub fn set_unmatched(&mut self, unmatched: Vec<String>) {
+        self.unmatched = unmatched;
+    }
+
+    /// Dismisses the reload report.
+    pub fn dismiss_report(&mut self) {
+        self.unmatched.clear();
+    }
+}
@@ -0,0 +1,125 @@
+//! Repaint policy state.
+//!
+//! egui already repaints on input, so the viewer only needs to ask for extra
+//! frames when something changes outside an input event (a background load
+//! finishing, a setting that takes effect next frame) or while an animation is
+//! running. Code that changes state records the need for a frame here instead
+//! of calling `request_repaint` directly; the frame loop then issues at most
+//! one request. Low-power mode stretches animation ticks and turns off UI
+//! animations.
+
+use std::time::Duration;
+
+/// Tick interval for running animations (e.g. the loading indicator).
+const ANIMATION_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Tick interval for running animations in low-power mode.
+const LOW_POWER_ANIMATION_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// State related to repaint scheduling.
+///
+/// Responsibilities:
+/// - Tracking whether low-power mode is enabled
+/// - Collecting repaint requests made during a frame
+/// - Tracking whether the policy changed and must be re-applied to egui
+#[derive(Debug, Clone)]
+pub struct RepaintState {
+    /// Reduce repaint frequency and disable UI animations
+    low_power: bool,
+    /// A state change during this frame needs another frame to show
+    pending: bool,
+    /// The policy changed since it was last applied to the egui context
+    policy_dirty: bool,
+}
+
+impl Default for RepaintState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RepaintState {
+    /// Creates a repaint state with low-power mode off.
+    pub fn new() -> Self {
+        Self {
+            low_power: false,
+            pending: false,
+            // Apply the policy on the first frame
+            policy_dirty: true,
+        }
+    }
+
+    // ===== Queries =====
+
+    /// Returns true if low-power mode is enabled.
+    pub fn low_power_enabled(&self) -> bool {
+        self.low_power
+    }
+
+    /// Returns how often a running animation should tick.
+    pub fn animation_interval(&self) -> Duration {
+        if self.low_power {
+            LOW_POWER_ANIMATION_INTERVAL
+        } else {
+            ANIMATION_INTERVAL
+        }
+    }
+
+    // ===== Mutations =====
+
+    /// Enables or disables low-power mode.
+    pub fn set_low_power(&mut self, enabled: bool) {
+        if self.low_power != enabled {
+            self.low_power = enabled;
+            self.policy_dirty = true;
+            self.pending = true;
+        }
+    }
+
+    /// Records that the current frame changed state that is only visible next frame.
+    pub fn request(&mut self) {
+        self.pending = true;
+    }
+
+    /// Returns and clears the pending repaint request.
+    pub fn take_pending(&mut self) -> bool {
+        std::mem::take(&mut self.pending)
+    }
+
+    /// Returns and clears the policy-changed flag.
+    pub fn take_policy_dirty(&mut self) -> bool {
+        std::mem::take(&mut self.policy_dirty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requests_are_coalesced() {
+        let mut repaint = RepaintState::new();
+        assert!(!repaint.take_pending());
+
+        repaint.request();
+        repaint.request();
+        assert!(repaint.take_pending());
+        assert!(!repaint.take_pending());
+    }
+
+    #[test]
+    fn test_low_power_toggle() {
+        let mut repaint = RepaintState::new();
+        assert!(repaint.take_policy_dirty());
+        assert_eq!(repaint.animation_interval(), ANIMATION_INTERVAL);
+
+        repaint.set_low_power(true);
+        assert!(repaint.take_policy_dirty());
+        assert!(repaint.take_pending());
+        assert_eq!(repaint.animation_interval(), LOW_POWER_ANIMATION_INTERVAL);
+
+        // Setting the same value is not a change
+        repaint.set_low_power(true);
+        assert!(!repaint.take_policy_dirty());
+    }
+}
@@ -3,23 +3,33 @@
 //! This module encapsulates all state related to user selection,
 //! including selected records, events, and hover information.
 
+use crate::domain::cursor_snap::SnapTarget;
+
 /// State related to user selection and hover.
 ///
 /// Responsibilities:
 /// - Tracking selected record ID
+/// - Tracking the multi-selection of records (Ctrl+click, Shift+click ranges)
 /// - Tracking selected event (record + clock)
 /// - Managing hover position and clock value
+/// - Holding the event the cursor snaps to on the hovered row
 /// - Providing intent-revealing selection queries
 #[derive(Debug, Clone, Default)]
 pub struct SelectionState {
     /// Currently selected record ID
     selected_record_id: Option<u64>,
+    /// All selected records in selection order (contains the selected record)
+    selected_records: Vec<u64>,
+    /// Record a Shift+click range starts from
+    range_anchor: Option<u64>,
     /// Currently selected event (record_id, event_clk)
     selected_event: Option<(u64, i64)>,
     /// Cursor hover position for visual feedback
     cursor_hover_pos: Option<egui::Pos2>,
     /// Clock value at cursor hover position
     cursor_hover_clk: Option<i64>,
+    /// Nearest event of the hovered timeline row, if close enough to the cursor
+    cursor_snap: Option<SnapTarget>,
 }
 
 impl SelectionState {
@@ -27,18 +37,24 @@ impl SelectionState {
     pub fn new() -> Self {
         Self {
             selected_record_id: None,
+            selected_records: Vec::new(),
+            range_anchor: None,
             selected_event: None,
             cursor_hover_pos: None,
             cursor_hover_clk: None,
+            cursor_snap: None,
         }
     }
 
     /// Clears all selection and hover state.
     pub fn clear(&mut self) {
         self.selected_record_id = None;
+        self.selected_records.clear();
+        self.range_anchor = None;
         self.selected_event = None;
         self.cursor_hover_pos = None;
         self.cursor_hover_clk = None;
+        self.cursor_snap = None;
     }
 
     // ===== Selection Queries =====
@@ -48,6 +64,19 @@ impl SelectionState {
         self.selected_record_id
     }
 
+    /// Returns all selected records in the order they were selected.
+    ///
+    /// Holds just the selected record unless more were added with
+    /// [`Self::toggle_record`] or [`Self::select_range`].
+    pub fn selected_records(&self) -> &[u64] {
+        &self.selected_records
+    }
+
+    /// Returns the record a Shift+click range starts from, if any.
+    pub fn range_anchor(&self) -> Option<u64> {
+        self.range_anchor
+    }
+
     /// Returns the currently selected event (record_id, event_clk), if any.
     pub fn selected_event(&self) -> Option<(u64, i64)> {
         self.selected_event
@@ -65,6 +94,16 @@ impl SelectionState {
         self.cursor_hover_clk
     }
 
+    /// Returns the event the cursor snaps to (Alt+click selects it), if any.
+    pub fn cursor_snap(&self) -> Option<SnapTarget> {
+        self.cursor_snap
+    }
+
+    /// Sets the event the cursor snaps to, found while drawing the timeline rows.
+    pub fn set_cursor_snap(&mut self, snap: Option<SnapTarget>) {
+        self.cursor_snap = snap;
+    }
+
     // ===== Selection Mutations =====
 
     /// Selects a record and optionally auto-selects its first event.
@@ -73,7 +112,7 @@ impl SelectionState {
     /// * `record_id` - The record to select
     /// * `first_event_clk` - Optional first event clock to auto-select
     pub fn select_record(&mut self, record_id: u64, first_event_clk: Option<i64>) {
-        self.selected_record_id = Some(record_id);
+        self.set_single(record_id);
 
         // Auto-select first event if provided
         if let Some(event_clk) = first_event_clk {
@@ -87,10 +126,55 @@ impl SelectionState {
     /// * `record_id` - The parent record ID
     /// * `event_clk` - The event clock value
     pub fn select_event(&mut self, record_id: u64, event_clk: i64) {
-        self.selected_record_id = Some(record_id);
+        self.set_single(record_id);
         self.selected_event = Some((record_id, event_clk));
     }
 
+    /// Adds a record to the selection, or removes it if already selected.
+    ///
+    /// An added record becomes the selected record and the range anchor;
+    /// removing the selected record or the anchor falls back to the last
+    /// one remaining.
+    pub fn toggle_record(&mut self, record_id: u64) {
+        if let Some(index) = self.selected_records.iter().position(|&id| id == record_id) {
+            self.selected_records.remove(index);
+            if self.selected_record_id == Some(record_id) {
+                self.selected_record_id = self.selected_records.last().copied();
+            }
+            if self.range_anchor == Some(record_id) {
+                self.range_anchor = self.selected_record_id;
+            }
+            if self.selected_event.is_some_and(|(id, _)| id == record_id) {
+                self.selected_event = None;
+            }
+        } else {
+            self.selected_records.push(record_id);
+            self.selected_record_id = Some(record_id);
+            self.range_anchor = Some(record_id);
+        }
+    }
+
+    /// Replaces the selection with a range of records, keeping the anchor.
+    ///
+    /// # Arguments
+    /// * `record_ids` - The records of the range, in display order
+    /// * `record_id` - The clicked end of the range, which becomes the selected record
+    pub fn select_range(&mut self, record_ids: Vec<u64>, record_id: u64) {
+        self.selected_records = record_ids;
+        self.selected_record_id = Some(record_id);
+        if self.range_anchor.is_none() {
+            self.range_anchor = Some(record_id);
+        }
+    }
+
+    /// Makes `record_id` the only selected record and the range anchor.
+    fn set_single(&mut self, record_id: u64) {
+        self.selected_record_id = Some(record_id);
+        self.selected_records.clear();
+        self.selected_records.push(record_id);
+        self.range_anchor = Some(record_id);
+    }
+
     // ===== Low-Level Accessors (for input handlers) =====
     // These methods provide direct mutable access to internal state
     // for performance-critical input handling code that needs fine-grained control.
@@ -103,3 +187,33 @@ impl SelectionState {
         (&mut self.cursor_hover_pos, &mut self.cursor_hover_clk)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle_and_range_build_multi_selection() {
+        let mut selection = SelectionState::new();
+        selection.select_record(1, Some(10));
+        selection.toggle_record(5);
+        selection.toggle_record(3);
+        assert_eq!(selection.selected_records(), &[1, 5, 3]);
+        assert_eq!(selection.selected_record_id(), Some(3));
+
+        selection.toggle_record(3);
+        assert_eq!(selection.selected_records(), &[1, 5]);
+        assert_eq!(selection.selected_record_id(), Some(5));
+        selection.toggle_record(1);
+        assert_eq!(selection.selected_event(), None, "event of a deselected record is dropped");
+
+        // The range runs from the last toggled record and keeps its anchor
+        selection.select_range(vec![5, 6, 7], 7);
+        assert_eq!(selection.selected_records(), &[5, 6, 7]);
+        assert_eq!((selection.selected_record_id(), selection.range_anchor()), (Some(7), Some(5)));
+
+        selection.select_event(2, 20);
+        assert_eq!(selection.selected_records(), &[2]);
+        assert_eq!(selection.range_anchor(), Some(2));
+    }
+}
@@ -10,12 +10,15 @@ use rjets::ThemeManager;
 /// Responsibilities:
 /// - Managing theme instances
 /// - Tracking current theme selection
+/// - Tracking whether the selection still has to be applied to egui
 /// - Providing theme-related queries
 pub struct ThemeState {
     /// Theme manager instance
     theme_manager: ThemeManager,
     /// Name of currently selected theme
     current_theme_name: String,
+    /// Theme last applied to the egui context (None until the first frame)
+    applied_theme_name: Option<String>,
 }
 
 impl std::fmt::Debug for ThemeState {
@@ -38,6 +41,7 @@ impl ThemeState {
         Self {
             theme_manager: ThemeManager::new(),
             current_theme_name: "Dark".to_string(),
+            applied_theme_name: None,
         }
     }
 
@@ -49,6 +53,7 @@ impl ThemeState {
         Self {
             theme_manager: ThemeManager::new(),
             current_theme_name: theme_name,
+            applied_theme_name: None,
         }
     }
 
@@ -64,6 +69,11 @@ impl ThemeState {
         &self.current_theme_name
     }
 
+    /// Returns true if the current theme differs from the one applied to egui.
+    pub fn needs_apply(&self) -> bool {
+        self.applied_theme_name.as_deref() != Some(self.current_theme_name.as_str())
+    }
+
     // ===== Theme Mutations =====
 
     /// Sets the current theme by name.
@@ -73,4 +83,9 @@ impl ThemeState {
     pub fn set_theme(&mut self, theme_name: String) {
         self.current_theme_name = theme_name;
     }
+
+    /// Records that the current theme has been applied to egui.
+    pub fn mark_applied(&mut self) {
+        self.applied_theme_name = Some(self.current_theme_name.clone());
+    }
 }
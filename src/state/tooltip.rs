@@ -0,0 +1,161 @@
+//! Record tooltip state management.
+//!
+//! Record tooltips (timeline bars, tree rows) go through one controller so
+//! they share the configured delay, build their content once per hovered
+//! record instead of every frame, and can be pinned in place.
+
+use std::sync::Arc;
+
+/// Text shown in a record tooltip, built once per hovered record.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TooltipContent {
+    /// Record name
+    pub title: String,
+    /// Record description (may contain markup)
+    pub description: String,
+    /// Label/value lines below the description
+    pub fields: Vec<(String, String)>,
+}
+
+/// A tooltip kept on screen after the pointer left its record.
+#[derive(Debug, Clone)]
+pub struct PinnedTooltip {
+    /// Record the tooltip describes
+    pub record_id: u64,
+    /// Content at the time it was pinned
+    pub content: Arc<TooltipContent>,
+    /// Screen position of the tooltip's top-left corner
+    pub pos: egui::Pos2,
+}
+
+/// Record under the pointer and how long it has been hovered.
+#[derive(Debug, Clone)]
+struct HoverTrack {
+    record_id: u64,
+    /// Input time when hovering this record began (seconds)
+    since: f64,
+    /// Last frame the record was reported as hovered
+    last_frame: u64,
+    /// Content built for the record, once the tooltip was first shown
+    content: Option<Arc<TooltipContent>>,
+}
+
+/// State related to record tooltips.
+///
+/// Responsibilities:
+/// - Tracking the hovered record and hover duration across frames
+/// - Caching the hovered record's tooltip content
+/// - Holding the pinned tooltip
+#[derive(Debug, Clone, Default)]
+pub struct TooltipState {
+    /// Currently hovered record
+    hover: Option<HoverTrack>,
+    /// Tooltip pinned with Space, shown until closed
+    pinned: Option<PinnedTooltip>,
+}
+
+impl TooltipState {
+    /// Creates a state with nothing hovered or pinned.
+    pub fn new() -> Self {
+        Self {
+            hover: None,
+            pinned: None,
+        }
+    }
+
+    /// Forgets the hovered record and the pinned tooltip (e.g. on trace load).
+    pub fn clear(&mut self) {
+        self.hover = None;
+        self.pinned = None;
+    }
+
+    // ===== Queries =====
+
+    /// Returns the pinned tooltip, if any.
+    pub fn pinned(&self) -> Option<&PinnedTooltip> {
+        self.pinned.as_ref()
+    }
+
+    // ===== Mutations =====
+
+    /// Records that `record_id` is hovered in `frame` and returns how long it
+    /// has been hovered, in seconds.
+    ///
+    /// Hovering restarts when the record changes or was not hovered in the
+    /// previous frame.
+    pub fn hover(&mut self, record_id: u64, now: f64, frame: u64) -> f64 {
+        match &mut self.hover {
+            Some(track) if track.record_id == record_id && track.last_frame + 1 >= frame => {
+                track.last_frame = frame;
+                now - track.since
+            }
+            _ => {
+                self.hover = Some(HoverTrack {
+                    record_id,
+                    since: now,
+                    last_frame: frame,
+                    content: None,
+                });
+                0.0
+            }
+        }
+    }
+
+    /// Returns the hovered record's content, building it on first use.
+    ///
+    /// Must follow [`Self::hover`] for the same record.
+    pub fn content_or_build(&mut self, build: impl FnOnce() -> TooltipContent) -> Arc<TooltipContent> {
+        match &mut self.hover {
+            Some(track) => track.content.get_or_insert_with(|| Arc::new(build())).clone(),
+            None => Arc::new(build()),
+        }
+    }
+
+    /// Pins a tooltip at the given screen position.
+    pub fn pin(&mut self, record_id: u64, content: Arc<TooltipContent>, pos: egui::Pos2) {
+        self.pinned = Some(PinnedTooltip { record_id, content, pos });
+    }
+
+    /// Closes the pinned tooltip.
+    pub fn unpin(&mut self) {
+        self.pinned = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hover_duration_restarts_on_new_record_or_gap() {
+        let mut tooltip = TooltipState::new();
+        assert_eq!(tooltip.hover(1, 10.0, 1), 0.0);
+        assert_eq!(tooltip.hover(1, 10.5, 2), 0.5);
+        // Another record restarts the timer
+        assert_eq!(tooltip.hover(2, 11.0, 3), 0.0);
+        // A frame without hover restarts it as well
+        assert_eq!(tooltip.hover(2, 12.0, 5), 0.0);
+    }
+
+    #[test]
+    fn test_content_built_once_per_hover() {
+        let mut tooltip = TooltipState::new();
+        let mut builds = 0;
+        tooltip.hover(7, 0.0, 1);
+        for frame in 2..5 {
+            tooltip.hover(7, 0.1, frame);
+            tooltip.content_or_build(|| {
+                builds += 1;
+                TooltipContent::default()
+            });
+        }
+        assert_eq!(builds, 1);
+
+        tooltip.hover(8, 0.2, 5);
+        tooltip.content_or_build(|| {
+            builds += 1;
+            TooltipContent::default()
+        });
+        assert_eq!(builds, 2);
+    }
+}
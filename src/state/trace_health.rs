@@ -0,0 +1,57 @@
+//! Trace health window state management.
+//!
+//! The health window lists the anomalies found by
+//! [`crate::domain::trace_health`]. The check runs on request, and its result
+//! belongs to the loaded trace and is cleared with it.
+
+use crate::domain::trace_health::TraceHealth;
+
+/// State related to the trace health window.
+///
+/// Responsibilities:
+/// - Tracking whether the window is open
+/// - Holding the result of the last health check
+#[derive(Debug, Clone, Default)]
+pub struct TraceHealthState {
+    /// Whether the window is shown
+    open: bool,
+    /// Result of the last check of the loaded trace
+    health: Option<TraceHealth>,
+}
+
+impl TraceHealthState {
+    /// Creates a closed window with no result.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drops the result (e.g. when a new trace is loaded); the window stays open.
+    pub fn clear(&mut self) {
+        self.health = None;
+    }
+
+    // ===== Queries =====
+
+    /// Returns true if the window is shown.
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Returns the result of the last check, if the trace has been checked.
+    pub fn health(&self) -> Option<&TraceHealth> {
+        self.health.as_ref()
+    }
+
+    // ===== Mutations =====
+
+    /// Shows or hides the window.
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    /// Stores the result of a check and opens the window.
+    pub fn set_health(&mut self, health: TraceHealth) {
+        self.health = Some(health);
+        self.open = true;
+    }
+}
@@ -3,8 +3,37 @@
 //! This module encapsulates all state related to the loaded trace file,
 //! including the trace data itself, file path, and trace time extent.
 
-use rjets::{DynTraceData, TraceMetadata};
+use rjets::{DynTraceData, TraceMetadata, Verification};
+use std::ops::Deref;
 use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Cheap, cloneable handle to one loaded trace for background passes.
+///
+/// A snapshot keeps its trace alive after the UI loads another one, so a
+/// long-running pass never reads freed data and never holds up the reload.
+/// Its generation tells whether a result still belongs to the shown trace
+/// (see [`TraceState::is_current`]).
+#[derive(Clone)]
+pub struct TraceSnapshot {
+    data: Arc<DynTraceData>,
+    generation: u64,
+}
+
+impl TraceSnapshot {
+    /// Returns the load generation the snapshot was taken from.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+impl Deref for TraceSnapshot {
+    type Target = DynTraceData;
+
+    fn deref(&self) -> &DynTraceData {
+        &self.data
+    }
+}
 
 /// State related to the loaded trace file and its time extent.
 ///
@@ -12,16 +41,22 @@ use std::path::PathBuf;
 /// - Managing trace data lifetime
 /// - Tracking source file path
 /// - Maintaining trace time boundaries (min/max clock)
+/// - Handing out snapshots to background passes
+/// - Holding the outcome of the background provenance check
 #[derive(Default)]
 pub struct TraceState {
-    /// The currently loaded trace data (if any)
-    trace_data: Option<DynTraceData>,
+    /// The currently loaded trace data (if any), shared with background tasks
+    trace_data: Option<Arc<DynTraceData>>,
+    /// Bumped whenever the trace is replaced or cleared
+    generation: u64,
     /// Path to the currently loaded file (None for virtual traces)
     file_path: Option<PathBuf>,
     /// Minimum clock value in the trace
     min_clk: i64,
     /// Maximum clock value in the trace
     max_clk: i64,
+    /// Provenance check result (None while pending or if the trace has no block)
+    verification: Option<Result<Verification, String>>,
 }
 
 impl TraceState {
@@ -29,9 +64,11 @@ impl TraceState {
     pub fn new() -> Self {
         Self {
             trace_data: None,
+            generation: 0,
             file_path: None,
             min_clk: 0,
             max_clk: 0,
+            verification: None,
         }
     }
 
@@ -42,23 +79,40 @@ impl TraceState {
     /// * `path` - Optional file path (None for virtual traces)
     pub fn load_trace(&mut self, data: DynTraceData, path: Option<PathBuf>) {
         let (min, max) = data.metadata().trace_extent();
-        self.trace_data = Some(data);
+        self.trace_data = Some(Arc::new(data));
+        self.generation += 1;
         self.file_path = path;
         self.min_clk = min;
         self.max_clk = max;
+        self.verification = None;
     }
 
     /// Clears all trace state, resetting to empty state.
     pub fn clear(&mut self) {
         self.trace_data = None;
+        self.generation += 1;
         self.file_path = None;
         self.min_clk = 0;
         self.max_clk = 0;
+        self.verification = None;
     }
 
     /// Returns a reference to the loaded trace data, if any.
     pub fn trace_data(&self) -> Option<&DynTraceData> {
-        self.trace_data.as_ref()
+        self.trace_data.as_deref()
+    }
+
+    /// Returns a snapshot of the loaded trace for use on other threads.
+    pub fn snapshot(&self) -> Option<TraceSnapshot> {
+        self.trace_data.as_ref().map(|data| TraceSnapshot {
+            data: Arc::clone(data),
+            generation: self.generation,
+        })
+    }
+
+    /// Returns true if `generation` is that of the trace currently loaded.
+    pub fn is_current(&self, generation: u64) -> bool {
+        self.trace_data.is_some() && generation == self.generation
     }
 
     /// Returns the file path of the loaded trace, if any.
@@ -75,4 +129,14 @@ impl TraceState {
     pub fn max_clk(&self) -> i64 {
         self.max_clk
     }
+
+    /// Returns the provenance check result, if it has finished.
+    pub fn verification(&self) -> Option<&Result<Verification, String>> {
+        self.verification.as_ref()
+    }
+
+    /// Stores the provenance check result for the loaded trace.
+    pub fn set_verification(&mut self, result: Result<Verification, String>) {
+        self.verification = Some(result);
+    }
 }
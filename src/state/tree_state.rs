@@ -3,25 +3,32 @@
 //! This module encapsulates all state related to the tree view,
 //! specifically which nodes are expanded or collapsed.
 
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
+/// Identifies a custom sort key registered in [`crate::domain::sorting::SortKeyRegistry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ComparatorId(pub u32);
+
 /// Sort key for tree node ordering.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum SortKey {
     Description,
     StartClock,
     Duration,
+    /// A registered custom comparator
+    Custom(ComparatorId),
 }
 
 /// Sort direction.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum SortDir {
     Asc,
     Desc,
 }
 
 /// Complete sorting specification.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct SortSpec {
     pub key: SortKey,
     pub dir: SortDir,
@@ -34,12 +41,15 @@ pub struct SortSpec {
 /// - Providing intent-revealing expansion queries
 /// - Managing bulk expansion operations
 /// - Managing sorting specification
+/// - Tracking rows pinned by a row-scoped region zoom
 #[derive(Debug, Clone, Default)]
 pub struct TreeState {
     /// Set of expanded node IDs
     expanded_nodes: HashSet<u64>,
     /// Active sort specification (None = default backend order)
     active_sort: Option<SortSpec>,
+    /// Records the row list is limited to (empty = no limit)
+    pinned_rows: HashSet<u64>,
 }
 
 impl TreeState {
@@ -48,12 +58,14 @@ impl TreeState {
         Self {
             expanded_nodes: HashSet::new(),
             active_sort: None,
+            pinned_rows: HashSet::new(),
         }
     }
 
-    /// Clears all expansion state (collapses all nodes).
+    /// Clears all expansion state (collapses all nodes) and unpins rows.
     pub fn clear(&mut self) {
         self.expanded_nodes.clear();
+        self.pinned_rows.clear();
     }
 
     // ===== Sorting State =====
@@ -71,6 +83,33 @@ impl TreeState {
         self.active_sort = spec;
     }
 
+    // ===== Pinned Rows =====
+
+    /// Returns the records the row list is limited to (empty = all rows shown).
+    pub fn pinned_rows(&self) -> &HashSet<u64> {
+        &self.pinned_rows
+    }
+
+    /// Limits the row list to the given records.
+    pub fn pin_rows(&mut self, record_ids: impl IntoIterator<Item = u64>) {
+        self.pinned_rows = record_ids.into_iter().collect();
+    }
+
+    /// Adds a record to the pinned rows.
+    pub fn pin_row(&mut self, record_id: u64) {
+        self.pinned_rows.insert(record_id);
+    }
+
+    /// Shows all rows again.
+    ///
+    /// # Returns
+    /// `true` if any rows were pinned.
+    pub fn unpin_rows(&mut self) -> bool {
+        let was_pinned = !self.pinned_rows.is_empty();
+        self.pinned_rows.clear();
+        was_pinned
+    }
+
     // ===== Expansion Queries =====
 
     /// Returns a reference to the set of expanded node IDs.
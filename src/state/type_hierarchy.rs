@@ -0,0 +1,57 @@
+//! Record type hierarchy window state management.
+//!
+//! The window shows the trace folded by record type
+//! ([`crate::domain::type_hierarchy`]). Folding walks every record, so it runs
+//! on request; the result belongs to the loaded trace and is cleared with it.
+
+use crate::domain::type_hierarchy::TypeHierarchy;
+
+/// State related to the record type hierarchy window.
+///
+/// Responsibilities:
+/// - Tracking whether the window is open
+/// - Holding the last folded hierarchy
+#[derive(Debug, Clone, Default)]
+pub struct TypeHierarchyState {
+    /// Whether the window is shown
+    open: bool,
+    /// Hierarchy of the loaded trace, once folded
+    hierarchy: Option<TypeHierarchy>,
+}
+
+impl TypeHierarchyState {
+    /// Creates a closed window with no hierarchy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drops the hierarchy (e.g. when a new trace is loaded); the window stays open.
+    pub fn clear(&mut self) {
+        self.hierarchy = None;
+    }
+
+    // ===== Queries =====
+
+    /// Returns true if the window is shown.
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Returns the folded hierarchy, if the trace has been folded.
+    pub fn hierarchy(&self) -> Option<&TypeHierarchy> {
+        self.hierarchy.as_ref()
+    }
+
+    // ===== Mutations =====
+
+    /// Shows or hides the window.
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    /// Stores a folded hierarchy and opens the window.
+    pub fn set_hierarchy(&mut self, hierarchy: TypeHierarchy) {
+        self.hierarchy = Some(hierarchy);
+        self.open = true;
+    }
+}
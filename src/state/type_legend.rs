@@ -0,0 +1,267 @@
+//! Record type legend state management.
+//!
+//! The legend lists every record_type of the trace. It is where the user
+//! overrides the color of a type, hides types from the tree and timeline and
+//! turns a type into a value track, so those settings live here, next to the
+//! marker shapes chosen per event name. Color overrides, value tracks and
+//! marker shapes are user preferences and outlive the trace; hidden types are
+//! cleared when a new trace is loaded.
+
+use egui::Color32;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::domain::event_markers::MarkerShape;
+use crate::domain::type_hints::TypeHints;
+
+/// Value track of a record type: bar height follows a numeric attribute.
+///
+/// The attribute value is mapped linearly from `min..=max` onto the row
+/// height, so e.g. an occupancy attribute with range 0..100 draws a bar
+/// chart over time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValueTrack {
+    /// Record attribute holding the value
+    pub attribute: String,
+    /// Value drawn as an empty bar
+    pub min: f64,
+    /// Value drawn as a full-height bar
+    pub max: f64,
+}
+
+impl ValueTrack {
+    /// Creates a track over `attribute` with the default range 0..100.
+    pub fn new(attribute: impl Into<String>) -> Self {
+        Self {
+            attribute: attribute.into(),
+            min: 0.0,
+            max: 100.0,
+        }
+    }
+
+    /// Returns the bar height fraction (0..=1) for an attribute value.
+    ///
+    /// Numbers and numeric strings (an optional trailing `%` is ignored) are
+    /// accepted; anything else, or an empty range, yields None.
+    pub fn fraction(&self, value: &serde_json::Value) -> Option<f32> {
+        let value = match value {
+            serde_json::Value::Number(n) => n.as_f64()?,
+            serde_json::Value::String(s) => s.trim().trim_end_matches('%').trim().parse().ok()?,
+            _ => return None,
+        };
+        if !value.is_finite() || self.max <= self.min {
+            return None;
+        }
+        Some(((value - self.min) / (self.max - self.min)).clamp(0.0, 1.0) as f32)
+    }
+}
+
+/// State related to the record type legend.
+///
+/// Responsibilities:
+/// - Tracking whether the legend window is open
+/// - Tracking per-type color overrides
+/// - Tracking which record types are hidden
+/// - Tracking per-type value tracks
+/// - Tracking per-event-name marker shapes
+/// - Holding the loaded trace's per-type hints
+#[derive(Debug, Clone, Default)]
+pub struct TypeLegendState {
+    /// Whether the legend window is shown
+    open: bool,
+    /// Colors chosen by the user, keyed by record_type
+    color_overrides: HashMap<String, Color32>,
+    /// Record types hidden from tree and timeline
+    hidden_types: HashSet<String>,
+    /// Value tracks, keyed by record_type
+    value_tracks: HashMap<String, ValueTrack>,
+    /// Marker shapes chosen by the user, keyed by event name
+    event_shapes: HashMap<String, MarkerShape>,
+    /// Hints from the loaded trace's header
+    trace_hints: TypeHints,
+}
+
+impl TypeLegendState {
+    /// Creates a legend state with no overrides and all types shown.
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            color_overrides: HashMap::new(),
+            hidden_types: HashSet::new(),
+            value_tracks: HashMap::new(),
+            event_shapes: HashMap::new(),
+            trace_hints: TypeHints::default(),
+        }
+    }
+
+    /// Clears per-trace state (hidden types, trace hints); color overrides,
+    /// value tracks and marker shapes are kept.
+    pub fn clear(&mut self) {
+        self.hidden_types.clear();
+        self.trace_hints = TypeHints::default();
+    }
+
+    // ===== Queries =====
+
+    /// Returns true if the legend window is shown.
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Returns the color override for a record type, if any.
+    pub fn color_override(&self, record_type: &str) -> Option<Color32> {
+        self.color_overrides.get(record_type).copied()
+    }
+
+    /// Returns all color overrides.
+    pub fn color_overrides(&self) -> &HashMap<String, Color32> {
+        &self.color_overrides
+    }
+
+    /// Returns the set of hidden record types.
+    pub fn hidden_types(&self) -> &HashSet<String> {
+        &self.hidden_types
+    }
+
+    /// Returns true if the record type is shown.
+    pub fn is_type_visible(&self, record_type: &str) -> bool {
+        !self.hidden_types.contains(record_type)
+    }
+
+    /// Returns the value track of a record type, if any.
+    pub fn value_track(&self, record_type: &str) -> Option<&ValueTrack> {
+        self.value_tracks.get(record_type)
+    }
+
+    /// Returns all value tracks.
+    pub fn value_tracks(&self) -> &HashMap<String, ValueTrack> {
+        &self.value_tracks
+    }
+
+    /// Returns the marker shapes chosen per event name.
+    pub fn event_shapes(&self) -> &HashMap<String, MarkerShape> {
+        &self.event_shapes
+    }
+
+    /// Returns the per-type hints of the loaded trace (empty if ignored).
+    pub fn trace_hints(&self) -> &TypeHints {
+        &self.trace_hints
+    }
+
+    // ===== Mutations =====
+
+    /// Shows or hides the legend window.
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    /// Sets (or with `None`, removes) the color override for a record type.
+    pub fn set_color_override(&mut self, record_type: &str, color: Option<Color32>) {
+        match color {
+            Some(color) => {
+                self.color_overrides.insert(record_type.to_string(), color);
+            }
+            None => {
+                self.color_overrides.remove(record_type);
+            }
+        }
+    }
+
+    /// Replaces all color overrides (e.g. when restoring settings).
+    pub fn set_color_overrides(&mut self, overrides: HashMap<String, Color32>) {
+        self.color_overrides = overrides;
+    }
+
+    /// Sets (or with `None`, removes) the value track of a record type.
+    pub fn set_value_track(&mut self, record_type: &str, track: Option<ValueTrack>) {
+        match track {
+            Some(track) => {
+                self.value_tracks.insert(record_type.to_string(), track);
+            }
+            None => {
+                self.value_tracks.remove(record_type);
+            }
+        }
+    }
+
+    /// Replaces all value tracks (e.g. when restoring settings).
+    pub fn set_value_tracks(&mut self, tracks: HashMap<String, ValueTrack>) {
+        self.value_tracks = tracks;
+    }
+
+    /// Sets (or with `None`, removes) the marker shape of an event name.
+    pub fn set_event_shape(&mut self, event_name: &str, shape: Option<MarkerShape>) {
+        match shape {
+            Some(shape) => {
+                self.event_shapes.insert(event_name.to_string(), shape);
+            }
+            None => {
+                self.event_shapes.remove(event_name);
+            }
+        }
+    }
+
+    /// Replaces all marker shapes (e.g. when restoring settings).
+    pub fn set_event_shapes(&mut self, shapes: HashMap<String, MarkerShape>) {
+        self.event_shapes = shapes;
+    }
+
+    /// Replaces the per-type hints (when a trace is opened or hints are toggled).
+    pub fn set_trace_hints(&mut self, hints: TypeHints) {
+        self.trace_hints = hints;
+    }
+
+    /// Hides a shown record type or shows a hidden one.
+    pub fn toggle_type_visible(&mut self, record_type: &str) {
+        if !self.hidden_types.remove(record_type) {
+            self.hidden_types.insert(record_type.to_string());
+        }
+    }
+
+    /// Shows all record types.
+    pub fn show_all_types(&mut self) {
+        self.hidden_types.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle_type_visibility() {
+        let mut legend = TypeLegendState::new();
+        assert!(legend.is_type_visible("Warp"));
+
+        legend.toggle_type_visible("Warp");
+        assert!(!legend.is_type_visible("Warp"));
+        legend.toggle_type_visible("Warp");
+        assert!(legend.is_type_visible("Warp"));
+    }
+
+    #[test]
+    fn test_clear_keeps_color_overrides() {
+        let mut legend = TypeLegendState::new();
+        legend.set_color_override("Warp", Some(Color32::RED));
+        legend.toggle_type_visible("Warp");
+
+        legend.clear();
+        assert!(legend.is_type_visible("Warp"));
+        assert_eq!(legend.color_override("Warp"), Some(Color32::RED));
+
+        legend.set_color_override("Warp", None);
+        assert_eq!(legend.color_override("Warp"), None);
+    }
+
+    #[test]
+    fn test_value_track_fraction() {
+        let track = ValueTrack::new("occupancy");
+        assert_eq!(track.fraction(&serde_json::json!(25)), Some(0.25));
+        assert_eq!(track.fraction(&serde_json::json!("50%")), Some(0.5));
+        assert_eq!(track.fraction(&serde_json::json!(150.0)), Some(1.0));
+        assert_eq!(track.fraction(&serde_json::json!("busy")), None);
+
+        let empty = ValueTrack { min: 1.0, max: 1.0, ..track };
+        assert_eq!(empty.fraction(&serde_json::json!(1)), None);
+    }
+}
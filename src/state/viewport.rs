@@ -2,6 +2,95 @@
 //!
 //! This module encapsulates all state related to the visible viewport,
 //! including zoom level, visible time range, and scroll position.
+//!
+//! The zoom level is not stored: it is the trace extent over the visible
+//! range. Zooming is bounded by the whole trace and a minimum viewport width
+//! ([`viewport_operations::MIN_VIEWPORT_CLKS`]).
+//!
+//! Fit, zoom buttons, zoom-to-record and jumps move the range with a short
+//! eased animation so the user can follow where the view went. The frame
+//! loop advances it with [`ViewportState::tick`]; any direct range change
+//! (dragging, wheel zoom) cancels it.
+//!
+//! The split timeline adds a second time window ([`SPLIT_WINDOW`]) with its
+//! own range and zoom over the same rows. Everything else (the viewport
+//! filter, animations, region exports) follows the main window.
+
+use crate::domain::viewport_operations;
+
+/// Default length of an animated viewport move, in seconds.
+const ANIMATION_SECONDS: f64 = 0.2;
+
+/// Index of the main time window.
+pub const MAIN_WINDOW: usize = 0;
+
+/// Index of the second time window shown while the timeline is split.
+pub const SPLIT_WINDOW: usize = 1;
+
+/// Range of the split timeline's second window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TimeWindow {
+    start_clk: i64,
+    end_clk: i64,
+}
+
+/// An animated move from one clock range to another.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ViewportAnimation {
+    from: (i64, i64),
+    to: (i64, i64),
+    /// Trace extent the zoom level is computed against
+    trace_extent: (i64, i64),
+    /// Frame time of the first tick (None until the animation is first ticked)
+    started_at: Option<f64>,
+    /// Range the animation last applied; anything else means input moved the view
+    shown: (i64, i64),
+}
+
+impl ViewportAnimation {
+    /// Range at a point of the animation (`t` in 0..=1, already eased).
+    ///
+    /// The center moves linearly and the width geometrically, so a large
+    /// zoom changes at a steady visual rate instead of collapsing at the end.
+    fn range_at(&self, t: f64) -> (i64, i64) {
+        let center = |(start, end): (i64, i64)| (start as f64 + end as f64) / 2.0;
+        let width = |(start, end): (i64, i64)| viewport_operations::clk_span(start, end).max(1.0);
+        let (from_center, to_center) = (center(self.from), center(self.to));
+        let (from_width, to_width) = (width(self.from), width(self.to));
+
+        let center = from_center + (to_center - from_center) * t;
+        let width = from_width * (to_width / from_width).powf(t);
+        ((center - width / 2.0).round() as i64, (center + width / 2.0).round() as i64)
+    }
+}
+
+/// Where a row scrolled to lands in the tree's visible area.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowAlignment {
+    /// Row at the top edge
+    Top,
+    /// Row in the middle
+    Center,
+}
+
+/// Vertical scroll requested from outside the tree's scroll area.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScrollRequest {
+    /// Scroll offset in pixels
+    Offset(f32),
+    /// Bring a row (by index in the current row list) into view
+    Row {
+        row_index: usize,
+        alignment: RowAlignment,
+        /// Scroll smoothly instead of jumping
+        animated: bool,
+    },
+}
+
+/// Cubic ease-out: fast start, gentle landing.
+fn ease_out(t: f64) -> f64 {
+    1.0 - (1.0 - t).powi(3)
+}
 
 /// State related to the visible viewport and zoom.
 ///
@@ -10,18 +99,29 @@
 /// - Coordinating horizontal (time) panning
 /// - Tracking vertical scroll position
 /// - Enforcing viewport boundaries
+/// - Holding the second time window of the split timeline
 #[derive(Debug, Clone)]
 pub struct ViewportState {
-    /// Current zoom level (1.0 = fit entire trace, higher = zoomed in)
-    zoom_level: f32,
+    /// Trace bounds the zoom level is computed against
+    trace_extent: (i64, i64),
     /// Start of visible viewport in clock units
     viewport_start_clk: i64,
     /// End of visible viewport in clock units
     viewport_end_clk: i64,
     /// Shared vertical scroll position between tree and timeline
     shared_scroll_y: f32,
+    /// Scroll position requested from outside the tree's scroll area (applied once)
+    scroll_request: Option<ScrollRequest>,
     /// Whether viewport filter is enabled (filters tree to show only records within viewport time range)
     viewport_filter_enabled: bool,
+    /// Whether the viewport filter also drops parents without a leaf in range
+    hide_empty_parents: bool,
+    /// Running range animation, if any
+    animation: Option<ViewportAnimation>,
+    /// Length of animated moves in seconds (0 = jump immediately)
+    animation_seconds: f64,
+    /// Second time window while the timeline is split
+    split: Option<TimeWindow>,
 }
 
 impl Default for ViewportState {
@@ -34,11 +134,16 @@ impl ViewportState {
     /// Creates a new viewport state with default values.
     pub fn new() -> Self {
         Self {
-            zoom_level: 1.0,
+            trace_extent: (0, 0),
             viewport_start_clk: 0,
             viewport_end_clk: 0,
             shared_scroll_y: 0.0,
+            scroll_request: None,
             viewport_filter_enabled: false,
+            hide_empty_parents: false,
+            animation: None,
+            animation_seconds: ANIMATION_SECONDS,
+            split: None,
         }
     }
 
@@ -50,21 +155,36 @@ impl ViewportState {
     pub fn fit_to_trace(&mut self, min_clk: i64, max_clk: i64) {
         self.set_range(min_clk, max_clk, min_clk, max_clk);
         self.shared_scroll_y = 0.0;
+        if self.split.is_some() {
+            self.set_window_range(SPLIT_WINDOW, min_clk, max_clk, min_clk, max_clk);
+        }
     }
 
     /// Resets viewport to initial state.
     pub fn reset(&mut self) {
         self.viewport_start_clk = 0;
         self.viewport_end_clk = 0;
-        self.zoom_level = 1.0;
+        self.trace_extent = (0, 0);
         self.shared_scroll_y = 0.0;
+        self.scroll_request = None;
+        self.animation = None;
+        if let Some(split) = self.split.as_mut() {
+            *split = TimeWindow { start_clk: 0, end_clk: 0 };
+        }
     }
 
     // ===== Viewport Queries =====
 
-    /// Returns the current zoom level.
-    pub fn zoom_level(&self) -> f32 {
-        self.zoom_level
+    /// Returns the current zoom level (1.0 = fit entire trace, higher = zoomed in).
+    pub fn zoom_level(&self) -> f64 {
+        let (trace_min, trace_max) = self.trace_extent;
+        viewport_operations::zoom_level(self.viewport_start_clk, self.viewport_end_clk, trace_min, trace_max)
+    }
+
+    /// Returns true if the viewport is as narrow as zooming goes.
+    pub fn at_max_zoom(&self) -> bool {
+        let (trace_min, trace_max) = self.trace_extent;
+        self.zoom_level() >= viewport_operations::max_zoom_level(trace_min, trace_max)
     }
 
     /// Returns the start of the visible viewport in clock units.
@@ -77,11 +197,6 @@ impl ViewportState {
         self.viewport_end_clk
     }
 
-    /// Returns the visible duration in clock units.
-    pub fn visible_duration(&self) -> i64 {
-        self.viewport_end_clk - self.viewport_start_clk
-    }
-
     /// Returns the shared vertical scroll position.
     pub fn scroll_y(&self) -> f32 {
         self.shared_scroll_y
@@ -92,9 +207,50 @@ impl ViewportState {
         self.viewport_filter_enabled
     }
 
+    /// Returns whether the viewport filter hides parents without a leaf in range.
+    pub fn hide_empty_parents(&self) -> bool {
+        self.hide_empty_parents
+    }
+
+    /// Returns true if moves are animated (off in low-power mode).
+    pub fn animations_enabled(&self) -> bool {
+        self.animation_seconds > 0.0
+    }
+
+    /// Returns true while an animated move is running.
+    pub fn is_animating(&self) -> bool {
+        self.animation.is_some()
+    }
+
+    /// Returns true if the timeline shows a second time window.
+    pub fn is_split(&self) -> bool {
+        self.split.is_some()
+    }
+
+    /// Returns the number of time windows shown (2 while split).
+    pub fn window_count(&self) -> usize {
+        if self.split.is_some() { 2 } else { 1 }
+    }
+
+    /// Returns the range of a time window ([`MAIN_WINDOW`] or [`SPLIT_WINDOW`]).
+    ///
+    /// The split window falls back to the main range while not split.
+    pub fn window_range(&self, window: usize) -> (i64, i64) {
+        match self.split {
+            Some(split) if window == SPLIT_WINDOW => (split.start_clk, split.end_clk),
+            _ => (self.viewport_start_clk, self.viewport_end_clk),
+        }
+    }
+
+    /// Returns the range the viewport is moving to (the current range if not animating).
+    pub fn target_range(&self) -> (i64, i64) {
+        self.animation
+            .map_or((self.viewport_start_clk, self.viewport_end_clk), |animation| animation.to)
+    }
+
     // ===== Viewport Mutations =====
 
-    /// Sets the visible viewport range and automatically calculates zoom level.
+    /// Sets the visible viewport range and the trace extent the zoom level is relative to.
     ///
     /// # Arguments
     /// * `start_clk` - Start of viewport in clock units
@@ -102,52 +258,144 @@ impl ViewportState {
     /// * `trace_min_clk` - Minimum clock value in trace (for zoom calculation)
     /// * `trace_max_clk` - Maximum clock value in trace (for zoom calculation)
     pub fn set_range(&mut self, start_clk: i64, end_clk: i64, trace_min_clk: i64, trace_max_clk: i64) {
+        self.animation = None;
+        self.apply_range(start_clk, end_clk, trace_min_clk, trace_max_clk);
+    }
+
+    fn apply_range(&mut self, start_clk: i64, end_clk: i64, trace_min_clk: i64, trace_max_clk: i64) {
         self.viewport_start_clk = start_clk;
         self.viewport_end_clk = end_clk;
-
-        // Calculate zoom level based on trace extent vs viewport extent
-        let trace_extent = (trace_max_clk - trace_min_clk) as f32;
-        let viewport_extent = (end_clk - start_clk) as f32;
-        self.zoom_level = if viewport_extent > 0.0 {
-            trace_extent / viewport_extent
-        } else {
-            1.0
-        };
+        self.trace_extent = (trace_min_clk, trace_max_clk);
     }
 
     /// Zooms in/out around a specific clock point.
     ///
+    /// Stops at the whole trace and at the minimum viewport width (see
+    /// [`viewport_operations::zoom_range`]).
+    ///
     /// # Arguments
     /// * `zoom_factor` - Multiplicative zoom factor (>1 = zoom in, <1 = zoom out)
     /// * `focus_clk` - Clock value to zoom around (stays at same screen position)
     /// * `min_clk` - Minimum allowed clock (trace boundary)
     /// * `max_clk` - Maximum allowed clock (trace boundary)
     pub fn zoom_around(&mut self, zoom_factor: f32, focus_clk: i64, min_clk: i64, max_clk: i64) {
-        let old_duration = self.visible_duration() as f32;
-        let new_duration = (old_duration / zoom_factor).max(1.0) as i64;
+        let (start, end) = viewport_operations::zoom_range(
+            zoom_factor as f64,
+            focus_clk,
+            self.viewport_start_clk,
+            self.viewport_end_clk,
+            min_clk,
+            max_clk,
+        );
+        self.set_range(start, end, min_clk, max_clk);
+    }
 
-        // Calculate how much of the old range was before/after the focus point
-        let focus_ratio = (focus_clk - self.viewport_start_clk) as f32 / old_duration;
+    /// Moves the viewport to a range with an animation (see [`Self::tick`]).
+    ///
+    /// Jumps immediately if animations are off. A move started during
+    /// another one starts from the range currently shown.
+    ///
+    /// # Arguments
+    /// * `start_clk` - Start of the target range
+    /// * `end_clk` - End of the target range
+    /// * `trace_min_clk` - Minimum clock value in trace (for zoom calculation)
+    /// * `trace_max_clk` - Maximum clock value in trace (for zoom calculation)
+    pub fn animate_to(&mut self, start_clk: i64, end_clk: i64, trace_min_clk: i64, trace_max_clk: i64) {
+        let from = (self.viewport_start_clk, self.viewport_end_clk);
+        if self.animation_seconds <= 0.0 || from == (start_clk, end_clk) || from.1 <= from.0 || end_clk <= start_clk {
+            self.set_range(start_clk, end_clk, trace_min_clk, trace_max_clk);
+            return;
+        }
+        self.animation = Some(ViewportAnimation {
+            from,
+            to: (start_clk, end_clk),
+            trace_extent: (trace_min_clk, trace_max_clk),
+            started_at: None,
+            shown: from,
+        });
+    }
+
+    /// Animated version of [`Self::zoom_around`].
+    ///
+    /// Zooms relative to the range being moved to, so repeated clicks
+    /// accumulate instead of restarting from a half-finished animation.
+    pub fn animate_zoom_around(&mut self, zoom_factor: f32, focus_clk: i64, min_clk: i64, max_clk: i64) {
+        let mut target = self.clone();
+        let (start, end) = self.target_range();
+        target.set_range(start, end, min_clk, max_clk);
+        target.zoom_around(zoom_factor, focus_clk, min_clk, max_clk);
+        self.animate_to(target.viewport_start_clk, target.viewport_end_clk, min_clk, max_clk);
+    }
 
-        let mut new_start = focus_clk - (new_duration as f32 * focus_ratio) as i64;
-        let mut new_end = new_start + new_duration;
+    /// Advances a running animation to the given frame time.
+    ///
+    /// The animation is dropped if the range was changed through
+    /// [`Self::for_input_handler`] since the last tick.
+    ///
+    /// # Arguments
+    /// * `now` - Frame time in seconds (any monotonic clock)
+    ///
+    /// # Returns
+    /// True if the animation is still running after this tick.
+    pub fn tick(&mut self, now: f64) -> bool {
+        let current = (self.viewport_start_clk, self.viewport_end_clk);
+        let Some(animation) = self.animation.as_mut() else {
+            return false;
+        };
+        // Panning or wheel zoom through the input handler takes over
+        if animation.shown != current {
+            self.animation = None;
+            return false;
+        }
+        let started_at = *animation.started_at.get_or_insert(now);
+        let progress = ((now - started_at) / self.animation_seconds).clamp(0.0, 1.0);
+        let (start, end) = if progress >= 1.0 { animation.to } else { animation.range_at(ease_out(progress)) };
+        animation.shown = (start, end);
+        let trace_extent = animation.trace_extent;
 
-        // Clamp to trace boundaries
-        if new_start < min_clk {
-            new_start = min_clk;
-            new_end = new_start + new_duration;
+        self.apply_range(start, end, trace_extent.0, trace_extent.1);
+        if progress >= 1.0 {
+            self.animation = None;
         }
-        if new_end > max_clk {
-            new_end = max_clk;
-            new_start = new_end - new_duration;
-            if new_start < min_clk {
-                new_start = min_clk;
+        self.animation.is_some()
+    }
+
+    /// Turns animated moves on or off (off jumps straight to the target).
+    pub fn set_animations_enabled(&mut self, enabled: bool) {
+        self.animation_seconds = if enabled { ANIMATION_SECONDS } else { 0.0 };
+        if !enabled {
+            if let Some(animation) = self.animation.take() {
+                let (start, end) = animation.to;
+                self.apply_range(start, end, animation.trace_extent.0, animation.trace_extent.1);
             }
         }
+    }
 
-        self.viewport_start_clk = new_start;
-        self.viewport_end_clk = new_end;
-        self.zoom_level = (max_clk - min_clk) as f32 / new_duration as f32;
+    /// Shows or hides the split timeline's second window.
+    ///
+    /// A newly shown window starts at the main window's range.
+    pub fn set_split(&mut self, enabled: bool) {
+        self.split = match (enabled, self.split) {
+            (true, Some(split)) => Some(split),
+            (true, None) => Some(TimeWindow {
+                start_clk: self.viewport_start_clk,
+                end_clk: self.viewport_end_clk,
+            }),
+            (false, _) => None,
+        };
+    }
+
+    /// Sets the range of a time window (see [`Self::set_range`] for the main one).
+    ///
+    /// Setting the split window's range while not split does nothing.
+    pub fn set_window_range(&mut self, window: usize, start_clk: i64, end_clk: i64, trace_min_clk: i64, trace_max_clk: i64) {
+        if window != SPLIT_WINDOW {
+            self.set_range(start_clk, end_clk, trace_min_clk, trace_max_clk);
+            return;
+        }
+        if let Some(split) = self.split.as_mut() {
+            *split = TimeWindow { start_clk, end_clk };
+        }
     }
 
     /// Sets the vertical scroll position.
@@ -158,6 +406,27 @@ impl ViewportState {
         self.shared_scroll_y = y.max(0.0);
     }
 
+    /// Requests a vertical scroll position from outside the tree panel.
+    ///
+    /// The tree's scroll area owns the offset, so it applies the request on
+    /// its next frame (see [`Self::take_scroll_request`]).
+    pub fn request_scroll_y(&mut self, y: f32) {
+        self.scroll_request = Some(ScrollRequest::Offset(y.max(0.0)));
+    }
+
+    /// Requests scrolling a row into view (applied like [`Self::request_scroll_y`]).
+    ///
+    /// The animation is skipped while animations are off.
+    pub fn request_scroll_to_row(&mut self, row_index: usize, alignment: RowAlignment, animated: bool) {
+        let animated = animated && self.animations_enabled();
+        self.scroll_request = Some(ScrollRequest::Row { row_index, alignment, animated });
+    }
+
+    /// Takes the pending scroll request, if any.
+    pub fn take_scroll_request(&mut self) -> Option<ScrollRequest> {
+        self.scroll_request.take()
+    }
+
     /// Sets whether viewport filter is enabled.
     ///
     /// # Arguments
@@ -166,9 +435,9 @@ impl ViewportState {
         self.viewport_filter_enabled = enabled;
     }
 
-    /// Toggles viewport filter on/off.
-    pub fn toggle_viewport_filter(&mut self) {
-        self.viewport_filter_enabled = !self.viewport_filter_enabled;
+    /// Sets whether the viewport filter hides parents without a leaf in range.
+    pub fn set_hide_empty_parents(&mut self, hide: bool) {
+        self.hide_empty_parents = hide;
     }
 
     // ===== Low-Level Accessors (for input handlers) =====
@@ -177,9 +446,151 @@ impl ViewportState {
 
     /// Returns multiple mutable references for input handling (splits borrows).
     ///
+    /// # Arguments
+    /// * `window` - [`MAIN_WINDOW`] or [`SPLIT_WINDOW`] (the main window while not split)
+    ///
     /// # Returns
-    /// Tuple of (viewport_start_clk, viewport_end_clk, zoom_level)
-    pub(crate) fn for_input_handler(&mut self) -> (&mut i64, &mut i64, &mut f32) {
-        (&mut self.viewport_start_clk, &mut self.viewport_end_clk, &mut self.zoom_level)
+    /// Tuple of (viewport_start_clk, viewport_end_clk) of the window
+    pub(crate) fn for_input_handler(&mut self, window: usize) -> (&mut i64, &mut i64) {
+        match (window, self.split.as_mut()) {
+            (SPLIT_WINDOW, Some(split)) => (&mut split.start_clk, &mut split.end_clk),
+            _ => (&mut self.viewport_start_clk, &mut self.viewport_end_clk),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_animation_reaches_target() {
+        let mut viewport = ViewportState::new();
+        viewport.set_range(0, 1000, 0, 1000);
+
+        viewport.animate_to(400, 500, 0, 1000);
+        assert!(viewport.is_animating());
+        assert_eq!(viewport.target_range(), (400, 500));
+
+        // The first tick only records the start time
+        assert!(viewport.tick(10.0));
+        assert_eq!((viewport.viewport_start_clk(), viewport.viewport_end_clk()), (0, 1000));
+
+        assert!(viewport.tick(10.0 + ANIMATION_SECONDS / 2.0));
+        let (start, end) = (viewport.viewport_start_clk(), viewport.viewport_end_clk());
+        assert!(start > 0 && start < 400 && end > 500 && end < 1000);
+
+        assert!(!viewport.tick(11.0));
+        assert_eq!((viewport.viewport_start_clk(), viewport.viewport_end_clk()), (400, 500));
+        assert_eq!(viewport.zoom_level(), 10.0);
+    }
+
+    #[test]
+    fn test_animation_between_extreme_ranges() {
+        let mut viewport = ViewportState::new();
+        viewport.set_range(i64::MIN, i64::MAX, i64::MIN, i64::MAX);
+
+        viewport.animate_to(i64::MAX - 1000, i64::MAX, i64::MIN, i64::MAX);
+        assert!(viewport.tick(0.0));
+        assert!(viewport.tick(ANIMATION_SECONDS / 2.0));
+        let (start, end) = (viewport.viewport_start_clk(), viewport.viewport_end_clk());
+        // Halfway in eased time the center has moved most of the way right
+        assert!(start > 0 && start < end);
+
+        assert!(!viewport.tick(1.0));
+        assert_eq!((viewport.viewport_start_clk(), viewport.viewport_end_clk()), (i64::MAX - 1000, i64::MAX));
+
+        // And back out to the whole clock domain
+        viewport.animate_to(i64::MIN, i64::MAX, i64::MIN, i64::MAX);
+        assert!(viewport.tick(2.0));
+        assert!(viewport.tick(2.0 + ANIMATION_SECONDS / 2.0));
+        assert!(!viewport.tick(3.0));
+        assert_eq!((viewport.viewport_start_clk(), viewport.viewport_end_clk()), (i64::MIN, i64::MAX));
+    }
+
+    #[test]
+    fn test_zoom_level_is_derived_and_bounded() {
+        let mut viewport = ViewportState::new();
+        viewport.fit_to_trace(0, 1_000_000_000);
+        assert_eq!(viewport.zoom_level(), 1.0);
+
+        // Far past the old 10000x limit, down to a 10 clock window
+        for _ in 0..100 {
+            viewport.zoom_around(10.0, 500_000_000, 0, 1_000_000_000);
+        }
+        let (start, end) = viewport.window_range(MAIN_WINDOW);
+        assert_eq!(end - start, viewport_operations::MIN_VIEWPORT_CLKS);
+        assert_eq!(viewport.zoom_level(), 100_000_000.0);
+        assert!(viewport.at_max_zoom());
+
+        // Ranges moved through the input handler change the zoom level with them
+        *viewport.for_input_handler(MAIN_WINDOW).1 += 10;
+        assert_eq!(viewport.zoom_level(), 50_000_000.0);
+        assert!(!viewport.at_max_zoom());
+
+        viewport.zoom_around(0.0001, 0, 0, 1_000_000_000);
+        viewport.zoom_around(0.0001, 0, 0, 1_000_000_000);
+        assert_eq!(viewport.window_range(MAIN_WINDOW), (0, 1_000_000_000));
+    }
+
+    #[test]
+    fn test_direct_change_cancels_animation() {
+        let mut viewport = ViewportState::new();
+        viewport.set_range(0, 1000, 0, 1000);
+        viewport.animate_zoom_around(2.0, 500, 0, 1000);
+        viewport.animate_zoom_around(2.0, 500, 0, 1000);
+        assert_eq!(viewport.target_range(), (375, 625));
+
+        viewport.set_range(100, 200, 0, 1000);
+        assert!(!viewport.is_animating());
+        assert!(!viewport.tick(1.0));
+
+        viewport.animate_to(0, 1000, 0, 1000);
+        *viewport.for_input_handler(MAIN_WINDOW).0 = 150;
+        assert!(!viewport.tick(1.0));
+        assert_eq!(viewport.viewport_start_clk(), 150);
+
+        viewport.set_animations_enabled(false);
+        viewport.animate_to(0, 1000, 0, 1000);
+        assert!(!viewport.is_animating());
+        assert_eq!(viewport.viewport_end_clk(), 1000);
+    }
+
+    #[test]
+    fn test_row_scroll_request_follows_animation_setting() {
+        let mut viewport = ViewportState::new();
+        viewport.request_scroll_to_row(40, RowAlignment::Center, true);
+        assert_eq!(
+            viewport.take_scroll_request(),
+            Some(ScrollRequest::Row { row_index: 40, alignment: RowAlignment::Center, animated: true })
+        );
+        assert_eq!(viewport.take_scroll_request(), None, "applied once");
+
+        viewport.set_animations_enabled(false);
+        viewport.request_scroll_to_row(40, RowAlignment::Top, true);
+        assert!(matches!(viewport.take_scroll_request(), Some(ScrollRequest::Row { animated: false, .. })));
+    }
+
+    #[test]
+    fn test_split_window_is_independent() {
+        let mut viewport = ViewportState::new();
+        viewport.set_range(100, 200, 0, 1000);
+        assert_eq!(viewport.window_range(SPLIT_WINDOW), (100, 200));
+
+        viewport.set_split(true);
+        assert_eq!(viewport.window_count(), 2);
+        viewport.set_window_range(SPLIT_WINDOW, 600, 800, 0, 1000);
+        *viewport.for_input_handler(MAIN_WINDOW).0 = 120;
+        assert_eq!(viewport.window_range(MAIN_WINDOW), (120, 200));
+        assert_eq!(viewport.window_range(SPLIT_WINDOW), (600, 800));
+
+        // A new trace fits both windows
+        viewport.reset();
+        viewport.fit_to_trace(0, 50);
+        assert_eq!(viewport.window_range(SPLIT_WINDOW), (0, 50));
+
+        viewport.set_split(false);
+        assert_eq!(viewport.window_range(SPLIT_WINDOW), (0, 50));
+        assert_eq!(viewport.window_count(), 1);
     }
 }
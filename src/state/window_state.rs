@@ -0,0 +1,235 @@
+//! Window placement state management.
+//!
+//! eframe remembers one window rectangle, which can put the window on a
+//! monitor that is no longer attached after undocking. Placements are kept
+//! per monitor configuration instead, identified by the size and scale of the
+//! monitor the window is on. On startup the last placement is applied and
+//! checked: if the window does not end up on that monitor, the placement
+//! saved for the monitor it did land on is used.
+
+use egui::{ViewportCommand, ViewportInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Frames to wait for the window to report its monitor (at startup and after a restore).
+const SETTLE_FRAMES: u32 = 10;
+
+/// Position, size and maximized state of the main window.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowPlacement {
+    /// Outer top-left corner in points
+    pub pos: [f32; 2],
+    /// Inner (content) size in points
+    pub size: [f32; 2],
+    pub maximized: bool,
+}
+
+impl WindowPlacement {
+    /// Reads the placement of a window, if the platform reports it.
+    fn of(info: &ViewportInfo) -> Option<Self> {
+        let outer = info.outer_rect?;
+        let inner = info.inner_rect?;
+        Some(Self {
+            pos: [outer.min.x, outer.min.y],
+            size: [inner.width(), inner.height()],
+            maximized: info.maximized.unwrap_or(false),
+        })
+    }
+
+    /// Returns the viewport commands that move the window to this placement.
+    fn commands(&self) -> Vec<ViewportCommand> {
+        let pos = ViewportCommand::OuterPosition(egui::pos2(self.pos[0], self.pos[1]));
+        if self.maximized {
+            vec![pos, ViewportCommand::Maximized(true)]
+        } else {
+            vec![pos, ViewportCommand::InnerSize(egui::vec2(self.size[0], self.size[1]))]
+        }
+    }
+}
+
+/// Persisted window placements, one per monitor configuration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowPlacements {
+    /// Monitor key of the most recent placement
+    pub last_monitor: Option<String>,
+    /// Placement per monitor key (see [`monitor_key`])
+    pub by_monitor: BTreeMap<String, WindowPlacement>,
+}
+
+/// Identifies the monitor a window is on by its size and scale factor.
+pub fn monitor_key(info: &ViewportInfo) -> Option<String> {
+    let size = info.monitor_size?;
+    let scale = info.native_pixels_per_point.unwrap_or(1.0);
+    Some(format!("{}x{}@{}", size.x.round(), size.y.round(), scale))
+}
+
+/// Progress of restoring the saved placement at startup.
+#[derive(Debug, Clone, PartialEq)]
+enum RestorePhase {
+    /// Waiting for the window to report its monitor
+    Pending { frames_left: u32 },
+    /// Last placement applied; waiting for the window to report its new monitor
+    Verifying { expected: String, frames_left: u32 },
+    /// Restoring is over; the current placement is tracked every frame
+    Done,
+}
+
+/// State related to the main window placement.
+///
+/// Responsibilities:
+/// - Holding the persisted per-monitor placements
+/// - Restoring the last placement at startup, with a per-monitor fallback
+/// - Tracking the current placement once restoring is over
+#[derive(Debug, Clone)]
+pub struct WindowState {
+    /// Saved placements
+    placements: WindowPlacements,
+    /// Startup restore progress
+    phase: RestorePhase,
+}
+
+impl Default for WindowState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WindowState {
+    /// Creates a state with no saved placements.
+    pub fn new() -> Self {
+        Self::with_placements(WindowPlacements::default())
+    }
+
+    /// Creates a state that restores the given placements at startup.
+    pub fn with_placements(placements: WindowPlacements) -> Self {
+        Self {
+            placements,
+            phase: RestorePhase::Pending { frames_left: SETTLE_FRAMES },
+        }
+    }
+
+    // ===== Queries =====
+
+    /// Returns the placements to persist.
+    pub fn placements(&self) -> &WindowPlacements {
+        &self.placements
+    }
+
+    // ===== Mutations =====
+
+    /// Advances restoring (or tracking) by one frame.
+    ///
+    /// # Arguments
+    /// * `info` - The main viewport's current info
+    ///
+    /// # Returns
+    /// Viewport commands to send this frame.
+    pub fn on_frame(&mut self, info: &ViewportInfo) -> Vec<ViewportCommand> {
+        let key = monitor_key(info);
+        match &mut self.phase {
+            RestorePhase::Pending { frames_left } => {
+                if key.is_none() && *frames_left > 0 {
+                    *frames_left -= 1;
+                    return Vec::new();
+                }
+                let last = self.placements.last_monitor.clone().and_then(|monitor| {
+                    let placement = *self.placements.by_monitor.get(&monitor)?;
+                    Some((monitor, placement))
+                });
+                match last {
+                    Some((expected, placement)) => {
+                        self.phase = RestorePhase::Verifying { expected, frames_left: SETTLE_FRAMES };
+                        placement.commands()
+                    }
+                    None => {
+                        self.phase = RestorePhase::Done;
+                        Vec::new()
+                    }
+                }
+            }
+            RestorePhase::Verifying { expected, frames_left } => {
+                if key.as_ref() == Some(expected) {
+                    self.phase = RestorePhase::Done;
+                    return Vec::new();
+                }
+                if *frames_left > 0 {
+                    *frames_left -= 1;
+                    return Vec::new();
+                }
+                // The saved monitor configuration is gone: use this monitor's placement,
+                // or bring the window back to the origin if it is on no known monitor
+                self.phase = RestorePhase::Done;
+                match key.and_then(|key| self.placements.by_monitor.get(&key)) {
+                    Some(placement) => placement.commands(),
+                    None if info.monitor_size.is_none() => {
+                        vec![ViewportCommand::OuterPosition(egui::Pos2::ZERO)]
+                    }
+                    None => Vec::new(),
+                }
+            }
+            RestorePhase::Done => {
+                let visible = !info.minimized.unwrap_or(false) && !info.fullscreen.unwrap_or(false);
+                if let (Some(key), Some(placement), true) = (key, WindowPlacement::of(info), visible) {
+                    self.placements.by_monitor.insert(key.clone(), placement);
+                    self.placements.last_monitor = Some(key);
+                }
+                Vec::new()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(monitor: (f32, f32), pos: (f32, f32)) -> ViewportInfo {
+        ViewportInfo {
+            monitor_size: Some(egui::vec2(monitor.0, monitor.1)),
+            native_pixels_per_point: Some(1.0),
+            outer_rect: Some(egui::Rect::from_min_size(egui::pos2(pos.0, pos.1), egui::vec2(800.0, 600.0))),
+            inner_rect: Some(egui::Rect::from_min_size(egui::pos2(pos.0, pos.1 + 30.0), egui::vec2(800.0, 570.0))),
+            maximized: Some(false),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_tracks_placement_per_monitor() {
+        let mut window = WindowState::new();
+        assert!(window.on_frame(&info((1920.0, 1080.0), (10.0, 20.0))).is_empty());
+        window.on_frame(&info((1920.0, 1080.0), (10.0, 20.0)));
+        window.on_frame(&info((2560.0, 1440.0), (2000.0, 40.0)));
+
+        let placements = window.placements();
+        assert_eq!(placements.by_monitor.len(), 2);
+        assert_eq!(placements.last_monitor.as_deref(), Some("2560x1440@1"));
+        assert_eq!(placements.by_monitor["1920x1080@1"].pos, [10.0, 20.0]);
+    }
+
+    #[test]
+    fn test_restores_last_placement_and_falls_back_per_monitor() {
+        let mut tracked = WindowState::new();
+        tracked.on_frame(&info((1920.0, 1080.0), (0.0, 0.0)));
+        tracked.on_frame(&info((1920.0, 1080.0), (100.0, 100.0)));
+        tracked.on_frame(&info((2560.0, 1440.0), (2000.0, 40.0)));
+        let saved = tracked.placements().clone();
+
+        // External monitor attached: the last placement is applied and kept
+        let mut window = WindowState::with_placements(saved.clone());
+        let commands = window.on_frame(&info((1920.0, 1080.0), (0.0, 0.0)));
+        assert_eq!(commands[0], ViewportCommand::OuterPosition(egui::pos2(2000.0, 40.0)));
+        assert!(window.on_frame(&info((2560.0, 1440.0), (2000.0, 40.0))).is_empty());
+        assert_eq!(window.phase, RestorePhase::Done);
+
+        // External monitor gone: after settling, the laptop placement is used
+        let mut window = WindowState::with_placements(saved);
+        window.on_frame(&info((1920.0, 1080.0), (0.0, 0.0)));
+        let mut commands = Vec::new();
+        for _ in 0..=SETTLE_FRAMES {
+            commands = window.on_frame(&info((1920.0, 1080.0), (0.0, 0.0)));
+        }
+        assert_eq!(commands[0], ViewportCommand::OuterPosition(egui::pos2(100.0, 100.0)));
+    }
+}
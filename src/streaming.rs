@@ -0,0 +1,345 @@
+//! Line-by-line reading of JETS traces.
+//!
+//! [`parse_trace`](crate::parse_trace) builds the whole record arena before
+//! returning, so memory grows with the trace. A [`StreamingJetsParser`]
+//! instead yields one [`TraceLine`] at a time and keeps only the current
+//! line, which lets tools scan multi-GB traces and keep just the index they
+//! need. Lines are decoded as by the full parser (legacy layouts upgraded,
+//! the header checked to come first), but nothing is cross-checked: a
+//! `record_end` for an unknown record is yielded like any other line.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use rjets::{StreamingJetsParser, TraceLine};
+//! # use std::collections::HashMap;
+//! # fn main() -> anyhow::Result<()> {
+//! // Records per type, without holding the records
+//! let mut per_type: HashMap<String, usize> = HashMap::new();
+//! for line in StreamingJetsParser::open("huge.jets.br")? {
+//!     if let TraceLine::Record { record_type, .. } = line? {
+//!         *per_type.entry(record_type).or_default() += 1;
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io::BufRead;
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use serde_json::value::RawValue;
+
+use crate::compat::{self, LegacyLayout};
+use crate::lazy_payload::LazyPayload;
+use crate::parser::open_trace_file;
+use crate::provenance::Provenance;
+use crate::schema::FormatVersion;
+use crate::traits::ExternalId;
+
+/// One trace line after its `type` tag has been resolved.
+///
+/// IDs are the ones written in the file; payloads stay unparsed until read.
+#[derive(Debug)]
+pub enum TraceLine {
+    Header {
+        version: String,
+        metadata: serde_json::Value,
+    },
+    Record {
+        clk: i64,
+        name: String,
+        record_type: String,
+        id: ExternalId,
+        parent_id: Option<ExternalId>,
+        description: String,
+        data: Option<LazyPayload>,
+        /// Records this one depends on (empty if the line has no `depends_on`)
+        depends_on: Vec<ExternalId>,
+    },
+    RecordEnd {
+        clk: i64,
+        record_id: ExternalId,
+    },
+    Annotation {
+        name: String,
+        record_id: ExternalId,
+        description: String,
+        data: serde_json::Value,
+    },
+    Event {
+        clk: i64,
+        name: String,
+        record_id: ExternalId,
+        description: String,
+        data: Option<LazyPayload>,
+    },
+    Footer {
+        capture_end_clk: Option<i64>,
+        total_records: Option<usize>,
+        total_annotations: Option<usize>,
+        total_events: Option<usize>,
+        provenance: Option<Provenance>,
+    },
+}
+
+/// A line as deserialized: the union of all line types' fields.
+///
+/// Payloads are captured as raw JSON text. serde's internally tagged enums
+/// buffer every field before picking the variant, which would build the
+/// full value of each payload; a flat struct lets `data` stay unparsed.
+#[derive(Deserialize)]
+struct RawTraceLine<'a> {
+    #[serde(rename = "type")]
+    line_type: String,
+    version: Option<String>,
+    #[serde(borrow)]
+    metadata: Option<&'a RawValue>,
+    clk: Option<i64>,
+    name: Option<String>,
+    record_type: Option<String>,
+    id: Option<ExternalId>,
+    parent_id: Option<ExternalId>,
+    record_id: Option<ExternalId>,
+    description: Option<String>,
+    #[serde(borrow)]
+    data: Option<&'a RawValue>,
+    depends_on: Option<Vec<ExternalId>>,
+    capture_end_clk: Option<i64>,
+    total_records: Option<usize>,
+    total_annotations: Option<usize>,
+    total_events: Option<usize>,
+    provenance: Option<Provenance>,
+}
+
+impl TryFrom<RawTraceLine<'_>> for TraceLine {
+    type Error = anyhow::Error;
+
+    fn try_from(line: RawTraceLine<'_>) -> Result<Self> {
+        fn required<T>(value: Option<T>, field: &str) -> Result<T> {
+            value.ok_or_else(|| anyhow!("missing field `{}`", field))
+        }
+        fn parsed(raw: Option<&RawValue>, field: &str) -> Result<serde_json::Value> {
+            Ok(serde_json::from_str(required(raw, field)?.get())?)
+        }
+        // An explicit `null` payload is the same as none
+        let payload = |raw: Option<&RawValue>| raw.filter(|raw| raw.get() != "null").map(|raw| LazyPayload::from_raw(raw.get()));
+
+        Ok(match line.line_type.as_str() {
+            "header" => TraceLine::Header {
+                version: required(line.version, "version")?,
+                metadata: parsed(line.metadata, "metadata")?,
+            },
+            "record" => TraceLine::Record {
+                clk: required(line.clk, "clk")?,
+                name: required(line.name, "name")?,
+                record_type: required(line.record_type, "record_type")?,
+                id: required(line.id, "id")?,
+                parent_id: line.parent_id,
+                description: required(line.description, "description")?,
+                data: payload(line.data),
+                depends_on: line.depends_on.unwrap_or_default(),
+            },
+            "record_end" => TraceLine::RecordEnd {
+                clk: required(line.clk, "clk")?,
+                record_id: required(line.record_id, "record_id")?,
+            },
+            "annotation" => TraceLine::Annotation {
+                name: required(line.name, "name")?,
+                record_id: required(line.record_id, "record_id")?,
+                description: required(line.description, "description")?,
+                data: parsed(line.data, "data")?,
+            },
+            "event" => TraceLine::Event {
+                clk: required(line.clk, "clk")?,
+                name: required(line.name, "name")?,
+                record_id: required(line.record_id, "record_id")?,
+                description: required(line.description, "description")?,
+                data: payload(line.data),
+            },
+            "footer" => TraceLine::Footer {
+                capture_end_clk: line.capture_end_clk,
+                total_records: line.total_records,
+                total_annotations: line.total_annotations,
+                total_events: line.total_events,
+                provenance: line.provenance,
+            },
+            other => return Err(anyhow!("unknown line type `{}`", other)),
+        })
+    }
+}
+
+/// Iterator over the lines of a JETS trace.
+///
+/// Blank lines are skipped. The iteration ends after the first error.
+pub struct StreamingJetsParser<R> {
+    reader: R,
+    /// Text of the current line, reused between lines
+    buffer: String,
+    /// 1-based number of the last line read
+    line_number: usize,
+    /// Set from the header; lines of legacy traces are upgraded before deserializing
+    layout: Option<&'static LegacyLayout>,
+    /// Version warnings from the header
+    warnings: Vec<String>,
+    failed: bool,
+}
+
+impl<R: BufRead> StreamingJetsParser<R> {
+    /// Reads lines from any buffered reader yielding plain (already
+    /// decompressed) JSON Lines.
+    pub fn new(reader: R) -> Self {
+        StreamingJetsParser {
+            reader,
+            buffer: String::new(),
+            line_number: 0,
+            layout: None,
+            warnings: Vec::new(),
+            failed: false,
+        }
+    }
+
+    /// Returns the 1-based file line number of the last yielded line or error.
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    /// Returns the warnings about the header's version so far.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Consumes the parser, returning its warnings.
+    pub fn into_warnings(self) -> Vec<String> {
+        self.warnings
+    }
+
+    /// Decodes the line in the buffer.
+    fn decode(&mut self) -> Result<TraceLine> {
+        let line_number = self.line_number;
+        // Legacy lines are rewritten to the current layout and read from the rewritten text
+        let upgraded = match self.layout {
+            None => None,
+            Some(layout) => {
+                let mut value: serde_json::Value = serde_json::from_str(&self.buffer)
+                    .with_context(|| format!("Failed to parse JSON at line {}", line_number))?;
+                if let Some(object) = value.as_object_mut() {
+                    layout.upgrade(object);
+                }
+                Some(value.to_string())
+            }
+        };
+        let trace_line = decode_line(upgraded.as_deref().unwrap_or(&self.buffer))
+            .with_context(|| format!("Failed to parse JSON at line {}", line_number))?;
+
+        if let TraceLine::Header { version, .. } = &trace_line {
+            if line_number != 1 {
+                return Err(anyhow!("Header must be first line (found at line {})", line_number));
+            }
+            self.layout = check_version(version, &mut self.warnings);
+        }
+        Ok(trace_line)
+    }
+}
+
+/// Decodes one line written in the current layout.
+pub(crate) fn decode_line(text: &str) -> Result<TraceLine> {
+    serde_json::from_str::<RawTraceLine>(text)
+        .map_err(anyhow::Error::from)
+        .and_then(TraceLine::try_from)
+}
+
+/// Returns the legacy layout a header's version needs, warning about newer
+/// or unrecognized versions (which are read in the current layout).
+pub(crate) fn check_version(version: &str, warnings: &mut Vec<String>) -> Option<&'static LegacyLayout> {
+    match FormatVersion::parse(version) {
+        Some(parsed) if parsed.major > FormatVersion::CURRENT.major => {
+            warnings.push(format!(
+                "Trace version {} is newer than the supported version {}; unknown fields are ignored",
+                version,
+                FormatVersion::CURRENT
+            ));
+            None
+        }
+        Some(parsed) => compat::layout_for(parsed),
+        None => {
+            warnings.push(format!(
+                "Unrecognized trace version '{}'; reading as version {}",
+                version,
+                FormatVersion::CURRENT
+            ));
+            None
+        }
+    }
+}
+
+impl StreamingJetsParser<Box<dyn BufRead>> {
+    /// Opens a trace file, decompressing `.br` files.
+    pub fn open(file_path: &str) -> Result<Self> {
+        Ok(Self::new(open_trace_file(file_path)?))
+    }
+}
+
+impl<R: BufRead> Iterator for StreamingJetsParser<R> {
+    type Item = Result<TraceLine>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.failed {
+            self.buffer.clear();
+            let read = self.reader.read_line(&mut self.buffer);
+            // Drop the terminator as `BufRead::lines` does, so error columns match
+            let text_len = self.buffer.trim_end_matches(['\n', '\r']).len();
+            self.buffer.truncate(text_len);
+            let result = match read {
+                Ok(0) => return None,
+                Ok(_) if self.buffer.trim().is_empty() => {
+                    self.line_number += 1;
+                    continue;
+                }
+                Ok(_) => {
+                    self.line_number += 1;
+                    self.decode()
+                }
+                Err(e) => {
+                    self.line_number += 1;
+                    Err(anyhow::Error::from(e).context(format!("Failed to read line {}", self.line_number)))
+                }
+            };
+            self.failed = result.is_err();
+            return Some(result);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_streams_lines_with_numbers() {
+        let input = concat!(
+            r#"{"type":"header","version":"1.0","metadata":{}}"#, "\n",
+            r#"{"type":"record","clk":0,"name":"core","record_type":"Core","id":1,"parent_id":null,"description":""}"#, "\n",
+            "\n",
+            r#"{"type":"event","clk":3,"name":"IRQ","id":1}"#, "\n",
+            r#"{"type":"record_end","clk":9,"record_id":7}"#, "\n",
+            r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+            r#"{"type":"record_end","clk":9,"record_id":1}"#, "\n",
+        );
+        let mut lines = StreamingJetsParser::new(input.as_bytes());
+
+        assert!(matches!(lines.next(), Some(Ok(TraceLine::Header { .. }))));
+        assert!(matches!(lines.next(), Some(Ok(TraceLine::Record { clk: 0, .. }))));
+        // Version 1.0 layout: `id` and the missing description are upgraded
+        let Some(Ok(TraceLine::Event { name, .. })) = lines.next() else { panic!("expected event") };
+        assert_eq!((name.as_str(), lines.line_number()), ("IRQ", 4), "blank line counted");
+        // Not cross-checked against the records seen
+        assert!(matches!(lines.next(), Some(Ok(TraceLine::RecordEnd { record_id: ExternalId::Int(7), .. }))));
+
+        let error = lines.next().unwrap().unwrap_err();
+        assert!(error.to_string().contains("Header must be first line"), "{}", error);
+        assert!(lines.next().is_none(), "stops after an error");
+    }
+}
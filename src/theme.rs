@@ -44,6 +44,18 @@ pub struct ThemeColors {
     pub purple: Color32,
     pub magenta: Color32,
     pub gray: Color32,
+
+    // Timeline overlay and axis colors
+    /// Hover cursor line and its clock label
+    pub cursor: Color32,
+    /// Region selection rectangle fill (translucent)
+    pub selection_fill: Color32,
+    /// Region selection rectangle border
+    pub selection_border: Color32,
+    /// Time axis tick marks (minor ticks are drawn fainter)
+    pub axis_tick: Color32,
+    /// Time axis clock labels
+    pub axis_label: Color32,
 }
 
 /// A complete theme definition with metadata and color palette
@@ -171,6 +183,13 @@ fn light_theme() -> Theme {
             purple: Color32::from_rgb(140, 60, 180),
             magenta: Color32::from_rgb(200, 40, 160),
             gray: Color32::from_rgb(120, 120, 120),
+
+            // Timeline overlay and axis colors
+            cursor: Color32::from_rgb(180, 140, 0),
+            selection_fill: with_alpha(Color32::from_rgb(40, 100, 200), 80),
+            selection_border: Color32::from_rgb(40, 100, 200),
+            axis_tick: Color32::from_rgb(0, 0, 0),
+            axis_label: Color32::from_rgb(0, 0, 0),
         },
     }
 }
@@ -206,6 +225,13 @@ fn dark_theme() -> Theme {
             purple: Color32::from_rgb(155, 89, 182),
             magenta: Color32::from_rgb(255, 121, 198),
             gray: Color32::from_rgb(149, 165, 166),
+
+            // Timeline overlay and axis colors
+            cursor: Color32::from_rgb(241, 196, 15),
+            selection_fill: with_alpha(Color32::from_rgb(52, 152, 219), 80),
+            selection_border: Color32::from_rgb(52, 152, 219),
+            axis_tick: Color32::from_rgb(255, 255, 255),
+            axis_label: Color32::from_rgb(255, 255, 255),
         },
     }
 }
@@ -248,6 +274,13 @@ fn dracula_theme() -> Theme {
             purple: hex_to_color32("#bd93f9"),   // Purple
             magenta: hex_to_color32("#ff79c6"),  // Pink
             gray: hex_to_color32("#6272a4"),     // Comment
+
+            // Timeline overlay and axis colors
+            cursor: hex_to_color32("#f1fa8c"),
+            selection_fill: with_alpha(hex_to_color32("#bd93f9"), 80),
+            selection_border: hex_to_color32("#bd93f9"),
+            axis_tick: hex_to_color32("#f8f8f2"),
+            axis_label: hex_to_color32("#f8f8f2"),
         },
     }
 }
@@ -290,6 +323,13 @@ fn one_dark_pro_theme() -> Theme {
             purple: hex_to_color32("#c678dd"),   // Magenta (purple)
             magenta: hex_to_color32("#c678dd"),  // Magenta
             gray: hex_to_color32("#5c6370"),     // Comment Grey
+
+            // Timeline overlay and axis colors
+            cursor: hex_to_color32("#e5c07b"),
+            selection_fill: with_alpha(hex_to_color32("#61afef"), 80),
+            selection_border: hex_to_color32("#61afef"),
+            axis_tick: hex_to_color32("#abb2bf"),
+            axis_label: hex_to_color32("#abb2bf"),
         },
     }
 }
@@ -320,3 +360,64 @@ pub fn adjust_brightness(color: Color32, factor: f32) -> Color32 {
 pub fn with_alpha(color: Color32, alpha: u8) -> Color32 {
     Color32::from_rgba_premultiplied(color.r(), color.g(), color.b(), alpha)
 }
+
+/// Relative luminance of a color (0.0 = black, 1.0 = white), per WCAG 2.x
+pub fn relative_luminance(color: Color32) -> f32 {
+    let channel = |c: u8| {
+        let c = c as f32 / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * channel(color.r()) + 0.7152 * channel(color.g()) + 0.0722 * channel(color.b())
+}
+
+/// Picks the theme text color that reads best on top of `background`.
+///
+/// Chooses between the theme's strong text color and its extreme background
+/// (the darkest color of a dark theme, the lightest of a light one), whichever
+/// has the higher contrast ratio against `background`.
+pub fn contrast_text_color(background: Color32, colors: &ThemeColors) -> Color32 {
+    let contrast = |color: Color32| {
+        let (a, b) = (relative_luminance(color), relative_luminance(background));
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    };
+    if contrast(colors.text_strong) >= contrast(colors.extreme_background) {
+        colors.text_strong
+    } else {
+        colors.extreme_background
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_luminance_bounds() {
+        assert_eq!(relative_luminance(Color32::BLACK), 0.0);
+        assert!((relative_luminance(Color32::WHITE) - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_contrast_text_color_follows_background() {
+        let manager = ThemeManager::new();
+        for name in manager.list_themes() {
+            let colors = &manager.get_theme(name).unwrap().colors;
+            let on_white = contrast_text_color(Color32::WHITE, colors);
+            let on_black = contrast_text_color(Color32::BLACK, colors);
+            assert!(relative_luminance(on_white) < relative_luminance(on_black), "theme {}", name);
+        }
+    }
+
+    #[test]
+    fn test_selection_fill_is_translucent() {
+        let manager = ThemeManager::new();
+        for name in manager.list_themes() {
+            let colors = &manager.get_theme(name).unwrap().colors;
+            assert!(colors.selection_fill.a() < 255, "theme {}", name);
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use rjets::TraceWriter;
+use rjets::{SortedTraceWriter, TraceWriter};
+use rjets::logging;
+use rjets::schema::FormatVersion;
 use anyhow::Result;
 use std::env;
 
@@ -29,6 +31,10 @@ const REGISTERS: &[&str] = &[
     "s8", "s9", "s10", "s11", "t3", "t4", "t5", "t6",
 ];
 
+// How far (in clocks) an instruction's events may run ahead of later
+// instructions' starts
+const REORDER_WINDOW: i64 = 64;
+
 // Simple PRNG (Linear Congruential Generator)
 struct SimpleRng {
     state: u64,
@@ -84,42 +90,10 @@ impl Default for Config {
     }
 }
 
-// Items to emit, sorted by clock
-#[derive(Clone)]
-enum TraceItem {
-    Record {
-        id: u64,
-        parent_id: Option<u64>,
-        record_type: String,
-        clk: i64,
-        name: String,
-        description: String,
-        data: Option<serde_json::Value>,
-    },
-    Event {
-        record_id: u64,
-        name: String,
-        description: String,
-        clk: i64,
-    },
-    RecordEnd {
-        id: u64,
-        clk: i64,
-    },
-}
-
-impl TraceItem {
-    fn clk(&self) -> i64 {
-        match self {
-            TraceItem::Record { clk, .. } => *clk,
-            TraceItem::Event { clk, .. } => *clk,
-            TraceItem::RecordEnd { clk, .. } => *clk,
-        }
-    }
-}
-
 fn parse_args() -> Result<Config> {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+    let log_level = logging::take_log_level_arg(&mut args).map_err(anyhow::Error::msg)?;
+    logging::init(log_level.unwrap_or(logging::Level::WARN), None);
     let mut config = Config::default();
 
     let mut i = 1;
@@ -180,7 +154,7 @@ fn parse_args() -> Result<Config> {
                 std::process::exit(0);
             }
             _ => {
-                eprintln!("Warning: Unknown argument: {}", args[i]);
+                tracing::warn!("Unknown argument: {}", args[i]);
             }
         }
         i += 1;
@@ -201,6 +175,7 @@ fn print_help() {
     println!("                         If two numbers provided, generates random count in range [N, M]");
     println!("  -out <FILE>            Output file path (default: trace.jets)");
     println!("  -brotli                Write compressed trace using Brotli (output: *.jets.br)");
+    println!("  --log-level <LEVEL>    Log verbosity: error, warn, info, debug, trace (default: warn)");
     println!("  -h, -help, --help      Show this help message");
 }
 
@@ -216,9 +191,20 @@ fn main() -> Result<()> {
                 "trace.jets".to_string()
             }
         });
-    let mut writer = TraceWriter::new(&output_path)?;
-
+    let mut writer = TraceWriter::new(&output_path)?
+        .with_provenance(concat!("jets-tracegen ", env!("CARGO_PKG_VERSION")));
+
+    tracing::info!(
+        path = output_path.as_str(),
+        clusters = config.num_clusters,
+        cores = config.num_cores,
+        threads = config.num_threads,
+        "generating trace"
+    );
+    let started = std::time::Instant::now();
     generate_trace(&mut writer, &config)?;
+    writer.finish()?;
+    tracing::info!(elapsed_ms = started.elapsed().as_millis(), "trace generated");
 
     if output_path == "trace.jets" || output_path == "trace.jets.br" {
         println!("Trace written to: {}", output_path);
@@ -239,7 +225,7 @@ fn generate_trace(writer: &mut TraceWriter, config: &Config) -> Result<()> {
 
     // Write header
     writer.write_header(
-        "2.0",
+        &FormatVersion::CURRENT.to_string(),
         serde_json::json!({
             "hardware_model": "RISC-V SoC",
             "architecture": "RISC-V Pipeline",
@@ -299,19 +285,19 @@ fn generate_trace(writer: &mut TraceWriter, config: &Config) -> Result<()> {
 
                 let thread_start_clk = 0;
 
-                // Buffer all items for this thread to emit in sorted order
-                let mut items = Vec::new();
+                // Instructions overlap, so their events are written ahead of
+                // later instructions' starts and reordered by clock
+                let mut sorted = SortedTraceWriter::new(writer, REORDER_WINDOW);
 
-                // Add thread record
-                items.push(TraceItem::Record {
-                    id: thread_id,
-                    parent_id: Some(core_id),
-                    record_type: "Thread".to_string(),
-                    clk: thread_start_clk,
-                    name: format!("thread_{}", thread_idx),
-                    description: format!("Thread {}", thread_idx),
-                    data: None,
-                });
+                sorted.write_record(
+                    thread_id,
+                    Some(core_id),
+                    "Thread",
+                    thread_start_clk,
+                    &format!("thread_{}", thread_idx),
+                    &format!("Thread {}", thread_idx),
+                    None,
+                )?;
 
                 let mut pc = 0xFFFFFFFF00000000u64 + (cluster_idx * 0x100000 + core_idx * 0x10000 + thread_idx * 0x1000) as u64;
                 let mut instr_start_clk = clk;
@@ -366,131 +352,73 @@ fn generate_trace(writer: &mut TraceWriter, config: &Config) -> Result<()> {
                     let instr_name = format!("0x{:016X}-{}", pc, mnemonic);
 
                     // Add instruction record
-                    items.push(TraceItem::Record {
-                        id: instr_id,
-                        parent_id: Some(thread_id),
-                        record_type: "Instruction".to_string(),
-                        clk: instr_start_clk,
-                        name: instr_name,
-                        description: disassembly.clone(),
-                        data: Some(serde_json::json!({
+                    sorted.write_record(
+                        instr_id,
+                        Some(thread_id),
+                        "Instruction",
+                        instr_start_clk,
+                        &instr_name,
+                        &disassembly,
+                        Some(serde_json::json!({
                             "pc": format!("0x{:016X}", pc),
                             "opcode": mnemonic,
                             "disassembly": disassembly
                         })),
-                    });
+                    )?;
 
                     // Generate pipeline events
                     let mut event_clk = instr_start_clk;
 
                     // F1 - Fetch 1
-                    items.push(TraceItem::Event {
-                        record_id: instr_id,
-                        name: "F1".to_string(),
-                        description: "Fetch 1. Instruction fetch request, PC generation".to_string(),
-                        clk: event_clk,
-                    });
+                    sorted.write_event(instr_id, "F1", "Fetch 1. Instruction fetch request, PC generation", event_clk, None)?;
                     event_clk += 1;
 
                     // F2 - Fetch 2
-                    items.push(TraceItem::Event {
-                        record_id: instr_id,
-                        name: "F2".to_string(),
-                        description: "Fetch 2. Instruction cache access and retrieval".to_string(),
-                        clk: event_clk,
-                    });
+                    sorted.write_event(instr_id, "F2", "Fetch 2. Instruction cache access and retrieval", event_clk, None)?;
                     event_clk += 1;
 
                     // D - Decode
-                    items.push(TraceItem::Event {
-                        record_id: instr_id,
-                        name: "D".to_string(),
-                        description: "Decode. Instruction decode and branch prediction".to_string(),
-                        clk: event_clk,
-                    });
+                    sorted.write_event(instr_id, "D", "Decode. Instruction decode and branch prediction", event_clk, None)?;
                     event_clk += 1;
 
                     // RN - Rename
-                    items.push(TraceItem::Event {
-                        record_id: instr_id,
-                        name: "RN".to_string(),
-                        description: "Rename. Register renaming to eliminate false dependencies".to_string(),
-                        clk: event_clk,
-                    });
+                    sorted.write_event(instr_id, "RN", "Rename. Register renaming to eliminate false dependencies", event_clk, None)?;
                     event_clk += 1;
 
                     // DS - Dispatch
-                    items.push(TraceItem::Event {
-                        record_id: instr_id,
-                        name: "DS".to_string(),
-                        description: "Dispatch. Dispatch instructions to reservation stations/issue queues".to_string(),
-                        clk: event_clk,
-                    });
+                    sorted.write_event(instr_id, "DS", "Dispatch. Dispatch instructions to reservation stations/issue queues", event_clk, None)?;
                     event_clk += 1;
 
                     // IS - Issue (might stall)
                     if rng.gen_range(0, 10) < 2 {
                         event_clk += rng.gen_i64_range(1, 4); // Random stall
                     }
-                    items.push(TraceItem::Event {
-                        record_id: instr_id,
-                        name: "IS".to_string(),
-                        description: "Issue. Issue instructions to execution units when operands are ready".to_string(),
-                        clk: event_clk,
-                    });
+                    sorted.write_event(instr_id, "IS", "Issue. Issue instructions to execution units when operands are ready", event_clk, None)?;
                     event_clk += 1;
 
                     // RR - Register Read
-                    items.push(TraceItem::Event {
-                        record_id: instr_id,
-                        name: "RR".to_string(),
-                        description: "Register Read. Read physical registers from register file".to_string(),
-                        clk: event_clk,
-                    });
+                    sorted.write_event(instr_id, "RR", "Register Read. Read physical registers from register file", event_clk, None)?;
                     event_clk += 1;
 
                     // EX - Execute
-                    items.push(TraceItem::Event {
-                        record_id: instr_id,
-                        name: "EX".to_string(),
-                        description: "Execute. Execute operation in ALU/FPU/other functional units".to_string(),
-                        clk: event_clk,
-                    });
+                    sorted.write_event(instr_id, "EX", "Execute. Execute operation in ALU/FPU/other functional units", event_clk, None)?;
                     event_clk += rng.gen_i64_range(1, 3); // Execution can take 1-2 cycles
 
                     // M - Memory (only for load/store)
                     if is_mem {
-                        items.push(TraceItem::Event {
-                            record_id: instr_id,
-                            name: "M".to_string(),
-                            description: "Memory. Memory access for load/store instructions".to_string(),
-                            clk: event_clk,
-                        });
+                        sorted.write_event(instr_id, "M", "Memory. Memory access for load/store instructions", event_clk, None)?;
                         event_clk += rng.gen_i64_range(2, 6); // Memory can take longer
                     }
 
                     // WB - Writeback
-                    items.push(TraceItem::Event {
-                        record_id: instr_id,
-                        name: "WB".to_string(),
-                        description: "Writeback. Write results back to physical register file".to_string(),
-                        clk: event_clk,
-                    });
+                    sorted.write_event(instr_id, "WB", "Writeback. Write results back to physical register file", event_clk, None)?;
                     event_clk += 1;
 
                     // C - Commit
-                    items.push(TraceItem::Event {
-                        record_id: instr_id,
-                        name: "C".to_string(),
-                        description: "Commit/Retire. Commit instructions in program order and update architectural state".to_string(),
-                        clk: event_clk,
-                    });
+                    sorted.write_event(instr_id, "C", "Commit/Retire. Commit instructions in program order and update architectural state", event_clk, None)?;
 
                     // Instruction ends
-                    items.push(TraceItem::RecordEnd {
-                        id: instr_id,
-                        clk: event_clk,
-                    });
+                    sorted.write_record_end(instr_id, event_clk)?;
 
                     // Update PC
                     pc += 4;
@@ -500,34 +428,10 @@ fn generate_trace(writer: &mut TraceWriter, config: &Config) -> Result<()> {
                     instr_start_clk += rng.gen_i64_range(1, 3);
                 }
 
-                // Find max clock time for thread end
-                let thread_end_clk = items.iter()
-                    .map(|item| item.clk())
-                    .max()
-                    .unwrap_or(thread_start_clk) + 1;
-
-                items.push(TraceItem::RecordEnd {
-                    id: thread_id,
-                    clk: thread_end_clk,
-                });
-
-                // Sort all items by clock time to ensure monotonicity
-                items.sort_by_key(|item| item.clk());
-
-                // Emit all items in sorted order
-                for item in items {
-                    match item {
-                        TraceItem::Record { id, parent_id, record_type, clk, name, description, data } => {
-                            writer.write_record(id, parent_id, &record_type, clk, &name, &description, data)?;
-                        }
-                        TraceItem::Event { record_id, name, description, clk } => {
-                            writer.write_event(record_id, &name, &description, clk, None)?;
-                        }
-                        TraceItem::RecordEnd { id, clk } => {
-                            writer.write_record_end(id, clk)?;
-                        }
-                    }
-                }
+                // Thread ends after its last instruction
+                let thread_end_clk = sorted.latest_clk().unwrap_or(thread_start_clk) + 1;
+                sorted.write_record_end(thread_id, thread_end_clk)?;
+                sorted.finish()?;
 
                 // Update global clock to be past this thread
                 clk = thread_end_clk;
@@ -1,6 +1,57 @@
 /// Type alias for record IDs (domain identifiers from trace files)
 pub type RecordId = u64;
 
+/// A record ID as written in the trace file.
+///
+/// Producers may identify records by integer or by string (e.g. UUIDs).
+/// Readers map string IDs to `RecordId` handles internally and keep the
+/// original value so it can be shown to the user and written back on export.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+pub enum ExternalId {
+    Int(u64),
+    Str(std::sync::Arc<str>),
+}
+
+impl ExternalId {
+    /// Returns the ID as a JSON value (number or string).
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            ExternalId::Int(n) => serde_json::Value::from(*n),
+            ExternalId::Str(s) => serde_json::Value::from(s.as_ref()),
+        }
+    }
+}
+
+impl From<u64> for ExternalId {
+    fn from(id: u64) -> Self {
+        ExternalId::Int(id)
+    }
+}
+
+impl From<&str> for ExternalId {
+    fn from(id: &str) -> Self {
+        ExternalId::Str(std::sync::Arc::from(id))
+    }
+}
+
+impl std::fmt::Display for ExternalId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExternalId::Int(n) => write!(f, "{}", n),
+            ExternalId::Str(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+/// Non-timed data attached to a record by an `annotation` line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordAnnotation {
+    pub name: String,
+    pub description: String,
+    pub data: serde_json::Value,
+}
+
 /// Trait for accessing attributes in an ordered, efficient manner.
 /// 
 /// This trait provides methods to:
@@ -27,35 +78,80 @@ pub trait AttributeAccessor {
 }
 
 // Forward declarations for enum types (defined at end of file)
+#[allow(clippy::large_enum_variant)]
 pub enum DynTraceData {
     Jets(crate::parser::JetsTraceData),
     Virtual(crate::virtual_reader::VirtualTraceData),
+    Mmap(crate::mmap_reader::MmapJetsTraceData),
     Pipetrace(crate::pipetrace_reader::PipetraceData),
+    Multi(crate::multi_trace::MultiTraceData),
 }
 
 pub enum DynTraceMetadata<'a> {
     Jets(crate::parser::JetsTraceMetadataRef<'a>),
     Virtual(crate::virtual_reader::VirtualTraceDataRef<'a>),
     Pipetrace(crate::pipetrace_reader::PipetraceMetadataRef<'a>),
+    Multi(crate::multi_trace::MultiTraceMetadataRef<'a>),
 }
 
 #[derive(Clone)]
 pub enum DynTraceRecord<'a> {
     Jets(crate::parser::JetsTraceRecordRef<'a>),
     Virtual(crate::virtual_reader::VirtualTraceRecordRef<'a>),
+    Mmap(crate::mmap_reader::MmapJetsRecordRef<'a>),
     Pipetrace(crate::pipetrace_reader::PipetraceRecordRef<'a>),
+    Multi(crate::multi_trace::MultiTraceRecordRef<'a>),
 }
 
 pub enum DynTraceEvent<'a> {
     Jets(crate::parser::JetsTraceEventRef<'a>),
     Virtual(crate::virtual_reader::VirtualTraceEventRef<'a>),
     Pipetrace(crate::pipetrace_reader::PipetraceEventRef<'a>),
+    Multi(crate::multi_trace::MultiTraceEventRef<'a>),
 }
 
 /// Trait for reading trace files and returning TraceData
 pub trait TraceReader {
     /// Opens and parses a trace file, returning a DynTraceData enum
     fn read(&self, file_path: &str) -> anyhow::Result<DynTraceData>;
+
+    /// Like [`TraceReader::read`], but gives up once `cancel` is set.
+    ///
+    /// The default reads the whole file and only then checks the flag;
+    /// readers that can stop part-way override it.
+    fn read_cancelable(&self, file_path: &str, cancel: &std::sync::atomic::AtomicBool) -> anyhow::Result<DynTraceData> {
+        let data = self.read(file_path)?;
+        if cancel.load(std::sync::atomic::Ordering::Relaxed) {
+            anyhow::bail!("{}", LOAD_CANCELED);
+        }
+        Ok(data)
+    }
+}
+
+/// Error message of a read stopped by its cancel flag.
+pub const LOAD_CANCELED: &str = "load canceled";
+
+/// Future returned by [`AsyncTraceReader::read_async`].
+pub type TraceReadFuture =
+    std::pin::Pin<Box<dyn std::future::Future<Output = anyhow::Result<DynTraceData>> + Send>>;
+
+/// Trait for non-blocking trace backends (network, streaming)
+///
+/// Unlike [`TraceReader`], no thread is parked while the trace arrives: the
+/// returned future is polled by the caller and must return `Pending` (after
+/// arranging a wake-up) instead of blocking. No executor is assumed, so any
+/// I/O driver the backend needs is its own concern.
+pub trait AsyncTraceReader {
+    /// Starts reading the trace at `source` (a path, URL, ...).
+    fn read_async(&self, source: &str) -> TraceReadFuture;
+}
+
+/// Iterator over every record of a trace, returned by [`TraceData::records_iter`].
+pub type RecordsIter<'a, R> = Box<dyn Iterator<Item = R> + 'a>;
+
+/// Number of worker threads used by [`TraceData::par_fold_records`].
+pub fn record_fold_workers() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
 }
 
 /// Trait for accessing trace data
@@ -67,11 +163,100 @@ pub trait TraceData: Send {
     /// Returns metadata (information from headers and footers)
     fn metadata(&self) -> Self::Metadata<'_>;
 
-    /// Returns the IDs of root records
-    fn root_ids(&self) -> Vec<RecordId>;
+    /// Returns the IDs of root records without allocating
+    fn roots(&self) -> &[RecordId];
+
+    /// Returns the IDs of root records as an owned vector
+    fn root_ids(&self) -> Vec<RecordId> {
+        self.roots().to_vec()
+    }
 
     /// Gets a record by ID
     fn get_record(&self, id: RecordId) -> Option<Self::Record<'_>>;
+
+    /// Returns up to `len` children of record `id`, starting at child index `start`.
+    ///
+    /// Lets traversal and virtual scrolling ask for just the visible slice of a
+    /// wide parent. The default goes through `child_at`, which is cheap for
+    /// in-memory backends; remote or lazy backends should override it to fetch
+    /// the slice in one request.
+    fn children_range(&self, id: RecordId, start: usize, len: usize) -> Vec<Self::Record<'_>> {
+        let Some(parent) = self.get_record(id) else {
+            return Vec::new();
+        };
+        let end = start.saturating_add(len).min(parent.num_children());
+        (start..end).filter_map(|i| parent.child_at(i)).collect()
+    }
+
+    /// Returns the number of children of record `id` if known without fetching them.
+    ///
+    /// `None` means unknown (e.g. not yet loaded by a remote backend); callers
+    /// then fall back to `num_children` on the record.
+    fn num_children_hint(&self, id: RecordId) -> Option<usize> {
+        self.get_record(id).map(|record| record.num_children())
+    }
+
+    /// Finds the record with an ID as it appears in the source trace.
+    ///
+    /// The default handles numeric IDs, which backends use as-is; backends
+    /// with string IDs override it.
+    fn find_external_id(&self, id: &ExternalId) -> Option<RecordId> {
+        match id {
+            ExternalId::Int(n) => self.get_record(*n).map(|_| *n),
+            ExternalId::Str(_) => None,
+        }
+    }
+
+    /// Iterates every record of the trace exactly once, in unspecified order.
+    ///
+    /// Meant for whole-trace passes (statistics, search) that don't care about
+    /// the hierarchy. The default walks the tree from the roots; backends
+    /// that store records flat should override it with a plain scan.
+    fn records_iter(&self) -> RecordsIter<'_, Self::Record<'_>> {
+        let mut stack: Vec<Self::Record<'_>> =
+            self.roots().iter().rev().filter_map(|&id| self.get_record(id)).collect();
+        Box::new(std::iter::from_fn(move || {
+            let record = stack.pop()?;
+            stack.extend((0..record.num_children()).rev().filter_map(|i| record.child_at(i)));
+            Some(record)
+        }))
+    }
+
+    /// Parallel variant of [`records_iter`](Self::records_iter): folds every
+    /// record on [`record_fold_workers`] threads.
+    ///
+    /// Each worker starts from `init()`; the per-worker results are returned
+    /// for the caller to combine. The default splits the roots between the
+    /// workers, so a trace with a single root runs on one thread.
+    fn par_fold_records<'s, A, I, F>(&'s self, init: I, fold: F) -> Vec<A>
+    where
+        Self: Sync,
+        A: Send,
+        I: Fn() -> A + Sync,
+        F: Fn(A, Self::Record<'s>) -> A + Sync,
+    {
+        let roots = self.roots();
+        let chunk_len = roots.len().div_ceil(record_fold_workers()).max(1);
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = roots
+                .chunks(chunk_len)
+                .map(|chunk| {
+                    let (init, fold) = (&init, &fold);
+                    scope.spawn(move || {
+                        let mut acc = init();
+                        let mut stack: Vec<Self::Record<'s>> =
+                            chunk.iter().filter_map(|&id| self.get_record(id)).collect();
+                        while let Some(record) = stack.pop() {
+                            stack.extend((0..record.num_children()).filter_map(|i| record.child_at(i)));
+                            acc = fold(acc, record);
+                        }
+                        acc
+                    })
+                })
+                .collect();
+            workers.into_iter().map(|w| w.join().expect("record fold worker panicked")).collect()
+        })
+    }
 }
 
 /// Trait for accessing trace metadata
@@ -96,6 +281,19 @@ pub trait TraceMetadata {
 
     /// Returns the trace extent as (min_clk, max_clk) computed during parsing
     fn trace_extent(&self) -> (i64, i64);
+
+    /// Returns non-fatal problems found while reading the trace
+    /// (e.g. a version newer than the reader supports)
+    fn warnings(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Returns the footer's provenance block (content hash and producer), if any.
+    ///
+    /// The hash is not checked on load; see [`crate::provenance::verify_trace_file`].
+    fn provenance(&self) -> Option<crate::provenance::Provenance> {
+        None
+    }
 }
 
 /// Trait for accessing trace record
@@ -112,21 +310,52 @@ pub trait TraceRecord<'data>: Clone + AttributeAccessor {
     /// Returns the end timestamp (if available)
     fn end_clk(&self) -> Option<i64>;
 
+    /// Returns true if the end timestamp was inferred rather than recorded.
+    fn end_inferred(&self) -> bool {
+        false
+    }
+
     /// Returns the computed duration (end_clk - clk)
     fn duration(&self) -> Option<i64>;
 
     /// Returns the record name
     fn name(&self) -> String;
 
+    /// Returns the semantic record type (e.g. "Thread", "Instruction")
+    fn record_type(&self) -> String;
+
     /// Returns the record ID
     fn id(&self) -> RecordId;
 
     /// Returns the parent ID (if this is a child record)
     fn parent_id(&self) -> Option<RecordId>;
 
+    /// Returns the record ID as it appears in the source trace.
+    ///
+    /// Backends with purely numeric IDs can rely on the default.
+    fn external_id(&self) -> ExternalId {
+        ExternalId::Int(self.id())
+    }
+
     /// Returns the record description
     fn description(&self) -> String;
 
+    /// Returns the record's annotations in file order.
+    ///
+    /// Annotations are also the last entries of `attrs()`, after the `data`
+    /// attributes. Backends without annotations can rely on the default.
+    fn annotations(&self) -> Vec<RecordAnnotation> {
+        Vec::new()
+    }
+
+    /// Returns the IDs of the records this one depends on (the `depends_on`
+    /// field), all of which exist in the trace.
+    ///
+    /// Backends without dependencies can rely on the default.
+    fn depends_on(&self) -> Vec<RecordId> {
+        Vec::new()
+    }
+
     /// Returns the number of children
     fn num_children(&self) -> usize;
 
@@ -187,16 +416,20 @@ impl TraceData for DynTraceData {
         match self {
             DynTraceData::Jets(d) => DynTraceMetadata::Jets(d.metadata()),
             DynTraceData::Virtual(d) => DynTraceMetadata::Virtual(d.metadata()),
+            DynTraceData::Mmap(d) => DynTraceMetadata::Jets(d.metadata()),
             DynTraceData::Pipetrace(d) => DynTraceMetadata::Pipetrace(d.metadata()),
+            DynTraceData::Multi(d) => DynTraceMetadata::Multi(d.metadata()),
         }
     }
 
     #[inline]
-    fn root_ids(&self) -> Vec<RecordId> {
+    fn roots(&self) -> &[RecordId] {
         match self {
-            DynTraceData::Jets(d) => d.root_ids(),
-            DynTraceData::Virtual(d) => d.root_ids(),
-            DynTraceData::Pipetrace(d) => d.root_ids(),
+            DynTraceData::Jets(d) => d.roots(),
+            DynTraceData::Virtual(d) => d.roots(),
+            DynTraceData::Mmap(d) => d.roots(),
+            DynTraceData::Pipetrace(d) => d.roots(),
+            DynTraceData::Multi(d) => d.roots(),
         }
     }
 
@@ -205,7 +438,66 @@ impl TraceData for DynTraceData {
         match self {
             DynTraceData::Jets(d) => d.get_record(id).map(DynTraceRecord::Jets),
             DynTraceData::Virtual(d) => d.get_record(id).map(DynTraceRecord::Virtual),
+            DynTraceData::Mmap(d) => d.get_record(id).map(DynTraceRecord::Mmap),
             DynTraceData::Pipetrace(d) => d.get_record(id).map(DynTraceRecord::Pipetrace),
+            DynTraceData::Multi(d) => d.get_record(id).map(DynTraceRecord::Multi),
+        }
+    }
+
+    fn children_range(&self, id: RecordId, start: usize, len: usize) -> Vec<Self::Record<'_>> {
+        match self {
+            DynTraceData::Jets(d) => d.children_range(id, start, len).into_iter().map(DynTraceRecord::Jets).collect(),
+            DynTraceData::Virtual(d) => d.children_range(id, start, len).into_iter().map(DynTraceRecord::Virtual).collect(),
+            DynTraceData::Mmap(d) => d.children_range(id, start, len).into_iter().map(DynTraceRecord::Mmap).collect(),
+            DynTraceData::Pipetrace(d) => d.children_range(id, start, len).into_iter().map(DynTraceRecord::Pipetrace).collect(),
+            DynTraceData::Multi(d) => d.children_range(id, start, len).into_iter().map(DynTraceRecord::Multi).collect(),
+        }
+    }
+
+    #[inline]
+    fn num_children_hint(&self, id: RecordId) -> Option<usize> {
+        match self {
+            DynTraceData::Jets(d) => d.num_children_hint(id),
+            DynTraceData::Virtual(d) => d.num_children_hint(id),
+            DynTraceData::Mmap(d) => d.num_children_hint(id),
+            DynTraceData::Pipetrace(d) => d.num_children_hint(id),
+            DynTraceData::Multi(d) => d.num_children_hint(id),
+        }
+    }
+
+    fn find_external_id(&self, id: &ExternalId) -> Option<RecordId> {
+        match self {
+            DynTraceData::Jets(d) => d.find_external_id(id),
+            DynTraceData::Virtual(d) => d.find_external_id(id),
+            DynTraceData::Mmap(d) => d.find_external_id(id),
+            DynTraceData::Pipetrace(d) => d.find_external_id(id),
+            DynTraceData::Multi(d) => d.find_external_id(id),
+        }
+    }
+
+    fn records_iter(&self) -> RecordsIter<'_, Self::Record<'_>> {
+        match self {
+            DynTraceData::Jets(d) => Box::new(d.records_iter().map(DynTraceRecord::Jets)),
+            DynTraceData::Virtual(d) => Box::new(d.records_iter().map(DynTraceRecord::Virtual)),
+            DynTraceData::Mmap(d) => Box::new(d.records_iter().map(DynTraceRecord::Mmap)),
+            DynTraceData::Pipetrace(d) => Box::new(d.records_iter().map(DynTraceRecord::Pipetrace)),
+            DynTraceData::Multi(d) => Box::new(d.records_iter().map(DynTraceRecord::Multi)),
+        }
+    }
+
+    fn par_fold_records<'s, A, I, F>(&'s self, init: I, fold: F) -> Vec<A>
+    where
+        Self: Sync,
+        A: Send,
+        I: Fn() -> A + Sync,
+        F: Fn(A, Self::Record<'s>) -> A + Sync,
+    {
+        match self {
+            DynTraceData::Jets(d) => d.par_fold_records(init, |acc, r| fold(acc, DynTraceRecord::Jets(r))),
+            DynTraceData::Virtual(d) => d.par_fold_records(init, |acc, r| fold(acc, DynTraceRecord::Virtual(r))),
+            DynTraceData::Mmap(d) => d.par_fold_records(init, |acc, r| fold(acc, DynTraceRecord::Mmap(r))),
+            DynTraceData::Pipetrace(d) => d.par_fold_records(init, |acc, r| fold(acc, DynTraceRecord::Pipetrace(r))),
+            DynTraceData::Multi(d) => d.par_fold_records(init, |acc, r| fold(acc, DynTraceRecord::Multi(r))),
         }
     }
 }
@@ -217,6 +509,7 @@ impl<'a> TraceMetadata for DynTraceMetadata<'a> {
             DynTraceMetadata::Jets(m) => m.version(),
             DynTraceMetadata::Virtual(m) => m.version(),
             DynTraceMetadata::Pipetrace(m) => m.version(),
+            DynTraceMetadata::Multi(m) => m.version(),
         }
     }
 
@@ -226,6 +519,7 @@ impl<'a> TraceMetadata for DynTraceMetadata<'a> {
             DynTraceMetadata::Jets(m) => m.header_data(),
             DynTraceMetadata::Virtual(m) => m.header_data(),
             DynTraceMetadata::Pipetrace(m) => m.header_data(),
+            DynTraceMetadata::Multi(m) => m.header_data(),
         }
     }
 
@@ -235,6 +529,7 @@ impl<'a> TraceMetadata for DynTraceMetadata<'a> {
             DynTraceMetadata::Jets(m) => m.capture_end_clk(),
             DynTraceMetadata::Virtual(m) => m.capture_end_clk(),
             DynTraceMetadata::Pipetrace(m) => m.capture_end_clk(),
+            DynTraceMetadata::Multi(m) => m.capture_end_clk(),
         }
     }
 
@@ -244,6 +539,7 @@ impl<'a> TraceMetadata for DynTraceMetadata<'a> {
             DynTraceMetadata::Jets(m) => m.total_records(),
             DynTraceMetadata::Virtual(m) => m.total_records(),
             DynTraceMetadata::Pipetrace(m) => m.total_records(),
+            DynTraceMetadata::Multi(m) => m.total_records(),
         }
     }
 
@@ -253,6 +549,7 @@ impl<'a> TraceMetadata for DynTraceMetadata<'a> {
             DynTraceMetadata::Jets(m) => m.total_annotations(),
             DynTraceMetadata::Virtual(m) => m.total_annotations(),
             DynTraceMetadata::Pipetrace(m) => m.total_annotations(),
+            DynTraceMetadata::Multi(m) => m.total_annotations(),
         }
     }
 
@@ -262,6 +559,7 @@ impl<'a> TraceMetadata for DynTraceMetadata<'a> {
             DynTraceMetadata::Jets(m) => m.total_events(),
             DynTraceMetadata::Virtual(m) => m.total_events(),
             DynTraceMetadata::Pipetrace(m) => m.total_events(),
+            DynTraceMetadata::Multi(m) => m.total_events(),
         }
     }
 
@@ -271,6 +569,27 @@ impl<'a> TraceMetadata for DynTraceMetadata<'a> {
             DynTraceMetadata::Jets(m) => m.trace_extent(),
             DynTraceMetadata::Virtual(m) => m.trace_extent(),
             DynTraceMetadata::Pipetrace(m) => m.trace_extent(),
+            DynTraceMetadata::Multi(m) => m.trace_extent(),
+        }
+    }
+
+    #[inline]
+    fn warnings(&self) -> Vec<String> {
+        match self {
+            DynTraceMetadata::Jets(m) => m.warnings(),
+            DynTraceMetadata::Virtual(m) => m.warnings(),
+            DynTraceMetadata::Pipetrace(m) => m.warnings(),
+            DynTraceMetadata::Multi(m) => m.warnings(),
+        }
+    }
+
+    #[inline]
+    fn provenance(&self) -> Option<crate::provenance::Provenance> {
+        match self {
+            DynTraceMetadata::Jets(m) => m.provenance(),
+            DynTraceMetadata::Virtual(m) => m.provenance(),
+            DynTraceMetadata::Pipetrace(m) => m.provenance(),
+            DynTraceMetadata::Multi(m) => m.provenance(),
         }
     }
 }
@@ -288,7 +607,9 @@ impl<'a> AttributeAccessor for DynTraceRecord<'a> {
         match self {
             DynTraceRecord::Jets(r) => r.attr_count(),
             DynTraceRecord::Virtual(r) => r.attr_count(),
+            DynTraceRecord::Mmap(r) => r.attr_count(),
             DynTraceRecord::Pipetrace(r) => r.attr_count(),
+            DynTraceRecord::Multi(r) => r.attr_count(),
         }
     }
 
@@ -297,7 +618,9 @@ impl<'a> AttributeAccessor for DynTraceRecord<'a> {
         match self {
             DynTraceRecord::Jets(r) => r.attr(key),
             DynTraceRecord::Virtual(r) => r.attr(key),
+            DynTraceRecord::Mmap(r) => r.attr(key),
             DynTraceRecord::Pipetrace(r) => r.attr(key),
+            DynTraceRecord::Multi(r) => r.attr(key),
         }
     }
 
@@ -306,7 +629,9 @@ impl<'a> AttributeAccessor for DynTraceRecord<'a> {
         match self {
             DynTraceRecord::Jets(r) => r.attr_at(index),
             DynTraceRecord::Virtual(r) => r.attr_at(index),
+            DynTraceRecord::Mmap(r) => r.attr_at(index),
             DynTraceRecord::Pipetrace(r) => r.attr_at(index),
+            DynTraceRecord::Multi(r) => r.attr_at(index),
         }
     }
 
@@ -315,7 +640,9 @@ impl<'a> AttributeAccessor for DynTraceRecord<'a> {
         match self {
             DynTraceRecord::Jets(r) => r.attrs(),
             DynTraceRecord::Virtual(r) => r.attrs(),
+            DynTraceRecord::Mmap(r) => r.attrs(),
             DynTraceRecord::Pipetrace(r) => r.attrs(),
+            DynTraceRecord::Multi(r) => r.attrs(),
         }
     }
 }
@@ -328,7 +655,9 @@ impl<'a> TraceRecord<'a> for DynTraceRecord<'a> {
         match self {
             DynTraceRecord::Jets(r) => r.clk(),
             DynTraceRecord::Virtual(r) => r.clk(),
+            DynTraceRecord::Mmap(r) => r.clk(),
             DynTraceRecord::Pipetrace(r) => r.clk(),
+            DynTraceRecord::Multi(r) => r.clk(),
         }
     }
 
@@ -337,7 +666,20 @@ impl<'a> TraceRecord<'a> for DynTraceRecord<'a> {
         match self {
             DynTraceRecord::Jets(r) => r.end_clk(),
             DynTraceRecord::Virtual(r) => r.end_clk(),
+            DynTraceRecord::Mmap(r) => r.end_clk(),
             DynTraceRecord::Pipetrace(r) => r.end_clk(),
+            DynTraceRecord::Multi(r) => r.end_clk(),
+        }
+    }
+
+    #[inline]
+    fn end_inferred(&self) -> bool {
+        match self {
+            DynTraceRecord::Jets(r) => r.end_inferred(),
+            DynTraceRecord::Virtual(r) => r.end_inferred(),
+            DynTraceRecord::Mmap(r) => r.end_inferred(),
+            DynTraceRecord::Pipetrace(r) => r.end_inferred(),
+            DynTraceRecord::Multi(r) => r.end_inferred(),
         }
     }
 
@@ -346,7 +688,9 @@ impl<'a> TraceRecord<'a> for DynTraceRecord<'a> {
         match self {
             DynTraceRecord::Jets(r) => r.duration(),
             DynTraceRecord::Virtual(r) => r.duration(),
+            DynTraceRecord::Mmap(r) => r.duration(),
             DynTraceRecord::Pipetrace(r) => r.duration(),
+            DynTraceRecord::Multi(r) => r.duration(),
         }
     }
 
@@ -355,7 +699,20 @@ impl<'a> TraceRecord<'a> for DynTraceRecord<'a> {
         match self {
             DynTraceRecord::Jets(r) => r.name(),
             DynTraceRecord::Virtual(r) => r.name(),
+            DynTraceRecord::Mmap(r) => r.name(),
             DynTraceRecord::Pipetrace(r) => r.name(),
+            DynTraceRecord::Multi(r) => r.name(),
+        }
+    }
+
+    #[inline]
+    fn record_type(&self) -> String {
+        match self {
+            DynTraceRecord::Jets(r) => r.record_type(),
+            DynTraceRecord::Virtual(r) => r.record_type(),
+            DynTraceRecord::Mmap(r) => r.record_type(),
+            DynTraceRecord::Pipetrace(r) => r.record_type(),
+            DynTraceRecord::Multi(r) => r.record_type(),
         }
     }
 
@@ -364,7 +721,9 @@ impl<'a> TraceRecord<'a> for DynTraceRecord<'a> {
         match self {
             DynTraceRecord::Jets(r) => r.id(),
             DynTraceRecord::Virtual(r) => r.id(),
+            DynTraceRecord::Mmap(r) => r.id(),
             DynTraceRecord::Pipetrace(r) => r.id(),
+            DynTraceRecord::Multi(r) => r.id(),
         }
     }
 
@@ -373,7 +732,20 @@ impl<'a> TraceRecord<'a> for DynTraceRecord<'a> {
         match self {
             DynTraceRecord::Jets(r) => r.parent_id(),
             DynTraceRecord::Virtual(r) => r.parent_id(),
+            DynTraceRecord::Mmap(r) => r.parent_id(),
             DynTraceRecord::Pipetrace(r) => r.parent_id(),
+            DynTraceRecord::Multi(r) => r.parent_id(),
+        }
+    }
+
+    #[inline]
+    fn external_id(&self) -> ExternalId {
+        match self {
+            DynTraceRecord::Jets(r) => r.external_id(),
+            DynTraceRecord::Virtual(r) => r.external_id(),
+            DynTraceRecord::Mmap(r) => r.external_id(),
+            DynTraceRecord::Pipetrace(r) => r.external_id(),
+            DynTraceRecord::Multi(r) => r.external_id(),
         }
     }
 
@@ -382,7 +754,31 @@ impl<'a> TraceRecord<'a> for DynTraceRecord<'a> {
         match self {
             DynTraceRecord::Jets(r) => r.description(),
             DynTraceRecord::Virtual(r) => r.description(),
+            DynTraceRecord::Mmap(r) => r.description(),
             DynTraceRecord::Pipetrace(r) => r.description(),
+            DynTraceRecord::Multi(r) => r.description(),
+        }
+    }
+
+    #[inline]
+    fn annotations(&self) -> Vec<RecordAnnotation> {
+        match self {
+            DynTraceRecord::Jets(r) => r.annotations(),
+            DynTraceRecord::Virtual(r) => r.annotations(),
+            DynTraceRecord::Mmap(r) => r.annotations(),
+            DynTraceRecord::Pipetrace(r) => r.annotations(),
+            DynTraceRecord::Multi(r) => r.annotations(),
+        }
+    }
+
+    #[inline]
+    fn depends_on(&self) -> Vec<RecordId> {
+        match self {
+            DynTraceRecord::Jets(r) => r.depends_on(),
+            DynTraceRecord::Virtual(r) => r.depends_on(),
+            DynTraceRecord::Mmap(r) => r.depends_on(),
+            DynTraceRecord::Pipetrace(r) => r.depends_on(),
+            DynTraceRecord::Multi(r) => r.depends_on(),
         }
     }
 
@@ -391,7 +787,9 @@ impl<'a> TraceRecord<'a> for DynTraceRecord<'a> {
         match self {
             DynTraceRecord::Jets(r) => r.num_children(),
             DynTraceRecord::Virtual(r) => r.num_children(),
+            DynTraceRecord::Mmap(r) => r.num_children(),
             DynTraceRecord::Pipetrace(r) => r.num_children(),
+            DynTraceRecord::Multi(r) => r.num_children(),
         }
     }
 
@@ -400,7 +798,9 @@ impl<'a> TraceRecord<'a> for DynTraceRecord<'a> {
         match self {
             DynTraceRecord::Jets(r) => r.child_at(index).map(DynTraceRecord::Jets),
             DynTraceRecord::Virtual(r) => r.child_at(index).map(DynTraceRecord::Virtual),
+            DynTraceRecord::Mmap(r) => r.child_at(index).map(DynTraceRecord::Mmap),
             DynTraceRecord::Pipetrace(r) => r.child_at(index).map(DynTraceRecord::Pipetrace),
+            DynTraceRecord::Multi(r) => r.child_at(index).map(DynTraceRecord::Multi),
         }
     }
 
@@ -409,7 +809,9 @@ impl<'a> TraceRecord<'a> for DynTraceRecord<'a> {
         match self {
             DynTraceRecord::Jets(r) => r.num_events(),
             DynTraceRecord::Virtual(r) => r.num_events(),
+            DynTraceRecord::Mmap(r) => r.num_events(),
             DynTraceRecord::Pipetrace(r) => r.num_events(),
+            DynTraceRecord::Multi(r) => r.num_events(),
         }
     }
 
@@ -418,7 +820,9 @@ impl<'a> TraceRecord<'a> for DynTraceRecord<'a> {
         match self {
             DynTraceRecord::Jets(r) => r.event_at(index).map(DynTraceEvent::Jets),
             DynTraceRecord::Virtual(r) => r.event_at(index).map(DynTraceEvent::Virtual),
+            DynTraceRecord::Mmap(r) => r.event_at(index).map(DynTraceEvent::Jets),
             DynTraceRecord::Pipetrace(r) => r.event_at(index).map(DynTraceEvent::Pipetrace),
+            DynTraceRecord::Multi(r) => r.event_at(index).map(DynTraceEvent::Multi),
         }
     }
 
@@ -427,7 +831,9 @@ impl<'a> TraceRecord<'a> for DynTraceRecord<'a> {
         match self {
             DynTraceRecord::Jets(r) => r.subtree_depth(),
             DynTraceRecord::Virtual(r) => r.subtree_depth(),
+            DynTraceRecord::Mmap(r) => r.subtree_depth(),
             DynTraceRecord::Pipetrace(r) => r.subtree_depth(),
+            DynTraceRecord::Multi(r) => r.subtree_depth(),
         }
     }
 }
@@ -439,6 +845,7 @@ impl<'a> AttributeAccessor for DynTraceEvent<'a> {
             DynTraceEvent::Jets(e) => e.attr_count(),
             DynTraceEvent::Virtual(e) => e.attr_count(),
             DynTraceEvent::Pipetrace(e) => e.attr_count(),
+            DynTraceEvent::Multi(e) => e.attr_count(),
         }
     }
 
@@ -448,6 +855,7 @@ impl<'a> AttributeAccessor for DynTraceEvent<'a> {
             DynTraceEvent::Jets(e) => e.attr(key),
             DynTraceEvent::Virtual(e) => e.attr(key),
             DynTraceEvent::Pipetrace(e) => e.attr(key),
+            DynTraceEvent::Multi(e) => e.attr(key),
         }
     }
 
@@ -457,6 +865,7 @@ impl<'a> AttributeAccessor for DynTraceEvent<'a> {
             DynTraceEvent::Jets(e) => e.attr_at(index),
             DynTraceEvent::Virtual(e) => e.attr_at(index),
             DynTraceEvent::Pipetrace(e) => e.attr_at(index),
+            DynTraceEvent::Multi(e) => e.attr_at(index),
         }
     }
 
@@ -466,6 +875,7 @@ impl<'a> AttributeAccessor for DynTraceEvent<'a> {
             DynTraceEvent::Jets(e) => e.attrs(),
             DynTraceEvent::Virtual(e) => e.attrs(),
             DynTraceEvent::Pipetrace(e) => e.attrs(),
+            DynTraceEvent::Multi(e) => e.attrs(),
         }
     }
 }
@@ -477,6 +887,7 @@ impl<'a> TraceEvent for DynTraceEvent<'a> {
             DynTraceEvent::Jets(e) => e.clk(),
             DynTraceEvent::Virtual(e) => e.clk(),
             DynTraceEvent::Pipetrace(e) => e.clk(),
+            DynTraceEvent::Multi(e) => e.clk(),
         }
     }
 
@@ -486,6 +897,7 @@ impl<'a> TraceEvent for DynTraceEvent<'a> {
             DynTraceEvent::Jets(e) => e.name(),
             DynTraceEvent::Virtual(e) => e.name(),
             DynTraceEvent::Pipetrace(e) => e.name(),
+            DynTraceEvent::Multi(e) => e.name(),
         }
     }
 
@@ -495,6 +907,7 @@ impl<'a> TraceEvent for DynTraceEvent<'a> {
             DynTraceEvent::Jets(e) => e.record_id(),
             DynTraceEvent::Virtual(e) => e.record_id(),
             DynTraceEvent::Pipetrace(e) => e.record_id(),
+            DynTraceEvent::Multi(e) => e.record_id(),
         }
     }
 
@@ -504,6 +917,7 @@ impl<'a> TraceEvent for DynTraceEvent<'a> {
             DynTraceEvent::Jets(e) => e.description(),
             DynTraceEvent::Virtual(e) => e.description(),
             DynTraceEvent::Pipetrace(e) => e.description(),
+            DynTraceEvent::Multi(e) => e.description(),
         }
     }
 }
@@ -0,0 +1,109 @@
+//! Screen-reader annotations and keyboard focus helpers.
+//!
+//! Tree rows and timeline bars are painted by hand, so egui knows nothing
+//! about what they show. These helpers attach AccessKit roles and labels to
+//! their responses, give icon-only buttons readable names, and draw the
+//! focus outline for keyboard navigation (see [`crate::state::FocusState`]).
+
+use eframe::egui;
+use egui::accesskit::Role;
+use egui::Key;
+
+use crate::app::AppState;
+use crate::utils::format_clock;
+
+/// Width of the keyboard focus outline in pixels.
+const FOCUS_OUTLINE_WIDTH: f32 = 2.0;
+
+/// Builds the spoken label of a record (tree row or timeline bar).
+///
+/// # Arguments
+/// * `name` - Record name
+/// * `record_type` - Record type
+/// * `duration` - Record duration, if it has ended
+pub fn record_label(name: &str, record_type: &str, duration: Option<i64>) -> String {
+    match duration {
+        Some(duration) => format!("{} ({}), duration {}", name, record_type, format_clock(duration)),
+        None => format!("{} ({}), no end", name, record_type),
+    }
+}
+
+/// Describes a tree row to assistive technologies.
+///
+/// # Arguments
+/// * `response` - The row's response
+/// * `label` - Builds the spoken label (see [`record_label`]); only called when AccessKit is active
+/// * `depth` - Nesting depth (roots are 0)
+/// * `expanded` - Expansion state, None for leaves
+/// * `selected` - Whether the row is selected
+pub fn annotate_tree_row(
+    response: &egui::Response,
+    label: impl Fn() -> String,
+    depth: usize,
+    expanded: Option<bool>,
+    selected: bool,
+) {
+    response.widget_info(|| egui::WidgetInfo::selected(egui::WidgetType::SelectableLabel, true, selected, label()));
+    response.ctx.accesskit_node_builder(response.id, |node| {
+        node.set_role(Role::TreeItem);
+        node.set_level(depth + 1);
+        if let Some(expanded) = expanded {
+            node.set_expanded(expanded);
+        }
+        node.set_selected(selected);
+    });
+}
+
+/// Describes a timeline bar to assistive technologies.
+///
+/// `label` is only called when AccessKit is active.
+pub fn annotate_timeline_bar(response: &egui::Response, label: impl FnOnce() -> String, start_clk: i64, selected: bool) {
+    response.ctx.accesskit_node_builder(response.id, |node| {
+        node.set_role(Role::ListItem);
+        node.set_label(label());
+        node.set_description(format!("starts at {}", format_clock(start_clk)));
+        node.set_selected(selected);
+    });
+}
+
+/// Gives a widget (typically an icon button) a readable name.
+pub fn set_accessible_name(response: &egui::Response, name: &str) {
+    response.ctx.accesskit_node_builder(response.id, |node| node.set_label(name));
+}
+
+/// Updates focus outline visibility from this frame's input.
+///
+/// Called at the start of every frame.
+pub fn track_focus_input(ctx: &egui::Context, state: &mut AppState) {
+    let (navigated, pointer_pressed) = ctx.input(|i| {
+        (i.key_pressed(Key::Tab) || i.key_pressed(Key::F6), i.pointer.any_pressed())
+    });
+    state.focus.on_input(navigated, pointer_pressed);
+}
+
+/// Draws an outline around the widget with keyboard focus.
+///
+/// Called at the end of every frame, above all panels.
+pub fn paint_focus_outline(ctx: &egui::Context, state: &AppState) {
+    if !state.focus.focus_visible() {
+        return;
+    }
+    let Some(response) = ctx.memory(|m| m.focused()).and_then(|id| ctx.read_response(id)) else {
+        return;
+    };
+    let stroke = egui::Stroke::new(FOCUS_OUTLINE_WIDTH, ctx.style().visuals.selection.stroke.color);
+    ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("focus_outline")))
+        .with_clip_rect(response.interact_rect.expand(FOCUS_OUTLINE_WIDTH * 2.0))
+        .rect_stroke(response.interact_rect, 2.0, stroke, egui::StrokeKind::Outside);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_label_mentions_duration() {
+        assert!(record_label("fetch", "Op", Some(12)).starts_with("fetch (Op), duration "));
+        assert_eq!(record_label("fetch", "Op", None), "fetch (Op), no end");
+    }
+}
@@ -0,0 +1,97 @@
+//! Command palette overlay
+//!
+//! A search field over the list of registered actions, opened with
+//! Ctrl+Shift+P. Typing filters the list fuzzily; Up/Down move the
+//! highlight, Enter or a click runs the action and Escape closes the palette.
+
+use eframe::egui;
+
+use crate::app::ActionRegistry;
+use crate::state::CommandPaletteState;
+
+/// Entries shown at most; the query narrows longer lists down
+const MAX_ENTRIES: usize = 12;
+
+/// Renders the command palette if it is open.
+///
+/// # Arguments
+/// * `ctx` - The egui context the overlay is shown in
+/// * `palette` - Palette open state, query and highlight
+/// * `registry` - Actions to list
+///
+/// # Returns
+/// * `Option<usize>` - Index of the action to run
+pub fn render_command_palette(
+    ctx: &egui::Context,
+    palette: &mut CommandPaletteState,
+    registry: &ActionRegistry,
+) -> Option<usize> {
+    if !palette.is_open() {
+        return None;
+    }
+
+    let mut matches = registry.matching(palette.query());
+    matches.truncate(MAX_ENTRIES);
+    let (escape, enter, up, down) = ctx.input_mut(|i| {
+        (
+            i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+        )
+    });
+    if up {
+        palette.move_highlight(-1, matches.len());
+    }
+    if down {
+        palette.move_highlight(1, matches.len());
+    }
+    let highlighted = palette.highlighted(matches.len());
+    let mut chosen = (enter && !matches.is_empty()).then(|| matches[highlighted]);
+
+    let response = egui::Window::new("command_palette")
+        .title_bar(false)
+        .resizable(false)
+        .collapsible(false)
+        .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 60.0))
+        .fixed_size(egui::vec2(460.0, 0.0))
+        .show(ctx, |ui| {
+            let search = ui.add(
+                egui::TextEdit::singleline(palette.query_mut())
+                    .hint_text("Type a command…")
+                    .desired_width(f32::INFINITY),
+            );
+            search.request_focus();
+            if search.changed() {
+                palette.reset_highlight();
+            }
+            ui.separator();
+
+            if matches.is_empty() {
+                ui.weak("No matching commands");
+            }
+            for (row, &index) in matches.iter().enumerate() {
+                let action = &registry.actions()[index];
+                ui.horizontal(|ui| {
+                    let entry = ui.add(
+                        egui::Button::selectable(row == highlighted, &action.name)
+                            .min_size(egui::vec2(ui.available_width() - 120.0, 0.0)),
+                    );
+                    if entry.clicked() {
+                        chosen = Some(index);
+                    }
+                    if let Some(shortcut) = &action.shortcut {
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            ui.weak(ctx.format_shortcut(shortcut));
+                        });
+                    }
+                });
+            }
+        });
+
+    let clicked_outside = response.is_some_and(|r| r.response.clicked_elsewhere());
+    if escape || chosen.is_some() || clicked_outside {
+        palette.close();
+    }
+    chosen
+}
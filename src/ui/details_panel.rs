@@ -1,23 +1,75 @@
 //! Details panel UI rendering
 //!
-//! Handles the details panel showing annotations, data, and events for the selected record.
+//! Handles the details panel showing annotations, data, events, and user notes for the selected record.
+//! Durations can also be shown as a share of the parent and of the whole trace.
+//! Events whose name is hidden by the event filter are left out of the list.
+//! Descriptions containing markup are also shown formatted below their raw JSON line.
+//! Thread records (and their children) get a flame chart of the thread's children.
+//! Attribute values referencing another record get a link that jumps to it.
+//! With several records selected, their clocks, durations and attributes are compared side by side.
 
 use eframe::egui;
 use egui::{Color32, RichText, ScrollArea};
 use rjets::ThemeColors;
 use crate::app::AppState;
-use rjets::{TraceData, TraceRecord, TraceEvent, AttributeAccessor};
+use crate::presentation::markup;
+use crate::state::NotesState;
+use crate::rendering::mini_timeline_renderer::{self, MiniTimelineInteraction};
+use crate::rendering::flame_chart_renderer::{self, FlameChartInteraction};
+use crate::rendering::text_utils;
+use crate::domain::duration_share;
+use crate::domain::flame_chart;
+use crate::domain::number_format::NumberFormat;
+use crate::domain::record_links;
+use crate::domain::severity::Severity;
+use crate::domain::record_comparison::{self, ComparisonField};
+use crate::state::InputSettings;
+use crate::presentation::color_mapping;
+use rjets::{DynTraceData, TraceData, TraceRecord, TraceEvent, AttributeAccessor, ExternalId};
+
+/// Result of user interaction with the details panel
+pub enum DetailsInteraction {
+    /// A record link in an attribute value was clicked
+    RecordLinkClicked(u64),
+}
 
 /// Renders the details panel showing annotations, data, and events for the selected record
 ///
 /// # Arguments
 /// * `ui` - The egui UI context for drawing
-/// * `state` - Reference to application state
+/// * `state` - Mutable reference to application state (notes are editable)
 /// * `theme_colors` - Color palette for the current theme
-pub fn render_details_panel(ui: &mut egui::Ui, state: &AppState, theme_colors: &ThemeColors) {
+pub fn render_details_panel(ui: &mut egui::Ui, state: &mut AppState, theme_colors: &ThemeColors) -> Option<DetailsInteraction> {
+    let mut interaction = None;
+    text_utils::scale_text_styles(ui.style_mut(), state.input_settings.settings().details_text_scale());
+    if let Some(trace) = state.trace.trace_data().filter(|_| state.selection.selected_records().len() > 1) {
+        render_comparison(ui, trace, state.selection.selected_records(), state.input_settings.settings(), theme_colors);
+        return None;
+    }
     if let (Some(trace), Some(selected_id)) = (state.trace.trace_data(), state.selection.selected_record_id()) {
         if let Some(record) = trace.get_record(selected_id) {
-            ui.label(RichText::new(format!("Details for record: {}", selected_id)).strong());
+            let external_id = record.external_id();
+            let parent_external_id = record.parent_id().map(|pid| {
+                trace.get_record(pid).map_or(pid.into(), |parent| parent.external_id())
+            });
+            let formats = state.input_settings.settings().column_formats;
+            ui.label(RichText::new(format!("Details for record: {}", formats.format_id(&external_id))).strong());
+            // The JSON line below keeps clocks in decimal
+            if formats.start_clock != NumberFormat::Decimal || formats.duration != NumberFormat::Decimal {
+                let mut timing = format!("Start: {}", formats.start_clock.format_readable(record.clk()));
+                if let Some(duration) = record.duration() {
+                    timing.push_str(&format!("   Duration: {}", formats.duration.format_readable(duration)));
+                }
+                ui.weak(timing);
+            }
+            if let Some(duration) = record.duration().filter(|_| state.input_settings.settings().duration_share) {
+                let share = duration_share::duration_share(trace, selected_id, &mut state.tree_cache.duration_shares);
+                let parts: Vec<String> = [(share.of_parent, "parent"), (share.of_trace, "trace")]
+                    .into_iter()
+                    .filter_map(|(fraction, of)| Some(format!("{} of {}", duration_share::format_share(fraction?), of)))
+                    .collect();
+                ui.weak(format!("Duration: {} ({})", formats.duration.format_readable(duration), parts.join(", ")));
+            }
             ui.separator();
 
             let available_height = ui.available_height();
@@ -32,12 +84,23 @@ pub fn render_details_panel(ui: &mut egui::Ui, state: &AppState, theme_colors: &
                     "clk": record.clk(),
                     "name": record.name(),
                     "description": record.description(),
-                    "id": record.id(),
-                    "parent_id": record.parent_id()
+                    "id": external_id.to_json(),
+                    "parent_id": parent_external_id.as_ref().map(ExternalId::to_json)
                 });
                 ui.colored_label(theme_colors.blue,
                     serde_json::to_string(&record_json).unwrap());
 
+                // Description with its markup applied (the line above keeps the raw text)
+                let description = record.description();
+                if markup::has_markup(&description) {
+                    ui.add_space(4.0);
+                    markup::show_markup(ui, &description, ui.visuals().text_color());
+                }
+
+                ui.add_space(10.0);
+
+                render_notes_section(ui, &mut state.notes, selected_id, theme_colors);
+
                 ui.add_space(10.0);
 
                 // Show merged data (includes annotations) - ALL of them, sorted by key
@@ -48,13 +111,27 @@ pub fn render_details_panel(ui: &mut egui::Ui, state: &AppState, theme_colors: &
                     attrs.sort_by(|a, b| a.0.cmp(&b.0));
 
                     for (key, value) in attrs {
+                        let target = record_links::resolve_link(trace, selected_id, &key, &value)
+                            .and_then(|target_id| trace.get_record(target_id));
                         let data_json = serde_json::json!({
                             key: value
                         });
-                        ui.colored_label(
-                            theme_colors.green,
-                            serde_json::to_string(&data_json).unwrap()
-                        );
+                        ui.horizontal_wrapped(|ui| {
+                            ui.colored_label(
+                                theme_colors.green,
+                                serde_json::to_string(&data_json).unwrap()
+                            );
+                            if let Some(target) = target {
+                                let name = state.input_settings.settings().display_name(&target.name()).into_owned();
+                                if ui
+                                    .link(format!("→ {}", name))
+                                    .on_hover_text(format!("Select {} record {}", target.record_type(), target.external_id()))
+                                    .clicked()
+                                {
+                                    interaction = Some(DetailsInteraction::RecordLinkClicked(target.id()));
+                                }
+                            }
+                        });
                     }
                 } else {
                     ui.colored_label(Color32::GRAY, "(no data)");
@@ -62,15 +139,120 @@ pub fn render_details_panel(ui: &mut egui::Ui, state: &AppState, theme_colors: &
 
                 ui.add_space(10.0);
 
-                // Show events - ALL of them, sorted by timestamp
+                // Flame chart of the thread the record belongs to (the record itself or its parent)
+                let flame_thread = Some(record.clone())
+                    .filter(flame_chart::has_flame_chart)
+                    .or_else(|| {
+                        record.parent_id()
+                            .and_then(|pid| trace.get_record(pid))
+                            .filter(flame_chart::has_flame_chart)
+                    });
+                if let Some(thread) = flame_thread {
+                    let thread_id = thread.id();
+                    let layout = flame_chart::flame_layout(&thread);
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new("Flame Chart:").strong());
+                        if state.flame_chart.is_zoomed(thread_id) && ui.small_button("⛶ Fit").clicked() {
+                            state.flame_chart.reset();
+                        }
+                        ui.weak(format!(
+                            "{} in flight at peak, {:.2} on average",
+                            layout.depth_count,
+                            layout.mean_occupancy()
+                        ));
+                    });
+                    if thread_id != selected_id {
+                        let settings = state.input_settings.settings();
+                        ui.weak(format!("Children of {}", settings.display_name(&thread.name())));
+                    }
+                    let extent = flame_chart_renderer::flame_extent(&layout);
+                    let range = state.flame_chart.range(thread_id, extent);
+                    let color_overrides = state.type_legend.color_overrides();
+                    let type_hints = state.type_legend.trace_hints();
+                    let settings = state.input_settings.settings();
+                    let bar_label = |record_id| {
+                        trace.get_record(record_id).map_or((String::new(), theme_colors.gray), |child| {
+                            let name = child.name();
+                            let color = color_mapping::record_color(&name, &child.record_type(), color_overrides, type_hints, theme_colors);
+                            (settings.display_name(&name).into_owned(), color)
+                        })
+                    };
+                    match flame_chart_renderer::render_flame_chart(
+                        ui,
+                        &layout,
+                        range,
+                        Some(selected_id),
+                        bar_label,
+                        theme_colors,
+                        state.input_settings.settings(),
+                    ) {
+                        Some(FlameChartInteraction::BarClicked { record_id }) => {
+                            state.selection.select_record(record_id, None);
+                        }
+                        Some(FlameChartInteraction::Zoom { zoom_factor, focus_clk }) => {
+                            state.flame_chart.zoom_around(thread_id, extent, zoom_factor, focus_clk);
+                        }
+                        Some(FlameChartInteraction::Pan { delta_clk }) => {
+                            state.flame_chart.pan(thread_id, extent, delta_clk);
+                        }
+                        Some(FlameChartInteraction::Reset) => state.flame_chart.reset(),
+                        None => {}
+                    }
+
+                    ui.add_space(10.0);
+                }
+
+                // Mini timeline of this record's events, zoomed independently of the main view
+                if record.num_events() > 0 {
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new("Event Timeline:").strong());
+                        if state.mini_timeline.is_zoomed(selected_id) && ui.small_button("⛶ Fit").clicked() {
+                            state.mini_timeline.reset();
+                        }
+                        ui.weak("Ctrl+wheel to zoom, drag to pan");
+                    });
+                    let extent = mini_timeline_renderer::event_extent(&record);
+                    let range = state.mini_timeline.range(selected_id, extent);
+                    let selected_clk = state.selection.selected_event()
+                        .filter(|(id, _)| *id == selected_id)
+                        .map(|(_, clk)| clk);
+                    match mini_timeline_renderer::render_mini_timeline(
+                        ui,
+                        &record,
+                        range,
+                        selected_clk,
+                        &state.event_type_filter.event_strategy(Severity::Info),
+                        theme_colors,
+                        state.input_settings.settings(),
+                    ) {
+                        Some(MiniTimelineInteraction::EventClicked { event_clk }) => {
+                            state.selection.select_event(selected_id, event_clk);
+                        }
+                        Some(MiniTimelineInteraction::Zoom { zoom_factor, focus_clk }) => {
+                            state.mini_timeline.zoom_around(selected_id, extent, zoom_factor, focus_clk);
+                        }
+                        Some(MiniTimelineInteraction::Pan { delta_clk }) => {
+                            state.mini_timeline.pan(selected_id, extent, delta_clk);
+                        }
+                        Some(MiniTimelineInteraction::Reset) => state.mini_timeline.reset(),
+                        None => {}
+                    }
+
+                    ui.add_space(10.0);
+                }
+
+                // Show events - all but the hidden event names, sorted by timestamp
                 ui.label(RichText::new("Events:").strong());
                 let num_events = record.num_events();
+                let event_filter = state.event_type_filter.event_strategy(Severity::Info);
                 let mut events: Vec<_> = (0..num_events)
                     .filter_map(|i| record.event_at(i))
+                    .filter(|e| event_filter.include_event(e))
                     .collect();
                 events.sort_by_key(|e| e.clk());
+                let hidden_events = num_events - events.len();
                 if !events.is_empty() {
-                    for event in &events {
+                    for (index, event) in events.iter().enumerate() {
                         let event_attrs = event.attrs();
                         let data_obj: serde_json::Map<String, serde_json::Value> = event_attrs.into_iter().collect();
                         let evt_json = serde_json::json!({
@@ -104,13 +286,103 @@ pub fn render_details_panel(ui: &mut egui::Ui, state: &AppState, theme_colors: &
                                 event_text
                             );
                         }
+
+                        let event_description = event.description();
+                        if markup::has_markup(&event_description) {
+                            ui.indent(("event_description", index), |ui| {
+                                markup::show_markup(ui, &event_description, theme_colors.orange);
+                            });
+                        }
                     }
-                } else {
+                } else if hidden_events == 0 {
                     ui.colored_label(Color32::GRAY, "(no events)");
                 }
+                if hidden_events > 0 {
+                    ui.colored_label(Color32::GRAY, format!("({} events hidden by the event filter)", hidden_events));
+                }
             });
         }
     } else {
         ui.label("Data & Events (select a record to view)");
     }
+    interaction
+}
+
+/// Renders the selected records side by side, fields that differ highlighted.
+fn render_comparison(ui: &mut egui::Ui, trace: &DynTraceData, record_ids: &[u64], settings: &InputSettings, theme_colors: &ThemeColors) {
+    let comparison = record_comparison::compare_records(trace, record_ids);
+    let formats = settings.column_formats;
+    ui.label(RichText::new(format!("Comparing {} records", comparison.records.len())).strong());
+    ui.weak("Ctrl+click adds or removes records, Shift+click selects a range of tree rows");
+    ui.separator();
+
+    ScrollArea::both()
+        .id_salt("details_compare_scroll_area")
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            egui::Grid::new("details_compare_grid")
+                .striped(true)
+                .num_columns(comparison.records.len() + 1)
+                .show(ui, |ui| {
+                    ui.label("");
+                    for record in comparison.records.iter().filter_map(|&id| trace.get_record(id)) {
+                        ui.label(RichText::new(settings.display_name(&record.name())).strong())
+                            .on_hover_text(format!("{} record {}", record.record_type(), formats.format_id(&record.external_id())));
+                    }
+                    ui.end_row();
+
+                    for row in &comparison.rows {
+                        let color = if row.differs() { theme_colors.orange } else { ui.visuals().text_color() };
+                        ui.label(RichText::new(row.field.label()).strong());
+                        for value in &row.values {
+                            let text = match (value, &row.field) {
+                                (None, _) => "—".to_string(),
+                                (Some(value), ComparisonField::Start) => value.as_i64().map_or_else(|| value.to_string(), |clk| formats.start_clock.format_readable(clk)),
+                                (Some(value), ComparisonField::Duration) => value.as_i64().map_or_else(|| value.to_string(), |duration| formats.duration.format_readable(duration)),
+                                (Some(serde_json::Value::String(text)), _) => text.clone(),
+                                (Some(value), _) => value.to_string(),
+                            };
+                            ui.colored_label(color, text);
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+/// Renders the editable note text and tag chips for a record.
+fn render_notes_section(ui: &mut egui::Ui, notes: &mut NotesState, record_id: u64, theme_colors: &ThemeColors) {
+    ui.label(RichText::new("Notes & Tags:").strong());
+
+    let mut text = notes.note(record_id).map(|n| n.text.clone()).unwrap_or_default();
+    let response = ui.add(
+        egui::TextEdit::multiline(&mut text)
+            .hint_text("Add a note for this record…")
+            .desired_rows(2)
+            .desired_width(f32::INFINITY),
+    );
+    if response.changed() {
+        notes.set_text(record_id, text);
+    }
+
+    let tags = notes.note(record_id).map(|n| n.tags.clone()).unwrap_or_default();
+    ui.horizontal_wrapped(|ui| {
+        for tag in &tags {
+            ui.colored_label(theme_colors.purple, format!("#{}", tag));
+            if ui.small_button("✕").on_hover_text("Remove tag").clicked() {
+                notes.remove_tag(record_id, tag);
+            }
+        }
+
+        let response = ui.add(
+            egui::TextEdit::singleline(notes.tag_input_mut())
+                .hint_text("+ tag")
+                .desired_width(100.0),
+        );
+        if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            let tag = std::mem::take(notes.tag_input_mut());
+            notes.add_tag(record_id, &tag);
+            response.request_focus();
+        }
+    });
 }
@@ -0,0 +1,101 @@
+//! Event type filter window
+//!
+//! Lists every event name of the trace with its count. Unchecking a name
+//! hides those events from the timeline markers and the details panel event
+//! list; "Only" keeps a single name, and Hide All followed by checking a few
+//! names shows just those (e.g. only EX and WB).
+
+use eframe::egui;
+use egui::RichText;
+use rjets::ThemeColors;
+
+use crate::app::AppState;
+use crate::cache::EVENT_NAME_LIMIT;
+
+/// Renders the event filter window if it is open.
+///
+/// # Arguments
+/// * `ctx` - The egui context the window is shown in
+/// * `state` - Mutable reference to application state
+/// * `theme_colors` - Color palette for the current theme
+pub fn render_event_filter_window(ctx: &egui::Context, state: &mut AppState, theme_colors: &ThemeColors) {
+    let mut open = state.event_type_filter.is_open();
+    if !open {
+        return;
+    }
+
+    let mut changed = false;
+    egui::Window::new("🔖 Event Filter")
+        .open(&mut open)
+        .resizable(true)
+        .default_width(280.0)
+        .show(ctx, |ui| {
+            let Some(summary) = &state.tree_cache.trace_summary else {
+                if state.trace.trace_data().is_some() {
+                    ui.label(RichText::new("Indexing…").color(theme_colors.text_dim));
+                } else {
+                    ui.label(RichText::new("No trace loaded").color(theme_colors.text_dim));
+                }
+                return;
+            };
+            // Cloned so rows can mutate the filter state while iterating
+            let event_names: Vec<(String, usize)> =
+                summary.event_name_counts.iter().map(|(n, c)| (n.clone(), *c)).collect();
+            if event_names.is_empty() {
+                ui.label(RichText::new("The trace has no events").color(theme_colors.text_dim));
+                return;
+            }
+
+            egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                egui::Grid::new("event_filter_grid")
+                    .num_columns(3)
+                    .striped(true)
+                    .spacing([8.0, 4.0])
+                    .show(ui, |ui| {
+                        for (name, count) in &event_names {
+                            let mut visible = state.event_type_filter.is_event_name_visible(name);
+                            if ui.checkbox(&mut visible, name).changed() {
+                                state.event_type_filter.set_event_name_visible(name, visible);
+                                changed = true;
+                            }
+                            ui.label(RichText::new(count.to_string()).color(theme_colors.text_dim));
+                            if ui.small_button("Only").on_hover_text("Show only this event name").clicked() {
+                                let known = event_names.iter().map(|(n, _)| n.as_str());
+                                state.event_type_filter.show_only_event_names(known, &[name.as_str()]);
+                                changed = true;
+                            }
+                            ui.end_row();
+                        }
+                    });
+                if event_names.len() >= EVENT_NAME_LIMIT {
+                    ui.label(
+                        RichText::new(format!("Only the first {} event names are listed", EVENT_NAME_LIMIT))
+                            .small()
+                            .color(theme_colors.text_dim),
+                    );
+                }
+            });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                let hidden = state.event_type_filter.hidden_event_names().len();
+                if ui.add_enabled(hidden > 0, egui::Button::new("Show All")).clicked() {
+                    state.event_type_filter.show_all_events();
+                    changed = true;
+                }
+                if ui.button("Hide All").clicked() {
+                    let known = event_names.iter().map(|(n, _)| n.as_str());
+                    state.event_type_filter.show_only_event_names(known, &[]);
+                    changed = true;
+                }
+                if hidden > 0 {
+                    ui.label(RichText::new(format!("{} hidden", hidden)).color(theme_colors.text_dim));
+                }
+            });
+        });
+
+    if changed {
+        state.repaint.request();
+    }
+    state.event_type_filter.set_open(open);
+}
@@ -0,0 +1,267 @@
+//! Active filter chip row
+//!
+//! Shown under the header while any filter hides rows or events: one chip
+//! per active filter with a ✕ that clears it, plus "Clear all", so missing
+//! rows can always be traced back to the filter hiding them.
+//!
+//! When the filters hide the selected record, the row also carries a banner
+//! offering to reveal it (see [`crate::domain::hidden_selection`]).
+
+use eframe::egui;
+use egui::RichText;
+use rjets::ThemeColors;
+
+use crate::app::AppState;
+use crate::domain::hidden_selection::{self, HidingFilter, RowFilters};
+use crate::domain::severity::Severity;
+use crate::domain::visibility::ClockStride;
+
+/// A filter currently narrowing what is shown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActiveFilter {
+    /// Only leaf records starting inside the viewport
+    Viewport,
+    /// Record types hidden in the legend
+    HiddenTypes(usize),
+    /// Only leaf records on one phase of a clock stride
+    ClockStride(ClockStride),
+    /// Leaf records failing table header column filters
+    Columns(usize),
+    /// Timeline events below a severity hidden
+    EventSeverity(Severity),
+    /// Event names hidden in the event filter
+    EventTypes(usize),
+    /// Roots hidden in the root selector
+    HiddenRoots(usize),
+    /// Only rows pinned by a region zoom
+    PinnedRows(usize),
+}
+
+impl ActiveFilter {
+    /// Returns the chip text.
+    pub fn label(&self) -> String {
+        match self {
+            ActiveFilter::Viewport => "⏱ Viewport".to_string(),
+            ActiveFilter::HiddenTypes(n) => format!("🎨 {} types hidden", n),
+            ActiveFilter::ClockStride(stride) => {
+                format!("⏲ clk % {} = {}", stride.stride, stride.offset.rem_euclid(stride.stride))
+            }
+            ActiveFilter::Columns(n) => format!("▾ {} columns filtered", n),
+            ActiveFilter::EventSeverity(Severity::Error) => "⚠ Errors only".to_string(),
+            ActiveFilter::EventSeverity(_) => "⚠ Warnings and errors".to_string(),
+            ActiveFilter::EventTypes(n) => format!("🔖 {} event names hidden", n),
+            ActiveFilter::HiddenRoots(n) => format!("🗂 {} roots hidden", n),
+            ActiveFilter::PinnedRows(n) => format!("📌 {} pinned rows", n),
+        }
+    }
+
+    /// Returns the chip hover text.
+    fn description(&self) -> &'static str {
+        match self {
+            ActiveFilter::Viewport => "Only leaf records starting in the viewport are shown",
+            ActiveFilter::HiddenTypes(_) => "Records of hidden types (and their subtrees) are not shown",
+            ActiveFilter::ClockStride(_) => "Only leaf records starting on this clock phase are shown",
+            ActiveFilter::Columns(_) => "Only leaf records passing the column header filters are shown",
+            ActiveFilter::EventSeverity(_) => "Timeline events below this severity are hidden",
+            ActiveFilter::EventTypes(_) => "Events with these names are hidden from the timeline and details",
+            ActiveFilter::HiddenRoots(_) => "Hidden roots and their subtrees are not shown",
+            ActiveFilter::PinnedRows(_) => "Only rows pinned by a region zoom are shown",
+        }
+    }
+}
+
+/// Lists the filters currently active, in header order.
+pub fn active_filters(state: &AppState) -> Vec<ActiveFilter> {
+    let mut filters = Vec::new();
+    if state.viewport.viewport_filter_enabled() {
+        filters.push(ActiveFilter::Viewport);
+    }
+    if let Some(stride) = state.clock_filter.stride() {
+        filters.push(ActiveFilter::ClockStride(stride));
+    }
+    let filtered_columns = state.column_filter.filters().len();
+    if filtered_columns > 0 {
+        filters.push(ActiveFilter::Columns(filtered_columns));
+    }
+    let min_severity = state.event_filter.min_severity();
+    if min_severity > Severity::Info {
+        filters.push(ActiveFilter::EventSeverity(min_severity));
+    }
+    let hidden_event_names = state.event_type_filter.hidden_event_names().len();
+    if hidden_event_names > 0 {
+        filters.push(ActiveFilter::EventTypes(hidden_event_names));
+    }
+    let pinned = state.tree.pinned_rows().len();
+    if pinned > 0 {
+        filters.push(ActiveFilter::PinnedRows(pinned));
+    }
+    let hidden_types = state.type_legend.hidden_types().len();
+    if hidden_types > 0 {
+        filters.push(ActiveFilter::HiddenTypes(hidden_types));
+    }
+    let hidden_roots = state.lanes.hidden_roots().len();
+    if hidden_roots > 0 {
+        filters.push(ActiveFilter::HiddenRoots(hidden_roots));
+    }
+    filters
+}
+
+/// Lists the filters hiding the selected record's row (empty if it has one).
+///
+/// Swimlane rows are grouped by attribute rather than by the tree, so the
+/// selection is never reported hidden while swimlanes are on.
+pub fn hidden_selection(state: &AppState) -> Vec<HidingFilter> {
+    let (Some(trace), Some(record_id)) = (state.trace.trace_data(), state.selection.selected_record_id()) else {
+        return Vec::new();
+    };
+    if state.lanes.lane_attribute().is_some() {
+        return Vec::new();
+    }
+    let filters = RowFilters {
+        hidden_roots: state.lanes.hidden_roots(),
+        hidden_types: state.type_legend.hidden_types(),
+        pinned_rows: state.tree.pinned_rows(),
+        clock_stride: state.clock_filter.stride(),
+        column_filters: state.column_filter.filters(),
+        viewport_range: state
+            .viewport
+            .viewport_filter_enabled()
+            .then(|| (state.viewport.viewport_start_clk(), state.viewport.viewport_end_clk())),
+        hide_empty_parents: state.viewport.hide_empty_parents(),
+    };
+    hidden_selection::hiding_filters(trace, record_id, &filters)
+}
+
+/// Turns off one filter and invalidates the caches it affects.
+pub fn clear_filter(state: &mut AppState, filter: &ActiveFilter) {
+    match filter {
+        ActiveFilter::Viewport => {
+            state.viewport.set_viewport_filter_enabled(false);
+            state.tree_cache.invalidate_filtered_cache();
+        }
+        ActiveFilter::ClockStride(_) => {
+            state.clock_filter.set_enabled(false);
+            state.tree_cache.invalidate_filtered_cache();
+        }
+        ActiveFilter::Columns(_) => {
+            state.column_filter.clear_filters();
+            state.tree_cache.invalidate_filtered_cache();
+        }
+        ActiveFilter::EventSeverity(_) => {
+            state.event_filter.set_min_severity(Severity::Info);
+        }
+        ActiveFilter::EventTypes(_) => {
+            state.event_type_filter.show_all_events();
+        }
+        ActiveFilter::PinnedRows(_) => {
+            state.tree.unpin_rows();
+            state.tree_cache.invalidate_filtered_cache();
+        }
+        ActiveFilter::HiddenTypes(_) => {
+            state.type_legend.show_all_types();
+            state.tree_cache.invalidate();
+        }
+        ActiveFilter::HiddenRoots(_) => {
+            state.lanes.show_all_roots();
+            state.tree_cache.invalidate();
+        }
+    }
+    state.repaint.request();
+}
+
+/// Renders the chip row for the given active filters.
+///
+/// # Arguments
+/// * `ui` - The egui UI context for drawing
+/// * `state` - Mutable reference to application state
+/// * `filters` - Filters to show (see [`active_filters`])
+/// * `theme_colors` - Color palette for the current theme
+pub fn render_filter_chips(
+    ui: &mut egui::Ui,
+    state: &mut AppState,
+    filters: &[ActiveFilter],
+    theme_colors: &ThemeColors,
+) {
+    let mut cleared: Vec<&ActiveFilter> = Vec::new();
+
+    ui.horizontal_wrapped(|ui| {
+        ui.label(RichText::new("Filters:").color(theme_colors.text_dim));
+        for filter in filters {
+            let chip = egui::Button::new(RichText::new(format!("{}  ✕", filter.label())).color(theme_colors.text))
+                .fill(theme_colors.hover)
+                .stroke(egui::Stroke::new(1.0, theme_colors.border))
+                .corner_radius(8.0);
+            if ui
+                .add(chip)
+                .on_hover_text(format!("{}\nClick to clear", filter.description()))
+                .clicked()
+            {
+                cleared.push(filter);
+            }
+        }
+        if filters.len() > 1 && ui.small_button("Clear all").clicked() {
+            cleared = filters.iter().collect();
+        }
+    });
+
+    for filter in cleared {
+        clear_filter(state, filter);
+    }
+}
+
+/// Renders the banner shown while the selected record is hidden.
+///
+/// # Arguments
+/// * `ui` - The egui UI context for drawing
+/// * `hiding` - Filters hiding the selection (see [`hidden_selection`])
+/// * `theme_colors` - Color palette for the current theme
+///
+/// # Returns
+/// `true` if Reveal was clicked.
+pub fn render_hidden_selection_banner(ui: &mut egui::Ui, hiding: &[HidingFilter], theme_colors: &ThemeColors) -> bool {
+    let relaxations: Vec<String> = hiding.iter().map(|filter| format!("• {}", filter.relaxation())).collect();
+    ui.horizontal(|ui| {
+        ui.label(RichText::new("Selected record hidden by filters —").color(theme_colors.text));
+        ui.button("Reveal")
+            .on_hover_text(format!("Relaxes only the filters hiding it:\n{}", relaxations.join("\n")))
+            .clicked()
+    })
+    .inner
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::column_filters::{ColumnFilter, TreeColumn};
+
+    #[test]
+    fn test_active_filters_and_clear() {
+        let mut state = AppState::new();
+        assert!(active_filters(&state).is_empty());
+
+        state.viewport.set_viewport_filter_enabled(true);
+        state.event_filter.set_min_severity(Severity::Error);
+        state.event_type_filter.set_event_name_visible("F1", false);
+        state.tree.pin_rows([1, 2, 3]);
+        state.type_legend.toggle_type_visible("Bubble");
+        state.column_filter.set_filter(TreeColumn::Id, ColumnFilter::Range { min: Some(10), max: None });
+        assert_eq!(
+            active_filters(&state),
+            vec![
+                ActiveFilter::Viewport,
+                ActiveFilter::Columns(1),
+                ActiveFilter::EventSeverity(Severity::Error),
+                ActiveFilter::EventTypes(1),
+                ActiveFilter::PinnedRows(3),
+                ActiveFilter::HiddenTypes(1),
+            ]
+        );
+
+        clear_filter(&mut state, &ActiveFilter::PinnedRows(3));
+        assert_eq!(active_filters(&state).len(), 5);
+        for filter in active_filters(&state) {
+            clear_filter(&mut state, &filter);
+        }
+        assert!(active_filters(&state).is_empty());
+    }
+}
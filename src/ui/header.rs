@@ -5,14 +5,55 @@
 use eframe::egui;
 use egui::Color32;
 use std::path::PathBuf;
-use crate::app::AppState;
+use crate::app::{AppState, ApplicationCoordinator};
+use crate::domain::severity::Severity;
+use crate::state::{DockPanel, DockSlot, MIN_CLOCK_STRIDE};
+use crate::ui::accessibility::set_accessible_name;
+use rjets::{TraceData, TraceRecord};
 
 /// Result of user interaction with the header panel
 pub enum HeaderInteraction {
-    /// User clicked "Open Trace" button
-    OpenFileRequested(PathBuf),
+    /// User picked one or more files with the "Open Trace" button
+    OpenFilesRequested(Vec<PathBuf>),
+    /// User clicked the "Reload" button
+    ReloadRequested,
     /// User clicked "Virtual Trace" button
     OpenVirtualTraceRequested,
+    /// User chose a destination for an analysis report export
+    ExportReport {
+        /// Which analysis to export
+        scope: ReportScope,
+        /// Destination file (.md or .html)
+        path: PathBuf,
+    },
+    /// User chose a destination for a `.jetspack` session pack
+    SaveSessionPack(PathBuf),
+    /// User chose a destination for a JETS file of the viewport clock range
+    ExportTimeSlice(PathBuf),
+    /// User chose a destination for a JETS copy of the whole trace
+    ExportJets(PathBuf),
+    /// User chose a record type and a CSV destination for its event grid
+    ExportEventGrid {
+        record_type: String,
+        path: PathBuf,
+    },
+}
+
+/// Analysis exported as a report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportScope {
+    /// Statistics over the whole trace
+    WholeTrace,
+    /// Statistics over the current viewport clock range
+    ViewportRegion,
+    /// Anomalies found by the last trace health check
+    TraceHealth,
+    /// The last folded record type hierarchy
+    TypeHierarchy,
+    /// Idle gaps found by the last search
+    IdleGaps,
+    /// The last flame graph shown in the timeline panel
+    FlameGraph,
 }
 
 /// Renders the application header with file controls and zoom controls
@@ -27,41 +68,55 @@ pub fn render_header(ui: &mut egui::Ui, state: &mut AppState) -> Option<HeaderIn
     let mut interaction = None;
 
     ui.horizontal(|ui| {
-        if ui.button("📁 Open Trace").clicked() {
-            let mut dialog = rfd::FileDialog::new()
-                .add_filter("All Trace Files", &["jets", "jsonl", "br", "pt", "gz"])
-                .add_filter("JETS Traces", &["jets", "jsonl", "br"])
-                .add_filter("PipeTrace Files", &["pt", "gz"]);
-
-            if let Ok(cwd) = std::env::current_dir() {
-                dialog = dialog.set_directory(cwd);
+        let open_button = ui.button("📁 Open Trace");
+        set_accessible_name(&open_button, "Open trace");
+        if open_button.clicked() {
+            if let Some(paths) = pick_trace_files() {
+                interaction = Some(HeaderInteraction::OpenFilesRequested(paths));
             }
+        }
+        if let Some(path) = render_recent_files_menu(ui, state) {
+            interaction = Some(HeaderInteraction::OpenFilesRequested(vec![path]));
+        }
 
-            if let Some(path) = dialog.pick_file() {
-                interaction = Some(HeaderInteraction::OpenFileRequested(path));
+        if state.trace.file_path().is_some() {
+            let reload_button = ui.button("⟳ Reload")
+                .on_hover_text("Read the trace from disk again, keeping notes, tags and pinned rows (Ctrl+R)");
+            set_accessible_name(&reload_button, "Reload trace");
+            if reload_button.clicked() {
+                interaction = Some(HeaderInteraction::ReloadRequested);
             }
         }
 
-        if ui.button("🔮 Virtual Trace").clicked() {
+        let virtual_button = ui.button("🔮 Virtual Trace");
+        set_accessible_name(&virtual_button, "Open virtual trace");
+        if virtual_button.clicked() {
             interaction = Some(HeaderInteraction::OpenVirtualTraceRequested);
         }
 
         ui.separator();
 
         if state.trace.trace_data().is_some() {
-            // Zoom controls
-            if ui.button("🔍+").clicked() {
-                let center = (state.viewport.viewport_start_clk() + state.viewport.viewport_end_clk()) / 2;
-                state.viewport.zoom_around(1.5, center, state.trace.min_clk(), state.trace.max_clk());
+            // Zoom controls (icon-only, so they get spoken names)
+            let zoom_in = ui
+                .add_enabled(!state.viewport.at_max_zoom(), egui::Button::new("🔍+"))
+                .on_hover_text("Zoom in")
+                .on_disabled_hover_text("Zoomed in to the narrowest viewport");
+            set_accessible_name(&zoom_in, "Zoom in");
+            if zoom_in.clicked() {
+                ApplicationCoordinator::zoom_around_center(state, 1.5);
             }
 
-            if ui.button("🔍-").clicked() {
-                let center = (state.viewport.viewport_start_clk() + state.viewport.viewport_end_clk()) / 2;
-                state.viewport.zoom_around(1.0 / 1.5, center, state.trace.min_clk(), state.trace.max_clk());
+            let zoom_out = ui.button("🔍-").on_hover_text("Zoom out");
+            set_accessible_name(&zoom_out, "Zoom out");
+            if zoom_out.clicked() {
+                ApplicationCoordinator::zoom_around_center(state, 1.0 / 1.5);
             }
 
-            if ui.button("⛶ Fit").clicked() {
-                state.viewport.set_range(
+            let fit = ui.button("⛶ Fit");
+            set_accessible_name(&fit, "Zoom to fit the whole trace");
+            if fit.clicked() {
+                state.viewport.animate_to(
                     state.trace.min_clk(),
                     state.trace.max_clk(),
                     state.trace.min_clk(),
@@ -73,6 +128,105 @@ pub fn render_header(ui: &mut egui::Ui, state: &mut AppState) -> Option<HeaderIn
 
             ui.separator();
 
+            ui.menu_button("📝 Export Report", |ui| {
+                let mut scope = None;
+                if ui.button("Trace statistics…").clicked() {
+                    scope = Some(ReportScope::WholeTrace);
+                }
+                if ui.button("Viewport region statistics…").clicked() {
+                    scope = Some(ReportScope::ViewportRegion);
+                }
+                if ui
+                    .add_enabled(state.trace_health.health().is_some(), egui::Button::new("Trace health…"))
+                    .on_disabled_hover_text("Check the trace in the Trace Health window first")
+                    .clicked()
+                {
+                    scope = Some(ReportScope::TraceHealth);
+                }
+                if ui
+                    .add_enabled(state.type_hierarchy.hierarchy().is_some(), egui::Button::new("Record type hierarchy…"))
+                    .on_disabled_hover_text("Build the hierarchy in the Record Type Hierarchy window first")
+                    .clicked()
+                {
+                    scope = Some(ReportScope::TypeHierarchy);
+                }
+                if ui
+                    .add_enabled(state.idle_gaps.root().is_some(), egui::Button::new("Idle gaps…"))
+                    .on_disabled_hover_text("Search for gaps in the Idle Gaps window first")
+                    .clicked()
+                {
+                    scope = Some(ReportScope::IdleGaps);
+                }
+                if ui
+                    .add_enabled(state.flame_graph.graph().is_some(), egui::Button::new("Flame graph…"))
+                    .on_disabled_hover_text("Show a flame graph in the timeline panel first")
+                    .clicked()
+                {
+                    scope = Some(ReportScope::FlameGraph);
+                }
+
+                if let Some(scope) = scope {
+                    ui.close();
+                    if let Some(path) = pick_report_path() {
+                        interaction = Some(HeaderInteraction::ExportReport { scope, path });
+                    }
+                }
+            });
+
+            if ui.button("📦 Save Session Pack…")
+                .on_hover_text("Bundle the visible slice, view, notes and statistics into a .jetspack file")
+                .clicked()
+            {
+                if let Some(path) = pick_session_pack_path() {
+                    interaction = Some(HeaderInteraction::SaveSessionPack(path));
+                }
+            }
+
+            if ui.button("✂ Export Time Slice…")
+                .on_hover_text("Write the records overlapping the viewport clock range to a new JETS file")
+                .clicked()
+            {
+                if let Some(path) = pick_time_slice_path() {
+                    interaction = Some(HeaderInteraction::ExportTimeSlice(path));
+                }
+            }
+
+            if ui.button("💾 Export as JETS…")
+                .on_hover_text("Convert the whole trace, whatever its source format, into a JETS file (.br to compress)")
+                .clicked()
+            {
+                if let Some(path) = pick_export_jets_path() {
+                    interaction = Some(HeaderInteraction::ExportJets(path));
+                }
+            }
+
+            ui.menu_button("▦ Export Event Grid", |ui| {
+                let Some(summary) = &state.tree_cache.trace_summary else {
+                    ui.label(if state.trace.trace_data().is_some() { "Indexing…" } else { "No trace loaded" });
+                    return;
+                };
+                ui.label("One row per record, one column per event name:")
+                    .on_hover_text("Each cell is the clock of that event on that record, e.g. instruction × stage");
+                ui.separator();
+                let mut chosen = None;
+                for (record_type, stats) in &summary.type_stats {
+                    if ui.button(format!("{} ({} records)…", record_type, stats.count)).clicked() {
+                        chosen = Some(record_type.clone());
+                    }
+                }
+                if let Some(record_type) = chosen {
+                    ui.close();
+                    if let Some(path) = pick_event_grid_path(&record_type) {
+                        interaction = Some(HeaderInteraction::ExportEventGrid { record_type, path });
+                    }
+                }
+            });
+
+            render_root_selector(ui, state);
+            render_swimlane_selector(ui, state);
+
+            ui.separator();
+
             // Viewport boundary controls
             ui.label("Viewport:");
 
@@ -111,7 +265,7 @@ pub fn render_header(ui: &mut egui::Ui, state: &mut AppState) -> Option<HeaderIn
                         }
 
                         // Apply the new range (zoom is calculated automatically)
-                        state.viewport.set_range(
+                        state.viewport.animate_to(
                             new_start,
                             new_end,
                             state.trace.min_clk(),
@@ -159,6 +313,52 @@ pub fn render_header(ui: &mut egui::Ui, state: &mut AppState) -> Option<HeaderIn
                     "Show only leaf records that start within the viewport time range"
                 );
             }
+
+            let mut hide_empty = state.viewport.hide_empty_parents();
+            if ui
+                .add_enabled(filter_enabled, egui::Checkbox::new(&mut hide_empty, "Hide empty parents"))
+                .on_hover_text("Also hide parents without a leaf record in the viewport time range")
+                .on_disabled_hover_text("Enable the viewport filter first")
+                .changed()
+            {
+                state.viewport.set_hide_empty_parents(hide_empty);
+                state.tree_cache.invalidate_filtered_cache();
+            }
+
+            render_clock_filter(ui, state);
+            render_severity_filter(ui, state);
+
+            // Rows pinned by a row-scoped region zoom
+            let pinned_count = state.tree.pinned_rows().len();
+            if pinned_count > 0
+                && ui
+                    .button(format!("📌 Unpin {} rows", pinned_count))
+                    .on_hover_text("Show all rows again")
+                    .clicked()
+            {
+                state.tree.unpin_rows();
+                state.tree_cache.invalidate_filtered_cache();
+            }
+
+            ui.separator();
+
+            let mut legend_open = state.type_legend.is_open();
+            if ui.toggle_value(&mut legend_open, "🎨 Legend")
+                .on_hover_text("Record type colors, counts and visibility")
+                .changed()
+            {
+                state.type_legend.set_open(legend_open);
+            }
+
+            let mut flame_graph = state.flame_graph.is_enabled();
+            if ui.toggle_value(&mut flame_graph, "🔥 Flame Graph")
+                .on_hover_text("Show the time of the selected subtree aggregated by record name instead of the timeline")
+                .changed()
+            {
+                state.flame_graph.set_enabled(flame_graph);
+            }
+
+            render_view_menu(ui, state);
         }
 
         // Push theme selector to the right
@@ -181,16 +381,474 @@ pub fn render_header(ui: &mut egui::Ui, state: &mut AppState) -> Option<HeaderIn
             if old_theme != current_theme {
                 state.theme.set_theme(current_theme);
                 // Mark that we need to save on next frame (we'll handle this in update with frame.storage_mut)
-                ui.ctx().request_repaint();
+                state.repaint.request();
             }
 
             ui.label("Theme:");
+
+            ui.separator();
+
+            let mut settings_open = state.input_settings.is_dialog_open();
+            if ui.toggle_value(&mut settings_open, "⚙ Settings")
+                .on_hover_text("Click actions, wheel orientation and zoom sensitivity")
+                .changed()
+            {
+                state.input_settings.set_dialog_open(settings_open);
+            }
+
+            ui.separator();
+
+            let mut low_power = state.repaint.low_power_enabled();
+            if ui.checkbox(&mut low_power, "🔋 Low Power")
+                .on_hover_text("Slow down background refreshes and turn off UI animations")
+                .changed()
+            {
+                state.repaint.set_low_power(low_power);
+            }
         });
     });
 
     if let Some(err) = &state.error_message {
         ui.colored_label(Color32::RED, err);
     }
+    render_reload_report(ui, state);
+    render_settings_notice(ui, state);
 
     interaction
 }
+
+/// Shows the notice about settings migrated or reset at startup, until dismissed.
+fn render_settings_notice(ui: &mut egui::Ui, state: &mut AppState) {
+    let Some(notice) = &state.settings_notice else {
+        return;
+    };
+    let mut dismissed = false;
+    ui.horizontal(|ui| {
+        ui.colored_label(Color32::YELLOW, format!("⚙ {}", notice));
+        dismissed = ui.small_button("✕").on_hover_text("Dismiss").clicked();
+    });
+    if dismissed {
+        state.settings_notice = None;
+    }
+}
+
+/// Renders the Recent menu: pinned files, then the others most recent first.
+///
+/// # Returns
+/// The file to open, if one was clicked.
+fn render_recent_files_menu(ui: &mut egui::Ui, state: &mut AppState) -> Option<PathBuf> {
+    let entries = state.recent_files.entries().to_vec();
+    let mut opened = None;
+    let mut toggled_pin = None;
+    let mut removed = None;
+
+    ui.add_enabled_ui(!entries.is_empty(), |ui| {
+        ui.menu_button("🕘 Recent", |ui| {
+            for (index, entry) in entries.iter().enumerate() {
+                if index > 0 && entries[index - 1].pinned && !entry.pinned {
+                    ui.separator();
+                }
+                ui.horizontal(|ui| {
+                    let pin = if entry.pinned { "📌" } else { "📍" };
+                    let pin_hint = if entry.pinned { "Unpin" } else { "Pin to the top of the list" };
+                    if ui.small_button(pin).on_hover_text(pin_hint).clicked() {
+                        toggled_pin = Some(entry.path.clone());
+                    }
+                    let name = entry.path.file_name().map_or_else(
+                        || entry.path.display().to_string(),
+                        |name| name.to_string_lossy().into_owned(),
+                    );
+                    let exists = entry.path.exists();
+                    let file_button = ui
+                        .add_enabled(exists, egui::Button::new(name).frame(false))
+                        .on_hover_text(entry.path.display().to_string())
+                        .on_disabled_hover_text(format!("{} (not found)", entry.path.display()));
+                    if file_button.clicked() {
+                        opened = Some(entry.path.clone());
+                        ui.close();
+                    }
+                    if ui.small_button("✕").on_hover_text("Remove from the list").clicked() {
+                        removed = Some(entry.path.clone());
+                    }
+                });
+            }
+            if entries.iter().any(|entry| !entry.pinned) {
+                ui.separator();
+                if ui.button("Clear unpinned").clicked() {
+                    state.recent_files.clear_unpinned();
+                    ui.close();
+                }
+            }
+        })
+        .response
+        .on_hover_text("Reopen a recently opened trace")
+        .on_disabled_hover_text("No traces opened yet");
+    });
+
+    if let Some(path) = toggled_pin {
+        state.recent_files.toggle_pinned(&path);
+    }
+    if let Some(path) = removed {
+        state.recent_files.remove(&path);
+    }
+    opened
+}
+
+/// Lists the notes and pins the last reload could not re-match, until dismissed.
+fn render_reload_report(ui: &mut egui::Ui, state: &mut AppState) {
+    let unmatched = state.reload.unmatched();
+    if unmatched.is_empty() {
+        return;
+    }
+
+    let summary = format!("⚠ Reload: {} item(s) could not be re-matched", unmatched.len());
+    let details = unmatched.join("\n");
+    ui.horizontal(|ui| {
+        ui.colored_label(Color32::YELLOW, summary).on_hover_text(details);
+        if ui.small_button("✕").on_hover_text("Dismiss").clicked() {
+            state.reload.dismiss_report();
+        }
+    });
+}
+
+/// Asks for trace files to open, starting in the working directory.
+///
+/// Several selected files are opened as one merged trace.
+pub fn pick_trace_files() -> Option<Vec<PathBuf>> {
+    let mut dialog = rfd::FileDialog::new()
+        .add_filter("All Trace Files", &["jets", "jsonl", "br", "pt", "gz", "perf", "ftrace", "txt", "json", "jetspack"])
+        .add_filter("JETS Traces", &["jets", "jsonl", "br"])
+        .add_filter("PipeTrace Files", &["pt", "gz"])
+        .add_filter("perf script / ftrace Output", &["perf", "ftrace", "txt"])
+        .add_filter("Chrome / Perfetto Trace JSON", &["json"])
+        .add_filter("Session Packs", &["jetspack"]);
+
+    if let Ok(cwd) = std::env::current_dir() {
+        dialog = dialog.set_directory(cwd);
+    }
+    dialog.pick_files().filter(|paths| !paths.is_empty())
+}
+
+/// Asks for the destination of a statistics report (.md or .html).
+pub fn pick_report_path() -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("Markdown", &["md"])
+        .add_filter("HTML", &["html", "htm"])
+        .set_file_name("report.md")
+        .save_file()
+}
+
+/// Asks for the destination of a `.jetspack` session pack.
+pub fn pick_session_pack_path() -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("Session Packs", &["jetspack"])
+        .set_file_name("session.jetspack")
+        .save_file()
+}
+
+/// Asks for the destination of an exported time slice.
+pub fn pick_time_slice_path() -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("JETS Traces", &["jets", "br"])
+        .set_file_name("slice.jets")
+        .save_file()
+}
+
+/// Asks for the destination of a JETS copy of the whole trace.
+pub fn pick_export_jets_path() -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("JETS Traces", &["jets", "br"])
+        .set_file_name("trace.jets.br")
+        .save_file()
+}
+
+/// Asks for the destination of an event grid of `record_type`.
+pub fn pick_event_grid_path(record_type: &str) -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("CSV", &["csv"])
+        .set_file_name(format!("{}_events.csv", record_type))
+        .save_file()
+}
+
+/// Renders the View menu with the timeline overlay toggles.
+fn render_view_menu(ui: &mut egui::Ui, state: &mut AppState) {
+    ui.menu_button("👁 View", |ui| {
+        let settings = state.input_settings.settings_mut();
+        ui.checkbox(&mut settings.capture_bounds, "Trace extent and capture end")
+            .on_hover_text("Mark where the data ends and where the capture ended; time after the capture is shaded");
+        ui.checkbox(&mut settings.gridlines, "Gridlines at major ticks");
+        ui.checkbox(&mut settings.bottom_axis, "Time axis at the bottom too");
+        let mut split = state.viewport.is_split();
+        if ui
+            .checkbox(&mut split, "Split timeline")
+            .on_hover_text("Stack a second time window over the same rows, panned and zoomed on its own")
+            .changed()
+        {
+            state.viewport.set_split(split);
+        }
+        let mut measuring = state.measurement.is_enabled();
+        if ui
+            .checkbox(&mut measuring, "Measurement cursors")
+            .on_hover_text("Clicks on the timeline drop cursors A and B and show the time, records and events between them (Alt+click snaps to an event)")
+            .changed()
+        {
+            state.measurement.set_enabled(measuring);
+        }
+        ui.separator();
+        ui.menu_button("Layout", |ui| render_layout_menu(ui, state));
+    });
+}
+
+/// Renders the dock layout submenu: panel placement, presets and reset.
+fn render_layout_menu(ui: &mut egui::Ui, state: &mut AppState) {
+    egui::Grid::new("dock_panel_grid").num_columns(2).show(ui, |ui| {
+        for panel in DockPanel::ALL {
+            let current = state.dock.layout().slot_of(panel);
+            let mut placement = current;
+            ui.label(panel.title());
+            egui::ComboBox::from_id_salt(("dock_panel_slot", panel))
+                .selected_text(placement.map_or("Hidden", DockSlot::label))
+                .show_ui(ui, |ui| {
+                    for slot in DockSlot::ALL {
+                        ui.selectable_value(&mut placement, Some(slot), slot.label());
+                    }
+                    ui.selectable_value(&mut placement, None, "Hidden");
+                });
+            if placement != current {
+                match placement {
+                    Some(slot) => state.dock.layout_mut().move_panel(panel, slot),
+                    None => state.dock.layout_mut().close_panel(panel),
+                }
+            }
+            ui.end_row();
+        }
+    });
+
+    ui.separator();
+    let names: Vec<String> = state.dock.presets().keys().cloned().collect();
+    for name in names {
+        ui.horizontal(|ui| {
+            if ui.button(&name).on_hover_text("Switch to this layout").clicked() {
+                state.dock.apply_preset(&name);
+                ui.close();
+            }
+            if ui.small_button("🗑").on_hover_text("Delete preset").clicked() {
+                state.dock.delete_preset(&name);
+            }
+        });
+    }
+    ui.horizontal(|ui| {
+        ui.add(egui::TextEdit::singleline(state.dock.preset_name_input_mut()).hint_text("Preset name").desired_width(120.0));
+        let name = state.dock.preset_name_input_mut().trim().to_string();
+        if ui.add_enabled(!name.is_empty(), egui::Button::new("Save")).clicked() {
+            state.dock.save_preset(&name);
+            state.dock.preset_name_input_mut().clear();
+        }
+    });
+
+    ui.separator();
+    if ui.button("Reset to Default Layout").clicked() {
+        state.dock.reset_layout();
+        ui.close();
+    }
+}
+
+/// Renders the minimum event severity selector and the event filter toggle.
+fn render_severity_filter(ui: &mut egui::Ui, state: &mut AppState) {
+    let mut min_severity = state.event_filter.min_severity();
+    let label = |severity: Severity| match severity {
+        Severity::Info => "All events",
+        Severity::Warning => "Warnings and errors",
+        Severity::Error => "Errors only",
+    };
+    egui::ComboBox::from_id_salt("severity_filter")
+        .selected_text(format!("⚠ {}", label(min_severity)))
+        .show_ui(ui, |ui| {
+            for severity in Severity::ALL {
+                ui.selectable_value(&mut min_severity, severity, label(severity));
+            }
+        })
+        .response
+        .on_hover_text("Hide timeline events below a severity (set by the \"severity\" event attribute)");
+    if min_severity != state.event_filter.min_severity() {
+        state.event_filter.set_min_severity(min_severity);
+        state.repaint.request();
+    }
+
+    let mut event_filter_open = state.event_type_filter.is_open();
+    if ui.toggle_value(&mut event_filter_open, "🔖 Events")
+        .on_hover_text("Show or hide events by name")
+        .changed()
+    {
+        state.event_type_filter.set_open(event_filter_open);
+    }
+}
+
+/// Renders the clock stride filter popover.
+///
+/// Editing the filter changes the visible row set, so the filtered cache is
+/// invalidated here.
+fn render_clock_filter(ui: &mut egui::Ui, state: &mut AppState) {
+    let title = match state.clock_filter.stride() {
+        Some(stride) => format!("⏲ clk % {} = {}", stride.stride, stride.offset.rem_euclid(stride.stride)),
+        None => "⏲ Clock Stride".to_string(),
+    };
+
+    let response = ui.menu_button(title, |ui| {
+        let mut changed = false;
+        let mut enabled = state.clock_filter.is_enabled();
+        changed |= ui.checkbox(&mut enabled, "Filter by clock stride").changed();
+        state.clock_filter.set_enabled(enabled);
+
+        let (stride, offset) = state.clock_filter.stride_and_offset_mut();
+        egui::Grid::new("clock_stride_grid").num_columns(2).show(ui, |ui| {
+            ui.label("Stride");
+            changed |= ui.add(egui::DragValue::new(stride).range(MIN_CLOCK_STRIDE..=i64::MAX)).changed();
+            ui.end_row();
+
+            ui.label("Offset");
+            changed |= ui.add(egui::DragValue::new(offset)).changed();
+            ui.end_row();
+        });
+        ui.label(
+            egui::RichText::new("Shows leaf records whose start clk satisfies (clk - offset) % stride == 0")
+                .small()
+                .weak(),
+        );
+
+        if changed {
+            state.tree_cache.invalidate_filtered_cache();
+        }
+    });
+    response
+        .response
+        .on_hover_text("Show only records starting on one phase of a clock stride");
+}
+
+/// Renders the root selector menu: lane mode toggle plus per-root visibility.
+///
+/// Changing root visibility alters the visible row set, so the tree cache is
+/// invalidated here.
+fn render_root_selector(ui: &mut egui::Ui, state: &mut AppState) {
+    let Some(trace) = state.trace.trace_data() else {
+        return;
+    };
+    let root_ids = trace.roots();
+    let shown = root_ids.iter().filter(|&&id| state.lanes.is_root_visible(id)).count();
+
+    ui.menu_button(format!("🗂 Roots {}/{}", shown, root_ids.len()), |ui| {
+        let mut root_lanes = state.lanes.root_lanes_enabled();
+        if ui.checkbox(&mut root_lanes, "Show roots as lanes").changed() {
+            state.lanes.set_root_lanes_enabled(root_lanes);
+        }
+        ui.separator();
+
+        ui.add(
+            egui::TextEdit::singleline(state.lanes.root_filter_text_mut())
+                .hint_text("Filter roots…")
+                .desired_width(200.0),
+        );
+        let filter = state.lanes.root_filter_text_mut().to_lowercase();
+        let matching: Vec<_> = root_ids
+            .iter()
+            .filter_map(|&id| trace.get_record(id))
+            .filter(|r| {
+                filter.is_empty()
+                    || r.name().to_lowercase().contains(&filter)
+                    || r.external_id().to_string().to_lowercase().contains(&filter)
+            })
+            .collect();
+
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            if ui.button("Show all").clicked() {
+                state.lanes.show_all_roots();
+                changed = true;
+            }
+            if ui.button("Show matching only").clicked() {
+                let matching_ids: std::collections::HashSet<u64> = matching.iter().map(|r| r.id()).collect();
+                state.lanes.set_hidden_roots(root_ids.iter().copied().filter(|id| !matching_ids.contains(id)));
+                changed = true;
+            }
+        });
+
+        egui::ScrollArea::vertical()
+            .id_salt("root_selector_scroll")
+            .max_height(300.0)
+            .show(ui, |ui| {
+                for record in &matching {
+                    let mut visible = state.lanes.is_root_visible(record.id());
+                    let display_name = state.input_settings.settings().display_name(&record.name()).into_owned();
+                    let mut label = format!("{} ({})", display_name, record.external_id());
+                    if let Some(size) = state.tree_cache.trace_summary.as_ref()
+                        .and_then(|s| s.root_subtree_sizes.get(&record.id()))
+                    {
+                        label.push_str(&format!(" · {} records", size));
+                    }
+                    if ui.checkbox(&mut visible, label).changed() {
+                        changed |= state.lanes.set_root_visible(record.id(), visible);
+                    }
+                }
+            });
+
+        if changed {
+            state.tree_cache.invalidate();
+        }
+    });
+}
+
+/// Renders the swimlane menu: choose an attribute to group rows by, or
+/// return to tree order.
+///
+/// Suggestions are sampled from the trace only while the menu is open.
+fn render_swimlane_selector(ui: &mut egui::Ui, state: &mut AppState) {
+    let Some(trace) = state.trace.trace_data() else {
+        return;
+    };
+    let title = match state.lanes.lane_attribute() {
+        Some(attribute) => format!("🏊 Lanes: {}", attribute),
+        None => "🏊 Lanes: tree".to_string(),
+    };
+
+    ui.menu_button(title, |ui| {
+        let mut selected: Option<Option<String>> = None;
+
+        ui.horizontal(|ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(state.lanes.lane_attribute_text_mut())
+                    .hint_text("Attribute key…")
+                    .desired_width(160.0),
+            );
+            let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if ui.button("Apply").clicked() || submitted {
+                selected = Some(Some(state.lanes.lane_attribute_text_mut().trim().to_string()));
+            }
+            if ui.button("Off").clicked() {
+                selected = Some(None);
+            }
+        });
+
+        ui.separator();
+        ui.label("Attributes in trace:");
+        egui::ScrollArea::vertical()
+            .id_salt("swimlane_attribute_scroll")
+            .max_height(250.0)
+            .show(ui, |ui| {
+                for key in crate::domain::swimlanes::sample_attribute_keys(trace) {
+                    let is_current = state.lanes.lane_attribute() == Some(key.as_str());
+                    if ui.selectable_label(is_current, &key).clicked() {
+                        selected = Some(Some(key));
+                    }
+                }
+            });
+
+        if let Some(attribute) = selected {
+            if let Some(key) = &attribute {
+                *state.lanes.lane_attribute_text_mut() = key.clone();
+            }
+            state.lanes.set_lane_attribute(attribute);
+            state.tree_cache.invalidate();
+            ui.close();
+        }
+    });
+}
@@ -0,0 +1,131 @@
+//! Idle gap window
+//!
+//! Lists the longest idle gaps under the searched subtree. Clicking a gap, or
+//! stepping with Previous/Next, moves the viewport onto it with a margin on
+//! both sides so the records around the gap stay visible.
+
+use eframe::egui;
+use egui::RichText;
+use rjets::ThemeColors;
+
+use crate::app::AppState;
+use crate::ui::header;
+use crate::utils::format_clock;
+
+/// Result of user interaction with the idle gap window
+pub enum IdleGapsInteraction {
+    /// User asked to search under the selected record
+    SearchRequested,
+    /// User chose a destination for a report of the gaps found
+    ExportReport(std::path::PathBuf),
+}
+
+/// Renders the idle gap window if it is open.
+///
+/// # Arguments
+/// * `ctx` - The egui context the window is shown in
+/// * `state` - Mutable reference to application state
+/// * `theme_colors` - Color palette for the current theme
+pub fn render_idle_gaps_window(
+    ctx: &egui::Context,
+    state: &mut AppState,
+    theme_colors: &ThemeColors,
+) -> Option<IdleGapsInteraction> {
+    let mut open = state.idle_gaps.is_open();
+    if !open {
+        return None;
+    }
+
+    let mut interaction = None;
+    egui::Window::new("⏸ Idle Gaps")
+        .open(&mut open)
+        .resizable(true)
+        .default_width(320.0)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let has_selection = state.selection.selected_record_id().is_some();
+                if ui
+                    .add_enabled(has_selection, egui::Button::new("Find Under Selection"))
+                    .on_hover_text("Find intervals where no leaf record under the selected record is active")
+                    .on_disabled_hover_text("Select a record first")
+                    .clicked()
+                {
+                    interaction = Some(IdleGapsInteraction::SearchRequested);
+                }
+                if let Some((_, name)) = state.idle_gaps.root() {
+                    if ui.button("Export Report…").on_hover_text("Save the gaps as Markdown or HTML").clicked() {
+                        if let Some(path) = header::pick_report_path() {
+                            interaction = Some(IdleGapsInteraction::ExportReport(path));
+                        }
+                    }
+                    ui.label(RichText::new(format!("under {}", name)).color(theme_colors.text_dim));
+                }
+            });
+
+            if state.idle_gaps.root().is_none() {
+                return;
+            }
+            if state.idle_gaps.gaps().is_empty() {
+                ui.label(RichText::new("No idle gaps").color(theme_colors.text_dim));
+                return;
+            }
+
+            let count = state.idle_gaps.gaps().len();
+            ui.horizontal(|ui| {
+                let current = state.idle_gaps.current();
+                if ui.add_enabled(current.is_some_and(|i| i > 0), egui::Button::new("◀ Previous")).clicked() {
+                    jump_to_gap(state, current.unwrap_or(0).saturating_sub(1));
+                }
+                let next = current.map_or(0, |i| i + 1);
+                if ui.add_enabled(next < count, egui::Button::new("Next ▶")).clicked() {
+                    jump_to_gap(state, next);
+                }
+            });
+            ui.separator();
+
+            let mut clicked = None;
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                egui::Grid::new("idle_gaps_grid")
+                    .num_columns(3)
+                    .striped(true)
+                    .spacing([12.0, 4.0])
+                    .show(ui, |ui| {
+                        ui.label(RichText::new("Duration").strong());
+                        ui.label(RichText::new("Start").strong());
+                        ui.label(RichText::new("End").strong());
+                        ui.end_row();
+
+                        for (index, gap) in state.idle_gaps.gaps().iter().enumerate() {
+                            let selected = state.idle_gaps.current() == Some(index);
+                            if ui.selectable_label(selected, format_clock(gap.duration())).clicked() {
+                                clicked = Some(index);
+                            }
+                            ui.label(format_clock(gap.start_clk));
+                            ui.label(format_clock(gap.end_clk));
+                            ui.end_row();
+                        }
+                    });
+            });
+            if let Some(index) = clicked {
+                jump_to_gap(state, index);
+            }
+        });
+
+    state.idle_gaps.set_open(open);
+    interaction
+}
+
+/// Moves the viewport onto a gap, with a tenth of its length as margin.
+fn jump_to_gap(state: &mut AppState, index: usize) {
+    let Some(gap) = state.idle_gaps.gaps().get(index).copied() else {
+        return;
+    };
+    let margin = (gap.duration() / 10).max(1);
+    state.viewport.animate_to(
+        gap.start_clk - margin,
+        gap.end_clk + margin,
+        state.trace.min_clk(),
+        state.trace.max_clk(),
+    );
+    state.idle_gaps.set_current(index);
+}
@@ -5,11 +5,18 @@
 //! - Drag panning (left mouse + drag)
 //! - Zoom to region (Ctrl+drag or right mouse + drag)
 //! - Scroll wheel zoom (Ctrl + wheel)
-//! - Scroll wheel pan (wheel without Ctrl)
+//! - Scroll wheel pan or row scroll (wheel without Ctrl, Shift swapping them)
 //! - Cursor tracking for hover position
+//! - Measurement cursor placement (click while measurement mode is on)
+//!
+//! A completed region zoom reports its vertical span so the panel can scroll
+//! to or pin the selected rows.
+//!
+//! Wheel direction and zoom speed follow the user's [`InputSettings`].
 
 use eframe::egui;
 use crate::domain::viewport_operations;
+use crate::state::{InputSettings, WheelAction};
 
 /// Result of timeline input handling
 pub enum TimelineInputResult {
@@ -19,6 +26,12 @@ pub enum TimelineInputResult {
     ViewportUpdated,
     /// Cursor hover position changed
     CursorMoved,
+    /// Viewport was zoomed to a region selection spanning this screen Y range
+    RegionZoomed { top_y: f32, bottom_y: f32 },
+    /// Wheel asked to scroll the shared rows by this many points (positive = up)
+    RowsScrolled { delta_y: f32 },
+    /// A measurement cursor was placed at this clock
+    MeasureCursorPlaced { clk: i64 },
 }
 
 /// Handles all timeline input events and updates viewport/interaction state.
@@ -31,13 +44,14 @@ pub enum TimelineInputResult {
 /// * `viewport_end_clk` - Current viewport end clock (mutable)
 /// * `trace_min_clk` - Minimum trace clock for clamping
 /// * `trace_max_clk` - Maximum trace clock for clamping
-/// * `zoom_level` - Current zoom level (mutable)
 /// * `is_dragging` - Drag state flag (mutable)
 /// * `drag_start_clk` - Clock where drag started (mutable)
 /// * `is_selecting_region` - Region selection state flag (mutable)
 /// * `region_start_pos` - Region selection start position (mutable)
 /// * `cursor_hover_pos` - Cursor hover position (mutable)
 /// * `cursor_hover_clk` - Cursor hover clock value (mutable)
+/// * `input_settings` - Wheel action, orientation and zoom sensitivity
+/// * `measuring` - Whether a plain click places a measurement cursor
 ///
 /// # Returns
 /// The result of input handling
@@ -50,15 +64,17 @@ pub fn handle_timeline_input(
     viewport_end_clk: &mut i64,
     trace_min_clk: i64,
     trace_max_clk: i64,
-    zoom_level: &mut f32,
     is_dragging: &mut bool,
     drag_start_clk: &mut i64,
     is_selecting_region: &mut bool,
     region_start_pos: &mut Option<egui::Pos2>,
     cursor_hover_pos: &mut Option<egui::Pos2>,
     cursor_hover_clk: &mut Option<i64>,
+    input_settings: &InputSettings,
+    measuring: bool,
 ) -> TimelineInputResult {
     let mut result = TimelineInputResult::None;
+    let mut region_span: Option<(f32, f32)> = None;
 
     // Check if Ctrl is held or right mouse button is being used
     let ctrl_held = ctx.input(|i| i.modifiers.ctrl);
@@ -90,28 +106,15 @@ pub fn handle_timeline_input(
                     // drag started (debug print removed)
             }
 
-            // Calculate how much clock time the drag represents
-            let viewport_range = (*viewport_end_clk - *viewport_start_clk) as f32;
-            let pixels_to_clk_ratio = viewport_range / canvas_rect.width();
-            let clk_delta = (-drag_delta.x * pixels_to_clk_ratio) as i64;
-
-            // dragging (debug print removed)
-
-            // Apply the pan
-            *viewport_start_clk += clk_delta;
-            *viewport_end_clk += clk_delta;
-
-            // Clamp to trace bounds
-            if *viewport_start_clk < trace_min_clk {
-                let diff = trace_min_clk - *viewport_start_clk;
-                *viewport_start_clk = trace_min_clk;
-                *viewport_end_clk += diff;
-            }
-            if *viewport_end_clk > trace_max_clk {
-                let diff = *viewport_end_clk - trace_max_clk;
-                *viewport_end_clk = trace_max_clk;
-                *viewport_start_clk -= diff;
-            }
+            // Apply the pan, clamped to trace bounds
+            (*viewport_start_clk, *viewport_end_clk) = viewport_operations::drag_pan(
+                drag_delta.x,
+                canvas_rect.width(),
+                *viewport_start_clk,
+                *viewport_end_clk,
+                trace_min_clk,
+                trace_max_clk,
+            );
 
             // viewport after drag (debug print removed)
             result = TimelineInputResult::ViewportUpdated;
@@ -136,17 +139,17 @@ pub fn handle_timeline_input(
                         (end_clk, start_clk)
                     };
 
-                    // Apply zoom to the selected region
-                    *viewport_start_clk = new_start_clk.max(trace_min_clk);
-                    *viewport_end_clk = new_end_clk.min(trace_max_clk);
-
-                    // Update zoom level
-                    let new_range = (*viewport_end_clk - *viewport_start_clk) as f32;
-                    let full_range = (trace_max_clk - trace_min_clk) as f32;
-                    *zoom_level = full_range / new_range;
+                    // Apply zoom to the selected region, no narrower than the minimum viewport width
+                    (*viewport_start_clk, *viewport_end_clk) = viewport_operations::fit_range(
+                        new_start_clk.max(trace_min_clk),
+                        new_end_clk.min(trace_max_clk),
+                        trace_min_clk,
+                        trace_max_clk,
+                    );
 
                     // zoomed to region (debug print removed)
                     result = TimelineInputResult::ViewportUpdated;
+                    region_span = Some((start_pos.y.min(current_pos.y), start_pos.y.max(current_pos.y)));
                 } else {
                     // region selection too small (debug print removed)
                 }
@@ -198,84 +201,62 @@ pub fn handle_timeline_input(
             if i.modifiers.ctrl && scroll_y != 0.0 {
                 // zoom triggered (debug print removed)
 
-                let zoom_factor = 1.0 + scroll_y * 0.002;
+                let zoom_factor = input_settings.wheel_zoom_factor(scroll_y);
                 let mouse_pos = i.pointer.hover_pos().unwrap_or(canvas_rect.center());
                 let mouse_clk = viewport_operations::x_to_clk(mouse_pos.x, *viewport_start_clk, *viewport_end_clk, canvas_rect);
 
                 // zoom factor computed (debug print removed)
 
-                *zoom_level = (*zoom_level * zoom_factor).clamp(1.0, 10000.0);
-
-                let new_range = (trace_max_clk - trace_min_clk) as f32 / *zoom_level;
-                let old_range = (*viewport_end_clk - *viewport_start_clk) as f32;
-                let left_ratio = if old_range > 0.0 {
-                    (mouse_clk - *viewport_start_clk) as f32 / old_range
-                } else {
-                    0.5
-                };
-
-                *viewport_start_clk = mouse_clk - (left_ratio * new_range) as i64;
-                *viewport_end_clk = *viewport_start_clk + new_range as i64;
-                *viewport_start_clk = (*viewport_start_clk).max(trace_min_clk);
-                *viewport_end_clk = (*viewport_end_clk).min(trace_max_clk);
+                (*viewport_start_clk, *viewport_end_clk) = viewport_operations::zoom_range(
+                    zoom_factor as f64,
+                    mouse_clk,
+                    *viewport_start_clk,
+                    *viewport_end_clk,
+                    trace_min_clk,
+                    trace_max_clk,
+                );
 
                 // new zoom level applied (debug print removed)
                 result = TimelineInputResult::ViewportUpdated;
             }
 
-            // Handle pan (mouse wheel without Ctrl or middle-mouse drag)
-            // Mouse wheel Y-axis pans horizontally in the timeline
-            let scroll_y_for_pan = if i.raw_scroll_delta.y != 0.0 {
-                i.raw_scroll_delta.y
+            // Handle plain wheel: pan time or scroll rows, Shift swapping the two.
+            // egui turns Shift+wheel into a horizontal scroll, so take both axes then.
+            let wheel = if i.modifiers.shift {
+                let delta = if i.raw_scroll_delta != egui::Vec2::ZERO { i.raw_scroll_delta } else { i.smooth_scroll_delta };
+                delta.x + delta.y
             } else {
-                i.smooth_scroll_delta.y
+                scroll_y
             };
 
-            if !i.modifiers.ctrl && scroll_y_for_pan != 0.0 {
-                // pan triggered (debug print removed)
-
-                // Negative scroll_y means scroll down/right, positive means scroll up/left
-                // Invert the sign so scrolling down moves the timeline left (showing later times)
-                let viewport_range = (*viewport_end_clk - *viewport_start_clk) as f32;
-
-                // Calculate pan amount with minimum threshold to ensure movement at high zoom
-                let pan_amount = (-scroll_y_for_pan / 100.0) * viewport_range * 0.1;
-
-                // At high zoom levels (small viewport_range), ensure we always move at least 1 clock
-                // Use a minimum of 1 clock or 2% of viewport range, whichever is larger
-                let min_pan = (viewport_range * 0.02).max(1.0);
-                let pan_clk = if pan_amount.abs() < min_pan {
-                    if pan_amount >= 0.0 {
-                        min_pan
-                    } else {
-                        -min_pan
+            if !i.modifiers.ctrl && wheel != 0.0 {
+                match input_settings.wheel_action(i.modifiers.shift) {
+                    WheelAction::PanTime => {
+                        (*viewport_start_clk, *viewport_end_clk) = viewport_operations::wheel_pan(
+                            input_settings.oriented_scroll(wheel),
+                            *viewport_start_clk,
+                            *viewport_end_clk,
+                            trace_min_clk,
+                            trace_max_clk,
+                        );
+                        result = TimelineInputResult::ViewportUpdated;
                     }
-                } else {
-                    pan_amount
-                };
-
-                // pan calculation (debug print removed)
-
-                *viewport_start_clk += pan_clk as i64;
-                *viewport_end_clk += pan_clk as i64;
-
-                // Clamp to trace bounds
-                if *viewport_start_clk < trace_min_clk {
-                    let diff = trace_min_clk - *viewport_start_clk;
-                    *viewport_start_clk = trace_min_clk;
-                    *viewport_end_clk += diff;
+                    WheelAction::ScrollRows => result = TimelineInputResult::RowsScrolled { delta_y: wheel },
                 }
-                if *viewport_end_clk > trace_max_clk {
-                    let diff = *viewport_end_clk - trace_max_clk;
-                    *viewport_end_clk = trace_max_clk;
-                    *viewport_start_clk -= diff;
-                }
-
-                // new viewport after pan (debug print removed)
-                result = TimelineInputResult::ViewportUpdated;
             }
         });
     }
 
+    // A completed region zoom takes precedence so the caller can act on the rows
+    if let Some((top_y, bottom_y)) = region_span {
+        return TimelineInputResult::RegionZoomed { top_y, bottom_y };
+    }
+
+    // A click without a drag places a measurement cursor (Ctrl+click keeps its record gesture)
+    if measuring && !ctrl_held && ctx.input(|i| i.pointer.primary_clicked()) {
+        if let Some(clk) = *cursor_hover_clk {
+            return TimelineInputResult::MeasureCursorPlaced { clk };
+        }
+    }
     result
 }
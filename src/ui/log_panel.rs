@@ -0,0 +1,72 @@
+//! Log console panel
+//!
+//! Lists the log entries collected since startup, filtered by level and
+//! text, so load failures and timings can be copied into a bug report
+//! without rerunning the viewer from a terminal.
+
+use eframe::egui;
+use egui::RichText;
+use rjets::logging::Level;
+use rjets::ThemeColors;
+
+use crate::app::AppState;
+
+/// Levels offered in the level selector, most severe first.
+const LEVELS: [Level; 5] = [Level::ERROR, Level::WARN, Level::INFO, Level::DEBUG, Level::TRACE];
+
+/// Renders the log console panel contents.
+///
+/// # Arguments
+/// * `ui` - The egui UI context for drawing
+/// * `state` - Mutable reference to application state
+/// * `theme_colors` - Color palette for the current theme
+pub fn render_log_panel(ui: &mut egui::Ui, state: &mut AppState, theme_colors: &ThemeColors) {
+    let entries = state.log_console.visible_entries();
+
+    ui.horizontal(|ui| {
+        let mut min_level = state.log_console.min_level();
+        egui::ComboBox::from_id_salt("log_level")
+            .selected_text(min_level.as_str())
+            .show_ui(ui, |ui| {
+                for level in LEVELS {
+                    ui.selectable_value(&mut min_level, level, level.as_str());
+                }
+            });
+        state.log_console.set_min_level(min_level);
+
+        ui.add(
+            egui::TextEdit::singleline(state.log_console.filter_mut())
+                .hint_text("Filter")
+                .desired_width(180.0),
+        );
+        if ui.button("Copy").on_hover_text("Copy the shown entries").clicked() {
+            let text: Vec<String> = entries.iter().map(|entry| entry.to_string()).collect();
+            ui.ctx().copy_text(text.join("\n"));
+        }
+        if ui.button("Clear").clicked() {
+            state.log_console.clear();
+        }
+        ui.label(
+            RichText::new(format!("{} of {} entries", entries.len(), state.log_console.total_entries()))
+                .color(theme_colors.text_dim),
+        );
+    });
+    ui.separator();
+
+    let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+    egui::ScrollArea::both()
+        .id_salt("log_scroll_area")
+        .auto_shrink([false, false])
+        .stick_to_bottom(true)
+        .show_rows(ui, row_height, entries.len(), |ui, rows| {
+            for entry in &entries[rows] {
+                let color = match entry.level {
+                    Level::ERROR => theme_colors.red,
+                    Level::WARN => theme_colors.yellow,
+                    Level::INFO => theme_colors.text,
+                    _ => theme_colors.text_dim,
+                };
+                ui.label(RichText::new(entry.to_string()).monospace().color(color));
+            }
+        });
+}
@@ -2,23 +2,45 @@
 //!
 //! This module contains all UI panel rendering logic for the JETS trace viewer:
 //! - Header panel (file controls, zoom, theme selector)
+//! - Filter chips (active filters with quick-clear buttons)
 //! - Tree panel (hierarchical signal view)
 //! - Timeline panel (temporal view with panning and zooming)
 //! - Details panel (record details, annotations, events)
+//! - Statistics panel (whole-trace summary by record type and severity)
+//! - Log console panel (collected log entries with level and text filters)
 //! - Status bar (trace metadata display)
+//! - Type legend (record type colors, counts and visibility)
+//! - Event filter window (event names shown or hidden in the timeline and details)
+//! - Idle gap window (longest idle intervals under a subtree)
+//! - Trace health window (orphans, clock anomalies and parser warnings)
+//! - Type hierarchy window (record counts and durations per record type chain)
+//! - Settings dialog (interaction preferences)
+//! - Command palette (fuzzy search over registered actions)
 //! - Table header component (resizable column headers)
 //! - Virtual scrolling (viewport-based visible node collection)
 //! - Virtual scroll manager (shared scrolling logic)
-//! - Panel manager (panel orchestration and layout)
+//! - Panel manager (panel orchestration and dock layout)
 //! - Input handling (mouse, keyboard, touch interactions)
+//! - Accessibility (screen-reader annotations, keyboard focus outline)
 
 pub mod header;
+pub mod filter_chips;
 pub mod tree_panel;
 pub mod timeline_panel;
 pub mod details_panel;
+pub mod statistics_panel;
+pub mod log_panel;
 pub mod status_bar;
+pub mod type_legend_panel;
+pub mod event_filter_window;
+pub mod idle_gaps_window;
+pub mod trace_health_window;
+pub mod type_hierarchy_window;
+pub mod settings_dialog;
+pub mod command_palette;
 pub mod table_header;
 pub mod virtual_scrolling;
 pub mod virtual_scroll_manager;
 pub mod panel_manager;
 pub mod input;
+pub mod accessibility;
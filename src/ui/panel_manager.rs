@@ -1,26 +1,64 @@
 //! Panel orchestration and layout management.
 //!
-//! Coordinates all UI panels (header, tree, timeline, details, status) and manages
-//! their layout, resizing, and interaction coordination.
+//! Coordinates all UI panels (header, tree, timeline, details, statistics,
+//! status) and manages their layout, resizing, and interaction coordination.
+//! Header and status bar are fixed; the other panels are placed by the dock
+//! layout (see [`crate::state::DockLayout`]).
 
 use crate::app::AppState;
 use crate::io::AsyncLoader;
-use crate::ui::{details_panel, header, status_bar, timeline_panel, tree_panel};
+use crate::state::{DockLayout, DockPanel, DockSlot};
+use crate::ui::{
+    details_panel, event_filter_window, filter_chips, header, idle_gaps_window, log_panel, settings_dialog, statistics_panel, status_bar, timeline_panel,
+    trace_health_window, tree_panel, type_hierarchy_window, type_legend_panel,
+};
 use crate::presentation::color_mapping;
-use egui::Color32;
+use crate::rendering::tooltip_renderer;
+use egui::{Color32, RichText};
+use rjets::ThemeColors;
+use std::collections::HashMap;
+
+/// Default width of the right dock slot as a fraction of the window width.
+const RIGHT_SLOT_WIDTH_RATIO: f32 = 0.25;
 
 /// Result of panel interactions that need to be handled by the application coordinator.
 pub enum PanelInteraction {
-    /// User requested to open a file
-    OpenFileRequested(std::path::PathBuf),
+    /// User requested to open files (several are merged into one trace)
+    OpenFilesRequested(Vec<std::path::PathBuf>),
+    /// User requested to reload the current trace from disk
+    ReloadRequested,
+    /// User canceled the load in progress
+    LoadCancelRequested,
     /// User requested to open a virtual trace
     OpenVirtualTraceRequested,
+    /// User requested exporting an analysis report
+    ExportReportRequested {
+        scope: header::ReportScope,
+        path: std::path::PathBuf,
+    },
+    /// User requested saving a session pack
+    SaveSessionPackRequested(std::path::PathBuf),
+    /// User requested exporting the viewport clock range as a JETS file
+    ExportTimeSliceRequested(std::path::PathBuf),
+    /// User requested converting the whole trace into a JETS file
+    ExportJetsRequested(std::path::PathBuf),
+    /// User requested exporting the event grid of a record type
+    ExportEventGridRequested {
+        record_type: String,
+        path: std::path::PathBuf,
+    },
+    /// User requested exporting the settings profile
+    ExportSettingsProfileRequested(std::path::PathBuf),
+    /// User requested importing a settings profile
+    ImportSettingsProfileRequested(std::path::PathBuf),
     /// A tree node was selected
     TreeNodeSelected {
         record_id: u64,
         was_already_selected: bool,
         first_event_clk: Option<i64>,
     },
+    /// A tree node was Shift+clicked to select a range of rows
+    TreeNodeRangeSelected { record_id: u64 },
     /// A tree node's expansion state was toggled
     TreeNodeExpandToggled {
         record_id: u64,
@@ -32,13 +70,47 @@ pub enum PanelInteraction {
         was_already_selected: bool,
         first_event_clk: Option<i64>,
     },
+    /// A tree row or timeline bar was double-clicked or Ctrl+clicked
+    RecordGesture {
+        record_id: u64,
+        gesture: crate::state::ClickGesture,
+    },
     /// A timeline event was clicked
     TimelineEventClicked {
         record_id: u64,
         event_clk: i64,
     },
+    /// An event hidden below a collapsed record should be shown
+    EventRevealRequested {
+        record_id: u64,
+        event_clk: i64,
+    },
+    /// Navigation to a record related to `record_id` was requested
+    RecordNavigationRequested {
+        record_id: u64,
+        scope: crate::domain::record_navigation::NavigationScope,
+        forward: bool,
+    },
+    /// User requested idle gaps under the selected record
+    IdleGapSearchRequested,
+    /// User asked to reveal the selected record hidden by filters
+    RevealSelectionRequested,
+    /// User requested a health check of the loaded trace
+    TraceHealthCheckRequested,
+    /// A record listed in the trace health window or linked from the details panel was clicked
+    RecordRevealRequested(u64),
+    /// User requested the record type hierarchy of the loaded trace
+    TypeHierarchyRequested,
+    /// The flame graph view needs the graph of this record's subtree
+    FlameGraphRequested { root_id: u64 },
     /// User requested sorting by clicking a column header
     TreeSortRequested(crate::state::SortSpec),
+    /// User requested sorting by an attribute from the sort menu
+    AttributeSortRequested(String),
+    /// A swimlane header was clicked in either panel
+    SwimlaneToggled(String),
+    /// A measurement cursor was placed on the timeline
+    MeasureCursorPlaced { clk: i64 },
 }
 
 /// Manages the layout and rendering of all UI panels.
@@ -58,99 +130,297 @@ impl PanelManager {
 
         // Get theme colors for rendering
         let theme_colors = color_mapping::theme_colors(state.theme.theme_manager(), state.theme.current_theme_name()).clone();
+        // Cloned so the color closure does not borrow `state` while panels mutate it
+        let color_overrides = state.type_legend.color_overrides().clone();
 
         // Header panel at the top
         egui::TopBottomPanel::top("header").show(ctx, |ui| {
             if let Some(header_interaction) = header::render_header(ui, state) {
                 interaction = Some(match header_interaction {
-                    header::HeaderInteraction::OpenFileRequested(path) => {
-                        PanelInteraction::OpenFileRequested(path)
+                    header::HeaderInteraction::OpenFilesRequested(paths) => {
+                        PanelInteraction::OpenFilesRequested(paths)
                     }
+                    header::HeaderInteraction::ReloadRequested => PanelInteraction::ReloadRequested,
                     header::HeaderInteraction::OpenVirtualTraceRequested => {
                         PanelInteraction::OpenVirtualTraceRequested
                     }
+                    header::HeaderInteraction::ExportReport { scope, path } => {
+                        PanelInteraction::ExportReportRequested { scope, path }
+                    }
+                    header::HeaderInteraction::SaveSessionPack(path) => {
+                        PanelInteraction::SaveSessionPackRequested(path)
+                    }
+                    header::HeaderInteraction::ExportTimeSlice(path) => {
+                        PanelInteraction::ExportTimeSliceRequested(path)
+                    }
+                    header::HeaderInteraction::ExportJets(path) => {
+                        PanelInteraction::ExportJetsRequested(path)
+                    }
+                    header::HeaderInteraction::ExportEventGrid { record_type, path } => {
+                        PanelInteraction::ExportEventGridRequested { record_type, path }
+                    }
                 });
             }
         });
 
+        // Chip row listing active filters, only while any is active
+        let active_filters = filter_chips::active_filters(state);
+        if !active_filters.is_empty() {
+            let hidden_selection = filter_chips::hidden_selection(state);
+            egui::TopBottomPanel::top("filter_chips").show(ctx, |ui| {
+                filter_chips::render_filter_chips(ui, state, &active_filters, &theme_colors);
+                if !hidden_selection.is_empty()
+                    && filter_chips::render_hidden_selection_banner(ui, &hidden_selection, &theme_colors)
+                {
+                    interaction = Some(PanelInteraction::RevealSelectionRequested);
+                }
+            });
+        }
+
+        // Floating record type legend
+        type_legend_panel::render_type_legend(ctx, state, &theme_colors);
+        event_filter_window::render_event_filter_window(ctx, state, &theme_colors);
+        match idle_gaps_window::render_idle_gaps_window(ctx, state, &theme_colors) {
+            Some(idle_gaps_window::IdleGapsInteraction::SearchRequested) => {
+                interaction = Some(PanelInteraction::IdleGapSearchRequested);
+            }
+            Some(idle_gaps_window::IdleGapsInteraction::ExportReport(path)) => {
+                interaction = Some(PanelInteraction::ExportReportRequested {
+                    scope: header::ReportScope::IdleGaps,
+                    path,
+                });
+            }
+            None => {}
+        }
+        match trace_health_window::render_trace_health_window(ctx, state, &theme_colors) {
+            Some(trace_health_window::TraceHealthInteraction::CheckRequested) => {
+                interaction = Some(PanelInteraction::TraceHealthCheckRequested);
+            }
+            Some(trace_health_window::TraceHealthInteraction::RecordClicked(record_id)) => {
+                interaction = Some(PanelInteraction::RecordRevealRequested(record_id));
+            }
+            Some(trace_health_window::TraceHealthInteraction::ExportReport(path)) => {
+                interaction = Some(PanelInteraction::ExportReportRequested {
+                    scope: header::ReportScope::TraceHealth,
+                    path,
+                });
+            }
+            None => {}
+        }
+        match type_hierarchy_window::render_type_hierarchy_window(ctx, state, &theme_colors) {
+            Some(type_hierarchy_window::TypeHierarchyInteraction::BuildRequested) => {
+                interaction = Some(PanelInteraction::TypeHierarchyRequested);
+            }
+            Some(type_hierarchy_window::TypeHierarchyInteraction::ExportReport(path)) => {
+                interaction = Some(PanelInteraction::ExportReportRequested {
+                    scope: header::ReportScope::TypeHierarchy,
+                    path,
+                });
+            }
+            None => {}
+        }
+        if let Some(dialog_interaction) = settings_dialog::render_settings_dialog(ctx, state) {
+            interaction = Some(match dialog_interaction {
+                settings_dialog::SettingsDialogInteraction::ExportProfile(path) => {
+                    PanelInteraction::ExportSettingsProfileRequested(path)
+                }
+                settings_dialog::SettingsDialogInteraction::ImportProfile(path) => {
+                    PanelInteraction::ImportSettingsProfileRequested(path)
+                }
+            });
+        }
+        tooltip_renderer::render_pinned_tooltip(ctx, &mut state.tooltip, &theme_colors);
+
         // Status panel at the very bottom
         egui::TopBottomPanel::bottom("status_panel").show(ctx, |ui| {
             status_bar::render_status_bar(ui, state);
         });
 
-        // Details panel above status panel
-        egui::TopBottomPanel::bottom("details_panel")
-            .default_height(ctx.content_rect().height() * (1.0 - state.layout.split_ratio()))
-            .resizable(true)
-            .show(ctx, |ui| {
-                egui::Frame::default().inner_margin(4.0).show(ui, |ui| {
-                    details_panel::render_details_panel(ui, state, &theme_colors);
-                });
-            });
-
-        // Left panel: Tree
-        let tree_frame = egui::Frame::default()
-            .inner_margin(egui::Margin::same(4))
-            .fill(ctx.style().visuals.panel_fill);
-
-        egui::SidePanel::left("tree_panel")
-            .default_width(ctx.content_rect().width() * state.layout.timeline_split_ratio())
-            .resizable(true)
-            .frame(tree_frame)
-            .show(ctx, |ui| {
-                ui.heading("Trace Records");
-                ui.separator();
-
-                if let Some(tree_interaction) = tree_panel::render_tree_panel(ui, state, &theme_colors) {
-                    interaction = Some(match tree_interaction {
-                        tree_panel::TreePanelInteraction::NodeSelected {
-                            record_id,
-                            was_already_selected,
-                            first_event_clk,
-                        } => PanelInteraction::TreeNodeSelected {
-                            record_id,
-                            was_already_selected,
-                            first_event_clk,
-                        },
-                        tree_panel::TreePanelInteraction::NodeExpandToggled {
-                            record_id,
-                            was_expanded,
-                        } => PanelInteraction::TreeNodeExpandToggled {
-                            record_id,
-                            was_expanded,
-                        },
-                        tree_panel::TreePanelInteraction::SortRequested(spec) => {
-                            PanelInteraction::TreeSortRequested(spec)
-                        },
-                    });
+        // Docked panels, slot by slot (the central panel must come last)
+        let layout = state.dock.layout().clone();
+        let bottom_height = ctx.content_rect().height() * (1.0 - state.layout.split_ratio());
+        let left_width = ctx.content_rect().width() * state.layout.timeline_split_ratio();
+        for slot in DockSlot::ALL {
+            if layout.panels(slot).is_empty() && slot != DockSlot::Center {
+                continue;
+            }
+            let frame = egui::Frame::default()
+                .inner_margin(egui::Margin::same(4))
+                .fill(ctx.style().visuals.panel_fill);
+            let mut render = |ui: &mut egui::Ui| {
+                if let Some(slot_interaction) =
+                    Self::render_slot(ui, ctx, state, loader, &layout, slot, &theme_colors, &color_overrides)
+                {
+                    interaction = Some(slot_interaction);
                 }
+            };
+            match slot {
+                DockSlot::Bottom => {
+                    egui::TopBottomPanel::bottom("dock_bottom")
+                        .default_height(bottom_height)
+                        .resizable(true)
+                        .frame(frame)
+                        .show(ctx, |ui| render(ui));
+                }
+                DockSlot::Left => {
+                    egui::SidePanel::left("dock_left")
+                        .default_width(left_width)
+                        .resizable(true)
+                        .frame(frame)
+                        .show(ctx, |ui| render(ui));
+                }
+                DockSlot::Right => {
+                    egui::SidePanel::right("dock_right")
+                        .default_width(ctx.content_rect().width() * RIGHT_SLOT_WIDTH_RATIO)
+                        .resizable(true)
+                        .frame(frame)
+                        .show(ctx, |ui| render(ui));
+                }
+                DockSlot::Center => {
+                    egui::CentralPanel::default().frame(frame).show(ctx, |ui| render(ui));
+                }
+            }
+        }
+
+        interaction
+    }
+    /// Renders the tab strip of a slot and its active panel.
+    ///
+    /// A tab's context menu moves the panel to another slot or closes it.
+    #[allow(clippy::too_many_arguments)]
+    fn render_slot(
+        ui: &mut egui::Ui,
+        ctx: &egui::Context,
+        state: &mut AppState,
+        loader: &AsyncLoader,
+        layout: &DockLayout,
+        slot: DockSlot,
+        theme_colors: &ThemeColors,
+        color_overrides: &HashMap<String, Color32>,
+    ) -> Option<PanelInteraction> {
+        let Some(active) = layout.active_panel(slot) else {
+            ui.centered_and_justified(|ui| {
+                ui.label(RichText::new("No panel docked here (View ▸ Layout)").color(theme_colors.text_dim));
             });
+            return None;
+        };
 
-        // Right panel: Timeline
-        let timeline_frame = egui::Frame::default()
-            .inner_margin(egui::Margin::same(4))
-            .fill(ctx.style().visuals.panel_fill);
+        ui.horizontal(|ui| {
+            for &panel in layout.panels(slot) {
+                // Tabs take keyboard focus so F6 can land on the panel
+                let tab = if layout.panels(slot).len() == 1 {
+                    ui.add(egui::Label::new(RichText::new(panel.title()).heading()).sense(egui::Sense::click()))
+                } else {
+                    ui.selectable_label(panel == active, RichText::new(panel.title()).heading())
+                };
+                tab.widget_info(|| {
+                    egui::WidgetInfo::selected(egui::WidgetType::SelectableLabel, true, panel == active, panel.title())
+                });
+                if state.focus.take_focus_request(panel) {
+                    tab.request_focus();
+                }
+                if tab.has_focus() {
+                    state.focus.set_focused_panel(panel);
+                }
+                if tab.clicked() {
+                    state.dock.layout_mut().activate(panel);
+                }
+                tab.context_menu(|ui| {
+                    for target in DockSlot::ALL.into_iter().filter(|&target| target != slot) {
+                        if ui.button(format!("Move to {}", target.label())).clicked() {
+                            state.dock.layout_mut().move_panel(panel, target);
+                            ui.close();
+                        }
+                    }
+                    ui.separator();
+                    if ui.button("Close").clicked() {
+                        state.dock.layout_mut().close_panel(panel);
+                        ui.close();
+                    }
+                });
+            }
+        });
+        ui.separator();
 
-        egui::CentralPanel::default()
-            .frame(timeline_frame)
-            .show(ctx, |ui| {
-                ui.heading("Timeline View");
-                ui.separator();
+        Self::render_dock_panel(ui, ctx, state, loader, active, theme_colors, color_overrides)
+    }
 
-                // Create color mapping closure
-                let get_record_color = |name: &str| -> Color32 {
-                    color_mapping::get_record_color(name, &theme_colors)
+    /// Renders one docked panel and maps its interaction.
+    fn render_dock_panel(
+        ui: &mut egui::Ui,
+        ctx: &egui::Context,
+        state: &mut AppState,
+        loader: &AsyncLoader,
+        panel: DockPanel,
+        theme_colors: &ThemeColors,
+        color_overrides: &HashMap<String, Color32>,
+    ) -> Option<PanelInteraction> {
+        match panel {
+            DockPanel::Details => {
+                details_panel::render_details_panel(ui, state, theme_colors).map(|interaction| match interaction {
+                    details_panel::DetailsInteraction::RecordLinkClicked(record_id) => {
+                        PanelInteraction::RecordRevealRequested(record_id)
+                    }
+                })
+            }
+            DockPanel::Statistics => {
+                statistics_panel::render_statistics_panel(ui, state, theme_colors);
+                None
+            }
+            DockPanel::Log => {
+                log_panel::render_log_panel(ui, state, theme_colors);
+                None
+            }
+            DockPanel::Tree => {
+                tree_panel::render_tree_panel(ui, state, theme_colors).map(|tree_interaction| match tree_interaction {
+                    tree_panel::TreePanelInteraction::NodeSelected {
+                        record_id,
+                        was_already_selected,
+                        first_event_clk,
+                    } => PanelInteraction::TreeNodeSelected {
+                        record_id,
+                        was_already_selected,
+                        first_event_clk,
+                    },
+                    tree_panel::TreePanelInteraction::NodeRangeSelected { record_id } => {
+                        PanelInteraction::TreeNodeRangeSelected { record_id }
+                    },
+                    tree_panel::TreePanelInteraction::NodeExpandToggled {
+                        record_id,
+                        was_expanded,
+                    } => PanelInteraction::TreeNodeExpandToggled {
+                        record_id,
+                        was_expanded,
+                    },
+                    tree_panel::TreePanelInteraction::NodeGesture { record_id, gesture } => {
+                        PanelInteraction::RecordGesture { record_id, gesture }
+                    },
+                    tree_panel::TreePanelInteraction::ProblemRevealRequested { record_id, event_clk } => {
+                        PanelInteraction::EventRevealRequested { record_id, event_clk }
+                    },
+                    tree_panel::TreePanelInteraction::NavigationRequested { record_id, scope, forward } => {
+                        PanelInteraction::RecordNavigationRequested { record_id, scope, forward }
+                    },
+                    tree_panel::TreePanelInteraction::SortRequested(spec) => {
+                        PanelInteraction::TreeSortRequested(spec)
+                    },
+                    tree_panel::TreePanelInteraction::AttributeSortRequested(attribute) => {
+                        PanelInteraction::AttributeSortRequested(attribute)
+                    },
+                    tree_panel::TreePanelInteraction::SwimlaneToggled(key) => {
+                        PanelInteraction::SwimlaneToggled(key)
+                    },
+                })
+            }
+            DockPanel::Timeline => {
+                // Create color mapping closure (hints cloned for the same reason as the overrides)
+                let type_hints = state.type_legend.trace_hints().clone();
+                let get_record_color = |name: &str, record_type: &str| -> Color32 {
+                    color_mapping::record_color(name, record_type, color_overrides, &type_hints, theme_colors)
                 };
 
-                if let Some(timeline_interaction) = timeline_panel::render_timeline_panel(
-                    ui,
-                    ctx,
-                    state,
-                    loader,
-                    &theme_colors,
-                    get_record_color,
-                ) {
-                    interaction = Some(match timeline_interaction {
+                timeline_panel::render_timeline_panel(ui, ctx, state, loader, theme_colors, get_record_color).map(
+                    |timeline_interaction| match timeline_interaction {
                         timeline_panel::TimelinePanelInteraction::BarClicked {
                             record_id,
                             was_already_selected,
@@ -160,6 +430,9 @@ impl PanelManager {
                             was_already_selected,
                             first_event_clk,
                         },
+                        timeline_panel::TimelinePanelInteraction::BarGesture { record_id, gesture } => {
+                            PanelInteraction::RecordGesture { record_id, gesture }
+                        },
                         timeline_panel::TimelinePanelInteraction::EventClicked {
                             record_id,
                             event_clk,
@@ -167,10 +440,31 @@ impl PanelManager {
                             record_id,
                             event_clk,
                         },
-                    });
-                }
-            });
-
-        interaction
+                        timeline_panel::TimelinePanelInteraction::LaneToggled {
+                            record_id,
+                            was_expanded,
+                        } => PanelInteraction::TreeNodeExpandToggled {
+                            record_id,
+                            was_expanded,
+                        },
+                        timeline_panel::TimelinePanelInteraction::SwimlaneToggled(key) => {
+                            PanelInteraction::SwimlaneToggled(key)
+                        },
+                        timeline_panel::TimelinePanelInteraction::MeasureCursorPlaced { clk } => {
+                            PanelInteraction::MeasureCursorPlaced { clk }
+                        },
+                        timeline_panel::TimelinePanelInteraction::LoadCancelRequested => {
+                            PanelInteraction::LoadCancelRequested
+                        },
+                        timeline_panel::TimelinePanelInteraction::FlameGraphRequested { root_id } => {
+                            PanelInteraction::FlameGraphRequested { root_id }
+                        },
+                        timeline_panel::TimelinePanelInteraction::ExportFlameGraphReport(path) => {
+                            PanelInteraction::ExportReportRequested { scope: header::ReportScope::FlameGraph, path }
+                        },
+                    },
+                )
+            }
+        }
     }
 }
@@ -0,0 +1,345 @@
+//! Settings dialog
+//!
+//! Floating window for user preferences that do not warrant a header control.
+//! Currently holds the interaction section: what double-click and Ctrl+click do
+//! on a record, wheel orientation, wheel zoom sensitivity, what the
+//! vertical extent of a Ctrl+drag region selection does, and record tooltip
+//! delay and verbosity, followed by timeline and loading options (including
+//! the tree expansion applied to newly opened traces), the number format of
+//! the numeric tree columns and the tree and details text size, and the
+//! record name alias rules with a live preview. Its footer exports and imports settings profiles.
+
+use eframe::egui;
+use rjets::{TraceData, TraceRecord};
+use std::path::PathBuf;
+
+use crate::app::AppState;
+use crate::domain::column_filters::TreeColumn;
+use crate::domain::name_aliases::{AliasRule, NameAliases};
+use crate::domain::number_format::NumberFormat;
+use crate::domain::type_hints::TypeHints;
+use crate::io::settings_profile;
+use crate::state::{
+    ClickAction, ExpansionPolicy, InputSettings, RegionRowMode, INITIAL_EXPAND_DEPTH_RANGE, TooltipVerbosity, WheelAction, WheelOrientation, TOOLTIP_DELAY_RANGE_MS,
+    DEFAULT_TREE_FONT_SIZE, TREE_FONT_SIZE_RANGE, ZOOM_SENSITIVITY_RANGE,
+};
+
+/// Result of settings dialog interactions that need file I/O.
+pub enum SettingsDialogInteraction {
+    /// User chose a file to export the settings profile to
+    ExportProfile(PathBuf),
+    /// User chose a settings profile to import
+    ImportProfile(PathBuf),
+}
+
+/// Renders the settings dialog if it is open.
+///
+/// Changes apply immediately; they are persisted with the other settings.
+///
+/// # Arguments
+/// * `ctx` - The egui context the window is shown in
+/// * `state` - Mutable reference to application state
+///
+/// # Returns
+/// * `Option<SettingsDialogInteraction>` - Profile export or import request
+pub fn render_settings_dialog(ctx: &egui::Context, state: &mut AppState) -> Option<SettingsDialogInteraction> {
+    let mut open = state.input_settings.is_dialog_open();
+    if !open {
+        return None;
+    }
+    let mut interaction = None;
+
+    egui::Window::new("⚙ Settings")
+        .open(&mut open)
+        .resizable(false)
+        .collapsible(false)
+        .show(ctx, |ui| {
+            ui.heading("Interaction");
+            ui.separator();
+
+            let settings = state.input_settings.settings_mut();
+            egui::Grid::new("interaction_settings_grid")
+                .num_columns(2)
+                .spacing([12.0, 6.0])
+                .show(ui, |ui| {
+                    ui.label("Double-click on record:");
+                    click_action_combo(ui, "double_click_action", &mut settings.double_click);
+                    ui.end_row();
+
+                    ui.label("Ctrl+click on record:");
+                    click_action_combo(ui, "ctrl_click_action", &mut settings.ctrl_click);
+                    ui.end_row();
+
+                    ui.label("Wheel orientation:");
+                    ui.horizontal(|ui| {
+                        ui.radio_value(&mut settings.wheel_orientation, WheelOrientation::Standard, "Standard");
+                        ui.radio_value(&mut settings.wheel_orientation, WheelOrientation::Inverted, "Inverted");
+                    });
+                    ui.end_row();
+
+                    ui.label("Timeline wheel:");
+                    ui.horizontal(|ui| {
+                        for option in WheelAction::ALL {
+                            ui.radio_value(&mut settings.plain_wheel, option, option.label());
+                        }
+                    })
+                    .response
+                    .on_hover_text("Shift+wheel does the other; over the tree, Shift+wheel pans time");
+                    ui.end_row();
+
+                    ui.label("Zoom sensitivity:");
+                    ui.add(
+                        egui::Slider::new(&mut settings.zoom_sensitivity, ZOOM_SENSITIVITY_RANGE)
+                            .logarithmic(true)
+                            .suffix("×"),
+                    )
+                    .on_hover_text("Speed of Ctrl+wheel zoom in the timeline and the event timeline");
+                    ui.end_row();
+
+                    ui.label("Ctrl+drag region:");
+                    egui::ComboBox::from_id_salt("region_row_mode")
+                        .selected_text(settings.region_rows.label())
+                        .show_ui(ui, |ui| {
+                            for option in RegionRowMode::ALL {
+                                ui.selectable_value(&mut settings.region_rows, option, option.label());
+                            }
+                        })
+                        .response
+                        .on_hover_text("Whether the rows covered by the selection are scrolled to or pinned");
+                    ui.end_row();
+
+                    ui.label("Tooltip delay:");
+                    ui.add(egui::Slider::new(&mut settings.tooltip_delay_ms, TOOLTIP_DELAY_RANGE_MS).suffix(" ms"))
+                        .on_hover_text("Hover time before a record tooltip appears; press Space on a tooltip to pin it");
+                    ui.end_row();
+
+                    ui.label("Tooltip details:");
+                    ui.horizontal(|ui| {
+                        ui.radio_value(&mut settings.tooltip_verbosity, TooltipVerbosity::Minimal, "Minimal");
+                        ui.radio_value(&mut settings.tooltip_verbosity, TooltipVerbosity::Full, "All attributes");
+                    });
+                    ui.end_row();
+                });
+
+            ui.add_space(8.0);
+            ui.heading("Timeline");
+            ui.separator();
+            ui.checkbox(&mut settings.bottom_axis, "Time axis at the bottom too")
+                .on_hover_text("Repeat the time axis below the timeline, handy on tall windows");
+            ui.checkbox(&mut settings.gridlines, "Gridlines at major ticks");
+            ui.checkbox(&mut settings.capture_bounds, "Trace extent and capture end markers");
+            ui.checkbox(&mut settings.fit_on_expand, "Fit to expanded records with children out of view")
+                .on_hover_text(
+                    "Expanding a tree row whose children all lie outside the visible time range zooms to the \
+                     record and its children",
+                );
+
+            ui.add_space(8.0);
+            ui.heading("Loading");
+            ui.separator();
+            ui.checkbox(&mut settings.infer_record_ends, "Infer missing record ends")
+                .on_hover_text(
+                    "Records without a record_end line end where their next sibling starts, or with their \
+                     parent. Applies to traces opened afterwards.",
+                );
+            ui.horizontal(|ui| {
+                ui.label("On open:");
+                egui::ComboBox::from_id_salt("initial_expansion")
+                    .selected_text(settings.initial_expansion.label())
+                    .show_ui(ui, |ui| {
+                        for option in ExpansionPolicy::ALL {
+                            ui.selectable_value(&mut settings.initial_expansion, option, option.label());
+                        }
+                    })
+                    .response
+                    .on_hover_text("Tree rows expanded after a trace is opened (a reload keeps the current expansion)");
+                if settings.initial_expansion == ExpansionPolicy::ExpandToDepth {
+                    ui.add(egui::DragValue::new(&mut settings.initial_expand_depth).range(INITIAL_EXPAND_DEPTH_RANGE))
+                        .on_hover_text("Levels below the roots to show");
+                }
+            });
+            let hints_toggled = ui
+                .checkbox(&mut settings.type_hints, "Use type hints from the trace header")
+                .on_hover_text(
+                    "Producers can suggest per record type whether it starts collapsed, its color, its bar \
+                     label and how its events are drawn. Colors chosen in the type legend still win.",
+                )
+                .changed();
+            if hints_toggled {
+                let hints = TypeHints::for_trace(state.trace.trace_data(), settings.type_hints);
+                state.type_legend.set_trace_hints(hints);
+            }
+            let hints = state.type_legend.trace_hints();
+            if !hints.is_empty() {
+                ui.weak(format!("This trace has hints for {} record types", hints.len()));
+            }
+
+            ui.add_space(8.0);
+            ui.heading("Columns");
+            ui.separator();
+            let formats = &mut state.input_settings.settings_mut().column_formats;
+            egui::Grid::new("column_format_grid")
+                .num_columns(2)
+                .spacing([12.0, 6.0])
+                .show(ui, |ui| {
+                    for column in TreeColumn::ALL {
+                        let Some(format) = formats.get_mut(column) else { continue };
+                        ui.label(format!("{}:", column.title()));
+                        egui::ComboBox::from_id_salt(("column_format", column.title()))
+                            .selected_text(format.label())
+                            .show_ui(ui, |ui| {
+                                for option in NumberFormat::ALL {
+                                    ui.selectable_value(format, option, option.label());
+                                }
+                            })
+                            .response
+                            .on_hover_text("Also used in the details panel, record tooltips and exported reports");
+                        ui.end_row();
+                    }
+                });
+            let settings = state.input_settings.settings_mut();
+            ui.checkbox(&mut settings.duration_share, "Show duration as a share of the parent")
+                .on_hover_text(
+                    "Append each record's share of its parent's duration to the Duration column (roots: of the \
+                     whole trace); the details panel shows both",
+                );
+            ui.horizontal(|ui| {
+                ui.label("Text size:");
+                ui.add(egui::Slider::new(&mut settings.tree_font_size, TREE_FONT_SIZE_RANGE).step_by(0.5).suffix(" pt"))
+                    .on_hover_text(
+                        "Size of the tree and details text; rows keep the timeline row height, so long names \
+                         trade density for readability",
+                    );
+                if ui.small_button("Reset").clicked() {
+                    settings.tree_font_size = DEFAULT_TREE_FONT_SIZE;
+                }
+            });
+
+            ui.add_space(8.0);
+            ui.heading("Name Aliases");
+            ui.separator();
+            render_name_aliases(ui, state);
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("Restore Defaults").clicked() {
+                    state.input_settings.set_settings(InputSettings::default());
+                }
+                if ui
+                    .button("Export settings profile…")
+                    .on_hover_text("Save theme, column layout, record type colors and these preferences to a file")
+                    .clicked()
+                {
+                    if let Some(path) = pick_profile_export_path() {
+                        interaction = Some(SettingsDialogInteraction::ExportProfile(path));
+                    }
+                }
+                if ui.button("Import profile…").clicked() {
+                    if let Some(path) = pick_profile_import_path() {
+                        interaction = Some(SettingsDialogInteraction::ImportProfile(path));
+                    }
+                }
+            });
+        });
+
+    state.input_settings.set_dialog_open(open);
+    interaction
+}
+
+/// Asks for the destination of a settings profile export.
+pub fn pick_profile_export_path() -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("Settings Profiles", &[settings_profile::EXTENSION])
+        .set_file_name("jets-profile.json")
+        .save_file()
+}
+
+/// Asks for a settings profile to import.
+pub fn pick_profile_import_path() -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("Settings Profiles", &[settings_profile::EXTENSION])
+        .pick_file()
+}
+
+
+/// Renders the alias rule editor and a preview of its effect.
+///
+/// The preview rewrites the text typed into it, or the selected record's
+/// name while that is empty.
+fn render_name_aliases(ui: &mut egui::Ui, state: &mut AppState) {
+    let settings = state.input_settings.settings();
+    let mut rules = settings.name_aliases.rules().to_vec();
+    let mut remove = None;
+
+    egui::Grid::new("name_alias_grid")
+        .num_columns(4)
+        .spacing([6.0, 4.0])
+        .show(ui, |ui| {
+            for (index, rule) in rules.iter_mut().enumerate() {
+                ui.checkbox(&mut rule.enabled, "");
+                let error = settings.name_aliases.rule_error(index);
+                let pattern = ui.add(
+                    egui::TextEdit::singleline(&mut rule.pattern)
+                        .hint_text("Regex")
+                        .desired_width(180.0)
+                        .text_color_opt(error.map(|_| ui.visuals().error_fg_color)),
+                );
+                if let Some(error) = error {
+                    pattern.on_hover_text(error);
+                }
+                ui.add(
+                    egui::TextEdit::singleline(&mut rule.replacement)
+                        .hint_text("Replacement ($1 = group)")
+                        .desired_width(140.0),
+                );
+                if ui.small_button("✕").on_hover_text("Remove rule").clicked() {
+                    remove = Some(index);
+                }
+                ui.end_row();
+            }
+        });
+    if let Some(index) = remove {
+        rules.remove(index);
+    }
+    if ui.button("➕ Add rule").clicked() {
+        rules.push(AliasRule::default());
+    }
+
+    let selected_name = state
+        .selection
+        .selected_record_id()
+        .and_then(|id| state.trace.trace_data()?.get_record(id))
+        .map(|record| record.name())
+        .unwrap_or_default();
+    let settings = state.input_settings.settings_mut();
+    if rules != settings.name_aliases.rules() {
+        settings.name_aliases = NameAliases::new(rules);
+    }
+    ui.checkbox(&mut settings.aliases_in_exports, "Apply in exported reports")
+        .on_hover_text("Rewrite record names in statistics reports and session pack reports");
+
+    let preview_text = state.input_settings.alias_preview_mut();
+    ui.horizontal(|ui| {
+        ui.label("Preview:");
+        ui.add(
+            egui::TextEdit::singleline(preview_text)
+                .hint_text(if selected_name.is_empty() { "Record name" } else { selected_name.as_str() })
+                .desired_width(180.0),
+        );
+    });
+    let sample = if preview_text.is_empty() { selected_name } else { preview_text.clone() };
+    if !sample.is_empty() {
+        let shown = state.input_settings.settings().display_name(&sample).into_owned();
+        ui.label(format!("→ {}", shown));
+    }
+}
+/// Renders a combo box choosing a click action.
+fn click_action_combo(ui: &mut egui::Ui, id: &str, action: &mut ClickAction) {
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(action.label())
+        .show_ui(ui, |ui| {
+            for option in ClickAction::ALL {
+                ui.selectable_value(action, option, option.label());
+            }
+        });
+}
@@ -0,0 +1,132 @@
+//! Statistics panel
+//!
+//! Shows the whole-trace summary computed in the background after a load:
+//! record count, tree depth, event severity counts, an activity profile of
+//! leaf records over time and a per-record_type breakdown sorted by total
+//! duration.
+
+use eframe::egui;
+use egui::RichText;
+use rjets::ThemeColors;
+
+use crate::app::AppState;
+use crate::domain::aggregation::TimeBuckets;
+use crate::domain::severity::Severity;
+use crate::presentation::color_mapping;
+use crate::utils::format_clock;
+
+/// Renders the statistics panel contents.
+///
+/// # Arguments
+/// * `ui` - The egui UI context for drawing
+/// * `state` - Reference to application state
+/// * `theme_colors` - Color palette for the current theme
+pub fn render_statistics_panel(ui: &mut egui::Ui, state: &AppState, theme_colors: &ThemeColors) {
+    let Some(summary) = &state.tree_cache.trace_summary else {
+        let message = if state.trace.trace_data().is_some() { "Indexing…" } else { "No trace loaded" };
+        ui.label(RichText::new(message).color(theme_colors.text_dim));
+        return;
+    };
+
+    ui.horizontal(|ui| {
+        ui.label(RichText::new(format!("Records: {}", summary.record_count)).strong());
+        ui.separator();
+        ui.label(RichText::new(format!("Max depth: {}", summary.max_depth)).strong());
+        for severity in Severity::ALL.into_iter().rev() {
+            let count = summary.severity_counts.get(&severity).copied().unwrap_or(0);
+            let label = match severity {
+                Severity::Error => "Errors",
+                Severity::Warning => "Warnings",
+                Severity::Info => "Info events",
+            };
+            ui.separator();
+            ui.label(
+                RichText::new(format!("{}: {}", label, count))
+                    .color(color_mapping::severity_color(severity, theme_colors)),
+            );
+        }
+    });
+    if !summary.activity.is_empty() {
+        ui.label(RichText::new("Active leaves").color(theme_colors.text_dim));
+        render_bucket_strip(ui, &summary.activity, theme_colors.blue, theme_colors, |buckets, index| {
+            format!("Average active leaves: {:.2}", buckets.sums[index] / buckets.bucket_width())
+        });
+        ui.label(RichText::new("Record starts").color(theme_colors.text_dim));
+        render_bucket_strip(ui, &summary.starts, theme_colors.green, theme_colors, |buckets, index| {
+            format!("Records started: {}", buckets.counts[index])
+        });
+    }
+    ui.separator();
+
+    let mut types: Vec<_> = summary.type_stats.iter().collect();
+    types.sort_by(|a, b| b.1.total_duration.cmp(&a.1.total_duration).then_with(|| a.0.cmp(b.0)));
+
+    egui::ScrollArea::vertical()
+        .id_salt("statistics_scroll_area")
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            egui::Grid::new("statistics_type_grid")
+                .num_columns(3)
+                .striped(true)
+                .spacing([16.0, 4.0])
+                .show(ui, |ui| {
+                    ui.label(RichText::new("Type").strong());
+                    ui.label(RichText::new("Count").strong());
+                    ui.label(RichText::new("Total Duration").strong());
+                    ui.end_row();
+
+                    for (record_type, stats) in types {
+                        ui.label(record_type);
+                        ui.label(stats.count.to_string());
+                        ui.label(format_clock(stats.total_duration));
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+/// Height of an activity strip in pixels.
+const ACTIVITY_STRIP_HEIGHT: f32 = 32.0;
+
+/// Draws bucket sums as a bar per bucket, scaled to the largest.
+///
+/// `describe` returns the hover text line for a bucket.
+fn render_bucket_strip(
+    ui: &mut egui::Ui,
+    activity: &TimeBuckets,
+    bar_color: egui::Color32,
+    theme_colors: &ThemeColors,
+    describe: impl Fn(&TimeBuckets, usize) -> String,
+) {
+    let (rect, response) = ui.allocate_exact_size(
+        egui::vec2(ui.available_width(), ACTIVITY_STRIP_HEIGHT),
+        egui::Sense::hover(),
+    );
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 0.0, theme_colors.extreme_background);
+
+    let max_sum = activity.max_sum();
+    let bar_width = rect.width() / activity.len() as f32;
+    if max_sum > 0.0 {
+        for (index, &sum) in activity.sums.iter().enumerate() {
+            let height = (sum / max_sum) as f32 * rect.height();
+            let left = rect.left() + index as f32 * bar_width;
+            let bar = egui::Rect::from_min_max(
+                egui::pos2(left, rect.bottom() - height),
+                egui::pos2(left + bar_width.max(1.0), rect.bottom()),
+            );
+            painter.rect_filled(bar, 0.0, bar_color);
+        }
+    }
+
+    if let Some(pos) = response.hover_pos() {
+        let index = (((pos.x - rect.left()) / bar_width) as usize).min(activity.len() - 1);
+        let (start, end) = activity.bucket_range(index);
+        response.on_hover_text(format!(
+            "{} – {}\n{}",
+            format_clock(start),
+            format_clock(end),
+            describe(activity, index)
+        ));
+    }
+}
@@ -1,12 +1,15 @@
 //! Status bar UI rendering
 //!
-//! Handles the bottom status bar displaying trace metadata.
+//! Handles the bottom status bar displaying trace metadata and the clock
+//! under the timeline cursor with the event it snaps to.
 
 use eframe::egui;
 use egui::RichText;
 use crate::app::AppState;
+use crate::domain::severity::Severity;
+use crate::presentation::color_mapping;
 use crate::utils::{format_clock, get_current_memory_mb, format_memory_mb};
-use rjets::{TraceData, TraceMetadata};
+use rjets::{TraceData, TraceEvent, TraceMetadata, TraceRecord, Verification};
 
 /// Renders the status panel at the bottom of the window with trace metadata
 ///
@@ -25,12 +28,16 @@ pub fn render_status_bar(ui: &mut egui::Ui, state: &AppState) {
             let metadata = trace.metadata();
             let (min_clk, max_clk) = metadata.trace_extent();
             let time_range = format!("{}..{}", format_clock(min_clk), format_clock(max_clk));
-            let total_records = metadata.total_records().map(|n| n.to_string()).unwrap_or_else(|| "?".to_string());
+            let total_records = metadata
+                .total_records()
+                .or_else(|| state.tree_cache.trace_summary.as_ref().map(|s| s.record_count))
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| "?".to_string());
             let total_events = metadata.total_events().map(|n| n.to_string()).unwrap_or_else(|| "?".to_string());
 
             if state.trace.file_path().is_none() {
                 // Virtual trace metadata
-                let num_roots = trace.root_ids().len();
+                let num_roots = trace.roots().len();
                 ui.label(RichText::new(format!(
                     "Virtual Trace | Seed: 42 | Roots: {} | Time: {} | Records: {} | Events: {}",
                     num_roots, time_range, total_records, total_events
@@ -65,6 +72,100 @@ pub fn render_status_bar(ui: &mut egui::Ui, state: &AppState) {
                     filtered_count, total_count
                 )).strong().color(egui::Color32::YELLOW));
             }
+
+            // Clock under the timeline cursor and the nearest event of the hovered row
+            if let Some(hover_clk) = state.selection.hover_clk() {
+                ui.label(RichText::new("|").strong());
+                let mut readout = format!("Cursor: {}", format_clock(hover_clk));
+                let snapped = state.selection.cursor_snap().and_then(|snap| {
+                    let record = trace.get_record(snap.record_id)?;
+                    let event = record.event_at(snap.event_index)?;
+                    let name = state.input_settings.settings().display_name(&record.name()).into_owned();
+                    Some((name, event.name(), event.clk()))
+                });
+                if let Some((record_name, event_name, event_clk)) = snapped {
+                    let delta = event_clk as i128 - hover_clk as i128;
+                    let sign = if delta < 0 { "-" } else { "+" };
+                    readout.push_str(&format!(
+                        " | Nearest: {} @ {} ({}{}) on {}",
+                        event_name,
+                        format_clock(event_clk),
+                        sign,
+                        format_clock(i64::try_from(delta.unsigned_abs()).unwrap_or(i64::MAX)),
+                        record_name
+                    ));
+                }
+                ui.label(RichText::new(readout).strong())
+                    .on_hover_text("Alt+click on the timeline selects the nearest event");
+            }
+
+            // Whole-trace summary, filled in by the background precompute pass
+            ui.label(RichText::new("|").strong());
+            match &state.tree_cache.trace_summary {
+                Some(summary) => {
+                    let type_breakdown = summary
+                        .type_stats
+                        .iter()
+                        .map(|(record_type, stats)| format!("{}: {}", record_type, stats.count))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    ui.label(RichText::new(format!(
+                        "Depth: {} | Types: {}",
+                        summary.max_depth,
+                        summary.type_stats.len()
+                    )).strong())
+                    .on_hover_text(type_breakdown);
+
+                    let errors = summary.severity_counts.get(&Severity::Error).copied().unwrap_or(0);
+                    let warnings = summary.severity_counts.get(&Severity::Warning).copied().unwrap_or(0);
+                    let infos = summary.severity_counts.get(&Severity::Info).copied().unwrap_or(0);
+                    if errors + warnings > 0 {
+                        ui.label(RichText::new("|").strong());
+                        let colors = color_mapping::theme_colors(state.theme.theme_manager(), state.theme.current_theme_name());
+                        ui.label(RichText::new(format!("Errors: {}", errors)).strong().color(colors.red));
+                        ui.label(RichText::new(format!("Warnings: {}", warnings)).strong().color(colors.orange));
+                        ui.label(RichText::new(format!("Info: {}", infos)).strong());
+                    }
+                }
+                None => {
+                    ui.label(RichText::new("Indexing…").strong().color(ui.visuals().weak_text_color()));
+                }
+            }
+
+            // Non-fatal load problems (e.g. newer format version); details on hover
+            let warnings = metadata.warnings();
+            if !warnings.is_empty() {
+                ui.label(RichText::new("|").strong());
+                ui.label(RichText::new(format!("⚠ {} load warning(s)", warnings.len()))
+                    .strong()
+                    .color(ui.visuals().warn_fg_color))
+                    .on_hover_text(warnings.join("\n"));
+            }
+
+            // Content hash check, run in the background for traces with a provenance block
+            if let Some(provenance) = metadata.provenance() {
+                ui.label(RichText::new("|").strong());
+                let colors = color_mapping::theme_colors(state.theme.theme_manager(), state.theme.current_theme_name());
+                let details = format!("{} {}\nWritten by {}", provenance.algorithm, provenance.hash, provenance.producer);
+                match state.trace.verification() {
+                    None => {
+                        ui.label(RichText::new("Verifying…").strong().color(ui.visuals().weak_text_color()))
+                            .on_hover_text(details);
+                    }
+                    Some(Ok(Verification::Verified(_))) => {
+                        ui.label(RichText::new("✔ Verified").strong().color(colors.green))
+                            .on_hover_text(details);
+                    }
+                    Some(Ok(verification)) => {
+                        ui.label(RichText::new("✖ Not verified").strong().color(colors.red))
+                            .on_hover_text(format!("{}\n{}", verification, details));
+                    }
+                    Some(Err(e)) => {
+                        ui.label(RichText::new("⚠ Not verified").strong().color(ui.visuals().warn_fg_color))
+                            .on_hover_text(format!("Could not check the content hash: {}\n{}", e, details));
+                    }
+                }
+            }
         } else {
             ui.label(RichText::new("| No trace loaded").strong());
         }
@@ -1,16 +1,34 @@
 //! Table header component rendering
 //!
-//! Handles the rendering of resizable column headers for the tree view.
+//! Handles the rendering of resizable column headers for the tree view,
+//! including the per-column filter popovers and the sort menu (right-click),
+//! which also lists the custom sort keys.
+
+use std::collections::HashSet;
 
 use eframe::egui;
 use egui::Color32;
+use rjets::DynTraceData;
+use crate::domain::column_filters::{ColumnFilter, TreeColumn};
+use crate::domain::column_stats::STATS_COLUMNS;
+use crate::domain::sorting::SortKeyRegistry;
 use crate::rendering::text_utils::truncate_text_to_fit;
-use crate::state::{LayoutState, SortSpec, SortKey, SortDir};
+use crate::state::{ColumnFilterState, LayoutState, SortSpec, SortKey, SortDir};
+
+/// Width of the filter button at the right edge of each column header.
+const FILTER_BUTTON_WIDTH: f32 = 16.0;
 
 /// Interaction result from table header rendering.
 pub enum TableHeaderInteraction {
-    /// User clicked on a sortable column header.
+    /// User clicked on a sortable column header or picked a key in the sort menu.
     SortRequested(SortSpec),
+    /// User asked to sort by an attribute's value in the sort menu.
+    AttributeSortRequested(String),
+    /// User changed a column filter in its popover.
+    FiltersChanged,
+    /// Pointer rests on a header with quick stats; the caller shows them
+    /// in a tooltip for the response.
+    StatsHovered(TreeColumn, egui::Response),
 }
 
 /// Renders the resizable column headers for the tree view table
@@ -19,6 +37,9 @@ pub enum TableHeaderInteraction {
 /// * `ui` - The egui UI context for drawing
 /// * `layout` - Mutable reference to layout state containing expand_width and column_widths
 /// * `current_sort` - Currently active sort specification
+/// * `sort_keys` - Custom sort keys listed in the sort menu
+/// * `column_filter` - Column filters edited in the header popovers
+/// * `trace` - The loaded trace, for the value lists of text columns
 ///
 /// # Returns
 /// * `Option<TableHeaderInteraction>` - Interaction result (e.g., sort request)
@@ -26,8 +47,11 @@ pub fn render_table_header(
     ui: &mut egui::Ui,
     layout: &mut LayoutState,
     current_sort: Option<SortSpec>,
+    sort_keys: &SortKeyRegistry,
+    column_filter: &mut ColumnFilterState,
+    trace: Option<&DynTraceData>,
 ) -> Option<TableHeaderInteraction> {
-    let column_names = ["Name", "Description", "Start Clock", "Duration", "ID"];
+    let column_names = TreeColumn::ALL.map(TreeColumn::title);
 
     let mut x_offset = 0.0;
     let header_height = 24.0;
@@ -52,14 +76,29 @@ pub fn render_table_header(
         egui::vec2(expand_width, header_height),
     );
 
-    // Draw "Tree" label for the expand column
+    // Draw "Tree" label for the expand column, naming an active custom sort
+    let tree_label = match current_sort {
+        Some(SortSpec { key: SortKey::Custom(id), dir }) => format!(
+            "Tree: {}{}",
+            sort_keys.label(id).unwrap_or("custom"),
+            sort_arrow(dir)
+        ),
+        _ => "Tree".to_string(),
+    };
+    let tree_label = truncate_text_to_fit(&tree_label, expand_width - 8.0, &font_id, painter);
     painter.text(
         expand_label_rect.left_center() + egui::vec2(4.0, 0.0),
         egui::Align2::LEFT_CENTER,
-        "Tree",
+        tree_label,
         font_id.clone(),
         ui.visuals().strong_text_color(),
     );
+    let tree_label_response = ui.interact(expand_label_rect, ui.id().with("header_click_tree"), egui::Sense::click());
+    tree_label_response.context_menu(|ui| {
+        if let Some(menu_interaction) = render_sort_menu(ui, current_sort, sort_keys) {
+            interaction = Some(menu_interaction);
+        }
+    });
 
     x_offset += expand_width;
 
@@ -104,11 +143,15 @@ pub fn render_table_header(
         let sort_key = map_column_index_to_sort_key(i);
         let is_sortable = sort_key.is_some();
 
-        // Make header clickable if sortable
+        // Every header opens the sort menu; sortable ones also sort on click
+        let header_id = ui.id().with(format!("header_click_{}", i));
+        let header_response = ui.interact(label_rect, header_id, egui::Sense::click());
+        header_response.context_menu(|ui| {
+            if let Some(menu_interaction) = render_sort_menu(ui, current_sort, sort_keys) {
+                interaction = Some(menu_interaction);
+            }
+        });
         if is_sortable {
-            let header_id = ui.id().with(format!("header_click_{}", i));
-            let header_response = ui.interact(label_rect, header_id, egui::Sense::click());
-
             if header_response.clicked() {
                 if let Some(key) = sort_key {
                     let new_spec = toggle_sort_direction(current_sort, key);
@@ -119,6 +162,10 @@ pub fn render_table_header(
             // Highlight on hover
             if header_response.hovered() {
                 painter.rect_filled(label_rect, 0.0, Color32::from_white_alpha(10));
+                let stats_column = TreeColumn::from_index(i).filter(|column| STATS_COLUMNS.contains(column));
+                if let (Some(column), None) = (stats_column, &interaction) {
+                    interaction = Some(TableHeaderInteraction::StatsHovered(column, header_response));
+                }
             }
         }
 
@@ -130,14 +177,10 @@ pub fn render_table_header(
         // Build display text with sort indicator
         let mut display_text = name.to_string();
         if let Some(dir) = is_active_sort {
-            let arrow = match dir {
-                SortDir::Asc => " ▲",
-                SortDir::Desc => " ▼",
-            };
-            display_text.push_str(arrow);
+            display_text.push_str(sort_arrow(dir));
         }
 
-        let truncated_name = truncate_text_to_fit(&display_text, width, &font_id, painter);
+        let truncated_name = truncate_text_to_fit(&display_text, width - FILTER_BUTTON_WIDTH, &font_id, painter);
         painter.text(
             label_rect.left_center() + egui::vec2(4.0, 0.0),
             egui::Align2::LEFT_CENTER,
@@ -146,6 +189,40 @@ pub fn render_table_header(
             ui.visuals().strong_text_color(),
         );
 
+        // Filter button and popover
+        if let (Some(column), Some(trace)) = (TreeColumn::from_index(i), trace) {
+            let button_rect = egui::Rect::from_min_max(
+                egui::pos2(label_rect.right() - FILTER_BUTTON_WIDTH - 4.0, label_rect.top()),
+                egui::pos2(label_rect.right() - 4.0, label_rect.bottom()),
+            );
+            let button_response = ui
+                .interact(button_rect, ui.id().with(("header_filter", i)), egui::Sense::click())
+                .on_hover_text(format!("Filter by {}", column.title()));
+            let filtered = column_filter.is_filtered(column);
+            let button_color = if filtered {
+                Color32::from_rgb(100, 150, 255)
+            } else if button_response.hovered() {
+                ui.visuals().strong_text_color()
+            } else {
+                ui.visuals().weak_text_color()
+            };
+            painter.text(
+                button_rect.center(),
+                egui::Align2::CENTER_CENTER,
+                if filtered { "⏷" } else { "▾" },
+                egui::FontId::proportional(12.0),
+                button_color,
+            );
+
+            let changed = egui::Popup::menu(&button_response)
+                .close_behavior(egui::PopupCloseBehavior::CloseOnClickOutside)
+                .show(|ui| render_filter_popover(ui, column, column_filter, trace))
+                .is_some_and(|popover| popover.inner);
+            if changed {
+                interaction = Some(TableHeaderInteraction::FiltersChanged);
+            }
+        }
+
         x_offset += width;
 
         // Column resize handle
@@ -181,6 +258,100 @@ pub fn render_table_header(
     interaction
 }
 
+/// Renders the filter popover of one column.
+///
+/// Text columns get a checkbox per distinct value, numeric columns optional
+/// min/max bounds.
+///
+/// # Returns
+/// * `bool` - True if the column's filter changed
+fn render_filter_popover(
+    ui: &mut egui::Ui,
+    column: TreeColumn,
+    column_filter: &mut ColumnFilterState,
+    trace: &DynTraceData,
+) -> bool {
+    ui.set_min_width(200.0);
+    let mut changed = false;
+
+    if column.is_numeric() {
+        let (mut min, mut max) = match column_filter.filters().get(column) {
+            Some(ColumnFilter::Range { min, max }) => (*min, *max),
+            _ => (None, None),
+        };
+        egui::Grid::new(("column_range_grid", column)).num_columns(2).show(ui, |ui| {
+            for (label, bound) in [("Min", &mut min), ("Max", &mut max)] {
+                let mut enabled = bound.is_some();
+                if ui.checkbox(&mut enabled, label).changed() {
+                    *bound = enabled.then_some(0);
+                    changed = true;
+                }
+                if let Some(value) = bound {
+                    changed |= ui.add(egui::DragValue::new(value)).changed();
+                }
+                ui.end_row();
+            }
+        });
+        if changed {
+            column_filter.set_filter(column, ColumnFilter::Range { min, max });
+        }
+        let note = if column == TreeColumn::Duration {
+            "Shows leaf records within the bounds; records without an end are hidden"
+        } else {
+            "Shows leaf records within the bounds"
+        };
+        ui.label(egui::RichText::new(note).small().weak());
+    } else {
+        let mut excluded = match column_filter.filters().get(column) {
+            Some(ColumnFilter::Exclude(values)) => values.clone(),
+            _ => HashSet::new(),
+        };
+        match column_filter.value_list(trace, column) {
+            Some(values) => {
+                ui.horizontal(|ui| {
+                    if ui.small_button("All").clicked() {
+                        excluded.clear();
+                        changed = true;
+                    }
+                    if ui.small_button("None").clicked() {
+                        excluded = values.iter().map(|(value, _)| value.clone()).collect();
+                        changed = true;
+                    }
+                });
+                egui::ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                    for (value, count) in values {
+                        let mut shown = !excluded.contains(value);
+                        let text = if value.is_empty() { "(empty)" } else { value.as_str() };
+                        if ui.checkbox(&mut shown, format!("{} ({})", text, count)).changed() {
+                            if shown {
+                                excluded.remove(value);
+                            } else {
+                                excluded.insert(value.clone());
+                            }
+                            changed = true;
+                        }
+                    }
+                });
+            }
+            None => {
+                ui.label(egui::RichText::new("Too many distinct values for a value list").weak());
+            }
+        }
+        if changed {
+            column_filter.set_filter(column, ColumnFilter::Exclude(excluded));
+        }
+    }
+
+    if column_filter.is_filtered(column) {
+        ui.separator();
+        if ui.button("Clear filter").clicked() {
+            column_filter.clear_filter(column);
+            changed = true;
+        }
+    }
+    changed
+}
+
 /// Maps column index to sort key (if sortable).
 ///
 /// # Arguments
@@ -197,6 +368,61 @@ fn map_column_index_to_sort_key(index: usize) -> Option<SortKey> {
     }
 }
 
+/// Returns the indicator appended to the label of the active sort key.
+fn sort_arrow(dir: SortDir) -> &'static str {
+    match dir {
+        SortDir::Asc => " ▲",
+        SortDir::Desc => " ▼",
+    }
+}
+
+/// Renders the sort menu: the built-in keys, the custom keys, and a field
+/// to sort by an attribute. Picking the active key again flips its direction.
+fn render_sort_menu(
+    ui: &mut egui::Ui,
+    current_sort: Option<SortSpec>,
+    sort_keys: &SortKeyRegistry,
+) -> Option<TableHeaderInteraction> {
+    let mut interaction = None;
+    let builtin = [
+        (SortKey::Description, "Description"),
+        (SortKey::StartClock, "Start Clock"),
+        (SortKey::Duration, "Duration"),
+    ];
+    let custom = sort_keys.keys().iter().map(|key| (SortKey::Custom(key.id), key.label.as_str()));
+
+    ui.label(egui::RichText::new("Sort by").strong());
+    for (index, (key, label)) in builtin.into_iter().chain(custom).enumerate() {
+        if index == builtin.len() {
+            ui.separator();
+        }
+        let active = current_sort.filter(|spec| spec.key == key);
+        let text = format!("{}{}", label, active.map_or("", |spec| sort_arrow(spec.dir)));
+        if ui.selectable_label(active.is_some(), text).clicked() {
+            interaction = Some(TableHeaderInteraction::SortRequested(toggle_sort_direction(current_sort, key)));
+            ui.close();
+        }
+    }
+
+    ui.separator();
+    let input_id = ui.id().with("attribute_sort_input");
+    let mut attribute: String = ui.data_mut(|data| data.get_temp(input_id)).unwrap_or_default();
+    ui.horizontal(|ui| {
+        let response = ui.add(egui::TextEdit::singleline(&mut attribute).hint_text("attribute").desired_width(120.0));
+        let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        let clicked = ui.add_enabled(!attribute.trim().is_empty(), egui::Button::new("Sort")).clicked();
+        if (submitted || clicked) && !attribute.trim().is_empty() {
+            interaction = Some(TableHeaderInteraction::AttributeSortRequested(attribute.trim().to_string()));
+            ui.close();
+        }
+    })
+    .response
+    .on_hover_text("Sort by an attribute's value: numbers, then text, then records without it");
+    ui.data_mut(|data| data.insert_temp(input_id, attribute));
+
+    interaction
+}
+
 /// Toggles sort direction or sets new sort key.
 ///
 /// If the same key is clicked, toggles between Asc and Desc.
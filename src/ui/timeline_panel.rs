@@ -1,16 +1,36 @@
 //! Timeline panel UI rendering
 //!
 //! Handles the center/right panel with timeline visualization of trace records.
-//! Includes pan, zoom, and event selection capabilities.
+//! Includes pan, zoom, and event selection capabilities. With the split
+//! timeline on, two time windows over the same rows are stacked, each
+//! panned and zoomed on its own.
+//! The cursor offers the nearest event of the hovered row; Alt+click selects it.
+//! In measurement mode, clicks place the A/B measurement cursors instead.
+//! In flame graph mode, the panel shows the selected subtree's flame graph.
 
-use crate::app::AppState;
+use crate::app::{AppState, RepaintCoordinator};
+use crate::domain::{cursor_snap, dependencies, scroll_heat, viewport_operations};
+use crate::domain::swimlanes::SwimlaneRow;
+use crate::domain::event_markers::MarkerShape;
+use crate::domain::event_visibility::EventTypeFilterStrategy;
+use crate::domain::type_hints::TypeHints;
 use crate::io::AsyncLoader;
-use crate::rendering::{time_axis_renderer, timeline_overlays, timeline_renderer};
-use crate::ui::input::timeline_input_handler;
+use crate::rendering::{flamegraph_renderer, scroll_heat_renderer, time_axis_renderer, timeline_overlays, timeline_renderer};
+use crate::rendering::time_axis_renderer::AxisEdge;
+use crate::state::{InputSettings, RegionRowMode, RowAlignment, TooltipState, ValueTrack, MAIN_WINDOW};
+use crate::ui::header;
+use crate::ui::input::timeline_input_handler::{self, TimelineInputResult};
 use crate::ui::virtual_scroll_manager::VirtualScrollManager;
-use crate::utils::{get_current_memory_mb, format_memory_mb};
+use crate::utils::{format_clock, get_current_memory_mb, format_memory_mb};
 use egui::ScrollArea;
-use rjets::ThemeColors;
+use std::collections::HashMap;
+use rjets::{ThemeColors, TraceData, TraceMetadata, TraceRecord};
+
+/// Height of the time axis header; matches the tree's table header (24px from render_table_header)
+const TIMELINE_HEADER_HEIGHT: f32 = 24.0;
+
+/// Vertical space between the windows of a split timeline.
+const SPLIT_GAP: f32 = 6.0;
 
 /// Result of timeline panel interactions that need to be handled by the application.
 pub enum TimelinePanelInteraction {
@@ -20,11 +40,31 @@ pub enum TimelinePanelInteraction {
         was_already_selected: bool,
         first_event_clk: Option<i64>,
     },
+    /// A timeline bar was double-clicked or Ctrl+clicked
+    BarGesture {
+        record_id: u64,
+        gesture: crate::state::ClickGesture,
+    },
     /// An event marker was clicked
     EventClicked {
         record_id: u64,
         event_clk: i64,
     },
+    /// A root lane header was clicked to collapse or expand the lane
+    LaneToggled {
+        record_id: u64,
+        was_expanded: bool,
+    },
+    /// A swimlane header was clicked to collapse or expand the lane
+    SwimlaneToggled(String),
+    /// A measurement cursor was placed at this clock
+    MeasureCursorPlaced { clk: i64 },
+    /// Cancel was clicked on the loading indicator
+    LoadCancelRequested,
+    /// The flame graph view needs the graph of this record's subtree
+    FlameGraphRequested { root_id: u64 },
+    /// User chose a destination for a report of the shown flame graph
+    ExportFlameGraphReport(std::path::PathBuf),
 }
 
 /// Renders the complete timeline panel with time axis, scrollable content, and overlays.
@@ -37,67 +77,215 @@ pub fn render_timeline_panel(
     state: &mut AppState,
     loader: &AsyncLoader,
     theme_colors: &ThemeColors,
-    get_record_color: impl Fn(&str) -> egui::Color32,
+    get_record_color: impl Fn(&str, &str) -> egui::Color32,
 ) -> Option<TimelinePanelInteraction> {
     // Check if loading is in progress
     if loader.is_loading() {
-        render_loading_indicator(ui, theme_colors, loader);
-        ctx.request_repaint_after(std::time::Duration::from_secs_f32(0.1));
-        return None;
+        let canceled = render_loading_indicator(ui, theme_colors);
+        RepaintCoordinator::schedule_animation(ctx, state);
+        return canceled.then_some(TimelinePanelInteraction::LoadCancelRequested);
     }
 
+    // Heat strip scrollbar on the right (rows of the previous frame's list)
+    render_heat_strip(ui, ctx, state, theme_colors);
+
     // Check if we have trace data
-    let trace = match state.trace.trace_data() {
-        Some(t) => t,
-        None => {
-            ui.label("No trace loaded - open a JETS trace file to view timeline");
-            return None;
+    if state.trace.trace_data().is_none() {
+        ui.label("No trace loaded - open a JETS trace file to view timeline");
+        return None;
+    }
+
+    if state.flame_graph.is_enabled() {
+        return render_flame_graph_view(ui, state, theme_colors, &get_record_color);
+    }
+
+    if !state.viewport.is_split() {
+        return render_time_window(ui, ctx, state, MAIN_WINDOW, theme_colors, &get_record_color);
+    }
+
+    // Split timeline: the windows stacked with equal heights
+    let full_rect = ui.available_rect_before_wrap();
+    let window_count = state.viewport.window_count();
+    let pane_height = (full_rect.height() - SPLIT_GAP * (window_count - 1) as f32) / window_count as f32;
+    let mut interaction = None;
+    for window in 0..window_count {
+        let top = full_rect.top() + window as f32 * (pane_height + SPLIT_GAP);
+        if window > 0 {
+            ui.painter().hline(
+                full_rect.x_range(),
+                top - SPLIT_GAP / 2.0,
+                egui::Stroke::new(1.0, theme_colors.border),
+            );
         }
-    };
+        let rect = egui::Rect::from_min_size(egui::pos2(full_rect.left(), top), egui::vec2(full_rect.width(), pane_height));
+        let builder = egui::UiBuilder::new().max_rect(rect).id_salt(("time_window", window));
+        let window_interaction = ui
+            .scope_builder(builder, |ui| {
+                ui.set_clip_rect(rect);
+                render_time_window(ui, ctx, state, window, theme_colors, &get_record_color)
+            })
+            .inner;
+        if window_interaction.is_some() {
+            interaction = window_interaction;
+        }
+    }
+    ui.advance_cursor_after_rect(full_rect);
+    interaction
+}
+
+/// Renders one time window: its time axis, the rows over its clock range
+/// and the cursor and region selection overlays.
+///
+/// All windows show the same rows at the shared scroll position; only the
+/// clock range differs.
+#[allow(clippy::too_many_arguments)]
+fn render_time_window(
+    ui: &mut egui::Ui,
+    ctx: &egui::Context,
+    state: &mut AppState,
+    window: usize,
+    theme_colors: &ThemeColors,
+    get_record_color: &impl Fn(&str, &str) -> egui::Color32,
+) -> Option<TimelinePanelInteraction> {
+    let trace = state.trace.trace_data()?;
 
     // Render time axis header (fixed at top)
-    render_timeline_header(ui, state);
+    let (view_start, view_end) = state.viewport.window_range(window);
+    render_timeline_header(ui, view_start, view_end, theme_colors);
     ui.separator();
 
+    // Optional second axis along the bottom, outside the scrolled rows
+    let axis_settings = state.input_settings.settings();
+    let (show_gridlines, show_capture_bounds) = (axis_settings.gridlines, axis_settings.capture_bounds);
+    let show_bottom_axis = axis_settings.bottom_axis && window + 1 == state.viewport.window_count();
+    let mut canvas_rect = ui.available_rect_before_wrap();
+    let bottom_axis_rect = show_bottom_axis.then(|| {
+        let rect = egui::Rect::from_min_max(
+            egui::pos2(canvas_rect.left(), canvas_rect.bottom() - TIMELINE_HEADER_HEIGHT),
+            canvas_rect.max,
+        );
+        canvas_rect.max.y = rect.top();
+        rect
+    });
+    if show_gridlines {
+        time_axis_renderer::render_gridlines(
+            ui.painter(),
+            canvas_rect,
+            view_start,
+            view_end,
+            theme_colors,
+        );
+    }
+    if show_capture_bounds {
+        let metadata = trace.metadata();
+        timeline_overlays::render_capture_bounds(
+            ui.painter(),
+            canvas_rect,
+            view_start,
+            view_end,
+            metadata.trace_extent(),
+            metadata.capture_end_clk(),
+            theme_colors,
+        );
+    }
+
     // Handle input (zoom, pan, region selection, cursor tracking)
-    let canvas_rect = ui.available_rect_before_wrap();
     let canvas_response = ui.interact(
         canvas_rect,
-        ui.id().with("timeline_canvas"),
+        ui.id().with(("timeline_canvas", window)),
         egui::Sense::drag().union(egui::Sense::hover()),
     );
 
-    // Get mutable references to state components for input handling
-    let trace_min_clk = state.trace.min_clk();
-    let trace_max_clk = state.trace.max_clk();
-    let (viewport_start_clk, viewport_end_clk, zoom_level) = state.viewport.for_input_handler();
-    let (is_dragging, drag_start_clk, is_selecting_region, region_start_pos) = state.interaction.for_input_handler();
-    let (cursor_hover_pos, cursor_hover_clk) = state.selection.for_input_handler();
+    // A drag or region selection belongs to the window it started in
+    let input_result = if state.interaction.is_busy_outside(window) {
+        TimelineInputResult::None
+    } else {
+        // Get mutable references to state components for input handling
+        let trace_min_clk = state.trace.min_clk();
+        let trace_max_clk = state.trace.max_clk();
+        let measuring = state.measurement.is_enabled();
+        let (viewport_start_clk, viewport_end_clk) = state.viewport.for_input_handler(window);
+        let (is_dragging, drag_start_clk, is_selecting_region, region_start_pos) = state.interaction.for_input_handler();
+        let (mut hover_pos, mut hover_clk) = (None, None);
 
-    timeline_input_handler::handle_timeline_input(
-        ctx,
-        canvas_rect,
-        &canvas_response,
-        viewport_start_clk,
-        viewport_end_clk,
-        trace_min_clk,
-        trace_max_clk,
-        zoom_level,
-        is_dragging,
-        drag_start_clk,
-        is_selecting_region,
-        region_start_pos,
-        cursor_hover_pos,
-        cursor_hover_clk,
-    );
+        let result = timeline_input_handler::handle_timeline_input(
+            ctx,
+            canvas_rect,
+            &canvas_response,
+            viewport_start_clk,
+            viewport_end_clk,
+            trace_min_clk,
+            trace_max_clk,
+            is_dragging,
+            drag_start_clk,
+            is_selecting_region,
+            region_start_pos,
+            &mut hover_pos,
+            &mut hover_clk,
+            state.input_settings.settings(),
+            measuring,
+        );
+        if state.interaction.is_dragging() || state.interaction.is_selecting_region() {
+            state.interaction.set_gesture_window(window);
+        }
+        // The main window sets or clears the cursor; the split window only sets it
+        if window == MAIN_WINDOW || hover_pos.is_some() {
+            let (cursor_hover_pos, cursor_hover_clk) = state.selection.for_input_handler();
+            *cursor_hover_pos = hover_pos;
+            *cursor_hover_clk = hover_clk;
+        }
+        result
+    };
+    let (view_start, view_end) = state.viewport.window_range(window);
+
+    // Rows covered by a completed region zoom, if the setting makes use of them
+    let region_row_mode = state.input_settings.settings().region_rows;
+    let mut pin_rows = None;
+    if let TimelineInputResult::RegionZoomed { top_y, bottom_y } = input_result {
+        let rows = viewport_operations::y_span_to_rows(
+            top_y,
+            bottom_y,
+            canvas_rect.top(),
+            state.viewport.scroll_y(),
+            crate::ui::virtual_scrolling::ROW_HEIGHT,
+        );
+        match region_row_mode {
+            RegionRowMode::TimeOnly => {}
+            // Swimlane rows are not tree rows, so they can only be scrolled to
+            RegionRowMode::PinRows if state.lanes.lane_attribute().is_none() => pin_rows = Some(rows),
+            RegionRowMode::ScrollToRows | RegionRowMode::PinRows => {
+                VirtualScrollManager::scroll_to_row(&mut state.viewport, rows.0, RowAlignment::Top, true);
+            }
+        }
+    }
+
+    // The tree's scroll area owns the shared offset and clamps it
+    if let TimelineInputResult::RowsScrolled { delta_y } = input_result {
+        state.viewport.request_scroll_y(state.viewport.scroll_y() - delta_y);
+    }
 
     // Track interactions to return
     let mut interaction: Option<TimelinePanelInteraction> = None;
 
+    // Row under the cursor in this window (record, row center y) and the
+    // center of every drawn row (for dependency arrows), found while drawing
+    let hover_y = state.selection.hover_pos().filter(|pos| canvas_rect.contains(*pos)).map(|pos| pos.y);
+    let mut hovered_row: Option<(u64, f32)> = None;
+    let mut row_centers: HashMap<u64, f32> = HashMap::new();
+    let mut track_row = |ui: &egui::Ui, record_id: u64| {
+        let row_top = ui.cursor().min.y;
+        let row_height = crate::ui::virtual_scrolling::ROW_HEIGHT;
+        if hover_y.is_some_and(|y| (row_top..row_top + row_height).contains(&y)) {
+            hovered_row = Some((record_id, row_top + row_height / 2.0));
+        }
+        row_centers.insert(record_id, row_top + row_height / 2.0);
+    };
+
     // Scrollable timeline content (synchronized with tree)
     let scroll_area = ScrollArea::vertical()
-        .id_salt("timeline_scroll_area")
+        .id_salt(("timeline_scroll_area", window))
         .scroll_bar_visibility(egui::scroll_area::ScrollBarVisibility::AlwaysHidden)
+        .max_height(canvas_rect.height())
         .vertical_scroll_offset(state.viewport.scroll_y());
 
     let scroll_output = scroll_area.show(ui, |ui| {
@@ -105,22 +293,98 @@ pub fn render_timeline_panel(
         let viewport_height = ui.available_height();
         let scroll_offset = state.viewport.scroll_y();
 
+        // Attribute swimlanes replace tree order (rows match the tree panel)
+        if let Some(attribute) = state.lanes.lane_attribute() {
+            let (rows, total_rows) = VirtualScrollManager::collect_swimlane_rows(
+                trace,
+                attribute,
+                state.lanes.collapsed_lanes(),
+                state.lanes.hidden_roots(),
+                state.type_legend.hidden_types(),
+                &mut state.tree_cache,
+                scroll_offset,
+                viewport_height,
+            );
+            let (top_padding, bottom_padding) =
+                VirtualScrollManager::calculate_swimlane_padding(&rows, total_rows);
+            ui.add_space(top_padding);
+
+            for (_, row) in &rows {
+                let row_interaction = match row {
+                    SwimlaneRow::Header { key, count, extent, collapsed } => {
+                        timeline_renderer::render_swimlane_header(
+                            ui,
+                            key,
+                            *count,
+                            *extent,
+                            view_start,
+                            view_end,
+                            *collapsed,
+                            state.interaction.is_dragging(),
+                            theme_colors,
+                            get_record_color(key, ""),
+                        )
+                        .map(|_| TimelinePanelInteraction::SwimlaneToggled(key.clone()))
+                    }
+                    SwimlaneRow::Record { record_id } => {
+                        track_row(ui, *record_id);
+                        render_timeline_row(
+                            ui,
+                            trace,
+                            *record_id,
+                            view_start,
+                            view_end,
+                            state.selection.selected_record_id(),
+                            state.selection.selected_event(),
+                            &state.event_type_filter.event_strategy(state.event_filter.min_severity()),
+                            state.type_legend.value_tracks(),
+                            state.type_legend.trace_hints(),
+                            state.type_legend.event_shapes(),
+                            state.interaction.is_dragging(),
+                            theme_colors,
+                            get_record_color,
+                            &mut state.tooltip,
+                            state.input_settings.settings(),
+                        )
+                    }
+                };
+                if row_interaction.is_some() {
+                    interaction = row_interaction;
+                }
+            }
+
+            ui.add_space(bottom_padding);
+            return;
+        }
+
         // Collect visible nodes (filtered or unfiltered based on viewport filter state)
         let visible_nodes = if state.viewport.viewport_filter_enabled() {
             VirtualScrollManager::collect_filtered_visible_nodes(
                 trace,
                 state.tree.expanded_nodes_set(),
+                state.lanes.hidden_roots(),
+                state.type_legend.hidden_types(),
+                state.tree.pinned_rows(),
+                state.clock_filter.stride(),
+                state.column_filter.filters(),
                 &mut state.tree_cache,
                 scroll_offset,
                 viewport_height,
+                // The row set follows the main window in both halves of a split
                 state.viewport.viewport_start_clk(),
                 state.viewport.viewport_end_clk(),
                 state.tree.active_sort(),
+                state.viewport.hide_empty_parents(),
             )
         } else {
             VirtualScrollManager::collect_visible_nodes(
                 trace,
                 state.tree.expanded_nodes_set(),
+                state.lanes.hidden_roots(),
+                state.type_legend.hidden_types(),
+                state.tree.pinned_rows(),
+                state.clock_filter.stride(),
+                state.column_filter.filters(),
                 &mut state.tree_cache,
                 scroll_offset,
                 viewport_height,
@@ -132,13 +396,32 @@ pub fn render_timeline_panel(
             return;
         }
 
-        // Calculate padding (use filtered count if filter is enabled)
-        let total_visible_nodes = if state.viewport.viewport_filter_enabled() {
+        // Pin the rows under a region selection; the list is rebuilt next frame
+        if let Some((first_row, last_row)) = pin_rows {
+            state.tree.pin_rows(
+                visible_nodes
+                    .iter()
+                    .filter(|node| (first_row..=last_row).contains(&node.row_index))
+                    .map(|node| node.record_id),
+            );
+            state.tree_cache.invalidate_filtered_cache();
+            state.viewport.request_scroll_y(0.0);
+            ctx.request_repaint();
+        }
+
+        // Calculate padding (use filtered count if a filter, hidden types, pinned rows, a clock stride or column filters apply)
+        let total_visible_nodes = if state.viewport.viewport_filter_enabled()
+            || !state.type_legend.hidden_types().is_empty()
+            || !state.tree.pinned_rows().is_empty()
+            || state.clock_filter.stride().is_some()
+            || !state.column_filter.filters().is_empty()
+        {
             state.tree_cache.filtered_node_count.unwrap_or(0)
         } else {
             VirtualScrollManager::get_total_visible_nodes(
                 trace,
                 state.tree.expanded_nodes_set(),
+                state.lanes.hidden_roots(),
                 &mut state.tree_cache,
             )
         };
@@ -149,19 +432,52 @@ pub fn render_timeline_panel(
             ui.add_space(top_padding);
         }
 
-        // Render visible timeline rows
+        // Render visible timeline rows (roots become lane headers in lane mode)
+        let root_lanes = state.lanes.root_lanes_enabled();
         for node in &visible_nodes {
+            if root_lanes && node.depth == 0 {
+                let lane_color = trace
+                    .get_record(node.record_id)
+                    .map(|r| get_record_color(&r.name(), &r.record_type()))
+                    .unwrap_or(theme_colors.gray);
+                let is_expanded = state.tree.expanded_nodes_set().contains(&node.record_id);
+                if let Some(timeline_renderer::TimelineRowInteraction::LaneToggled { record_id, was_expanded }) =
+                    timeline_renderer::render_lane_header(
+                        ui,
+                        trace,
+                        node.record_id,
+                        view_start,
+                        view_end,
+                        is_expanded,
+                        state.interaction.is_dragging(),
+                        theme_colors,
+                        lane_color,
+                        &state.input_settings.settings().name_aliases,
+                    )
+                {
+                    interaction = Some(TimelinePanelInteraction::LaneToggled { record_id, was_expanded });
+                }
+                continue;
+            }
+
+            track_row(ui, node.record_id);
             if let Some(row_interaction) = render_timeline_row(
                 ui,
                 trace,
                 node.record_id,
-                state.viewport.viewport_start_clk(),
-                state.viewport.viewport_end_clk(),
+                view_start,
+                view_end,
                 state.selection.selected_record_id(),
                 state.selection.selected_event(),
+                &state.event_type_filter.event_strategy(state.event_filter.min_severity()),
+                state.type_legend.value_tracks(),
+                state.type_legend.trace_hints(),
+                state.type_legend.event_shapes(),
                 state.interaction.is_dragging(),
                 theme_colors,
-                &get_record_color,
+                get_record_color,
+                &mut state.tooltip,
+                state.input_settings.settings(),
             ) {
                 interaction = Some(row_interaction);
             }
@@ -175,19 +491,96 @@ pub fn render_timeline_panel(
         }
     });
 
-    // Draw cursor line overlay if hovering
+    let arrows = dependencies::visible_arrows(trace, &row_centers, view_start, view_end);
+    if !arrows.is_empty() {
+        timeline_renderer::render_dependency_arrows(
+            &ui.painter_at(scroll_output.inner_rect),
+            &arrows,
+            &row_centers,
+            view_start,
+            view_end,
+            canvas_rect,
+            state.selection.selected_record_id(),
+            theme_colors,
+        );
+    }
+
+    if let Some(rect) = bottom_axis_rect {
+        ui.allocate_rect(rect, egui::Sense::hover());
+        time_axis_renderer::render_time_axis(
+            ui,
+            rect,
+            view_start,
+            view_end,
+            AxisEdge::Bottom,
+            theme_colors,
+        );
+    }
+
+    // Nearest event of the hovered row within snapping distance of the cursor
+    let snap = hovered_row.zip(state.selection.hover_clk()).and_then(|((record_id, _), clk)| {
+        let record = trace.get_record(record_id)?;
+        let radius = cursor_snap::snap_radius_clks(view_start, view_end, canvas_rect.width());
+        cursor_snap::nearest_event(&record, clk, radius)
+    });
+    // The main window sets or clears the snap; the split window only sets it
+    if window == MAIN_WINDOW || hover_y.is_some() {
+        state.selection.set_cursor_snap(snap);
+    }
+    let alt_held = ctx.input(|i| i.modifiers.alt);
+    if let Some(target) = snap {
+        if alt_held && ctx.input(|i| i.pointer.primary_clicked()) && !state.interaction.is_dragging() {
+            interaction = Some(TimelinePanelInteraction::EventClicked {
+                record_id: target.record_id,
+                event_clk: target.event_clk,
+            });
+        }
+    }
+
+    // Clicks place measurement cursors instead of selecting (on the snapped event while Alt is held)
+    if let TimelineInputResult::MeasureCursorPlaced { clk } = input_result {
+        let clk = snap.filter(|_| alt_held).map_or(clk, |target| target.event_clk);
+        interaction = Some(TimelinePanelInteraction::MeasureCursorPlaced { clk });
+    }
+
+    // Draw cursor line overlay if hovering this window (on the snapped event while Alt is held)
     if let (Some(hover_pos), Some(hover_clk)) = (state.selection.hover_pos(), state.selection.hover_clk()) {
-        timeline_overlays::render_cursor_overlay(
+        if canvas_rect.contains(hover_pos) {
+            let snap_pos = snap.zip(hovered_row).map(|(target, (_, row_y))| {
+                let x = viewport_operations::clk_to_x(target.event_clk, view_start, view_end, canvas_rect);
+                (egui::pos2(x, row_y), target.event_clk)
+            });
+            let (cursor_pos, cursor_clk) = match snap_pos {
+                Some((pos, clk)) if alt_held => (egui::pos2(pos.x, hover_pos.y), clk),
+                _ => (hover_pos, hover_clk),
+            };
+            timeline_overlays::render_cursor_overlay(
+                ctx,
+                scroll_output.inner_rect,
+                cursor_pos,
+                cursor_clk,
+                theme_colors,
+            );
+            if let Some((pos, _)) = snap_pos {
+                timeline_overlays::render_snap_marker(ctx, pos, theme_colors);
+            }
+        }
+    }
+
+    if state.measurement.is_enabled() {
+        timeline_overlays::render_measurement_overlay(
             ctx,
             scroll_output.inner_rect,
-            hover_pos,
-            hover_clk,
+            view_start,
+            view_end,
+            state.measurement.cursors(),
+            state.measurement.counts(),
             theme_colors,
         );
     }
 
-    // Draw zoom region selection overlay if active
-    if state.interaction.is_selecting_region() {
+    // Draw zoom region selection overlay if active in this window
+    if state.interaction.is_selecting_region() && !state.interaction.is_busy_outside(window) {
         if let (Some(start_pos), Some(current_pos)) =
             (state.interaction.region_start_pos(), ctx.input(|i| i.pointer.hover_pos()))
         {
@@ -196,6 +589,7 @@ pub fn render_timeline_panel(
                 scroll_output.inner_rect,
                 start_pos,
                 current_pos,
+                region_row_mode != RegionRowMode::TimeOnly,
                 theme_colors,
             );
         }
@@ -204,26 +598,133 @@ pub fn render_timeline_panel(
     interaction
 }
 
-/// Renders the timeline header area with time axis.
-fn render_timeline_header(ui: &mut egui::Ui, state: &AppState) {
-    // Match tree header height EXACTLY (24px from render_table_header)
-    let header_height = 24.0;
+/// Renders the flame graph of the selected subtree in place of the timeline.
+///
+/// When the selection moves to another record, its graph is requested and
+/// built in the background while a spinner is shown.
+fn render_flame_graph_view(
+    ui: &mut egui::Ui,
+    state: &mut AppState,
+    theme_colors: &ThemeColors,
+    get_record_color: &impl Fn(&str, &str) -> egui::Color32,
+) -> Option<TimelinePanelInteraction> {
+    let Some(root_id) = state.selection.selected_record_id() else {
+        ui.label("Select a record to show the flame graph of its subtree");
+        return None;
+    };
+    let Some(graph) = state.flame_graph.graph_for(root_id) else {
+        ui.horizontal(|ui| {
+            ui.spinner();
+            ui.label(egui::RichText::new("Aggregating the selected subtree…").color(theme_colors.text_dim));
+        });
+        return (!state.flame_graph.is_building(root_id)).then_some(TimelinePanelInteraction::FlameGraphRequested { root_id });
+    };
+
+    let mut interaction = None;
+    let mut export = None;
+    ui.horizontal(|ui| {
+        let root_name = graph.frames.first().map_or("", |frame| frame.name.as_str());
+        ui.label(egui::RichText::new(format!("🔥 {}", root_name)).strong());
+        ui.label(
+            egui::RichText::new(format!(
+                "{} clk total, {} records, {} frames",
+                format_clock(graph.total()),
+                graph.record_count,
+                graph.frames.len()
+            ))
+            .color(theme_colors.text_dim),
+        )
+        .on_hover_text("Records with the same name under the same ancestor names are merged into one frame");
+        if state.flame_graph.focus().is_some() && ui.small_button("⛶ Fit").clicked() {
+            interaction = Some(flamegraph_renderer::FlameGraphInteraction::Reset);
+        }
+        if ui.small_button("Export Report…").on_hover_text("Save the frames as Markdown or HTML").clicked() {
+            export = header::pick_report_path().map(TimelinePanelInteraction::ExportFlameGraphReport);
+        }
+    });
+    ui.separator();
+
+    ScrollArea::vertical().id_salt("flame_graph_scroll_area").show(ui, |ui| {
+        let frame_interaction = flamegraph_renderer::render_flame_graph(
+            ui,
+            graph,
+            state.flame_graph.focus(),
+            |frame| get_record_color(&frame.name, &frame.record_type),
+            theme_colors,
+        );
+        interaction = interaction.or(frame_interaction);
+    });
+
+    match interaction {
+        Some(flamegraph_renderer::FlameGraphInteraction::FrameClicked { index }) => state.flame_graph.set_focus(Some(index)),
+        Some(flamegraph_renderer::FlameGraphInteraction::Reset) => state.flame_graph.set_focus(None),
+        None => {}
+    }
+    export
+}
+
+/// Renders the heat strip scrollbar along the right edge of the panel.
+///
+/// Uses the row list cached by the last row collection; hidden in swimlane
+/// mode, where rows are not in tree order.
+fn render_heat_strip(ui: &mut egui::Ui, ctx: &egui::Context, state: &mut AppState, theme_colors: &ThemeColors) {
+    if state.lanes.lane_attribute().is_some() {
+        return;
+    }
+    let Some(heat) = &state.tree_cache.row_heat else {
+        return;
+    };
+    let total_rows = heat.total_rows();
+    let marks = heat.marks(state.selection.selected_record_id(), state.notes.all().keys().copied());
+
+    egui::SidePanel::right("timeline_heat_strip")
+        .exact_width(scroll_heat_renderer::STRIP_WIDTH)
+        .resizable(false)
+        .show_separator_line(false)
+        .frame(egui::Frame::NONE)
+        .show_inside(ui, |ui| {
+            // Line the track up with the rows, below the time axis header
+            ui.add_space(TIMELINE_HEADER_HEIGHT);
+            ui.separator();
+            let track = ui.available_rect_before_wrap();
+            let buckets = scroll_heat::bucket_marks(&marks, total_rows, track.height().max(0.0) as usize);
+            let content_height = total_rows as f32 * crate::ui::virtual_scrolling::ROW_HEIGHT;
+            if let Some(scroll_y) = scroll_heat_renderer::render_heat_scrollbar(
+                ui,
+                track,
+                &buckets,
+                content_height,
+                state.viewport.scroll_y(),
+                theme_colors,
+            ) {
+                state.viewport.request_scroll_y(scroll_y);
+                ctx.request_repaint();
+            }
+        });
+}
 
+/// Renders the timeline header area with the time axis of one window.
+fn render_timeline_header(ui: &mut egui::Ui, view_start: i64, view_end: i64, theme_colors: &ThemeColors) {
     // Reserve space for the header
     let (header_rect, _) =
-        ui.allocate_exact_size(egui::vec2(ui.available_width(), header_height), egui::Sense::hover());
+        ui.allocate_exact_size(egui::vec2(ui.available_width(), TIMELINE_HEADER_HEIGHT), egui::Sense::hover());
 
     // Draw time axis in this header space
     time_axis_renderer::render_time_axis(
         ui,
         header_rect,
-        state.viewport.viewport_start_clk(),
-        state.viewport.viewport_end_clk(),
+        view_start,
+        view_end,
+        AxisEdge::Top,
+        theme_colors,
     );
 }
 
 /// Renders a loading indicator when trace is being loaded.
-fn render_loading_indicator(ui: &mut egui::Ui, theme_colors: &ThemeColors, _loader: &AsyncLoader) {
+///
+/// # Returns
+/// `true` if its Cancel button was clicked.
+fn render_loading_indicator(ui: &mut egui::Ui, theme_colors: &ThemeColors) -> bool {
     let canvas_rect = ui.available_rect_before_wrap();
     let center_pos = canvas_rect.center();
 
@@ -251,9 +752,13 @@ fn render_loading_indicator(ui: &mut egui::Ui, theme_colors: &ThemeColors, _load
         memory_font,
         color,
     );
+
+    let button_rect = egui::Rect::from_center_size(egui::pos2(center_pos.x, center_pos.y + 110.0), egui::vec2(100.0, 28.0));
+    ui.put(button_rect, egui::Button::new("Cancel")).clicked()
 }
 
 /// Renders a single timeline row (delegates to timeline_renderer).
+#[allow(clippy::too_many_arguments)]
 fn render_timeline_row(
     ui: &mut egui::Ui,
     trace: &rjets::DynTraceData,
@@ -262,9 +767,15 @@ fn render_timeline_row(
     viewport_end_clk: i64,
     selected_record_id: Option<u64>,
     selected_event: Option<(u64, i64)>,
+    event_filter: &EventTypeFilterStrategy,
+    value_tracks: &HashMap<String, ValueTrack>,
+    type_hints: &TypeHints,
+    event_shapes: &HashMap<String, MarkerShape>,
     is_dragging: bool,
     theme_colors: &ThemeColors,
-    get_record_color: &impl Fn(&str) -> egui::Color32,
+    get_record_color: &impl Fn(&str, &str) -> egui::Color32,
+    tooltip: &mut TooltipState,
+    input_settings: &InputSettings,
 ) -> Option<TimelinePanelInteraction> {
     timeline_renderer::render_timeline_row(
         ui,
@@ -274,9 +785,15 @@ fn render_timeline_row(
         viewport_end_clk,
         selected_record_id,
         selected_event,
+        event_filter,
+        value_tracks,
+        type_hints,
+        event_shapes,
         is_dragging,
         theme_colors,
         get_record_color,
+        tooltip,
+        input_settings,
     )
     .map(|timeline_interaction| match timeline_interaction {
         timeline_renderer::TimelineRowInteraction::BarClicked {
@@ -288,6 +805,9 @@ fn render_timeline_row(
             was_already_selected,
             first_event_clk,
         },
+        timeline_renderer::TimelineRowInteraction::Gesture { record_id, gesture } => {
+            TimelinePanelInteraction::BarGesture { record_id, gesture }
+        }
         timeline_renderer::TimelineRowInteraction::EventClicked {
             record_id,
             event_clk,
@@ -295,5 +815,15 @@ fn render_timeline_row(
             record_id,
             event_clk,
         },
+        timeline_renderer::TimelineRowInteraction::LaneToggled {
+            record_id,
+            was_expanded,
+        } => TimelinePanelInteraction::LaneToggled {
+            record_id,
+            was_expanded,
+        },
+        timeline_renderer::TimelineRowInteraction::SwimlaneToggled { key } => {
+            TimelinePanelInteraction::SwimlaneToggled(key)
+        }
     })
 }
@@ -0,0 +1,130 @@
+//! Trace health window
+//!
+//! Lists the anomalies of the last health check, one collapsible section per
+//! kind, followed by the parser's warnings. Clicking a record selects it and
+//! brings it into view; orphaned records can only be selected, since they
+//! have no row.
+
+use eframe::egui;
+use egui::RichText;
+use rjets::ThemeColors;
+
+use crate::app::AppState;
+use crate::domain::trace_health::{HealthIssueKind, HEALTH_ISSUE_LIMIT};
+use crate::ui::header;
+
+/// Result of user interaction with the trace health window
+pub enum TraceHealthInteraction {
+    /// User asked to (re)check the loaded trace
+    CheckRequested,
+    /// User clicked a record of an issue
+    RecordClicked(u64),
+    /// User chose a destination for a report of the last check
+    ExportReport(std::path::PathBuf),
+}
+
+/// Renders the trace health window if it is open.
+///
+/// # Arguments
+/// * `ctx` - The egui context the window is shown in
+/// * `state` - Mutable reference to application state
+/// * `theme_colors` - Color palette for the current theme
+pub fn render_trace_health_window(
+    ctx: &egui::Context,
+    state: &mut AppState,
+    theme_colors: &ThemeColors,
+) -> Option<TraceHealthInteraction> {
+    let mut open = state.trace_health.is_open();
+    if !open {
+        return None;
+    }
+
+    let mut interaction = None;
+    egui::Window::new("🩺 Trace Health")
+        .open(&mut open)
+        .resizable(true)
+        .default_width(420.0)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let has_trace = state.trace.trace_data().is_some();
+                let label = if state.trace_health.health().is_some() { "Check Again" } else { "Check Trace" };
+                if ui
+                    .add_enabled(has_trace, egui::Button::new(label))
+                    .on_hover_text("Look for orphaned records, clock anomalies and parser fix-ups")
+                    .on_disabled_hover_text("Open a trace first")
+                    .clicked()
+                {
+                    interaction = Some(TraceHealthInteraction::CheckRequested);
+                }
+                if let Some(health) = state.trace_health.health() {
+                    if ui.button("Export Report…").on_hover_text("Save this check as Markdown or HTML").clicked() {
+                        if let Some(path) = header::pick_report_path() {
+                            interaction = Some(TraceHealthInteraction::ExportReport(path));
+                        }
+                    }
+                    ui.label(RichText::new(format!("{} records checked", health.records)).color(theme_colors.text_dim));
+                }
+            });
+
+            let Some(health) = state.trace_health.health() else {
+                return;
+            };
+            ui.separator();
+            if health.is_healthy() {
+                ui.label(RichText::new("✔ No anomalies found").color(theme_colors.green));
+                return;
+            }
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for kind in HealthIssueKind::ALL {
+                    let count = health.count(kind);
+                    if count == 0 {
+                        continue;
+                    }
+                    let title = RichText::new(format!("{} ({})", kind.title(), count)).color(theme_colors.orange);
+                    egui::CollapsingHeader::new(title)
+                        .id_salt(("trace_health", kind))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            ui.label(RichText::new(kind.consequence()).small().color(theme_colors.text_dim));
+                            egui::Grid::new(("trace_health_grid", kind))
+                                .num_columns(3)
+                                .striped(true)
+                                .spacing([12.0, 2.0])
+                                .show(ui, |ui| {
+                                    for issue in health.issues_of(kind) {
+                                        let selected = state.selection.selected_record_id() == Some(issue.record_id);
+                                        if ui.selectable_label(selected, &issue.name).clicked() {
+                                            interaction = Some(TraceHealthInteraction::RecordClicked(issue.record_id));
+                                        }
+                                        ui.label(RichText::new(format!("#{}", issue.record_id)).color(theme_colors.text_dim));
+                                        ui.label(&issue.detail);
+                                        ui.end_row();
+                                    }
+                                });
+                            if count > HEALTH_ISSUE_LIMIT {
+                                ui.label(
+                                    RichText::new(format!("… and {} more", count - HEALTH_ISSUE_LIMIT))
+                                        .color(theme_colors.text_dim),
+                                );
+                            }
+                        });
+                }
+
+                if !health.parser_warnings.is_empty() {
+                    let title = format!("Parser warnings ({})", health.parser_warnings.len());
+                    egui::CollapsingHeader::new(RichText::new(title).color(theme_colors.orange))
+                        .id_salt("trace_health_parser_warnings")
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            for warning in &health.parser_warnings {
+                                ui.label(warning);
+                            }
+                        });
+                }
+            });
+        });
+
+    state.trace_health.set_open(open);
+    interaction
+}
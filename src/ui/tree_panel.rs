@@ -4,10 +4,17 @@
 //! Uses virtual scrolling for performance with large traces.
 
 use crate::app::AppState;
+use crate::domain::column_filters::TreeColumn;
+use crate::domain::column_stats::HeaderStats;
+use crate::domain::number_format::NumberFormat;
+use crate::domain::swimlanes::SwimlaneRow;
+use crate::domain::viewport_operations;
 use crate::rendering::tree_renderer;
+use crate::state::{ScrollRequest, MAIN_WINDOW};
 use crate::ui::{table_header, virtual_scroll_manager::VirtualScrollManager};
+use crate::utils::format_clock;
 use egui::ScrollArea;
-use rjets::ThemeColors;
+use rjets::{ThemeColors, TraceData};
 
 /// Result of tree panel interactions that need to be handled by the application.
 pub enum TreePanelInteraction {
@@ -17,13 +24,35 @@ pub enum TreePanelInteraction {
         was_already_selected: bool,
         first_event_clk: Option<i64>,
     },
+    /// A tree node was Shift+clicked to select a range of rows
+    NodeRangeSelected { record_id: u64 },
     /// A tree node's expansion state was toggled
     NodeExpandToggled {
         record_id: u64,
         was_expanded: bool,
     },
+    /// A tree node was double-clicked or Ctrl+clicked
+    NodeGesture {
+        record_id: u64,
+        gesture: crate::state::ClickGesture,
+    },
+    /// A problem badge was clicked; the first problem event should be shown
+    ProblemRevealRequested {
+        record_id: u64,
+        event_clk: i64,
+    },
+    /// Navigation to a related record was requested from a row's context menu
+    NavigationRequested {
+        record_id: u64,
+        scope: crate::domain::record_navigation::NavigationScope,
+        forward: bool,
+    },
     /// User requested sorting by clicking a column header
     SortRequested(crate::state::SortSpec),
+    /// User requested sorting by an attribute in the sort menu
+    AttributeSortRequested(String),
+    /// A swimlane header was clicked to collapse or expand the lane
+    SwimlaneToggled(String),
 }
 
 /// Renders the complete tree panel with header and virtual scrolling content.
@@ -50,11 +79,40 @@ pub fn render_tree_panel(
         ui,
         &mut state.layout,
         state.tree.active_sort(),
+        &state.sort_keys,
+        &mut state.column_filter,
+        Some(trace),
     );
 
-    // Check for sort request from header
-    if let Some(table_header::TableHeaderInteraction::SortRequested(spec)) = header_interaction {
-        return Some(TreePanelInteraction::SortRequested(spec));
+    match header_interaction {
+        Some(table_header::TableHeaderInteraction::SortRequested(spec)) => {
+            return Some(TreePanelInteraction::SortRequested(spec));
+        }
+        Some(table_header::TableHeaderInteraction::AttributeSortRequested(attribute)) => {
+            return Some(TreePanelInteraction::AttributeSortRequested(attribute));
+        }
+        Some(table_header::TableHeaderInteraction::FiltersChanged) => {
+            state.tree_cache.invalidate_filtered_cache();
+            state.repaint.request();
+        }
+        Some(table_header::TableHeaderInteraction::StatsHovered(column, response)) => {
+            // Taken out so the row collection can borrow the whole state
+            let mut header_stats = state.tree_cache.header_stats.take();
+            egui::Tooltip::for_widget(&response).show(|ui| {
+                let frame = ui.ctx().cumulative_frame_nr();
+                if header_stats.as_ref().is_none_or(|stats| stats.frame != frame) {
+                    let rows = state.tree_rows();
+                    let records = rows.iter().filter_map(|node| trace.get_record(node.record_id));
+                    header_stats = Some(HeaderStats::compute(frame, records));
+                }
+                if let Some(stats) = &header_stats {
+                    let format = state.input_settings.settings().column_formats.get(column);
+                    render_column_stats(ui, column, format, stats);
+                }
+            });
+            state.tree_cache.header_stats = header_stats;
+        }
+        None => {}
     }
 
     ui.separator();
@@ -66,29 +124,107 @@ pub fn render_tree_panel(
     let mut interaction: Option<TreePanelInteraction> = None;
 
     // Render scrollable content with virtual scrolling
-    let scroll_area = ScrollArea::vertical()
-        .id_salt("tree_scroll_area")
+    let mut scroll_area = ScrollArea::vertical().id_salt("tree_scroll_area");
+    let mut row_scroll = None;
+    match state.viewport.take_scroll_request() {
+        Some(ScrollRequest::Offset(y)) => scroll_area = scroll_area.vertical_scroll_offset(y),
+        Some(ScrollRequest::Row { row_index, alignment, animated }) => row_scroll = Some((row_index, alignment, animated)),
+        None => {}
+    }
+    let scroll_area = scroll_area
         .show(ui, |ui| {
+            if let Some((row_index, alignment, animated)) = row_scroll {
+                VirtualScrollManager::apply_row_scroll(ui, ui.cursor().top(), row_index, alignment, animated);
+            }
+
             // Get viewport metrics
             let viewport_height = ui.available_height();
             let scroll_offset = state.viewport.scroll_y();
 
+            // Attribute swimlanes replace tree order entirely
+            if let Some(attribute) = state.lanes.lane_attribute() {
+                let (rows, total_rows) = VirtualScrollManager::collect_swimlane_rows(
+                    trace,
+                    attribute,
+                    state.lanes.collapsed_lanes(),
+                    state.lanes.hidden_roots(),
+                    state.type_legend.hidden_types(),
+                    &mut state.tree_cache,
+                    scroll_offset,
+                    viewport_height,
+                );
+                let (top_padding, bottom_padding) =
+                    VirtualScrollManager::calculate_swimlane_padding(&rows, total_rows);
+                ui.add_space(top_padding);
+
+                for (_, row) in &rows {
+                    let row_interaction = match row {
+                        SwimlaneRow::Header { key, count, collapsed, .. } => {
+                            tree_renderer::render_swimlane_header_row(
+                                ui,
+                                attribute,
+                                key,
+                                *count,
+                                *collapsed,
+                                state.input_settings.settings().tree_font_size(),
+                                theme_colors,
+                            )
+                            .then(|| TreePanelInteraction::SwimlaneToggled(key.clone()))
+                        }
+                        SwimlaneRow::Record { record_id } => render_tree_node(
+                            ui,
+                            trace,
+                            *record_id,
+                            1,
+                            expand_width,
+                            state.layout.column_widths(),
+                            state.tree.expanded_nodes_set(),
+                            state.selection.selected_record_id(),
+                            state.selection.selected_records(),
+                            theme_colors,
+                            &mut state.tree_cache,
+                            &[],
+                            false,
+                            &mut state.tooltip,
+                            state.input_settings.settings(),
+                        ),
+                    };
+                    if row_interaction.is_some() {
+                        interaction = row_interaction;
+                    }
+                }
+
+                ui.add_space(bottom_padding);
+                return;
+            }
+
             // Collect visible nodes (filtered or unfiltered based on viewport filter state)
             let visible_nodes = if state.viewport.viewport_filter_enabled() {
                 VirtualScrollManager::collect_filtered_visible_nodes(
                     trace,
                     state.tree.expanded_nodes_set(),
+                    state.lanes.hidden_roots(),
+                    state.type_legend.hidden_types(),
+                    state.tree.pinned_rows(),
+                    state.clock_filter.stride(),
+                    state.column_filter.filters(),
                     &mut state.tree_cache,
                     scroll_offset,
                     viewport_height,
                     state.viewport.viewport_start_clk(),
                     state.viewport.viewport_end_clk(),
                     state.tree.active_sort(),
+                    state.viewport.hide_empty_parents(),
                 )
             } else {
                 VirtualScrollManager::collect_visible_nodes(
                     trace,
                     state.tree.expanded_nodes_set(),
+                    state.lanes.hidden_roots(),
+                    state.type_legend.hidden_types(),
+                    state.tree.pinned_rows(),
+                    state.clock_filter.stride(),
+                    state.column_filter.filters(),
                     &mut state.tree_cache,
                     scroll_offset,
                     viewport_height,
@@ -100,13 +236,19 @@ pub fn render_tree_panel(
                 return;
             }
 
-            // Calculate padding (use filtered count if filter is enabled)
-            let total_visible_nodes = if state.viewport.viewport_filter_enabled() {
+            // Calculate padding (use filtered count if a filter, hidden types, pinned rows, a clock stride or column filters apply)
+            let total_visible_nodes = if state.viewport.viewport_filter_enabled()
+                || !state.type_legend.hidden_types().is_empty()
+                || !state.tree.pinned_rows().is_empty()
+                || state.clock_filter.stride().is_some()
+                || !state.column_filter.filters().is_empty()
+            {
                 state.tree_cache.filtered_node_count.unwrap_or(0)
             } else {
                 VirtualScrollManager::get_total_visible_nodes(
                     trace,
                     state.tree.expanded_nodes_set(),
+                    state.lanes.hidden_roots(),
                     &mut state.tree_cache,
                 )
             };
@@ -128,10 +270,13 @@ pub fn render_tree_panel(
                     state.layout.column_widths(),
                     state.tree.expanded_nodes_set(),
                     state.selection.selected_record_id(),
+                    state.selection.selected_records(),
                     theme_colors,
                     &mut state.tree_cache,
                     &node.branch_context,
                     node.is_last_child,
+                    &mut state.tooltip,
+                    state.input_settings.settings(),
                 ) {
                     interaction = Some(node_interaction);
                 }
@@ -150,10 +295,50 @@ pub fn render_tree_panel(
     // Update shared scroll position
     state.viewport.set_scroll_y(scroll_area.state.offset.y);
 
+    // Shift+wheel pans time here as over the timeline (egui makes it horizontal,
+    // so the vertical scroll area leaves it alone)
+    if ui.rect_contains_pointer(scroll_area.inner_rect) {
+        let wheel = ui.input(|i| {
+            let delta = if i.raw_scroll_delta != egui::Vec2::ZERO { i.raw_scroll_delta } else { i.smooth_scroll_delta };
+            (i.modifiers.shift && !i.modifiers.ctrl).then_some(delta.x + delta.y)
+        });
+        if let Some(wheel) = wheel.filter(|&wheel| wheel != 0.0) {
+            let (start, end) = state.viewport.window_range(MAIN_WINDOW);
+            let (min_clk, max_clk) = (state.trace.min_clk(), state.trace.max_clk());
+            let scroll = state.input_settings.settings().oriented_scroll(wheel);
+            let (start, end) = viewport_operations::wheel_pan(scroll, start, end, min_clk, max_clk);
+            state.viewport.set_range(start, end, min_clk, max_clk);
+        }
+    }
+
     interaction
 }
 
+
+/// Renders the quick stats tooltip of a column header.
+fn render_column_stats(ui: &mut egui::Ui, column: TreeColumn, format: NumberFormat, stats: &HeaderStats) {
+    ui.strong(format!("{} over {} rows", column.title(), format_clock(stats.rows as i64)));
+    let Some(column_stats) = stats.get(column) else {
+        ui.label("No values");
+        return;
+    };
+    egui::Grid::new("column_stats_grid").num_columns(2).show(ui, |ui| {
+        ui.label("Min");
+        ui.label(format.format_readable(column_stats.min));
+        ui.end_row();
+        ui.label("Mean");
+        ui.label(format!("{:.1}", column_stats.mean));
+        ui.end_row();
+        ui.label("Max");
+        ui.label(format.format_readable(column_stats.max));
+        ui.end_row();
+    });
+    if column_stats.count < stats.rows {
+        ui.label(egui::RichText::new(format!("{} rows without a value", stats.rows - column_stats.count)).small().weak());
+    }
+}
 /// Renders a single tree node row (delegates to tree_renderer).
+#[allow(clippy::too_many_arguments)]
 fn render_tree_node(
     ui: &mut egui::Ui,
     trace: &rjets::DynTraceData,
@@ -163,10 +348,13 @@ fn render_tree_node(
     column_widths: &[f32; 5],
     expanded_nodes: &std::collections::HashSet<u64>,
     selected_record_id: Option<u64>,
+    selected_records: &[u64],
     theme_colors: &ThemeColors,
     tree_cache: &mut crate::cache::TreeCache,
     branch_context: &[bool],
     is_last_child: bool,
+    tooltip: &mut crate::state::TooltipState,
+    input_settings: &crate::state::InputSettings,
 ) -> Option<TreePanelInteraction> {
     tree_renderer::render_tree_node(
         ui,
@@ -177,10 +365,13 @@ fn render_tree_node(
         column_widths,
         expanded_nodes,
         selected_record_id,
+        selected_records,
         theme_colors,
         tree_cache,
         branch_context,
         is_last_child,
+        tooltip,
+        input_settings,
     )
     .map(|tree_interaction| match tree_interaction {
         tree_renderer::TreeNodeInteraction::Selected {
@@ -192,6 +383,9 @@ fn render_tree_node(
             was_already_selected,
             first_event_clk,
         },
+        tree_renderer::TreeNodeInteraction::RangeSelected { record_id } => {
+            TreePanelInteraction::NodeRangeSelected { record_id }
+        }
         tree_renderer::TreeNodeInteraction::ExpandToggled {
             record_id,
             was_expanded,
@@ -199,5 +393,14 @@ fn render_tree_node(
             record_id,
             was_expanded,
         },
+        tree_renderer::TreeNodeInteraction::Gesture { record_id, gesture } => {
+            TreePanelInteraction::NodeGesture { record_id, gesture }
+        }
+        tree_renderer::TreeNodeInteraction::RevealProblem { record_id, event_clk } => {
+            TreePanelInteraction::ProblemRevealRequested { record_id, event_clk }
+        }
+        tree_renderer::TreeNodeInteraction::Navigate { record_id, scope, forward } => {
+            TreePanelInteraction::NavigationRequested { record_id, scope, forward }
+        }
     })
 }
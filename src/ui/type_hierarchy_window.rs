@@ -0,0 +1,133 @@
+//! Record type hierarchy window
+//!
+//! Shows the trace folded by record type as a collapsible tree: one node per
+//! chain of ancestor types, with the record count and the total and average
+//! duration of its records. Independent of the record tree's expansion, so it
+//! gives a structural overview of traces too large to browse.
+
+use eframe::egui;
+use egui::RichText;
+use rjets::ThemeColors;
+
+use crate::app::AppState;
+use crate::domain::number_format::NumberFormat;
+use crate::domain::type_hierarchy::TypeHierarchy;
+use crate::ui::header;
+
+/// Result of user interaction with the type hierarchy window
+pub enum TypeHierarchyInteraction {
+    /// User asked to (re)fold the loaded trace
+    BuildRequested,
+    /// User chose a destination for a report of the folded hierarchy
+    ExportReport(std::path::PathBuf),
+}
+
+/// Renders the type hierarchy window if it is open.
+///
+/// # Arguments
+/// * `ctx` - The egui context the window is shown in
+/// * `state` - Mutable reference to application state
+/// * `theme_colors` - Color palette for the current theme
+pub fn render_type_hierarchy_window(
+    ctx: &egui::Context,
+    state: &mut AppState,
+    theme_colors: &ThemeColors,
+) -> Option<TypeHierarchyInteraction> {
+    let mut open = state.type_hierarchy.is_open();
+    if !open {
+        return None;
+    }
+
+    let mut interaction = None;
+    let duration_format = state.input_settings.settings().column_formats.duration;
+    egui::Window::new("🗂 Record Type Hierarchy")
+        .open(&mut open)
+        .resizable(true)
+        .default_width(420.0)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let has_trace = state.trace.trace_data().is_some();
+                let label = if state.type_hierarchy.hierarchy().is_some() { "Rebuild" } else { "Build" };
+                if ui
+                    .add_enabled(has_trace, egui::Button::new(label))
+                    .on_hover_text("Count records and durations per chain of record types")
+                    .on_disabled_hover_text("Open a trace first")
+                    .clicked()
+                {
+                    interaction = Some(TypeHierarchyInteraction::BuildRequested);
+                }
+                if let Some(hierarchy) = state.type_hierarchy.hierarchy() {
+                    if ui.button("Export Report…").on_hover_text("Save the hierarchy as Markdown or HTML").clicked() {
+                        if let Some(path) = header::pick_report_path() {
+                            interaction = Some(TypeHierarchyInteraction::ExportReport(path));
+                        }
+                    }
+                    ui.label(RichText::new(format!("{} records", hierarchy.records)).color(theme_colors.text_dim));
+                }
+            });
+
+            let Some(hierarchy) = state.type_hierarchy.hierarchy() else {
+                return;
+            };
+            ui.separator();
+            if hierarchy.roots.is_empty() {
+                ui.label(RichText::new("No records").color(theme_colors.text_dim));
+                return;
+            }
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for &root in &hierarchy.roots {
+                    render_node(ui, hierarchy, root, duration_format, theme_colors);
+                }
+            });
+        });
+
+    state.type_hierarchy.set_open(open);
+    interaction
+}
+
+/// Renders one type node and, when expanded, its child types.
+fn render_node(
+    ui: &mut egui::Ui,
+    hierarchy: &TypeHierarchy,
+    index: usize,
+    duration_format: NumberFormat,
+    theme_colors: &ThemeColors,
+) {
+    let node = hierarchy.node(index);
+    let mut stats = format!("{} records", node.count);
+    if let Some(mean) = node.mean_duration() {
+        stats.push_str(&format!(
+            " · total {} · avg {}",
+            duration_format.format_readable(node.total_duration),
+            duration_format.format_readable(mean.round() as i64),
+        ));
+    }
+
+    if node.children.is_empty() {
+        ui.horizontal(|ui| {
+            // Line up with the labels of collapsible siblings
+            ui.add_space(ui.spacing().indent);
+            ui.label(RichText::new(&node.record_type).strong());
+            ui.label(RichText::new(stats).color(theme_colors.text_dim));
+        });
+        return;
+    }
+
+    let mut title = egui::text::LayoutJob::default();
+    title.append(&node.record_type, 0.0, egui::TextFormat {
+        color: ui.visuals().strong_text_color(),
+        ..Default::default()
+    });
+    title.append(&format!("  {}", stats), 0.0, egui::TextFormat {
+        color: theme_colors.text_dim,
+        ..Default::default()
+    });
+    egui::CollapsingHeader::new(title)
+        .id_salt(("type_hierarchy", index))
+        .default_open(true)
+        .show(ui, |ui| {
+            for &child in &node.children {
+                render_node(ui, hierarchy, child, duration_format, theme_colors);
+            }
+        });
+}
@@ -0,0 +1,232 @@
+//! Record type legend window
+//!
+//! Lists every record_type of the trace with its color swatch, record count and
+//! total duration. Clicking a swatch opens the color editor for that type;
+//! clicking a row hides or shows the type in the tree and timeline. The Bar
+//! Height column turns a type into a value track, whose timeline bars are as
+//! tall as a numeric attribute. The Event Markers section picks a marker shape
+//! per event name.
+
+use eframe::egui;
+use egui::RichText;
+use rjets::ThemeColors;
+
+use crate::app::AppState;
+use crate::cache::{TypeStats, EVENT_NAME_LIMIT};
+use crate::domain::event_markers::MarkerShape;
+use crate::presentation::color_mapping;
+use crate::state::ValueTrack;
+use crate::utils::format_clock;
+
+/// Renders the legend window if it is open.
+///
+/// Visibility changes alter the visible row set, so the tree cache is
+/// invalidated here.
+///
+/// # Arguments
+/// * `ctx` - The egui context the window is shown in
+/// * `state` - Mutable reference to application state
+/// * `theme_colors` - Color palette for the current theme
+pub fn render_type_legend(ctx: &egui::Context, state: &mut AppState, theme_colors: &ThemeColors) {
+    let mut open = state.type_legend.is_open();
+    if !open {
+        return;
+    }
+
+    egui::Window::new("🎨 Record Types")
+        .open(&mut open)
+        .resizable(true)
+        .default_width(360.0)
+        .show(ctx, |ui| {
+            let Some(summary) = &state.tree_cache.trace_summary else {
+                if state.trace.trace_data().is_some() {
+                    ui.label(RichText::new("Indexing…").color(theme_colors.text_dim));
+                } else {
+                    ui.label(RichText::new("No trace loaded").color(theme_colors.text_dim));
+                }
+                return;
+            };
+            // Cloned so rows can mutate the legend state while iterating
+            let type_stats: Vec<(String, TypeStats)> =
+                summary.type_stats.iter().map(|(t, s)| (t.clone(), s.clone())).collect();
+            let event_names: Vec<(String, usize)> =
+                summary.event_name_counts.iter().map(|(n, c)| (n.clone(), *c)).collect();
+
+            let mut visibility_changed = false;
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                egui::Grid::new("type_legend_grid")
+                    .num_columns(5)
+                    .striped(true)
+                    .spacing([8.0, 4.0])
+                    .show(ui, |ui| {
+                        ui.label(RichText::new("Color").strong());
+                        ui.label(RichText::new("Type").strong());
+                        ui.label(RichText::new("Count").strong());
+                        ui.label(RichText::new("Total Duration").strong());
+                        ui.label(RichText::new("Bar Height").strong());
+                        ui.end_row();
+
+                        for (record_type, stats) in &type_stats {
+                            render_type_row(ui, state, theme_colors, record_type, stats, &mut visibility_changed);
+                            ui.end_row();
+                        }
+                    });
+
+                if !event_names.is_empty() {
+                    ui.collapsing("Event Markers", |ui| {
+                        render_event_markers(ui, state, theme_colors, &event_names);
+                    });
+                }
+            });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                let hidden = state.type_legend.hidden_types().len();
+                if ui.add_enabled(hidden > 0, egui::Button::new("Show All")).clicked() {
+                    state.type_legend.show_all_types();
+                    visibility_changed = true;
+                }
+                if hidden > 0 {
+                    ui.label(RichText::new(format!("{} hidden", hidden)).color(theme_colors.text_dim));
+                }
+            });
+
+            if visibility_changed {
+                state.tree_cache.invalidate();
+            }
+        });
+
+    state.type_legend.set_open(open);
+}
+
+/// Renders one legend row: swatch, type name, count, total duration and
+/// bar height mode.
+fn render_type_row(
+    ui: &mut egui::Ui,
+    state: &mut AppState,
+    theme_colors: &ThemeColors,
+    record_type: &str,
+    stats: &TypeStats,
+    visibility_changed: &mut bool,
+) {
+    let visible = state.type_legend.is_type_visible(record_type);
+    let override_color = state.type_legend.color_override(record_type);
+    let mut color = override_color
+        .or_else(|| state.type_legend.trace_hints().color(record_type))
+        .unwrap_or_else(|| color_mapping::get_record_color(&stats.sample_name, theme_colors));
+
+    let swatch = ui.color_edit_button_srgba(&mut color);
+    if swatch.changed() {
+        state.type_legend.set_color_override(record_type, Some(color));
+    }
+    if override_color.is_some() {
+        swatch.context_menu(|ui| {
+            if ui.button("Reset to default color").clicked() {
+                state.type_legend.set_color_override(record_type, None);
+                ui.close();
+            }
+        });
+    }
+
+    let text_color = if visible { theme_colors.text } else { theme_colors.text_dim };
+    let name = if visible {
+        RichText::new(record_type).color(text_color)
+    } else {
+        RichText::new(record_type).color(text_color).strikethrough()
+    };
+    let row_hint = if visible { "Click to hide this type" } else { "Click to show this type" };
+    if ui.selectable_label(false, name).on_hover_text(row_hint).clicked() {
+        state.type_legend.toggle_type_visible(record_type);
+        *visibility_changed = true;
+    }
+
+    ui.label(RichText::new(stats.count.to_string()).color(text_color));
+    ui.label(RichText::new(format_clock(stats.total_duration)).color(text_color));
+
+    render_value_track_button(ui, state, record_type);
+}
+
+/// Renders the marker shape picker of each event name.
+///
+/// Shapes apply to info events; warnings and errors keep their severity
+/// shapes, and an event's `marker` attribute wins over the name.
+fn render_event_markers(
+    ui: &mut egui::Ui,
+    state: &mut AppState,
+    theme_colors: &ThemeColors,
+    event_names: &[(String, usize)],
+) {
+    egui::Grid::new("event_marker_grid")
+        .num_columns(3)
+        .striped(true)
+        .spacing([8.0, 4.0])
+        .show(ui, |ui| {
+            for (name, count) in event_names {
+                let current = state.type_legend.event_shapes().get(name).copied();
+                let mut selected = current;
+                egui::ComboBox::from_id_salt(("event_marker", name))
+                    .selected_text(selected.map_or("Default", MarkerShape::label))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut selected, None, "Default");
+                        for shape in MarkerShape::ALL {
+                            ui.selectable_value(&mut selected, Some(shape), shape.label());
+                        }
+                    });
+                if selected != current {
+                    state.type_legend.set_event_shape(name, selected);
+                }
+                ui.label(RichText::new(name).color(theme_colors.text));
+                ui.label(RichText::new(count.to_string()).color(theme_colors.text_dim));
+                ui.end_row();
+            }
+        });
+    if event_names.len() >= EVENT_NAME_LIMIT {
+        ui.label(
+            RichText::new(format!("Only the first {} event names are listed", EVENT_NAME_LIMIT))
+                .small()
+                .color(theme_colors.text_dim),
+        );
+    }
+}
+
+/// Renders the bar height button of a legend row and its value track editor.
+fn render_value_track_button(ui: &mut egui::Ui, state: &mut AppState, record_type: &str) {
+    let track = state.type_legend.value_track(record_type).cloned();
+    let button_text = match &track {
+        Some(track) => format!("📊 {}", track.attribute),
+        None => "Full".to_string(),
+    };
+
+    ui.menu_button(button_text, |ui| {
+        let mut enabled = track.is_some();
+        let mut edited = track.clone().unwrap_or_else(|| ValueTrack::new(""));
+        if ui
+            .checkbox(&mut enabled, "Bar height from attribute")
+            .on_hover_text("Draw each bar as tall as a numeric record attribute")
+            .changed()
+            && !enabled
+        {
+            state.type_legend.set_value_track(record_type, None);
+            return;
+        }
+        if !enabled {
+            return;
+        }
+
+        egui::Grid::new("value_track_grid").num_columns(2).show(ui, |ui| {
+            ui.label("Attribute:");
+            ui.text_edit_singleline(&mut edited.attribute);
+            ui.end_row();
+            ui.label("Empty at:");
+            ui.add(egui::DragValue::new(&mut edited.min).speed(1.0));
+            ui.end_row();
+            ui.label("Full at:");
+            ui.add(egui::DragValue::new(&mut edited.max).speed(1.0));
+            ui.end_row();
+        });
+
+        if track.as_ref() != Some(&edited) {
+            state.type_legend.set_value_track(record_type, Some(edited));
+        }
+    });
+}
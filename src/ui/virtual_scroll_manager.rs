@@ -2,10 +2,21 @@
 //!
 //! Provides shared logic for collecting visible nodes in the viewport,
 //! calculating padding, and managing scroll synchronization.
+//!
+//! Features that need a row on screen (go-to-record, region zoom, search)
+//! use [`VirtualScrollManager::scroll_to_record`] or
+//! [`VirtualScrollManager::scroll_to_row`]; the tree panel applies the
+//! request with [`VirtualScrollManager::apply_row_scroll`] on its next frame.
 
+use crate::app::AppState;
 use crate::cache::TreeCache;
+use crate::domain::scroll_heat::RowHeatIndex;
+use crate::domain::swimlanes::{self, SwimlaneRow};
+use crate::domain::column_filters::ColumnFilters;
+use crate::domain::visibility::ClockStride;
 use crate::ui::virtual_scrolling::{self, VisibleNode};
-use crate::state::SortSpec;
+use crate::state::{RowAlignment, SortSpec, ViewportState};
+use eframe::egui;
 use rjets::DynTraceData;
 use std::collections::HashSet;
 
@@ -13,41 +24,88 @@ use std::collections::HashSet;
 pub struct VirtualScrollManager;
 
 impl VirtualScrollManager {
-    /// Gets the total number of visible nodes in the tree (cached).
-    pub fn get_total_visible_nodes(
-        trace: &DynTraceData,
-        expanded_nodes: &HashSet<u64>,
-        cache: &mut TreeCache,
-    ) -> usize {
-        crate::domain::tree_operations::get_total_visible_nodes(trace, expanded_nodes, cache)
+    /// Requests scrolling a row of the current row list into view.
+    ///
+    /// # Arguments
+    /// * `viewport` - Viewport state holding the request until the tree panel applies it
+    /// * `row_index` - Index of the row in display order
+    /// * `alignment` - Where the row lands in the visible area
+    /// * `animated` - Scroll smoothly (ignored while animations are off)
+    pub fn scroll_to_row(viewport: &mut ViewportState, row_index: usize, alignment: RowAlignment, animated: bool) {
+        viewport.request_scroll_to_row(row_index, alignment, animated);
+    }
+
+    /// Requests scrolling a record's row into view.
+    ///
+    /// The record must have a row with the current expansion and filters
+    /// (expand its ancestors first). Swimlane rows are not in tree order and
+    /// are not searched.
+    ///
+    /// # Returns
+    /// False if the record has no row.
+    pub fn scroll_to_record(state: &mut AppState, record_id: u64, alignment: RowAlignment, animated: bool) -> bool {
+        if state.lanes.lane_attribute().is_some() {
+            return false;
+        }
+        let Some(row_index) = state.tree_rows().iter().position(|node| node.record_id == record_id) else {
+            return false;
+        };
+        Self::scroll_to_row(&mut state.viewport, row_index, alignment, animated);
+        true
     }
 
-    /// Gets the maximum visible depth in the tree (cached).
-    pub fn get_max_visible_depth(
+    /// Scrolls the enclosing scroll area to a row; call inside its content.
+    ///
+    /// # Arguments
+    /// * `ui` - The scroll area's content UI
+    /// * `content_top` - Screen position of the content's top edge (row 0)
+    /// * `row_index` - Index of the row in display order
+    /// * `alignment` - Where the row lands in the visible area
+    /// * `animated` - Scroll smoothly instead of jumping
+    pub fn apply_row_scroll(ui: &egui::Ui, content_top: f32, row_index: usize, alignment: RowAlignment, animated: bool) {
+        let row_height = virtual_scrolling::ROW_HEIGHT;
+        let top = content_top + row_index as f32 * row_height;
+        let rect = egui::Rect::from_x_y_ranges(ui.max_rect().x_range(), top..=top + row_height);
+        let align = match alignment {
+            RowAlignment::Top => egui::Align::TOP,
+            RowAlignment::Center => egui::Align::Center,
+        };
+        let animation = if animated { ui.style().scroll_animation } else { egui::style::ScrollAnimation::none() };
+        ui.scroll_to_rect_animation(rect, Some(align), animation);
+    }
+
+    /// Gets the total number of visible nodes in the tree (cached).
+    pub fn get_total_visible_nodes(
         trace: &DynTraceData,
         expanded_nodes: &HashSet<u64>,
+        hidden_roots: &HashSet<u64>,
         cache: &mut TreeCache,
     ) -> usize {
-        crate::domain::tree_operations::get_max_visible_depth(trace, expanded_nodes, cache)
+        crate::domain::tree_operations::get_total_visible_nodes(trace, expanded_nodes, hidden_roots, cache)
     }
 
     /// Collects nodes visible in the current viewport plus buffer.
+    ///
+    /// When record types are hidden, rows are pinned, a clock stride is set or
+    /// columns are filtered,
+    /// the row count differs from the cached total, so it is stored in
+    /// `TreeCache::filtered_node_count`.
+    /// Once the heat index for the current row list exists, unsorted and
+    /// unhidden trees only walk the on-screen window instead of the whole list.
+    #[allow(clippy::too_many_arguments)]
     pub fn collect_visible_nodes(
         trace: &DynTraceData,
         expanded_nodes: &HashSet<u64>,
+        hidden_roots: &HashSet<u64>,
+        hidden_types: &HashSet<String>,
+        pinned_rows: &HashSet<u64>,
+        clock_stride: Option<ClockStride>,
+        column_filters: &ColumnFilters,
         cache: &mut TreeCache,
         viewport_scroll_offset: f32,
         viewport_height: f32,
         active_sort: Option<SortSpec>,
     ) -> Vec<VisibleNode> {
-        // Use the new strategy-based traversal system with optional sorting
-        let all_nodes = crate::domain::tree_operations::collect_unfiltered_visible_nodes_with_sort(
-            trace,
-            expanded_nodes,
-            cache,
-            active_sort,
-        );
-
         // Apply vertical scroll culling with buffer
         let row_height = virtual_scrolling::ROW_HEIGHT;
         let first_visible_row = (viewport_scroll_offset / row_height).floor() as usize;
@@ -57,6 +115,39 @@ impl VirtualScrollManager {
         let first_visible_row = first_visible_row.saturating_sub(virtual_scrolling::VIEWPORT_BUFFER_ROWS);
         let last_visible_row = last_visible_row + virtual_scrolling::VIEWPORT_BUFFER_ROWS;
 
+        let heat_is_current = cache.row_heat.as_ref().is_some_and(|heat| heat.filter_range().is_none());
+        let all_nodes = if heat_is_current && hidden_types.is_empty() && pinned_rows.is_empty() && clock_stride.is_none() && column_filters.is_empty() && active_sort.is_none() {
+            crate::domain::tree_operations::collect_unfiltered_window_nodes(
+                trace,
+                expanded_nodes,
+                hidden_roots,
+                cache,
+                first_visible_row,
+                last_visible_row,
+            )
+        } else {
+            // Use the new strategy-based traversal system with optional sorting
+            let all_nodes = crate::domain::tree_operations::collect_unfiltered_visible_nodes_with_sort(
+                trace,
+                expanded_nodes,
+                hidden_roots,
+                hidden_types,
+                clock_stride,
+                column_filters,
+                cache,
+                active_sort,
+            );
+            let all_nodes = crate::domain::tree_operations::retain_pinned_rows(all_nodes, pinned_rows);
+
+            if !hidden_types.is_empty() || !pinned_rows.is_empty() || clock_stride.is_some() || !column_filters.is_empty() {
+                cache.filtered_node_count = Some(all_nodes.len());
+            }
+            if !heat_is_current {
+                cache.row_heat = Some(RowHeatIndex::build(trace, &all_nodes, None));
+            }
+            all_nodes
+        };
+
         all_nodes
             .into_iter()
             .filter(|node| {
@@ -72,14 +163,6 @@ impl VirtualScrollManager {
             .collect()
     }
 
-    /// Calculates the expand column width based on tree depth.
-    ///
-    /// Reserves space for at least 5 levels by default to avoid resizing.
-    /// Formula: 20px base for expand icon + 20px per indent level
-    pub fn calculate_expand_width(max_depth: usize) -> f32 {
-        20.0 + (max_depth.max(5) as f32 * 20.0)
-    }
-
     /// Calculates top padding for virtual scrolling (rows before viewport).
     pub fn calculate_top_padding(visible_nodes: &[VisibleNode]) -> f32 {
         let first_row = visible_nodes.first().map(|n| n.row_index).unwrap_or(0);
@@ -108,28 +191,46 @@ impl VirtualScrollManager {
     ///
     /// This method applies temporal filtering based on the viewport clock range,
     /// showing only leaf records that start within [viewport_start_clk, viewport_end_clk].
+    /// With `hide_empty_parents`, parents without such a leaf below them are dropped too.
+    #[allow(clippy::too_many_arguments)]
     pub fn collect_filtered_visible_nodes(
         trace: &DynTraceData,
         expanded_nodes: &HashSet<u64>,
+        hidden_roots: &HashSet<u64>,
+        hidden_types: &HashSet<String>,
+        pinned_rows: &HashSet<u64>,
+        clock_stride: Option<ClockStride>,
+        column_filters: &ColumnFilters,
         cache: &mut TreeCache,
         viewport_scroll_offset: f32,
         viewport_height: f32,
         viewport_start_clk: i64,
         viewport_end_clk: i64,
         active_sort: Option<SortSpec>,
+        hide_empty_parents: bool,
     ) -> Vec<VisibleNode> {
         // Use the new strategy-based traversal system with viewport filter and optional sorting
         let filtered_nodes = crate::domain::tree_operations::collect_viewport_filtered_nodes_with_sort(
             trace,
             expanded_nodes,
+            hidden_roots,
+            hidden_types,
+            clock_stride,
+            column_filters,
             cache,
             active_sort,
             viewport_start_clk,
             viewport_end_clk,
+            hide_empty_parents,
         );
+        let filtered_nodes = crate::domain::tree_operations::retain_pinned_rows(filtered_nodes, pinned_rows);
 
         // Update cache with filtered node count
         cache.filtered_node_count = Some(filtered_nodes.len());
+        let filter_range = Some((viewport_start_clk, viewport_end_clk));
+        if cache.row_heat.as_ref().is_none_or(|heat| heat.filter_range() != filter_range) {
+            cache.row_heat = Some(RowHeatIndex::build(trace, &filtered_nodes, filter_range));
+        }
 
         // Apply vertical scroll culling
         let row_height = virtual_scrolling::ROW_HEIGHT;
@@ -150,4 +251,50 @@ impl VirtualScrollManager {
             })
             .collect()
     }
+
+    /// Collects swimlane rows visible in the current viewport plus buffer.
+    ///
+    /// The full row list is built once and cached in `TreeCache::swimlane_rows`.
+    /// Returns `(row_index, row)` pairs and the total row count.
+    #[allow(clippy::too_many_arguments)]
+    pub fn collect_swimlane_rows(
+        trace: &DynTraceData,
+        attribute: &str,
+        collapsed_lanes: &HashSet<String>,
+        hidden_roots: &HashSet<u64>,
+        hidden_types: &HashSet<String>,
+        cache: &mut TreeCache,
+        viewport_scroll_offset: f32,
+        viewport_height: f32,
+    ) -> (Vec<(usize, SwimlaneRow)>, usize) {
+        let rows = cache.swimlane_rows.get_or_insert_with(|| {
+            swimlanes::build_swimlane_rows(trace, attribute, collapsed_lanes, hidden_roots, hidden_types)
+        });
+
+        let row_height = virtual_scrolling::ROW_HEIGHT;
+        let first_visible_row = (viewport_scroll_offset / row_height).floor() as usize;
+        let last_visible_row = first_visible_row + (viewport_height / row_height).ceil() as usize;
+        let first_visible_row = first_visible_row.saturating_sub(virtual_scrolling::VIEWPORT_BUFFER_ROWS);
+        let last_visible_row = last_visible_row + virtual_scrolling::VIEWPORT_BUFFER_ROWS;
+
+        let visible = rows
+            .iter()
+            .enumerate()
+            .skip(first_visible_row)
+            .take(last_visible_row.saturating_sub(first_visible_row) + 1)
+            .map(|(index, row)| (index, row.clone()))
+            .collect();
+        (visible, rows.len())
+    }
+
+    /// Calculates (top, bottom) padding for a window of swimlane rows.
+    pub fn calculate_swimlane_padding(rows: &[(usize, SwimlaneRow)], total_rows: usize) -> (f32, f32) {
+        let row_height = virtual_scrolling::ROW_HEIGHT;
+        let first_row = rows.first().map(|(i, _)| *i).unwrap_or(0);
+        let last_row = rows.last().map(|(i, _)| *i).unwrap_or(0);
+        (
+            first_row as f32 * row_height,
+            total_rows.saturating_sub(last_row + 1) as f32 * row_height,
+        )
+    }
 }
@@ -16,7 +16,7 @@ pub fn format_clock(clk: i64) -> String {
     let mut result = String::new();
     let chars: Vec<char> = s.chars().collect();
     for (i, ch) in chars.iter().enumerate() {
-        if i > 0 && (chars.len() - i) % 3 == 0 {
+        if i > 0 && (chars.len() - i).is_multiple_of(3) {
             result.push(',');
         }
         result.push(*ch);
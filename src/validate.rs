@@ -0,0 +1,126 @@
+use rjets::schema::{self, FormatVersion};
+use rjets::validator;
+use rjets::logging;
+use anyhow::Result;
+use std::env;
+
+struct Config {
+    files: Vec<String>,
+    schema_version: Option<FormatVersion>,
+    quiet: bool,
+}
+
+fn parse_args() -> Result<Config> {
+    let mut args: Vec<String> = env::args().collect();
+    let log_level = logging::take_log_level_arg(&mut args).map_err(anyhow::Error::msg)?;
+    logging::init(log_level.unwrap_or(logging::Level::WARN), None);
+    let mut config = Config {
+        files: Vec::new(),
+        schema_version: None,
+        quiet: false,
+    };
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "-schema" => {
+                // Optional version argument; defaults to the current version
+                let mut version = FormatVersion::CURRENT;
+                if i + 1 < args.len() && !args[i + 1].starts_with('-') {
+                    if let Some(parsed) = FormatVersion::parse(&args[i + 1]) {
+                        i += 1;
+                        version = parsed;
+                    }
+                }
+                config.schema_version = Some(version);
+            }
+            "-q" | "-quiet" => {
+                config.quiet = true;
+            }
+            "-h" | "-help" | "--help" => {
+                print_help();
+                std::process::exit(0);
+            }
+            arg if arg.starts_with('-') => {
+                tracing::warn!("Unknown argument: {}", arg);
+            }
+            file => {
+                config.files.push(file.to_string());
+            }
+        }
+        i += 1;
+    }
+
+    Ok(config)
+}
+
+fn print_help() {
+    println!("JETS Trace Validator");
+    println!("Usage: jets-validate [OPTIONS] <FILE>...");
+    println!();
+    println!("Checks each trace against the JETS format and lists every problem found.");
+    println!("A footer provenance block is checked against the content hash.");
+    println!("Exits with status 1 if any file has errors (warnings do not fail).");
+    println!();
+    println!("OPTIONS:");
+    println!("  -schema [VERSION]      Print the JSON Schema for one JETS line and exit");
+    println!("                         (default version: {})", FormatVersion::CURRENT);
+    println!("  -q, -quiet             Print only the per-file summary");
+    println!("  --log-level <LEVEL>    Log verbosity: error, warn, info, debug, trace (default: warn)");
+    println!("  -h, -help, --help      Show this help message");
+}
+
+fn main() -> Result<()> {
+    let config = parse_args()?;
+
+    if let Some(version) = config.schema_version {
+        println!("{}", serde_json::to_string_pretty(&schema::json_schema(version))?);
+        return Ok(());
+    }
+
+    if config.files.is_empty() {
+        print_help();
+        std::process::exit(2);
+    }
+
+    let mut all_valid = true;
+    for file in &config.files {
+        let started = std::time::Instant::now();
+        let report = validator::validate_file(file)?;
+        tracing::info!(file = file.as_str(), lines = report.lines, elapsed_ms = started.elapsed().as_millis(), "validated");
+
+        if !config.quiet {
+            for issue in &report.issues {
+                println!("{}:{}: {}: {}", file, issue.line, issue.severity, issue.message);
+            }
+            let shown = report.issues.len();
+            let total = report.error_count() + report.warning_count();
+            if total > shown {
+                println!("{}: ... {} more issues not shown", file, total - shown);
+            }
+        }
+
+        let version = report
+            .version
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        println!(
+            "{}: {} ({} lines, version {}, {} errors, {} warnings)",
+            file,
+            if report.is_valid() { "OK" } else { "INVALID" },
+            report.lines,
+            version,
+            report.error_count(),
+            report.warning_count()
+        );
+        if let Some(provenance) = &report.provenance {
+            println!("{}: provenance {}", file, provenance);
+        }
+        all_valid &= report.is_valid();
+    }
+
+    if !all_valid {
+        std::process::exit(1);
+    }
+    Ok(())
+}
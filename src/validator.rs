@@ -0,0 +1,526 @@
+//! JETS trace validation.
+//!
+//! The parser stops at the first problem and is lenient where it can be
+//! (unknown fields are ignored). Producers need the opposite: a full list
+//! of everything wrong with their output. The validator checks every line
+//! against the [`crate::schema`] description and then checks the stream
+//! rules that a per-line schema cannot express (header first, footer last,
+//! no forward references, duplicate IDs, end before start). A footer
+//! provenance block is checked against the hash of the lines before it.
+//!
+//! # Examples
+//!
+//! ```
+//! use rjets::validator::validate_reader;
+//!
+//! let trace = r#"{"type":"header","version":"2.0","metadata":{}}
+//! {"type":"record_end","clk":5,"record_id":1}"#;
+//! let report = validate_reader(trace.as_bytes()).unwrap();
+//! assert!(!report.is_valid());
+//! ```
+
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::BufRead;
+
+use crate::compat::{self, LegacyLayout};
+use crate::parser::open_trace_file;
+use crate::provenance::{ContentHasher, Provenance, Verification};
+use crate::schema::{self, FormatVersion, SchemaViolation};
+use crate::traits::ExternalId;
+
+/// Maximum number of issues kept in a report; further issues are only counted.
+pub const MAX_REPORTED_ISSUES: usize = 1000;
+
+/// How serious a validation issue is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Readers cope with it, but it is probably a producer bug
+    Warning,
+    /// The trace does not conform to the format
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A single problem found in a trace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// 1-based line number
+    pub line: usize,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}: {}", self.line, self.severity, self.message)
+    }
+}
+
+/// Result of validating a trace.
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    /// Version declared in the header, if it could be parsed
+    pub version: Option<FormatVersion>,
+    /// Number of lines read (including empty lines)
+    pub lines: usize,
+    /// Issues in line order, at most [`MAX_REPORTED_ISSUES`]
+    pub issues: Vec<ValidationIssue>,
+    /// Outcome of the footer's provenance check, if the footer has a block
+    pub provenance: Option<Verification>,
+    error_count: usize,
+    warning_count: usize,
+}
+
+impl ValidationReport {
+    /// Returns true if no errors were found (warnings are allowed).
+    pub fn is_valid(&self) -> bool {
+        self.error_count == 0
+    }
+
+    /// Total number of errors, including ones not kept in `issues`.
+    pub fn error_count(&self) -> usize {
+        self.error_count
+    }
+
+    /// Total number of warnings, including ones not kept in `issues`.
+    pub fn warning_count(&self) -> usize {
+        self.warning_count
+    }
+
+    fn push(&mut self, line: usize, severity: Severity, message: impl Into<String>) {
+        match severity {
+            Severity::Error => self.error_count += 1,
+            Severity::Warning => self.warning_count += 1,
+        }
+        if self.issues.len() < MAX_REPORTED_ISSUES {
+            self.issues.push(ValidationIssue {
+                line,
+                severity,
+                message: message.into(),
+            });
+        }
+    }
+}
+
+/// What the validator remembers about a record.
+struct SeenRecord {
+    clk: i64,
+    ended: bool,
+}
+
+/// Stream state carried between lines.
+#[derive(Default)]
+struct StreamState {
+    report: ValidationReport,
+    records: HashMap<ExternalId, SeenRecord>,
+    header_line: Option<usize>,
+    /// Set once "header must be first" has been reported
+    header_order_reported: bool,
+    footer_line: Option<usize>,
+    /// Adapter for the header's version, if it is a legacy layout
+    layout: Option<&'static LegacyLayout>,
+    last_clk: Option<i64>,
+    annotations: usize,
+    events: usize,
+    /// Hash of the lines before the footer
+    hasher: ContentHasher,
+}
+
+/// Validates a JETS trace file.
+///
+/// Brotli-compressed files (`.br`) are decompressed transparently.
+pub fn validate_file(file_path: &str) -> Result<ValidationReport> {
+    validate_reader(open_trace_file(file_path)?)
+}
+
+/// Validates JETS lines from any buffered reader.
+///
+/// Only I/O failures are returned as errors; problems with the trace itself
+/// are collected in the report.
+pub fn validate_reader<R: BufRead>(reader: R) -> Result<ValidationReport> {
+    let mut state = StreamState::default();
+
+    for (index, line_result) in reader.lines().enumerate() {
+        let line_num = index + 1;
+        let line = line_result.with_context(|| format!("Failed to read line {}", line_num))?;
+        state.report.lines = line_num;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(footer_line) = state.footer_line {
+            state.report.push(
+                line_num,
+                Severity::Error,
+                format!("line after footer (footer at line {} must be last)", footer_line),
+            );
+        }
+
+        let parsed = serde_json::from_str::<Value>(&line);
+        let is_footer = matches!(&parsed, Ok(Value::Object(o)) if o.get("type").and_then(Value::as_str) == Some("footer"));
+        if !is_footer {
+            state.hasher.update_line(&line);
+        }
+
+        let mut object = match parsed {
+            Ok(Value::Object(object)) => object,
+            Ok(other) => {
+                let found = schema::json_type_name(&other);
+                state.report.push(line_num, Severity::Error, format!("line must be a JSON object, found {}", found));
+                continue;
+            }
+            Err(e) => {
+                state.report.push(line_num, Severity::Error, format!("invalid JSON: {}", e));
+                continue;
+            }
+        };
+
+        // Legacy lines are checked in their upgraded form, like the parser reads them
+        if let Some(layout) = state.layout {
+            layout.upgrade(&mut object);
+        }
+        validate_line(&mut state, line_num, &object);
+    }
+
+    if state.header_line.is_none() {
+        state.report.push(state.report.lines.max(1), Severity::Error, "missing header line");
+    }
+
+    Ok(state.report)
+}
+
+/// Validates one parsed line against the schema and the stream rules.
+fn validate_line(state: &mut StreamState, line_num: usize, object: &Map<String, Value>) {
+    let Some(line_type) = object.get("type").and_then(Value::as_str) else {
+        state.report.push(line_num, Severity::Error, "missing string field 'type'");
+        return;
+    };
+    let Some(spec) = schema::line_spec(line_type) else {
+        state.report.push(line_num, Severity::Error, format!("unknown line type '{}'", line_type));
+        return;
+    };
+
+    if line_type == "header" {
+        check_header(state, line_num, object);
+    } else if state.header_line.is_none() && !state.header_order_reported {
+        state.report.push(line_num, Severity::Error, "header must be the first line");
+        state.header_order_reported = true;
+    }
+
+    let version = state.report.version.unwrap_or(FormatVersion::CURRENT);
+    let violations = spec.check(object, version);
+    let schema_ok = !violations.iter().any(|v| !matches!(v, SchemaViolation::UnknownField(_)));
+    for violation in violations {
+        let severity = match violation {
+            SchemaViolation::UnknownField(_) => Severity::Warning,
+            _ => Severity::Error,
+        };
+        state.report.push(line_num, severity, violation.to_string());
+    }
+    if !schema_ok {
+        return;
+    }
+
+    if let Some(clk) = object.get("clk").and_then(Value::as_i64) {
+        if state.last_clk.is_some_and(|last| clk < last) {
+            state.report.push(
+                line_num,
+                Severity::Warning,
+                format!("clock goes backwards ({} after {})", clk, state.last_clk.unwrap_or_default()),
+            );
+        }
+        state.last_clk = Some(clk);
+    }
+
+    match line_type {
+        "record" => check_record(state, line_num, object),
+        "record_end" => check_record_end(state, line_num, object),
+        "annotation" => {
+            check_reference(state, line_num, object, "annotation");
+            state.annotations += 1;
+        }
+        "event" => {
+            check_reference(state, line_num, object, "event");
+            state.events += 1;
+        }
+        "footer" => check_footer(state, line_num, object),
+        _ => {}
+    }
+}
+
+fn check_header(state: &mut StreamState, line_num: usize, object: &Map<String, Value>) {
+    if let Some(first) = state.header_line {
+        state.report.push(line_num, Severity::Error, format!("duplicate header (first at line {})", first));
+        return;
+    }
+    state.header_line = Some(line_num);
+    if line_num != 1 && !state.header_order_reported {
+        state.report.push(line_num, Severity::Error, "header must be the first line");
+        state.header_order_reported = true;
+    }
+
+    let Some(version) = object.get("version").and_then(Value::as_str) else {
+        return;
+    };
+    match FormatVersion::parse(version) {
+        Some(parsed) => {
+            if parsed.major > FormatVersion::CURRENT.major {
+                state.report.push(
+                    line_num,
+                    Severity::Warning,
+                    format!(
+                        "version {} is newer than the supported version {}",
+                        parsed,
+                        FormatVersion::CURRENT
+                    ),
+                );
+            }
+            state.report.version = Some(parsed);
+            state.layout = compat::layout_for(parsed);
+        }
+        None => {
+            state.report.push(
+                line_num,
+                Severity::Error,
+                format!("version '{}' is not of the form MAJOR.MINOR", version),
+            );
+        }
+    }
+}
+
+/// Reads a record ID field that already passed the schema check.
+fn external_id(object: &Map<String, Value>, field: &str) -> Option<ExternalId> {
+    object
+        .get(field)
+        .filter(|v| !v.is_null())
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+}
+
+fn check_record(state: &mut StreamState, line_num: usize, object: &Map<String, Value>) {
+    let Some(id) = external_id(object, "id") else {
+        return;
+    };
+    if state.records.contains_key(&id) {
+        state.report.push(line_num, Severity::Error, format!("duplicate record ID '{}'", id));
+        return;
+    }
+    if let Some(parent_id) = external_id(object, "parent_id") {
+        if !state.records.contains_key(&parent_id) {
+            state.report.push(
+                line_num,
+                Severity::Error,
+                format!("parent '{}' of record '{}' has not been defined yet", parent_id, id),
+            );
+        }
+    }
+
+    // Readers resolve dependencies after the whole file, so a forward one only warns
+    let dependencies = object.get("depends_on").and_then(Value::as_array).into_iter().flatten();
+    for dependency in dependencies.filter_map(|v| serde_json::from_value::<ExternalId>(v.clone()).ok()) {
+        if !state.records.contains_key(&dependency) {
+            state.report.push(
+                line_num,
+                Severity::Warning,
+                format!("dependency '{}' of record '{}' has not been defined yet", dependency, id),
+            );
+        }
+    }
+
+    let clk = object.get("clk").and_then(Value::as_i64).unwrap_or_default();
+    state.records.insert(id, SeenRecord { clk, ended: false });
+}
+
+fn check_record_end(state: &mut StreamState, line_num: usize, object: &Map<String, Value>) {
+    let Some(record_id) = external_id(object, "record_id") else {
+        return;
+    };
+    let clk = object.get("clk").and_then(Value::as_i64).unwrap_or_default();
+
+    let Some(record) = state.records.get_mut(&record_id) else {
+        state.report.push(line_num, Severity::Error, format!("record_end references unknown record '{}'", record_id));
+        return;
+    };
+    if record.ended {
+        state.report.push(line_num, Severity::Warning, format!("record '{}' ended more than once", record_id));
+    }
+    if clk < record.clk {
+        state.report.push(
+            line_num,
+            Severity::Error,
+            format!("record '{}' ends at {} before it starts at {}", record_id, clk, record.clk),
+        );
+    }
+    record.ended = true;
+}
+
+fn check_reference(state: &mut StreamState, line_num: usize, object: &Map<String, Value>, line_type: &str) {
+    if let Some(record_id) = external_id(object, "record_id") {
+        if !state.records.contains_key(&record_id) {
+            state.report.push(
+                line_num,
+                Severity::Error,
+                format!("{} references unknown record '{}'", line_type, record_id),
+            );
+        }
+    }
+}
+
+fn check_footer(state: &mut StreamState, line_num: usize, object: &Map<String, Value>) {
+    state.footer_line = Some(line_num);
+
+    let totals = [
+        ("total_records", state.records.len()),
+        ("total_annotations", state.annotations),
+        ("total_events", state.events),
+    ];
+    for (field, actual) in totals {
+        if let Some(declared) = object.get(field).and_then(Value::as_u64) {
+            if declared != actual as u64 {
+                state.report.push(
+                    line_num,
+                    Severity::Warning,
+                    format!("{} is {} but the trace contains {}", field, declared, actual),
+                );
+            }
+        }
+    }
+
+    let Some(block) = object.get("provenance") else {
+        return;
+    };
+    let provenance = match serde_json::from_value::<Provenance>(block.clone()) {
+        Ok(provenance) => provenance,
+        Err(e) => {
+            state.report.push(line_num, Severity::Error, format!("invalid provenance block: {}", e));
+            return;
+        }
+    };
+    let verification = Verification::check(provenance, state.hasher.clone().finish());
+    match &verification {
+        Verification::Mismatch { provenance, actual } => state.report.push(
+            line_num,
+            Severity::Error,
+            format!("content hash {} does not match provenance hash {}", actual, provenance.hash),
+        ),
+        Verification::Unsupported(_) => {
+            state.report.push(line_num, Severity::Warning, verification.to_string());
+        }
+        Verification::Verified(_) | Verification::Unsigned => {}
+    }
+    state.report.provenance = Some(verification);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(text: &str) -> ValidationReport {
+        validate_reader(text.as_bytes()).unwrap()
+    }
+
+    fn messages(report: &ValidationReport) -> Vec<String> {
+        report.issues.iter().map(|i| i.to_string()).collect()
+    }
+
+    const HEADER: &str = r#"{"type":"header","version":"2.0","metadata":{}}"#;
+
+    #[test]
+    fn test_valid_trace() {
+        let text = format!(
+            "{}\n{}\n{}\n{}\n{}\n",
+            HEADER,
+            r#"{"type":"record","clk":0,"name":"A","record_type":"T","id":1,"parent_id":null,"description":""}"#,
+            r#"{"type":"event","clk":2,"name":"E","record_id":1,"description":""}"#,
+            r#"{"type":"record_end","clk":5,"record_id":1}"#,
+            r#"{"type":"footer","total_records":1,"total_events":1}"#,
+        );
+        let report = validate(&text);
+        assert!(report.is_valid(), "{:?}", messages(&report));
+        assert_eq!(report.warning_count(), 0);
+        assert_eq!(report.version, Some(FormatVersion::V2_0));
+    }
+
+    #[test]
+    fn test_stream_rule_errors() {
+        let text = format!(
+            "{}\n{}\n{}\n{}\n{}\n",
+            HEADER,
+            r#"{"type":"record","clk":10,"name":"A","record_type":"T","id":1,"parent_id":7,"description":""}"#,
+            r#"{"type":"record","clk":10,"name":"B","record_type":"T","id":1,"parent_id":null,"description":""}"#,
+            r#"{"type":"record_end","clk":5,"record_id":1}"#,
+            r#"{"type":"event","clk":6,"name":"E","record_id":"x","description":""}"#,
+        );
+        let report = validate(&text);
+        assert_eq!(report.error_count(), 4, "{:?}", messages(&report));
+        assert_eq!(report.issues[0].line, 2);
+        assert!(report.issues[1].message.contains("duplicate record ID"));
+    }
+
+    #[test]
+    fn test_forward_dependency_warns() {
+        let text = format!(
+            "{}\n{}\n{}\n",
+            HEADER,
+            r#"{"type":"record","clk":0,"name":"A","record_type":"T","id":1,"parent_id":null,"description":"","depends_on":[2]}"#,
+            r#"{"type":"record","clk":1,"name":"B","record_type":"T","id":2,"parent_id":null,"description":"","depends_on":[1]}"#,
+        );
+        let report = validate(&text);
+        assert!(report.is_valid(), "{:?}", messages(&report));
+        assert_eq!(messages(&report), ["line 2: warning: dependency '2' of record '1' has not been defined yet"]);
+    }
+
+    #[test]
+    fn test_schema_errors_and_unknown_fields() {
+        let text = format!(
+            "{}\n{}\n{}\nnot json\n",
+            HEADER,
+            r#"{"type":"record","clk":"0","name":"A","record_type":"T","id":1,"parent_id":null,"description":"","color":"red"}"#,
+            r#"{"type":"span"}"#,
+        );
+        let report = validate(&text);
+        assert_eq!(report.error_count(), 3, "{:?}", messages(&report));
+        assert_eq!(report.warning_count(), 1);
+    }
+
+    #[test]
+    fn test_header_rules_and_versions() {
+        let report = validate(r#"{"type":"record_end","clk":1,"record_id":1}"#);
+        assert!(messages(&report).iter().any(|m| m.contains("missing header")));
+
+        let report = validate(r#"{"type":"header","version":"9.0","metadata":{}}"#);
+        assert!(report.is_valid());
+        assert_eq!(report.warning_count(), 1);
+
+        // String IDs did not exist in 1.x
+        let text = format!(
+            "{}\n{}\n",
+            r#"{"type":"header","version":"1.0","metadata":{}}"#,
+            r#"{"type":"record","clk":0,"name":"A","record_type":"T","id":"a","parent_id":null,"description":""}"#,
+        );
+        assert!(!validate(&text).is_valid());
+    }
+
+    #[test]
+    fn test_footer_must_be_last() {
+        let text = format!(
+            "{}\n{}\n{}\n",
+            HEADER,
+            r#"{"type":"footer","total_records":3}"#,
+            r#"{"type":"record","clk":0,"name":"A","record_type":"T","id":1,"parent_id":null,"description":""}"#,
+        );
+        let report = validate(&text);
+        assert_eq!(report.error_count(), 1);
+        assert_eq!(report.warning_count(), 1);
+    }
+}
@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 use rand::{Rng, SeedableRng};
 use rand::rngs::StdRng;
-use crate::traits::{TraceReader, TraceData, TraceMetadata, TraceRecord, TraceEvent, DynTraceData, AttributeAccessor};
+use std::task::Poll;
+use crate::traits::{TraceReader, AsyncTraceReader, TraceReadFuture, TraceData, TraceMetadata, TraceRecord, TraceEvent, DynTraceData, AttributeAccessor, RecordsIter};
 
 const DEFAULT_MAX_DEPTH: usize = 5;
 const DEFAULT_MAX_CHILDREN: usize = 10;
@@ -30,28 +31,93 @@ impl VirtualTraceReader {
     }
 }
 
+impl Default for VirtualTraceReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TraceReader for VirtualTraceReader {
     fn read(&self, _file_path: &str) -> anyhow::Result<DynTraceData> {
-        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut generator = VirtualTraceGenerator::new(self);
+        while generator.generate_root() {}
+        Ok(generator.finish())
+    }
+}
+
+impl AsyncTraceReader for VirtualTraceReader {
+    /// Generates the same trace as [`TraceReader::read`], one root per poll,
+    /// so a caller polling once per frame stays responsive meanwhile.
+    fn read_async(&self, _source: &str) -> TraceReadFuture {
+        let mut generator = Some(VirtualTraceGenerator::new(self));
+        Box::pin(std::future::poll_fn(move |cx| {
+            let Some(mut pending) = generator.take() else {
+                return Poll::Ready(Err(anyhow::anyhow!("virtual trace already generated")));
+            };
+            if pending.generate_root() {
+                generator = Some(pending);
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            Poll::Ready(Ok(pending.finish()))
+        }))
+    }
+}
+
+/// Generates the roots of a virtual trace one at a time.
+struct VirtualTraceGenerator {
+    rng: StdRng,
+    max_depth: usize,
+    max_children: usize,
+    num_roots: usize,
+    roots: Vec<VirtualTraceRecord>,
+    next_id: u64,
+}
+
+impl VirtualTraceGenerator {
+    fn new(reader: &VirtualTraceReader) -> Self {
+        let mut rng = StdRng::seed_from_u64(reader.seed);
 
         // Generate 1-5 root records
         let num_roots = rng.gen_range(1..=5);
-        let mut roots = Vec::new();
-        let mut next_id = 1;
-
-        for _ in 0..num_roots {
-            let record = VirtualTraceRecord::generate(&mut rng, next_id, None, 0, 0, self.max_depth, self.max_children, &mut next_id);
-            roots.push(record);
-            next_id += 1; // Increment for next root
+        Self {
+            rng,
+            max_depth: reader.max_depth,
+            max_children: reader.max_children,
+            num_roots,
+            roots: Vec::new(),
+            next_id: 1,
         }
+    }
 
-        Ok(DynTraceData::Virtual(VirtualTraceData::new(roots)))
+    /// Generates the next root with its subtree; false once all are generated.
+    fn generate_root(&mut self) -> bool {
+        if self.roots.len() == self.num_roots {
+            return false;
+        }
+        let record = VirtualTraceRecord::generate(
+            &mut self.rng,
+            self.next_id,
+            None,
+            0,
+            0,
+            self.max_depth,
+            self.max_children,
+            &mut self.next_id,
+        );
+        self.roots.push(record);
+        self.next_id += 1; // Increment for next root
+        true
+    }
+
+    fn finish(self) -> DynTraceData {
+        DynTraceData::Virtual(VirtualTraceData::new(self.roots))
     }
 }
 
 #[derive(Clone)]
 pub struct VirtualTraceData {
-    roots: Vec<VirtualTraceRecord>,
+    root_ids: Vec<u64>,
     records_by_id: HashMap<u64, VirtualTraceRecord>,
     trace_extent: (i64, i64),
 }
@@ -75,7 +141,7 @@ impl VirtualTraceData {
         let trace_extent = calculate_virtual_trace_extent(&records_by_id);
 
         Self {
-            roots,
+            root_ids: roots.iter().map(|r| r.id).collect(),
             records_by_id,
             trace_extent,
         }
@@ -184,6 +250,10 @@ impl<'a> TraceRecord<'a> for VirtualTraceRecordRef<'a> {
         self.0.name()
     }
 
+    fn record_type(&self) -> String {
+        self.0.record_type()
+    }
+
     fn id(&self) -> u64 {
         self.0.id()
     }
@@ -268,13 +338,17 @@ impl TraceData for VirtualTraceData {
         VirtualTraceDataRef(self)
     }
 
-    fn root_ids(&self) -> Vec<u64> {
-        self.roots.iter().map(|r| r.id).collect()
+    fn roots(&self) -> &[u64] {
+        &self.root_ids
     }
 
     fn get_record(&self, id: u64) -> Option<Self::Record<'_>> {
         self.records_by_id.get(&id).map(VirtualTraceRecordRef)
     }
+
+    fn records_iter(&self) -> RecordsIter<'_, Self::Record<'_>> {
+        Box::new(self.records_by_id.values().map(VirtualTraceRecordRef))
+    }
 }
 
 impl TraceMetadata for VirtualTraceData {
@@ -317,6 +391,7 @@ impl TraceMetadata for VirtualTraceData {
 pub struct VirtualTraceRecord {
     id: u64,
     name: String,
+    record_type: String,
     description: String,
     clk: i64,
     end_clk: Option<i64>,
@@ -328,6 +403,7 @@ pub struct VirtualTraceRecord {
 }
 
 impl VirtualTraceRecord {
+    #[allow(clippy::too_many_arguments)]
     fn generate(
         rng: &mut StdRng,
         id: u64,
@@ -385,6 +461,7 @@ impl VirtualTraceRecord {
         Self {
             id,
             name,
+            record_type: format!("Level{}", depth),
             description,
             clk,
             end_clk: Some(end_clk),
@@ -416,6 +493,10 @@ impl<'a> TraceRecord<'a> for &'a VirtualTraceRecord {
         self.name.clone()
     }
 
+    fn record_type(&self) -> String {
+        self.record_type.clone()
+    }
+
     fn id(&self) -> u64 {
         self.id
     }
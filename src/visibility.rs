@@ -8,7 +8,11 @@
 //! visibility policy (implemented per strategy), making it easy to add new
 //! filtering modes without duplicating traversal logic.
 
-use rjets::TraceRecord;
+use crate::column_filters::ColumnFilters;
+use crate::TraceRecord;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
 
 /// Provider for custom child ordering.
@@ -18,20 +22,25 @@ use std::marker::PhantomData;
 pub trait ChildIndexProvider<'a, R: TraceRecord<'a>> {
     /// Returns custom child indices for a parent, or None for natural order.
     ///
+    /// Indices refer to the natural order (`child_at`), so a strategy's
+    /// [`VisibilityStrategy::child_window_hint`] can still be applied to them.
+    /// Providers backed by a cache should borrow from it: the traversal asks
+    /// once per expanded parent on every frame.
+    ///
     /// # Arguments
     /// * `parent` - The parent record
     /// * `depth` - Current depth in the tree
     ///
     /// # Returns
-    /// Some(Vec<usize>) for custom ordering, None for natural 0..num_children order
-    fn child_indices(&self, parent: &R, depth: usize) -> Option<Vec<usize>>;
+    /// Some(indices) for custom ordering, None for natural 0..num_children order
+    fn child_indices(&self, parent: &R, depth: usize) -> Option<Cow<'_, [usize]>>;
 }
 
 /// Default child index provider that uses natural ordering.
 pub struct NaturalChildOrder;
 
 impl<'a, R: TraceRecord<'a>> ChildIndexProvider<'a, R> for NaturalChildOrder {
-    fn child_indices(&self, _parent: &R, _depth: usize) -> Option<Vec<usize>> {
+    fn child_indices(&self, _parent: &R, _depth: usize) -> Option<Cow<'_, [usize]>> {
         None
     }
 }
@@ -56,7 +65,6 @@ pub struct VisibleNode<'a, R: TraceRecord<'a>> {
     /// Depth in the tree hierarchy (0 for root)
     pub depth: usize,
     /// Whether this is a parent or leaf node
-    #[allow(dead_code)]
     pub kind: NodeKind,
     /// Tree branch context: For each depth level (0 to depth-1), indicates
     /// whether there are more siblings below this node at that level.
@@ -114,6 +122,10 @@ pub trait VisibilityStrategy<'a, R: TraceRecord<'a>> {
     /// (e.g., via binary search on sorted children), it can return an index range
     /// here. The traversal may use this to limit child iteration.
     ///
+    /// The range is over natural child indices, whatever order the children
+    /// are shown in; with a custom [`ChildIndexProvider`] the traversal keeps
+    /// the custom indices that fall inside it.
+    ///
     /// # Arguments
     /// * `_parent` - The parent record whose children are being considered
     /// * `_depth` - Current depth in the tree
@@ -191,46 +203,17 @@ impl<'a, R: TraceRecord<'a>> VisibilityStrategy<'a, R> for ViewportFilterStrateg
             return None;
         }
 
-        // Check if first child is a leaf (subtree_depth == 0)
+        // Check if first child is a leaf
         let first_child = parent.child_at(0)?;
-        if first_child.subtree_depth() != 0 {
+        if first_child.num_children() != 0 {
             // Children are not leaves, must visit all to find visible descendants
             return None;
         }
 
-        // Binary search for first child with clk >= start
-        let mut left = 0;
-        let mut right = num_children;
-        while left < right {
-            let mid = left + (right - left) / 2;
-            if let Some(child) = parent.child_at(mid) {
-                if child.clk() < self.start {
-                    left = mid + 1;
-                } else {
-                    right = mid;
-                }
-            } else {
-                break;
-            }
-        }
-        let first_idx = left;
-
-        // Binary search for last child with clk <= end
-        left = 0;
-        right = num_children;
-        while left < right {
-            let mid = left + (right - left) / 2;
-            if let Some(child) = parent.child_at(mid) {
-                if child.clk() <= self.end {
-                    left = mid + 1;
-                } else {
-                    right = mid;
-                }
-            } else {
-                break;
-            }
-        }
-        let last_idx = if left == 0 { 0 } else { left - 1 };
+        // Children are sorted by clock: find those with clk in [start, end]
+        let first_idx = partition_children(parent, num_children, |clk| clk < self.start);
+        let end_idx = partition_children(parent, num_children, |clk| clk <= self.end);
+        let last_idx = end_idx.saturating_sub(1);
 
         // Return the window if there's overlap
         if first_idx <= last_idx && last_idx < num_children {
@@ -241,6 +224,188 @@ impl<'a, R: TraceRecord<'a>> VisibilityStrategy<'a, R> for ViewportFilterStrateg
     }
 }
 
+/// Returns the index of the first child whose clock fails `pred`.
+///
+/// Children must be sorted by clock so that `pred` holds for a prefix.
+fn partition_children<'a, R: TraceRecord<'a>>(parent: &R, num_children: usize, pred: impl Fn(i64) -> bool) -> usize {
+    let mut left = 0;
+    let mut right = num_children;
+    while left < right {
+        let mid = left + (right - left) / 2;
+        match parent.child_at(mid) {
+            Some(child) if pred(child.clk()) => left = mid + 1,
+            Some(_) => right = mid,
+            None => break,
+        }
+    }
+    left
+}
+
+/// Record-type filter layered on top of another strategy.
+///
+/// Records whose `record_type` is hidden are dropped together with their
+/// subtrees; everything else is decided by the base strategy.
+pub struct RecordTypeFilterStrategy<'s, S> {
+    /// Strategy deciding visibility of records with a shown type
+    pub base: S,
+    /// Record types to hide
+    pub hidden_types: &'s HashSet<String>,
+}
+
+impl<S> RecordTypeFilterStrategy<'_, S> {
+    fn is_hidden<'a, R: TraceRecord<'a>>(&self, record: &R) -> bool {
+        self.hidden_types.contains(&record.record_type())
+    }
+}
+
+impl<'a, R: TraceRecord<'a>, S: VisibilityStrategy<'a, R>> VisibilityStrategy<'a, R> for RecordTypeFilterStrategy<'_, S> {
+    fn include_parent(&self, parent: &R, depth: usize) -> bool {
+        !self.is_hidden(parent) && self.base.include_parent(parent, depth)
+    }
+
+    fn include_leaf(&self, leaf: &R, depth: usize) -> bool {
+        !self.is_hidden(leaf) && self.base.include_leaf(leaf, depth)
+    }
+
+    fn descend_into(&self, parent: &R, depth: usize) -> bool {
+        !self.is_hidden(parent) && self.base.descend_into(parent, depth)
+    }
+
+    fn child_window_hint(&self, parent: &R, depth: usize) -> Option<(usize, usize)> {
+        self.base.child_window_hint(parent, depth)
+    }
+}
+
+/// Clock residue class `clk ≡ offset (mod stride)`.
+///
+/// Used to look at one phase of a multi-cycle clock domain, e.g. only the
+/// records starting on even cycles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockStride {
+    pub stride: i64,
+    pub offset: i64,
+}
+
+impl ClockStride {
+    /// Returns true if `clk` is in the residue class (a stride below 2 matches every clock).
+    pub fn matches(&self, clk: i64) -> bool {
+        self.stride <= 1 || (clk - self.offset).rem_euclid(self.stride) == 0
+    }
+}
+
+/// Clock stride filter layered on top of another strategy.
+///
+/// Leaves whose start clock is outside the residue class are dropped.
+/// Parents are kept as anchors so matching descendants stay reachable.
+pub struct ClockStrideFilterStrategy<S> {
+    /// Strategy deciding visibility of matching records
+    pub base: S,
+    pub stride: ClockStride,
+}
+
+impl<'a, R: TraceRecord<'a>, S: VisibilityStrategy<'a, R>> VisibilityStrategy<'a, R> for ClockStrideFilterStrategy<S> {
+    fn include_parent(&self, parent: &R, depth: usize) -> bool {
+        self.base.include_parent(parent, depth)
+    }
+
+    fn include_leaf(&self, leaf: &R, depth: usize) -> bool {
+        self.stride.matches(leaf.clk()) && self.base.include_leaf(leaf, depth)
+    }
+
+    fn descend_into(&self, parent: &R, depth: usize) -> bool {
+        self.base.descend_into(parent, depth)
+    }
+
+    fn child_window_hint(&self, parent: &R, depth: usize) -> Option<(usize, usize)> {
+        self.base.child_window_hint(parent, depth)
+    }
+}
+
+/// Column filters layered on top of another strategy.
+///
+/// Leaves failing a column filter are dropped. Parents are kept as anchors
+/// so matching descendants stay reachable.
+pub struct ColumnFilterStrategy<'s, S> {
+    /// Strategy deciding visibility of matching records
+    pub base: S,
+    pub filters: &'s ColumnFilters,
+}
+
+impl<'a, R: TraceRecord<'a>, S: VisibilityStrategy<'a, R>> VisibilityStrategy<'a, R> for ColumnFilterStrategy<'_, S> {
+    fn include_parent(&self, parent: &R, depth: usize) -> bool {
+        self.base.include_parent(parent, depth)
+    }
+
+    fn include_leaf(&self, leaf: &R, depth: usize) -> bool {
+        self.filters.matches(leaf) && self.base.include_leaf(leaf, depth)
+    }
+
+    fn descend_into(&self, parent: &R, depth: usize) -> bool {
+        self.base.descend_into(parent, depth)
+    }
+
+    fn child_window_hint(&self, parent: &R, depth: usize) -> Option<(usize, usize)> {
+        self.base.child_window_hint(parent, depth)
+    }
+}
+
+/// Drops parents without a visible leaf anywhere below them.
+///
+/// Filters that only judge leaves keep every parent as an anchor, which
+/// leaves chains of empty parents in the tree. This wrapper looks ahead into
+/// each parent's subtree (collapsed or not) with the base strategy and keeps
+/// the parent only if some leaf there would be shown when expanded. Results
+/// are memoized per record, so nested parents share one search.
+pub struct EmptyParentFilterStrategy<S> {
+    /// Strategy deciding visibility of leaves and descent
+    pub base: S,
+    has_visible_leaf: RefCell<HashMap<u64, bool>>,
+}
+
+impl<S> EmptyParentFilterStrategy<S> {
+    pub fn new(base: S) -> Self {
+        EmptyParentFilterStrategy { base, has_visible_leaf: RefCell::new(HashMap::new()) }
+    }
+
+    /// Returns true if the base strategy shows a leaf somewhere below `parent`.
+    fn has_visible_leaf<'a, R: TraceRecord<'a>>(&self, parent: &R, depth: usize) -> bool
+    where
+        S: VisibilityStrategy<'a, R>,
+    {
+        if let Some(&known) = self.has_visible_leaf.borrow().get(&parent.id()) {
+            return known;
+        }
+        let found = self.base.descend_into(parent, depth) && {
+            let (start, end) = self.base.child_window_hint(parent, depth).unwrap_or((0, parent.num_children()));
+            (start..end.min(parent.num_children())).any(|i| match parent.child_at(i) {
+                Some(child) if child.num_children() == 0 => self.base.include_leaf(&child, depth + 1),
+                Some(child) => self.base.include_parent(&child, depth + 1) && self.has_visible_leaf(&child, depth + 1),
+                None => false,
+            })
+        };
+        self.has_visible_leaf.borrow_mut().insert(parent.id(), found);
+        found
+    }
+}
+
+impl<'a, R: TraceRecord<'a>, S: VisibilityStrategy<'a, R>> VisibilityStrategy<'a, R> for EmptyParentFilterStrategy<S> {
+    fn include_parent(&self, parent: &R, depth: usize) -> bool {
+        self.base.include_parent(parent, depth) && self.has_visible_leaf(parent, depth)
+    }
+
+    fn include_leaf(&self, leaf: &R, depth: usize) -> bool {
+        self.base.include_leaf(leaf, depth)
+    }
+
+    fn descend_into(&self, parent: &R, depth: usize) -> bool {
+        self.base.descend_into(parent, depth) && self.has_visible_leaf(parent, depth)
+    }
+
+    fn child_window_hint(&self, parent: &R, depth: usize) -> Option<(usize, usize)> {
+        self.base.child_window_hint(parent, depth)
+    }
+}
+
 /// Stack frame for iterative depth-first traversal.
 #[derive(Clone)]
 struct TraversalFrame<'a, R: TraceRecord<'a>> {
@@ -249,8 +414,6 @@ struct TraversalFrame<'a, R: TraceRecord<'a>> {
     /// If Some, we've already yielded this parent and are processing children
     /// at the given index. If None, we haven't processed this node yet.
     child_index: Option<usize>,
-    /// For each ancestor level, whether there are more siblings below
-    branch_context: Vec<bool>,
     /// Whether this node is the last child of its parent
     is_last_child: bool,
     /// Phantom data to mark the lifetime
@@ -262,8 +425,15 @@ struct TraversalFrame<'a, R: TraceRecord<'a>> {
 /// This iterator performs a depth-first traversal using an explicit stack
 /// to avoid recursion and enable lazy evaluation. It consults the strategy
 /// at each step to determine visibility and whether to descend.
+///
+/// Frames carry no branch context. The iterator keeps the one of the
+/// current path instead, and a node gets a copy of it only when yielded, so
+/// children that are walked past (filtered leaves) cost no allocation.
 pub struct TraversalIter<'a, R: TraceRecord<'a>, S: VisibilityStrategy<'a, R>, P: ChildIndexProvider<'a, R>> {
     stack: Vec<TraversalFrame<'a, R>>,
+    /// For each depth of the current path, whether the ancestor there has
+    /// more siblings below (entry `d` is pushed when descending from depth `d`)
+    branch_path: Vec<bool>,
     strategy: &'a S,
     child_index_provider: P,
 }
@@ -285,7 +455,6 @@ impl<'a, R: TraceRecord<'a>, S: VisibilityStrategy<'a, R>, P: ChildIndexProvider
                 record,
                 depth: 0,
                 child_index: None,
-                branch_context: Vec::new(),
                 is_last_child: i == num_roots - 1,
                 _phantom: PhantomData,
             })
@@ -293,7 +462,7 @@ impl<'a, R: TraceRecord<'a>, S: VisibilityStrategy<'a, R>, P: ChildIndexProvider
 
         stack.reverse();
 
-        TraversalIter { stack, strategy, child_index_provider: provider }
+        TraversalIter { stack, branch_path: Vec::new(), strategy, child_index_provider: provider }
     }
 }
 
@@ -304,6 +473,8 @@ impl<'a, R: TraceRecord<'a>, S: VisibilityStrategy<'a, R>, P: ChildIndexProvider
         while let Some(frame) = self.stack.pop() {
             let depth = frame.depth;
             let num_children = frame.record.num_children();
+            // Entries deeper than this belong to subtrees already finished
+            self.branch_path.truncate(depth);
 
             // Determine if this is a parent or leaf
             let is_parent = num_children > 0;
@@ -316,48 +487,39 @@ impl<'a, R: TraceRecord<'a>, S: VisibilityStrategy<'a, R>, P: ChildIndexProvider
                     // Check if we should descend into children
                     let should_descend = self.strategy.descend_into(&frame.record, depth);
 
-                    // Clone frame data that we'll need
-                    let parent_branch_context = frame.branch_context.clone();
                     let parent_is_last_child = frame.is_last_child;
                     let parent_record = frame.record;
 
                     if should_descend {
-                        // Get custom child ordering if available, otherwise use natural order
-                        let ordered_indices: Vec<usize> = if let Some(custom) = self.child_index_provider.child_indices(&parent_record, depth) {
-                            custom
-                        } else {
-                            // Use natural order, optionally filtered by window hint
-                            if let Some((start, end)) = self.strategy.child_window_hint(&parent_record, depth) {
-                                (start..end.min(num_children)).collect()
-                            } else {
-                                (0..num_children).collect()
-                            }
-                        };
-
-                        // Collect indices in reverse for stack (LIFO order)
-                        let child_indices: Vec<(usize, usize)> = ordered_indices.into_iter().rev().enumerate().collect();
-
-                        // Collect all children with clones - use a for loop to avoid closure lifetime issues
-                        let mut children_to_push = Vec::new();
-                        for (child_idx, i) in child_indices {
+                        // Get custom child ordering if available, otherwise use natural order.
+                        // A window hint is a range of natural (clock-sorted) indices, so a
+                        // custom order keeps the children whose index is inside the range
+                        // rather than taking a slice of its own positions.
+                        let window = self.strategy.child_window_hint(&parent_record, depth);
+                        let range = window.map_or(0..num_children, |(start, end)| start..end.min(num_children));
+                        let custom = self.child_index_provider.child_indices(&parent_record, depth);
+
+                        self.branch_path.push(!parent_is_last_child);
+
+                        // Children are pushed last to first (LIFO order), so the
+                        // first one pushed is the last child
+                        let stack = &mut self.stack;
+                        let first_pushed = stack.len();
+                        let mut push_child = |i: usize| {
                             if let Some(child) = parent_record.child_at(i) {
-                                let mut child_branch_context = parent_branch_context.clone();
-                                child_branch_context.push(!parent_is_last_child);
-                                let is_last = child_idx == 0;
-                                children_to_push.push((child.clone(), depth + 1, child_branch_context, is_last));
+                                let is_last_child = stack.len() == first_pushed;
+                                stack.push(TraversalFrame {
+                                    record: child,
+                                    depth: depth + 1,
+                                    child_index: None,
+                                    is_last_child,
+                                    _phantom: PhantomData,
+                                });
                             }
-                        }
-
-                        // Now push all children (parent_record is no longer borrowed)
-                        for (child_record, child_depth, child_branch_context, is_last) in children_to_push {
-                            self.stack.push(TraversalFrame {
-                                record: child_record,
-                                depth: child_depth,
-                                child_index: None,
-                                branch_context: child_branch_context,
-                                is_last_child: is_last,
-                                _phantom: PhantomData,
-                            });
+                        };
+                        match &custom {
+                            Some(order) => order.iter().rev().filter(|i| range.contains(i)).for_each(|&i| push_child(i)),
+                            None => range.clone().rev().for_each(push_child),
                         }
                     }
 
@@ -367,7 +529,7 @@ impl<'a, R: TraceRecord<'a>, S: VisibilityStrategy<'a, R>, P: ChildIndexProvider
                             record: parent_record.clone(), // Clone to avoid lifetime issues
                             depth,
                             kind: NodeKind::Parent,
-                            branch_context: parent_branch_context,
+                            branch_context: self.branch_path[..depth].to_vec(),
                             is_last_child: parent_is_last_child,
                             _phantom: PhantomData,
                         });
@@ -385,7 +547,7 @@ impl<'a, R: TraceRecord<'a>, S: VisibilityStrategy<'a, R>, P: ChildIndexProvider
                         record: frame.record,
                         depth,
                         kind: NodeKind::Leaf,
-                        branch_context: frame.branch_context,
+                        branch_context: self.branch_path[..depth].to_vec(),
                         is_last_child: frame.is_last_child,
                         _phantom: PhantomData,
                     });
@@ -415,7 +577,7 @@ impl<'a, R: TraceRecord<'a>, S: VisibilityStrategy<'a, R>, P: ChildIndexProvider
 /// use jets::domain::visibility::{traverse_visible, UnfilteredStrategy};
 ///
 /// let strategy = UnfilteredStrategy;
-/// let roots = trace.root_ids().iter()
+/// let roots = trace.roots().iter()
 ///     .filter_map(|&id| trace.get_record(id));
 ///
 /// for node in traverse_visible(roots, &strategy) {
@@ -463,7 +625,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rjets::TraceEvent;
+    use crate::TraceEvent;
 
     // Mock TraceRecord for testing
     #[derive(Clone)]
@@ -475,9 +637,9 @@ mod tests {
 
     // Mock TraceEvent for testing
     #[derive(Clone, Copy)]
-    struct MockEvent<'a>(&'a ());
+    struct MockEvent<'a>(std::marker::PhantomData<&'a ()>);
 
-    impl<'a> rjets::AttributeAccessor for MockEvent<'a> {
+    impl crate::AttributeAccessor for MockEvent<'_> {
         fn attr_count(&self) -> u64 { 0 }
         fn attr(&self, _key: &str) -> Option<serde_json::Value> { None }
         fn attr_at(&self, _index: u64) -> Option<(String, serde_json::Value)> { None }
@@ -491,7 +653,7 @@ mod tests {
         fn description(&self) -> String { "".to_string() }
     }
 
-    impl<'a> rjets::AttributeAccessor for &'a MockRecord {
+    impl crate::AttributeAccessor for &MockRecord {
         fn attr_count(&self) -> u64 { 0 }
         fn attr(&self, _key: &str) -> Option<serde_json::Value> { None }
         fn attr_at(&self, _index: u64) -> Option<(String, serde_json::Value)> { None }
@@ -512,6 +674,9 @@ mod tests {
         fn name(&self) -> String {
             "mock".to_string()
         }
+        fn record_type(&self) -> String {
+            "Mock".to_string()
+        }
         fn id(&self) -> u64 {
             self.id
         }
@@ -588,6 +753,47 @@ mod tests {
         assert!(!strategy.descend_into(&&parent_after_end, 0));
     }
 
+    #[test]
+    fn test_record_type_filter_hides_type_and_subtree() {
+        let root = MockRecord {
+            id: 1,
+            clk: 0,
+            children: vec![MockRecord { id: 2, clk: 10, children: vec![] }],
+        };
+
+        let hidden: HashSet<String> = ["Mock".to_string()].into_iter().collect();
+        let strategy = RecordTypeFilterStrategy { base: UnfilteredStrategy, hidden_types: &hidden };
+        assert!(!strategy.include_parent(&&root, 0));
+        assert!(!strategy.descend_into(&&root, 0));
+        assert_eq!(traverse_visible(vec![&root], &strategy).count(), 0);
+
+        let none_hidden = HashSet::new();
+        let strategy = RecordTypeFilterStrategy { base: UnfilteredStrategy, hidden_types: &none_hidden };
+        assert_eq!(traverse_visible(vec![&root], &strategy).count(), 2);
+    }
+
+    #[test]
+    fn test_clock_stride_filter_keeps_matching_leaves() {
+        let stride = ClockStride { stride: 4, offset: 1 };
+        assert!(stride.matches(-3));
+        assert!(stride.matches(9));
+        assert!(!stride.matches(8));
+        assert!(ClockStride { stride: 1, offset: 5 }.matches(8));
+
+        let root = MockRecord {
+            id: 1,
+            clk: 0,
+            children: vec![
+                MockRecord { id: 2, clk: 5, children: vec![] },
+                MockRecord { id: 3, clk: 6, children: vec![] },
+                MockRecord { id: 4, clk: 13, children: vec![] },
+            ],
+        };
+        let strategy = ClockStrideFilterStrategy { base: UnfilteredStrategy, stride };
+        let ids: Vec<u64> = traverse_visible(vec![&root], &strategy).map(|node| node.record.id()).collect();
+        assert_eq!(ids, vec![1, 2, 4]);
+    }
+
     #[test]
     fn test_viewport_filter_child_window_hint() {
         let strategy = ViewportFilterStrategy { start: 100, end: 200 };
@@ -609,6 +815,93 @@ mod tests {
         assert_eq!(hint, Some((1, 4))); // Indices 1, 2, 3 (clk 100, 150, 200)
     }
 
+    /// Orders children by descending clock, like a sort on the start column.
+    struct ReverseChildOrder;
+
+    impl<'a> ChildIndexProvider<'a, &'a MockRecord> for ReverseChildOrder {
+        fn child_indices(&self, parent: &&'a MockRecord, _depth: usize) -> Option<Cow<'_, [usize]>> {
+            Some(Cow::Owned((0..parent.children.len()).rev().collect()))
+        }
+    }
+
+    fn leaf_parent() -> MockRecord {
+        MockRecord {
+            id: 1,
+            clk: 0,
+            children: [50, 100, 150, 200, 250]
+                .into_iter()
+                .enumerate()
+                .map(|(i, clk)| MockRecord { id: 2 + i as u64, clk, children: vec![] })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_sorted_children_with_viewport_filter_keep_window_leaves() {
+        let parent = leaf_parent();
+        let strategy = ViewportFilterStrategy { start: 100, end: 200 };
+
+        // The window (natural indices 1..4) selects children, the provider orders them
+        let ids: Vec<u64> = traverse_visible_with_order(vec![&parent], &strategy, ReverseChildOrder)
+            .map(|node| node.record.id())
+            .collect();
+        assert_eq!(ids, vec![1, 5, 4, 3]);
+
+        let nodes: Vec<_> = traverse_visible_with_order(vec![&parent], &strategy, ReverseChildOrder).collect();
+        assert!(nodes[3].is_last_child && !nodes[1].is_last_child);
+
+        // Same rows as the natural order, only reordered
+        let natural: HashSet<u64> = traverse_visible(vec![&parent], &strategy).map(|node| node.record.id()).collect();
+        assert_eq!(natural, ids.into_iter().collect());
+    }
+
+    #[test]
+    fn test_sorted_children_with_layered_filters() {
+        let parent = leaf_parent();
+        let hidden = HashSet::new();
+        let strategy = ClockStrideFilterStrategy {
+            base: RecordTypeFilterStrategy {
+                base: ViewportFilterStrategy { start: 60, end: 260 },
+                hidden_types: &hidden,
+            },
+            stride: ClockStride { stride: 100, offset: 0 },
+        };
+
+        // Window 100..=250 passes through both wrappers; the stride keeps 100 and 200
+        let ids: Vec<u64> = traverse_visible_with_order(vec![&parent], &strategy, ReverseChildOrder)
+            .map(|node| node.record.id())
+            .collect();
+        assert_eq!(ids, vec![1, 5, 3]);
+    }
+
+    #[test]
+    fn test_branch_context_follows_path_past_filtered_leaves() {
+        let leaf = |id, clk| MockRecord { id, clk, children: vec![] };
+        let first = MockRecord {
+            id: 1,
+            clk: 0,
+            children: vec![MockRecord { id: 2, clk: 10, children: vec![leaf(3, 0), leaf(4, 10)] }, leaf(5, 10)],
+        };
+        let second = MockRecord { id: 6, clk: 10, children: vec![leaf(7, 10)] };
+
+        // Leaf 3 is outside the window; its sibling still sees the same path
+        let strategy = ViewportFilterStrategy { start: 5, end: 100 };
+        let rows: Vec<(u64, Vec<bool>, bool)> = traverse_visible(vec![&first, &second], &strategy)
+            .map(|node| (node.record.id(), node.branch_context, node.is_last_child))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (1, vec![], false),
+                (2, vec![true], false),
+                (4, vec![true, true], true),
+                (5, vec![true], true),
+                (6, vec![], true),
+                (7, vec![false], true),
+            ]
+        );
+    }
+
     #[test]
     fn test_traverse_visible_unfiltered_simple() {
         let strategy = UnfilteredStrategy;
@@ -733,6 +1026,30 @@ mod tests {
         assert_eq!(nodes[2].kind, NodeKind::Leaf);
     }
 
+    #[test]
+    fn test_empty_parent_filter_drops_parents_without_visible_leaves() {
+        let strategy = EmptyParentFilterStrategy::new(ViewportFilterStrategy { start: 100, end: 200 });
+        let leaf = |id, clk| MockRecord { id, clk, children: vec![] };
+        let root = MockRecord {
+            id: 1,
+            clk: 0,
+            children: vec![
+                // Empty chain: nothing below starts in the viewport
+                MockRecord { id: 2, clk: 10, children: vec![MockRecord { id: 3, clk: 20, children: vec![leaf(4, 30)] }] },
+                // Visible leaf two levels down
+                MockRecord { id: 5, clk: 90, children: vec![MockRecord { id: 6, clk: 95, children: vec![leaf(7, 150)] }] },
+                // Starts after the viewport
+                MockRecord { id: 8, clk: 300, children: vec![leaf(9, 310)] },
+            ],
+        };
+
+        let ids: Vec<u64> = traverse_visible(vec![&root], &strategy).map(|node| node.record.id()).collect();
+        assert_eq!(ids, vec![1, 5, 6, 7]);
+
+        let lonely = MockRecord { id: 10, clk: 0, children: vec![leaf(11, 50)] };
+        assert_eq!(traverse_visible(vec![&lonely], &strategy).count(), 0, "an empty root is dropped too");
+    }
+
     #[test]
     fn test_traverse_visible_empty() {
         let strategy = UnfilteredStrategy;
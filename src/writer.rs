@@ -1,15 +1,21 @@
 use serde::Serialize;
 use std::fs::File;
-use std::io::{BufWriter, Write};
+use std::io::{self, BufWriter, Write};
 use anyhow::{Result, Context};
 use brotli::enc::BrotliEncoderParams;
 use brotli::CompressorWriter;
+use crate::counter::{COUNTER_RECORD_TYPE, COUNTER_SAMPLE_EVENT, COUNTER_VALUE_ATTR};
+use crate::provenance::{ContentHasher, Provenance, PROVENANCE_ALGORITHM};
+use crate::schema::FormatVersion;
+use crate::traits::{AttributeAccessor, ExternalId, TraceData, TraceEvent, TraceMetadata, TraceRecord};
 
 pub struct TraceWriter {
-    writer: Box<dyn Write>,
+    writer: Output,
     record_count: usize,
     annotation_count: usize,
     event_count: usize,
+    /// Producer name and hash of the lines written so far, if provenance is on
+    provenance: Option<(String, ContentHasher)>,
 }
 
 impl TraceWriter {
@@ -40,18 +46,11 @@ impl TraceWriter {
         let file = File::create(file_path)
             .with_context(|| format!("Failed to create file: {}", file_path))?;
 
-        let writer: Box<dyn Write> = if file_path.ends_with(".br") {
-            // Brotli compression enabled
-            let buf_writer = BufWriter::new(file);
-            let params = BrotliEncoderParams {
-                quality: 6,  // Balanced compression
-                lgwin: 22,   // Window size
-                ..Default::default()
-            };
-            Box::new(CompressorWriter::with_params(buf_writer, 4096, &params))
+        let writer = if file_path.ends_with(".br") {
+            Output::Brotli(Box::new(BrotliWriter::new(BufWriter::new(file))))
         } else {
             // No compression
-            Box::new(BufWriter::new(file))
+            Output::Plain(Box::new(BufWriter::new(file)))
         };
 
         Ok(TraceWriter {
@@ -59,9 +58,55 @@ impl TraceWriter {
             record_count: 0,
             annotation_count: 0,
             event_count: 0,
+            provenance: None,
         })
     }
 
+    /// Creates a TraceWriter that emits lines into an arbitrary writer.
+    ///
+    /// No compression is applied; wrap the writer yourself if needed. This is
+    /// useful for embedding a trace in another container or writing to memory.
+    pub fn from_writer(writer: Box<dyn Write>) -> Self {
+        Self::with_output(Output::Plain(writer))
+    }
+
+    /// Creates a TraceWriter that emits lines into a Brotli stream.
+    ///
+    /// Lets a container write its own lines into the stream before the trace;
+    /// [`finish`](Self::finish) then ends the stream.
+    pub fn from_brotli(writer: BrotliWriter) -> Self {
+        Self::with_output(Output::Brotli(Box::new(writer)))
+    }
+
+    fn with_output(writer: Output) -> Self {
+        TraceWriter {
+            writer,
+            record_count: 0,
+            annotation_count: 0,
+            event_count: 0,
+            provenance: None,
+        }
+    }
+
+    /// Records a content hash and the producer in the footer (see [`crate::provenance`]).
+    ///
+    /// Call before writing the header: the hash covers every line written
+    /// after this call.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rjets::TraceWriter;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut writer = TraceWriter::new("trace.jets")?.with_provenance("hwtracer 0.1");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_provenance(mut self, producer: impl Into<String>) -> Self {
+        self.provenance = Some((producer.into(), ContentHasher::new()));
+        self
+    }
+
     pub fn write_header(&mut self, version: &str, metadata: serde_json::Value) -> Result<()> {
         let header = serde_json::json!({
             "type": "header",
@@ -73,6 +118,7 @@ impl TraceWriter {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn write_record(
         &mut self,
         id: u64,
@@ -83,104 +129,155 @@ impl TraceWriter {
         description: &str,
         data: Option<serde_json::Value>,
     ) -> Result<()> {
-        let mut map = serde_json::Map::new();
-        map.insert("clk".to_string(), serde_json::Value::Number(clk.into()));
-        map.insert("type".to_string(), serde_json::Value::String("record".to_string()));
-        map.insert("name".to_string(), serde_json::Value::String(name.to_string()));
-        map.insert("record_type".to_string(), serde_json::Value::String(record_type.to_string()));
-        map.insert("id".to_string(), serde_json::Value::Number(id.into()));
-        map.insert("parent_id".to_string(),
-            parent_id.map(|p| serde_json::Value::Number(p.into()))
-                .unwrap_or(serde_json::Value::Null));
-        map.insert("description".to_string(), serde_json::Value::String(description.to_string()));
-
-        if let Some(d) = data {
-            map.insert("data".to_string(), d);
-        }
+        self.write_record_external(
+            &ExternalId::Int(id),
+            parent_id.map(ExternalId::Int).as_ref(),
+            record_type,
+            clk,
+            name,
+            description,
+            data,
+        )
+    }
 
-        self.write_line(&serde_json::Value::Object(map))?;
+    /// Writes a record whose ID and parent ID may be strings.
+    ///
+    /// Use this when re-exporting traces whose producers emitted string IDs;
+    /// events and `record_end` lines referencing them accept an `ExternalId` too.
+    #[allow(clippy::too_many_arguments)]
+    pub fn write_record_external(
+        &mut self,
+        id: &ExternalId,
+        parent_id: Option<&ExternalId>,
+        record_type: &str,
+        clk: i64,
+        name: &str,
+        description: &str,
+        data: Option<serde_json::Value>,
+    ) -> Result<()> {
+        self.write_line(&record_line(id, parent_id, record_type, clk, name, description, data))?;
         self.record_count += 1;
         Ok(())
     }
 
-    pub fn write_record_end(&mut self, id: u64, clk: i64) -> Result<()> {
-        let mut map = serde_json::Map::new();
-        map.insert("clk".to_string(), serde_json::Value::Number(clk.into()));
-        map.insert("type".to_string(), serde_json::Value::String("record_end".to_string()));
-        map.insert("record_id".to_string(), serde_json::Value::Number(id.into()));
-
-        self.write_line(&serde_json::Value::Object(map))?;
+    pub fn write_record_end(&mut self, id: impl Into<ExternalId>, clk: i64) -> Result<()> {
+        self.write_line(&record_end_line(&id.into(), clk))?;
         Ok(())
     }
 
     pub fn write_annotation(
         &mut self,
-        record_id: u64,
+        record_id: impl Into<ExternalId>,
         name: &str,
         description: &str,
         data: serde_json::Value,
     ) -> Result<()> {
-        let mut map = serde_json::Map::new();
-        map.insert("type".to_string(), serde_json::Value::String("annotation".to_string()));
-        map.insert("name".to_string(), serde_json::Value::String(name.to_string()));
-        map.insert("record_id".to_string(), serde_json::Value::Number(record_id.into()));
-        map.insert("description".to_string(), serde_json::Value::String(description.to_string()));
-        map.insert("data".to_string(), data);
-
-        self.write_line(&serde_json::Value::Object(map))?;
+        self.write_line(&annotation_line(&record_id.into(), name, description, data))?;
         self.annotation_count += 1;
         Ok(())
     }
 
     pub fn write_event(
         &mut self,
-        record_id: u64,
+        record_id: impl Into<ExternalId>,
         name: &str,
         description: &str,
         clk: i64,
         data: Option<serde_json::Value>,
     ) -> Result<()> {
-        let mut map = serde_json::Map::new();
-        map.insert("clk".to_string(), serde_json::Value::Number(clk.into()));
-        map.insert("type".to_string(), serde_json::Value::String("event".to_string()));
-        map.insert("name".to_string(), serde_json::Value::String(name.to_string()));
-        map.insert("record_id".to_string(), serde_json::Value::Number(record_id.into()));
-        map.insert("description".to_string(), serde_json::Value::String(description.to_string()));
-
-        if let Some(d) = data {
-            map.insert("data".to_string(), d);
-        }
-
-        self.write_line(&serde_json::Value::Object(map))?;
+        self.write_line(&event_line(&record_id.into(), name, description, clk, data))?;
         self.event_count += 1;
         Ok(())
     }
 
+    /// Writes a counter record (see [`crate::counter`]).
+    ///
+    /// The unit, if given, is stored as the record's `unit` attribute.
+    pub fn write_counter_record(
+        &mut self,
+        id: u64,
+        parent_id: Option<u64>,
+        clk: i64,
+        name: &str,
+        unit: Option<&str>,
+    ) -> Result<()> {
+        let data = unit.map(|unit| serde_json::json!({ "unit": unit }));
+        self.write_record(id, parent_id, COUNTER_RECORD_TYPE, clk, name, "", data)
+    }
+
+    /// Writes one sample of a counter: its value from `clk` until the next sample.
+    pub fn write_counter_sample(&mut self, record_id: impl Into<ExternalId>, clk: i64, value: f64) -> Result<()> {
+        let data = serde_json::json!({ COUNTER_VALUE_ATTR: value });
+        self.write_event(record_id, COUNTER_SAMPLE_EVENT, "", clk, Some(data))
+    }
+
     pub fn write_footer(&mut self, capture_end_clk: Option<i64>) -> Result<()> {
-        let footer = serde_json::json!({
+        let mut footer = serde_json::json!({
             "type": "footer",
             "capture_end_clk": capture_end_clk,
             "total_records": self.record_count,
             "total_annotations": self.annotation_count,
             "total_events": self.event_count
         });
+        // Taken so the footer line itself is not hashed
+        if let Some((producer, hasher)) = self.provenance.take() {
+            let provenance = Provenance {
+                algorithm: PROVENANCE_ALGORITHM.to_string(),
+                hash: hasher.finish(),
+                producer,
+            };
+            footer["provenance"] = serde_json::to_value(provenance)?;
+        }
 
         self.write_line(&footer)?;
         Ok(())
     }
 
+    /// Writes lines serialized elsewhere (see [`crate::concurrent_writer`]).
+    ///
+    /// `lines` holds whole lines, each ending in a newline; `counts` says how
+    /// many of them are records, annotations and events for the footer.
+    pub(crate) fn write_serialized(&mut self, lines: &str, counts: LineCounts) -> Result<()> {
+        self.writer.write_all(lines.as_bytes())
+            .context("Failed to write lines")?;
+        if let Some((_, hasher)) = &mut self.provenance {
+            for line in lines.lines() {
+                hasher.update_line(line);
+            }
+        }
+        self.record_count += counts.records;
+        self.annotation_count += counts.annotations;
+        self.event_count += counts.events;
+
+        self.writer.flush()
+            .context("Failed to flush writer")?;
+        Ok(())
+    }
+
     fn write_line<T: Serialize>(&mut self, value: &T) -> Result<()> {
         let json = serde_json::to_string(value)
             .context("Failed to serialize to JSON")?;
 
         writeln!(self.writer, "{}", json)
             .context("Failed to write line")?;
+        if let Some((_, hasher)) = &mut self.provenance {
+            hasher.update_line(&json);
+        }
 
         self.writer.flush()
             .context("Failed to flush writer")?;
 
         Ok(())
     }
+
+    /// Flushes the output and, for Brotli output, ends the compressed stream.
+    ///
+    /// Dropping the writer does the same but ignores errors, so call this
+    /// after the footer to find out whether the file is complete.
+    pub fn finish(mut self) -> Result<()> {
+        let output = std::mem::replace(&mut self.writer, Output::Plain(Box::new(io::sink())));
+        output.finish().context("Failed to finish writing trace")
+    }
 }
 
 impl Drop for TraceWriter {
@@ -188,3 +285,256 @@ impl Drop for TraceWriter {
         let _ = self.writer.flush();
     }
 }
+
+/// Where a [`TraceWriter`] sends its lines.
+enum Output {
+    Plain(Box<dyn Write>),
+    Brotli(Box<BrotliWriter>),
+}
+
+impl Output {
+    fn finish(self) -> io::Result<()> {
+        match self {
+            Output::Plain(mut writer) => writer.flush(),
+            Output::Brotli(writer) => writer.finish(),
+        }
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Output::Plain(writer) => writer.write(buf),
+            Output::Brotli(writer) => writer.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Output::Plain(writer) => writer.flush(),
+            Output::Brotli(writer) => writer.flush(),
+        }
+    }
+}
+
+/// Brotli compressor whose end of stream can fail loudly.
+///
+/// `brotli::CompressorWriter` writes the end of the stream when it is
+/// dropped or unwrapped and discards any error doing so. This wrapper
+/// records the first error the underlying writer returns and reports it
+/// from [`finish`](Self::finish).
+pub struct BrotliWriter {
+    compressor: CompressorWriter<ErrorLatch>,
+}
+
+impl BrotliWriter {
+    /// Compresses into `inner` at quality 6 (balanced speed/ratio).
+    pub fn new(inner: impl Write + 'static) -> Self {
+        let params = BrotliEncoderParams {
+            quality: 6,  // Balanced compression
+            lgwin: 22,   // Window size
+            ..Default::default()
+        };
+        let latch = ErrorLatch { inner: Box::new(inner), error: None };
+        BrotliWriter {
+            compressor: CompressorWriter::with_params(latch, 4096, &params),
+        }
+    }
+
+    /// Ends the compressed stream and flushes the underlying writer.
+    pub fn finish(self) -> io::Result<()> {
+        let mut latch = self.compressor.into_inner();
+        if let Some(error) = latch.error.take() {
+            return Err(error);
+        }
+        latch.inner.flush()
+    }
+}
+
+impl Write for BrotliWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.compressor.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.compressor.flush()
+    }
+}
+
+/// Passes writes through and keeps the first error for [`BrotliWriter::finish`].
+struct ErrorLatch {
+    inner: Box<dyn Write>,
+    error: Option<io::Error>,
+}
+
+impl Write for ErrorLatch {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf).inspect_err(|error| {
+            if self.error.is_none() {
+                self.error = Some(io::Error::new(error.kind(), error.to_string()));
+            }
+        })
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Writes any loaded trace to `file_path` as a canonical JETS file
+/// (Brotli-compressed for `.br` paths, see [`TraceWriter::new`]).
+///
+/// Returns the number of records written.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use rjets::{PipetraceReader, TraceReader};
+/// # fn main() -> anyhow::Result<()> {
+/// let trace = PipetraceReader::new().read("run.pt")?;
+/// rjets::writer::export_trace(&trace, "run.jets.br")?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn export_trace<T: TraceData>(trace: &T, file_path: &str) -> Result<usize> {
+    let mut writer = TraceWriter::new(file_path)?;
+    let count = write_trace(trace, &mut writer)?;
+    writer.finish()?;
+    Ok(count)
+}
+
+/// Re-emits a trace of any backend through `writer`.
+///
+/// The header carries the current format version and the source header's
+/// metadata. Records follow depth first, each with its annotations and
+/// events, and are ended after their descendants; the footer keeps the
+/// source's capture end. Records keep their external (integer or string)
+/// IDs, and the `data` attributes are written apart from the annotations.
+pub fn write_trace<T: TraceData>(trace: &T, writer: &mut TraceWriter) -> Result<usize> {
+    let metadata = trace.metadata();
+    let header = match metadata.header_data() {
+        serde_json::Value::Object(data) => serde_json::Value::Object(data.clone()),
+        _ => serde_json::json!({}),
+    };
+    writer.write_header(&FormatVersion::CURRENT.to_string(), header)?;
+
+    let mut written = 0;
+    for &root_id in trace.roots() {
+        let Some(root) = trace.get_record(root_id) else { continue };
+        // (record, external parent ID, children pushed); ends are written on the way up
+        let mut stack = vec![(root, None, false)];
+        while let Some((record, parent_id, visited)) = stack.pop() {
+            let id = record.external_id();
+            if visited {
+                if let Some(end_clk) = record.end_clk() {
+                    writer.write_record_end(id, end_clk)?;
+                }
+                continue;
+            }
+
+            let annotations = record.annotations();
+            let mut attrs = record.attrs();
+            attrs.truncate(attrs.len().saturating_sub(annotations.len()));
+            let data = (!attrs.is_empty()).then(|| serde_json::Value::Object(attrs.into_iter().collect()));
+            writer.write_record_external(
+                &id,
+                parent_id.as_ref(),
+                &record.record_type(),
+                record.clk(),
+                &record.name(),
+                &record.description(),
+                data,
+            )?;
+            written += 1;
+            for annotation in annotations {
+                writer.write_annotation(id.clone(), &annotation.name, &annotation.description, annotation.data)?;
+            }
+            for event in (0..record.num_events()).filter_map(|i| record.event_at(i)) {
+                let attrs = event.attrs();
+                let data = (!attrs.is_empty()).then(|| serde_json::Value::Object(attrs.into_iter().collect()));
+                writer.write_event(id.clone(), &event.name(), &event.description(), event.clk(), data)?;
+            }
+
+            let children: Vec<_> = (0..record.num_children()).filter_map(|i| record.child_at(i)).collect();
+            stack.push((record, None, true));
+            for child in children.into_iter().rev() {
+                stack.push((child, Some(id.clone()), false));
+            }
+        }
+    }
+
+    writer.write_footer(metadata.capture_end_clk())?;
+    Ok(written)
+}
+
+/// Lines of each counted kind in a batch of serialized lines.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct LineCounts {
+    pub records: usize,
+    pub annotations: usize,
+    pub events: usize,
+}
+
+pub(crate) fn record_line(
+    id: &ExternalId,
+    parent_id: Option<&ExternalId>,
+    record_type: &str,
+    clk: i64,
+    name: &str,
+    description: &str,
+    data: Option<serde_json::Value>,
+) -> serde_json::Value {
+    let mut map = serde_json::Map::new();
+    map.insert("clk".to_string(), serde_json::Value::Number(clk.into()));
+    map.insert("type".to_string(), serde_json::Value::String("record".to_string()));
+    map.insert("name".to_string(), serde_json::Value::String(name.to_string()));
+    map.insert("record_type".to_string(), serde_json::Value::String(record_type.to_string()));
+    map.insert("id".to_string(), id.to_json());
+    map.insert("parent_id".to_string(),
+        parent_id.map(ExternalId::to_json)
+            .unwrap_or(serde_json::Value::Null));
+    map.insert("description".to_string(), serde_json::Value::String(description.to_string()));
+
+    if let Some(d) = data {
+        map.insert("data".to_string(), d);
+    }
+    serde_json::Value::Object(map)
+}
+
+pub(crate) fn record_end_line(id: &ExternalId, clk: i64) -> serde_json::Value {
+    let mut map = serde_json::Map::new();
+    map.insert("clk".to_string(), serde_json::Value::Number(clk.into()));
+    map.insert("type".to_string(), serde_json::Value::String("record_end".to_string()));
+    map.insert("record_id".to_string(), id.to_json());
+    serde_json::Value::Object(map)
+}
+
+pub(crate) fn annotation_line(record_id: &ExternalId, name: &str, description: &str, data: serde_json::Value) -> serde_json::Value {
+    let mut map = serde_json::Map::new();
+    map.insert("type".to_string(), serde_json::Value::String("annotation".to_string()));
+    map.insert("name".to_string(), serde_json::Value::String(name.to_string()));
+    map.insert("record_id".to_string(), record_id.to_json());
+    map.insert("description".to_string(), serde_json::Value::String(description.to_string()));
+    map.insert("data".to_string(), data);
+    serde_json::Value::Object(map)
+}
+
+pub(crate) fn event_line(
+    record_id: &ExternalId,
+    name: &str,
+    description: &str,
+    clk: i64,
+    data: Option<serde_json::Value>,
+) -> serde_json::Value {
+    let mut map = serde_json::Map::new();
+    map.insert("clk".to_string(), serde_json::Value::Number(clk.into()));
+    map.insert("type".to_string(), serde_json::Value::String("event".to_string()));
+    map.insert("name".to_string(), serde_json::Value::String(name.to_string()));
+    map.insert("record_id".to_string(), record_id.to_json());
+    map.insert("description".to_string(), serde_json::Value::String(description.to_string()));
+
+    if let Some(d) = data {
+        map.insert("data".to_string(), d);
+    }
+    serde_json::Value::Object(map)
+}
@@ -0,0 +1,164 @@
+//! Snapshot tests over the fixture corpus.
+//!
+//! Every file under `tests/fixtures/corpus` is read with the reader for its
+//! extension ([`READERS`]) and the parsed structure, or the error, is compared
+//! with the `<file>.snap` next to it. A new reader gets a row in `READERS` and
+//! its fixtures in the corpus; files without a reader fail the test.
+//!
+//! After an intended change, rewrite the snapshots and review the diff:
+//!
+//! ```text
+//! JETS_UPDATE_SNAPSHOTS=1 cargo test --test fixture_corpus
+//! ```
+//!
+//! The `tracegen.*` fixtures come from `jets-tracegen`, which is
+//! deterministic; regenerate them with
+//! `cargo test --test fixture_corpus -- --ignored` and then the snapshots.
+
+use anyhow::{Context, Result};
+use rjets::{
+    AttributeAccessor, DynTraceData, DynTraceRecord, JetsTraceReader, PerfScriptReader, PerfettoTraceReader, PipetraceReader,
+    TraceData, TraceEvent, TraceMetadata, TraceReader, TraceRecord,
+};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+type MakeReader = fn() -> Box<dyn TraceReader>;
+
+/// Readers by file name suffix; the first match wins.
+const READERS: &[(&str, MakeReader)] = &[
+    (".jets.br", || Box::new(JetsTraceReader::new())),
+    (".jets", || Box::new(JetsTraceReader::new())),
+    (".pt", || Box::new(PipetraceReader::new())),
+    (".perf", || Box::new(PerfScriptReader::new())),
+    (".ftrace", || Box::new(PerfScriptReader::new())),
+    (".json", || Box::new(PerfettoTraceReader::new())),
+];
+
+const SNAPSHOT_EXT: &str = "snap";
+
+fn corpus_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/corpus")
+}
+
+/// Returns the corpus files (not snapshots) below `dir`, sorted.
+fn corpus_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to list {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(corpus_files(&path)?);
+        } else if path.extension().is_none_or(|ext| ext != SNAPSHOT_EXT) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Renders what a reader made of a fixture as stable text.
+fn snapshot(result: Result<DynTraceData>) -> String {
+    let trace = match result {
+        Ok(trace) => trace,
+        // Paths in error chains differ between checkouts
+        Err(e) => return format!("error: {:#}\n", e).replace(concat!(env!("CARGO_MANIFEST_DIR"), "/"), ""),
+    };
+
+    let mut out = String::new();
+    let metadata = trace.metadata();
+    let (start, end) = metadata.trace_extent();
+    writeln!(out, "version: {}", metadata.version()).unwrap();
+    writeln!(out, "extent: {}..{}", start, end).unwrap();
+    writeln!(out, "capture_end: {:?}", metadata.capture_end_clk()).unwrap();
+    for warning in metadata.warnings() {
+        writeln!(out, "warning: {}", warning).unwrap();
+    }
+    for id in trace.root_ids() {
+        if let Some(record) = trace.get_record(id) {
+            write_record(&mut out, &record, 0);
+        }
+    }
+    out
+}
+
+fn write_record(out: &mut String, record: &DynTraceRecord<'_>, depth: usize) {
+    let indent = "  ".repeat(depth);
+    let end = record.end_clk().map_or("?".to_string(), |end| end.to_string());
+    let inferred = if record.end_inferred() { " (inferred)" } else { "" };
+    writeln!(
+        out,
+        "{}{} [{}] id={} {}..{}{}",
+        indent,
+        record.name(),
+        record.record_type(),
+        record.external_id(),
+        record.clk(),
+        end,
+        inferred,
+    )
+    .unwrap();
+    for (key, value) in record.attrs() {
+        writeln!(out, "{}  .{} = {}", indent, key, value).unwrap();
+    }
+    for event in (0..record.num_events()).filter_map(|i| record.event_at(i)) {
+        writeln!(out, "{}  @{} {}", indent, event.clk(), event.name()).unwrap();
+    }
+    for child in (0..record.num_children()).filter_map(|i| record.child_at(i)) {
+        write_record(out, &child, depth + 1);
+    }
+}
+
+#[test]
+fn test_corpus_matches_snapshots() -> Result<()> {
+    let update = std::env::var_os("JETS_UPDATE_SNAPSHOTS").is_some();
+    let root = corpus_dir();
+    let files = corpus_files(&root)?;
+    assert!(!files.is_empty(), "empty corpus at {}", root.display());
+
+    let mut failures = Vec::new();
+    for path in &files {
+        let name = path.strip_prefix(&root)?.display().to_string();
+        let Some((_, reader)) = READERS.iter().find(|(suffix, _)| name.ends_with(suffix)) else {
+            failures.push(format!("{}: no reader for this extension", name));
+            continue;
+        };
+        let actual = snapshot(reader().read(path.to_str().context("non-UTF-8 fixture path")?));
+
+        let mut snap_path = path.clone().into_os_string();
+        snap_path.push(".");
+        snap_path.push(SNAPSHOT_EXT);
+        if update {
+            fs::write(&snap_path, &actual)?;
+            continue;
+        }
+        match fs::read_to_string(&snap_path) {
+            Ok(expected) if expected == actual => {}
+            Ok(expected) => failures.push(format!("{}: snapshot differs\n--- expected\n{}--- actual\n{}", name, expected, actual)),
+            Err(_) => failures.push(format!("{}: no snapshot (run with JETS_UPDATE_SNAPSHOTS=1)", name)),
+        }
+    }
+    assert!(failures.is_empty(), "{} of {} fixtures failed:\n{}", failures.len(), files.len(), failures.join("\n"));
+    Ok(())
+}
+
+#[test]
+#[ignore = "rewrites the tracegen fixtures"]
+fn regenerate_tracegen_fixtures() -> Result<()> {
+    let root = corpus_dir();
+    for file in ["tracegen.jets", "tracegen.jets.br"] {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_jets-tracegen"));
+        command.args(["-num_threads", "2", "-num_instr", "3", "-out"]).arg(root.join(file));
+        if file.ends_with(".br") {
+            command.arg("-brotli");
+        }
+        let status = command.status()?;
+        anyhow::ensure!(status.success(), "jets-tracegen failed for {}: {}", file, status);
+    }
+
+    // A compressed stream cut off mid-way, as left by an interrupted capture
+    let compressed = fs::read(root.join("tracegen.jets.br"))?;
+    fs::write(root.join("malformed/truncated.jets.br"), &compressed[..compressed.len() / 2])?;
+    Ok(())
+}
@@ -1,4 +1,7 @@
-use rjets::{TraceWriter, TraceReader, JetsTraceReader, VirtualTraceReader, parse_trace};
+#![allow(clippy::len_zero)]
+
+use rjets::{BrotliWriter, TraceWriter, TraceReader, JetsTraceReader, VirtualTraceReader, parse_trace, parse_trace_from_reader, ExternalId};
+use rjets::{ParseOptions, parse_trace_from_reader_with_options, MmapJetsReader};
 use rjets::{TraceData, TraceRecord, TraceMetadata, TraceEvent, DynTraceData, AttributeAccessor};
 use anyhow::Result;
 use std::fs;
@@ -522,3 +525,628 @@ fn test_brotli_detection_by_extension() -> Result<()> {
     fs::remove_file(br_file)?;
     Ok(())
 }
+
+#[test]
+fn test_string_and_integer_record_ids() -> Result<()> {
+    let test_file = env::temp_dir().join("test_string_ids.jets");
+    let test_file = test_file.to_str().unwrap();
+    let _ = fs::remove_file(test_file);
+
+    let root: ExternalId = "3f2a-uuid-root".into();
+    let child: ExternalId = "3f2a-uuid-child".into();
+    {
+        let mut writer = TraceWriter::new(test_file)?;
+        writer.write_header("2.0", serde_json::json!({}))?;
+        writer.write_record_external(&root, None, "Host", 0, "root", "", None)?;
+        writer.write_record_external(&child, Some(&root), "Task", 10, "child", "", None)?;
+        // Integer IDs can be mixed with string parents
+        writer.write_record_external(&ExternalId::Int(7), Some(&child), "Op", 20, "op", "", None)?;
+        writer.write_event(child.clone(), "E", "", 15, None)?;
+        writer.write_record_end(7, 30)?;
+        writer.write_record_end(child.clone(), 40)?;
+        writer.write_record_end(root.clone(), 50)?;
+        writer.write_footer(Some(50))?;
+    }
+
+    // The file keeps the string IDs verbatim
+    let content = fs::read_to_string(test_file)?;
+    assert!(content.contains(r#""id":"3f2a-uuid-child""#));
+    assert!(content.contains(r#""parent_id":"3f2a-uuid-root""#));
+
+    let trace = parse_trace(test_file)?;
+    let root_ids = trace.root_ids();
+    assert_eq!(root_ids.len(), 1);
+
+    let root_record = trace.get_record(root_ids[0]).unwrap();
+    assert_eq!(root_record.external_id(), root);
+    assert_eq!(root_record.end_clk(), Some(50));
+
+    let child_record = root_record.child_at(0).unwrap();
+    assert_eq!(child_record.external_id(), child);
+    assert_eq!(child_record.parent_id(), Some(root_record.id()));
+    assert_eq!(child_record.num_events(), 1);
+    assert_eq!(trace.id_map.handle_of(&child), Some(child_record.id()));
+
+    let op = child_record.child_at(0).unwrap();
+    assert_eq!(op.id(), 7);
+    assert_eq!(op.external_id(), ExternalId::Int(7));
+
+    // Handles are derived from the string, so they are stable across loads
+    let reloaded = parse_trace(test_file)?;
+    assert_eq!(reloaded.root_ids(), root_ids);
+
+    fs::remove_file(test_file)?;
+    Ok(())
+}
+
+#[test]
+fn test_full_range_integer_ids_with_string_ids() {
+    // 12638214688346347271 is the FNV-1a hash of "x", the handle a string ID
+    // "x" would get if no integer ID held it
+    let input = concat!(
+        r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+        r#"{"type":"record","clk":0,"name":"max","record_type":"T","id":18446744073709551615,"parent_id":null,"description":""}"#, "\n",
+        r#"{"type":"record","clk":1,"name":"s","record_type":"T","id":"x","parent_id":18446744073709551615,"description":""}"#, "\n",
+        r#"{"type":"record","clk":2,"name":"hash","record_type":"T","id":12638214688346347271,"parent_id":"x","description":""}"#, "\n",
+        r#"{"type":"event","clk":3,"name":"E","record_id":"x","description":""}"#, "\n",
+        r#"{"type":"record_end","clk":9,"record_id":18446744073709551615}"#, "\n",
+    );
+    let trace = parse_trace_from_reader(input.as_bytes()).unwrap();
+    assert_eq!(trace.root_ids(), vec![u64::MAX]);
+
+    let root = trace.get_record(u64::MAX).unwrap();
+    assert_eq!(root.external_id(), ExternalId::Int(u64::MAX));
+    assert_eq!(root.end_clk(), Some(9));
+
+    // The string ID gets its own handle, clear of the integer holding its hash
+    let string_record = root.child_at(0).unwrap();
+    assert_eq!(string_record.external_id(), ExternalId::from("x"));
+    assert_ne!(string_record.id(), 12638214688346347271);
+    assert_eq!(string_record.event_at(0).unwrap().record_id(), string_record.id());
+
+    let hash_record = string_record.child_at(0).unwrap();
+    assert_eq!(hash_record.id(), 12638214688346347271);
+    assert_eq!(hash_record.external_id(), ExternalId::Int(12638214688346347271));
+}
+
+#[test]
+fn test_repeated_record_end_is_reported() -> Result<()> {
+    let input = concat!(
+        r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+        r#"{"type":"record","clk":0,"name":"r","record_type":"T","id":1,"parent_id":null,"description":""}"#, "\n",
+        r#"{"type":"record_end","clk":5,"record_id":1}"#, "\n",
+        r#"{"type":"record_end","clk":8,"record_id":1}"#, "\n",
+    );
+    let trace = parse_trace_from_reader(input.as_bytes())?;
+    assert_eq!(trace.get_record(1).unwrap().end_clk(), Some(8));
+    let warnings = trace.metadata().warnings();
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].contains("line 4") && warnings[0].contains("clock 5"), "{}", warnings[0]);
+    Ok(())
+}
+
+#[test]
+fn test_record_dependencies() -> Result<()> {
+    let input = concat!(
+        r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+        r#"{"type":"record","clk":0,"name":"ld","record_type":"Mem","id":"ld-1","parent_id":null,"description":""}"#, "\n",
+        r#"{"type":"record","clk":4,"name":"add","record_type":"Insn","id":2,"parent_id":null,"description":"","depends_on":["ld-1",3,99]}"#, "\n",
+        r#"{"type":"record","clk":1,"name":"mul","record_type":"Insn","id":3,"parent_id":null,"description":""}"#, "\n",
+    );
+    let trace = parse_trace_from_reader(input.as_bytes())?;
+    let load = trace.id_map.handle_of(&"ld-1".into()).unwrap();
+    // Forward references resolve; unknown IDs are dropped with a warning
+    assert_eq!(trace.get_record(2).unwrap().depends_on(), vec![load, 3]);
+    assert!(trace.get_record(3).unwrap().depends_on().is_empty());
+    assert_eq!(trace.metadata().warnings(), vec!["record '2' depends on unknown record '99'".to_string()]);
+    Ok(())
+}
+
+#[test]
+fn test_infers_missing_record_ends() -> Result<()> {
+    let input = concat!(
+        r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+        r#"{"type":"record","clk":0,"name":"a","record_type":"T","id":1,"parent_id":null,"description":""}"#, "\n",
+        r#"{"type":"record","clk":2,"name":"a1","record_type":"Op","id":11,"parent_id":1,"description":""}"#, "\n",
+        r#"{"type":"record","clk":5,"name":"a2","record_type":"Op","id":12,"parent_id":1,"description":""}"#, "\n",
+        r#"{"type":"record","clk":20,"name":"b","record_type":"T","id":2,"parent_id":null,"description":""}"#, "\n",
+        r#"{"type":"record_end","clk":25,"record_id":2}"#, "\n",
+        r#"{"type":"record","clk":30,"name":"c","record_type":"T","id":3,"parent_id":null,"description":""}"#, "\n",
+        r#"{"type":"footer","capture_end_clk":40}"#, "\n",
+    );
+
+    // Without the option, open records stay open
+    let plain = parse_trace_from_reader(input.as_bytes())?;
+    assert_eq!(plain.get_record(1).unwrap().end_clk(), None);
+
+    let data = parse_trace_from_reader_with_options(input.as_bytes(), ParseOptions { infer_end: true, ..ParseOptions::default() })?;
+    let end = |id| {
+        let record = data.get_record(id).unwrap();
+        (record.end_clk(), record.end_inferred())
+    };
+    // Next sibling start, then the (inferred) parent end, then the capture end
+    assert_eq!(end(1), (Some(20), true));
+    assert_eq!(end(11), (Some(5), true));
+    assert_eq!(end(12), (Some(20), true));
+    assert_eq!(end(2), (Some(25), false));
+    assert_eq!(end(3), (Some(40), true));
+    assert_eq!(data.metadata().trace_extent(), (0, 40));
+    Ok(())
+}
+
+#[test]
+fn test_compact_events_read_like_plain_events() -> Result<()> {
+    let mut input = String::from(concat!(
+        r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+        r#"{"type":"record","clk":0,"name":"pipe","record_type":"Instr","id":1,"parent_id":null,"description":""}"#, "\n",
+    ));
+    for i in 0..100 {
+        let stage = ["IF", "ID", "EX", "WB"][i % 4];
+        input.push_str(&format!(
+            r#"{{"type":"event","clk":{},"name":"{}","record_id":1,"description":"","data":{{"lane":{}}}}}"#,
+            i * 2, stage, i % 3
+        ));
+        input.push('\n');
+    }
+
+    let plain = parse_trace_from_reader_with_options(
+        input.as_bytes(),
+        ParseOptions { compact_event_threshold: usize::MAX, ..ParseOptions::default() },
+    )?;
+    let compact = parse_trace_from_reader(input.as_bytes())?;
+    assert!(!plain.get_record(1).unwrap().cold().events.is_compact());
+    assert!(compact.get_record(1).unwrap().cold().events.is_compact());
+
+    let (a, b) = (plain.get_record(1).unwrap(), compact.get_record(1).unwrap());
+    assert_eq!(b.num_events(), 100);
+    for index in [0, 63, 64, 99] {
+        let (x, y) = (a.event_at(index).unwrap(), b.event_at(index).unwrap());
+        assert_eq!((x.clk(), x.name(), x.record_id()), (y.clk(), y.name(), y.record_id()));
+        assert_eq!(x.attrs(), y.attrs());
+    }
+    assert!(b.event_at(100).is_none());
+    Ok(())
+}
+
+#[test]
+fn test_validator_accepts_writer_output() -> Result<()> {
+    let test_file = env::temp_dir().join("test_validator_writer.jets");
+    let test_file = test_file.to_str().unwrap();
+
+    {
+        let mut writer = TraceWriter::new(test_file)?;
+        writer.write_header(&rjets::schema::FormatVersion::CURRENT.to_string(), serde_json::json!({}))?;
+        writer.write_record(1, None, "Root", 0, "root", "", None)?;
+        writer.write_record_external(&ExternalId::from("child"), Some(&ExternalId::Int(1)), "Op", 5, "child", "", None)?;
+        writer.write_event(ExternalId::from("child"), "E", "", 6, None)?;
+        writer.write_annotation(1, "A", "", serde_json::json!([1, 2]))?;
+        writer.write_record_end(ExternalId::from("child"), 8)?;
+        writer.write_record_end(1, 10)?;
+        writer.write_footer(None)?;
+    }
+
+    let report = rjets::validator::validate_file(test_file)?;
+    fs::remove_file(test_file)?;
+    assert!(report.is_valid(), "{:?}", report.issues);
+    assert_eq!(report.warning_count(), 0, "{:?}", report.issues);
+
+    let report = rjets::validator::validate_file(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/trace.jets"))?;
+    assert!(report.is_valid(), "{:?}", report.issues);
+    Ok(())
+}
+
+/// Reads a versioned fixture and checks the content shared by all of them.
+fn read_version_fixture(name: &str) -> Result<rjets::JetsTraceData> {
+    let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+    let trace = parse_trace(&path)?;
+
+    let root = trace.get_record(trace.root_ids()[0]).unwrap();
+    assert_eq!(root.name(), "Core0");
+    assert_eq!(root.record_type(), "Core");
+    assert_eq!(root.end_clk(), Some(20));
+
+    let insn = root.child_at(0).unwrap();
+    assert_eq!(insn.record_type(), "Instruction");
+    assert_eq!(insn.parent_id(), Some(root.id()));
+    assert_eq!(insn.duration(), Some(5));
+    assert_eq!(insn.num_events(), 1);
+    assert_eq!(insn.attr("opcode"), Some(serde_json::json!("ADD")));
+    Ok(trace)
+}
+
+#[test]
+fn test_reads_version_1_layout() -> Result<()> {
+    let trace = read_version_fixture("v1.0.jets")?;
+    assert_eq!(trace.metadata().version(), "1.0");
+    assert!(trace.metadata().warnings().is_empty());
+
+    // Legacy traces without descriptions read as empty descriptions
+    let root = trace.get_record(trace.root_ids()[0]).unwrap();
+    assert_eq!(root.child_at(0).unwrap().description(), "");
+    Ok(())
+}
+
+#[test]
+fn test_reads_version_2_layout() -> Result<()> {
+    let trace = read_version_fixture("v2.0.jets")?;
+    assert!(trace.metadata().warnings().is_empty());
+    let root = trace.get_record(trace.root_ids()[0]).unwrap();
+    assert_eq!(root.child_at(0).unwrap().external_id(), ExternalId::from("insn-2"));
+    Ok(())
+}
+
+#[test]
+fn test_newer_version_reads_with_warning() -> Result<()> {
+    let trace = read_version_fixture("v3.0.jets")?;
+    let warnings = trace.metadata().warnings();
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].contains("newer"), "{}", warnings[0]);
+    Ok(())
+}
+
+#[test]
+fn test_version_fixtures_validate() -> Result<()> {
+    for name in ["v1.0.jets", "v2.0.jets", "v3.0.jets"] {
+        let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+        let report = rjets::validator::validate_file(&path)?;
+        assert!(report.is_valid(), "{}: {:?}", name, report.issues);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_arena_columns_match_tree_links() -> Result<()> {
+    let trace = parse_trace(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/trace.jets"))?;
+    let arena = &trace.arena;
+    assert_eq!(arena.len(), trace.records_by_id.len());
+    assert_eq!(arena.end_clk.len(), arena.len());
+    assert_eq!(arena.cold.len(), arena.len());
+
+    for index in 0..arena.len() {
+        let record = arena.record(index).unwrap();
+        assert_eq!(record.arena_index(), index);
+        assert_eq!(trace.records_by_id[&record.id()], index);
+        let child_slice = arena.children_of(index);
+        assert_eq!(child_slice.len(), record.num_children());
+        let paged: Vec<u64> = trace.children_range(record.id(), 1, 2).iter().map(|c| c.id()).collect();
+        let expected: Vec<u64> = record.children().skip(1).take(2).map(|c| c.id()).collect();
+        assert_eq!(paged, expected);
+        for (i, child) in record.children().enumerate() {
+            assert_eq!(child.arena_index(), child_slice[i]);
+            assert_eq!(arena.parent[child.arena_index()], Some(index));
+            assert_eq!(child.parent_id(), Some(record.id()));
+            if i > 0 {
+                let prev = record.child_at(i - 1).unwrap();
+                assert!(prev.clk() <= child.clk(), "children are sorted by clock");
+            }
+        }
+        if let Some(end) = record.end_clk() {
+            assert_eq!(record.duration(), Some(end - record.clk()));
+        }
+    }
+    assert_eq!(arena.child_list.len(), arena.len() - trace.root_indices.len());
+    assert_eq!(trace.roots().len(), trace.root_indices.len());
+    assert_eq!(trace.roots(), trace.root_ids().as_slice());
+    assert!(arena.record(arena.len()).is_none());
+    Ok(())
+}
+
+#[test]
+fn test_records_iter_visits_every_record_once() -> Result<()> {
+    let jets = JetsTraceReader::new().read(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/trace.jets"))?;
+    let virtual_trace = VirtualTraceReader::new().read("")?;
+    let mapped = MmapJetsReader::new().read(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/trace.jets"))?;
+
+    for trace in [jets, virtual_trace, mapped] {
+        // Reference count: records reachable from the roots
+        let mut expected = std::collections::HashSet::new();
+        let mut stack: Vec<u64> = trace.root_ids();
+        while let Some(id) = stack.pop() {
+            let record = trace.get_record(id).unwrap();
+            assert!(expected.insert(id));
+            stack.extend(record.children().map(|c| c.id()));
+        }
+
+        let ids: Vec<u64> = trace.records_iter().map(|r| r.id()).collect();
+        assert_eq!(ids.len(), expected.len());
+        assert_eq!(ids.iter().copied().collect::<std::collections::HashSet<_>>(), expected);
+
+        let partials = trace.par_fold_records(|| (0usize, 0i64), |(count, clk_sum), record| {
+            (count + 1, clk_sum + record.clk())
+        });
+        let total: usize = partials.iter().map(|&(count, _)| count).sum();
+        let clk_sum: i64 = partials.iter().map(|&(_, sum)| sum).sum();
+        assert_eq!(total, expected.len());
+        assert_eq!(clk_sum, trace.records_iter().map(|r| r.clk()).sum::<i64>());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_mapped_trace_reads_like_parsed_trace() -> Result<()> {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/trace.jets");
+    let parsed = JetsTraceReader::new().read(path)?;
+    let mapped = MmapJetsReader::new().read(path)?;
+    assert!(matches!(mapped, DynTraceData::Mmap(_)));
+    assert_eq!(mapped.metadata().trace_extent(), parsed.metadata().trace_extent());
+    assert_eq!(mapped.metadata().version(), parsed.metadata().version());
+
+    let sorted = |mut ids: Vec<u64>| { ids.sort_unstable(); ids };
+    assert_eq!(sorted(mapped.root_ids()), sorted(parsed.root_ids()));
+    for record in parsed.records_iter() {
+        let other = mapped.get_record(record.id()).expect("record missing from mapped trace");
+        assert_eq!(other.name(), record.name());
+        assert_eq!(other.record_type(), record.record_type());
+        assert_eq!((other.clk(), other.end_clk()), (record.clk(), record.end_clk()));
+        assert_eq!(other.parent_id(), record.parent_id());
+        assert_eq!(other.attrs(), record.attrs());
+        assert_eq!(other.depends_on(), record.depends_on());
+        assert_eq!(other.subtree_depth(), record.subtree_depth());
+        assert_eq!(
+            sorted(other.children().map(|c| c.id()).collect()),
+            sorted(record.children().map(|c| c.id()).collect()),
+        );
+        let events = |r: &rjets::DynTraceRecord| -> Vec<(i64, String)> {
+            (0..r.num_events()).filter_map(|i| r.event_at(i)).map(|e| (e.clk(), e.name())).collect()
+        };
+        assert_eq!(events(&other), events(&record));
+    }
+
+    let compressed = env::temp_dir().join("mmap_rejects.jets.br");
+    assert!(MmapJetsReader::new().read(compressed.to_str().unwrap()).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_merged_traces_keep_ids_apart() -> Result<()> {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/trace.jets");
+    let string_ids = concat!(
+        r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+        r#"{"type":"record","clk":5,"name":"host","record_type":"Host","id":"h-1","parent_id":null,"description":""}"#, "\n",
+        r#"{"type":"record","clk":6,"name":"task","record_type":"Task","id":"t-1","parent_id":"h-1","description":""}"#, "\n",
+        r#"{"type":"event","clk":7,"name":"E","record_id":"t-1","description":""}"#, "\n",
+    );
+    let single = JetsTraceReader::new().read(path)?;
+    let merged = rjets::MultiTraceData::new(vec![
+        (path.to_string(), JetsTraceReader::new().read(path)?),
+        (format!("other/{}", path), JetsTraceReader::new().read(path)?),
+        ("strings.jets".to_string(), DynTraceData::Jets(parse_trace_from_reader(string_ids.as_bytes())?)),
+    ])?;
+
+    // One synthetic root per file, named after it
+    let roots = merged.root_ids();
+    assert_eq!(roots.len(), 3);
+    let files: Vec<_> = roots.iter().map(|&id| merged.get_record(id).unwrap()).collect();
+    assert_eq!(files[0].name(), "trace.jets");
+    assert_eq!(files[1].name(), "trace.jets (2)");
+    assert_eq!(files[0].num_children(), single.roots().len());
+    assert_eq!(files[0].record_type(), rjets::multi_trace::FILE_RECORD_TYPE);
+
+    // Same IDs in both copies of the fixture map to distinct merged IDs
+    let ids: Vec<u64> = merged.records_iter().map(|r| r.id()).collect();
+    let unique: std::collections::HashSet<u64> = ids.iter().copied().collect();
+    assert_eq!(ids.len(), unique.len());
+    assert_eq!(ids.len(), 2 * single.records_iter().count() + 3 + 2);
+
+    // Parents, lookups and event owners use merged IDs
+    for record in merged.records_iter() {
+        let found = merged.get_record(record.id()).unwrap();
+        assert_eq!(found.name(), record.name());
+        if let Some(parent) = record.parent_id() {
+            let parent = merged.get_record(parent).unwrap();
+            assert!((0..parent.num_children()).any(|i| parent.child_at(i).unwrap().id() == record.id()));
+        }
+        for i in 0..record.num_events() {
+            assert_eq!(record.event_at(i).unwrap().record_id(), record.id());
+        }
+    }
+
+    let task = files[2].child_at(0).unwrap().child_at(0).unwrap();
+    assert_eq!(task.external_id(), ExternalId::Str("strings.jets:t-1".into()));
+    assert_eq!(merged.metadata().trace_extent().0, single.metadata().trace_extent().0.min(5));
+    Ok(())
+}
+
+#[test]
+fn test_counter_samples_round_trip() -> Result<()> {
+    let path = env::temp_dir().join("test_counter_samples.jets");
+    {
+        let mut writer = TraceWriter::new(path.to_str().unwrap())?;
+        writer.write_header("2.0", serde_json::json!({}))?;
+        writer.write_record(1, None, "Core", 0, "core0", "", None)?;
+        writer.write_counter_record(2, Some(1), 0, "queue depth", Some("entries"))?;
+        writer.write_counter_sample(2u64, 0, 0.0)?;
+        writer.write_counter_sample(2u64, 10, 4.0)?;
+        writer.write_counter_sample(2u64, 25, 1.5)?;
+        writer.write_footer(Some(40))?;
+    }
+
+    let data = parse_trace(path.to_str().unwrap())?;
+    fs::remove_file(&path)?;
+
+    let counter = data.get_record(2).unwrap();
+    assert!(rjets::is_counter(&counter));
+    assert!(!rjets::is_counter(&data.get_record(1).unwrap()));
+    assert_eq!(counter.attr("unit"), Some(serde_json::json!("entries")));
+    let samples: Vec<(i64, Option<f64>)> = (0..counter.num_events())
+        .map(|i| counter.event_at(i).unwrap())
+        .map(|e| (e.clk(), rjets::counter_value(&e)))
+        .collect();
+    assert_eq!(samples, vec![(0, Some(0.0)), (10, Some(4.0)), (25, Some(1.5))]);
+
+    // Hand-written samples may carry the bare number as their data
+    let input = concat!(
+        r#"{"type":"header","version":"2.0","metadata":{}}"#, "\n",
+        r#"{"type":"record","clk":0,"name":"power","record_type":"Counter","id":1,"parent_id":null,"description":""}"#, "\n",
+        r#"{"type":"event","clk":3,"name":"sample","record_id":1,"description":"","data":12}"#, "\n",
+    );
+    let data = parse_trace_from_reader(input.as_bytes())?;
+    let power = data.get_record(1).unwrap();
+    assert_eq!(rjets::counter_value(&power.event_at(0).unwrap()), Some(12.0));
+    Ok(())
+}
+
+#[test]
+fn test_log_subscriber_collects_structured_events() {
+    use rjets::logging::{self, Level, LogBuffer, LogSubscriber};
+
+    let buffer = LogBuffer::new();
+    tracing::subscriber::with_default(LogSubscriber::new(Level::INFO, Some(buffer.clone())), || {
+        tracing::info!(records = 3, "trace loaded");
+        tracing::debug!("not recorded at info level");
+        tracing::warn!(path = "a.jets", "slow load");
+    });
+    let entries = buffer.snapshot();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].message, "trace loaded records=3");
+    assert_eq!(entries[1].level, Level::WARN);
+    assert_eq!(entries[1].message, "slow load path=\"a.jets\"");
+
+    let mut args: Vec<String> = ["trace.jets", "--log-level", "debug"].iter().map(|s| s.to_string()).collect();
+    assert_eq!(logging::take_log_level_arg(&mut args), Ok(Some(Level::DEBUG)));
+    assert_eq!(args, vec!["trace.jets".to_string()]);
+    let mut args = vec!["--log-level=loud".to_string()];
+    assert!(logging::take_log_level_arg(&mut args).is_err());
+}
+
+#[test]
+fn test_provenance_hash_round_trip() -> Result<()> {
+    use rjets::{verify_trace_file, Verification};
+
+    let path = env::temp_dir().join("test_provenance.jets.br");
+    let path_str = path.to_str().unwrap();
+    {
+        let mut writer = TraceWriter::new(path_str)?.with_provenance("integration-test 1.0");
+        writer.write_header("2.0", serde_json::json!({}))?;
+        writer.write_record(1, None, "Op", 0, "fetch", "", None)?;
+        writer.write_event(1u64, "hit", "", 4, None)?;
+        writer.write_record_end(1u64, 10)?;
+        writer.write_footer(Some(10))?;
+    }
+
+    let data = parse_trace(path_str)?;
+    let provenance = data.metadata().provenance().expect("footer should carry provenance");
+    assert_eq!(provenance.producer, "integration-test 1.0");
+    assert!(matches!(verify_trace_file(path_str)?, Verification::Verified(_)));
+    let report = rjets::validator::validate_file(path_str)?;
+    assert!(report.is_valid());
+    assert!(matches!(report.provenance, Some(Verification::Verified(_))));
+    fs::remove_file(&path)?;
+
+    // Editing a line after the fact breaks the hash
+    let footer = format!(
+        r#"{{"type":"footer","provenance":{}}}"#,
+        serde_json::to_string(&provenance)?
+    );
+    let tampered = format!(
+        "{}\n{}\n{}\n",
+        r#"{"type":"header","version":"2.0","metadata":{}}"#,
+        r#"{"clk":0,"type":"record","name":"fetch","record_type":"Op","id":1,"parent_id":null,"description":"edited"}"#,
+        footer
+    );
+    assert!(matches!(
+        rjets::provenance::verify_reader(tampered.as_bytes())?,
+        Verification::Mismatch { .. }
+    ));
+    assert!(!rjets::validator::validate_reader(tampered.as_bytes())?.is_valid());
+    assert_eq!(
+        rjets::provenance::verify_reader(r#"{"type":"header","version":"2.0","metadata":{}}"#.as_bytes())?,
+        Verification::Unsigned
+    );
+    Ok(())
+}
+
+#[test]
+fn test_export_trace_round_trips_any_backend() -> Result<()> {
+    let input = concat!(
+        r#"{"type":"header","version":"2.0","metadata":{"model":"core-a"}}"#, "\n",
+        r#"{"type":"record","clk":0,"name":"thread","record_type":"Thread","id":"t-1","parent_id":null,"description":"main"}"#, "\n",
+        r#"{"type":"record","clk":2,"name":"add","record_type":"Instr","id":5,"parent_id":"t-1","description":"","data":{"pc":64}}"#, "\n",
+        r#"{"type":"annotation","name":"note","record_id":5,"description":"by hand","data":"hot"}"#, "\n",
+        r#"{"type":"event","clk":3,"name":"D","record_id":5,"description":"","data":{"port":1}}"#, "\n",
+        r#"{"type":"record_end","clk":6,"record_id":5}"#, "\n",
+        r#"{"type":"footer","capture_end_clk":9}"#, "\n",
+    );
+    let original = DynTraceData::Jets(parse_trace_from_reader(input.as_bytes())?);
+    let path = env::temp_dir().join("test_export_round_trip.jets");
+    let path_str = path.to_str().unwrap();
+    assert_eq!(rjets::writer::export_trace(&original, path_str)?, 2);
+    assert!(rjets::validator::validate_file(path_str)?.is_valid());
+
+    let exported = parse_trace(path_str)?;
+    assert_eq!(exported.metadata().header_data()["model"], "core-a");
+    assert_eq!(exported.metadata().capture_end_clk(), Some(9));
+    assert_eq!(exported.metadata().total_annotations(), Some(1));
+    let thread = exported.get_record(exported.root_ids()[0]).unwrap();
+    assert_eq!(thread.external_id(), ExternalId::from("t-1"));
+    assert_eq!((thread.description(), thread.end_clk()), ("main".to_string(), None));
+    let add = thread.child_at(0).unwrap();
+    assert_eq!(add.attrs(), vec![("pc".to_string(), serde_json::json!(64)), ("note".to_string(), serde_json::json!("hot"))]);
+    assert_eq!(add.annotations()[0].description, "by hand");
+    let event = add.event_at(0).unwrap();
+    assert_eq!((event.clk(), event.attr("port")), (3, Some(serde_json::json!(1))));
+    assert_eq!(add.end_clk(), Some(6));
+    fs::remove_file(&path)?;
+
+    // Other backends convert the same way
+    let virtual_trace = VirtualTraceReader::new().read("")?;
+    let count = rjets::writer::export_trace(&virtual_trace, path_str)?;
+    assert_eq!(parse_trace(path_str)?.records_iter().count(), count);
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn test_finish_reports_brotli_stream_errors() -> Result<()> {
+    /// Accepts writes until `failing` is set
+    struct Flaky(std::rc::Rc<std::cell::Cell<bool>>);
+    impl std::io::Write for Flaky {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if self.0.get() {
+                return Err(std::io::Error::other("disk full"));
+            }
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let failing = std::rc::Rc::new(std::cell::Cell::new(false));
+    let mut writer = TraceWriter::from_brotli(BrotliWriter::new(Flaky(failing.clone())));
+    writer.write_header("2.0", serde_json::json!({}))?;
+    writer.write_footer(None)?;
+    failing.set(true);
+    let error = writer.finish().unwrap_err();
+    assert!(format!("{:#}", error).contains("disk full"));
+
+    // A healthy .br file reads back after finish
+    let path = env::temp_dir().join("test_finish_brotli.jets.br");
+    let path_str = path.to_str().unwrap();
+    let mut writer = TraceWriter::new(path_str)?;
+    writer.write_header("2.0", serde_json::json!({}))?;
+    writer.write_record(1, None, "Root", 0, "root", "", None)?;
+    writer.write_footer(Some(5))?;
+    writer.finish()?;
+    assert_eq!(parse_trace(path_str)?.metadata().capture_end_clk(), Some(5));
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn test_cancelable_read() -> Result<()> {
+    use std::sync::atomic::AtomicBool;
+
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/trace.jets");
+    let reader = JetsTraceReader::new();
+    let expected = reader.read(path)?.records_iter().count();
+    assert_eq!(reader.read_cancelable(path, &AtomicBool::new(false))?.records_iter().count(), expected);
+
+    let Err(error) = reader.read_cancelable(path, &AtomicBool::new(true)) else {
+        panic!("a canceled read should fail");
+    };
+    assert!(format!("{:#}", error).contains(rjets::LOAD_CANCELED), "{:#}", error);
+    // Readers without early exit still honor the flag once done
+    assert!(VirtualTraceReader::new().read_cancelable("", &AtomicBool::new(true)).is_err());
+    Ok(())
+}